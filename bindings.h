@@ -5,31 +5,195 @@
 #include <stdlib.h>
 
 
+/**
+ * The request was made, but there was no data at the given location or db
+ */
 #define DATA_NOT_FOUND_STATE 2
 
+/**
+ * The request failed, or an argument was null or not valid, the reason is given by `smol_db_client_last_error`
+ */
 #define ERROR_STATE 1
 
+/**
+ * The request succeeded
+ */
 #define OK_STATE 0
 
+/**
+ * A client connected to a server, made with `smol_db_client_new` and freed with `smol_db_client_free`
+ */
 typedef struct FFISmolDBClient FFISmolDBClient;
 
+/**
+ * The result of a request that can respond with data.
+ * `data_ptr` points to the nul terminated response, and `data_len` is its length in bytes without the nul,
+ * it is null when the request failed or there was no data to respond with.
+ * The data is owned by the caller, and is freed with `smol_db_free_string`
+ */
+typedef struct FFIResult {
+  /**
+   * `OK_STATE`, `ERROR_STATE`, or `DATA_NOT_FOUND_STATE`
+   */
+  int32_t code;
+  char *data_ptr;
+  size_t data_len;
+} FFIResult;
+
+/**
+ * Creates a db with the settings, given as json in the form returned by `smol_db_client_get_db_settings`
+ *
+ * # Safety
+ * `client_ptr` must be made by `smol_db_client_new`, and `name` and `settings_json` must point to nul terminated strings
+ */
+int32_t smol_db_client_create_db(struct FFISmolDBClient *client_ptr,
+                                 const char *name,
+                                 const char *settings_json);
+
+/**
+ * Deletes the value at the location in the db
+ *
+ * # Safety
+ * `client_ptr` must be made by `smol_db_client_new`, and `name` and `location` must point to nul terminated strings
+ */
+int32_t smol_db_client_delete_data(struct FFISmolDBClient *client_ptr,
+                                   const char *name,
+                                   const char *location);
+
+/**
+ * Deletes the db
+ *
+ * # Safety
+ * `client_ptr` must be made by `smol_db_client_new`, and `name` must point to a nul terminated string
+ */
+int32_t smol_db_client_delete_db(struct FFISmolDBClient *client_ptr,
+                                 const char *name);
+
+/**
+ * Disconnects the client from the server, it can be connected again with `smol_db_client_reconnect`
+ *
+ * # Safety
+ * `client_ptr` must be made by `smol_db_client_new`
+ */
 int32_t smol_db_client_disconnect(struct FFISmolDBClient *client_ptr);
 
+/**
+ * Frees the client, disconnecting it from the server
+ *
+ * # Safety
+ * `client_ptr` must be null or made by `smol_db_client_new`, and not used again after this
+ */
 void smol_db_client_free(struct FFISmolDBClient *client_ptr);
 
+/**
+ * Gets the settings of the db, as json
+ *
+ * # Safety
+ * `client_ptr` must be made by `smol_db_client_new`, and `name` must point to a nul terminated string
+ */
+struct FFIResult smol_db_client_get_db_settings(struct FFISmolDBClient *client_ptr,
+                                                const char *name);
+
+/**
+ * Gets the role the client has in the db, as a json string such as `"SuperAdmin"`
+ *
+ * # Safety
+ * `client_ptr` must be made by `smol_db_client_new`, and `name` must point to a nul terminated string
+ */
+struct FFIResult smol_db_client_get_role(struct FFISmolDBClient *client_ptr,
+                                         const char *name);
+
+/**
+ * Returns the reason the last function that failed on this thread failed, or null if none has.
+ * The string is owned by the caller, and is freed with `smol_db_free_string`
+ */
+char *smol_db_client_last_error(void);
+
+/**
+ * Lists the names of the dbs on the server, as a json array
+ *
+ * # Safety
+ * `client_ptr` must be made by `smol_db_client_new`
+ */
+struct FFIResult smol_db_client_list_db(struct FFISmolDBClient *client_ptr);
+
+/**
+ * Lists every key and value in the db, as a json object
+ *
+ * # Safety
+ * `client_ptr` must be made by `smol_db_client_new`, and `name` must point to a nul terminated string
+ */
+struct FFIResult smol_db_client_list_db_contents(struct FFISmolDBClient *client_ptr,
+                                                 const char *name);
+
+/**
+ * Connects to the server at the ip, such as `localhost:8222`, returning null if it could not connect
+ *
+ * # Safety
+ * `ip` must point to a nul terminated string
+ */
 struct FFISmolDBClient *smol_db_client_new(const char *ip);
 
-const char *smol_db_client_read_db(struct FFISmolDBClient *client_ptr,
-                                   const char *name,
-                                   const char *location);
+/**
+ * Reads the value at the location in the db
+ *
+ * # Safety
+ * `client_ptr` must be made by `smol_db_client_new`, and `name` and `location` must point to nul terminated strings
+ */
+struct FFIResult smol_db_client_read_db(struct FFISmolDBClient *client_ptr,
+                                        const char *name,
+                                        const char *location);
 
+/**
+ * Reconnects the client to the server it was connected to
+ *
+ * # Safety
+ * `client_ptr` must be made by `smol_db_client_new`
+ */
 int32_t smol_db_client_reconnect(struct FFISmolDBClient *client_ptr);
 
-int32_t smol_db_client_set_key(struct FFISmolDBClient *client_ptr, const char *key_ptr);
+/**
+ * Sets the settings of the db, given as json in the form returned by `smol_db_client_get_db_settings`
+ *
+ * # Safety
+ * `client_ptr` must be made by `smol_db_client_new`, and `name` and `settings_json` must point to nul terminated strings
+ */
+int32_t smol_db_client_set_db_settings(struct FFISmolDBClient *client_ptr,
+                                       const char *name,
+                                       const char *settings_json);
+
+/**
+ * Sets the access key of the client
+ *
+ * # Safety
+ * `client_ptr` must be made by `smol_db_client_new`, and `key_ptr` must point to a nul terminated string
+ */
+int32_t smol_db_client_set_key(struct FFISmolDBClient *client_ptr,
+                               const char *key_ptr);
 
+/**
+ * Switches the client to end to end encryption
+ *
+ * # Safety
+ * `client_ptr` must be made by `smol_db_client_new`
+ */
 int32_t smol_db_client_setup_encryption(struct FFISmolDBClient *client_ptr);
 
-const char *smol_db_client_write_db(struct FFISmolDBClient *client_ptr,
-                                    const char *name,
-                                    const char *location,
-                                    const char *data);
+/**
+ * Writes the data to the location in the db, responding with the value that was there before, which is null if there was none
+ *
+ * # Safety
+ * `client_ptr` must be made by `smol_db_client_new`, and `name`, `location` and `data` must point to nul terminated strings
+ */
+struct FFIResult smol_db_client_write_db(struct FFISmolDBClient *client_ptr,
+                                         const char *name,
+                                         const char *location,
+                                         const char *data);
+
+/**
+ * Frees the data of an `FFIResult`, or a string returned by `smol_db_client_last_error`
+ *
+ * # Safety
+ * `data_ptr` must be null or a string returned by this library, and not used again after this
+ */
+void smol_db_free_string(char *data_ptr);
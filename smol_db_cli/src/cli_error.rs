@@ -0,0 +1,40 @@
+//! Contains `CliError`, the reasons a command can fail
+use smol_db_client::client_error::ClientError;
+use std::fmt::{Display, Formatter};
+
+/// The reason a command failed
+#[derive(Debug)]
+pub enum CliError {
+    /// The request to the server failed, or a file could not be read or written
+    Client(ClientError),
+    /// The settings given are not valid json of `DBSettings`
+    InvalidSettings(serde_json::Error),
+    /// The command needs a db name, such as importing a json or csv file
+    MissingDBName,
+}
+
+impl From<ClientError> for CliError {
+    fn from(err: ClientError) -> Self {
+        Self::Client(err)
+    }
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Client(err) => write!(f, "{}", err),
+            Self::InvalidSettings(err) => write!(f, "the settings are not valid json: {}", err),
+            Self::MissingDBName => write!(f, "a db name is required, given with --db"),
+        }
+    }
+}
+
+impl std::error::Error for CliError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Client(err) => Some(err),
+            Self::InvalidSettings(err) => Some(err),
+            Self::MissingDBName => None,
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Contains `Command`, every request the cli can make, and `execute`, which makes it with a connected client
+use crate::cli_error::CliError;
+use crate::output::Output;
+use clap::{Subcommand, ValueEnum};
+use smol_db_client::client_error::ClientError::{BadPacket, ValueStreamError};
+use smol_db_client::db_settings::DBSettings;
+use smol_db_client::prelude::SmolDbClient;
+use smol_db_client::DBSuccessResponse;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// A request to make to the server
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Prints the value at the key of the db
+    Read { db: String, key: String },
+    /// Writes the value to the key of the db
+    Write {
+        db: String,
+        key: String,
+        value: String,
+    },
+    /// Deletes the value at the key of the db
+    Delete { db: String, key: String },
+    /// Prints the name of every db on the server
+    ListDb,
+    /// Prints every key and value of the db
+    ListContents { db: String },
+    /// Creates a db, with the default settings unless settings are given
+    CreateDb {
+        db: String,
+        /// Settings of the db as json, in the form printed by `settings get`
+        #[arg(long)]
+        settings: Option<String>,
+    },
+    /// Deletes a db, moving it to the trash of the server
+    DeleteDb { db: String },
+    /// Prints or replaces the settings of a db
+    #[command(subcommand)]
+    Settings(SettingsCommand),
+    /// Writes the db to the file at the path, replacing the file if it exists
+    Export {
+        db: String,
+        path: PathBuf,
+        #[arg(long, value_enum, default_value_t = TransferFormat::Json)]
+        format: TransferFormat,
+    },
+    /// Writes the file at the path to a db, creating the db if it does not exist.
+    /// A dump is imported into the db it was exported from, which must not exist yet
+    Import {
+        path: PathBuf,
+        /// The db json and csv files are imported into
+        #[arg(long)]
+        db: Option<String>,
+        #[arg(long, value_enum, default_value_t = TransferFormat::Json)]
+        format: TransferFormat,
+    },
+    /// Makes the access key the first super admin of the server, using the bootstrap token the server printed on startup
+    ClaimSuperAdmin { token: String },
+}
+
+/// Prints or replaces the settings of a db
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum SettingsCommand {
+    /// Prints the settings of the db as json
+    Get { db: String },
+    /// Replaces the settings of the db with the given json, in the form printed by `settings get`
+    Set { db: String, settings: String },
+}
+
+/// The format of the file a db is exported to or imported from
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferFormat {
+    /// A single json object, with each key mapped to its value
+    Json,
+    /// A `key,value` header row followed by a row for each key
+    Csv,
+    /// A dump of the content, settings and statistics of the db, which requires super admin privileges
+    Dump,
+}
+
+/// Makes the request of the command with the client, returning what is printed
+pub fn execute(client: &mut SmolDbClient, command: Command) -> Result<Output, CliError> {
+    match command {
+        Command::Read { db, key } => match client.read_db(&db, &key)? {
+            DBSuccessResponse::SuccessReply(value) => Ok(Output::Value(value)),
+            DBSuccessResponse::SuccessNoData | DBSuccessResponse::SuccessData(_) => {
+                Err(BadPacket.into())
+            }
+        },
+        Command::Write { db, key, value } => {
+            let _ = client.write_db(&db, &key, &value)?;
+            Ok(Output::Done)
+        }
+        Command::Delete { db, key } => {
+            let _ = client.delete_data(&db, &key)?;
+            Ok(Output::Done)
+        }
+        Command::ListDb => {
+            let names = client
+                .list_db()?
+                .iter()
+                .map(|db| db.get_db_name().to_string())
+                .collect();
+            Ok(Output::Names(names))
+        }
+        Command::ListContents { db } => Ok(Output::Contents(
+            client.stream_db_contents(&db)?.into_iter().collect(),
+        )),
+        Command::CreateDb { db, settings } => {
+            let settings = match settings {
+                Some(settings) => parse_settings(&settings)?,
+                None => DBSettings::default(),
+            };
+            let _ = client.create_db(&db, settings)?;
+            Ok(Output::Done)
+        }
+        Command::DeleteDb { db } => {
+            let _ = client.delete_db(&db)?;
+            Ok(Output::Done)
+        }
+        Command::Settings(SettingsCommand::Get { db }) => {
+            Ok(Output::Settings(Box::new(client.get_db_settings(&db)?)))
+        }
+        Command::Settings(SettingsCommand::Set { db, settings }) => {
+            let _ = client.set_db_settings(&db, parse_settings(&settings)?)?;
+            Ok(Output::Done)
+        }
+        Command::Export { db, path, format } => {
+            Ok(Output::Count(export_db(client, &db, format, &path)?))
+        }
+        Command::Import { path, db, format } => import_db(client, db, format, &path),
+        Command::ClaimSuperAdmin { token } => {
+            let _ = client.claim_super_admin(&token)?;
+            Ok(Output::Done)
+        }
+    }
+}
+
+fn parse_settings(settings: &str) -> Result<DBSettings, CliError> {
+    serde_json::from_str(settings).map_err(CliError::InvalidSettings)
+}
+
+/// Writes the db to the file at the path in the format, returning the number of entries, or bytes for a dump, written
+fn export_db(
+    client: &mut SmolDbClient,
+    db_name: &str,
+    format: TransferFormat,
+    path: &Path,
+) -> Result<usize, CliError> {
+    if format == TransferFormat::Dump {
+        return Ok(client.export_db(db_name, path)?);
+    }
+
+    let mut writer = BufWriter::new(File::create(path).map_err(ValueStreamError)?);
+    let written = match format {
+        TransferFormat::Csv => client.export_table_csv(db_name, &mut writer)?,
+        TransferFormat::Json | TransferFormat::Dump => {
+            client.export_table_json(db_name, &mut writer)?
+        }
+    };
+    writer.flush().map_err(ValueStreamError)?;
+    Ok(written)
+}
+
+/// Writes the file at the path to the db, creating the db if it does not exist.
+/// A dump is imported into the db it holds, so it needs no db name
+fn import_db(
+    client: &mut SmolDbClient,
+    db_name: Option<String>,
+    format: TransferFormat,
+    path: &Path,
+) -> Result<Output, CliError> {
+    if format == TransferFormat::Dump {
+        return Ok(Output::Imported {
+            db_name: client.import_db(path)?,
+            entries: None,
+        });
+    }
+    let db_name = db_name.ok_or(CliError::MissingDBName)?;
+
+    let mut reader = BufReader::new(File::open(path).map_err(ValueStreamError)?);
+    if !client.db_exists(&db_name)? {
+        let _ = client.create_db(&db_name, DBSettings::default())?;
+    }
+    let entries = match format {
+        TransferFormat::Csv => client.import_table_csv(&db_name, &mut reader)?,
+        TransferFormat::Json | TransferFormat::Dump => {
+            client.import_table_json(&db_name, &mut reader)?
+        }
+    };
+    Ok(Output::Imported {
+        db_name,
+        entries: Some(entries),
+    })
+}
@@ -0,0 +1,31 @@
+//! Library behind the `smol_db_cli` binary, which runs a single command against a `smol_db` server so scripts do not need to be written in rust,
+//! or runs an interactive shell of commands with `run_shell`.
+//! Each command is a `Command`, made with a connected client by `execute`, and printed as text or json using its `Output`.
+use smol_db_client::client_error::ClientError;
+use smol_db_client::prelude::SmolDbClient;
+
+pub mod cli_error;
+pub mod command;
+pub mod output;
+pub mod shell;
+
+pub use cli_error::CliError;
+pub use command::{execute, Command, SettingsCommand, TransferFormat};
+pub use output::Output;
+pub use shell::run_shell;
+
+/// Connects to the server at the address, switching to end to end encryption before the key is set if `encrypt` is true
+pub fn connect(
+    address: &str,
+    key: Option<String>,
+    encrypt: bool,
+) -> Result<SmolDbClient, ClientError> {
+    let mut client = SmolDbClient::new(address)?;
+    if encrypt {
+        let _ = client.setup_encryption()?;
+    }
+    if let Some(key) = key {
+        let _ = client.set_access_key(key)?;
+    }
+    Ok(client)
+}
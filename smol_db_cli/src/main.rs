@@ -0,0 +1,70 @@
+//! Binary application that runs a single command against a `smol_db` server, printing the result for scripts and ci jobs to use,
+//! or an interactive shell when no command is given
+use clap::Parser;
+use smol_db_cli::{connect, execute, run_shell, CliError, Command};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Parser, Debug)]
+#[command(version, about)]
+/// Command line arguments of the cli, the connection options can also be given as environment variables.
+struct CliArgs {
+    /// Address and port of the server
+    #[arg(short, long, env = "SMOL_DB_ADDRESS", default_value = "localhost:8222")]
+    address: String,
+
+    /// Access key set once connected
+    #[arg(short, long, env = "SMOL_DB_KEY")]
+    key: Option<String>,
+
+    /// Switch to end to end encryption before the key is sent
+    #[arg(short, long)]
+    encrypt: bool,
+
+    /// Print the result as json
+    #[arg(long)]
+    json: bool,
+
+    /// File the command history of the shell is loaded from and saved to
+    #[arg(long, env = "SMOL_DB_HISTORY")]
+    history: Option<PathBuf>,
+
+    /// The command to run, an interactive shell is started if there is none
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+fn main() -> ExitCode {
+    let args = CliArgs::parse();
+
+    let mut client = match connect(&args.address, args.key, args.encrypt) {
+        Ok(client) => client,
+        Err(err) => {
+            eprintln!("Error: {}", CliError::from(err));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let exit_code = match args.command {
+        Some(command) => match execute(&mut client, command) {
+            Ok(output) => {
+                output.print(args.json);
+                ExitCode::SUCCESS
+            }
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                ExitCode::FAILURE
+            }
+        },
+        None => match run_shell(&mut client, args.json, args.history.as_deref()) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                ExitCode::FAILURE
+            }
+        },
+    };
+
+    let _ = client.disconnect();
+    exit_code
+}
@@ -0,0 +1,81 @@
+//! Contains `Output`, the result of a command, which is printed as plain text or as json for scripts to parse
+use serde_json::{json, Value};
+use smol_db_client::db_settings::DBSettings;
+use std::collections::BTreeMap;
+
+/// The result of a command that succeeded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Output {
+    /// The command succeeded with nothing to show
+    Done,
+    /// The value read from a db
+    Value(String),
+    /// Names, such as of the dbs on the server
+    Names(Vec<String>),
+    /// Every key and value of a db, sorted by key
+    Contents(BTreeMap<String, String>),
+    Settings(Box<DBSettings>),
+    /// The number of entries, or bytes for a dump, exported
+    Count(usize),
+    /// A file was imported into the db, writing the number of entries if it was not a dump
+    Imported {
+        db_name: String,
+        entries: Option<usize>,
+    },
+}
+
+impl Output {
+    /// Prints the output as json or text, nothing is printed for text output that is empty
+    pub fn print(&self, json: bool) {
+        if json {
+            println!("{}", self.to_json());
+        } else {
+            let text = self.to_text();
+            if !text.is_empty() {
+                println!("{}", text);
+            }
+        }
+    }
+
+    /// Returns the output as json, a command with nothing to show is `null`
+    pub fn to_json(&self) -> Value {
+        match self {
+            Self::Done => Value::Null,
+            Self::Value(value) => json!(value),
+            Self::Names(names) => json!(names),
+            Self::Contents(contents) => json!(contents),
+            Self::Settings(settings) => json!(settings),
+            Self::Count(count) => json!(count),
+            Self::Imported { db_name, entries } => json!({
+                "db_name": db_name,
+                "entries": entries,
+            }),
+        }
+    }
+
+    /// Returns the output as plain text, a line for each name, or tab separated key and value, with no trailing line break.
+    /// A command with nothing to show is empty
+    pub fn to_text(&self) -> String {
+        match self {
+            Self::Done => "".to_string(),
+            Self::Value(value) => value.clone(),
+            Self::Names(names) => names.join("\n"),
+            Self::Contents(contents) => contents
+                .iter()
+                .map(|(key, value)| format!("{}\t{}", key, value))
+                .collect::<Vec<String>>()
+                .join("\n"),
+            // settings are printed as json even as text, so they can be edited and passed to `settings set`
+            Self::Settings(settings) => serde_json::to_string_pretty(settings).unwrap_or_default(),
+            Self::Count(count) => count.to_string(),
+            Self::Imported {
+                db_name,
+                entries: Some(entries),
+            } => format!("Imported {} entries into {}", entries, db_name),
+            Self::Imported {
+                db_name,
+                entries: None,
+            } => format!("Imported {}", db_name),
+        }
+    }
+}
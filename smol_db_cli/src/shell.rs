@@ -0,0 +1,244 @@
+//! Contains `run_shell`, an interactive shell that makes commands with a single connection until it is exited.
+//! Lines are split into arguments like a command line, except json objects and arrays are kept whole, and may span several lines.
+use crate::command::{execute, Command};
+use clap::{CommandFactory, Parser, Subcommand};
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::{ValidationContext, ValidationResult, Validator};
+use rustyline::{Context, Editor, Helper};
+use smol_db_client::prelude::SmolDbClient;
+use std::path::Path;
+
+const PROMPT: &str = "smol_db> ";
+
+/// A line entered into the shell
+#[derive(Parser, Debug)]
+#[command(no_binary_name = true, disable_version_flag = true)]
+struct ShellLine {
+    #[command(subcommand)]
+    command: ShellCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum ShellCommand {
+    #[command(flatten)]
+    Command(Command),
+    /// Leaves the shell
+    #[command(alias = "quit")]
+    Exit,
+}
+
+/// Completes the names of commands, and of the dbs on the server, and keeps a line open while a quote or json value is unclosed
+struct ShellHelper {
+    command_names: Vec<String>,
+    /// Names of the dbs on the server, read when the shell starts and after each command that adds or removes a db
+    db_names: Vec<String>,
+}
+
+impl ShellHelper {
+    fn new(client: &mut SmolDbClient) -> Self {
+        let command_names = ShellLine::command()
+            .get_subcommands()
+            .map(|command| command.get_name().to_string())
+            .collect();
+        let mut helper = Self {
+            command_names,
+            db_names: vec![],
+        };
+        helper.read_db_names(client);
+        helper
+    }
+
+    /// Reads the names of the dbs on the server again, completing no db names if they can not be read
+    fn read_db_names(&mut self, client: &mut SmolDbClient) {
+        self.db_names = client
+            .list_db()
+            .map(|list| list.iter().map(|db| db.get_db_name().to_string()).collect())
+            .unwrap_or_default();
+    }
+}
+
+impl Completer for ShellHelper {
+    type Candidate = String;
+
+    /// Completes the first word as a command, the word after `settings` as `get` or `set`, and any other word that is not a flag as a db name
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let start = line[..pos]
+            .rfind(char::is_whitespace)
+            .map_or(0, |index| index + 1);
+        let word = &line[start..pos];
+        let previous_words: Vec<&str> = line[..start].split_whitespace().collect();
+
+        let candidates: Vec<&str> = match previous_words.as_slice() {
+            [] => self.command_names.iter().map(String::as_str).collect(),
+            ["settings"] => vec!["get", "set"],
+            _ if word.starts_with('-') => vec![],
+            _ => self.db_names.iter().map(String::as_str).collect(),
+        };
+
+        Ok((
+            start,
+            candidates
+                .into_iter()
+                .filter(|candidate| candidate.starts_with(word))
+                .map(str::to_string)
+                .collect(),
+        ))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {
+    /// Keeps reading lines while a quote or json value is unclosed, so json can be entered over several lines
+    fn validate(&self, ctx: &mut ValidationContext) -> rustyline::Result<ValidationResult> {
+        Ok(match split_line(ctx.input()) {
+            Some(_) => ValidationResult::Valid(None),
+            None => ValidationResult::Incomplete,
+        })
+    }
+}
+
+impl Helper for ShellHelper {}
+
+/// Runs the shell until it is exited, printing the output of each command as json or text.
+/// History is loaded from and saved to the file at the history path if there is one
+pub fn run_shell(
+    client: &mut SmolDbClient,
+    json: bool,
+    history_path: Option<&Path>,
+) -> Result<(), ReadlineError> {
+    let mut editor: Editor<ShellHelper, DefaultHistory> = Editor::new()?;
+    editor.set_helper(Some(ShellHelper::new(client)));
+    if let Some(path) = history_path {
+        // there is no history yet the first time the shell is run
+        let _ = editor.load_history(path);
+    }
+
+    loop {
+        let line = match editor.readline(PROMPT) {
+            Ok(line) => line,
+            // control-c discards the line being entered, control-d leaves the shell
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(err) => return Err(err),
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line.as_str())?;
+
+        let Some(args) = split_line(&line) else {
+            eprintln!("Error: a quote or json value is not closed");
+            continue;
+        };
+        let command = match ShellLine::try_parse_from(args) {
+            Ok(ShellLine {
+                command: ShellCommand::Command(command),
+            }) => command,
+            Ok(ShellLine {
+                command: ShellCommand::Exit,
+            }) => break,
+            Err(err) => {
+                // help is also shown this way
+                let _ = err.print();
+                continue;
+            }
+        };
+
+        let changes_db_names = matches!(
+            command,
+            Command::CreateDb { .. } | Command::DeleteDb { .. } | Command::Import { .. }
+        );
+        match execute(client, command) {
+            Ok(output) => output.print(json),
+            Err(err) => eprintln!("Error: {}", err),
+        }
+        if changes_db_names {
+            if let Some(helper) = editor.helper_mut() {
+                helper.read_db_names(client);
+            }
+        }
+    }
+
+    if let Some(path) = history_path {
+        editor.save_history(path)?;
+    }
+    Ok(())
+}
+
+/// Splits the line into arguments at whitespace. Quotes group an argument, and a json object or array is kept whole even if it contains whitespace.
+/// Inside double quotes, a backslash escapes the next character.
+/// Returns none if a quote or json value is not closed
+pub fn split_line(line: &str) -> Option<Vec<String>> {
+    let mut args = vec![];
+    let mut chars = line.chars().peekable();
+
+    while let Some(&first) = chars.peek() {
+        if first.is_whitespace() {
+            let _ = chars.next();
+            continue;
+        }
+
+        let mut arg = String::new();
+        match first {
+            '\'' => {
+                let _ = chars.next();
+                loop {
+                    match chars.next()? {
+                        '\'' => break,
+                        char => arg.push(char),
+                    }
+                }
+            }
+            '"' => {
+                let _ = chars.next();
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => arg.push(chars.next()?),
+                        char => arg.push(char),
+                    }
+                }
+            }
+            '{' | '[' => {
+                let mut depth = 0;
+                let mut in_string = false;
+                loop {
+                    let char = chars.next()?;
+                    arg.push(char);
+                    match (in_string, char) {
+                        (true, '\\') => arg.push(chars.next()?),
+                        (_, '"') => in_string = !in_string,
+                        (false, '{' | '[') => depth += 1,
+                        (false, '}' | ']') => depth -= 1,
+                        _ => {}
+                    }
+                    if depth == 0 {
+                        break;
+                    }
+                }
+            }
+            _ => {
+                while let Some(char) = chars.next_if(|char| !char.is_whitespace()) {
+                    arg.push(char);
+                }
+            }
+        }
+        args.push(arg);
+    }
+
+    Some(args)
+}
@@ -0,0 +1,323 @@
+#[cfg(test)]
+mod tests {
+    use smol_db_cli::shell::split_line;
+    use smol_db_cli::{
+        connect, execute, CliError, Command, Output, SettingsCommand, TransferFormat,
+    };
+    use smol_db_test_harness::spawn_server;
+    use std::collections::BTreeMap;
+    use std::io::Write;
+    use std::process;
+    use std::process::Stdio;
+
+    #[test]
+    fn test_read_write_delete() {
+        let server = spawn_server();
+        let mut client =
+            connect(server.address(), Some("test_key_123".to_string()), false).unwrap();
+        let db = "test_cli_read_write_delete".to_string();
+
+        let create = Command::CreateDb {
+            db: db.clone(),
+            settings: None,
+        };
+        assert_eq!(execute(&mut client, create).unwrap(), Output::Done);
+        assert_eq!(
+            execute(&mut client, Command::ListDb).unwrap(),
+            Output::Names(vec![db.clone()])
+        );
+
+        let write = Command::Write {
+            db: db.clone(),
+            key: "name".to_string(),
+            value: "smol_db".to_string(),
+        };
+        assert_eq!(execute(&mut client, write).unwrap(), Output::Done);
+        let read = Command::Read {
+            db: db.clone(),
+            key: "name".to_string(),
+        };
+        assert_eq!(
+            execute(&mut client, read.clone()).unwrap(),
+            Output::Value("smol_db".to_string())
+        );
+        assert_eq!(
+            execute(&mut client, Command::ListContents { db: db.clone() }).unwrap(),
+            Output::Contents(BTreeMap::from([(
+                "name".to_string(),
+                "smol_db".to_string()
+            )]))
+        );
+
+        let delete = Command::Delete {
+            db: db.clone(),
+            key: "name".to_string(),
+        };
+        assert_eq!(execute(&mut client, delete).unwrap(), Output::Done);
+        assert!(matches!(
+            execute(&mut client, read),
+            Err(CliError::Client(_))
+        ));
+
+        assert_eq!(
+            execute(&mut client, Command::DeleteDb { db }).unwrap(),
+            Output::Done
+        );
+    }
+
+    #[test]
+    fn test_settings() {
+        let server = spawn_server();
+        let mut client = connect(server.address(), Some("test_key_123".to_string()), true).unwrap();
+        let db = "test_cli_settings".to_string();
+
+        let _ = execute(
+            &mut client,
+            Command::CreateDb {
+                db: db.clone(),
+                settings: None,
+            },
+        )
+        .unwrap();
+
+        let Output::Settings(mut settings) = execute(
+            &mut client,
+            Command::Settings(SettingsCommand::Get { db: db.clone() }),
+        )
+        .unwrap() else {
+            panic!("settings get did not print settings");
+        };
+        settings.users.push("test_cli_user".to_string());
+
+        // settings are set from the json printed by settings get
+        let set = SettingsCommand::Set {
+            db: db.clone(),
+            settings: Output::Settings(settings).to_text(),
+        };
+        assert_eq!(
+            execute(&mut client, Command::Settings(set)).unwrap(),
+            Output::Done
+        );
+        let Output::Settings(settings) = execute(
+            &mut client,
+            Command::Settings(SettingsCommand::Get { db: db.clone() }),
+        )
+        .unwrap() else {
+            panic!("settings get did not print settings");
+        };
+        assert_eq!(settings.users.len(), 1);
+
+        let invalid = SettingsCommand::Set {
+            db: db.clone(),
+            settings: "{".to_string(),
+        };
+        assert!(matches!(
+            execute(&mut client, Command::Settings(invalid)),
+            Err(CliError::InvalidSettings(_))
+        ));
+
+        let _ = execute(&mut client, Command::DeleteDb { db }).unwrap();
+    }
+
+    #[test]
+    fn test_export_import() {
+        let server = spawn_server();
+        let mut client =
+            connect(server.address(), Some("test_key_123".to_string()), false).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let db = "test_cli_export_import".to_string();
+
+        let _ = execute(
+            &mut client,
+            Command::CreateDb {
+                db: db.clone(),
+                settings: None,
+            },
+        )
+        .unwrap();
+        for key in ["a", "b", "c"] {
+            let write = Command::Write {
+                db: db.clone(),
+                key: key.to_string(),
+                value: format!("value, {}", key),
+            };
+            let _ = execute(&mut client, write).unwrap();
+        }
+        let contents = execute(&mut client, Command::ListContents { db: db.clone() }).unwrap();
+
+        for format in [TransferFormat::Json, TransferFormat::Csv] {
+            let path = dir.path().join(format!("{:?}", format));
+            let export = Command::Export {
+                db: db.clone(),
+                path: path.clone(),
+                format,
+            };
+            assert_eq!(execute(&mut client, export).unwrap(), Output::Count(3));
+
+            // the db is created by the import
+            let imported_db = format!("{}_{:?}", db, format);
+            let import = Command::Import {
+                path,
+                db: Some(imported_db.clone()),
+                format,
+            };
+            assert_eq!(
+                execute(&mut client, import).unwrap(),
+                Output::Imported {
+                    db_name: imported_db.clone(),
+                    entries: Some(3)
+                }
+            );
+            assert_eq!(
+                execute(&mut client, Command::ListContents { db: imported_db }).unwrap(),
+                contents
+            );
+        }
+
+        let missing_db = Command::Import {
+            path: dir.path().join("Json"),
+            db: None,
+            format: TransferFormat::Json,
+        };
+        assert!(matches!(
+            execute(&mut client, missing_db),
+            Err(CliError::MissingDBName)
+        ));
+
+        let dump_path = dir.path().join("dump");
+        let export = Command::Export {
+            db: db.clone(),
+            path: dump_path.clone(),
+            format: TransferFormat::Dump,
+        };
+        let _ = execute(&mut client, export).unwrap();
+        let _ = execute(&mut client, Command::DeleteDb { db: db.clone() }).unwrap();
+        let import = Command::Import {
+            path: dump_path,
+            db: None,
+            format: TransferFormat::Dump,
+        };
+        assert_eq!(
+            execute(&mut client, import).unwrap(),
+            Output::Imported {
+                db_name: db.clone(),
+                entries: None
+            }
+        );
+        assert_eq!(
+            execute(&mut client, Command::ListContents { db }).unwrap(),
+            contents
+        );
+    }
+
+    #[test]
+    fn test_binary_json_output() {
+        let server = spawn_server();
+        let run = |args: &[&str]| {
+            process::Command::new(env!("CARGO_BIN_EXE_smol_db_cli"))
+                .args([
+                    "--address",
+                    server.address(),
+                    "--key",
+                    "test_key_123",
+                    "--json",
+                ])
+                .args(args)
+                .output()
+                .unwrap()
+        };
+
+        assert!(run(&["create-db", "test_cli_binary"]).status.success());
+        assert!(run(&["write", "test_cli_binary", "name", "smol_db"])
+            .status
+            .success());
+
+        let output = run(&["list-contents", "test_cli_binary"]);
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "{\"name\":\"smol_db\"}\n"
+        );
+
+        let output = run(&["read", "test_cli_binary", "missing"]);
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr)
+            .unwrap()
+            .starts_with("Error: "));
+    }
+
+    #[test]
+    fn test_claim_super_admin() {
+        use smol_db_test_harness::{spawn_unclaimed_server, BOOTSTRAP_TOKEN};
+
+        let server = spawn_unclaimed_server();
+        let mut client =
+            connect(server.address(), Some("test_key_123".to_string()), false).unwrap();
+        let create = Command::CreateDb {
+            db: "test_cli_claim_super_admin".to_string(),
+            settings: None,
+        };
+        assert!(execute(&mut client, create.clone()).is_err());
+
+        let claim = Command::ClaimSuperAdmin {
+            token: BOOTSTRAP_TOKEN.to_string(),
+        };
+        assert_eq!(execute(&mut client, claim.clone()).unwrap(), Output::Done);
+        assert_eq!(execute(&mut client, create).unwrap(), Output::Done);
+        assert!(execute(&mut client, claim).is_err());
+    }
+
+    #[test]
+    fn test_split_line() {
+        assert_eq!(
+            split_line("write db key value").unwrap(),
+            vec!["write", "db", "key", "value"]
+        );
+        assert_eq!(
+            split_line(
+                r#"write 'my db' "key \"1\"" {"a": [1, "} ]"],
+ "b": 2}"#
+            )
+            .unwrap(),
+            vec![
+                "write",
+                "my db",
+                "key \"1\"",
+                "{\"a\": [1, \"} ]\"],\n \"b\": 2}"
+            ]
+        );
+        // unclosed quotes and json values keep the shell reading lines
+        assert_eq!(split_line("write db key 'value"), None);
+        assert_eq!(split_line(r#"write db key {"a": ["#), None);
+        assert_eq!(split_line(r#"write db key {"a": "}"#), None);
+    }
+
+    #[test]
+    fn test_shell() {
+        let server = spawn_server();
+        let mut shell = process::Command::new(env!("CARGO_BIN_EXE_smol_db_cli"))
+            .args(["--address", server.address(), "--key", "test_key_123"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        // the commands share one connection, and the shell keeps going after a command fails
+        shell
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(
+                b"create-db test_cli_shell\nread test_cli_shell missing\nwrite test_cli_shell key {\"a\": 1}\nread test_cli_shell key\nexit\nlist-db\n",
+            )
+            .unwrap();
+        let output = shell.wait_with_output().unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "{\"a\": 1}\n");
+        assert!(String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("Error: "));
+    }
+}
@@ -1,56 +1,215 @@
 use crate::client_error::ClientError;
+#[cfg(not(feature = "async"))]
+use crate::client_error::ClientError::ValueStreamError;
 use crate::client_error::ClientError::{
     BadPacket, EncryptionSetupError, KeyGenerationError, PacketDeserializationError,
-    PacketEncryptionError, PacketSerializationError, SocketReadError, SocketWriteError,
-    UnableToConnect,
+    PacketEncryptionError, PacketSerializationError, ServerIdentityChanged, SocketReadError,
+    SocketWriteError, UnableToConnect,
+};
+use crate::offline_queue::{
+    is_offline_error, ConflictPolicy, ConflictResolution, OfflineQueue, QueuedWrite, ReplayReport,
 };
+use crate::prelude::DBResponseError;
+use crate::prelude::KnownServers;
+use crate::prelude::OptionalResponse;
+use crate::prelude::StreamHandle;
 #[cfg(not(feature = "async"))]
 use crate::prelude::TableIter;
-use crate::prelude::{DBResponseError};
+use crate::prelude::TypedTable;
+#[cfg(not(feature = "async"))]
+use crate::prelude::WatchIter;
+use crate::request_hook::{BoxedRequestHook, RequestHook};
+#[cfg(feature = "cache")]
+use crate::response_cache::ResponseCache;
 use serde::{Deserialize, Serialize};
 use smol_db_common::db::Role;
+#[cfg(not(feature = "async"))]
+use smol_db_common::db_bytes::STREAM_CHUNK_SIZE;
+use smol_db_common::db_packets::packet_checksum::{deserialize_response, is_incomplete};
 use smol_db_common::encryption::client_encrypt::ClientKey;
+#[cfg(feature = "cache")]
+use smol_db_common::prelude::ChangeEvent;
+#[cfg(feature = "statistics")]
+use smol_db_common::prelude::StatsFormat;
 use smol_db_common::prelude::{
-    DBPacket, DBPacketInfo, DBPacketResponseError, DBSettings, DBSuccessResponse, RsaPublicKey,
-    SuccessNoData, SuccessReply,
+    DBBytes, DBListing, DBMetadata, DBPacket, DBPacketInfo, DBSettings, DBSuccessResponse,
+    EffectivePermissions, IpFilter, KeyAclRule, KeyPattern, NamespaceSettings, ResponseData,
+    Revision, RevisionInfo, RsaPublicKey, ScoredMember, ServerInfo, StreamItem, SuccessData,
+    SuccessNoData, SuccessReply, TransactionBuilder, ValueSchema, PONG,
 };
 #[cfg(feature = "statistics")]
-use smol_db_common::statistics::DBStatistics;
-use std::collections::HashMap;
-use std::io::Error;
+use smol_db_common::statistics::{DBStatistics, ServerStatistics};
+use std::collections::{BTreeMap, HashMap};
+#[cfg(not(feature = "async"))]
+use std::fs::File;
+use std::io::{Error, ErrorKind};
 #[cfg(not(feature = "async"))]
 use std::io::{Read, Write};
 #[cfg(not(feature = "async"))]
 use std::net::Shutdown;
+#[cfg(not(feature = "async"))]
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 use std::net::SocketAddr;
 
 #[cfg(feature = "async")]
 use tokio::{io::AsyncReadExt, io::AsyncWriteExt, net::TcpStream};
-use tracing::{error, info, warn};
 #[cfg(not(feature = "async"))]
 use tracing::debug;
+use tracing::{error, info, warn};
 
 #[cfg(not(feature = "async"))]
 use std::net::TcpStream;
 
+/// Quotes the field if it contains a comma, quote, or line break, doubling any quotes inside it.
+#[cfg(not(feature = "async"))]
+fn csv_field(field: &str) -> std::borrow::Cow<'_, str> {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\"")).into()
+    } else {
+        field.into()
+    }
+}
+
+/// Splits csv into its rows of fields, unquoting quoted fields. Rows end with `\r\n` or `\n`, and a trailing line break does not start a new row.
+/// Returns an error if a quoted field is never closed.
+#[cfg(not(feature = "async"))]
+fn parse_csv(text: &str) -> Result<Vec<Vec<String>>, Error> {
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        match (in_quotes, char) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                let _ = chars.next();
+                field.push('"');
+            }
+            (true, '"') => in_quotes = false,
+            (true, _) => field.push(char),
+            (false, '"') => in_quotes = true,
+            (false, ',') => row.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            (false, _) => field.push(char),
+        }
+    }
+
+    if in_quotes {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "csv ends inside a quoted field",
+        ));
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// Most bytes of a serialized `WriteMany` packet sent when importing a table, leaving room in the packet for encryption
+#[cfg(not(feature = "async"))]
+const IMPORT_BATCH_BYTES: usize = 512;
+
 #[derive(Debug)]
 /// `SmolDbClient` struct used for communicating to the database.
 /// This struct has implementations that allow for end to end communication with the database server.
 pub struct SmolDbClient {
     socket: TcpStream,
+    /// Address of the server, kept so the client can reconnect after the socket has been shut down
+    server_address: SocketAddr,
     encryption: Option<ClientKey>,
+    /// Fingerprint the server key must have when setting up encryption, set by `pin_server_fingerprint` or the first time encryption is setup
+    pinned_fingerprint: Option<String>,
+    /// Fingerprints of servers trusted on first use, shared between runs of the program
+    known_servers: Option<KnownServers>,
+    /// Values and settings read recently, set by `enable_cache`
+    #[cfg(feature = "cache")]
+    cache: Option<ResponseCache>,
+    /// Called after every request, set by `set_request_hook`
+    request_hook: Option<BoxedRequestHook>,
+    /// Writes that could not be sent while disconnected, set by `set_offline_queue`
+    offline_queue: Option<OfflineQueue>,
 }
 
 impl SmolDbClient {
-
     #[allow(dead_code)]
     pub(crate) fn get_socket(&mut self) -> &mut TcpStream {
         &mut self.socket
     }
 
+    /// Reads a whole message from the server, which can take more than one read when it is larger than the read buffer.
+    /// Reads until the message is as long as the length given in its checksum header, so nothing is left behind to be mistaken for the next response.
+    /// Fails with `ErrorKind::InvalidData` as soon as the data read can not be a message, such as a header giving a length over `MAX_PACKET_SIZE`.
+    #[cfg(not(feature = "async"))]
+    pub(crate) fn read_message(&mut self) -> std::io::Result<Vec<u8>> {
+        let mut message = vec![];
+        let mut buf: [u8; 1024] = [0; 1024];
+        loop {
+            let read_len = self.socket.read(&mut buf)?;
+            if read_len == 0 {
+                return Err(ErrorKind::UnexpectedEof.into());
+            }
+            message.extend_from_slice(&buf[0..read_len]);
+            if !is_incomplete(&message).map_err(|err| Error::new(ErrorKind::InvalidData, err))? {
+                return Ok(message);
+            }
+        }
+    }
+
+    /// Reads a whole message from the server, which can take more than one read when it is larger than the read buffer.
+    /// Reads until the message is as long as the length given in its checksum header, so nothing is left behind to be mistaken for the next response.
+    /// Fails with `ErrorKind::InvalidData` as soon as the data read can not be a message, such as a header giving a length over `MAX_PACKET_SIZE`.
+    #[cfg(feature = "async")]
+    pub(crate) async fn read_message(&mut self) -> std::io::Result<Vec<u8>> {
+        let mut message = vec![];
+        let mut buf: [u8; 1024] = [0; 1024];
+        loop {
+            let read_len = self.socket.read(&mut buf).await?;
+            if read_len == 0 {
+                return Err(ErrorKind::UnexpectedEof.into());
+            }
+            message.extend_from_slice(&buf[0..read_len]);
+            if !is_incomplete(&message).map_err(|err| Error::new(ErrorKind::InvalidData, err))? {
+                return Ok(message);
+            }
+        }
+    }
+
+    /// Streams every key and value of the given db, one pair for each call to `next`, so the db does not need to fit in a single packet.
+    /// The stream ends when the iter runs out of pairs or is dropped, after which the client can be used normally again.
+    /// Each pair is returned as a `Result`, ending with the error if reading the stream fails part way through.
+    /// Requires read and stream permissions on the given db
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_stream_table",DBSettings::default()).unwrap();
+    /// for key in 0..5 {
+    ///     let _ = client.write_db("doctest_stream_table",&key.to_string(),"value").unwrap();
+    /// }
+    ///
+    /// let pairs: Vec<(String, String)> = client.stream_table("doctest_stream_table").unwrap().collect::<Result<_, _>>().unwrap();
+    /// assert_eq!(pairs.len(), 5);
+    ///
+    /// // stopping early ends the stream
+    /// let first_two: Vec<(String, String)> = client.stream_table("doctest_stream_table").unwrap().take(2).collect::<Result<_, _>>().unwrap();
+    /// assert_eq!(first_two.len(), 2);
+    /// assert_eq!(client.read_db("doctest_stream_table","0").unwrap(), SuccessReply("value".to_string()));
+    ///
+    /// let _ = client.delete_db("doctest_stream_table").unwrap();
+    /// ```
     #[cfg(not(feature = "async"))]
-    pub fn stream_table(&mut self, table_name: &str) -> Result<TableIter, ClientError> {
+    pub fn stream_table(&mut self, table_name: &str) -> Result<TableIter<'_>, ClientError> {
         let packet = DBPacket::new_stream_table(table_name);
 
         debug!("Sending packet");
@@ -58,820 +217,5287 @@ impl SmolDbClient {
         let resp = self.send_packet(&packet)?;
 
         debug!("Sent packet: {}", resp);
-        let table_iter = TableIter(self);
+        let table_iter = TableIter::new(self, Self::stream_len(&resp));
 
         Ok(table_iter)
     }
 
-    /// Creates a new `SmolDBClient` struct connected to the ip address given.
+    /// Returns the number of pairs the server will stream, sent as the response starting the stream,
+    /// `None` if the server did not send it.
+    #[cfg(not(feature = "async"))]
+    fn stream_len(resp: &DBSuccessResponse<String>) -> Option<usize> {
+        resp.as_option().and_then(|len| len.parse().ok())
+    }
+
+    /// Streams the key value pairs of the given db whose key starts with `key_prefix` and whose value contains `value_contains`,
+    /// an empty prefix or text matches every pair. Pairs that do not match are skipped by the server, so they are never sent.
+    /// Requires read and stream permissions on the given db
     /// ```
-    /// use smol_db_client::prelude::SmolDbClient;
+    /// use smol_db_client::prelude::*;
     ///
-    /// // create the new client
     /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
-    /// // client should be functional provided a database server was able to be connected to at the given location
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_stream_filtered",DBSettings::default()).unwrap();
+    /// let _ = client.write_db("doctest_stream_filtered","user:1","active").unwrap();
+    /// let _ = client.write_db("doctest_stream_filtered","user:2","inactive").unwrap();
+    /// let _ = client.write_db("doctest_stream_filtered","group:1","active").unwrap();
+    ///
+    /// let users: Vec<(String, String)> = client.stream_table_filtered("doctest_stream_filtered","user:","").unwrap().collect::<Result<_, _>>().unwrap();
+    /// assert_eq!(users.len(), 2);
+    ///
+    /// let inactive_users: Vec<(String, String)> = client.stream_table_filtered("doctest_stream_filtered","user:","inactive").unwrap().collect::<Result<_, _>>().unwrap();
+    /// assert_eq!(inactive_users, vec![("user:2".to_string(), "inactive".to_string())]);
+    ///
+    /// let _ = client.delete_db("doctest_stream_filtered").unwrap();
     /// ```
     #[cfg(not(feature = "async"))]
     #[tracing::instrument]
-    pub fn new(ip: &str) -> Result<Self, ClientError> {
-        info!("Creating new client");
-        let socket = TcpStream::connect(ip);
-        match socket {
-            Ok(s) => Ok(Self {
-                socket: s,
-                encryption: None,
-            }),
-            Err(err) => {
-                error!("Error creating client: {}", err);
-                Err(UnableToConnect(err))
-            }
-        }
-    }
+    pub fn stream_table_filtered(
+        &mut self,
+        table_name: &str,
+        key_prefix: &str,
+        value_contains: &str,
+    ) -> Result<TableIter<'_>, ClientError> {
+        let packet = DBPacket::new_stream_table_filtered(table_name, key_prefix, value_contains);
 
-    #[cfg(feature = "async")]
-    #[tracing::instrument]
-    pub async fn new(ip: &str) -> Result<Self, ClientError> {
-        info!("Creating new client");
-        let socket = TcpStream::connect(ip).await;
-        match socket {
-            Ok(s) => Ok(Self {
-                socket: s,
-                encryption: None,
-            }),
-            Err(err) => {
-                error!("Error creating client: {}", err);
-                Err(UnableToConnect(err))
-            }
-        }
+        let resp = self.send_packet(&packet)?;
+
+        debug!("Sent packet: {}", resp);
+        Ok(TableIter::new(self, Self::stream_len(&resp)))
     }
 
-    /// Requests the server to use encryption for communication. Encryption is done both ways, and is done using RSA with a 2048-bit key
-    /// This function is slow due to large rsa key size ~1-4 seconds to generate the key
-    /// Encryption is done invisibly.
+    /// Opens a stream of the given db that does not take over the connection, returning a handle to read it with `next_stream_item`.
+    /// Any other request can be sent while the stream is open, including opening more streams.
+    /// Requires read and stream permissions on the given db
     /// ```
-    /// use smol_db_client::prelude::SmolDbClient;
-    /// use smol_db_common::prelude::DBSettings;
+    /// use smol_db_client::prelude::*;
     ///
-    /// let key = "test_key_123";
     /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
-    /// client.set_access_key(key.to_string()).unwrap();
-    /// client.setup_encryption().unwrap(); // encryption is done invisibly after it is setup, nothing else is needed :)
-    /// client.create_db("docsetup_encryption_test",DBSettings::default()).unwrap();
-    /// let _ = client.delete_db("docsetup_encryption_test").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_open_stream",DBSettings::default()).unwrap();
+    /// for key in 0..3 {
+    ///     let _ = client.write_db("doctest_open_stream",&key.to_string(),"value").unwrap();
+    /// }
+    ///
+    /// let handle = client.open_stream("doctest_open_stream").unwrap();
+    /// let mut streamed = vec![];
+    /// while let Some((key, value)) = client.next_stream_item(&handle).unwrap() {
+    ///     // the client can be used normally while the stream is open
+    ///     assert_eq!(client.read_db("doctest_open_stream",&key).unwrap(), SuccessReply(value.clone()));
+    ///     streamed.push((key, value));
+    /// }
+    /// assert_eq!(streamed.len(), 3);
+    ///
+    /// // a stream can also be closed before it is read to its end
+    /// let handle = client.open_stream("doctest_open_stream").unwrap();
+    /// assert!(client.next_stream_item(&handle).unwrap().is_some());
+    /// let _ = client.close_stream(handle).unwrap();
+    ///
+    /// let _ = client.delete_db("doctest_open_stream").unwrap();
     /// ```
     #[cfg(not(feature = "async"))]
     #[tracing::instrument]
-    pub fn setup_encryption(&mut self) -> Result<DBSuccessResponse<String>, ClientError> {
-        info!("Setting up encryption on client");
-        let server_pub_key_ser = self
-            .send_packet(&DBPacket::SetupEncryption)?
-            .as_option()
-            .ok_or(EncryptionSetupError)?
-            .to_string();
-        let server_pub_key = serde_json::from_str::<RsaPublicKey>(&server_pub_key_ser)
-            .map_err(|err| PacketDeserializationError(Error::from(err)))?;
-        // this function is really slow due to long key length generation, this can be modified if needed, but at the moment, this is ok.
-        let pri_key = ClientKey::new(server_pub_key).map_err(KeyGenerationError)?;
-        let pub_client_key = pri_key.get_pub_key().clone();
-        self.encryption = Some(pri_key);
-        let resp = self.send_packet(&DBPacket::PubKey(pub_client_key));
-        if resp.is_err() {
-            self.encryption = None;
-            error!("Response from server: {:?}", resp);
-        } else {
-            info!("Response from server: {:?}", resp);
+    pub fn open_stream(&mut self, table_name: &str) -> Result<StreamHandle, ClientError> {
+        let packet = DBPacket::new_open_stream(table_name);
+
+        match self.send_packet(&packet)? {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => data.parse::<u64>().map(StreamHandle).map_err(|_| BadPacket),
         }
-        resp
     }
 
+    /// Opens a stream of the given db that does not take over the connection, returning a handle to read it with `next_stream_item`.
+    /// Any other request can be sent while the stream is open, including opening more streams.
+    /// Requires read and stream permissions on the given db
     #[cfg(feature = "async")]
     #[tracing::instrument]
-    pub async fn setup_encryption(&mut self) -> Result<DBSuccessResponse<String>, ClientError> {
-        info!("Setting up encryption on client");
-        let server_pub_key_ser = self
-            .send_packet(&DBPacket::SetupEncryption)
-            .await?
-            .as_option()
-            .ok_or(EncryptionSetupError)?
-            .to_string();
-        let server_pub_key = serde_json::from_str::<RsaPublicKey>(&server_pub_key_ser)
-            .map_err(|err| PacketDeserializationError(Error::from(err)))?;
-        // this function is really slow due to long key length generation, this can be modified if needed, but at the moment, this is ok.
-        let pri_key = ClientKey::new(server_pub_key).map_err(KeyGenerationError)?;
-        let pub_client_key = pri_key.get_pub_key().clone();
-        self.encryption = Some(pri_key);
-        let resp = self.send_packet(&DBPacket::PubKey(pub_client_key)).await;
-        if resp.is_err() {
-            error!("Response from server: {:?}", resp);
-            self.encryption = None;
-        } else {
-            info!("Response from server: {:?}", resp);
+    pub async fn open_stream(&mut self, table_name: &str) -> Result<StreamHandle, ClientError> {
+        let packet = DBPacket::new_open_stream(table_name);
+
+        match self.send_packet(&packet).await? {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => data.parse::<u64>().map(StreamHandle).map_err(|_| BadPacket),
         }
-        resp
     }
 
-    /// Returns true if end-to-end encryption is enabled
+    /// Returns the next key and value of the open stream, or `None` once every pair was read, after which the stream is closed.
+    /// Returns `StreamNotFound` if the stream is not open.
+    #[cfg(not(feature = "async"))]
     #[tracing::instrument]
-    pub fn is_encryption_enabled(&self) -> bool {
-        self.encryption.is_some()
+    pub fn next_stream_item(
+        &mut self,
+        handle: &StreamHandle,
+    ) -> Result<Option<(String, String)>, ClientError> {
+        let packet = DBPacket::new_read_stream_item(handle.0);
+        let resp = self.send_packet(&packet)?;
+        Self::stream_item_from_response(resp)
     }
 
-    /// Reconnects the client, this will reset the session, which can be used to remove any key that was used.
-    /// Or to reconnect in the event of a loss of connection
+    /// Returns the next key and value of the open stream, or `None` once every pair was read, after which the stream is closed.
+    /// Returns `StreamNotFound` if the stream is not open.
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn next_stream_item(
+        &mut self,
+        handle: &StreamHandle,
+    ) -> Result<Option<(String, String)>, ClientError> {
+        let packet = DBPacket::new_read_stream_item(handle.0);
+        let resp = self.send_packet(&packet).await?;
+        Self::stream_item_from_response(resp)
+    }
+
+    fn stream_item_from_response(
+        resp: DBSuccessResponse<String>,
+    ) -> Result<Option<(String, String)>, ClientError> {
+        match resp {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<StreamItem>(&data) {
+                Ok(StreamItem::Item(key, value)) => Ok(Some((key, value))),
+                Ok(StreamItem::StreamEnd) => Ok(None),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Closes the open stream before it is read to its end, freeing the copy of the table the server holds for it.
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn close_stream(
+        &mut self,
+        handle: StreamHandle,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_close_stream(handle.0);
+        self.send_packet(&packet)
+    }
+
+    /// Closes the open stream before it is read to its end, freeing the copy of the table the server holds for it.
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn close_stream(
+        &mut self,
+        handle: StreamHandle,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_close_stream(handle.0);
+        self.send_packet(&packet).await
+    }
+
+    /// Writes every key and value of the given db to the writer as csv, with a `key,value` header row.
+    /// The table is streamed, so it does not need to fit in memory. Returns the number of rows written, not counting the header.
+    /// Requires read permissions on the given db
     /// ```
-    /// use smol_db_client::prelude::SmolDbClient;
-    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    /// use smol_db_client::prelude::*;
     ///
-    /// // disconnecting is optional between reconnects
-    /// client.disconnect().unwrap();
-    /// client.reconnect().unwrap();
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
     ///
-    /// // as shown here
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_export_csv",DBSettings::default()).unwrap();
+    /// let _ = client.write_db("doctest_export_csv","name","smol, \"db\"").unwrap();
     ///
-    /// client.reconnect().unwrap();
+    /// let mut csv = vec![];
+    /// assert_eq!(client.export_table_csv("doctest_export_csv",&mut csv).unwrap(), 1);
+    /// assert_eq!(String::from_utf8(csv).unwrap(), "key,value\r\nname,\"smol, \"\"db\"\"\"\r\n");
     ///
+    /// let _ = client.delete_db("doctest_export_csv").unwrap();
     /// ```
     #[cfg(not(feature = "async"))]
-    #[tracing::instrument]
-    pub fn reconnect(&mut self) -> Result<(), ClientError> {
-        info!("Reconnecting client to database");
-        let ip = self.socket.peer_addr().map_err(UnableToConnect)?;
-        let new_socket = TcpStream::connect(ip).map_err(UnableToConnect)?;
-        self.socket = new_socket;
-        Ok(())
-    }
+    #[tracing::instrument(skip(writer))]
+    pub fn export_table_csv(
+        &mut self,
+        table_name: &str,
+        writer: &mut impl Write,
+    ) -> Result<usize, ClientError> {
+        writer
+            .write_all(b"key,value\r\n")
+            .map_err(ValueStreamError)?;
 
-    /// Reconnects the client, this will reset the session, which can be used to remove any key that was used.
-    /// Or to reconnect in the event of a loss of connection
-    #[cfg(feature = "async")]
-    #[tracing::instrument]
-    pub async fn reconnect(&mut self) -> Result<(), ClientError> {
-        info!("Reconnecting client to database");
-        let ip = self.socket.peer_addr().map_err(UnableToConnect)?;
-        let new_socket = TcpStream::connect(ip).await.map_err(UnableToConnect)?;
-        self.socket = new_socket;
-        Ok(())
-    }
+        let mut rows = 0;
+        for pair in self.stream_table(table_name)? {
+            let (key, value) = pair?;
+            write!(writer, "{},{}\r\n", csv_field(&key), csv_field(&value))
+                .map_err(ValueStreamError)?;
+            rows += 1;
+        }
 
-    /// Returns a result containing the peer address of this client
-    #[tracing::instrument]
-    pub fn get_connected_ip(&self) -> std::io::Result<SocketAddr> {
-        self.socket.peer_addr()
+        Ok(rows)
     }
 
-    /// Disconnects the socket from the database.
+    /// Writes every key and value of the given db to the writer as a single json object, with each key mapped to its value.
+    /// The table is streamed, so it does not need to fit in memory. Returns the number of keys written.
+    /// Requires read permissions on the given db
     /// ```
-    /// use smol_db_client::prelude::SmolDbClient;
+    /// use smol_db_client::prelude::*;
     ///
     /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
     ///
-    /// // disconnect the client
-    /// let _ = client.disconnect().expect("Failed to disconnect socket");
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_export_json",DBSettings::default()).unwrap();
+    /// let _ = client.write_db("doctest_export_json","name","smol_db").unwrap();
+    ///
+    /// let mut json = vec![];
+    /// assert_eq!(client.export_table_json("doctest_export_json",&mut json).unwrap(), 1);
+    /// assert_eq!(String::from_utf8(json).unwrap(), r#"{"name":"smol_db"}"#);
+    ///
+    /// let _ = client.delete_db("doctest_export_json").unwrap();
     /// ```
     #[cfg(not(feature = "async"))]
-    #[tracing::instrument]
-    pub fn disconnect(&self) -> std::io::Result<()> {
-        info!("Disconnecting client from database");
-        self.socket.shutdown(Shutdown::Both)
-    }
+    #[tracing::instrument(skip(writer))]
+    pub fn export_table_json(
+        &mut self,
+        table_name: &str,
+        writer: &mut impl Write,
+    ) -> Result<usize, ClientError> {
+        writer.write_all(b"{").map_err(ValueStreamError)?;
 
-    /// Disconnects the socket from the database.
-    #[cfg(feature = "async")]
-    #[tracing::instrument]
-    pub async fn disconnect(&mut self) -> std::io::Result<()> {
-        info!("Disconnecting client from database");
-        self.socket.shutdown().await
+        let mut keys = 0;
+        for pair in self.stream_table(table_name)? {
+            let (key, value) = pair?;
+            if keys > 0 {
+                writer.write_all(b",").map_err(ValueStreamError)?;
+            }
+            serde_json::to_writer(&mut *writer, &key)
+                .map_err(|err| ValueStreamError(Error::from(err)))?;
+            writer.write_all(b":").map_err(ValueStreamError)?;
+            serde_json::to_writer(&mut *writer, &value)
+                .map_err(|err| ValueStreamError(Error::from(err)))?;
+            keys += 1;
+        }
+
+        writer.write_all(b"}").map_err(ValueStreamError)?;
+        Ok(keys)
     }
 
-    /// Deletes the data at the given db location, requires permissions to do so.
+    /// Writes every key and value of csv with a `key,value` header row, as written by `export_table_csv`, to the given db.
+    /// The pairs are written in batches, existing values at the keys are overwritten. Returns the number of rows written, not counting the header.
+    /// Requires write permissions on the given db
     /// ```
-    /// use smol_db_client::client_error::ClientError;
-    /// use smol_db_client::prelude::SmolDbClient;
-    /// use smol_db_common::db_packets::db_packet_response::DBPacketResponseError;
-    /// use smol_db_common::db_packets::db_settings::DBSettings;
+    /// use smol_db_client::prelude::*;
     ///
     /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
     ///
     /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
-    /// let _ = client.create_db("doctest_delete_data",DBSettings::default()).unwrap();
-    /// let _ = client.write_db("doctest_delete_data","cool_data_location","cool_data");
-    /// let read_data1 = client.read_db("doctest_delete_data","cool_data_location").unwrap().as_option().unwrap().to_string();
-    /// assert_eq!(read_data1.as_str(),"cool_data");
+    /// let _ = client.create_db("doctest_import_csv",DBSettings::default()).unwrap();
     ///
-    /// // delete the data at the given location
-    /// let _ = client.delete_data("doctest_delete_data","cool_data_location").unwrap();
-    /// let read_data2 = client.read_db("doctest_delete_data","cool_data_location");
-    /// assert_eq!(read_data2.unwrap_err(),ClientError::DBResponseError(DBPacketResponseError::ValueNotFound)); // is err here means DBResponseError(ValueNotFound)
+    /// let csv = "key,value\r\nname,\"smol, \"\"db\"\"\"\r\n";
+    /// assert_eq!(client.import_table_csv("doctest_import_csv",&mut csv.as_bytes()).unwrap(), 1);
+    /// assert_eq!(client.read_db("doctest_import_csv","name").unwrap(), SuccessReply("smol, \"db\"".to_string()));
     ///
-    /// let _ = client.delete_db("doctest_delete_data").unwrap();
+    /// let _ = client.delete_db("doctest_import_csv").unwrap();
     /// ```
     #[cfg(not(feature = "async"))]
-    #[tracing::instrument]
-    pub fn delete_data(
+    #[tracing::instrument(skip(reader))]
+    pub fn import_table_csv(
         &mut self,
-        db_name: &str,
-        db_location: &str,
-    ) -> Result<DBSuccessResponse<String>, ClientError> {
-        let packet = DBPacket::new_delete_data(db_name, db_location);
-        self.send_packet(&packet)
+        table_name: &str,
+        reader: &mut impl Read,
+    ) -> Result<usize, ClientError> {
+        let mut text = String::new();
+        let _ = reader.read_to_string(&mut text).map_err(ValueStreamError)?;
+
+        let mut rows = parse_csv(&text).map_err(ValueStreamError)?.into_iter();
+        if rows.next().is_some_and(|header| header != ["key", "value"]) {
+            return Err(ValueStreamError(Error::new(
+                ErrorKind::InvalidData,
+                "csv does not start with a key,value header row",
+            )));
+        }
+
+        let pairs = rows
+            .map(|row| match <[String; 2]>::try_from(row) {
+                Ok([key, value]) => Ok((key, value)),
+                Err(_) => Err(ValueStreamError(Error::new(
+                    ErrorKind::InvalidData,
+                    "csv row does not have exactly a key and a value",
+                ))),
+            })
+            .collect::<Result<Vec<(String, String)>, ClientError>>()?;
+
+        self.write_table_pairs(table_name, &pairs)
     }
 
-    /// Deletes the data at the given db location, requires permissions to do so.
-    #[cfg(feature = "async")]
-    #[tracing::instrument]
-    pub async fn delete_data(
+    /// Writes every key and value of a json object, as written by `export_table_json`, to the given db.
+    /// Values that are not strings are written as json. The pairs are written in batches, existing values at the keys are overwritten.
+    /// Returns the number of keys written.
+    /// Requires write permissions on the given db
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_import_json",DBSettings::default()).unwrap();
+    ///
+    /// let json = r#"{"name":"smol_db","stars":5}"#;
+    /// assert_eq!(client.import_table_json("doctest_import_json",&mut json.as_bytes()).unwrap(), 2);
+    /// assert_eq!(client.read_db("doctest_import_json","stars").unwrap(), SuccessReply("5".to_string()));
+    ///
+    /// let _ = client.delete_db("doctest_import_json").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument(skip(reader))]
+    pub fn import_table_json(
         &mut self,
-        db_name: &str,
-        db_location: &str,
-    ) -> Result<DBSuccessResponse<String>, ClientError> {
-        let packet = DBPacket::new_delete_data(db_name, db_location);
-        self.send_packet(&packet).await
+        table_name: &str,
+        reader: &mut impl Read,
+    ) -> Result<usize, ClientError> {
+        let object: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_reader(reader).map_err(|err| ValueStreamError(Error::from(err)))?;
+
+        let pairs = object
+            .into_iter()
+            .map(|(key, value)| match value {
+                serde_json::Value::String(value) => (key, value),
+                value => (key, value.to_string()),
+            })
+            .collect::<Vec<(String, String)>>();
+
+        self.write_table_pairs(table_name, &pairs)
     }
 
-    /// Returns the `DBStatistics` struct if permissions allow it on a given db
-    #[cfg(feature = "statistics")]
+    /// Writes the pairs to the db in batches that serialize to at most `IMPORT_BATCH_BYTES`, returning the number of pairs written.
+    /// A pair larger than that is written in a batch of its own.
     #[cfg(not(feature = "async"))]
-    #[tracing::instrument]
-    pub fn get_stats(&mut self, db_name: &str) -> Result<DBStatistics, ClientError> {
-        let packet = DBPacket::new_get_stats(db_name);
-        let resp = self.send_packet(&packet)?;
+    fn write_table_pairs(
+        &mut self,
+        table_name: &str,
+        pairs: &[(String, String)],
+    ) -> Result<usize, ClientError> {
+        let mut batch: Vec<(&str, &str)> = vec![];
 
-        match resp {
-            SuccessNoData => Err(BadPacket),
-            SuccessReply(data) => match serde_json::from_str::<DBStatistics>(&data) {
-                Ok(statistics) => Ok(statistics),
-                Err(err) => Err(PacketDeserializationError(Error::from(err))),
-            },
+        for (key, value) in pairs {
+            batch.push((key, value));
+            let batch_bytes = DBPacket::new_write_many(table_name, &batch)
+                .serialize_packet()
+                .map_err(|err| PacketSerializationError(Error::from(err)))?
+                .len();
+            if batch.len() > 1 && batch_bytes > IMPORT_BATCH_BYTES {
+                let last = batch.pop();
+                let _ = self.write_many(table_name, &batch)?;
+                batch = last.into_iter().collect();
+            }
+        }
+        if !batch.is_empty() {
+            let _ = self.write_many(table_name, &batch)?;
         }
+
+        Ok(pairs.len())
     }
 
-    /// Returns the `DBStatistics` struct if permissions allow it on a given db
-    #[cfg(feature = "statistics")]
-    #[cfg(feature = "async")]
+    /// Watches the given db for changes to keys matching the pattern, returning an iterator that blocks until each change happens.
+    /// The connection is dedicated to the watch until the returned `WatchIter` is dropped.
+    /// Requires read permissions on the given db
+    /// ```
+    /// use smol_db_client::prelude::*;
+    /// use std::thread;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_watch",DBSettings::default()).unwrap();
+    ///
+    /// let mut watch = client.watch("doctest_watch", KeyPattern::new_glob("user_*")).unwrap();
+    ///
+    /// let writer = thread::spawn(|| {
+    ///     let mut writer = SmolDbClient::new("localhost:8222").unwrap();
+    ///     let _ = writer.set_access_key("test_key_123".to_string()).unwrap();
+    ///     let _ = writer.write_db("doctest_watch", "other", "ignored").unwrap();
+    ///     let _ = writer.write_db("doctest_watch", "user_1", "bob").unwrap();
+    /// });
+    ///
+    /// let event = watch.next().unwrap();
+    /// assert_eq!(event, ChangeEvent::Written { key: "user_1".to_string(), value: "bob".to_string() });
+    /// drop(watch);
+    /// writer.join().unwrap();
+    ///
+    /// let _ = client.delete_db("doctest_watch").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
     #[tracing::instrument]
-    pub async fn get_stats(&mut self, db_name: &str) -> Result<DBStatistics, ClientError> {
-        let packet = DBPacket::new_get_stats(db_name);
-        let resp = self.send_packet(&packet).await?;
+    pub fn watch(
+        &mut self,
+        db_name: &str,
+        pattern: KeyPattern,
+    ) -> Result<WatchIter<'_>, ClientError> {
+        let packet = DBPacket::new_subscribe(db_name, pattern);
 
-        match resp {
-            SuccessNoData => Err(BadPacket),
-            SuccessReply(data) => match serde_json::from_str::<DBStatistics>(&data) {
-                Ok(statistics) => Ok(statistics),
-                Err(err) => Err(PacketDeserializationError(Error::from(err))),
-            },
-        }
+        let _ = self.send_packet(&packet)?;
+
+        Ok(WatchIter::new(self))
     }
 
-    /// Returns the role of the given client in the given db.
+    /// Subscribes to the pub/sub channel, returning an iterator that blocks until each message is published to the channel.
+    /// The connection is dedicated to the subscription until the returned `WatchIter` is dropped.
     /// ```
-    /// use smol_db_client::prelude::SmolDbClient;
-    /// use smol_db_common::db::Role;
-    /// use smol_db_common::db_packets::db_settings::DBSettings;
+    /// use smol_db_client::prelude::*;
+    /// use std::thread;
     ///
     /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
     ///
-    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
-    /// let _ = client.create_db("doctest_get_role",DBSettings::default()).unwrap();
+    /// let mut subscription = client.subscribe("doctest_channel").unwrap();
     ///
-    /// // get the given clients role from a db
-    /// let role = client.get_role("doctest_get_role").unwrap();
-    /// assert_eq!(role,Role::SuperAdmin);
+    /// let publisher = thread::spawn(|| {
+    ///     let mut publisher = SmolDbClient::new("localhost:8222").unwrap();
+    ///     assert_eq!(publisher.publish("doctest_channel", "hello").unwrap(), 1);
+    /// });
     ///
-    /// let _ = client.delete_db("doctest_get_role").unwrap();
+    /// assert_eq!(subscription.next().unwrap(), "hello");
+    /// drop(subscription);
+    /// publisher.join().unwrap();
     /// ```
     #[cfg(not(feature = "async"))]
     #[tracing::instrument]
-    pub fn get_role(&mut self, db_name: &str) -> Result<Role, ClientError> {
-        let packet = DBPacket::new_get_role(db_name);
+    pub fn subscribe(&mut self, channel_name: &str) -> Result<WatchIter<'_, String>, ClientError> {
+        let packet = DBPacket::new_subscribe_channel(channel_name);
 
-        let resp = self.send_packet(&packet)?;
+        let _ = self.send_packet(&packet)?;
 
-        match resp {
-            SuccessNoData => Err(BadPacket),
-            SuccessReply(data) => match serde_json::from_str::<Role>(&data) {
-                Ok(role) => Ok(role),
-                Err(err) => Err(PacketDeserializationError(Error::from(err))),
-            },
-        }
+        Ok(WatchIter::new(self))
     }
 
-    /// Returns the role of the given client in the given db.
-    #[cfg(feature = "async")]
+    /// Streams each value of the list in the given db in order, returning an iterator that ends once every value has been received.
+    /// The values are copied when the stream begins, so changes made to the list during the stream are not seen.
+    /// The connection is dedicated to the stream until the returned `WatchIter` is dropped.
+    /// Requires permissions to read from the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_stream_list",DBSettings::default()).unwrap();
+    /// let _ = client.list_push("doctest_stream_list","list","a").unwrap();
+    /// let _ = client.list_push("doctest_stream_list","list","b").unwrap();
+    ///
+    /// let values: Vec<String> = client.stream_list("doctest_stream_list","list").unwrap().collect();
+    /// assert_eq!(values, vec!["a".to_string(), "b".to_string()]);
+    ///
+    /// let _ = client.delete_db("doctest_stream_list").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
     #[tracing::instrument]
-    pub async fn get_role(&mut self, db_name: &str) -> Result<Role, ClientError> {
-        let packet = DBPacket::new_get_role(db_name);
+    pub fn stream_list(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+    ) -> Result<WatchIter<'_, String>, ClientError> {
+        let packet = DBPacket::new_stream_list(db_name, list_name);
 
-        let resp = self.send_packet(&packet).await?;
+        let _ = self.send_packet(&packet)?;
 
-        match resp {
-            SuccessNoData => Err(BadPacket),
-            SuccessReply(data) => match serde_json::from_str::<Role>(&data) {
-                Ok(role) => Ok(role),
-                Err(err) => Err(PacketDeserializationError(Error::from(err))),
-            },
-        }
+        Ok(WatchIter::new(self))
     }
 
-    /// Gets the `DBSettings` of the given DB.
-    /// Error on IO error, or when database name does not exist, or when the user lacks permissions to view `DBSettings`.
+    /// Writes every byte from the reader to the location in the given db as a binary value, sending it in chunks so it does not need to fit in a single packet.
+    /// The value is only stored once the reader is finished, if reading fails nothing is stored. Returns the number of bytes written.
+    /// The value can be read back using `stream_read_db`, or `read_db_bytes` if it is small.
+    /// Requires permissions to write to the given DB
     /// ```
-    /// use smol_db_client::prelude::SmolDbClient;
-    /// use smol_db_common::db_packets::db_settings::DBSettings;
+    /// use smol_db_client::prelude::*;
     ///
     /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
     ///
     /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
-    /// let _ = client.create_db("doctest_get_db_settings",DBSettings::default()).unwrap();
+    /// let _ = client.create_db("doctest_stream_write_db",DBSettings::default()).unwrap();
     ///
-    /// // get the db settings
-    /// let settings = client.get_db_settings("doctest_get_db_settings").unwrap();
-    /// assert_eq!(settings,DBSettings::default());
+    /// let data = vec![7u8; 4000];
+    /// assert_eq!(client.stream_write_db("doctest_stream_write_db","blob",&mut data.as_slice()).unwrap(), 4000);
     ///
-    /// let _ = client.delete_db("doctest_get_db_settings").unwrap();
+    /// let mut read_back = vec![];
+    /// assert_eq!(client.stream_read_db("doctest_stream_write_db","blob",&mut read_back).unwrap(), 4000);
+    /// assert_eq!(read_back, data);
+    ///
+    /// let _ = client.delete_db("doctest_stream_write_db").unwrap();
     /// ```
     #[cfg(not(feature = "async"))]
-    #[tracing::instrument]
-    pub fn get_db_settings(&mut self, db_name: &str) -> Result<DBSettings, ClientError> {
-        let packet = DBPacket::new_get_db_settings(db_name);
+    #[tracing::instrument(skip(reader))]
+    pub fn stream_write_db(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+        reader: &mut impl Read,
+    ) -> Result<usize, ClientError> {
+        let _ = self.send_packet(&DBPacket::new_begin_stream_write(db_name, db_location))?;
 
-        let resp = self.send_packet(&packet)?;
-        match resp {
-            SuccessNoData => Err(BadPacket),
-            SuccessReply(data) => match serde_json::from_str::<DBSettings>(&data) {
-                Ok(db_settings) => Ok(db_settings),
-                Err(err) => Err(PacketDeserializationError(Error::from(err))),
-            },
+        self.write_stream_chunks(reader)
+            .map(|(total_len, _)| total_len)
+    }
+
+    /// Sends every byte from the reader as a `StreamChunk`, followed by `EndStreamWrite`, once the server has accepted a stream write.
+    /// Returns the number of bytes sent, and the response to `EndStreamWrite`.
+    #[cfg(not(feature = "async"))]
+    fn write_stream_chunks(
+        &mut self,
+        reader: &mut impl Read,
+    ) -> Result<(usize, DBSuccessResponse<String>), ClientError> {
+        let mut chunk = [0u8; STREAM_CHUNK_SIZE];
+        let mut total_len = 0;
+
+        loop {
+            let read_len = match reader.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(read_len) => read_len,
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => {
+                    let _ = self.send_stream_packet(&DBPacket::AbortStreamWrite);
+                    return Err(ValueStreamError(err));
+                }
+            };
+
+            let packet = DBPacket::StreamChunk(DBBytes::new(chunk[..read_len].to_vec()));
+            let _ = self.send_stream_packet(&packet)?;
+            total_len += read_len;
         }
+
+        let response = self.send_stream_packet(&DBPacket::EndStreamWrite)?;
+
+        Ok((total_len, response))
     }
 
-    /// Gets the `DBSettings` of the given DB.
-    /// Error on IO error, or when database name does not exist, or when the user lacks permissions to view `DBSettings`.
-    #[cfg(feature = "async")]
-    #[tracing::instrument]
-    pub async fn get_db_settings(&mut self, db_name: &str) -> Result<DBSettings, ClientError> {
-        let packet = DBPacket::new_get_db_settings(db_name);
+    /// Reads the binary value at the location in the given db into the writer, receiving it in chunks so it does not need to fit in a single packet.
+    /// Returns the number of bytes read.
+    /// Requires permissions to read from the given DB
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument(skip(writer))]
+    pub fn stream_read_db(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+        writer: &mut impl Write,
+    ) -> Result<usize, ClientError> {
+        let _ = self.send_packet(&DBPacket::new_begin_stream_read(db_name, db_location))?;
 
-        let resp = self.send_packet(&packet).await?;
-        match resp {
-            SuccessNoData => Err(BadPacket),
-            SuccessReply(data) => match serde_json::from_str::<DBSettings>(&data) {
-                Ok(db_settings) => Ok(db_settings),
-                Err(err) => Err(PacketDeserializationError(Error::from(err))),
-            },
+        let mut total_len = 0;
+
+        for chunk in WatchIter::<DBBytes>::new(self) {
+            writer
+                .write_all(chunk.get_data())
+                .map_err(ValueStreamError)?;
+            total_len += chunk.get_data().len();
         }
+
+        Ok(total_len)
     }
 
-    /// Sets the `DBSettings` of a given DB
-    /// Error on IO Error, or when database does not exist, or when the user lacks permissions to set `DBSettings`
+    /// Saves a dump of the given db to the file at the path, receiving it in chunks so it does not need to fit in a single packet.
+    /// The dump holds the content, settings and statistics of the db, and can be imported into any server using `import_db`.
+    /// Returns the number of bytes written to the file.
+    /// Requires super admin privileges on the given DB Server
     /// ```
-    /// use std::time::Duration;
-    /// use smol_db_client::prelude::SmolDbClient;
-    /// use smol_db_common::db_packets::db_settings::DBSettings;
+    /// use smol_db_client::prelude::*;
     ///
     /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
     ///
     /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
-    /// let _ = client.create_db("doctest_set_db_settings",DBSettings::default()).unwrap();
+    /// let _ = client.create_db("doctest_export_db",DBSettings::default()).unwrap();
+    /// let _ = client.write_db("doctest_export_db","key","value").unwrap();
     ///
-    /// // set the new db settings
-    /// let new_settings = DBSettings::new(Duration::from_secs(10),(true,false,true),(false,false,false),vec![],vec![]);
-    /// let _ = client.set_db_settings("doctest_set_db_settings",new_settings.clone()).unwrap();
+    /// let dump_path = std::env::temp_dir().join("doctest_export_db.json");
+    /// assert!(client.export_db("doctest_export_db",&dump_path).unwrap() > 0);
+    /// let _ = client.delete_db("doctest_export_db").unwrap();
     ///
-    /// let settings = client.get_db_settings("doctest_set_db_settings").unwrap();
-    /// assert_eq!(settings,new_settings);
+    /// assert_eq!(client.import_db(&dump_path).unwrap(), "doctest_export_db");
+    /// assert_eq!(client.read_db("doctest_export_db","key").unwrap(),SuccessReply("value".to_string()));
     ///
-    /// let _ = client.delete_db("doctest_set_db_settings").unwrap();
+    /// let _ = client.delete_db("doctest_export_db").unwrap();
+    /// let _ = std::fs::remove_file(dump_path);
     /// ```
     #[cfg(not(feature = "async"))]
     #[tracing::instrument]
-    pub fn set_db_settings(
-        &mut self,
-        db_name: &str,
-        db_settings: DBSettings,
-    ) -> Result<DBSuccessResponse<String>, ClientError> {
-        let packet = DBPacket::new_set_db_settings(db_name, db_settings);
-        self.send_packet(&packet)
+    pub fn export_db(&mut self, db_name: &str, path: &Path) -> Result<usize, ClientError> {
+        let mut file = File::create(path).map_err(ValueStreamError)?;
+
+        let _ = self.send_packet(&DBPacket::new_export_db(db_name))?;
+
+        let mut total_len = 0;
+
+        for chunk in WatchIter::<DBBytes>::new(self) {
+            file.write_all(chunk.get_data()).map_err(ValueStreamError)?;
+            total_len += chunk.get_data().len();
+        }
+
+        Ok(total_len)
     }
 
-    /// Sets the `DBSettings` of a given DB
-    /// Error on IO Error, or when database does not exist, or when the user lacks permissions to set `DBSettings`
-    #[cfg(feature = "async")]
+    /// Creates a db from a dump saved by `export_db`, sending it in chunks so it does not need to fit in a single packet.
+    /// Returns the name of the created db, which is the name the db had when it was exported.
+    /// Requires super admin privileges on the given DB Server
+    #[cfg(not(feature = "async"))]
     #[tracing::instrument]
-    pub async fn set_db_settings(
+    pub fn import_db(&mut self, path: &Path) -> Result<String, ClientError> {
+        let mut file = File::open(path).map_err(ValueStreamError)?;
+
+        let _ = self.send_packet(&DBPacket::new_import_db())?;
+
+        match self.write_stream_chunks(&mut file)? {
+            (_, SuccessReply(db_name)) => Ok(db_name),
+            (_, SuccessNoData | SuccessData(_)) => Err(BadPacket),
+        }
+    }
+
+    /// Sends the packet without encryption and reads the response, used for packets sent while a stream is open.
+    #[cfg(not(feature = "async"))]
+    fn send_stream_packet(
         &mut self,
-        db_name: &str,
-        db_settings: DBSettings,
+        packet: &DBPacket,
     ) -> Result<DBSuccessResponse<String>, ClientError> {
-        let packet = DBPacket::new_set_db_settings(db_name, db_settings);
-        self.send_packet(&packet).await
+        let ser = packet
+            .serialize_packet()
+            .map_err(|err| PacketSerializationError(Error::from(err)))?;
+        self.socket
+            .write_all(ser.as_bytes())
+            .map_err(SocketWriteError)?;
+
+        let message = self.read_message().map_err(SocketReadError)?;
+
+        match deserialize_response(&message) {
+            Ok(response) => response.map_err(DBResponseError),
+            Err(err) => Err(PacketDeserializationError(Error::from(err))),
+        }
     }
 
-    /// Sets this clients access key within the DB Server. The server will persist the key until the session is disconnected, or connection is lost.
+    /// Returns a `TypedTable` for the given db, which reads and writes every value as `T`.
+    /// Avoids repeating the db name and the type of the data on every generic call.
     /// ```
+    /// # #[cfg(not(feature = "async"))]
+    /// # fn main() {
+    /// use serde::{Deserialize, Serialize};
     /// use smol_db_client::prelude::SmolDbClient;
     /// use smol_db_common::db_packets::db_settings::DBSettings;
     ///
+    /// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    /// struct Score {
+    ///     points: u32,
+    /// }
+    ///
     /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
     ///
-    /// // sets the access key of the given client
     /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_typed_table",DBSettings::default()).unwrap();
+    ///
+    /// let mut table = client.table::<Score>("doctest_typed_table");
+    /// let _ = table.write("player1", Score { points: 10 }).unwrap();
+    /// let score = table.read("player1").unwrap().into_option().unwrap();
+    /// assert_eq!(score, Score { points: 10 });
+    ///
+    /// let _ = client.delete_db("doctest_typed_table").unwrap();
+    /// # }
+    /// # #[cfg(feature = "async")]
+    /// # fn main() {}
+    /// ```
+    pub fn table<T>(&mut self, db_name: &str) -> TypedTable<'_, T>
+    where
+        for<'a> T: Serialize + Deserialize<'a>,
+    {
+        TypedTable::new(self, db_name)
+    }
+
+    /// Creates a new `SmolDBClient` struct connected to the ip address given.
+    /// ```
+    /// use smol_db_client::prelude::SmolDbClient;
+    ///
+    /// // create the new client
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    /// // client should be functional provided a database server was able to be connected to at the given location
     /// ```
     #[cfg(not(feature = "async"))]
     #[tracing::instrument]
-    pub fn set_access_key(
-        &mut self,
-        key: String,
-    ) -> Result<DBSuccessResponse<String>, ClientError> {
-        let packet = DBPacket::new_set_key(key);
-        self.send_packet(&packet)
+    pub fn new(ip: &str) -> Result<Self, ClientError> {
+        info!("Creating new client");
+        let socket = TcpStream::connect(ip);
+        match socket {
+            Ok(s) => Ok(Self {
+                server_address: s.peer_addr().map_err(UnableToConnect)?,
+                socket: s,
+                encryption: None,
+                pinned_fingerprint: None,
+                known_servers: None,
+                #[cfg(feature = "cache")]
+                cache: None,
+                request_hook: None,
+                offline_queue: None,
+            }),
+            Err(err) => {
+                error!("Error creating client: {}", err);
+                Err(UnableToConnect(err))
+            }
+        }
     }
 
-    /// Sets this clients access key within the DB Server. The server will persist the key until the session is disconnected, or connection is lost.
     #[cfg(feature = "async")]
     #[tracing::instrument]
-    pub async fn set_access_key(
-        &mut self,
-        key: String,
-    ) -> Result<DBSuccessResponse<String>, ClientError> {
-        let packet = DBPacket::new_set_key(key);
-        self.send_packet(&packet).await
+    pub async fn new(ip: &str) -> Result<Self, ClientError> {
+        info!("Creating new client");
+        let socket = TcpStream::connect(ip).await;
+        match socket {
+            Ok(s) => Ok(Self {
+                server_address: s.peer_addr().map_err(UnableToConnect)?,
+                socket: s,
+                encryption: None,
+                pinned_fingerprint: None,
+                known_servers: None,
+                #[cfg(feature = "cache")]
+                cache: None,
+                request_hook: None,
+                offline_queue: None,
+            }),
+            Err(err) => {
+                error!("Error creating client: {}", err);
+                Err(UnableToConnect(err))
+            }
+        }
     }
 
-    /// Sends a packet to the clients currently connected database and returns the result
+    /// Requests the server to use encryption for communication. Encryption is done both ways, using AES-256-GCM with a session key generated by the client.
+    /// The session key is sent to the server encrypted with the servers 2048-bit RSA public key, so RSA is only used once when setting up encryption.
+    /// Encryption is done invisibly.
+    /// ```
+    /// use smol_db_client::prelude::SmolDbClient;
+    /// use smol_db_common::prelude::DBSettings;
+    ///
+    /// let key = "test_key_123";
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    /// client.set_access_key(key.to_string()).unwrap();
+    /// client.setup_encryption().unwrap(); // encryption is done invisibly after it is setup, nothing else is needed :)
+    /// client.create_db("docsetup_encryption_test",DBSettings::default()).unwrap();
+    /// let _ = client.delete_db("docsetup_encryption_test").unwrap();
+    /// ```
     #[cfg(not(feature = "async"))]
     #[tracing::instrument]
-    pub(crate) fn send_packet(
-        &mut self,
-        sent_packet: &DBPacket,
-    ) -> Result<DBSuccessResponse<String>, ClientError> {
-        let mut buf: [u8; 1024] = [0; 1024];
+    pub fn setup_encryption(&mut self) -> Result<DBSuccessResponse<String>, ClientError> {
+        info!("Setting up encryption on client");
+        let server_pub_key_ser = self
+            .send_packet(&DBPacket::SetupEncryption)?
+            .as_option()
+            .ok_or(EncryptionSetupError)?
+            .to_string();
+        let server_pub_key = serde_json::from_str::<RsaPublicKey>(&server_pub_key_ser)
+            .map_err(|err| PacketDeserializationError(Error::from(err)))?;
+        let client_key = ClientKey::new(server_pub_key).map_err(KeyGenerationError)?;
+        self.verify_server_identity(client_key.get_server_fingerprint())?;
+        let session_key_packet = client_key.session_key_packet();
+        self.encryption = Some(client_key);
+        let resp = self.send_packet(&session_key_packet);
+        if resp.is_err() {
+            self.encryption = None;
+            error!("Response from server: {:?}", resp);
+        } else {
+            info!("Response from server: {:?}", resp);
+        }
+        resp
+    }
 
-        // branch depending on if we are using encryption with communication
-        let ser_packet = match &mut self.encryption {
-            None => {
-                let p = sent_packet
-                    .serialize_packet()
-                    .map_err(|err| PacketSerializationError(Error::from(err)));
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn setup_encryption(&mut self) -> Result<DBSuccessResponse<String>, ClientError> {
+        info!("Setting up encryption on client");
+        let server_pub_key_ser = self
+            .send_packet(&DBPacket::SetupEncryption)
+            .await?
+            .as_option()
+            .ok_or(EncryptionSetupError)?
+            .to_string();
+        let server_pub_key = serde_json::from_str::<RsaPublicKey>(&server_pub_key_ser)
+            .map_err(|err| PacketDeserializationError(Error::from(err)))?;
+        let client_key = ClientKey::new(server_pub_key).map_err(KeyGenerationError)?;
+        self.verify_server_identity(client_key.get_server_fingerprint())?;
+        let session_key_packet = client_key.session_key_packet();
+        self.encryption = Some(client_key);
+        let resp = self.send_packet(&session_key_packet).await;
+        if resp.is_err() {
+            error!("Response from server: {:?}", resp);
+            self.encryption = None;
+        } else {
+            info!("Response from server: {:?}", resp);
+        }
+        resp
+    }
 
-                match p.as_ref() {
-                    Ok(_) => {
-                        info!("Successfully serialized packet");
-                    }
-                    Err(e) => {
-                        error!("Failed to serialize packet: {:?}", e);
-                    }
+    /// Returns true if end-to-end encryption is enabled
+    #[tracing::instrument]
+    pub fn is_encryption_enabled(&self) -> bool {
+        self.encryption.is_some()
+    }
+
+    /// Pins the fingerprint the server key must have, `setup_encryption` errors with `ServerIdentityChanged` if the server presents a different key.
+    /// The fingerprint of a server is printed when it starts, and returned by `server_fingerprint` once encryption is setup.
+    /// Without a pinned fingerprint, the key presented the first time encryption is setup is trusted, and pinned for the rest of the clients life.
+    #[tracing::instrument]
+    pub fn pin_server_fingerprint(&mut self, fingerprint: &str) {
+        self.pinned_fingerprint = Some(fingerprint.to_string());
+    }
+
+    /// Sets the known servers the fingerprint of the server is checked against when setting up encryption.
+    /// A server that is not known yet is trusted on first use, and saved to the known servers.
+    #[tracing::instrument]
+    pub fn set_known_servers(&mut self, known_servers: KnownServers) {
+        self.known_servers = Some(known_servers);
+    }
+
+    /// Returns the fingerprint of the server key encryption was setup with, or None if encryption is not enabled
+    #[tracing::instrument]
+    pub fn server_fingerprint(&self) -> Option<&str> {
+        self.encryption
+            .as_ref()
+            .map(|client_key| client_key.get_server_fingerprint())
+    }
+
+    /// Checks the fingerprint the server presented against the pinned fingerprint and the known servers, erroring with `ServerIdentityChanged` if it differs from either.
+    /// A fingerprint that is not pinned or known yet is trusted, and pinned or saved to the known servers.
+    fn verify_server_identity(&mut self, fingerprint: &str) -> Result<(), ClientError> {
+        let server = self.server_address.to_string();
+        let expected = self.pinned_fingerprint.as_deref().or_else(|| {
+            self.known_servers
+                .as_ref()
+                .and_then(|known_servers| known_servers.get_fingerprint(&server))
+        });
+        if let Some(expected) = expected.filter(|expected| *expected != fingerprint) {
+            error!(
+                "Server {} presented key with fingerprint {}, expected {}",
+                server, fingerprint, expected
+            );
+            return Err(ServerIdentityChanged {
+                expected: expected.to_string(),
+                presented: fingerprint.to_string(),
+            });
+        }
+
+        if let Some(known_servers) = &mut self.known_servers {
+            if known_servers.get_fingerprint(&server).is_none() {
+                // the server is still trusted for the rest of the clients life when saving fails
+                if let Err(err) = known_servers.trust(&server, fingerprint) {
+                    warn!("Unable to save known servers: {}", err);
                 }
+            }
+        }
+        self.pinned_fingerprint = Some(fingerprint.to_string());
+        Ok(())
+    }
 
-                p?
+    /// Reconnects the client, this will reset the session, which can be used to remove any key that was used.
+    /// Or to reconnect in the event of a loss of connection
+    /// ```
+    /// use smol_db_client::prelude::SmolDbClient;
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// // disconnecting is optional between reconnects
+    /// client.disconnect().unwrap();
+    /// client.reconnect().unwrap();
+    ///
+    /// // as shown here
+    ///
+    /// client.reconnect().unwrap();
+    ///
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn reconnect(&mut self) -> Result<(), ClientError> {
+        info!("Reconnecting client to database");
+        let new_socket = TcpStream::connect(self.server_address).map_err(UnableToConnect)?;
+        self.socket = new_socket;
+        // the new connection has no access key, so what was read with the old one may no longer be readable
+        #[cfg(feature = "cache")]
+        self.clear_cache();
+        Ok(())
+    }
+
+    /// Reconnects the client, this will reset the session, which can be used to remove any key that was used.
+    /// Or to reconnect in the event of a loss of connection
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn reconnect(&mut self) -> Result<(), ClientError> {
+        info!("Reconnecting client to database");
+        let new_socket = TcpStream::connect(self.server_address)
+            .await
+            .map_err(UnableToConnect)?;
+        self.socket = new_socket;
+        // the new connection has no access key, so what was read with the old one may no longer be readable
+        #[cfg(feature = "cache")]
+        self.clear_cache();
+        Ok(())
+    }
+
+    /// Sets the hook called after every request the client sends, with the kind of packet, how long the request took, and its result,
+    /// replacing any hook already set. Used to export client side metrics, such as request counts, latencies, and error rates.
+    /// ```
+    /// # #[cfg(not(feature = "async"))]
+    /// # fn main() {
+    /// use smol_db_client::prelude::*;
+    /// use std::sync::{Arc, Mutex};
+    /// use std::time::Duration;
+    ///
+    /// let requests = Arc::new(Mutex::new(vec![]));
+    /// let recorded = requests.clone();
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    /// client.set_request_hook(move |packet_kind: &str, _duration: Duration, result: &Result<DBSuccessResponse<String>, client_error::ClientError>| {
+    ///     recorded.lock().unwrap().push((packet_kind.to_string(), result.is_ok()));
+    /// });
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.read_db("doctest_request_hook_missing","key");
+    ///
+    /// assert_eq!(*requests.lock().unwrap(), vec![("SetKey".to_string(), true), ("Read".to_string(), false)]);
+    /// # }
+    /// # #[cfg(feature = "async")]
+    /// # fn main() {}
+    /// ```
+    pub fn set_request_hook(&mut self, hook: impl RequestHook + 'static) {
+        self.request_hook = Some(BoxedRequestHook(Box::new(hook)));
+    }
+
+    /// Removes the request hook, if one is set
+    pub fn clear_request_hook(&mut self) {
+        self.request_hook = None;
+    }
+
+    /// Sets the queue `write_db` and `delete_data` queue writes in when the server can not be reached, replacing any queue already set.
+    /// The queued writes are sent with `replay_offline_queue` once the client is connected again.
+    /// ```
+    /// # #[cfg(not(feature = "async"))]
+    /// # fn main() {
+    /// use smol_db_client::prelude::*;
+    /// use smol_db_client::offline_queue::LastWriteWins;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_offline_queue",DBSettings::default()).unwrap();
+    /// client.set_offline_queue(OfflineQueue::new(100));
+    ///
+    /// // writes made while disconnected are queued
+    /// client.disconnect().unwrap();
+    /// assert_eq!(client.write_db("doctest_offline_queue","key","value").unwrap(), SuccessNoData);
+    /// assert_eq!(client.offline_queue().unwrap().len(), 1);
+    ///
+    /// // and sent once connected again
+    /// client.reconnect().unwrap();
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// assert_eq!(client.replay_offline_queue(&LastWriteWins).unwrap().applied, 1);
+    /// assert_eq!(client.read_db("doctest_offline_queue","key").unwrap(), SuccessReply("value".to_string()));
+    ///
+    /// let _ = client.delete_db("doctest_offline_queue").unwrap();
+    /// # }
+    /// # #[cfg(feature = "async")]
+    /// # fn main() {}
+    /// ```
+    pub fn set_offline_queue(&mut self, offline_queue: OfflineQueue) {
+        self.offline_queue = Some(offline_queue);
+    }
+
+    /// Returns the offline queue, if one is set
+    pub fn offline_queue(&self) -> Option<&OfflineQueue> {
+        self.offline_queue.as_ref()
+    }
+
+    /// Removes the offline queue and returns it, after which writes are no longer queued
+    pub fn take_offline_queue(&mut self) -> Option<OfflineQueue> {
+        self.offline_queue.take()
+    }
+
+    /// Sends the writes in the offline queue in the order they were made, oldest first.
+    /// Before each write, the value the key has on the server is read and passed to the conflict policy, which decides whether to send, skip, or replace the write.
+    /// Writes are removed from the queue once sent, skipped, or rejected by the server, if the server can not be reached the rest stay queued and the error is returned.
+    /// Should be called once the client is connected again and its access key set, before making new writes.
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument(skip(policy))]
+    pub fn replay_offline_queue(
+        &mut self,
+        policy: &impl ConflictPolicy,
+    ) -> Result<ReplayReport, ClientError> {
+        let mut report = ReplayReport::default();
+        while let Some(write) = self
+            .offline_queue
+            .as_ref()
+            .and_then(|queue| queue.front())
+            .cloned()
+        {
+            let result = self.replay_write(&write, policy);
+            Self::record_replay(&mut report, write, result)?;
+            if let Some(queue) = self.offline_queue.as_mut() {
+                queue.pop_front();
             }
-            Some(client_encrypt) => {
-                // if we are sending a public key packet, we don't encrypt it, since the server needs this to send data back properly
-                if !matches!(sent_packet, DBPacket::PubKey(_)) {
-                    let p = client_encrypt
-                        .encrypt_packet(sent_packet)
-                        .map_err(PacketEncryptionError)?
-                        .serialize_packet()
-                        .map_err(|err| PacketSerializationError(Error::from(err)));
+        }
+        Ok(report)
+    }
 
-                    match p.as_ref() {
-                        Ok(_) => {
-                            info!("Successfully encrypted packet");
-                        }
-                        Err(e) => {
-                            error!("Failed to encrypt packet: {:?}", e);
-                        }
-                    }
+    /// Sends the writes in the offline queue in the order they were made, oldest first.
+    /// Before each write, the value the key has on the server is read and passed to the conflict policy, which decides whether to send, skip, or replace the write.
+    /// Writes are removed from the queue once sent, skipped, or rejected by the server, if the server can not be reached the rest stay queued and the error is returned.
+    /// Should be called once the client is connected again and its access key set, before making new writes.
+    #[cfg(feature = "async")]
+    #[tracing::instrument(skip(policy))]
+    pub async fn replay_offline_queue(
+        &mut self,
+        policy: &impl ConflictPolicy,
+    ) -> Result<ReplayReport, ClientError> {
+        let mut report = ReplayReport::default();
+        while let Some(write) = self
+            .offline_queue
+            .as_ref()
+            .and_then(|queue| queue.front())
+            .cloned()
+        {
+            let result = self.replay_write(&write, policy).await;
+            Self::record_replay(&mut report, write, result)?;
+            if let Some(queue) = self.offline_queue.as_mut() {
+                queue.pop_front();
+            }
+        }
+        Ok(report)
+    }
 
-                    p?
-                } else {
-                    let p = sent_packet
-                        .serialize_packet()
-                        .map_err(|err| PacketSerializationError(Error::from(err)));
+    /// Sends a single queued write as the conflict policy decides, returning false if the policy skipped it
+    #[cfg(not(feature = "async"))]
+    fn replay_write(
+        &mut self,
+        write: &QueuedWrite,
+        policy: &impl ConflictPolicy,
+    ) -> Result<bool, ClientError> {
+        let server_value = self
+            .send_packet(&DBPacket::new_read(&write.db_name, &write.key))
+            .optional()?;
+        let value = match policy.resolve(write, server_value.as_deref()) {
+            ConflictResolution::Apply => write.value.clone(),
+            ConflictResolution::Replace(value) => value,
+            ConflictResolution::Skip => return Ok(false),
+        };
+        match value {
+            Some(value) => {
+                self.send_packet(&DBPacket::new_write(&write.db_name, &write.key, &value))?;
+            }
+            None => {
+                // deleting a key that no longer has a value leaves it as the queued delete would have
+                self.send_packet(&DBPacket::new_delete_data(&write.db_name, &write.key))
+                    .optional()?;
+            }
+        }
+        Ok(true)
+    }
 
-                    match p.as_ref() {
-                        Ok(_) => {
-                            info!("Successfully serialized public key packet");
-                        }
-                        Err(e) => {
-                            error!("Failed to serialize public key packet: {:?}", e);
-                        }
-                    }
+    /// Sends a single queued write as the conflict policy decides, returning false if the policy skipped it
+    #[cfg(feature = "async")]
+    async fn replay_write(
+        &mut self,
+        write: &QueuedWrite,
+        policy: &impl ConflictPolicy,
+    ) -> Result<bool, ClientError> {
+        let server_value = self
+            .send_packet(&DBPacket::new_read(&write.db_name, &write.key))
+            .await
+            .optional()?;
+        let value = match policy.resolve(write, server_value.as_deref()) {
+            ConflictResolution::Apply => write.value.clone(),
+            ConflictResolution::Replace(value) => value,
+            ConflictResolution::Skip => return Ok(false),
+        };
+        match value {
+            Some(value) => {
+                self.send_packet(&DBPacket::new_write(&write.db_name, &write.key, &value))
+                    .await?;
+            }
+            None => {
+                // deleting a key that no longer has a value leaves it as the queued delete would have
+                self.send_packet(&DBPacket::new_delete_data(&write.db_name, &write.key))
+                    .await
+                    .optional()?;
+            }
+        }
+        Ok(true)
+    }
 
-                    p?
-                }
+    /// Records how replaying the write went, returning the error if replaying should stop with the write still queued,
+    /// which is when the server could not be reached, or did not respond as expected.
+    fn record_replay(
+        report: &mut ReplayReport,
+        write: QueuedWrite,
+        result: Result<bool, ClientError>,
+    ) -> Result<(), ClientError> {
+        match result {
+            Ok(true) => report.applied += 1,
+            Ok(false) => report.skipped += 1,
+            Err(DBResponseError(err)) => {
+                warn!("Server rejected queued write {:?}: {}", write, err);
+                report.rejected.push((write, err));
+            }
+            Err(err) => return Err(err),
+        }
+        Ok(())
+    }
+
+    /// Sends the packet, or queues the write it makes if an offline queue is set and the server can not be reached,
+    /// or writes are already queued, so they are replayed in the order they were made.
+    #[cfg(not(feature = "async"))]
+    fn send_or_queue(
+        &mut self,
+        packet: &DBPacket,
+        write: QueuedWrite,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let Some(queue) = &self.offline_queue else {
+            return self.send_packet(packet);
+        };
+        if queue.is_empty() {
+            match self.send_packet(packet) {
+                Err(err) if is_offline_error(&err) => {}
+                result => return result,
             }
+        }
+        match self.offline_queue.as_mut() {
+            Some(queue) => queue.push(write).map(|_| SuccessNoData),
+            None => self.send_packet(packet),
+        }
+    }
+
+    /// Sends the packet, or queues the write it makes if an offline queue is set and the server can not be reached,
+    /// or writes are already queued, so they are replayed in the order they were made.
+    #[cfg(feature = "async")]
+    async fn send_or_queue(
+        &mut self,
+        packet: &DBPacket,
+        write: QueuedWrite,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let Some(queue) = &self.offline_queue else {
+            return self.send_packet(packet).await;
         };
+        if queue.is_empty() {
+            match self.send_packet(packet).await {
+                Err(err) if is_offline_error(&err) => {}
+                result => return result,
+            }
+        }
+        match self.offline_queue.as_mut() {
+            Some(queue) => queue.push(write).map(|_| SuccessNoData),
+            None => self.send_packet(packet).await,
+        }
+    }
+
+    /// Remembers each value read with `read_db` and the settings read with `get_db_settings` for the time to live,
+    /// reading them again within it returns the remembered response without a round trip to the server.
+    /// Writes sent by this client remove what they change from the cache, a write to a single key only removes that key,
+    /// while any other change, such as deleting a db or changing the access key, empties the cache.
+    /// Writes by other clients are only seen once the time to live passes, or once passed to `apply_change_event`.
+    /// ```
+    /// # #[cfg(not(feature = "async"))]
+    /// # fn main() {
+    /// use smol_db_client::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_enable_cache",DBSettings::default()).unwrap();
+    /// let _ = client.write_db("doctest_enable_cache","key","old").unwrap();
+    ///
+    /// client.enable_cache(Duration::from_secs(60));
+    /// assert_eq!(client.read_db("doctest_enable_cache","key").unwrap(), SuccessReply("old".to_string()));
+    ///
+    /// let mut other_client = SmolDbClient::new("localhost:8222").unwrap();
+    /// let _ = other_client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = other_client.write_db("doctest_enable_cache","key","new").unwrap();
+    ///
+    /// // the write by the other client is not seen until the cached value is invalidated
+    /// assert_eq!(client.read_db("doctest_enable_cache","key").unwrap(), SuccessReply("old".to_string()));
+    /// client.apply_change_event("doctest_enable_cache", &ChangeEvent::Deleted { key: "key".to_string() });
+    /// assert_eq!(client.read_db("doctest_enable_cache","key").unwrap(), SuccessReply("new".to_string()));
+    ///
+    /// // writes by this client are seen immediately
+    /// let _ = client.write_db("doctest_enable_cache","key","newer").unwrap();
+    /// assert_eq!(client.read_db("doctest_enable_cache","key").unwrap(), SuccessReply("newer".to_string()));
+    ///
+    /// let _ = client.delete_db("doctest_enable_cache").unwrap();
+    /// # }
+    /// # #[cfg(feature = "async")]
+    /// # fn main() {}
+    /// ```
+    #[cfg(feature = "cache")]
+    pub fn enable_cache(&mut self, ttl: Duration) {
+        self.cache = Some(ResponseCache::new(ttl));
+    }
+
+    /// Stops caching responses, forgetting every cached value and setting.
+    #[cfg(feature = "cache")]
+    pub fn disable_cache(&mut self) {
+        self.cache = None;
+    }
+
+    /// Forgets every cached value and setting, so they are read from the server again.
+    #[cfg(feature = "cache")]
+    pub fn clear_cache(&mut self) {
+        if let Some(cache) = self.cache.as_mut() {
+            cache.clear();
+        }
+    }
+
+    /// Removes the key the change happened to from the cache, so the change is seen on the next read.
+    /// Changes read from `watch` on another connection can be passed here, to see writes by other clients before the time to live passes.
+    #[cfg(feature = "cache")]
+    pub fn apply_change_event(&mut self, db_name: &str, event: &ChangeEvent) {
+        if let Some(cache) = self.cache.as_mut() {
+            cache.apply_change_event(db_name, event);
+        }
+    }
+
+    /// Returns a result containing the peer address of this client
+    #[tracing::instrument]
+    pub fn get_connected_ip(&self) -> std::io::Result<SocketAddr> {
+        self.socket.peer_addr()
+    }
+
+    /// Disconnects the socket from the database.
+    /// ```
+    /// use smol_db_client::prelude::SmolDbClient;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// // disconnect the client
+    /// let _ = client.disconnect().expect("Failed to disconnect socket");
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn disconnect(&self) -> std::io::Result<()> {
+        info!("Disconnecting client from database");
+        self.socket.shutdown(Shutdown::Both)
+    }
+
+    /// Disconnects the socket from the database.
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn disconnect(&mut self) -> std::io::Result<()> {
+        info!("Disconnecting client from database");
+        self.socket.shutdown().await
+    }
+
+    /// Deletes the data at the given db location, requires permissions to do so.
+    /// ```
+    /// use smol_db_client::client_error::ClientError;
+    /// use smol_db_client::prelude::SmolDbClient;
+    /// use smol_db_common::db_packets::db_packet_response::DBPacketResponseError;
+    /// use smol_db_common::db_packets::db_settings::DBSettings;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_delete_data",DBSettings::default()).unwrap();
+    /// let _ = client.write_db("doctest_delete_data","cool_data_location","cool_data");
+    /// let read_data1 = client.read_db("doctest_delete_data","cool_data_location").unwrap().as_option().unwrap().to_string();
+    /// assert_eq!(read_data1.as_str(),"cool_data");
+    ///
+    /// // delete the data at the given location
+    /// let _ = client.delete_data("doctest_delete_data","cool_data_location").unwrap();
+    /// let read_data2 = client.read_db("doctest_delete_data","cool_data_location");
+    /// assert_eq!(read_data2.unwrap_err(),ClientError::DBResponseError(DBPacketResponseError::ValueNotFound)); // is err here means DBResponseError(ValueNotFound)
+    ///
+    /// let _ = client.delete_db("doctest_delete_data").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn delete_data(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_delete_data(db_name, db_location);
+        let write = QueuedWrite {
+            db_name: db_name.to_string(),
+            key: db_location.to_string(),
+            value: None,
+        };
+        self.send_or_queue(&packet, write)
+    }
+
+    /// Deletes the data at the given db location, requires permissions to do so.
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn delete_data(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_delete_data(db_name, db_location);
+        let write = QueuedWrite {
+            db_name: db_name.to_string(),
+            key: db_location.to_string(),
+            value: None,
+        };
+        self.send_or_queue(&packet, write).await
+    }
+
+    /// Returns the `DBStatistics` struct if permissions allow it on a given db
+    #[cfg(feature = "statistics")]
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn get_stats(&mut self, db_name: &str) -> Result<DBStatistics, ClientError> {
+        let packet = DBPacket::new_get_stats(db_name);
+        let resp = self.send_packet(&packet)?;
+
+        match resp {
+            SuccessData(ResponseData::Stats(statistics)) => Ok(statistics),
+            SuccessNoData | SuccessReply(_) | SuccessData(_) => Err(BadPacket),
+        }
+    }
+
+    /// Returns the `DBStatistics` struct if permissions allow it on a given db
+    #[cfg(feature = "statistics")]
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn get_stats(&mut self, db_name: &str) -> Result<DBStatistics, ClientError> {
+        let packet = DBPacket::new_get_stats(db_name);
+        let resp = self.send_packet(&packet).await?;
+
+        match resp {
+            SuccessData(ResponseData::Stats(statistics)) => Ok(statistics),
+            SuccessNoData | SuccessReply(_) | SuccessData(_) => Err(BadPacket),
+        }
+    }
+
+    /// Returns the `n` most accessed keys of the given db and their number of accesses, most accessed first.
+    /// Keys are only counted when the db settings track key hits, see `DBSettings::with_key_hit_tracking`.
+    /// Requires admin privileges on the given db
+    #[cfg(feature = "statistics")]
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn get_hot_keys(
+        &mut self,
+        db_name: &str,
+        n: usize,
+    ) -> Result<Vec<(String, u64)>, ClientError> {
+        let packet = DBPacket::new_get_hot_keys(db_name, n);
+        let resp = self.send_packet(&packet)?;
+
+        match resp {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<(String, u64)>>(&data) {
+                Ok(hot_keys) => Ok(hot_keys),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns the `n` most accessed keys of the given db and their number of accesses, most accessed first.
+    /// Keys are only counted when the db settings track key hits, see `DBSettings::with_key_hit_tracking`.
+    /// Requires admin privileges on the given db
+    #[cfg(feature = "statistics")]
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn get_hot_keys(
+        &mut self,
+        db_name: &str,
+        n: usize,
+    ) -> Result<Vec<(String, u64)>, ClientError> {
+        let packet = DBPacket::new_get_hot_keys(db_name, n);
+        let resp = self.send_packet(&packet).await?;
+
+        match resp {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<(String, u64)>>(&data) {
+                Ok(hot_keys) => Ok(hot_keys),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Clears the `DBStatistics` of the given db.
+    /// Requires super admin privileges
+    #[cfg(feature = "statistics")]
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn reset_stats(&mut self, db_name: &str) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_reset_stats(db_name);
+        self.send_packet(&packet)
+    }
+
+    /// Clears the `DBStatistics` of the given db.
+    /// Requires super admin privileges
+    #[cfg(feature = "statistics")]
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn reset_stats(
+        &mut self,
+        db_name: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_reset_stats(db_name);
+        self.send_packet(&packet).await
+    }
+
+    /// Returns the server statistics and the statistics of every db in the given format.
+    /// Json can be deserialized into a `StatisticsExport`, csv has a `database,metric,key,value` header row.
+    /// Requires super admin privileges
+    #[cfg(feature = "statistics")]
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn export_stats(&mut self, format: StatsFormat) -> Result<String, ClientError> {
+        let packet = DBPacket::new_export_stats(format);
+        match self.send_packet(&packet)? {
+            SuccessReply(data) => Ok(data),
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+        }
+    }
+
+    /// Returns the server statistics and the statistics of every db in the given format.
+    /// Json can be deserialized into a `StatisticsExport`, csv has a `database,metric,key,value` header row.
+    /// Requires super admin privileges
+    #[cfg(feature = "statistics")]
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn export_stats(&mut self, format: StatsFormat) -> Result<String, ClientError> {
+        let packet = DBPacket::new_export_stats(format);
+        match self.send_packet(&packet).await? {
+            SuccessReply(data) => Ok(data),
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+        }
+    }
+
+    /// Returns the `ServerStatistics` of every request the server has handled.
+    /// Requires super admin privileges
+    #[cfg(feature = "statistics")]
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn get_server_stats(&mut self) -> Result<ServerStatistics, ClientError> {
+        let packet = DBPacket::new_get_server_stats();
+        let resp = self.send_packet(&packet)?;
+
+        match resp {
+            SuccessData(ResponseData::ServerStats(statistics)) => Ok(statistics),
+            SuccessNoData | SuccessReply(_) | SuccessData(_) => Err(BadPacket),
+        }
+    }
+
+    /// Returns the `ServerStatistics` of every request the server has handled.
+    /// Requires super admin privileges
+    #[cfg(feature = "statistics")]
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn get_server_stats(&mut self) -> Result<ServerStatistics, ClientError> {
+        let packet = DBPacket::new_get_server_stats();
+        let resp = self.send_packet(&packet).await?;
+
+        match resp {
+            SuccessData(ResponseData::ServerStats(statistics)) => Ok(statistics),
+            SuccessNoData | SuccessReply(_) | SuccessData(_) => Err(BadPacket),
+        }
+    }
+
+    /// Returns the role of the given client in the given db.
+    /// ```
+    /// use smol_db_client::prelude::SmolDbClient;
+    /// use smol_db_common::db::Role;
+    /// use smol_db_common::db_packets::db_settings::DBSettings;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_get_role",DBSettings::default()).unwrap();
+    ///
+    /// // get the given clients role from a db
+    /// let role = client.get_role("doctest_get_role").unwrap();
+    /// assert_eq!(role,Role::SuperAdmin);
+    ///
+    /// let _ = client.delete_db("doctest_get_role").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn get_role(&mut self, db_name: &str) -> Result<Role, ClientError> {
+        let packet = DBPacket::new_get_role(db_name);
+
+        let resp = self.send_packet(&packet)?;
+
+        match resp {
+            SuccessData(ResponseData::Role(role)) => Ok(role),
+            SuccessNoData | SuccessReply(_) | SuccessData(_) => Err(BadPacket),
+        }
+    }
+
+    /// Returns the role of the given client in the given db.
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn get_role(&mut self, db_name: &str) -> Result<Role, ClientError> {
+        let packet = DBPacket::new_get_role(db_name);
+
+        let resp = self.send_packet(&packet).await?;
+
+        match resp {
+            SuccessData(ResponseData::Role(role)) => Ok(role),
+            SuccessNoData | SuccessReply(_) | SuccessData(_) => Err(BadPacket),
+        }
+    }
+
+    /// Returns the role and permissions of this client in the given db, along with the key acl rules that limit it,
+    /// so an application can tell which actions the client may take without trying them.
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_get_effective_permissions", DBSettings::default()).unwrap();
+    ///
+    /// let permissions = client.get_effective_permissions("doctest_get_effective_permissions").unwrap();
+    /// assert_eq!(permissions.role, SuperAdmin);
+    /// assert!(permissions.can(Permission::ManageSettings));
+    ///
+    /// let _ = client.delete_db("doctest_get_effective_permissions").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn get_effective_permissions(
+        &mut self,
+        db_name: &str,
+    ) -> Result<EffectivePermissions, ClientError> {
+        let packet = DBPacket::new_get_effective_permissions(db_name);
+
+        match self.send_packet(&packet)? {
+            SuccessData(ResponseData::EffectivePermissions(permissions)) => Ok(permissions),
+            SuccessNoData | SuccessReply(_) | SuccessData(_) => Err(BadPacket),
+        }
+    }
+
+    /// Returns the role and permissions of this client in the given db, along with the key acl rules that limit it,
+    /// so an application can tell which actions the client may take without trying them.
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn get_effective_permissions(
+        &mut self,
+        db_name: &str,
+    ) -> Result<EffectivePermissions, ClientError> {
+        let packet = DBPacket::new_get_effective_permissions(db_name);
+
+        match self.send_packet(&packet).await? {
+            SuccessData(ResponseData::EffectivePermissions(permissions)) => Ok(permissions),
+            SuccessNoData | SuccessReply(_) | SuccessData(_) => Err(BadPacket),
+        }
+    }
+
+    /// Gets the `DBSettings` of the given DB.
+    /// Error on IO error, or when database name does not exist, or when the user lacks permissions to view `DBSettings`.
+    /// ```
+    /// use smol_db_client::prelude::SmolDbClient;
+    /// use smol_db_common::db_packets::db_settings::DBSettings;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_get_db_settings",DBSettings::default()).unwrap();
+    ///
+    /// // get the db settings
+    /// let settings = client.get_db_settings("doctest_get_db_settings").unwrap();
+    /// assert_eq!(settings,DBSettings::default());
+    ///
+    /// let _ = client.delete_db("doctest_get_db_settings").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn get_db_settings(&mut self, db_name: &str) -> Result<DBSettings, ClientError> {
+        #[cfg(feature = "cache")]
+        if let Some(db_settings) = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.get_settings(db_name))
+        {
+            return Ok(db_settings.clone());
+        }
+
+        let packet = DBPacket::new_get_db_settings(db_name);
+
+        let resp = self.send_packet(&packet)?;
+        match resp {
+            SuccessData(ResponseData::Settings(db_settings)) => {
+                #[cfg(feature = "cache")]
+                if let Some(cache) = self.cache.as_mut() {
+                    cache.insert_settings(db_name, &db_settings);
+                }
+                Ok(*db_settings)
+            }
+            SuccessNoData | SuccessReply(_) | SuccessData(_) => Err(BadPacket),
+        }
+    }
+
+    /// Gets the `DBSettings` of the given DB.
+    /// Error on IO error, or when database name does not exist, or when the user lacks permissions to view `DBSettings`.
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn get_db_settings(&mut self, db_name: &str) -> Result<DBSettings, ClientError> {
+        #[cfg(feature = "cache")]
+        if let Some(db_settings) = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.get_settings(db_name))
+        {
+            return Ok(db_settings.clone());
+        }
+
+        let packet = DBPacket::new_get_db_settings(db_name);
+
+        let resp = self.send_packet(&packet).await?;
+        match resp {
+            SuccessData(ResponseData::Settings(db_settings)) => {
+                #[cfg(feature = "cache")]
+                if let Some(cache) = self.cache.as_mut() {
+                    cache.insert_settings(db_name, &db_settings);
+                }
+                Ok(*db_settings)
+            }
+            SuccessNoData | SuccessReply(_) | SuccessData(_) => Err(BadPacket),
+        }
+    }
+
+    /// Sets the `DBSettings` of a given DB
+    /// Error on IO Error, or when database does not exist, or when the user lacks permissions to set `DBSettings`
+    /// ```
+    /// use std::time::Duration;
+    /// use smol_db_client::prelude::SmolDbClient;
+    /// use smol_db_common::db_packets::db_settings::DBSettings;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_set_db_settings",DBSettings::default()).unwrap();
+    ///
+    /// // set the new db settings
+    /// let new_settings = DBSettings::builder()
+    ///     .invalidation(Duration::from_secs(10))
+    ///     .others_can_read(true)
+    ///     .others_can_list(true)
+    ///     .build()
+    ///     .unwrap();
+    /// let _ = client.set_db_settings("doctest_set_db_settings",new_settings.clone()).unwrap();
+    ///
+    /// let settings = client.get_db_settings("doctest_set_db_settings").unwrap();
+    /// assert_eq!(settings,new_settings);
+    ///
+    /// let _ = client.delete_db("doctest_set_db_settings").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn set_db_settings(
+        &mut self,
+        db_name: &str,
+        db_settings: DBSettings,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_set_db_settings(db_name, db_settings);
+        self.send_packet(&packet)
+    }
+
+    /// Sets the `DBSettings` of a given DB
+    /// Error on IO Error, or when database does not exist, or when the user lacks permissions to set `DBSettings`
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn set_db_settings(
+        &mut self,
+        db_name: &str,
+        db_settings: DBSettings,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_set_db_settings(db_name, db_settings);
+        self.send_packet(&packet).await
+    }
+
+    /// Sets this clients access key within the DB Server. The server will persist the key until the session is disconnected, or connection is lost.
+    /// ```
+    /// use smol_db_client::prelude::SmolDbClient;
+    /// use smol_db_common::db_packets::db_settings::DBSettings;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// // sets the access key of the given client
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn set_access_key(
+        &mut self,
+        key: String,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_set_key(key);
+        self.send_packet(&packet)
+    }
+
+    /// Sets this clients access key within the DB Server. The server will persist the key until the session is disconnected, or connection is lost.
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn set_access_key(
+        &mut self,
+        key: String,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_set_key(key);
+        self.send_packet(&packet).await
+    }
+
+    /// Makes the access key of this client the first super admin of the server, using the bootstrap token the server printed on startup.
+    /// Fails once the server has a super admin, or if the token is wrong.
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument(skip(token))]
+    pub fn claim_super_admin(
+        &mut self,
+        token: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_claim_super_admin(token);
+        self.send_packet(&packet)
+    }
+
+    /// Makes the access key of this client the first super admin of the server, using the bootstrap token the server printed on startup.
+    /// Fails once the server has a super admin, or if the token is wrong.
+    #[cfg(feature = "async")]
+    #[tracing::instrument(skip(token))]
+    pub async fn claim_super_admin(
+        &mut self,
+        token: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_claim_super_admin(token);
+        self.send_packet(&packet).await
+    }
+
+    /// Adds the key as an admin of the db.
+    /// Requires super admin privileges on the given db
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument(skip(key))]
+    pub fn add_admin(
+        &mut self,
+        db_name: &str,
+        key: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_add_admin(db_name, key);
+        self.send_packet(&packet)
+    }
+
+    /// Adds the key as an admin of the db.
+    /// Requires super admin privileges on the given db
+    #[cfg(feature = "async")]
+    #[tracing::instrument(skip(key))]
+    pub async fn add_admin(
+        &mut self,
+        db_name: &str,
+        key: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_add_admin(db_name, key);
+        self.send_packet(&packet).await
+    }
+
+    /// Adds the key as a user of the db.
+    /// Requires manage users permissions on the given db, which admins always have
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_add_user",DBSettings::default()).unwrap();
+    ///
+    /// assert_eq!(client.add_user("doctest_add_user","user_key").unwrap(), DBSuccessResponse::SuccessNoData);
+    /// assert_eq!(client.get_db_settings("doctest_add_user").unwrap().get_user_list().len(), 1);
+    ///
+    /// assert_eq!(client.remove_user("doctest_add_user","user_key").unwrap(), DBSuccessResponse::SuccessNoData);
+    /// assert!(client.get_db_settings("doctest_add_user").unwrap().get_user_list().is_empty());
+    ///
+    /// let _ = client.delete_db("doctest_add_user").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument(skip(key))]
+    pub fn add_user(
+        &mut self,
+        db_name: &str,
+        key: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_add_user(db_name, key);
+        self.send_packet(&packet)
+    }
+
+    /// Adds the key as a user of the db.
+    /// Requires manage users permissions on the given db, which admins always have
+    #[cfg(feature = "async")]
+    #[tracing::instrument(skip(key))]
+    pub async fn add_user(
+        &mut self,
+        db_name: &str,
+        key: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_add_user(db_name, key);
+        self.send_packet(&packet).await
+    }
+
+    /// Removes an admin from the db, by its key or the hash of it listed in the db settings.
+    /// Requires super admin privileges on the given db
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument(skip(key))]
+    pub fn remove_admin(
+        &mut self,
+        db_name: &str,
+        key: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_remove_admin(db_name, key);
+        self.send_packet(&packet)
+    }
+
+    /// Removes an admin from the db, by its key or the hash of it listed in the db settings.
+    /// Requires super admin privileges on the given db
+    #[cfg(feature = "async")]
+    #[tracing::instrument(skip(key))]
+    pub async fn remove_admin(
+        &mut self,
+        db_name: &str,
+        key: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_remove_admin(db_name, key);
+        self.send_packet(&packet).await
+    }
+
+    /// Removes a user from the db, by its key or the hash of it listed in the db settings.
+    /// Requires manage users permissions on the given db, which admins always have
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument(skip(key))]
+    pub fn remove_user(
+        &mut self,
+        db_name: &str,
+        key: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_remove_user(db_name, key);
+        self.send_packet(&packet)
+    }
+
+    /// Removes a user from the db, by its key or the hash of it listed in the db settings.
+    /// Requires manage users permissions on the given db, which admins always have
+    #[cfg(feature = "async")]
+    #[tracing::instrument(skip(key))]
+    pub async fn remove_user(
+        &mut self,
+        db_name: &str,
+        key: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_remove_user(db_name, key);
+        self.send_packet(&packet).await
+    }
+
+    /// Limits the given key to reading and or writing the entries of the db whose key starts with the prefix, replacing the rule with the same key and prefix.
+    /// A key with any rules in a db loses the db wide permissions it has as a user or other, admins are not limited by rules.
+    /// Requires admin privileges on the given db
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument(skip(key))]
+    pub fn add_key_acl(
+        &mut self,
+        db_name: &str,
+        key: &str,
+        prefix: &str,
+        read: bool,
+        write: bool,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_add_key_acl(db_name, key, prefix, read, write);
+        self.send_packet(&packet)
+    }
+
+    /// Limits the given key to reading and or writing the entries of the db whose key starts with the prefix, replacing the rule with the same key and prefix.
+    /// A key with any rules in a db loses the db wide permissions it has as a user or other, admins are not limited by rules.
+    /// Requires admin privileges on the given db
+    #[cfg(feature = "async")]
+    #[tracing::instrument(skip(key))]
+    pub async fn add_key_acl(
+        &mut self,
+        db_name: &str,
+        key: &str,
+        prefix: &str,
+        read: bool,
+        write: bool,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_add_key_acl(db_name, key, prefix, read, write);
+        self.send_packet(&packet).await
+    }
+
+    /// Removes the rule with the given key and prefix from the db.
+    /// Requires admin privileges on the given db
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument(skip(key))]
+    pub fn remove_key_acl(
+        &mut self,
+        db_name: &str,
+        key: &str,
+        prefix: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_remove_key_acl(db_name, key, prefix);
+        self.send_packet(&packet)
+    }
+
+    /// Removes the rule with the given key and prefix from the db.
+    /// Requires admin privileges on the given db
+    #[cfg(feature = "async")]
+    #[tracing::instrument(skip(key))]
+    pub async fn remove_key_acl(
+        &mut self,
+        db_name: &str,
+        key: &str,
+        prefix: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_remove_key_acl(db_name, key, prefix);
+        self.send_packet(&packet).await
+    }
+
+    /// Returns every key acl rule of the db, the key of each rule is its hash.
+    /// Requires admin privileges on the given db
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn get_key_acls(&mut self, db_name: &str) -> Result<Vec<KeyAclRule>, ClientError> {
+        let packet = DBPacket::new_get_key_acls(db_name);
+        let resp = self.send_packet(&packet)?;
+
+        match resp {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<KeyAclRule>>(&data) {
+                Ok(rules) => Ok(rules),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns every key acl rule of the db, the key of each rule is its hash.
+    /// Requires admin privileges on the given db
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn get_key_acls(&mut self, db_name: &str) -> Result<Vec<KeyAclRule>, ClientError> {
+        let packet = DBPacket::new_get_key_acls(db_name);
+        let resp = self.send_packet(&packet).await?;
+
+        match resp {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<KeyAclRule>>(&data) {
+                Ok(rules) => Ok(rules),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Replaces the json schema every value written to the db must match, or removes it when given `None`.
+    /// Writes of values that do not match are then rejected with `SchemaViolation`, values already in the db are not checked.
+    /// Requires manage settings permissions on the given db
+    /// ```
+    /// use smol_db_client::prelude::*;
+    /// use serde_json::json;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_set_db_schema", DBSettings::default()).unwrap();
+    ///
+    /// let schema = ValueSchema::new(json!({ "type": "integer" })).unwrap();
+    /// let _ = client.set_db_schema("doctest_set_db_schema", Some(schema.clone())).unwrap();
+    /// assert_eq!(client.get_db_schema("doctest_set_db_schema").unwrap(), Some(schema));
+    ///
+    /// assert!(client.write_db("doctest_set_db_schema", "count", "5").is_ok());
+    /// assert!(client.write_db("doctest_set_db_schema", "count", "five").is_err());
+    ///
+    /// let _ = client.delete_db("doctest_set_db_schema").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn set_db_schema(
+        &mut self,
+        db_name: &str,
+        schema: Option<ValueSchema>,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_set_db_schema(db_name, schema);
+        self.send_packet(&packet)
+    }
+
+    /// Replaces the json schema every value written to the db must match, or removes it when given `None`.
+    /// Writes of values that do not match are then rejected with `SchemaViolation`, values already in the db are not checked.
+    /// Requires manage settings permissions on the given db
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn set_db_schema(
+        &mut self,
+        db_name: &str,
+        schema: Option<ValueSchema>,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_set_db_schema(db_name, schema);
+        self.send_packet(&packet).await
+    }
+
+    /// Returns the json schema of the db, or `None` if it has none.
+    /// Requires read or write permissions on the given db
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn get_db_schema(&mut self, db_name: &str) -> Result<Option<ValueSchema>, ClientError> {
+        let packet = DBPacket::new_get_db_schema(db_name);
+
+        match self.send_packet(&packet)? {
+            SuccessNoData => Ok(None),
+            SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<ValueSchema>(&data) {
+                Ok(schema) => Ok(Some(schema)),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns the json schema of the db, or `None` if it has none.
+    /// Requires read or write permissions on the given db
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn get_db_schema(
+        &mut self,
+        db_name: &str,
+    ) -> Result<Option<ValueSchema>, ClientError> {
+        let packet = DBPacket::new_get_db_schema(db_name);
+
+        match self.send_packet(&packet).await? {
+            SuccessNoData => Ok(None),
+            SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<ValueSchema>(&data) {
+                Ok(schema) => Ok(Some(schema)),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Replaces the description and tags of the db, the creation and modification times and creator are kept by the server.
+    /// Requires manage settings permissions on the given db
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_set_db_metadata", DBSettings::default()).unwrap();
+    ///
+    /// let _ = client
+    ///     .set_db_metadata("doctest_set_db_metadata", "users of the website", vec!["web".to_string()])
+    ///     .unwrap();
+    ///
+    /// let metadata = client.get_db_metadata("doctest_set_db_metadata").unwrap();
+    /// assert_eq!(metadata.description, "users of the website");
+    /// assert!(metadata.has_tag("web"));
+    /// assert!(metadata.created.is_some());
+    ///
+    /// let listing = client.list_db_with_metadata().unwrap();
+    /// let listed = listing
+    ///     .iter()
+    ///     .find(|listing| listing.info.get_db_name() == "doctest_set_db_metadata")
+    ///     .unwrap();
+    /// assert_eq!(listed.metadata, Some(metadata));
+    ///
+    /// let _ = client.delete_db("doctest_set_db_metadata").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn set_db_metadata(
+        &mut self,
+        db_name: &str,
+        description: &str,
+        tags: Vec<String>,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_set_db_metadata(db_name, description, tags);
+        self.send_packet(&packet)
+    }
+
+    /// Replaces the description and tags of the db, the creation and modification times and creator are kept by the server.
+    /// Requires manage settings permissions on the given db
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn set_db_metadata(
+        &mut self,
+        db_name: &str,
+        description: &str,
+        tags: Vec<String>,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_set_db_metadata(db_name, description, tags);
+        self.send_packet(&packet).await
+    }
+
+    /// Returns the description, tags, creation and modification times, and creator of the db.
+    /// Requires list permissions on the given db
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn get_db_metadata(&mut self, db_name: &str) -> Result<DBMetadata, ClientError> {
+        let packet = DBPacket::new_get_db_metadata(db_name);
+
+        match self.send_packet(&packet)? {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<DBMetadata>(&data) {
+                Ok(metadata) => Ok(metadata),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns the description, tags, creation and modification times, and creator of the db.
+    /// Requires list permissions on the given db
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn get_db_metadata(&mut self, db_name: &str) -> Result<DBMetadata, ClientError> {
+        let packet = DBPacket::new_get_db_metadata(db_name);
+
+        match self.send_packet(&packet).await? {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<DBMetadata>(&data) {
+                Ok(metadata) => Ok(metadata),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns the number and replacement time of each revision kept of the value at the key, oldest first.
+    /// Revisions are only kept when the settings of the db set a maximum number of revisions, see `DBSettings::with_max_revisions`
+    /// Requires read permissions on the given key
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client
+    ///     .create_db("doctest_list_revisions", DBSettings::default().with_max_revisions(5))
+    ///     .unwrap();
+    ///
+    /// let _ = client.write_db("doctest_list_revisions", "name", "cory").unwrap();
+    /// let _ = client.write_db("doctest_list_revisions", "name", "oops").unwrap();
+    ///
+    /// // undo the accidental overwrite
+    /// let revisions = client.list_revisions("doctest_list_revisions", "name").unwrap();
+    /// assert_eq!(revisions.len(), 1);
+    /// let revision = client
+    ///     .read_revision("doctest_list_revisions", "name", revisions[0].revision)
+    ///     .unwrap();
+    /// assert_eq!(revision.value, "cory");
+    /// let _ = client.write_db("doctest_list_revisions", "name", &revision.value).unwrap();
+    /// assert_eq!(client.read_db("doctest_list_revisions", "name").unwrap(), SuccessReply("cory".to_string()));
+    ///
+    /// let _ = client.delete_db("doctest_list_revisions").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn list_revisions(
+        &mut self,
+        db_name: &str,
+        key: &str,
+    ) -> Result<Vec<RevisionInfo>, ClientError> {
+        let packet = DBPacket::new_list_revisions(db_name, key);
+
+        match self.send_packet(&packet)? {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<RevisionInfo>>(&data) {
+                Ok(revisions) => Ok(revisions),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns the number and replacement time of each revision kept of the value at the key, oldest first.
+    /// Revisions are only kept when the settings of the db set a maximum number of revisions, see `DBSettings::with_max_revisions`
+    /// Requires read permissions on the given key
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn list_revisions(
+        &mut self,
+        db_name: &str,
+        key: &str,
+    ) -> Result<Vec<RevisionInfo>, ClientError> {
+        let packet = DBPacket::new_list_revisions(db_name, key);
+
+        match self.send_packet(&packet).await? {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<RevisionInfo>>(&data) {
+                Ok(revisions) => Ok(revisions),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns the revision of the value at the key with the given number, the error is `ValueNotFound` if the revision is no longer kept.
+    /// Requires read permissions on the given key
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn read_revision(
+        &mut self,
+        db_name: &str,
+        key: &str,
+        revision: u64,
+    ) -> Result<Revision, ClientError> {
+        let packet = DBPacket::new_read_revision(db_name, key, revision);
+
+        match self.send_packet(&packet)? {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Revision>(&data) {
+                Ok(revision) => Ok(revision),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns the revision of the value at the key with the given number, the error is `ValueNotFound` if the revision is no longer kept.
+    /// Requires read permissions on the given key
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn read_revision(
+        &mut self,
+        db_name: &str,
+        key: &str,
+        revision: u64,
+    ) -> Result<Revision, ClientError> {
+        let packet = DBPacket::new_read_revision(db_name, key, revision);
+
+        match self.send_packet(&packet).await? {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Revision>(&data) {
+                Ok(revision) => Ok(revision),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns a session token for the access key of this client, which `resume_session` accepts on another connection until the token expires.
+    /// The key itself is then not sent again on every connection, and can be rotated without ending existing sessions.
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn create_session(&mut self) -> Result<String, ClientError> {
+        let packet = DBPacket::new_create_session();
+        match self.send_packet(&packet)? {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(token) => Ok(token),
+        }
+    }
+
+    /// Returns a session token for the access key of this client, which `resume_session` accepts on another connection until the token expires.
+    /// The key itself is then not sent again on every connection, and can be rotated without ending existing sessions.
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn create_session(&mut self) -> Result<String, ClientError> {
+        let packet = DBPacket::new_create_session();
+        match self.send_packet(&packet).await? {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(token) => Ok(token),
+        }
+    }
+
+    /// Sets the access key of this client to the key the session token was created with, if the token has not expired.
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument(skip(token))]
+    pub fn resume_session(
+        &mut self,
+        token: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_resume_session(token);
+        self.send_packet(&packet)
+    }
+
+    /// Sets the access key of this client to the key the session token was created with, if the token has not expired.
+    #[cfg(feature = "async")]
+    #[tracing::instrument(skip(token))]
+    pub async fn resume_session(
+        &mut self,
+        token: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_resume_session(token);
+        self.send_packet(&packet).await
+    }
+
+    /// Ends the session the token belongs to, so it can no longer be resumed.
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument(skip(token))]
+    pub fn end_session(&mut self, token: &str) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_end_session(token);
+        self.send_packet(&packet)
+    }
+
+    /// Ends the session the token belongs to, so it can no longer be resumed.
+    #[cfg(feature = "async")]
+    #[tracing::instrument(skip(token))]
+    pub async fn end_session(
+        &mut self,
+        token: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_end_session(token);
+        self.send_packet(&packet).await
+    }
+
+    /// Replaces the rsa key of the server with a newly generated one, returning the fingerprint of the new key.
+    /// Clients that have already setup encryption are not affected, clients that pinned the old fingerprint need to pin the new one.
+    /// Requires super admin privileges.
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn rotate_server_key(&mut self) -> Result<String, ClientError> {
+        let packet = DBPacket::new_rotate_server_key();
+        match self.send_packet(&packet)? {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(fingerprint) => Ok(fingerprint),
+        }
+    }
+
+    /// Replaces the rsa key of the server with a newly generated one, returning the fingerprint of the new key.
+    /// Clients that have already setup encryption are not affected, clients that pinned the old fingerprint need to pin the new one.
+    /// Requires super admin privileges.
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn rotate_server_key(&mut self) -> Result<String, ClientError> {
+        let packet = DBPacket::new_rotate_server_key();
+        match self.send_packet(&packet).await? {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(fingerprint) => Ok(fingerprint),
+        }
+    }
+
+    /// Replaces the lists of addresses the server accepts connections from, until the server restarts.
+    /// Clients that are already connected stay connected.
+    /// Requires super admin privileges.
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn set_ip_filter(
+        &mut self,
+        ip_filter: IpFilter,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_set_ip_filter(ip_filter);
+        self.send_packet(&packet)
+    }
+
+    /// Replaces the lists of addresses the server accepts connections from, until the server restarts.
+    /// Clients that are already connected stay connected.
+    /// Requires super admin privileges.
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn set_ip_filter(
+        &mut self,
+        ip_filter: IpFilter,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_set_ip_filter(ip_filter);
+        self.send_packet(&packet).await
+    }
+
+    /// Logs in to the user account with the given username and password, the account key replaces the access key of this client for the rest of the session.
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument(skip(password))]
+    pub fn login(
+        &mut self,
+        username: &str,
+        password: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_login(username, password);
+        self.send_packet(&packet)
+    }
+
+    /// Logs in to the user account with the given username and password, the account key replaces the access key of this client for the rest of the session.
+    #[cfg(feature = "async")]
+    #[tracing::instrument(skip(password))]
+    pub async fn login(
+        &mut self,
+        username: &str,
+        password: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_login(username, password);
+        self.send_packet(&packet).await
+    }
+
+    /// Creates a user account with the given username and password.
+    /// The account has no roles until its account key, `UserRegistry::account_key`, is added as an admin or user of a db.
+    /// Requires super admin privileges.
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument(skip(password))]
+    pub fn create_user(
+        &mut self,
+        username: &str,
+        password: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_create_user(username, password);
+        self.send_packet(&packet)
+    }
+
+    /// Creates a user account with the given username and password.
+    /// The account has no roles until its account key, `UserRegistry::account_key`, is added as an admin or user of a db.
+    /// Requires super admin privileges.
+    #[cfg(feature = "async")]
+    #[tracing::instrument(skip(password))]
+    pub async fn create_user(
+        &mut self,
+        username: &str,
+        password: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_create_user(username, password);
+        self.send_packet(&packet).await
+    }
+
+    /// Disables or enables the user account with the given username.
+    /// A disabled account can not be logged in to, and clients logged in to it are logged out.
+    /// Requires super admin privileges.
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn set_user_disabled(
+        &mut self,
+        username: &str,
+        disabled: bool,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_set_user_disabled(username, disabled);
+        self.send_packet(&packet)
+    }
+
+    /// Disables or enables the user account with the given username.
+    /// A disabled account can not be logged in to, and clients logged in to it are logged out.
+    /// Requires super admin privileges.
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn set_user_disabled(
+        &mut self,
+        username: &str,
+        disabled: bool,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_set_user_disabled(username, disabled);
+        self.send_packet(&packet).await
+    }
+
+    /// Returns the username of every user account, and whether it is disabled.
+    /// Requires super admin privileges.
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn list_users(&mut self) -> Result<Vec<(String, bool)>, ClientError> {
+        let packet = DBPacket::new_list_users();
+        let resp = self.send_packet(&packet)?;
+
+        match resp {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<(String, bool)>>(&data) {
+                Ok(users) => Ok(users),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns the username of every user account, and whether it is disabled.
+    /// Requires super admin privileges.
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn list_users(&mut self) -> Result<Vec<(String, bool)>, ClientError> {
+        let packet = DBPacket::new_list_users();
+        let resp = self.send_packet(&packet).await?;
+
+        match resp {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<(String, bool)>>(&data) {
+                Ok(users) => Ok(users),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Replaces the super admins and limits of the namespace, the dbs named `namespace/name`.
+    /// The super admins of a namespace can create, delete and manage the dbs in it, but not the dbs of other namespaces.
+    /// Requires super admin privileges.
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument(skip(settings))]
+    pub fn set_namespace(
+        &mut self,
+        namespace: &str,
+        settings: NamespaceSettings,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_set_namespace(namespace, settings);
+        self.send_packet(&packet)
+    }
+
+    /// Replaces the super admins and limits of the namespace, the dbs named `namespace/name`.
+    /// The super admins of a namespace can create, delete and manage the dbs in it, but not the dbs of other namespaces.
+    /// Requires super admin privileges.
+    #[cfg(feature = "async")]
+    #[tracing::instrument(skip(settings))]
+    pub async fn set_namespace(
+        &mut self,
+        namespace: &str,
+        settings: NamespaceSettings,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_set_namespace(namespace, settings);
+        self.send_packet(&packet).await
+    }
+
+    /// Returns the settings of the namespace, the keys of its super admins are their hashes.
+    /// Requires super admin privileges, or super admin privileges of the namespace.
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn get_namespace(&mut self, namespace: &str) -> Result<NamespaceSettings, ClientError> {
+        let packet = DBPacket::new_get_namespace(namespace);
+        let resp = self.send_packet(&packet)?;
+
+        match resp {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<NamespaceSettings>(&data) {
+                Ok(settings) => Ok(settings),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns the settings of the namespace, the keys of its super admins are their hashes.
+    /// Requires super admin privileges, or super admin privileges of the namespace.
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn get_namespace(
+        &mut self,
+        namespace: &str,
+    ) -> Result<NamespaceSettings, ClientError> {
+        let packet = DBPacket::new_get_namespace(namespace);
+        let resp = self.send_packet(&packet).await?;
+
+        match resp {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<NamespaceSettings>(&data) {
+                Ok(settings) => Ok(settings),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Sends a packet to the clients currently connected database and returns the result,
+    /// passing how long it took and the result to the request hook if one is set.
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument(fields(op = sent_packet.name(), db = sent_packet.db_info().map(DBPacketInfo::get_db_name)))]
+    pub(crate) fn send_packet(
+        &mut self,
+        sent_packet: &DBPacket,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let started = Instant::now();
+        let result = self.exchange_packet(sent_packet);
+        if let Some(hook) = &self.request_hook {
+            hook.0
+                .on_request(sent_packet.name(), started.elapsed(), &result);
+        }
+        result
+    }
+
+    /// Writes the packet to the socket, encrypting it if encryption is setup, and reads the response of the server
+    #[cfg(not(feature = "async"))]
+    fn exchange_packet(
+        &mut self,
+        sent_packet: &DBPacket,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        // invalidated before sending, so a failed write does not leave a stale value behind
+        #[cfg(feature = "cache")]
+        if let Some(cache) = self.cache.as_mut() {
+            cache.invalidate_for(sent_packet);
+        }
+
+        // branch depending on if we are using encryption with communication
+        let ser_packet = match &mut self.encryption {
+            None => {
+                let p = sent_packet
+                    .serialize_packet()
+                    .map_err(|err| PacketSerializationError(Error::from(err)));
+
+                match p.as_ref() {
+                    Ok(_) => {
+                        info!("Successfully serialized packet");
+                    }
+                    Err(e) => {
+                        error!("Failed to serialize packet: {:?}", e);
+                    }
+                }
+
+                p?
+            }
+            Some(client_key) => {
+                // if we are sending the session key packet, we don't encrypt it, since the server needs the key to decrypt anything else
+                if !matches!(sent_packet, DBPacket::SessionKey(_)) {
+                    let p = client_key
+                        .encrypt_packet(sent_packet)
+                        .map_err(PacketEncryptionError)?
+                        .serialize_packet()
+                        .map_err(|err| PacketSerializationError(Error::from(err)));
+
+                    match p.as_ref() {
+                        Ok(_) => {
+                            info!("Successfully encrypted packet");
+                        }
+                        Err(e) => {
+                            error!("Failed to encrypt packet: {:?}", e);
+                        }
+                    }
+
+                    p?
+                } else {
+                    let p = sent_packet
+                        .serialize_packet()
+                        .map_err(|err| PacketSerializationError(Error::from(err)));
+
+                    match p.as_ref() {
+                        Ok(_) => {
+                            info!("Successfully serialized session key packet");
+                        }
+                        Err(e) => {
+                            error!("Failed to serialize session key packet: {:?}", e);
+                        }
+                    }
+
+                    p?
+                }
+            }
+        };
+
+        let s_res = self
+            .socket
+            .write_all(ser_packet.as_bytes())
+            .map_err(SocketWriteError);
+
+        match s_res.as_ref() {
+            Ok(()) => {
+                info!(
+                    "Successfully wrote {} bytes to socket: {}",
+                    ser_packet.len(),
+                    ser_packet
+                );
+            }
+            Err(e) => {
+                error!("Failed to write packet to socket: {:?}", e);
+            }
+        }
+
+        s_res?;
+
+        let message_res = self.read_message().map_err(SocketReadError);
+
+        match message_res.as_ref() {
+            Ok(message) => {
+                info!("Successfully read {} bytes from socket", message.len());
+            }
+            Err(e) => {
+                error!("Failed to read packet from socket: {:?}", e);
+            }
+        }
+
+        let message = message_res?;
+
+        match deserialize_response(&message) {
+            Ok(thing) => {
+                match thing.as_ref() {
+                    Ok(response) => {
+                        info!("Successful response from server: {}", response);
+                    }
+                    Err(err) => {
+                        error!("Error response from server: {}", err);
+                    }
+                }
+                thing.map_err(DBResponseError)
+            }
+            Err(err) => {
+                // if we fail to read a packet, check if it is an encrypted packet
+                if let Some(client_key) = &self.encryption {
+                    match client_key
+                        .decrypt_server_packet(&message)
+                        .map_err(PacketEncryptionError)
+                    {
+                        Ok(decrypted) => {
+                            info!("Successfully decrypted data from server packet");
+                            match decrypted.as_ref() {
+                                Ok(response) => {
+                                    info!("Successful response from server: {}", response);
+                                }
+                                Err(err) => {
+                                    error!("Error response from server: {}", err);
+                                }
+                            }
+                            decrypted.map_err(DBResponseError)
+                        }
+                        Err(err) => {
+                            error!("Error decrypting server packet: {:?}", err);
+                            Err(err)
+                        }
+                    }
+                } else {
+                    error!("Packet deserialization error: {}", err);
+                    Err(PacketDeserializationError(Error::from(err)))
+                }
+            }
+        }
+    }
+
+    /// Sends a packet to the clients currently connected database and returns the result,
+    /// passing how long it took and the result to the request hook if one is set.
+    #[cfg(feature = "async")]
+    #[tracing::instrument(fields(op = sent_packet.name(), db = sent_packet.db_info().map(DBPacketInfo::get_db_name)))]
+    pub(crate) async fn send_packet(
+        &mut self,
+        sent_packet: &DBPacket,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let started = Instant::now();
+        let result = self.exchange_packet(sent_packet).await;
+        if let Some(hook) = &self.request_hook {
+            hook.0
+                .on_request(sent_packet.name(), started.elapsed(), &result);
+        }
+        result
+    }
+
+    /// Writes the packet to the socket, encrypting it if encryption is setup, and reads the response of the server
+    #[cfg(feature = "async")]
+    async fn exchange_packet(
+        &mut self,
+        sent_packet: &DBPacket,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        // invalidated before sending, so a failed write does not leave a stale value behind
+        #[cfg(feature = "cache")]
+        if let Some(cache) = self.cache.as_mut() {
+            cache.invalidate_for(sent_packet);
+        }
+
+        // branch depending on if we are using encryption with communication
+        let ser_packet = match &mut self.encryption {
+            None => {
+                let p = sent_packet
+                    .serialize_packet()
+                    .map_err(|err| PacketSerializationError(Error::from(err)));
+
+                match p.as_ref() {
+                    Ok(_) => {
+                        info!("Successfully serialized packet");
+                    }
+                    Err(e) => {
+                        error!("Failed to serialize packet: {:?}", e);
+                    }
+                }
+
+                p?
+            }
+            Some(client_key) => {
+                // if we are sending the session key packet, we don't encrypt it, since the server needs the key to decrypt anything else
+                if !matches!(sent_packet, DBPacket::SessionKey(_)) {
+                    let p = client_key
+                        .encrypt_packet(sent_packet)
+                        .map_err(PacketEncryptionError)?
+                        .serialize_packet()
+                        .map_err(|err| PacketSerializationError(Error::from(err)));
+
+                    match p.as_ref() {
+                        Ok(_) => {
+                            info!("Successfully encrypted packet");
+                        }
+                        Err(e) => {
+                            error!("Failed to encrypt packet: {:?}", e);
+                        }
+                    }
+
+                    p?
+                } else {
+                    let p = sent_packet
+                        .serialize_packet()
+                        .map_err(|err| PacketSerializationError(Error::from(err)));
+
+                    match p.as_ref() {
+                        Ok(_) => {
+                            info!("Successfully serialized session key packet");
+                        }
+                        Err(e) => {
+                            error!("Failed to serialize session key packet: {:?}", e);
+                        }
+                    }
+
+                    p?
+                }
+            }
+        };
+
+        let s_res = self
+            .socket
+            .write_all(ser_packet.as_bytes())
+            .await
+            .map_err(SocketWriteError);
+
+        match s_res.as_ref() {
+            Ok(()) => {
+                info!("Successfully wrote {} bytes to socket", ser_packet.len());
+            }
+            Err(e) => {
+                error!("Failed to write packet to socket: {:?}", e);
+            }
+        }
+
+        s_res?;
+
+        let message_res = self.read_message().await.map_err(SocketReadError);
+
+        match message_res.as_ref() {
+            Ok(message) => {
+                info!("Successfully read {} bytes from socket", message.len());
+            }
+            Err(e) => {
+                error!("Failed to read packet from socket: {:?}", e);
+            }
+        }
+
+        let message = message_res?;
+
+        match deserialize_response(&message) {
+            Ok(thing) => {
+                match thing.as_ref() {
+                    Ok(response) => {
+                        info!("Successful response from server: {}", response);
+                    }
+                    Err(err) => {
+                        error!("Error response from server: {}", err);
+                    }
+                }
+                thing.map_err(DBResponseError)
+            }
+            Err(err) => {
+                // if we fail to read a packet, check if it is an encrypted packet
+                if let Some(client_key) = &self.encryption {
+                    match client_key
+                        .decrypt_server_packet(&message)
+                        .map_err(PacketEncryptionError)
+                    {
+                        Ok(decrypted) => {
+                            info!("Successfully decrypted data from server packet");
+                            match decrypted.as_ref() {
+                                Ok(response) => {
+                                    info!("Successful response from server: {}", response);
+                                }
+                                Err(err) => {
+                                    error!("Error response from server: {}", err);
+                                }
+                            }
+                            decrypted.map_err(DBResponseError)
+                        }
+                        Err(err) => {
+                            error!("Error decrypting server packet: {:?}", err);
+                            Err(err)
+                        }
+                    }
+                } else {
+                    error!("Packet deserialization error: {}", err);
+                    Err(PacketDeserializationError(Error::from(err)))
+                }
+            }
+        }
+    }
+
+    /// Creates a db through the client with the given name.
+    /// Error on IO Error, or when the user lacks permissions to create a DB
+    /// ```
+    /// use std::time::Duration;
+    /// use smol_db_client::prelude::SmolDbClient;
+    /// use smol_db_common::db_packets::db_settings::DBSettings;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_create_db",DBSettings::default()).unwrap();
+    ///
+    /// let _ = client.delete_db("doctest_create_db").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn create_db(
+        &mut self,
+        db_name: &str,
+        db_settings: DBSettings,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_create_db(db_name, db_settings);
+        let resp = self.send_packet(&packet)?;
+
+        Ok(resp)
+    }
+
+    /// Creates a db through the client with the given name.
+    /// Error on IO Error, or when the user lacks permissions to create a DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn create_db(
+        &mut self,
+        db_name: &str,
+        db_settings: DBSettings,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_create_db(db_name, db_settings);
+        let resp = self.send_packet(&packet).await?;
+
+        Ok(resp)
+    }
+
+    /// Writes to a db at the location specified, with the data given as a string.
+    /// Returns the data in the location that was overwritten if there was any.
+    /// Requires permissions to write to the given DB
+    /// ```
+    /// use smol_db_client::prelude::SmolDbClient;
+    /// use smol_db_common::db_packets::db_settings::DBSettings;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_write_data",DBSettings::default()).unwrap();
+    ///
+    /// // write the given data to the given location within the specified db
+    /// let _ = client.write_db("doctest_write_data","cool_data_location","cool_data");
+    ///
+    /// let read_data1 = client.read_db("doctest_write_data","cool_data_location").unwrap().as_option().unwrap().to_string();
+    /// assert_eq!(read_data1.as_str(),"cool_data");
+    ///
+    /// let _ = client.delete_db("doctest_write_data").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn write_db(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+        data: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_write(db_name, db_location, data);
+        let write = QueuedWrite {
+            db_name: db_name.to_string(),
+            key: db_location.to_string(),
+            value: Some(data.to_string()),
+        };
+
+        self.send_or_queue(&packet, write)
+    }
+
+    /// Writes to a db at the location specified, with the data given as a string.
+    /// Returns the data in the location that was overwritten if there was any.
+    /// Requires permissions to write to the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn write_db(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+        data: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_write(db_name, db_location, data);
+        let write = QueuedWrite {
+            db_name: db_name.to_string(),
+            key: db_location.to_string(),
+            value: Some(data.to_string()),
+        };
+
+        self.send_or_queue(&packet, write).await
+    }
+
+    /// Writes the new data to the location only if the value currently at the location is the expected value, with `None` expecting the location to be empty.
+    /// The comparison and write happen atomically on the server, allowing optimistic concurrency between multiple writers.
+    /// Returns the previous value when the write happened, or `DBResponseError(ValueMismatch)` containing the current value when it did not.
+    /// Requires permissions to read and write to the given DB
+    /// ```
+    /// use smol_db_client::client_error::ClientError;
+    /// use smol_db_client::prelude::SmolDbClient;
+    /// use smol_db_common::db_packets::db_packet_response::DBPacketResponseError;
+    /// use smol_db_common::db_packets::db_settings::DBSettings;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_compare_and_swap",DBSettings::default()).unwrap();
+    ///
+    /// let _ = client.compare_and_swap("doctest_compare_and_swap","cool_data_location",None,"1").unwrap();
+    /// let _ = client.compare_and_swap("doctest_compare_and_swap","cool_data_location",Some("1"),"2").unwrap();
+    ///
+    /// // the value is now "2", so this swap does not happen
+    /// let mismatch = client.compare_and_swap("doctest_compare_and_swap","cool_data_location",Some("1"),"3");
+    /// assert_eq!(mismatch.unwrap_err(),ClientError::DBResponseError(DBPacketResponseError::ValueMismatch(Some("2".to_string()))));
+    ///
+    /// let _ = client.delete_db("doctest_compare_and_swap").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn compare_and_swap(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+        expected: Option<&str>,
+        data: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_compare_and_swap(db_name, db_location, expected, data);
+
+        self.send_packet(&packet)
+    }
+
+    /// Writes the new data to the location only if the value currently at the location is the expected value, with `None` expecting the location to be empty.
+    /// The comparison and write happen atomically on the server, allowing optimistic concurrency between multiple writers.
+    /// Returns the previous value when the write happened, or `DBResponseError(ValueMismatch)` containing the current value when it did not.
+    /// Requires permissions to read and write to the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn compare_and_swap(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+        expected: Option<&str>,
+        data: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_compare_and_swap(db_name, db_location, expected, data);
+
+        self.send_packet(&packet).await
+    }
+
+    /// Adds the delta to the integer stored at the location, returning the new value. A location with no value is treated as 0.
+    /// The read and write happen atomically on the server, so this can be used to implement counters shared between clients.
+    /// Requires permissions to read and write to the given DB
+    /// ```
+    /// use smol_db_client::prelude::SmolDbClient;
+    /// use smol_db_common::db_packets::db_settings::DBSettings;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_increment",DBSettings::default()).unwrap();
+    ///
+    /// assert_eq!(client.increment("doctest_increment","counter",5).unwrap(),5);
+    /// assert_eq!(client.decrement("doctest_increment","counter",2).unwrap(),3);
+    ///
+    /// let _ = client.delete_db("doctest_increment").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn increment(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+        delta: i64,
+    ) -> Result<i64, ClientError> {
+        let packet = DBPacket::new_increment(db_name, db_location, delta);
+
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<i64>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Adds the delta to the integer stored at the location, returning the new value. A location with no value is treated as 0.
+    /// The read and write happen atomically on the server, so this can be used to implement counters shared between clients.
+    /// Requires permissions to read and write to the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn increment(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+        delta: i64,
+    ) -> Result<i64, ClientError> {
+        let packet = DBPacket::new_increment(db_name, db_location, delta);
+
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<i64>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Subtracts the delta from the integer stored at the location, returning the new value.
+    /// Equivalent to `increment` with a negated delta.
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn decrement(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+        delta: i64,
+    ) -> Result<i64, ClientError> {
+        self.increment(db_name, db_location, delta.wrapping_neg())
+    }
+
+    /// Subtracts the delta from the integer stored at the location, returning the new value.
+    /// Equivalent to `increment` with a negated delta.
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn decrement(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+        delta: i64,
+    ) -> Result<i64, ClientError> {
+        self.increment(db_name, db_location, delta.wrapping_neg())
+            .await
+    }
+
+    /// Applies every operation in the transaction to the db atomically, returning the result of each operation in order.
+    /// Reads result in the value at the location, writes result in the previous value, and deletes result in the removed value.
+    /// If any operation fails, none of the transaction is applied and `TransactionRolledBack` is returned.
+    /// Requires read permissions if the transaction reads, and write permissions if it writes or deletes.
+    /// ```
+    /// use smol_db_client::prelude::{SmolDbClient, TransactionBuilder};
+    /// use smol_db_common::db_packets::db_settings::DBSettings;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_transaction",DBSettings::default()).unwrap();
+    ///
+    /// let transaction = TransactionBuilder::new()
+    ///     .write("a", "1")
+    ///     .write("b", "2")
+    ///     .read("a");
+    ///
+    /// let results = client.transaction("doctest_transaction", transaction).unwrap();
+    /// assert_eq!(results, vec![None, None, Some("1".to_string())]);
+    ///
+    /// let _ = client.delete_db("doctest_transaction").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn transaction(
+        &mut self,
+        db_name: &str,
+        transaction: TransactionBuilder,
+    ) -> Result<Vec<Option<String>>, ClientError> {
+        let packet = DBPacket::new_transaction(db_name, transaction.build());
+
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<Option<String>>>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Applies every operation in the transaction to the db atomically, returning the result of each operation in order.
+    /// Reads result in the value at the location, writes result in the previous value, and deletes result in the removed value.
+    /// If any operation fails, none of the transaction is applied and `TransactionRolledBack` is returned.
+    /// Requires read permissions if the transaction reads, and write permissions if it writes or deletes.
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn transaction(
+        &mut self,
+        db_name: &str,
+        transaction: TransactionBuilder,
+    ) -> Result<Vec<Option<String>>, ClientError> {
+        let packet = DBPacket::new_transaction(db_name, transaction.build());
+
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<Option<String>>>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Writes the data to the location only if the location currently has no value.
+    /// Returns `ValueAlreadyExists` if the location is taken, which makes this usable as a simple lock shared between clients.
+    /// Requires permissions to write to the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_write_if_absent",DBSettings::default()).unwrap();
+    ///
+    /// assert_eq!(client.write_db_if_absent("doctest_write_if_absent","lock","owner_1").unwrap(),SuccessNoData);
+    /// assert_eq!(client.write_db_if_absent("doctest_write_if_absent","lock","owner_2").unwrap_err(),DBResponseError(ValueAlreadyExists));
+    ///
+    /// let _ = client.delete_db("doctest_write_if_absent").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn write_db_if_absent(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+        data: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_write_if_absent(db_name, db_location, data);
+
+        self.send_packet(&packet)
+    }
+
+    /// Writes the data to the location only if the location currently has no value.
+    /// Returns `ValueAlreadyExists` if the location is taken, which makes this usable as a simple lock shared between clients.
+    /// Requires permissions to write to the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn write_db_if_absent(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+        data: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_write_if_absent(db_name, db_location, data);
+
+        self.send_packet(&packet).await
+    }
+
+    /// Publishes the message to every client subscribed to the channel, returning how many subscribers received it.
+    /// Channels are independent of databases, and are open to every client.
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn publish(&mut self, channel_name: &str, message: &str) -> Result<usize, ClientError> {
+        let packet = DBPacket::new_publish(channel_name, message);
+
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<usize>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Publishes the message to every client subscribed to the channel, returning how many subscribers received it.
+    /// Channels are independent of databases, and are open to every client.
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn publish(
+        &mut self,
+        channel_name: &str,
+        message: &str,
+    ) -> Result<usize, ClientError> {
+        let packet = DBPacket::new_publish(channel_name, message);
+
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<usize>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Writes every location and value pair to the db in a single request, returning the previous value at each location in the same order.
+    /// Requires permissions to write to the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_read_write_many",DBSettings::default()).unwrap();
+    ///
+    /// let previous = client.write_many("doctest_read_write_many", &[("a", "1"), ("b", "2")]).unwrap();
+    /// assert_eq!(previous, vec![None, None]);
+    ///
+    /// let values = client.read_many("doctest_read_write_many", &["a", "missing", "b"]).unwrap();
+    /// assert_eq!(values, vec![Some("1".to_string()), None, Some("2".to_string())]);
+    ///
+    /// let _ = client.delete_db("doctest_read_write_many").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn write_many(
+        &mut self,
+        db_name: &str,
+        writes: &[(&str, &str)],
+    ) -> Result<Vec<Option<String>>, ClientError> {
+        let packet = DBPacket::new_write_many(db_name, writes);
+
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<Option<String>>>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Writes every location and value pair to the db in a single request, returning the previous value at each location in the same order.
+    /// Requires permissions to write to the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn write_many(
+        &mut self,
+        db_name: &str,
+        writes: &[(&str, &str)],
+    ) -> Result<Vec<Option<String>>, ClientError> {
+        let packet = DBPacket::new_write_many(db_name, writes);
+
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<Option<String>>>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Reads every location from the db in a single request, returning the value at each location in the same order, `None` if there is no value.
+    /// Requires permissions to read from the given DB
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn read_many(
+        &mut self,
+        db_name: &str,
+        db_locations: &[&str],
+    ) -> Result<Vec<Option<String>>, ClientError> {
+        let packet = DBPacket::new_read_many(db_name, db_locations);
+
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<Option<String>>>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Reads every location from the db in a single request, returning the value at each location in the same order, `None` if there is no value.
+    /// Requires permissions to read from the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn read_many(
+        &mut self,
+        db_name: &str,
+        db_locations: &[&str],
+    ) -> Result<Vec<Option<String>>, ClientError> {
+        let packet = DBPacket::new_read_many(db_name, db_locations);
+
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<Option<String>>>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Reads from a db at the location specific.
+    /// Returns an error if there is no data in the location.
+    /// Requires permissions to read from the given DB
+    /// ```
+    /// use smol_db_client::prelude::SmolDbClient;
+    /// use smol_db_common::db_packets::db_settings::DBSettings;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_read_db",DBSettings::default()).unwrap();
+    ///
+    ///let _ = client.write_db("doctest_read_db","cool_data_location","cool_data");
+    ///
+    /// // read the given database at the given location
+    /// let read_data1 = client.read_db("doctest_read_db","cool_data_location").unwrap().as_option().unwrap().to_string();
+    /// assert_eq!(read_data1.as_str(),"cool_data");
+    ///
+    /// let _ = client.delete_db("doctest_read_db").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn read_db(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        #[cfg(feature = "cache")]
+        if let Some(value) = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.get_value(db_name, db_location))
+        {
+            return Ok(SuccessReply(value.to_string()));
+        }
+
+        let packet = DBPacket::new_read(db_name, db_location);
+
+        let resp = self.send_packet(&packet)?;
+        #[cfg(feature = "cache")]
+        if let (Some(cache), SuccessReply(value)) = (self.cache.as_mut(), &resp) {
+            cache.insert_value(db_name, db_location, value);
+        }
+        Ok(resp)
+    }
+
+    /// Reads from a db at the location specific.
+    /// Returns an error if there is no data in the location.
+    /// Requires permissions to read from the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn read_db(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        #[cfg(feature = "cache")]
+        if let Some(value) = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.get_value(db_name, db_location))
+        {
+            return Ok(SuccessReply(value.to_string()));
+        }
+
+        let packet = DBPacket::new_read(db_name, db_location);
+
+        let resp = self.send_packet(&packet).await?;
+        #[cfg(feature = "cache")]
+        if let (Some(cache), SuccessReply(value)) = (self.cache.as_mut(), &resp) {
+            cache.insert_value(db_name, db_location, value);
+        }
+        Ok(resp)
+    }
+
+    /// Deletes the given db by name.
+    /// The db is moved to the trash, and can be restored with `restore_db` until the server purges it.
+    /// Requires super admin privileges on the given DB Server
+    /// ```
+    /// use smol_db_client::prelude::SmolDbClient;
+    /// use smol_db_common::db_packets::db_settings::DBSettings;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_delete_db",DBSettings::default()).unwrap();
+    ///
+    /// // delete the db with the given name
+    /// let _ = client.delete_db("doctest_delete_db").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn delete_db(&mut self, db_name: &str) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_delete_db(db_name);
+
+        self.send_packet(&packet)
+    }
+
+    /// Deletes the given db by name.
+    /// The db is moved to the trash, and can be restored with `restore_db` until the server purges it.
+    /// Requires super admin privileges on the given DB Server
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn delete_db(
+        &mut self,
+        db_name: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_delete_db(db_name);
+
+        self.send_packet(&packet).await
+    }
+
+    /// Restores the most recently deleted db with the given name from the trash.
+    /// The error is `DBNotFound` if no db with the name is in the trash, or `DBAlreadyExists` if a db with the name exists.
+    /// Requires super admin privileges on the given DB Server
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_restore_db", DBSettings::default()).unwrap();
+    /// let _ = client.write_db("doctest_restore_db", "key", "value").unwrap();
+    ///
+    /// // deleted by mistake
+    /// let _ = client.delete_db("doctest_restore_db").unwrap();
+    ///
+    /// let _ = client.restore_db("doctest_restore_db").unwrap();
+    /// assert_eq!(client.read_db("doctest_restore_db", "key").unwrap(), SuccessReply("value".to_string()));
+    ///
+    /// let _ = client.delete_db("doctest_restore_db").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn restore_db(&mut self, db_name: &str) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_restore_db(db_name);
+
+        self.send_packet(&packet)
+    }
+
+    /// Restores the most recently deleted db with the given name from the trash.
+    /// The error is `DBNotFound` if no db with the name is in the trash, or `DBAlreadyExists` if a db with the name exists.
+    /// Requires super admin privileges on the given DB Server
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn restore_db(
+        &mut self,
+        db_name: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_restore_db(db_name);
+
+        self.send_packet(&packet).await
+    }
+
+    /// Removes every deleted db from the trash so they can no longer be restored, returning how many were removed.
+    /// Deleted dbs are also purged by the server once they have been in the trash for longer than its trash retention.
+    /// Requires super admin privileges on the given DB Server
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn purge_trash(&mut self) -> Result<usize, ClientError> {
+        let packet = DBPacket::new_purge_trash();
+
+        match self.send_packet(&packet)? {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<usize>(&data) {
+                Ok(purged) => Ok(purged),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Removes every deleted db from the trash so they can no longer be restored, returning how many were removed.
+    /// Deleted dbs are also purged by the server once they have been in the trash for longer than its trash retention.
+    /// Requires super admin privileges on the given DB Server
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn purge_trash(&mut self) -> Result<usize, ClientError> {
+        let packet = DBPacket::new_purge_trash();
+
+        match self.send_packet(&packet).await? {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<usize>(&data) {
+                Ok(purged) => Ok(purged),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Renames the given db, keeping its content and settings.
+    /// Requires super admin privileges on the given DB Server
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_rename_db",DBSettings::default()).unwrap();
+    ///
+    /// let _ = client.rename_db("doctest_rename_db","doctest_renamed_db").unwrap();
+    ///
+    /// let _ = client.delete_db("doctest_renamed_db").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn rename_db(
+        &mut self,
+        from: &str,
+        to: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_rename_db(from, to);
+
+        self.send_packet(&packet)
+    }
+
+    /// Renames the given db, keeping its content and settings.
+    /// Requires super admin privileges on the given DB Server
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn rename_db(
+        &mut self,
+        from: &str,
+        to: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_rename_db(from, to);
+
+        self.send_packet(&packet).await
+    }
+
+    /// Creates a new db named `dest` containing a copy of the content of the `source` db.
+    /// When `include_settings` is true the settings of the source db are copied as well, otherwise the new db uses the default settings.
+    /// Requires super admin privileges on the given DB Server
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_copy_db",DBSettings::default()).unwrap();
+    /// let _ = client.write_db("doctest_copy_db","key","value").unwrap();
+    ///
+    /// let _ = client.copy_db("doctest_copy_db","doctest_copy_db_staging",true).unwrap();
+    /// assert_eq!(client.read_db("doctest_copy_db_staging","key").unwrap(),SuccessReply("value".to_string()));
+    ///
+    /// let _ = client.delete_db("doctest_copy_db").unwrap();
+    /// let _ = client.delete_db("doctest_copy_db_staging").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn copy_db(
+        &mut self,
+        source: &str,
+        dest: &str,
+        include_settings: bool,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_copy_db(source, dest, include_settings);
+
+        self.send_packet(&packet)
+    }
+
+    /// Creates a new db named `dest` containing a copy of the content of the `source` db.
+    /// When `include_settings` is true the settings of the source db are copied as well, otherwise the new db uses the default settings.
+    /// Requires super admin privileges on the given DB Server
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn copy_db(
+        &mut self,
+        source: &str,
+        dest: &str,
+        include_settings: bool,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_copy_db(source, dest, include_settings);
+
+        self.send_packet(&packet).await
+    }
+
+    /// Saves a snapshot of every database on the server to the backups directory within the servers data directory.
+    /// Returns the file name of the snapshot, which can be restored by starting the server with `--restore-snapshot`.
+    /// Requires super admin privileges on the given DB Server
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    ///
+    /// let snapshot_name = client.create_snapshot().unwrap();
+    /// assert!(snapshot_name.starts_with("snapshot_"));
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn create_snapshot(&mut self) -> Result<String, ClientError> {
+        let packet = DBPacket::new_create_snapshot();
+
+        match self.send_packet(&packet)? {
+            SuccessReply(file_name) => Ok(file_name),
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+        }
+    }
+
+    /// Saves a snapshot of every database on the server to the backups directory within the servers data directory.
+    /// Returns the file name of the snapshot, which can be restored by starting the server with `--restore-snapshot`.
+    /// Requires super admin privileges on the given DB Server
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn create_snapshot(&mut self) -> Result<String, ClientError> {
+        let packet = DBPacket::new_create_snapshot();
+
+        match self.send_packet(&packet).await? {
+            SuccessReply(file_name) => Ok(file_name),
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+        }
+    }
+
+    /// Returns the state of the server, such as its version, uptime, and how many databases it holds in memory.
+    /// Requires super admin privileges on the given DB Server
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    ///
+    /// let server_info = client.server_info().unwrap();
+    /// assert!(!server_info.version.is_empty());
+    /// assert!(server_info.cached_db_count <= server_info.db_count);
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn server_info(&mut self) -> Result<ServerInfo, ClientError> {
+        let packet = DBPacket::new_server_info();
+
+        match self.send_packet(&packet)? {
+            SuccessReply(data) => serde_json::from_str::<ServerInfo>(&data)
+                .map_err(|err| PacketDeserializationError(Error::from(err))),
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+        }
+    }
+
+    /// Returns the state of the server, such as its version, uptime, and how many databases it holds in memory.
+    /// Requires super admin privileges on the given DB Server
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn server_info(&mut self) -> Result<ServerInfo, ClientError> {
+        let packet = DBPacket::new_server_info();
+
+        match self.send_packet(&packet).await? {
+            SuccessReply(data) => serde_json::from_str::<ServerInfo>(&data)
+                .map_err(|err| PacketDeserializationError(Error::from(err))),
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+        }
+    }
+
+    /// Sends a `Ping` to the server, returning the time taken for the server to respond.
+    /// Requires no privileges, and touches no database, so it is a cheap way to check the connection is still alive.
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let round_trip = client.ping().unwrap();
+    /// assert!(round_trip.as_secs() < 5);
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn ping(&mut self) -> Result<Duration, ClientError> {
+        let packet = DBPacket::new_ping();
+        let sent_at = Instant::now();
+
+        match self.send_packet(&packet)? {
+            SuccessReply(reply) if reply == PONG => Ok(sent_at.elapsed()),
+            _ => Err(BadPacket),
+        }
+    }
+
+    /// Sends a `Ping` to the server, returning the time taken for the server to respond.
+    /// Requires no privileges, and touches no database, so it is a cheap way to check the connection is still alive.
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn ping(&mut self) -> Result<Duration, ClientError> {
+        let packet = DBPacket::new_ping();
+        let sent_at = Instant::now();
+
+        match self.send_packet(&packet).await? {
+            SuccessReply(reply) if reply == PONG => Ok(sent_at.elapsed()),
+            _ => Err(BadPacket),
+        }
+    }
+
+    /// Removes every key and value from the given db, keeping the db and its settings.
+    /// Requires admin privileges on the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_clear_db",DBSettings::default()).unwrap();
+    /// let _ = client.write_db("doctest_clear_db","key","value").unwrap();
+    ///
+    /// let _ = client.clear_db("doctest_clear_db").unwrap();
+    /// assert!(client.list_db_contents("doctest_clear_db").unwrap().is_empty());
+    ///
+    /// let _ = client.delete_db("doctest_clear_db").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn clear_db(&mut self, db_name: &str) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_clear_db(db_name);
+
+        self.send_packet(&packet)
+    }
+
+    /// Removes every key and value from the given db, keeping the db and its settings.
+    /// Requires admin privileges on the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn clear_db(
+        &mut self,
+        db_name: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_clear_db(db_name);
+
+        self.send_packet(&packet).await
+    }
+
+    /// Returns true if a db with the given name exists on the server.
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_db_exists",DBSettings::default()).unwrap();
+    /// let _ = client.write_db("doctest_db_exists","key","value").unwrap();
+    ///
+    /// assert!(client.db_exists("doctest_db_exists").unwrap());
+    /// assert!(!client.db_exists("doctest_db_does_not_exist").unwrap());
+    ///
+    /// assert!(client.key_exists("doctest_db_exists","key").unwrap());
+    /// assert!(!client.key_exists("doctest_db_exists","missing").unwrap());
+    ///
+    /// let _ = client.delete_db("doctest_db_exists").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn db_exists(&mut self, db_name: &str) -> Result<bool, ClientError> {
+        let packet = DBPacket::new_db_exists(db_name);
+
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<bool>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns true if a db with the given name exists on the server.
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn db_exists(&mut self, db_name: &str) -> Result<bool, ClientError> {
+        let packet = DBPacket::new_db_exists(db_name);
+
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<bool>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns true if the location in the given db has a value, without transferring the value.
+    /// Requires permissions to read from the given DB
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn key_exists(&mut self, db_name: &str, db_location: &str) -> Result<bool, ClientError> {
+        let packet = DBPacket::new_key_exists(db_name, db_location);
+
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<bool>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns true if the location in the given db has a value, without transferring the value.
+    /// Requires permissions to read from the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn key_exists(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+    ) -> Result<bool, ClientError> {
+        let packet = DBPacket::new_key_exists(db_name, db_location);
+
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<bool>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns the number of key value pairs in the given db, without transferring the content.
+    /// Requires permissions to list the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_get_db_len",DBSettings::default()).unwrap();
+    /// assert_eq!(client.get_db_len("doctest_get_db_len").unwrap(), 0);
+    ///
+    /// let _ = client.write_db("doctest_get_db_len","key1","value1").unwrap();
+    /// let _ = client.write_db("doctest_get_db_len","key2","value2").unwrap();
+    /// assert_eq!(client.get_db_len("doctest_get_db_len").unwrap(), 2);
+    ///
+    /// let _ = client.delete_db("doctest_get_db_len").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn get_db_len(&mut self, db_name: &str) -> Result<usize, ClientError> {
+        let packet = DBPacket::new_get_db_length(db_name);
+
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<usize>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns the number of key value pairs in the given db, without transferring the content.
+    /// Requires permissions to list the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn get_db_len(&mut self, db_name: &str) -> Result<usize, ClientError> {
+        let packet = DBPacket::new_get_db_length(db_name);
+
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<usize>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Pushes the value onto the end of the list in the given db, creating the list if it does not exist.
+    /// Returns the new length of the list.
+    /// Requires permissions to write to the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_list_push",DBSettings::default()).unwrap();
+    ///
+    /// assert_eq!(client.list_push("doctest_list_push","list","a").unwrap(), 1);
+    /// assert_eq!(client.list_push("doctest_list_push","list","b").unwrap(), 2);
+    /// assert_eq!(client.list_get("doctest_list_push","list",1).unwrap().into_option().unwrap(), "b");
+    ///
+    /// let _ = client.delete_db("doctest_list_push").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn list_push(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        value: &str,
+    ) -> Result<usize, ClientError> {
+        let packet = DBPacket::new_add_to_list(db_name, list_name, value, None);
+
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<usize>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Pushes the value onto the end of the list in the given db, creating the list if it does not exist.
+    /// Returns the new length of the list.
+    /// Requires permissions to write to the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn list_push(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        value: &str,
+    ) -> Result<usize, ClientError> {
+        let packet = DBPacket::new_add_to_list(db_name, list_name, value, None);
+
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<usize>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Inserts the value into the list in the given db at the index, shifting every value after it back by one.
+    /// The index may be the length of the list, which pushes the value onto the end. Returns the new length of the list.
+    /// Requires permissions to write to the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_list_insert",DBSettings::default()).unwrap();
+    ///
+    /// let _ = client.list_push("doctest_list_insert","list","b").unwrap();
+    /// assert_eq!(client.list_insert("doctest_list_insert","list",0,"a").unwrap(), 2);
+    /// assert_eq!(client.list_get("doctest_list_insert","list",0).unwrap().into_option().unwrap(), "a");
+    ///
+    /// let _ = client.delete_db("doctest_list_insert").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn list_insert(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        index: usize,
+        value: &str,
+    ) -> Result<usize, ClientError> {
+        let packet = DBPacket::new_add_to_list(db_name, list_name, value, Some(index));
+
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<usize>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Inserts the value into the list in the given db at the index, shifting every value after it back by one.
+    /// The index may be the length of the list, which pushes the value onto the end. Returns the new length of the list.
+    /// Requires permissions to write to the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn list_insert(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        index: usize,
+        value: &str,
+    ) -> Result<usize, ClientError> {
+        let packet = DBPacket::new_add_to_list(db_name, list_name, value, Some(index));
+
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<usize>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Reads the value at the index of the list in the given db.
+    /// Returns an error if the list does not exist, or the index is past the end of the list.
+    /// Requires permissions to read from the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_list_get",DBSettings::default()).unwrap();
+    ///
+    /// let _ = client.list_push("doctest_list_get","list","a").unwrap();
+    /// assert_eq!(client.list_get("doctest_list_get","list",0).unwrap().into_option().unwrap(), "a");
+    /// assert!(client.list_get("doctest_list_get","list",1).is_err());
+    ///
+    /// let _ = client.delete_db("doctest_list_get").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn list_get(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        index: usize,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_read_from_list(db_name, list_name, index);
+
+        self.send_packet(&packet)
+    }
+
+    /// Reads the value at the index of the list in the given db.
+    /// Returns an error if the list does not exist, or the index is past the end of the list.
+    /// Requires permissions to read from the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn list_get(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        index: usize,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_read_from_list(db_name, list_name, index);
+
+        self.send_packet(&packet).await
+    }
+
+    /// Removes the value at the index of the list in the given db, returning the removed value.
+    /// Returns an error if the list does not exist, or the index is past the end of the list.
+    /// Requires permissions to write to the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_list_remove",DBSettings::default()).unwrap();
+    ///
+    /// let _ = client.list_push("doctest_list_remove","list","a").unwrap();
+    /// let _ = client.list_push("doctest_list_remove","list","b").unwrap();
+    /// assert_eq!(client.list_remove("doctest_list_remove","list",0).unwrap().into_option().unwrap(), "a");
+    /// assert_eq!(client.list_len("doctest_list_remove","list").unwrap(), 1);
+    ///
+    /// let _ = client.delete_db("doctest_list_remove").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn list_remove(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        index: usize,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_remove_from_list(db_name, list_name, index);
+
+        self.send_packet(&packet)
+    }
+
+    /// Removes the value at the index of the list in the given db, returning the removed value.
+    /// Returns an error if the list does not exist, or the index is past the end of the list.
+    /// Requires permissions to write to the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn list_remove(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        index: usize,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_remove_from_list(db_name, list_name, index);
+
+        self.send_packet(&packet).await
+    }
+
+    /// Removes every value from the list in the given db.
+    /// Requires permissions to write to the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_list_clear",DBSettings::default()).unwrap();
+    ///
+    /// let _ = client.list_push("doctest_list_clear","list","a").unwrap();
+    /// let _ = client.list_clear("doctest_list_clear","list").unwrap();
+    /// assert_eq!(client.list_len("doctest_list_clear","list").unwrap(), 0);
+    ///
+    /// let _ = client.delete_db("doctest_list_clear").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn list_clear(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_clear_list(db_name, list_name);
+
+        self.send_packet(&packet)
+    }
+
+    /// Removes every value from the list in the given db.
+    /// Requires permissions to write to the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn list_clear(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let packet = DBPacket::new_clear_list(db_name, list_name);
+
+        self.send_packet(&packet).await
+    }
+
+    /// Returns the number of values in the list in the given db, a list that does not exist has no values.
+    /// Requires permissions to read from the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_list_len",DBSettings::default()).unwrap();
+    ///
+    /// assert_eq!(client.list_len("doctest_list_len","list").unwrap(), 0);
+    /// let _ = client.list_push("doctest_list_len","list","a").unwrap();
+    /// assert_eq!(client.list_len("doctest_list_len","list").unwrap(), 1);
+    ///
+    /// let _ = client.delete_db("doctest_list_len").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn list_len(&mut self, db_name: &str, list_name: &str) -> Result<usize, ClientError> {
+        let packet = DBPacket::new_get_list_length(db_name, list_name);
+
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<usize>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns the number of values in the list in the given db, a list that does not exist has no values.
+    /// Requires permissions to read from the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn list_len(&mut self, db_name: &str, list_name: &str) -> Result<usize, ClientError> {
+        let packet = DBPacket::new_get_list_length(db_name, list_name);
+
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<usize>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Adds the member to the set in the given db, creating the set if it does not exist.
+    /// Returns true if the member was not already in the set.
+    /// Requires permissions to write to the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_set_add",DBSettings::default()).unwrap();
+    ///
+    /// assert!(client.set_add("doctest_set_add","set","a").unwrap());
+    /// assert!(!client.set_add("doctest_set_add","set","a").unwrap());
+    ///
+    /// let _ = client.delete_db("doctest_set_add").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn set_add(
+        &mut self,
+        db_name: &str,
+        set_name: &str,
+        member: &str,
+    ) -> Result<bool, ClientError> {
+        let packet = DBPacket::new_set_add(db_name, set_name, member);
+
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<bool>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Adds the member to the set in the given db, creating the set if it does not exist.
+    /// Returns true if the member was not already in the set.
+    /// Requires permissions to write to the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn set_add(
+        &mut self,
+        db_name: &str,
+        set_name: &str,
+        member: &str,
+    ) -> Result<bool, ClientError> {
+        let packet = DBPacket::new_set_add(db_name, set_name, member);
+
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<bool>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Removes the member from the set in the given db.
+    /// Returns true if the member was in the set.
+    /// Requires permissions to write to the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_set_remove",DBSettings::default()).unwrap();
+    ///
+    /// let _ = client.set_add("doctest_set_remove","set","a").unwrap();
+    /// assert!(client.set_remove("doctest_set_remove","set","a").unwrap());
+    /// assert!(!client.set_remove("doctest_set_remove","set","a").unwrap());
+    ///
+    /// let _ = client.delete_db("doctest_set_remove").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn set_remove(
+        &mut self,
+        db_name: &str,
+        set_name: &str,
+        member: &str,
+    ) -> Result<bool, ClientError> {
+        let packet = DBPacket::new_set_remove(db_name, set_name, member);
+
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<bool>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Removes the member from the set in the given db.
+    /// Returns true if the member was in the set.
+    /// Requires permissions to write to the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn set_remove(
+        &mut self,
+        db_name: &str,
+        set_name: &str,
+        member: &str,
+    ) -> Result<bool, ClientError> {
+        let packet = DBPacket::new_set_remove(db_name, set_name, member);
+
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<bool>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns true if the member is in the set in the given db, a set that does not exist has no members.
+    /// Requires permissions to read from the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_set_contains",DBSettings::default()).unwrap();
+    ///
+    /// let _ = client.set_add("doctest_set_contains","set","a").unwrap();
+    /// assert!(client.set_contains("doctest_set_contains","set","a").unwrap());
+    /// assert!(!client.set_contains("doctest_set_contains","set","b").unwrap());
+    ///
+    /// let _ = client.delete_db("doctest_set_contains").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn set_contains(
+        &mut self,
+        db_name: &str,
+        set_name: &str,
+        member: &str,
+    ) -> Result<bool, ClientError> {
+        let packet = DBPacket::new_set_contains(db_name, set_name, member);
+
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<bool>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns true if the member is in the set in the given db, a set that does not exist has no members.
+    /// Requires permissions to read from the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn set_contains(
+        &mut self,
+        db_name: &str,
+        set_name: &str,
+        member: &str,
+    ) -> Result<bool, ClientError> {
+        let packet = DBPacket::new_set_contains(db_name, set_name, member);
+
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<bool>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns every member of the set in the given db, in order.
+    /// Requires permissions to read from the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_set_members",DBSettings::default()).unwrap();
+    ///
+    /// let _ = client.set_add("doctest_set_members","set","b").unwrap();
+    /// let _ = client.set_add("doctest_set_members","set","a").unwrap();
+    /// assert_eq!(client.set_members("doctest_set_members","set").unwrap(), vec!["a", "b"]);
+    ///
+    /// let _ = client.delete_db("doctest_set_members").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn set_members(
+        &mut self,
+        db_name: &str,
+        set_name: &str,
+    ) -> Result<Vec<String>, ClientError> {
+        let packet = DBPacket::new_set_members(db_name, set_name);
+
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<String>>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns every member of the set in the given db, in order.
+    /// Requires permissions to read from the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn set_members(
+        &mut self,
+        db_name: &str,
+        set_name: &str,
+    ) -> Result<Vec<String>, ClientError> {
+        let packet = DBPacket::new_set_members(db_name, set_name);
+
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<String>>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Adds the member to the sorted list in the given db with the given score, replacing its score if it was already a member.
+    /// Returns the rank of the member, which is its index in ascending order of score.
+    /// Requires permissions to write to the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_sorted_add",DBSettings::default()).unwrap();
+    ///
+    /// assert_eq!(client.sorted_add("doctest_sorted_add","scores","alice",10.0).unwrap(), 0);
+    /// assert_eq!(client.sorted_add("doctest_sorted_add","scores","bob",5.0).unwrap(), 0);
+    /// assert_eq!(client.sorted_rank("doctest_sorted_add","scores","alice").unwrap(), Some(1));
+    ///
+    /// let _ = client.delete_db("doctest_sorted_add").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn sorted_add(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        member: &str,
+        score: f64,
+    ) -> Result<usize, ClientError> {
+        let packet = DBPacket::new_sorted_add(db_name, list_name, member, score);
+
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<usize>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Adds the member to the sorted list in the given db with the given score, replacing its score if it was already a member.
+    /// Returns the rank of the member, which is its index in ascending order of score.
+    /// Requires permissions to write to the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn sorted_add(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        member: &str,
+        score: f64,
+    ) -> Result<usize, ClientError> {
+        let packet = DBPacket::new_sorted_add(db_name, list_name, member, score);
+
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<usize>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Removes the member from the sorted list in the given db.
+    /// Returns true if the member was in the sorted list.
+    /// Requires permissions to write to the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_sorted_remove",DBSettings::default()).unwrap();
+    ///
+    /// let _ = client.sorted_add("doctest_sorted_remove","scores","alice",10.0).unwrap();
+    /// assert!(client.sorted_remove("doctest_sorted_remove","scores","alice").unwrap());
+    /// assert_eq!(client.sorted_rank("doctest_sorted_remove","scores","alice").unwrap(), None);
+    ///
+    /// let _ = client.delete_db("doctest_sorted_remove").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn sorted_remove(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        member: &str,
+    ) -> Result<bool, ClientError> {
+        let packet = DBPacket::new_sorted_remove(db_name, list_name, member);
+
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<bool>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Removes the member from the sorted list in the given db.
+    /// Returns true if the member was in the sorted list.
+    /// Requires permissions to write to the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn sorted_remove(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        member: &str,
+    ) -> Result<bool, ClientError> {
+        let packet = DBPacket::new_sorted_remove(db_name, list_name, member);
+
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<bool>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns the rank of the member in the sorted list in the given db, which is its index in ascending order of score.
+    /// Returns `None` if the member is not in the sorted list.
+    /// Requires permissions to read from the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_sorted_rank",DBSettings::default()).unwrap();
+    ///
+    /// let _ = client.sorted_add("doctest_sorted_rank","scores","alice",10.0).unwrap();
+    /// assert_eq!(client.sorted_rank("doctest_sorted_rank","scores","alice").unwrap(), Some(0));
+    /// assert_eq!(client.sorted_rank("doctest_sorted_rank","scores","bob").unwrap(), None);
+    ///
+    /// let _ = client.delete_db("doctest_sorted_rank").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn sorted_rank(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        member: &str,
+    ) -> Result<Option<usize>, ClientError> {
+        let packet = DBPacket::new_sorted_rank(db_name, list_name, member);
+
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Option<usize>>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns the rank of the member in the sorted list in the given db, which is its index in ascending order of score.
+    /// Returns `None` if the member is not in the sorted list.
+    /// Requires permissions to read from the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn sorted_rank(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        member: &str,
+    ) -> Result<Option<usize>, ClientError> {
+        let packet = DBPacket::new_sorted_rank(db_name, list_name, member);
+
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Option<usize>>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns every member of the sorted list in the given db with a score between min and max inclusive, in ascending order of score.
+    /// Requires permissions to read from the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_sorted_range",DBSettings::default()).unwrap();
+    ///
+    /// let _ = client.sorted_add("doctest_sorted_range","scores","alice",10.0).unwrap();
+    /// let _ = client.sorted_add("doctest_sorted_range","scores","bob",20.0).unwrap();
+    /// let _ = client.sorted_add("doctest_sorted_range","scores","carol",30.0).unwrap();
+    ///
+    /// let members = client.sorted_range_by_score("doctest_sorted_range","scores",15.0,30.0).unwrap();
+    /// assert_eq!(members, vec![ScoredMember::new("bob".to_string(), 20.0), ScoredMember::new("carol".to_string(), 30.0)]);
+    ///
+    /// let _ = client.delete_db("doctest_sorted_range").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn sorted_range_by_score(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        min: f64,
+        max: f64,
+    ) -> Result<Vec<ScoredMember>, ClientError> {
+        let packet = DBPacket::new_sorted_range_by_score(db_name, list_name, min, max);
+
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<ScoredMember>>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns every member of the sorted list in the given db with a score between min and max inclusive, in ascending order of score.
+    /// Requires permissions to read from the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn sorted_range_by_score(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        min: f64,
+        max: f64,
+    ) -> Result<Vec<ScoredMember>, ClientError> {
+        let packet = DBPacket::new_sorted_range_by_score(db_name, list_name, min, max);
+
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<ScoredMember>>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns up to count of the highest scoring members of the sorted list in the given db, in descending order of score.
+    /// Requires permissions to read from the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_sorted_top",DBSettings::default()).unwrap();
+    ///
+    /// let _ = client.sorted_add("doctest_sorted_top","scores","alice",10.0).unwrap();
+    /// let _ = client.sorted_add("doctest_sorted_top","scores","bob",20.0).unwrap();
+    /// let _ = client.sorted_add("doctest_sorted_top","scores","carol",30.0).unwrap();
+    ///
+    /// let top: Vec<String> = client.sorted_top("doctest_sorted_top","scores",2).unwrap().into_iter().map(|m| m.member).collect();
+    /// assert_eq!(top, vec!["carol", "bob"]);
+    ///
+    /// let _ = client.delete_db("doctest_sorted_top").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn sorted_top(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        count: usize,
+    ) -> Result<Vec<ScoredMember>, ClientError> {
+        let packet = DBPacket::new_sorted_top(db_name, list_name, count);
+
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<ScoredMember>>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Returns up to count of the highest scoring members of the sorted list in the given db, in descending order of score.
+    /// Requires permissions to read from the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn sorted_top(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        count: usize,
+    ) -> Result<Vec<ScoredMember>, ClientError> {
+        let packet = DBPacket::new_sorted_top(db_name, list_name, count);
+
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<ScoredMember>>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Pushes the value onto the back of the queue in the given db, creating the queue if it does not exist.
+    /// Returns the new length of the queue. Queues are keyed lists, so the list methods can also be used on them.
+    /// Requires permissions to write to the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_queue_push",DBSettings::default()).unwrap();
+    ///
+    /// assert_eq!(client.queue_push("doctest_queue_push","jobs","job1").unwrap(), 1);
+    /// assert_eq!(client.queue_push("doctest_queue_push","jobs","job2").unwrap(), 2);
+    /// assert_eq!(client.list_len("doctest_queue_push","jobs").unwrap(), 2);
+    ///
+    /// let _ = client.delete_db("doctest_queue_push").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn queue_push(
+        &mut self,
+        db_name: &str,
+        queue_name: &str,
+        value: &str,
+    ) -> Result<usize, ClientError> {
+        let packet = DBPacket::new_queue_push(db_name, queue_name, value);
+
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<usize>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Pushes the value onto the back of the queue in the given db, creating the queue if it does not exist.
+    /// Returns the new length of the queue. Queues are keyed lists, so the list methods can also be used on them.
+    /// Requires permissions to write to the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn queue_push(
+        &mut self,
+        db_name: &str,
+        queue_name: &str,
+        value: &str,
+    ) -> Result<usize, ClientError> {
+        let packet = DBPacket::new_queue_push(db_name, queue_name, value);
+
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<usize>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
+    }
+
+    /// Removes and returns the value at the front of the queue in the given db, returning `None` if the queue is empty.
+    /// Requires permissions to write to the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_queue_pop",DBSettings::default()).unwrap();
+    ///
+    /// let _ = client.queue_push("doctest_queue_pop","jobs","job1").unwrap();
+    /// let _ = client.queue_push("doctest_queue_pop","jobs","job2").unwrap();
+    /// assert_eq!(client.queue_pop("doctest_queue_pop","jobs").unwrap(), Some("job1".to_string()));
+    /// assert_eq!(client.queue_pop("doctest_queue_pop","jobs").unwrap(), Some("job2".to_string()));
+    /// assert_eq!(client.queue_pop("doctest_queue_pop","jobs").unwrap(), None);
+    ///
+    /// let _ = client.delete_db("doctest_queue_pop").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn queue_pop(
+        &mut self,
+        db_name: &str,
+        queue_name: &str,
+    ) -> Result<Option<String>, ClientError> {
+        let packet = DBPacket::new_queue_pop(db_name, queue_name, None);
+
+        let response = self.send_packet(&packet)?;
+
+        Ok(response.into_option())
+    }
+
+    /// Removes and returns the value at the front of the queue in the given db, returning `None` if the queue is empty.
+    /// Requires permissions to write to the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn queue_pop(
+        &mut self,
+        db_name: &str,
+        queue_name: &str,
+    ) -> Result<Option<String>, ClientError> {
+        let packet = DBPacket::new_queue_pop(db_name, queue_name, None);
+
+        let response = self.send_packet(&packet).await?;
+
+        Ok(response.into_option())
+    }
+
+    /// Removes and returns the value at the front of the queue in the given db, waiting up to the timeout for a value to be pushed if the queue is empty.
+    /// Returns `None` if the queue was still empty once the timeout passed.
+    /// Requires permissions to write to the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_queue_pop_blocking",DBSettings::default()).unwrap();
+    ///
+    /// let _ = client.queue_push("doctest_queue_pop_blocking","jobs","job1").unwrap();
+    /// assert_eq!(client.queue_pop_blocking("doctest_queue_pop_blocking","jobs",Duration::from_millis(100)).unwrap(), Some("job1".to_string()));
+    /// assert_eq!(client.queue_pop_blocking("doctest_queue_pop_blocking","jobs",Duration::from_millis(100)).unwrap(), None);
+    ///
+    /// let _ = client.delete_db("doctest_queue_pop_blocking").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn queue_pop_blocking(
+        &mut self,
+        db_name: &str,
+        queue_name: &str,
+        timeout: Duration,
+    ) -> Result<Option<String>, ClientError> {
+        let packet = DBPacket::new_queue_pop(db_name, queue_name, Some(timeout));
+
+        let response = self.send_packet(&packet)?;
+
+        Ok(response.into_option())
+    }
+
+    /// Removes and returns the value at the front of the queue in the given db, waiting up to the timeout for a value to be pushed if the queue is empty.
+    /// Returns `None` if the queue was still empty once the timeout passed.
+    /// Requires permissions to write to the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn queue_pop_blocking(
+        &mut self,
+        db_name: &str,
+        queue_name: &str,
+        timeout: Duration,
+    ) -> Result<Option<String>, ClientError> {
+        let packet = DBPacket::new_queue_pop(db_name, queue_name, Some(timeout));
+
+        let response = self.send_packet(&packet).await?;
+
+        Ok(response.into_option())
+    }
+
+    /// Writes the binary data to the location in the given db, returning the binary data previously at the location if there was any.
+    /// Binary values are stored separately from string values, so they are read using `read_db_bytes`.
+    /// Requires permissions to write to the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_write_db_bytes",DBSettings::default()).unwrap();
+    ///
+    /// let data = vec![0, 159, 146, 150, 0];
+    /// assert_eq!(client.write_db_bytes("doctest_write_db_bytes","blob",&data).unwrap(), None);
+    /// assert_eq!(client.write_db_bytes("doctest_write_db_bytes","blob",&[1]).unwrap(), Some(data));
+    ///
+    /// let _ = client.delete_db("doctest_write_db_bytes").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument(skip(data))]
+    pub fn write_db_bytes(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+        data: &[u8],
+    ) -> Result<Option<Vec<u8>>, ClientError> {
+        let packet = DBPacket::new_write_bytes(db_name, db_location, data);
 
-        let s_res = self
-            .socket
-            .write(ser_packet.as_bytes())
-            .map_err(SocketWriteError);
+        let response = self.send_packet(&packet)?;
 
-        match s_res.as_ref() {
-            Ok(len) => {
-                info!("Successfully wrote {len} bytes to socket: {}", ser_packet);
-            }
-            Err(e) => {
-                error!("Failed to write packet to socket: {:?}", e);
-            }
+        match response {
+            SuccessNoData => Ok(None),
+            SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match DBBytes::from_base64(&data) {
+                Ok(bytes) => Ok(Some(bytes.get_data().to_vec())),
+                Err(err) => Err(PacketDeserializationError(Error::new(
+                    ErrorKind::InvalidData,
+                    err,
+                ))),
+            },
         }
+    }
 
-        s_res?;
+    /// Writes the binary data to the location in the given db, returning the binary data previously at the location if there was any.
+    /// Binary values are stored separately from string values, so they are read using `read_db_bytes`.
+    /// Requires permissions to write to the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument(skip(data))]
+    pub async fn write_db_bytes(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+        data: &[u8],
+    ) -> Result<Option<Vec<u8>>, ClientError> {
+        let packet = DBPacket::new_write_bytes(db_name, db_location, data);
 
-        let read_len_res = self.socket.read(&mut buf).map_err(SocketReadError);
+        let response = self.send_packet(&packet).await?;
 
-        match read_len_res.as_ref() {
-            Ok(len) => {
-                info!("Successfully read {len} bytes from socket");
-            }
-            Err(e) => {
-                error!("Failed to read packet from socket: {:?}", e);
-            }
+        match response {
+            SuccessNoData => Ok(None),
+            SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match DBBytes::from_base64(&data) {
+                Ok(bytes) => Ok(Some(bytes.get_data().to_vec())),
+                Err(err) => Err(PacketDeserializationError(Error::new(
+                    ErrorKind::InvalidData,
+                    err,
+                ))),
+            },
         }
+    }
 
-        let read_len = read_len_res?;
+    /// Reads the binary data at the location in the given db.
+    /// Returns an error if there is no binary data in the location.
+    /// Requires permissions to read from the given DB
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_read_db_bytes",DBSettings::default()).unwrap();
+    ///
+    /// let data = vec![0, 159, 146, 150, 0];
+    /// let _ = client.write_db_bytes("doctest_read_db_bytes","blob",&data).unwrap();
+    /// assert_eq!(client.read_db_bytes("doctest_read_db_bytes","blob").unwrap(), data);
+    ///
+    /// let _ = client.delete_db("doctest_read_db_bytes").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn read_db_bytes(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+    ) -> Result<Vec<u8>, ClientError> {
+        let packet = DBPacket::new_read_bytes(db_name, db_location);
 
-        match serde_json::from_slice::<Result<DBSuccessResponse<String>, DBPacketResponseError>>(
-            &buf[0..read_len],
-        ) {
-            Ok(thing) => {
-                match thing.as_ref() {
-                    Ok(response) => {
-                        info!("Successful response from server: {}", response);
-                    }
-                    Err(err) => {
-                        error!("Error response from server: {}", err);
-                    }
-                }
-                thing.map_err(DBResponseError)
-            }
-            Err(err) => {
-                // if we fail to read a packet, check if it is an encrypted packet
-                if let Some(client_private_key) = &self.encryption {
-                    match client_private_key
-                        .decrypt_server_packet(&buf[0..read_len])
-                        .map_err(PacketEncryptionError)
-                    {
-                        Ok(decrypted) => {
-                            info!("Successfully decrypted data from server packet");
-                            match decrypted.as_ref() {
-                                Ok(response) => {
-                                    info!("Successful response from server: {}", response);
-                                }
-                                Err(err) => {
-                                    error!("Error response from server: {}", err);
-                                }
-                            }
-                            decrypted.map_err(DBResponseError)
-                        }
-                        Err(err) => {
-                            error!("Error decrypting server packet: {:?}", err);
-                            return Err(err);
-                        }
-                    }
-                } else {
-                    error!("Packet deserialization error: {}", err);
-                    Err(PacketDeserializationError(Error::from(err)))
-                }
-            }
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match DBBytes::from_base64(&data) {
+                Ok(bytes) => Ok(bytes.get_data().to_vec()),
+                Err(err) => Err(PacketDeserializationError(Error::new(
+                    ErrorKind::InvalidData,
+                    err,
+                ))),
+            },
         }
     }
 
-    /// Sends a packet to the clients currently connected database and returns the result
+    /// Reads the binary data at the location in the given db.
+    /// Returns an error if there is no binary data in the location.
+    /// Requires permissions to read from the given DB
     #[cfg(feature = "async")]
     #[tracing::instrument]
-    pub(crate) async fn send_packet(
+    pub async fn read_db_bytes(
         &mut self,
-        sent_packet: &DBPacket,
-    ) -> Result<DBSuccessResponse<String>, ClientError> {
-        let mut buf: [u8; 1024] = [0; 1024];
+        db_name: &str,
+        db_location: &str,
+    ) -> Result<Vec<u8>, ClientError> {
+        let packet = DBPacket::new_read_bytes(db_name, db_location);
 
-        // branch depending on if we are using encryption with communication
-        let ser_packet = match &mut self.encryption {
-            None => {
-                let p = sent_packet
-                    .serialize_packet()
-                    .map_err(|err| PacketSerializationError(Error::from(err)));
+        let response = self.send_packet(&packet).await?;
 
-                match p.as_ref() {
-                    Ok(_) => {
-                        info!("Successfully serialized packet");
-                    }
-                    Err(e) => {
-                        error!("Failed to serialize packet: {:?}", e);
-                    }
-                }
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match DBBytes::from_base64(&data) {
+                Ok(bytes) => Ok(bytes.get_data().to_vec()),
+                Err(err) => Err(PacketDeserializationError(Error::new(
+                    ErrorKind::InvalidData,
+                    err,
+                ))),
+            },
+        }
+    }
 
-                p?
-            }
-            Some(client_encrypt) => {
-                // if we are sending a public key packet, we don't encrypt it, since the server needs this to send data back properly
-                if !matches!(sent_packet, DBPacket::PubKey(_)) {
-                    let p = client_encrypt
-                        .encrypt_packet(sent_packet)
-                        .map_err(PacketEncryptionError)?
-                        .serialize_packet()
-                        .map_err(|err| PacketSerializationError(Error::from(err)));
+    /// Lists all the current databases available by name from the server
+    /// Only error on IO Error
+    /// ```
+    /// use smol_db_client::prelude::SmolDbClient;
+    /// use smol_db_common::db_packets::db_packet_info::DBPacketInfo;
+    /// use smol_db_common::db_packets::db_settings::DBSettings;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_list_db1",DBSettings::default()).unwrap();
+    ///
+    /// // get list of databases currently on the server
+    /// let list_of_dbs1 = client.list_db().unwrap();
+    /// assert!(list_of_dbs1.contains(&DBPacketInfo::new("doctest_list_db1")));
+    /// assert!(!list_of_dbs1.contains(&DBPacketInfo::new("doctest_list_db2")));
+    ///
+    /// let _ = client.create_db("doctest_list_db2",DBSettings::default()).unwrap();
+    ///
+    /// // newly created databases show up after getting another copy of the list
+    /// let list_of_dbs2 = client.list_db().unwrap();
+    /// assert!(list_of_dbs2.contains(&DBPacketInfo::new("doctest_list_db2")));
+    /// assert!(list_of_dbs2.contains(&DBPacketInfo::new("doctest_list_db1")));
+    ///
+    /// let _ = client.delete_db("doctest_list_db1").unwrap();
+    /// let _ = client.delete_db("doctest_list_db2").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn list_db(&mut self) -> Result<Vec<DBPacketInfo>, ClientError> {
+        let packet = DBPacket::new_list_db();
 
-                    match p.as_ref() {
-                        Ok(_) => {
-                            info!("Successfully encrypted packet");
-                        }
-                        Err(e) => {
-                            error!("Failed to encrypt packet: {:?}", e);
-                        }
-                    }
+        let response = self.send_packet(&packet)?;
 
-                    p?
-                } else {
-                    let p = sent_packet
-                        .serialize_packet()
-                        .map_err(|err| PacketSerializationError(Error::from(err)));
+        match response {
+            SuccessData(ResponseData::DBList(thing)) => {
+                Ok(thing.into_iter().map(|listing| listing.info).collect())
+            }
+            SuccessNoData | SuccessReply(_) | SuccessData(_) => Err(BadPacket),
+        }
+    }
 
-                    match p.as_ref() {
-                        Ok(_) => {
-                            info!("Successfully serialized public key packet");
-                        }
-                        Err(e) => {
-                            error!("Failed to serialize public key packet: {:?}", e);
-                        }
-                    }
+    /// Lists all the current databases available by name from the server
+    /// Only error on IO Error
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn list_db(&mut self) -> Result<Vec<DBPacketInfo>, ClientError> {
+        let packet = DBPacket::new_list_db();
 
-                    p?
-                }
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessData(ResponseData::DBList(thing)) => {
+                Ok(thing.into_iter().map(|listing| listing.info).collect())
             }
-        };
+            SuccessNoData | SuccessReply(_) | SuccessData(_) => Err(BadPacket),
+        }
+    }
 
-        let s_res = self
-            .socket
-            .write(ser_packet.as_bytes())
-            .await
-            .map_err(SocketWriteError);
+    /// Lists all the current databases on the server, each with its metadata
+    /// The metadata of a database is `None` if the server could not load the database
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn list_db_with_metadata(&mut self) -> Result<Vec<DBListing>, ClientError> {
+        let packet = DBPacket::new_list_db();
 
-        match s_res.as_ref() {
-            Ok(len) => {
-                info!("Successfully wrote {len} bytes to socket");
-            }
-            Err(e) => {
-                error!("Failed to write packet to socket: {:?}", e);
-            }
+        match self.send_packet(&packet)? {
+            SuccessData(ResponseData::DBList(listings)) => Ok(listings),
+            SuccessNoData | SuccessReply(_) | SuccessData(_) => Err(BadPacket),
         }
+    }
 
-        s_res?;
+    /// Lists all the current databases on the server, each with its metadata
+    /// The metadata of a database is `None` if the server could not load the database
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn list_db_with_metadata(&mut self) -> Result<Vec<DBListing>, ClientError> {
+        let packet = DBPacket::new_list_db();
+
+        match self.send_packet(&packet).await? {
+            SuccessData(ResponseData::DBList(listings)) => Ok(listings),
+            SuccessNoData | SuccessReply(_) | SuccessData(_) => Err(BadPacket),
+        }
+    }
 
-        let read_len_res = self.socket.read(&mut buf).await.map_err(SocketReadError);
+    /// Lists the databases the key of this client has read or list permissions on, each with its metadata,
+    /// so databases the client has no access to are not revealed to it.
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn list_accessible_db(&mut self) -> Result<Vec<DBListing>, ClientError> {
+        let packet = DBPacket::new_list_accessible_db();
 
-        match read_len_res.as_ref() {
-            Ok(len) => {
-                info!("Successfully read {len} bytes from socket");
-            }
-            Err(e) => {
-                error!("Failed to read packet from socket: {:?}", e);
-            }
+        match self.send_packet(&packet)? {
+            SuccessData(ResponseData::DBList(listings)) => Ok(listings),
+            SuccessNoData | SuccessReply(_) | SuccessData(_) => Err(BadPacket),
         }
+    }
 
-        let read_len = read_len_res?;
+    /// Lists the databases the key of this client has read or list permissions on, each with its metadata,
+    /// so databases the client has no access to are not revealed to it.
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn list_accessible_db(&mut self) -> Result<Vec<DBListing>, ClientError> {
+        let packet = DBPacket::new_list_accessible_db();
 
-        match serde_json::from_slice::<Result<DBSuccessResponse<String>, DBPacketResponseError>>(
-            &buf[0..read_len],
-        ) {
-            Ok(thing) => {
-                match thing.as_ref() {
-                    Ok(response) => {
-                        info!("Successful response from server: {}", response);
-                    }
-                    Err(err) => {
-                        error!("Error response from server: {}", err);
-                    }
-                }
-                thing.map_err(DBResponseError)
-            }
-            Err(err) => {
-                // if we fail to read a packet, check if it is an encrypted packet
-                if let Some(client_private_key) = &self.encryption {
-                    match client_private_key
-                        .decrypt_server_packet(&buf[0..read_len])
-                        .map_err(PacketEncryptionError)
-                    {
-                        Ok(decrypted) => {
-                            info!("Successfully decrypted data from server packet");
-                            match decrypted.as_ref() {
-                                Ok(response) => {
-                                    info!("Successful response from server: {}", response);
-                                }
-                                Err(err) => {
-                                    error!("Error response from server: {}", err);
-                                }
-                            }
-                            decrypted.map_err(DBResponseError)
-                        }
-                        Err(err) => {
-                            error!("Error decrypting server packet: {:?}", err);
-                            return Err(err);
-                        }
-                    }
-                } else {
-                    error!("Packet deserialization error: {}", err);
-                    Err(PacketDeserializationError(Error::from(err)))
-                }
-            }
+        match self.send_packet(&packet).await? {
+            SuccessData(ResponseData::DBList(listings)) => Ok(listings),
+            SuccessNoData | SuccessReply(_) | SuccessData(_) => Err(BadPacket),
         }
     }
 
-    /// Creates a db through the client with the given name.
-    /// Error on IO Error, or when the user lacks permissions to create a DB
+    /// Get the hashmap of the contents of a database. Contents are always String:String for the hashmap.
+    /// Requires list permissions on the given DB
     /// ```
-    /// use std::time::Duration;
     /// use smol_db_client::prelude::SmolDbClient;
     /// use smol_db_common::db_packets::db_settings::DBSettings;
     ///
     /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
     ///
     /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
-    /// let _ = client.create_db("doctest_create_db",DBSettings::default()).unwrap();
+    /// let _ = client.create_db("doctest_list_cont_db",DBSettings::default()).unwrap();
+    ///
+    ///let _ = client.write_db("doctest_list_cont_db","cool_data_location","cool_data");
     ///
-    /// let _ = client.delete_db("doctest_create_db").unwrap();
+    /// let contents = client.list_db_contents("doctest_list_cont_db").unwrap();
+    /// assert_eq!(contents.len(),1);
+    /// assert_eq!(contents.get("cool_data_location").unwrap().as_str(),"cool_data");
+    ///
+    /// let _ = client.delete_db("doctest_list_cont_db").unwrap();
     /// ```
     #[cfg(not(feature = "async"))]
     #[tracing::instrument]
-    pub fn create_db(
+    pub fn list_db_contents(
         &mut self,
         db_name: &str,
-        db_settings: DBSettings,
-    ) -> Result<DBSuccessResponse<String>, ClientError> {
-        let packet = DBPacket::new_create_db(db_name, db_settings);
-        let resp = self.send_packet(&packet)?;
+    ) -> Result<HashMap<String, String>, ClientError> {
+        let packet = DBPacket::new_list_db_contents(db_name);
 
-        Ok(resp)
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<HashMap<String, String>>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
     }
 
-    /// Creates a db through the client with the given name.
-    /// Error on IO Error, or when the user lacks permissions to create a DB
+    /// Get the hashmap of the contents of a database. Contents are always String:String for the hashmap.
+    /// Requires list permissions on the given DB
     #[cfg(feature = "async")]
     #[tracing::instrument]
-    pub async fn create_db(
+    pub async fn list_db_contents(
         &mut self,
         db_name: &str,
-        db_settings: DBSettings,
-    ) -> Result<DBSuccessResponse<String>, ClientError> {
-        let packet = DBPacket::new_create_db(db_name, db_settings);
-        let resp = self.send_packet(&packet).await?;
+    ) -> Result<HashMap<String, String>, ClientError> {
+        let packet = DBPacket::new_list_db_contents(db_name);
 
-        Ok(resp)
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<HashMap<String, String>>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
     }
 
-    /// Writes to a db at the location specified, with the data given as a string.
-    /// Returns the data in the location that was overwritten if there was any.
-    /// Requires permissions to write to the given DB
+    /// Get the hashmap of the contents of a database, received in chunks rather than as a single response.
+    /// The server copies the contents when the stream begins and serializes them as the chunks are received,
+    /// so the whole serialized database is never held in memory at once, and other clients are not held up while it is serialized.
+    /// Requires list permissions on the given DB
     /// ```
-    /// use smol_db_client::prelude::SmolDbClient;
-    /// use smol_db_common::db_packets::db_settings::DBSettings;
+    /// use smol_db_client::prelude::*;
     ///
     /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
     ///
     /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
-    /// let _ = client.create_db("doctest_write_data",DBSettings::default()).unwrap();
+    /// let _ = client.create_db("doctest_stream_contents",DBSettings::default()).unwrap();
     ///
-    /// // write the given data to the given location within the specified db
-    /// let _ = client.write_db("doctest_write_data","cool_data_location","cool_data");
+    /// let value = "v".repeat(200);
+    /// for key in 0..20 {
+    ///     let _ = client.write_db("doctest_stream_contents",&key.to_string(),&value).unwrap();
+    /// }
     ///
-    /// let read_data1 = client.read_db("doctest_write_data","cool_data_location").unwrap().as_option().unwrap().to_string();
-    /// assert_eq!(read_data1.as_str(),"cool_data");
+    /// // serialized by the server a chunk at a time, rather than all at once as list_db_contents does
+    /// let contents = client.stream_db_contents("doctest_stream_contents").unwrap();
+    /// assert_eq!(contents.len(),20);
+    /// assert!(contents.values().all(|streamed| *streamed == value));
     ///
-    /// let _ = client.delete_db("doctest_write_data").unwrap();
+    /// let _ = client.delete_db("doctest_stream_contents").unwrap();
     /// ```
     #[cfg(not(feature = "async"))]
     #[tracing::instrument]
-    pub fn write_db(
+    pub fn stream_db_contents(
         &mut self,
         db_name: &str,
-        db_location: &str,
-        data: &str,
-    ) -> Result<DBSuccessResponse<String>, ClientError> {
-        let packet = DBPacket::new_write(db_name, db_location, data);
-
-        self.send_packet(&packet)
-    }
+    ) -> Result<HashMap<String, String>, ClientError> {
+        let _ = self.send_packet(&DBPacket::new_stream_db_contents(db_name))?;
 
-    /// Writes to a db at the location specified, with the data given as a string.
-    /// Returns the data in the location that was overwritten if there was any.
-    /// Requires permissions to write to the given DB
-    #[cfg(feature = "async")]
-    #[tracing::instrument]
-    pub async fn write_db(
-        &mut self,
-        db_name: &str,
-        db_location: &str,
-        data: &str,
-    ) -> Result<DBSuccessResponse<String>, ClientError> {
-        let packet = DBPacket::new_write(db_name, db_location, data);
+        let mut data = vec![];
+        for chunk in WatchIter::<DBBytes>::new(self) {
+            data.extend_from_slice(chunk.get_data());
+        }
 
-        self.send_packet(&packet).await
+        serde_json::from_slice::<HashMap<String, String>>(&data)
+            .map_err(|err| PacketDeserializationError(Error::from(err)))
     }
 
-    /// Reads from a db at the location specific.
-    /// Returns an error if there is no data in the location.
-    /// Requires permissions to read from the given DB
+    /// Get the list of keys in a database, without downloading the values stored at each key.
+    /// Requires list permissions on the given DB
     /// ```
     /// use smol_db_client::prelude::SmolDbClient;
     /// use smol_db_common::db_packets::db_settings::DBSettings;
@@ -879,192 +5505,223 @@ impl SmolDbClient {
     /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
     ///
     /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
-    /// let _ = client.create_db("doctest_read_db",DBSettings::default()).unwrap();
+    /// let _ = client.create_db("doctest_list_keys_db",DBSettings::default()).unwrap();
     ///
-    ///let _ = client.write_db("doctest_read_db","cool_data_location","cool_data");
+    /// let _ = client.write_db("doctest_list_keys_db","cool_data_location","cool_data");
     ///
-    /// // read the given database at the given location
-    /// let read_data1 = client.read_db("doctest_read_db","cool_data_location").unwrap().as_option().unwrap().to_string();
-    /// assert_eq!(read_data1.as_str(),"cool_data");
+    /// let keys = client.list_db_keys("doctest_list_keys_db").unwrap();
+    /// assert_eq!(keys, vec!["cool_data_location".to_string()]);
     ///
-    /// let _ = client.delete_db("doctest_read_db").unwrap();
+    /// let _ = client.delete_db("doctest_list_keys_db").unwrap();
     /// ```
     #[cfg(not(feature = "async"))]
     #[tracing::instrument]
-    pub fn read_db(
-        &mut self,
-        db_name: &str,
-        db_location: &str,
-    ) -> Result<DBSuccessResponse<String>, ClientError> {
-        let packet = DBPacket::new_read(db_name, db_location);
+    pub fn list_db_keys(&mut self, db_name: &str) -> Result<Vec<String>, ClientError> {
+        let packet = DBPacket::new_list_db_keys(db_name);
 
-        self.send_packet(&packet)
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<String>>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
     }
 
-    /// Reads from a db at the location specific.
-    /// Returns an error if there is no data in the location.
-    /// Requires permissions to read from the given DB
+    /// Get the list of keys in a database, without downloading the values stored at each key.
+    /// Requires list permissions on the given DB
     #[cfg(feature = "async")]
     #[tracing::instrument]
-    pub async fn read_db(
-        &mut self,
-        db_name: &str,
-        db_location: &str,
-    ) -> Result<DBSuccessResponse<String>, ClientError> {
-        let packet = DBPacket::new_read(db_name, db_location);
+    pub async fn list_db_keys(&mut self, db_name: &str) -> Result<Vec<String>, ClientError> {
+        let packet = DBPacket::new_list_db_keys(db_name);
 
-        self.send_packet(&packet).await
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<String>>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
     }
 
-    /// Deletes the given db by name.
-    /// Requires super admin privileges on the given DB Server
+    /// Get the names of the keyed lists in a database, ordered by name.
+    /// Requires list permissions on the given DB
     /// ```
-    /// use smol_db_client::prelude::SmolDbClient;
-    /// use smol_db_common::db_packets::db_settings::DBSettings;
+    /// use smol_db_client::prelude::*;
     ///
     /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
     ///
     /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
-    /// let _ = client.create_db("doctest_delete_db",DBSettings::default()).unwrap();
+    /// let _ = client.create_db("doctest_list_db_lists",DBSettings::default()).unwrap();
     ///
-    /// // delete the db with the given name
-    /// let _ = client.delete_db("doctest_delete_db").unwrap();
+    /// let _ = client.list_push("doctest_list_db_lists","list","a").unwrap();
+    ///
+    /// let lists = client.list_db_lists("doctest_list_db_lists").unwrap();
+    /// assert_eq!(lists, vec!["list".to_string()]);
+    ///
+    /// let _ = client.delete_db("doctest_list_db_lists").unwrap();
     /// ```
     #[cfg(not(feature = "async"))]
     #[tracing::instrument]
-    pub fn delete_db(&mut self, db_name: &str) -> Result<DBSuccessResponse<String>, ClientError> {
-        let packet = DBPacket::new_delete_db(db_name);
+    pub fn list_db_lists(&mut self, db_name: &str) -> Result<Vec<String>, ClientError> {
+        let packet = DBPacket::new_list_db_lists(db_name);
 
-        self.send_packet(&packet)
+        let response = self.send_packet(&packet)?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<String>>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
     }
 
-    /// Deletes the given db by name.
-    /// Requires super admin privileges on the given DB Server
+    /// Get the names of the keyed lists in a database, ordered by name.
+    /// Requires list permissions on the given DB
     #[cfg(feature = "async")]
     #[tracing::instrument]
-    pub async fn delete_db(
-        &mut self,
-        db_name: &str,
-    ) -> Result<DBSuccessResponse<String>, ClientError> {
-        let packet = DBPacket::new_delete_db(db_name);
+    pub async fn list_db_lists(&mut self, db_name: &str) -> Result<Vec<String>, ClientError> {
+        let packet = DBPacket::new_list_db_lists(db_name);
 
-        self.send_packet(&packet).await
+        let response = self.send_packet(&packet).await?;
+
+        match response {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<String>>(&data) {
+                Ok(thing) => Ok(thing),
+                Err(err) => Err(PacketDeserializationError(Error::from(err))),
+            },
+        }
     }
 
-    /// Lists all the current databases available by name from the server
-    /// Only error on IO Error
+    /// Reads every key value pair in a database whose key starts with the given prefix, ordered by key.
+    /// Useful for namespaced keys such as `user:123:*`.
+    /// Requires read permissions on the given DB
     /// ```
     /// use smol_db_client::prelude::SmolDbClient;
-    /// use smol_db_common::db_packets::db_packet_info::DBPacketInfo;
     /// use smol_db_common::db_packets::db_settings::DBSettings;
     ///
     /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
     ///
     /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
-    /// let _ = client.create_db("doctest_list_db1",DBSettings::default()).unwrap();
-    ///
-    /// // get list of databases currently on the server
-    /// let list_of_dbs1 = client.list_db().unwrap();
-    /// assert!(list_of_dbs1.contains(&DBPacketInfo::new("doctest_list_db1")));
-    /// assert!(!list_of_dbs1.contains(&DBPacketInfo::new("doctest_list_db2")));
+    /// let _ = client.create_db("doctest_read_prefix_db",DBSettings::default()).unwrap();
     ///
-    /// let _ = client.create_db("doctest_list_db2",DBSettings::default()).unwrap();
+    /// let _ = client.write_db("doctest_read_prefix_db","user:1:name","cool_name");
+    /// let _ = client.write_db("doctest_read_prefix_db","user:2:name","other_name");
     ///
-    /// // newly created databases show up after getting another copy of the list
-    /// let list_of_dbs2 = client.list_db().unwrap();
-    /// assert!(list_of_dbs2.contains(&DBPacketInfo::new("doctest_list_db2")));
-    /// assert!(list_of_dbs2.contains(&DBPacketInfo::new("doctest_list_db1")));
+    /// let values = client.read_prefix("doctest_read_prefix_db","user:1:").unwrap();
+    /// assert_eq!(values.len(),1);
+    /// assert_eq!(values.get("user:1:name").unwrap().as_str(),"cool_name");
     ///
-    /// let _ = client.delete_db("doctest_list_db1").unwrap();
-    /// let _ = client.delete_db("doctest_list_db2").unwrap();
+    /// let _ = client.delete_db("doctest_read_prefix_db").unwrap();
     /// ```
     #[cfg(not(feature = "async"))]
     #[tracing::instrument]
-    pub fn list_db(&mut self) -> Result<Vec<DBPacketInfo>, ClientError> {
-        let packet = DBPacket::new_list_db();
+    pub fn read_prefix(
+        &mut self,
+        db_name: &str,
+        prefix: &str,
+    ) -> Result<BTreeMap<String, String>, ClientError> {
+        let packet = DBPacket::new_read_prefix(db_name, prefix);
 
         let response = self.send_packet(&packet)?;
 
         match response {
-            SuccessNoData => Err(BadPacket),
-            SuccessReply(data) => match serde_json::from_str::<Vec<DBPacketInfo>>(&data) {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<BTreeMap<String, String>>(&data) {
                 Ok(thing) => Ok(thing),
                 Err(err) => Err(PacketDeserializationError(Error::from(err))),
             },
         }
     }
 
-    /// Lists all the current databases available by name from the server
-    /// Only error on IO Error
+    /// Reads every key value pair in a database whose key starts with the given prefix, ordered by key.
+    /// Useful for namespaced keys such as `user:123:*`.
+    /// Requires read permissions on the given DB
     #[cfg(feature = "async")]
     #[tracing::instrument]
-    pub async fn list_db(&mut self) -> Result<Vec<DBPacketInfo>, ClientError> {
-        let packet = DBPacket::new_list_db();
+    pub async fn read_prefix(
+        &mut self,
+        db_name: &str,
+        prefix: &str,
+    ) -> Result<BTreeMap<String, String>, ClientError> {
+        let packet = DBPacket::new_read_prefix(db_name, prefix);
 
         let response = self.send_packet(&packet).await?;
 
         match response {
-            SuccessNoData => Err(BadPacket),
-            SuccessReply(data) => match serde_json::from_str::<Vec<DBPacketInfo>>(&data) {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<BTreeMap<String, String>>(&data) {
                 Ok(thing) => Ok(thing),
                 Err(err) => Err(PacketDeserializationError(Error::from(err))),
             },
         }
     }
 
-    /// Get the hashmap of the contents of a database. Contents are always String:String for the hashmap.
+    /// Get the list of keys in a database that match the given glob or regex pattern, the filtering is done on the server.
     /// Requires list permissions on the given DB
     /// ```
     /// use smol_db_client::prelude::SmolDbClient;
     /// use smol_db_common::db_packets::db_settings::DBSettings;
+    /// use smol_db_common::db_packets::key_pattern::KeyPattern;
     ///
     /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
     ///
     /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
-    /// let _ = client.create_db("doctest_list_cont_db",DBSettings::default()).unwrap();
+    /// let _ = client.create_db("doctest_find_keys_db",DBSettings::default()).unwrap();
     ///
-    ///let _ = client.write_db("doctest_list_cont_db","cool_data_location","cool_data");
+    /// let _ = client.write_db("doctest_find_keys_db","user:1","cool_data");
+    /// let _ = client.write_db("doctest_find_keys_db","item:1","other_data");
     ///
-    /// let contents = client.list_db_contents("doctest_list_cont_db").unwrap();
-    /// assert_eq!(contents.len(),1);
-    /// assert_eq!(contents.get("cool_data_location").unwrap().as_str(),"cool_data");
+    /// let keys = client.find_keys("doctest_find_keys_db",KeyPattern::new_glob("user:*")).unwrap();
+    /// assert_eq!(keys, vec!["user:1".to_string()]);
     ///
-    /// let _ = client.delete_db("doctest_list_cont_db").unwrap();
+    /// let keys = client.find_keys("doctest_find_keys_db",KeyPattern::new_regex("^item:[0-9]+$")).unwrap();
+    /// assert_eq!(keys, vec!["item:1".to_string()]);
+    ///
+    /// let _ = client.delete_db("doctest_find_keys_db").unwrap();
     /// ```
     #[cfg(not(feature = "async"))]
     #[tracing::instrument]
-    pub fn list_db_contents(
+    pub fn find_keys(
         &mut self,
         db_name: &str,
-    ) -> Result<HashMap<String, String>, ClientError> {
-        let packet = DBPacket::new_list_db_contents(db_name);
+        pattern: KeyPattern,
+    ) -> Result<Vec<String>, ClientError> {
+        let packet = DBPacket::new_find_keys(db_name, pattern);
 
         let response = self.send_packet(&packet)?;
 
         match response {
-            SuccessNoData => Err(BadPacket),
-            SuccessReply(data) => match serde_json::from_str::<HashMap<String, String>>(&data) {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<String>>(&data) {
                 Ok(thing) => Ok(thing),
                 Err(err) => Err(PacketDeserializationError(Error::from(err))),
             },
         }
     }
 
-    /// Get the hashmap of the contents of a database. Contents are always String:String for the hashmap.
+    /// Get the list of keys in a database that match the given glob or regex pattern, the filtering is done on the server.
     /// Requires list permissions on the given DB
     #[cfg(feature = "async")]
     #[tracing::instrument]
-    pub async fn list_db_contents(
+    pub async fn find_keys(
         &mut self,
         db_name: &str,
-    ) -> Result<HashMap<String, String>, ClientError> {
-        let packet = DBPacket::new_list_db_contents(db_name);
+        pattern: KeyPattern,
+    ) -> Result<Vec<String>, ClientError> {
+        let packet = DBPacket::new_find_keys(db_name, pattern);
 
         let response = self.send_packet(&packet).await?;
 
         match response {
-            SuccessNoData => Err(BadPacket),
-            SuccessReply(data) => match serde_json::from_str::<HashMap<String, String>>(&data) {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => match serde_json::from_str::<Vec<String>>(&data) {
                 Ok(thing) => Ok(thing),
                 Err(err) => Err(PacketDeserializationError(Error::from(err))),
             },
@@ -1137,6 +5794,7 @@ impl SmolDbClient {
             Ok(ser_data) => match self.write_db(db_name, db_location, &ser_data) {
                 Ok(response) => match response {
                     SuccessNoData => Ok(smol_db_common::prelude::SuccessNoData),
+                    SuccessData(data) => Ok(SuccessData(data)),
                     SuccessReply(data_string) => match serde_json::from_str::<T>(&data_string) {
                         Ok(thing) => Ok(SuccessReply(thing)),
                         Err(err) => Err(PacketDeserializationError(Error::from(err))),
@@ -1164,6 +5822,7 @@ impl SmolDbClient {
             Ok(ser_data) => match self.write_db(db_name, db_location, &ser_data).await {
                 Ok(response) => match response {
                     SuccessNoData => Ok(smol_db_common::prelude::SuccessNoData),
+                    SuccessData(data) => Ok(SuccessData(data)),
                     SuccessReply(data_string) => match serde_json::from_str::<T>(&data_string) {
                         Ok(thing) => Ok(SuccessReply(thing)),
                         Err(err) => Err(PacketDeserializationError(Error::from(err))),
@@ -1189,6 +5848,7 @@ impl SmolDbClient {
         match self.read_db(db_name, db_location) {
             Ok(data) => match data {
                 SuccessNoData => Ok(SuccessNoData),
+                SuccessData(data) => Ok(SuccessData(data)),
                 SuccessReply(read_data) => match serde_json::from_str::<T>(&read_data) {
                     Ok(data) => Ok(SuccessReply(data)),
                     Err(err) => Err(PacketDeserializationError(Error::from(err))),
@@ -1212,6 +5872,7 @@ impl SmolDbClient {
         match self.read_db(db_name, db_location).await {
             Ok(data) => match data {
                 SuccessNoData => Ok(smol_db_common::prelude::SuccessNoData),
+                SuccessData(data) => Ok(SuccessData(data)),
                 SuccessReply(read_data) => match serde_json::from_str::<T>(&read_data) {
                     Ok(data) => Ok(SuccessReply(data)),
                     Err(err) => Err(PacketDeserializationError(Error::from(err))),
@@ -1220,4 +5881,117 @@ impl SmolDbClient {
             Err(err) => Err(err),
         }
     }
+
+    /// Reads the value at the key, deserialized to `T`, or `None` if the key has no value.
+    /// Requires permissions to read from the given DB
+    /// ```
+    /// use serde::{Deserialize, Serialize};
+    /// use smol_db_client::prelude::*;
+    ///
+    /// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    /// struct Score {
+    ///     points: u32,
+    /// }
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_get_set_remove",DBSettings::default()).unwrap();
+    ///
+    /// assert_eq!(client.get::<Score>("doctest_get_set_remove","player1").unwrap(), None);
+    /// assert_eq!(client.set("doctest_get_set_remove","player1",&Score { points: 10 }).unwrap(), None);
+    /// // setting returns the value that was replaced
+    /// assert_eq!(client.set("doctest_get_set_remove","player1",&Score { points: 20 }).unwrap(), Some(Score { points: 10 }));
+    /// assert_eq!(client.get("doctest_get_set_remove","player1").unwrap(), Some(Score { points: 20 }));
+    ///
+    /// assert!(client.remove("doctest_get_set_remove","player1").unwrap().is_some());
+    /// assert_eq!(client.remove("doctest_get_set_remove","player1").unwrap(), None);
+    /// assert_eq!(client.get::<Score>("doctest_get_set_remove","player1").unwrap(), None);
+    ///
+    /// let _ = client.delete_db("doctest_get_set_remove").unwrap();
+    /// ```
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn get<T>(&mut self, db_name: &str, key: &str) -> Result<Option<T>, ClientError>
+    where
+        for<'a> T: Serialize + Deserialize<'a>,
+    {
+        self.read_db_generic::<T>(db_name, key).optional()
+    }
+
+    /// Reads the value at the key, deserialized to `T`, or `None` if the key has no value.
+    /// Requires permissions to read from the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn get<T>(&mut self, db_name: &str, key: &str) -> Result<Option<T>, ClientError>
+    where
+        for<'a> T: Serialize + Deserialize<'a>,
+    {
+        self.read_db_generic::<T>(db_name, key).await.optional()
+    }
+
+    /// Writes the value serialized to the key, returning the value it replaced deserialized to `T`, or `None` if the key had no value.
+    /// Requires permissions to write to the given DB
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument(skip(value))]
+    pub fn set<T>(&mut self, db_name: &str, key: &str, value: &T) -> Result<Option<T>, ClientError>
+    where
+        for<'a> T: Serialize + Deserialize<'a>,
+    {
+        let ser_data = serde_json::to_string(value)
+            .map_err(|err| PacketSerializationError(Error::from(err)))?;
+
+        match self.write_db(db_name, key, &ser_data)? {
+            SuccessNoData => Ok(None),
+            SuccessData(_) => Err(BadPacket),
+            SuccessReply(previous) => serde_json::from_str::<T>(&previous)
+                .map(Some)
+                .map_err(|err| PacketDeserializationError(Error::from(err))),
+        }
+    }
+
+    /// Writes the value serialized to the key, returning the value it replaced deserialized to `T`, or `None` if the key had no value.
+    /// Requires permissions to write to the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument(skip(value))]
+    pub async fn set<T>(
+        &mut self,
+        db_name: &str,
+        key: &str,
+        value: &T,
+    ) -> Result<Option<T>, ClientError>
+    where
+        for<'a> T: Serialize + Deserialize<'a>,
+    {
+        let ser_data = serde_json::to_string(value)
+            .map_err(|err| PacketSerializationError(Error::from(err)))?;
+
+        match self.write_db(db_name, key, &ser_data).await? {
+            SuccessNoData => Ok(None),
+            SuccessData(_) => Err(BadPacket),
+            SuccessReply(previous) => serde_json::from_str::<T>(&previous)
+                .map(Some)
+                .map_err(|err| PacketDeserializationError(Error::from(err))),
+        }
+    }
+
+    /// Deletes the value at the key, returning the value as it was stored, or `None` if the key had no value.
+    /// Requires permissions to delete from the given DB
+    #[cfg(not(feature = "async"))]
+    #[tracing::instrument]
+    pub fn remove(&mut self, db_name: &str, key: &str) -> Result<Option<String>, ClientError> {
+        self.delete_data(db_name, key).optional()
+    }
+
+    /// Deletes the value at the key, returning the value as it was stored, or `None` if the key had no value.
+    /// Requires permissions to delete from the given DB
+    #[cfg(feature = "async")]
+    #[tracing::instrument]
+    pub async fn remove(
+        &mut self,
+        db_name: &str,
+        key: &str,
+    ) -> Result<Option<String>, ClientError> {
+        self.delete_data(db_name, key).await.optional()
+    }
 }
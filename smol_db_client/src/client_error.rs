@@ -1,6 +1,7 @@
 //! Contains various error enums that a client may return on an operation with a database
-use smol_db_common::db_packets::db_packet_response::DBPacketResponseError;
+use smol_db_common::db_packets::db_packet_response::{DBPacketResponseError, DBSuccessResponse};
 use smol_db_common::encryption::EncryptionError;
+use std::fmt::{Display, Formatter};
 use std::io::Error;
 
 #[derive(Debug)]
@@ -26,6 +27,17 @@ pub enum ClientError {
     EncryptionSetupError,
     /// Generating a key pair produced an error
     KeyGenerationError(smol_db_common::prelude::Error),
+    /// Reading the value to stream to the database, or writing the value streamed from the database failed
+    ValueStreamError(Error),
+    /// The server presented a key with a different fingerprint than the one expected when setting up encryption
+    ServerIdentityChanged {
+        /// Fingerprint the server key was expected to have
+        expected: String,
+        /// Fingerprint of the key the server presented
+        presented: String,
+    },
+    /// The write could not be sent, and the offline queue of the client already holds as many writes as it can
+    OfflineQueueFull,
 }
 
 impl PartialEq for ClientError {
@@ -62,6 +74,107 @@ impl PartialEq for ClientError {
             Self::KeyGenerationError(_) => {
                 matches!(other, Self::KeyGenerationError(_))
             }
+            Self::ValueStreamError(_) => {
+                matches!(other, Self::ValueStreamError(_))
+            }
+            Self::ServerIdentityChanged { .. } => {
+                matches!(other, Self::ServerIdentityChanged { .. })
+            }
+            Self::OfflineQueueFull => {
+                matches!(other, Self::OfflineQueueFull)
+            }
+        }
+    }
+}
+
+impl Display for ClientError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnableToConnect(err) => write!(f, "unable to connect to the server, check the address and that the server is running: {}", err),
+            Self::PacketSerializationError(err) => write!(f, "unable to serialize the packet: {}", err),
+            Self::SocketWriteError(err) => write!(f, "unable to send to the server, the connection may have been closed: {}", err),
+            Self::SocketReadError(err) => write!(f, "unable to read from the server, the connection may have been closed: {}", err),
+            Self::PacketDeserializationError(err) => write!(f, "unable to deserialize the response, the value stored may not be the expected type: {}", err),
+            Self::DBResponseError(err) => write!(f, "{}", err),
+            Self::BadPacket => write!(f, "the server replied with an unexpected response, the server and client versions may not match"),
+            Self::PacketEncryptionError(err) => write!(f, "unable to encrypt or decrypt the packet: {}", err),
+            Self::EncryptionSetupError => write!(f, "the server did not respond as expected when setting up encryption"),
+            Self::KeyGenerationError(err) => write!(f, "unable to generate a key pair: {}", err),
+            Self::ValueStreamError(err) => write!(f, "unable to read or write the streamed value: {}", err),
+            Self::ServerIdentityChanged { expected, presented } => write!(
+                f,
+                "the server presented a key with fingerprint {}, but {} was expected, the server may have changed its key or may not be the expected server",
+                presented, expected
+            ),
+            Self::OfflineQueueFull => write!(f, "the server could not be reached and the offline queue is full, replay the queue once connected again"),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::UnableToConnect(err)
+            | Self::PacketSerializationError(err)
+            | Self::SocketWriteError(err)
+            | Self::SocketReadError(err)
+            | Self::PacketDeserializationError(err)
+            | Self::ValueStreamError(err) => Some(err),
+            Self::DBResponseError(err) => Some(err),
+            Self::PacketEncryptionError(err) => Some(err),
+            Self::KeyGenerationError(err) => Some(err),
+            Self::BadPacket
+            | Self::EncryptionSetupError
+            | Self::ServerIdentityChanged { .. }
+            | Self::OfflineQueueFull => None,
+        }
+    }
+}
+
+impl ClientError {
+    /// Returns true if the same request could succeed when sent again, possibly after reconnecting,
+    /// such as when the connection failed, or the server was too busy to handle the request.
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// assert!(DBResponseError(RateLimited).is_retryable());
+    /// assert!(!DBResponseError(DBNotFound).is_retryable());
+    /// ```
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::UnableToConnect(_) | Self::SocketWriteError(_) | Self::SocketReadError(_) => true,
+            Self::DBResponseError(err) => err.is_retryable(),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the request failed because the access key lacks the permissions for it
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// assert!(DBResponseError(InvalidPermissions).is_permission_denied());
+    /// assert!(!DBResponseError(ValueNotFound).is_permission_denied());
+    /// ```
+    pub fn is_permission_denied(&self) -> bool {
+        match self {
+            Self::DBResponseError(err) => err.is_permission_denied(),
+            _ => false,
+        }
+    }
+}
+
+/// Converts the result of a request into an `Option`, for requests where a missing value is expected rather than an error.
+pub trait OptionalResponse<T> {
+    /// Returns `None` if the request failed with `ValueNotFound` or succeeded with no data, and the reply otherwise, any other error is still returned.
+    fn optional(self) -> Result<Option<T>, ClientError>;
+}
+
+impl<T> OptionalResponse<T> for Result<DBSuccessResponse<T>, ClientError> {
+    fn optional(self) -> Result<Option<T>, ClientError> {
+        match self {
+            Ok(response) => Ok(response.into()),
+            Err(ClientError::DBResponseError(DBPacketResponseError::ValueNotFound)) => Ok(None),
+            Err(err) => Err(err),
         }
     }
 }
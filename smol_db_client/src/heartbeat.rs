@@ -0,0 +1,85 @@
+//! Contains `Heartbeat`, which pings the server in the background so idle connections are kept open through NATs and firewalls, and dead connections are noticed early.
+use crate::prelude::SmolDbClient;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// `Heartbeat` pings the server on a background thread every interval, using a client shared with the rest of the application.
+/// Requests made through the client while a ping is in flight wait for the ping to finish.
+/// The heartbeat stops when it is dropped.
+/// ```
+/// use smol_db_client::prelude::*;
+/// use std::sync::{Arc, Mutex};
+/// use std::time::Duration;
+///
+/// let client = Arc::new(Mutex::new(SmolDbClient::new("localhost:8222").unwrap()));
+/// let heartbeat = Heartbeat::start(client.clone(), Duration::from_millis(10));
+///
+/// std::thread::sleep(Duration::from_millis(50));
+/// assert!(heartbeat.is_alive());
+///
+/// heartbeat.stop();
+/// ```
+pub struct Heartbeat {
+    /// False once a ping has failed, set back to true when a later ping succeeds
+    alive: Arc<AtomicBool>,
+    /// Dropping the sender wakes the heartbeat thread and stops it
+    stop_sender: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Heartbeat {
+    /// Starts pinging the server through the client every interval.
+    pub fn start(client: Arc<Mutex<SmolDbClient>>, interval: Duration) -> Self {
+        let alive = Arc::new(AtomicBool::new(true));
+        let (stop_sender, stop_receiver) = channel::<()>();
+
+        let thread = {
+            let alive = alive.clone();
+            thread::spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) = stop_receiver.recv_timeout(interval) {
+                    let ping = client.lock().unwrap().ping();
+                    match ping {
+                        Ok(round_trip) => {
+                            debug!("Heartbeat round trip took {:?}", round_trip);
+                            alive.store(true, Ordering::Relaxed);
+                        }
+                        Err(err) => {
+                            warn!("Heartbeat ping failed: {:?}", err);
+                            alive.store(false, Ordering::Relaxed);
+                        }
+                    }
+                }
+            })
+        };
+
+        Self {
+            alive,
+            stop_sender: Some(stop_sender),
+            thread: Some(thread),
+        }
+    }
+
+    /// Returns false if the most recent ping failed, in which case the client likely needs to reconnect.
+    pub fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::Relaxed)
+    }
+
+    /// Stops the heartbeat, waiting for a ping in flight to finish.
+    pub fn stop(self) {
+        drop(self);
+    }
+}
+
+impl Drop for Heartbeat {
+    fn drop(&mut self) {
+        drop(self.stop_sender.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
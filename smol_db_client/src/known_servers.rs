@@ -0,0 +1,94 @@
+//! Contains `KnownServers`, the fingerprints of the servers a client has trusted, saved to a file so they are remembered between runs.
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Fingerprints of the keys of servers that have been trusted on first use, keyed by server address.
+/// Saved as one `address fingerprint` line per server, similar to ssh known hosts.
+/// When a known server presents a different key, `SmolDbClient::setup_encryption` errors with `ServerIdentityChanged`,
+/// after a server rotates its key on purpose, `forget` the server to trust its new key.
+/// ```
+/// # #[cfg(not(feature = "async"))]
+/// # fn main() {
+/// use smol_db_client::prelude::*;
+///
+/// let path = std::env::temp_dir().join("smol_db_doc_known_servers");
+/// let _ = std::fs::remove_file(&path);
+///
+/// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+/// client.set_known_servers(KnownServers::load(&path).unwrap());
+/// client.setup_encryption().unwrap(); // the server is trusted on first use, and saved to the file
+///
+/// let known_servers = KnownServers::load(&path).unwrap();
+/// assert_eq!(
+///     known_servers.get_fingerprint(&client.get_connected_ip().unwrap().to_string()),
+///     client.server_fingerprint()
+/// );
+/// # std::fs::remove_file(&path).unwrap();
+/// # }
+/// # #[cfg(feature = "async")]
+/// # fn main() {}
+/// ```
+pub struct KnownServers {
+    path: PathBuf,
+    servers: BTreeMap<String, String>,
+}
+
+impl KnownServers {
+    /// Loads the known servers saved at the path, a missing file has no known servers.
+    #[tracing::instrument]
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err),
+        };
+        let servers = contents
+            .lines()
+            .filter_map(|line| line.split_once(' '))
+            .map(|(server, fingerprint)| (server.to_string(), fingerprint.trim().to_string()))
+            .collect();
+        Ok(Self {
+            path: path.to_path_buf(),
+            servers,
+        })
+    }
+
+    /// Returns the fingerprint trusted for the server address, if it is known
+    pub fn get_fingerprint(&self, server: &str) -> Option<&str> {
+        self.servers.get(server).map(String::as_str)
+    }
+
+    /// Trusts the fingerprint for the server address, and saves the known servers.
+    #[tracing::instrument(skip(self))]
+    pub fn trust(&mut self, server: &str, fingerprint: &str) -> io::Result<()> {
+        info!(
+            "Trusting server {} with fingerprint {}",
+            server, fingerprint
+        );
+        self.servers
+            .insert(server.to_string(), fingerprint.to_string());
+        self.save()
+    }
+
+    /// Forgets the server address, so the next key it presents is trusted, and saves the known servers.
+    /// Returns true if the server was known.
+    #[tracing::instrument(skip(self))]
+    pub fn forget(&mut self, server: &str) -> io::Result<bool> {
+        let removed = self.servers.remove(server).is_some();
+        self.save()?;
+        Ok(removed)
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let contents: String = self
+            .servers
+            .iter()
+            .map(|(server, fingerprint)| format!("{server} {fingerprint}\n"))
+            .collect();
+        fs::write(&self.path, contents)
+    }
+}
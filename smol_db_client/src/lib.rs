@@ -2,7 +2,22 @@
 
 mod client;
 pub mod client_error;
+#[cfg(not(feature = "async"))]
+mod heartbeat;
+mod known_servers;
+mod local_client;
+#[cfg(feature = "test-utils")]
+mod mock_client;
+pub mod offline_queue;
+pub mod request_hook;
+#[cfg(feature = "cache")]
+mod response_cache;
+mod stream_handle;
 mod table_iter;
+pub mod table_record;
+mod typed_table;
+#[cfg(not(feature = "async"))]
+mod watch_iter;
 pub use smol_db_common::{
     db::Role, db_packets::db_packet_response::DBPacketResponseError,
     db_packets::db_packet_response::DBSuccessResponse, db_packets::db_settings,
@@ -13,15 +28,47 @@ pub mod prelude {
     pub use crate::client::SmolDbClient;
     pub use crate::client_error;
     pub use crate::client_error::ClientError::DBResponseError;
+    pub use crate::client_error::OptionalResponse;
+    #[cfg(not(feature = "async"))]
+    pub use crate::heartbeat::Heartbeat;
+    pub use crate::known_servers::KnownServers;
+    pub use crate::local_client::LocalClient;
+    #[cfg(feature = "test-utils")]
+    pub use crate::mock_client::MockSmolDbClient;
+    pub use crate::offline_queue::OfflineQueue;
+    pub use crate::request_hook::RequestHook;
+    pub use crate::stream_handle::StreamHandle;
     pub use crate::table_iter::TableIter;
+    pub use crate::typed_table::TypedTable;
+    #[cfg(not(feature = "async"))]
+    pub use crate::watch_iter::WatchIter;
     pub use smol_db_common::db::Role;
     pub use smol_db_common::db::Role::*;
+    pub use smol_db_common::db_packets::change_event::ChangeEvent;
+    pub use smol_db_common::db_packets::db_metadata::{DBListing, DBMetadata};
     pub use smol_db_common::db_packets::db_packet_info::DBPacketInfo;
     pub use smol_db_common::db_packets::db_packet_response::DBPacketResponseError::*;
     pub use smol_db_common::db_packets::db_packet_response::DBSuccessResponse;
+    pub use smol_db_common::db_packets::db_packet_response::DBSuccessResponse::SuccessData;
     pub use smol_db_common::db_packets::db_packet_response::DBSuccessResponse::SuccessNoData;
     pub use smol_db_common::db_packets::db_packet_response::DBSuccessResponse::SuccessReply;
     pub use smol_db_common::db_packets::db_settings::DBSettings;
+    pub use smol_db_common::db_packets::db_settings_builder::{DBSettingsBuilder, DBSettingsError};
+    pub use smol_db_common::db_packets::effective_permissions::EffectivePermissions;
+    pub use smol_db_common::db_packets::ip_filter::{IpFilter, IpNet};
+    pub use smol_db_common::db_packets::key_acl::KeyAclRule;
+    pub use smol_db_common::db_packets::key_limits::{KeyCharset, KeyLimits};
+    pub use smol_db_common::db_packets::key_pattern::KeyPattern;
+    pub use smol_db_common::db_packets::namespace::NamespaceSettings;
+    pub use smol_db_common::db_packets::permissions::{Permission, Permissions};
+    pub use smol_db_common::db_packets::response_data::ResponseData;
+    pub use smol_db_common::db_packets::server_info::ServerInfo;
+    pub use smol_db_common::db_packets::storage_quota::StorageQuota;
+    pub use smol_db_common::db_packets::transaction::TransactionBuilder;
+    pub use smol_db_common::db_packets::value_schema::ValueSchema;
+    pub use smol_db_common::revision_history::{Revision, RevisionInfo};
+    pub use smol_db_common::sorted_list::ScoredMember;
     #[cfg(feature = "statistics")]
     pub use smol_db_common::statistics::DBStatistics;
+    pub use smol_db_common::user_registry::UserRegistry;
 }
@@ -0,0 +1,308 @@
+//! Contains `LocalClient`, a client that uses a `DBList` in the same process instead of connecting to a server
+use crate::client_error::ClientError;
+use crate::client_error::ClientError::{BadPacket, PacketDeserializationError};
+use crate::prelude::DBResponseError;
+use serde::de::DeserializeOwned;
+use smol_db_common::db::Role;
+use smol_db_common::encryption::key_hash::generate_token;
+use smol_db_common::prelude::{
+    DBData, DBList, DBListing, DBLocation, DBPacketInfo, DBPacketResponseError, DBSettings,
+    DBSuccessResponse, EffectivePermissions, ResponseData, SuccessData, SuccessNoData,
+    SuccessReply,
+};
+#[cfg(feature = "statistics")]
+use smol_db_common::statistics::DBStatistics;
+use std::collections::HashMap;
+use std::io::Error;
+use std::sync::Arc;
+
+/// A client that reads and writes a `DBList` directly, without any sockets.
+/// Useful for embedding a database in an application, or for unit tests, as every operation goes through the same permission checks a server would use.
+/// ```
+/// use smol_db_client::prelude::LocalClient;
+/// use smol_db_common::db_packets::db_settings::DBSettings;
+///
+/// let mut client = LocalClient::new_in_memory();
+///
+/// let _ = client.set_access_key("test_key_123".to_string());
+/// let _ = client.create_db("local_db", DBSettings::default()).unwrap();
+/// let _ = client.write_db("local_db", "cool_data_location", "cool_data").unwrap();
+///
+/// let read_data = client.read_db("local_db", "cool_data_location").unwrap();
+/// assert_eq!(read_data.as_option().unwrap(), "cool_data");
+/// ```
+#[derive(Debug, Clone)]
+pub struct LocalClient {
+    db_list: Arc<DBList>,
+    client_key: String,
+    /// Bootstrap token of a db list created by this client, claimed by the first access key set
+    bootstrap_token: Option<String>,
+}
+
+impl LocalClient {
+    /// Creates a client that uses the given db list, which can be shared with an embedded server or other clients.
+    pub const fn new(db_list: Arc<DBList>) -> Self {
+        Self {
+            db_list,
+            client_key: String::new(),
+            bootstrap_token: None,
+        }
+    }
+
+    /// Creates a client with its own empty db list, which is only kept in memory and never saved.
+    /// The first access key set becomes a super admin, as no other client can use the db list before it is shared.
+    pub fn new_in_memory() -> Self {
+        let db_list = DBList {
+            saving_enabled: false,
+            ..Default::default()
+        };
+        let bootstrap_token = generate_token();
+        db_list.set_bootstrap_token(&bootstrap_token);
+        Self {
+            bootstrap_token: Some(bootstrap_token),
+            ..Self::new(Arc::new(db_list))
+        }
+    }
+
+    /// Returns the db list this client uses.
+    pub fn get_db_list(&self) -> Arc<DBList> {
+        self.db_list.clone()
+    }
+
+    /// Returns the db list this client uses, without cloning it.
+    #[cfg(feature = "test-utils")]
+    pub(crate) const fn db_list(&self) -> &Arc<DBList> {
+        &self.db_list
+    }
+
+    /// Returns the hash of the access key this client uses.
+    #[cfg(feature = "test-utils")]
+    pub(crate) const fn client_key(&self) -> &String {
+        &self.client_key
+    }
+
+    /// Sets the access key this client uses for permissions.
+    /// The first key set on a client created with `new_in_memory` becomes a super admin, otherwise the key only has the roles it was given.
+    #[tracing::instrument]
+    pub fn set_access_key(&mut self, key: String) -> DBSuccessResponse<String> {
+        let key_hash = self.db_list.key_hasher().hash_key(&key);
+        if let Some(token) = self.bootstrap_token.take() {
+            // fails once the token was claimed by a clone of this client
+            let _ = self.db_list.claim_super_admin(&token, &key_hash);
+        }
+        self.client_key = key_hash;
+        SuccessNoData
+    }
+
+    /// Creates a db with the given name.
+    /// Requires super admin privileges
+    #[tracing::instrument]
+    pub fn create_db(
+        &mut self,
+        db_name: &str,
+        mut db_settings: DBSettings,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        self.db_list
+            .hash_settings_keys(&DBPacketInfo::new(db_name), &mut db_settings);
+        let resp = self
+            .db_list
+            .create_db(db_name, db_settings, &self.client_key);
+        if resp.is_ok() {
+            self.db_list.save_db_list();
+        }
+        resp.map_err(DBResponseError)
+    }
+
+    /// Deletes the given db by name.
+    /// Requires super admin privileges
+    #[tracing::instrument]
+    pub fn delete_db(&mut self, db_name: &str) -> Result<DBSuccessResponse<String>, ClientError> {
+        let resp = self.db_list.delete_db(db_name, &self.client_key);
+        if resp.is_ok() {
+            self.db_list.save_db_list();
+        }
+        resp.map_err(DBResponseError)
+    }
+
+    /// Writes the data to the location in the given db, returning the data that was overwritten if there was any.
+    /// Requires permissions to write to the given DB
+    #[tracing::instrument]
+    pub fn write_db(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+        data: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let db_info = DBPacketInfo::new(db_name);
+        let resp = self.db_list.write_db(
+            &db_info,
+            &DBLocation::new(db_location),
+            &DBData::new(data.to_string()),
+            &self.client_key,
+        );
+        if resp.is_ok() {
+            self.db_list.save_specific_db(&db_info);
+        }
+        resp.map_err(DBResponseError)
+    }
+
+    /// Reads the data at the location in the given db.
+    /// Returns an error if there is no data in the location.
+    /// Requires permissions to read from the given DB
+    #[tracing::instrument]
+    pub fn read_db(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        self.db_list
+            .read_db(
+                &DBPacketInfo::new(db_name),
+                &DBLocation::new(db_location),
+                &self.client_key,
+            )
+            .map_err(DBResponseError)
+    }
+
+    /// Deletes the data at the location in the given db.
+    /// Requires permissions to write to the given DB
+    #[tracing::instrument]
+    pub fn delete_data(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let db_info = DBPacketInfo::new(db_name);
+        let resp =
+            self.db_list
+                .delete_data(&db_info, &DBLocation::new(db_location), &self.client_key);
+        if resp.is_ok() {
+            self.db_list.save_specific_db(&db_info);
+        }
+        resp.map_err(DBResponseError)
+    }
+
+    /// Lists every db by name.
+    #[tracing::instrument]
+    pub fn list_db(&mut self) -> Result<Vec<DBPacketInfo>, ClientError> {
+        match data_reply(self.db_list.list_db())? {
+            ResponseData::DBList(listings) => {
+                Ok(listings.into_iter().map(|listing| listing.info).collect())
+            }
+            _ => Err(BadPacket),
+        }
+    }
+
+    /// Lists the dbs the key of this client has read or list permissions on, each with its metadata.
+    #[tracing::instrument]
+    pub fn list_accessible_db(&mut self) -> Result<Vec<DBListing>, ClientError> {
+        match data_reply(self.db_list.list_accessible_db(&self.client_key))? {
+            ResponseData::DBList(listings) => Ok(listings),
+            _ => Err(BadPacket),
+        }
+    }
+
+    /// Get the hashmap of the contents of a database.
+    /// Requires list permissions on the given DB
+    #[tracing::instrument]
+    pub fn list_db_contents(
+        &mut self,
+        db_name: &str,
+    ) -> Result<HashMap<String, String>, ClientError> {
+        deserialize_reply(
+            self.db_list
+                .list_db_contents(&DBPacketInfo::new(db_name), &self.client_key),
+        )
+    }
+
+    /// Gets the `DBSettings` of the given DB.
+    /// Requires super admin privileges
+    #[tracing::instrument]
+    pub fn get_db_settings(&mut self, db_name: &str) -> Result<DBSettings, ClientError> {
+        let resp = self
+            .db_list
+            .get_db_settings(&DBPacketInfo::new(db_name), &self.client_key);
+        match data_reply(resp)? {
+            ResponseData::Settings(data) => Ok(*data),
+            _ => Err(BadPacket),
+        }
+    }
+
+    /// Sets the `DBSettings` of the given DB.
+    /// Requires super admin privileges
+    #[tracing::instrument]
+    pub fn set_db_settings(
+        &mut self,
+        db_name: &str,
+        mut db_settings: DBSettings,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let db_info = DBPacketInfo::new(db_name);
+        self.db_list.hash_settings_keys(&db_info, &mut db_settings);
+        let resp = self
+            .db_list
+            .change_db_settings(&db_info, db_settings, &self.client_key);
+        if resp.is_ok() {
+            self.db_list.save_specific_db(&db_info);
+        }
+        resp.map_err(DBResponseError)
+    }
+
+    /// Returns the role of this client in the given db.
+    #[tracing::instrument]
+    pub fn get_role(&mut self, db_name: &str) -> Result<Role, ClientError> {
+        let resp = self
+            .db_list
+            .get_role(&DBPacketInfo::new(db_name), &self.client_key);
+        match data_reply(resp)? {
+            ResponseData::Role(data) => Ok(data),
+            _ => Err(BadPacket),
+        }
+    }
+
+    /// Returns the role and permissions of this client in the given db, along with the key acl rules that limit it.
+    #[tracing::instrument]
+    pub fn get_effective_permissions(
+        &mut self,
+        db_name: &str,
+    ) -> Result<EffectivePermissions, ClientError> {
+        let resp = self
+            .db_list
+            .get_effective_permissions(&DBPacketInfo::new(db_name), &self.client_key);
+        match data_reply(resp)? {
+            ResponseData::EffectivePermissions(permissions) => Ok(permissions),
+            _ => Err(BadPacket),
+        }
+    }
+
+    /// Returns the `DBStatistics` struct if permissions allow it on a given db
+    #[cfg(feature = "statistics")]
+    #[tracing::instrument]
+    pub fn get_stats(&mut self, db_name: &str) -> Result<DBStatistics, ClientError> {
+        let resp = self
+            .db_list
+            .get_stats(&DBPacketInfo::new(db_name), &self.client_key);
+        match data_reply(resp)? {
+            ResponseData::Stats(data) => Ok(data),
+            _ => Err(BadPacket),
+        }
+    }
+}
+
+/// Deserializes the data a `DBList` replied with, the same way `SmolDbClient` deserializes a response from a server.
+pub(crate) fn deserialize_reply<T: DeserializeOwned>(
+    resp: Result<DBSuccessResponse<String>, DBPacketResponseError>,
+) -> Result<T, ClientError> {
+    match resp.map_err(DBResponseError)? {
+        SuccessNoData | SuccessData(_) => Err(BadPacket),
+        SuccessReply(data) => {
+            serde_json::from_str(&data).map_err(|err| PacketDeserializationError(Error::from(err)))
+        }
+    }
+}
+
+/// Returns the typed data a `DBList` replied with, for the requests that reply with `SuccessData`.
+fn data_reply(
+    resp: Result<DBSuccessResponse<String>, DBPacketResponseError>,
+) -> Result<ResponseData, ClientError> {
+    resp.map_err(DBResponseError)?.into_data().ok_or(BadPacket)
+}
@@ -0,0 +1,948 @@
+//! Contains `MockSmolDbClient`, a stand in for `SmolDbClient` that keeps its databases in memory, for unit testing code that uses a client
+use crate::client_error::ClientError;
+use crate::client_error::ClientError::{
+    BadPacket, PacketDeserializationError, PacketSerializationError,
+};
+use crate::client_error::OptionalResponse;
+use crate::local_client::{deserialize_reply, LocalClient};
+use crate::prelude::DBResponseError;
+use serde::{Deserialize, Serialize};
+use smol_db_common::db::Role;
+use smol_db_common::db_content::DBContent;
+use smol_db_common::prelude::{
+    DBBytes, DBData, DBList, DBListing, DBLocation, DBPacketInfo, DBPacketResponseError,
+    DBSettings, DBSuccessResponse, EffectivePermissions, ScoredMember, SuccessData, SuccessNoData,
+    SuccessReply,
+};
+use smol_db_common::queue_waiters::QueueWaker;
+#[cfg(feature = "statistics")]
+use smol_db_common::statistics::DBStatistics;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Error, ErrorKind};
+use std::sync::mpsc::channel;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use std::vec;
+
+/// A client with the same methods as `SmolDbClient` for reading and writing databases, which uses an in memory `DBList` instead of connecting to a server.
+/// It covers the key value, list, set, sorted list, queue, binary value and table stream requests, along with creating databases and reading their settings and roles.
+/// Requests tied to a connection or to the server itself, such as watching, sessions, accounts, replication, backups and encryption, are left out.
+/// Every request goes through the same permission checks a server would use, and fails with the same errors,
+/// so code written against `SmolDbClient` can be unit tested without a server running.
+/// The methods block like those of `SmolDbClient` without the `async` feature.
+/// Only available with the `test-utils` feature.
+/// ```
+/// use smol_db_client::prelude::MockSmolDbClient;
+/// use smol_db_common::db_packets::db_settings::DBSettings;
+///
+/// let mut client = MockSmolDbClient::new();
+///
+/// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+/// let _ = client.create_db("mock_db", DBSettings::default()).unwrap();
+/// let _ = client.write_db("mock_db", "cool_data_location", "cool_data").unwrap();
+///
+/// let read_data = client.read_db("mock_db", "cool_data_location").unwrap();
+/// assert_eq!(read_data.as_option().unwrap(), "cool_data");
+/// assert_eq!(client.increment("mock_db", "counter", 5).unwrap(), 5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockSmolDbClient {
+    client: LocalClient,
+}
+
+impl MockSmolDbClient {
+    /// Creates a mock client with its own empty db list, as if connected to a server with no databases.
+    /// The first access key set becomes a super admin.
+    pub fn new() -> Self {
+        Self {
+            client: LocalClient::new_in_memory(),
+        }
+    }
+
+    /// Creates a mock client using the given db list, such as one shared with another mock client to test several clients at once.
+    pub const fn with_db_list(db_list: Arc<DBList>) -> Self {
+        Self {
+            client: LocalClient::new(db_list),
+        }
+    }
+
+    /// Returns the db list this mock client uses, to set up or inspect its databases directly.
+    pub fn get_db_list(&self) -> Arc<DBList> {
+        self.client.get_db_list()
+    }
+
+    /// Sets the access key this mock client uses for permissions.
+    /// The first key set on a mock client created with `new` becomes a super admin, otherwise the key only has the roles it was given.
+    pub fn set_access_key(
+        &mut self,
+        key: String,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        Ok(self.client.set_access_key(key))
+    }
+
+    /// Creates a db with the given name.
+    /// Requires super admin privileges
+    pub fn create_db(
+        &mut self,
+        db_name: &str,
+        db_settings: DBSettings,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        self.client.create_db(db_name, db_settings)
+    }
+
+    /// Deletes the given db by name.
+    /// Requires super admin privileges
+    pub fn delete_db(&mut self, db_name: &str) -> Result<DBSuccessResponse<String>, ClientError> {
+        self.client.delete_db(db_name)
+    }
+
+    /// Returns true if a db with the given name exists.
+    pub fn db_exists(&mut self, db_name: &str) -> Result<bool, ClientError> {
+        let resp = self.db_list().db_exists(&DBPacketInfo::new(db_name));
+        deserialize_reply(resp)
+    }
+
+    /// Returns true if the location in the given db has a value.
+    /// Requires read permissions on the given DB
+    pub fn key_exists(&mut self, db_name: &str, db_location: &str) -> Result<bool, ClientError> {
+        let resp = self.db_list().key_exists(
+            &DBPacketInfo::new(db_name),
+            &DBLocation::new(db_location),
+            self.client.client_key(),
+        );
+        deserialize_reply(resp)
+    }
+
+    /// Returns the number of key value pairs in the given db.
+    /// Requires read permissions on the given DB
+    pub fn get_db_len(&mut self, db_name: &str) -> Result<usize, ClientError> {
+        let resp = self
+            .db_list()
+            .get_db_len(&DBPacketInfo::new(db_name), self.client.client_key());
+        deserialize_reply(resp)
+    }
+
+    /// Writes the data to the location in the given db, returning the data that was overwritten if there was any.
+    /// Requires permissions to write to the given DB
+    pub fn write_db(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+        data: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        self.client.write_db(db_name, db_location, data)
+    }
+
+    /// Writes the data to the location only if it has no value, failing with `ValueAlreadyExists` otherwise.
+    /// Requires permissions to write to the given DB
+    pub fn write_db_if_absent(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+        data: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let db_info = DBPacketInfo::new(db_name);
+        self.saved(&db_info, |db_list, client_key| {
+            db_list.write_db_if_absent(
+                &db_info,
+                &DBLocation::new(db_location),
+                &DBData::new(data.to_string()),
+                client_key,
+            )
+        })
+        .map_err(DBResponseError)
+    }
+
+    /// Writes the data to the location only if its current value is `expected`, `None` meaning it has no value.
+    /// Requires permissions to write to the given DB
+    pub fn compare_and_swap(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+        expected: Option<&str>,
+        data: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let db_info = DBPacketInfo::new(db_name);
+        let expected = expected.map(|expected| DBData::new(expected.to_string()));
+        self.saved(&db_info, |db_list, client_key| {
+            db_list.compare_and_swap(
+                &db_info,
+                &DBLocation::new(db_location),
+                expected.as_ref(),
+                &DBData::new(data.to_string()),
+                client_key,
+            )
+        })
+        .map_err(DBResponseError)
+    }
+
+    /// Adds the delta to the integer stored at the location, returning the new value. A location with no value is treated as 0.
+    /// Requires permissions to write to the given DB
+    pub fn increment(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+        delta: i64,
+    ) -> Result<i64, ClientError> {
+        let db_info = DBPacketInfo::new(db_name);
+        deserialize_reply(self.saved(&db_info, |db_list, client_key| {
+            db_list.increment(&db_info, &DBLocation::new(db_location), delta, client_key)
+        }))
+    }
+
+    /// Subtracts the delta from the integer stored at the location, returning the new value.
+    /// Requires permissions to write to the given DB
+    pub fn decrement(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+        delta: i64,
+    ) -> Result<i64, ClientError> {
+        self.increment(db_name, db_location, delta.wrapping_neg())
+    }
+
+    /// Reads the data at the location in the given db.
+    /// Returns an error if there is no data in the location.
+    /// Requires permissions to read from the given DB
+    pub fn read_db(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        self.client.read_db(db_name, db_location)
+    }
+
+    /// Deletes the data at the location in the given db.
+    /// Requires permissions to write to the given DB
+    pub fn delete_data(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        self.client.delete_data(db_name, db_location)
+    }
+
+    /// Removes all content from the given db while keeping its settings.
+    /// Requires admin permissions on the given DB
+    pub fn clear_db(&mut self, db_name: &str) -> Result<DBSuccessResponse<String>, ClientError> {
+        let db_info = DBPacketInfo::new(db_name);
+        self.saved(&db_info, |db_list, client_key| {
+            db_list.clear_db(&db_info, client_key)
+        })
+        .map_err(DBResponseError)
+    }
+
+    /// Writes every location and value pair to the db, returning the previous value at each location in the same order.
+    /// Requires permissions to write to the given DB
+    pub fn write_many(
+        &mut self,
+        db_name: &str,
+        writes: &[(&str, &str)],
+    ) -> Result<Vec<Option<String>>, ClientError> {
+        let db_info = DBPacketInfo::new(db_name);
+        let writes = writes
+            .iter()
+            .map(|(location, data)| (DBLocation::new(location), DBData::new(data.to_string())))
+            .collect::<Vec<_>>();
+        deserialize_reply(self.saved(&db_info, |db_list, client_key| {
+            db_list.write_many(&db_info, &writes, client_key)
+        }))
+    }
+
+    /// Reads every location in the db, returning the value at each location in the same order, `None` if it has no value.
+    /// Requires permissions to read from the given DB
+    pub fn read_many(
+        &mut self,
+        db_name: &str,
+        db_locations: &[&str],
+    ) -> Result<Vec<Option<String>>, ClientError> {
+        let db_locations = db_locations
+            .iter()
+            .map(|location| DBLocation::new(location))
+            .collect::<Vec<_>>();
+        let resp = self.db_list().read_many(
+            &DBPacketInfo::new(db_name),
+            &db_locations,
+            self.client.client_key(),
+        );
+        deserialize_reply(resp)
+    }
+
+    /// Lists every db by name.
+    pub fn list_db(&mut self) -> Result<Vec<DBPacketInfo>, ClientError> {
+        self.client.list_db()
+    }
+
+    /// Lists the dbs the key of this client has read or list permissions on, each with its metadata.
+    pub fn list_accessible_db(&mut self) -> Result<Vec<DBListing>, ClientError> {
+        self.client.list_accessible_db()
+    }
+
+    /// Get the hashmap of the contents of a database.
+    /// Requires list permissions on the given DB
+    pub fn list_db_contents(
+        &mut self,
+        db_name: &str,
+    ) -> Result<HashMap<String, String>, ClientError> {
+        self.client.list_db_contents(db_name)
+    }
+
+    /// Lists every key in the given db.
+    /// Requires list permissions on the given DB
+    pub fn list_db_keys(&mut self, db_name: &str) -> Result<Vec<String>, ClientError> {
+        let resp = self
+            .db_list()
+            .list_db_keys(&DBPacketInfo::new(db_name), self.client.client_key());
+        deserialize_reply(resp)
+    }
+
+    /// Lists the names of the keyed lists in the given db, ordered by name.
+    /// Requires list permissions on the given DB
+    pub fn list_db_lists(&mut self, db_name: &str) -> Result<Vec<String>, ClientError> {
+        let resp = self
+            .db_list()
+            .list_db_lists(&DBPacketInfo::new(db_name), self.client.client_key());
+        deserialize_reply(resp)
+    }
+
+    /// Reads every key value pair in a database whose key starts with the given prefix, ordered by key.
+    /// Requires read permissions on the given DB
+    pub fn read_prefix(
+        &mut self,
+        db_name: &str,
+        prefix: &str,
+    ) -> Result<BTreeMap<String, String>, ClientError> {
+        let resp = self.db_list().read_prefix(
+            &DBPacketInfo::new(db_name),
+            prefix,
+            self.client.client_key(),
+        );
+        deserialize_reply(resp)
+    }
+
+    /// Gets the `DBSettings` of the given DB.
+    /// Requires super admin privileges
+    pub fn get_db_settings(&mut self, db_name: &str) -> Result<DBSettings, ClientError> {
+        self.client.get_db_settings(db_name)
+    }
+
+    /// Sets the `DBSettings` of the given DB.
+    /// Requires super admin privileges
+    pub fn set_db_settings(
+        &mut self,
+        db_name: &str,
+        db_settings: DBSettings,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        self.client.set_db_settings(db_name, db_settings)
+    }
+
+    /// Returns the role of this client in the given db.
+    pub fn get_role(&mut self, db_name: &str) -> Result<Role, ClientError> {
+        self.client.get_role(db_name)
+    }
+
+    /// Returns the role and permissions of this client in the given db, along with the key acl rules that limit it.
+    pub fn get_effective_permissions(
+        &mut self,
+        db_name: &str,
+    ) -> Result<EffectivePermissions, ClientError> {
+        self.client.get_effective_permissions(db_name)
+    }
+
+    /// Returns the `DBStatistics` struct if permissions allow it on a given db
+    #[cfg(feature = "statistics")]
+    pub fn get_stats(&mut self, db_name: &str) -> Result<DBStatistics, ClientError> {
+        self.client.get_stats(db_name)
+    }
+
+    /// Lists the given db's contents, deserializing the contents into a hash map.
+    pub fn list_db_contents_generic<T>(
+        &mut self,
+        db_name: &str,
+    ) -> Result<HashMap<String, T>, ClientError>
+    where
+        for<'a> T: Serialize + Deserialize<'a>,
+    {
+        self.list_db_contents(db_name)?
+            .into_iter()
+            .map(|(key, value)| {
+                serde_json::from_str::<T>(&value)
+                    .map(|value| (key, value))
+                    .map_err(|err| PacketDeserializationError(Error::from(err)))
+            })
+            .collect()
+    }
+
+    /// Writes to the db while serializing the given data, returning the data at the location given and deserialized to the same type.
+    pub fn write_db_generic<T>(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+        data: T,
+    ) -> Result<DBSuccessResponse<T>, ClientError>
+    where
+        for<'a> T: Serialize + Deserialize<'a>,
+    {
+        let ser_data = serde_json::to_string(&data)
+            .map_err(|err| PacketSerializationError(Error::from(err)))?;
+        deserialize_response(self.write_db(db_name, db_location, &ser_data)?)
+    }
+
+    /// Reads from db and tries to deserialize the content at the location to the given generic
+    pub fn read_db_generic<T>(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+    ) -> Result<DBSuccessResponse<T>, ClientError>
+    where
+        for<'a> T: Serialize + Deserialize<'a>,
+    {
+        deserialize_response(self.read_db(db_name, db_location)?)
+    }
+
+    /// Reads the key and deserializes it to `T`, returning `None` if the key has no value.
+    /// Requires permissions to read from the given DB
+    pub fn get<T>(&mut self, db_name: &str, key: &str) -> Result<Option<T>, ClientError>
+    where
+        for<'a> T: Serialize + Deserialize<'a>,
+    {
+        self.read_db_generic::<T>(db_name, key).optional()
+    }
+
+    /// Writes the value serialized to the key, returning the value it replaced deserialized to `T`, or `None` if the key had no value.
+    /// Requires permissions to write to the given DB
+    pub fn set<T>(&mut self, db_name: &str, key: &str, value: &T) -> Result<Option<T>, ClientError>
+    where
+        for<'a> T: Serialize + Deserialize<'a>,
+    {
+        let ser_data = serde_json::to_string(value)
+            .map_err(|err| PacketSerializationError(Error::from(err)))?;
+        match self.write_db(db_name, key, &ser_data)? {
+            SuccessNoData => Ok(None),
+            SuccessData(_) => Err(BadPacket),
+            SuccessReply(previous) => serde_json::from_str::<T>(&previous)
+                .map(Some)
+                .map_err(|err| PacketDeserializationError(Error::from(err))),
+        }
+    }
+
+    /// Deletes the key, returning the value it had, or `None` if the key had no value.
+    /// Requires permissions to write to the given DB
+    pub fn remove(&mut self, db_name: &str, key: &str) -> Result<Option<String>, ClientError> {
+        self.delete_data(db_name, key).optional()
+    }
+
+    /// Pushes the value onto the end of the list in the given db, creating the list if it does not exist.
+    /// Returns the new length of the list.
+    /// Requires permissions to write to the given DB
+    pub fn list_push(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        value: &str,
+    ) -> Result<usize, ClientError> {
+        self.add_to_list(db_name, list_name, value, None)
+    }
+
+    /// Inserts the value into the list in the given db at the index, shifting every value after it back by one.
+    /// The index may be the length of the list, which pushes the value onto the end. Returns the new length of the list.
+    /// Requires permissions to write to the given DB
+    pub fn list_insert(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        index: usize,
+        value: &str,
+    ) -> Result<usize, ClientError> {
+        self.add_to_list(db_name, list_name, value, Some(index))
+    }
+
+    /// Reads the value at the index of the list in the given db.
+    /// Returns an error if the list does not exist, or the index is past the end of the list.
+    /// Requires permissions to read from the given DB
+    pub fn list_get(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        index: usize,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        self.db_list()
+            .read_from_list(
+                &DBPacketInfo::new(db_name),
+                &DBLocation::new(list_name),
+                index,
+                self.client.client_key(),
+            )
+            .map_err(DBResponseError)
+    }
+
+    /// Removes the value at the index of the list in the given db, returning the removed value.
+    /// Returns an error if the list does not exist, or the index is past the end of the list.
+    /// Requires permissions to write to the given DB
+    pub fn list_remove(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        index: usize,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let db_info = DBPacketInfo::new(db_name);
+        self.saved(&db_info, |db_list, client_key| {
+            db_list.remove_from_list(&db_info, &DBLocation::new(list_name), index, client_key)
+        })
+        .map_err(DBResponseError)
+    }
+
+    /// Removes every value from the list in the given db.
+    /// Requires permissions to write to the given DB
+    pub fn list_clear(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        let db_info = DBPacketInfo::new(db_name);
+        self.saved(&db_info, |db_list, client_key| {
+            db_list.clear_list(&db_info, &DBLocation::new(list_name), client_key)
+        })
+        .map_err(DBResponseError)
+    }
+
+    /// Returns the number of values in the list in the given db, a list that does not exist has no values.
+    /// Requires permissions to read from the given DB
+    pub fn list_len(&mut self, db_name: &str, list_name: &str) -> Result<usize, ClientError> {
+        let resp = self.db_list().get_list_length(
+            &DBPacketInfo::new(db_name),
+            &DBLocation::new(list_name),
+            self.client.client_key(),
+        );
+        deserialize_reply(resp)
+    }
+
+    /// Returns every value of the list in the given db in order, copied when called so changes made to the list afterward are not seen.
+    /// Requires read and stream permissions on the given DB
+    pub fn stream_list(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+    ) -> Result<impl Iterator<Item = String>, ClientError> {
+        self.db_list()
+            .stream_list(
+                &DBPacketInfo::new(db_name),
+                &DBLocation::new(list_name),
+                self.client.client_key(),
+            )
+            .map(IntoIterator::into_iter)
+            .map_err(DBResponseError)
+    }
+
+    /// Adds the member to the set in the given db, creating the set if it does not exist.
+    /// Returns true if the member was not already in the set.
+    /// Requires permissions to write to the given DB
+    pub fn set_add(
+        &mut self,
+        db_name: &str,
+        set_name: &str,
+        member: &str,
+    ) -> Result<bool, ClientError> {
+        let db_info = DBPacketInfo::new(db_name);
+        deserialize_reply(self.saved(&db_info, |db_list, client_key| {
+            db_list.set_add(
+                &db_info,
+                &DBLocation::new(set_name),
+                &DBData::new(member.to_string()),
+                client_key,
+            )
+        }))
+    }
+
+    /// Removes the member from the set in the given db.
+    /// Returns true if the member was in the set.
+    /// Requires permissions to write to the given DB
+    pub fn set_remove(
+        &mut self,
+        db_name: &str,
+        set_name: &str,
+        member: &str,
+    ) -> Result<bool, ClientError> {
+        let db_info = DBPacketInfo::new(db_name);
+        deserialize_reply(self.saved(&db_info, |db_list, client_key| {
+            db_list.set_remove(
+                &db_info,
+                &DBLocation::new(set_name),
+                &DBData::new(member.to_string()),
+                client_key,
+            )
+        }))
+    }
+
+    /// Returns true if the member is in the set in the given db, a set that does not exist has no members.
+    /// Requires permissions to read from the given DB
+    pub fn set_contains(
+        &mut self,
+        db_name: &str,
+        set_name: &str,
+        member: &str,
+    ) -> Result<bool, ClientError> {
+        let resp = self.db_list().set_contains(
+            &DBPacketInfo::new(db_name),
+            &DBLocation::new(set_name),
+            &DBData::new(member.to_string()),
+            self.client.client_key(),
+        );
+        deserialize_reply(resp)
+    }
+
+    /// Returns every member of the set in the given db, in order.
+    /// Requires permissions to read from the given DB
+    pub fn set_members(
+        &mut self,
+        db_name: &str,
+        set_name: &str,
+    ) -> Result<Vec<String>, ClientError> {
+        let resp = self.db_list().set_members(
+            &DBPacketInfo::new(db_name),
+            &DBLocation::new(set_name),
+            self.client.client_key(),
+        );
+        deserialize_reply(resp)
+    }
+
+    /// Adds the member to the sorted list in the given db with the given score, replacing its score if it was already a member.
+    /// Returns the rank of the member, which is its index in ascending order of score.
+    /// Requires permissions to write to the given DB
+    pub fn sorted_add(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        member: &str,
+        score: f64,
+    ) -> Result<usize, ClientError> {
+        let db_info = DBPacketInfo::new(db_name);
+        deserialize_reply(self.saved(&db_info, |db_list, client_key| {
+            db_list.sorted_add(
+                &db_info,
+                &DBLocation::new(list_name),
+                &DBData::new(member.to_string()),
+                score,
+                client_key,
+            )
+        }))
+    }
+
+    /// Removes the member from the sorted list in the given db.
+    /// Returns true if the member was in the sorted list.
+    /// Requires permissions to write to the given DB
+    pub fn sorted_remove(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        member: &str,
+    ) -> Result<bool, ClientError> {
+        let db_info = DBPacketInfo::new(db_name);
+        deserialize_reply(self.saved(&db_info, |db_list, client_key| {
+            db_list.sorted_remove(
+                &db_info,
+                &DBLocation::new(list_name),
+                &DBData::new(member.to_string()),
+                client_key,
+            )
+        }))
+    }
+
+    /// Returns the rank of the member in the sorted list in the given db, which is its index in ascending order of score.
+    /// Returns `None` if the member is not in the sorted list.
+    /// Requires permissions to read from the given DB
+    pub fn sorted_rank(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        member: &str,
+    ) -> Result<Option<usize>, ClientError> {
+        let resp = self.db_list().sorted_rank(
+            &DBPacketInfo::new(db_name),
+            &DBLocation::new(list_name),
+            &DBData::new(member.to_string()),
+            self.client.client_key(),
+        );
+        deserialize_reply(resp)
+    }
+
+    /// Returns every member of the sorted list in the given db with a score between min and max inclusive, in ascending order of score.
+    /// Requires permissions to read from the given DB
+    pub fn sorted_range_by_score(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        min: f64,
+        max: f64,
+    ) -> Result<Vec<ScoredMember>, ClientError> {
+        let resp = self.db_list().sorted_range_by_score(
+            &DBPacketInfo::new(db_name),
+            &DBLocation::new(list_name),
+            min,
+            max,
+            self.client.client_key(),
+        );
+        deserialize_reply(resp)
+    }
+
+    /// Returns up to count of the highest scoring members of the sorted list in the given db, in descending order of score.
+    /// Requires permissions to read from the given DB
+    pub fn sorted_top(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        count: usize,
+    ) -> Result<Vec<ScoredMember>, ClientError> {
+        let resp = self.db_list().sorted_top(
+            &DBPacketInfo::new(db_name),
+            &DBLocation::new(list_name),
+            count,
+            self.client.client_key(),
+        );
+        deserialize_reply(resp)
+    }
+
+    /// Pushes the value onto the back of the queue in the given db, creating the queue if it does not exist.
+    /// Returns the new length of the queue. Queues are keyed lists, so the list methods can also be used on them.
+    /// Requires permissions to write to the given DB
+    pub fn queue_push(
+        &mut self,
+        db_name: &str,
+        queue_name: &str,
+        value: &str,
+    ) -> Result<usize, ClientError> {
+        self.add_to_list(db_name, queue_name, value, None)
+    }
+
+    /// Removes and returns the value at the front of the queue in the given db, returning `None` if the queue is empty.
+    /// Requires permissions to write to the given DB
+    pub fn queue_pop(
+        &mut self,
+        db_name: &str,
+        queue_name: &str,
+    ) -> Result<Option<String>, ClientError> {
+        let db_info = DBPacketInfo::new(db_name);
+        self.saved(&db_info, |db_list, client_key| {
+            db_list.queue_pop(&db_info, &DBLocation::new(queue_name), client_key)
+        })
+        .map(DBSuccessResponse::into_option)
+        .map_err(DBResponseError)
+    }
+
+    /// Removes and returns the value at the front of the queue in the given db, waiting up to the timeout for a value to be pushed if the queue is empty.
+    /// Returns `None` if the queue was still empty once the timeout passed.
+    /// Values pushed by another mock client using the same db list, such as one on another thread, wake this client as they would on a server.
+    /// Requires permissions to write to the given DB
+    pub fn queue_pop_blocking(
+        &mut self,
+        db_name: &str,
+        queue_name: &str,
+        timeout: Duration,
+    ) -> Result<Option<String>, ClientError> {
+        let deadline = Instant::now() + timeout;
+        let db_info = DBPacketInfo::new(db_name);
+        let (sender, pushed) = channel();
+        let waker: Arc<QueueWaker> = Arc::new(move || {
+            let _ = sender.send(());
+        });
+
+        loop {
+            // waiting starts before popping, so a value pushed after finding the queue empty still wakes this client
+            self.db_list()
+                .queue_waiters
+                .wait_for_push(&db_info, queue_name, &waker);
+
+            let popped = self.queue_pop(db_name, queue_name)?;
+            if popped.is_some() {
+                return Ok(popped);
+            }
+
+            if pushed
+                .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                .is_err()
+            {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Writes the binary data to the location in the given db, returning the binary data previously at the location if there was any.
+    /// Binary values are stored separately from string values, so they are read using `read_db_bytes`.
+    /// Requires permissions to write to the given DB
+    pub fn write_db_bytes(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+        data: &[u8],
+    ) -> Result<Option<Vec<u8>>, ClientError> {
+        let db_info = DBPacketInfo::new(db_name);
+        let resp = self.saved(&db_info, |db_list, client_key| {
+            db_list.write_db_bytes(
+                &db_info,
+                &DBLocation::new(db_location),
+                &DBBytes::new(data.to_vec()),
+                client_key,
+            )
+        });
+        match resp.map_err(DBResponseError)? {
+            SuccessNoData => Ok(None),
+            SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => bytes_from_base64(&data).map(Some),
+        }
+    }
+
+    /// Reads the binary data at the location in the given db.
+    /// Returns an error if there is no binary data in the location.
+    /// Requires permissions to read from the given DB
+    pub fn read_db_bytes(
+        &mut self,
+        db_name: &str,
+        db_location: &str,
+    ) -> Result<Vec<u8>, ClientError> {
+        let resp = self.db_list().read_db_bytes(
+            &DBPacketInfo::new(db_name),
+            &DBLocation::new(db_location),
+            self.client.client_key(),
+        );
+        match resp.map_err(DBResponseError)? {
+            SuccessNoData | SuccessData(_) => Err(BadPacket),
+            SuccessReply(data) => bytes_from_base64(&data),
+        }
+    }
+
+    /// Streams every key and value of the given db, one pair for each call to `next`.
+    /// The db is copied when called, so changes made to it afterward are not seen, as with a stream from a server.
+    /// Requires read and stream permissions on the given db
+    pub fn stream_table(
+        &mut self,
+        table_name: &str,
+    ) -> Result<impl Iterator<Item = Result<(String, String), ClientError>>, ClientError> {
+        let table = self
+            .db_list()
+            .get_stream_table(&DBPacketInfo::new(table_name), self.client.client_key())
+            .map_err(DBResponseError)?;
+        Ok(stream_pairs(&table))
+    }
+
+    /// Streams the key value pairs of the given db whose key starts with `key_prefix` and whose value contains `value_contains`,
+    /// an empty prefix or text matches every pair.
+    /// Requires read and stream permissions on the given db
+    pub fn stream_table_filtered(
+        &mut self,
+        table_name: &str,
+        key_prefix: &str,
+        value_contains: &str,
+    ) -> Result<impl Iterator<Item = Result<(String, String), ClientError>>, ClientError> {
+        let table = self
+            .db_list()
+            .get_filtered_stream_table(
+                &DBPacketInfo::new(table_name),
+                key_prefix,
+                value_contains,
+                self.client.client_key(),
+            )
+            .map_err(DBResponseError)?;
+        Ok(stream_pairs(&table))
+    }
+
+    /// Get the hashmap of the contents of a database, received in chunks rather than as a single response.
+    /// Requires list permissions on the given DB
+    pub fn stream_db_contents(
+        &mut self,
+        db_name: &str,
+    ) -> Result<HashMap<String, String>, ClientError> {
+        let chunks = self
+            .db_list()
+            .stream_db_contents(&DBPacketInfo::new(db_name), self.client.client_key())
+            .map_err(DBResponseError)?;
+
+        let mut data = vec![];
+        for chunk in chunks {
+            data.extend_from_slice(chunk.get_data());
+        }
+
+        serde_json::from_slice::<HashMap<String, String>>(&data)
+            .map_err(|err| PacketDeserializationError(Error::from(err)))
+    }
+
+    /// Pushes the value onto the list, or inserts it at the index if there is one, returning the new length of the list.
+    fn add_to_list(
+        &self,
+        db_name: &str,
+        list_name: &str,
+        value: &str,
+        index: Option<usize>,
+    ) -> Result<usize, ClientError> {
+        let db_info = DBPacketInfo::new(db_name);
+        deserialize_reply(self.saved(&db_info, |db_list, client_key| {
+            db_list.add_to_list(
+                &db_info,
+                &DBLocation::new(list_name),
+                &DBData::new(value.to_string()),
+                index,
+                client_key,
+            )
+        }))
+    }
+
+    /// The db list this mock client uses.
+    fn db_list(&self) -> &DBList {
+        self.client.db_list()
+    }
+
+    /// Runs a request that changes the given db, saving the db if it succeeded, as `LocalClient` does.
+    fn saved<F>(
+        &self,
+        db_info: &DBPacketInfo,
+        request: F,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError>
+    where
+        F: FnOnce(&DBList, &String) -> Result<DBSuccessResponse<String>, DBPacketResponseError>,
+    {
+        let db_list = self.db_list();
+        let resp = request(db_list, self.client.client_key());
+        if resp.is_ok() {
+            db_list.save_specific_db(db_info);
+        }
+        resp
+    }
+}
+
+impl Default for MockSmolDbClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Deserializes the value a read or write replied with to `T`.
+fn deserialize_response<T>(
+    response: DBSuccessResponse<String>,
+) -> Result<DBSuccessResponse<T>, ClientError>
+where
+    for<'a> T: Serialize + Deserialize<'a>,
+{
+    match response {
+        SuccessNoData => Ok(SuccessNoData),
+        SuccessData(data) => Ok(SuccessData(data)),
+        SuccessReply(data) => serde_json::from_str::<T>(&data)
+            .map(SuccessReply)
+            .map_err(|err| PacketDeserializationError(Error::from(err))),
+    }
+}
+
+/// Decodes the binary data a bytes request replied with.
+fn bytes_from_base64(data: &str) -> Result<Vec<u8>, ClientError> {
+    DBBytes::from_base64(data)
+        .map(|bytes| bytes.get_data().to_vec())
+        .map_err(|err| PacketDeserializationError(Error::new(ErrorKind::InvalidData, err)))
+}
+
+/// Returns each key value pair of a copied db as the items of a stream.
+fn stream_pairs(table: &DBContent) -> vec::IntoIter<Result<(String, String), ClientError>> {
+    table
+        .content
+        .iter()
+        .map(|(key, value)| Ok((key.clone(), value.clone())))
+        .collect::<Vec<_>>()
+        .into_iter()
+}
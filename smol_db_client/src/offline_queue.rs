@@ -0,0 +1,182 @@
+//! Contains `OfflineQueue`, the writes a `SmolDbClient` could not send while disconnected, replayed once it is connected again
+use crate::client_error::ClientError;
+use crate::client_error::ClientError::{
+    OfflineQueueFull, SocketReadError, SocketWriteError, UnableToConnect,
+};
+use serde::{Deserialize, Serialize};
+use smol_db_common::db_packets::db_packet_response::DBPacketResponseError;
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+/// A write or delete of a single key that was queued while the client was disconnected
+pub struct QueuedWrite {
+    /// Name of the database written to
+    pub db_name: String,
+    /// Key written to
+    pub key: String,
+    /// Value written, `None` if the key was deleted
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// What to do with a queued write when it is replayed, decided by a `ConflictPolicy`
+pub enum ConflictResolution {
+    /// Send the queued write as it is
+    Apply,
+    /// Drop the queued write, keeping the value on the server
+    Skip,
+    /// Write the given value instead, or delete the key if `None`, such as a merge of the queued and server values
+    Replace(Option<String>),
+}
+
+/// `ConflictPolicy` decides what happens to each queued write as it is replayed, given the value the key has on the server by then,
+/// which may have been changed by other clients while this one was disconnected.
+/// Any closure taking the same arguments as `resolve` can be used as a policy.
+pub trait ConflictPolicy {
+    /// Returns what to do with the queued write, given the value on the server, `None` if the key has no value
+    fn resolve(&self, write: &QueuedWrite, server_value: Option<&str>) -> ConflictResolution;
+}
+
+impl<F> ConflictPolicy for F
+where
+    F: Fn(&QueuedWrite, Option<&str>) -> ConflictResolution,
+{
+    fn resolve(&self, write: &QueuedWrite, server_value: Option<&str>) -> ConflictResolution {
+        self(write, server_value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// Replays every queued write as it is, overwriting any change made on the server while the client was disconnected
+pub struct LastWriteWins;
+
+impl ConflictPolicy for LastWriteWins {
+    fn resolve(&self, _write: &QueuedWrite, _server_value: Option<&str>) -> ConflictResolution {
+        ConflictResolution::Apply
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// What happened to the queued writes when they were replayed
+pub struct ReplayReport {
+    /// Number of writes sent to the server, including replaced ones
+    pub applied: usize,
+    /// Number of writes the conflict policy skipped
+    pub skipped: usize,
+    /// Writes the server rejected, such as for lacking permissions, with the error it responded with.
+    /// They are removed from the queue, as sending them again would fail the same way.
+    pub rejected: Vec<(QueuedWrite, DBPacketResponseError)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// `OfflineQueue` holds the writes and deletes a `SmolDbClient` could not send because it was disconnected, oldest first.
+/// Set on a client with `SmolDbClient::set_offline_queue`, after which `write_db` and `delete_data` queue the write
+/// when sending it fails with a connection error, or when writes are already queued, so writes are replayed in the order they were made.
+/// A queued write returns `SuccessNoData`, and fails with `OfflineQueueFull` once the queue holds its capacity.
+/// Once the client is connected again, and its access key set, `SmolDbClient::replay_offline_queue` sends the queued writes.
+/// A queue loaded from a file saves itself to the file on every change, so queued writes survive the program restarting.
+pub struct OfflineQueue {
+    /// Most writes the queue holds
+    capacity: usize,
+    /// File the queue is saved to, if it is persisted
+    path: Option<PathBuf>,
+    writes: VecDeque<QueuedWrite>,
+}
+
+impl OfflineQueue {
+    /// Creates a queue held only in memory, holding at most `capacity` writes
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            path: None,
+            writes: VecDeque::new(),
+        }
+    }
+
+    /// Loads the queue saved at the path, a missing file has no queued writes.
+    /// The queue is saved to the path on every change.
+    #[tracing::instrument]
+    pub fn load(path: &Path, capacity: usize) -> io::Result<Self> {
+        let writes = match fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => VecDeque::new(),
+            Err(err) => return Err(err),
+        };
+        Ok(Self {
+            capacity,
+            path: Some(path.to_path_buf()),
+            writes,
+        })
+    }
+
+    /// Returns the queued writes, oldest first
+    pub fn writes(&self) -> impl Iterator<Item = &QueuedWrite> {
+        self.writes.iter()
+    }
+
+    /// Returns the number of queued writes
+    pub fn len(&self) -> usize {
+        self.writes.len()
+    }
+
+    /// Returns true if no writes are queued
+    pub fn is_empty(&self) -> bool {
+        self.writes.is_empty()
+    }
+
+    /// Removes every queued write without sending them
+    pub fn clear(&mut self) {
+        self.writes.clear();
+        self.save();
+    }
+
+    /// Adds the write to the end of the queue, failing with `OfflineQueueFull` if the queue holds its capacity
+    pub(crate) fn push(&mut self, write: QueuedWrite) -> Result<(), ClientError> {
+        if self.writes.len() >= self.capacity {
+            warn!("Offline queue is full, dropping write to {:?}", write);
+            return Err(OfflineQueueFull);
+        }
+        info!("Queued write while offline: {:?}", write);
+        self.writes.push_back(write);
+        self.save();
+        Ok(())
+    }
+
+    /// Returns the oldest queued write
+    pub(crate) fn front(&self) -> Option<&QueuedWrite> {
+        self.writes.front()
+    }
+
+    /// Removes the oldest queued write, once it was replayed
+    pub(crate) fn pop_front(&mut self) {
+        self.writes.pop_front();
+        self.save();
+    }
+
+    /// Saves the queue to its file, if it has one. A failed save is logged rather than failing the write,
+    /// as the write is still queued in memory.
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let result = serde_json::to_string(&self.writes)
+            .map_err(io::Error::from)
+            .and_then(|contents| fs::write(path, contents));
+        if let Err(err) = result {
+            warn!("Unable to save offline queue to {:?}: {}", path, err);
+        }
+    }
+}
+
+/// Returns true if the error means the client could not reach the server, so a write that failed with it can be queued
+pub(crate) fn is_offline_error(err: &ClientError) -> bool {
+    matches!(
+        err,
+        UnableToConnect(_) | SocketWriteError(_) | SocketReadError(_)
+    )
+}
@@ -0,0 +1,41 @@
+//! Contains `RequestHook`, called by a `SmolDbClient` after every request it sends, so applications can export their own client side metrics
+use crate::client_error::ClientError;
+use smol_db_common::prelude::DBSuccessResponse;
+use std::fmt::{Debug, Formatter};
+use std::time::Duration;
+
+/// `RequestHook` is called by a `SmolDbClient` once each request it sends has been answered, or has failed.
+/// Set with `SmolDbClient::set_request_hook`, any closure taking the same arguments as `on_request` can be used as a hook.
+pub trait RequestHook: Send + Sync {
+    /// Called with the kind of packet sent, such as `Read` or `CreateDB`, how long the server took to answer it,
+    /// including sending and encrypting the packet, and the result the client received.
+    fn on_request(
+        &self,
+        packet_kind: &str,
+        duration: Duration,
+        result: &Result<DBSuccessResponse<String>, ClientError>,
+    );
+}
+
+impl<F> RequestHook for F
+where
+    F: Fn(&str, Duration, &Result<DBSuccessResponse<String>, ClientError>) + Send + Sync,
+{
+    fn on_request(
+        &self,
+        packet_kind: &str,
+        duration: Duration,
+        result: &Result<DBSuccessResponse<String>, ClientError>,
+    ) {
+        self(packet_kind, duration, result)
+    }
+}
+
+/// The hook set on a client, wrapped so the client can still derive `Debug`
+pub(crate) struct BoxedRequestHook(pub(crate) Box<dyn RequestHook>);
+
+impl Debug for BoxedRequestHook {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RequestHook")
+    }
+}
@@ -0,0 +1,91 @@
+//! Contains `ResponseCache`, the values and settings a `SmolDbClient` remembers reading, so reading them again does not need a round trip to the server
+use smol_db_common::prelude::{ChangeEvent, DBPacket, DBSettings};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+/// Values read with `read_db` and settings read with `get_db_settings`, each kept until its time to live passes.
+/// Writes sent by the client invalidate what they change, writes by other clients are only seen once the entry expires,
+/// or once they are passed to `SmolDbClient::apply_change_event`, such as from a watch on another connection.
+pub(crate) struct ResponseCache {
+    /// How long an entry is used before it is read from the server again
+    ttl: Duration,
+    /// Values by db name and key, with the time they were read
+    values: HashMap<(String, String), (Instant, String)>,
+    /// Settings by db name, with the time they were read
+    settings: HashMap<String, (Instant, DBSettings)>,
+}
+
+impl ResponseCache {
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            values: HashMap::new(),
+            settings: HashMap::new(),
+        }
+    }
+
+    /// Returns the cached value at the key, if it was read within the time to live
+    pub(crate) fn get_value(&self, db_name: &str, key: &str) -> Option<&str> {
+        self.values
+            .get(&(db_name.to_string(), key.to_string()))
+            .filter(|(read_at, _)| read_at.elapsed() < self.ttl)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub(crate) fn insert_value(&mut self, db_name: &str, key: &str, value: &str) {
+        self.values.insert(
+            (db_name.to_string(), key.to_string()),
+            (Instant::now(), value.to_string()),
+        );
+    }
+
+    /// Returns the cached settings of the db, if they were read within the time to live
+    pub(crate) fn get_settings(&self, db_name: &str) -> Option<&DBSettings> {
+        self.settings
+            .get(db_name)
+            .filter(|(read_at, _)| read_at.elapsed() < self.ttl)
+            .map(|(_, settings)| settings)
+    }
+
+    pub(crate) fn insert_settings(&mut self, db_name: &str, settings: &DBSettings) {
+        self.settings
+            .insert(db_name.to_string(), (Instant::now(), settings.clone()));
+    }
+
+    /// Removes the cached value at the key, so the next read of it goes to the server
+    pub(crate) fn invalidate_value(&mut self, db_name: &str, key: &str) {
+        self.values.remove(&(db_name.to_string(), key.to_string()));
+    }
+
+    /// Removes every cached value and setting
+    pub(crate) fn clear(&mut self) {
+        self.values.clear();
+        self.settings.clear();
+    }
+
+    /// Removes whatever the packet could change before it is sent.
+    /// A write to a single key only removes that key, any other change, or a change of access key, clears the whole cache,
+    /// as it may change many keys, settings, or what the client is allowed to read.
+    pub(crate) fn invalidate_for(&mut self, packet: &DBPacket) {
+        match packet {
+            DBPacket::Write(db, location, _)
+            | DBPacket::DeleteData(db, location)
+            | DBPacket::WriteIfAbsent(db, location, _)
+            | DBPacket::WriteBytes(db, location, _)
+            | DBPacket::BeginStreamWrite(db, location)
+            | DBPacket::CompareAndSwap { db, location, .. }
+            | DBPacket::Increment { db, location, .. } => {
+                self.invalidate_value(db.get_db_name(), location.as_key());
+            }
+            DBPacket::SetKey(_) | DBPacket::ResumeSession(_) => self.clear(),
+            _ if packet.is_mutation() => self.clear(),
+            _ => {}
+        }
+    }
+
+    /// Removes the value at the key of the change event, which happened within the given db
+    pub(crate) fn apply_change_event(&mut self, db_name: &str, event: &ChangeEvent) {
+        self.invalidate_value(db_name, event.get_key());
+    }
+}
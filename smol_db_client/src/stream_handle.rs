@@ -0,0 +1,15 @@
+//! Contains `StreamHandle`, the id of a stream opened with `SmolDbClient::open_stream`
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// `StreamHandle` identifies a stream of a table that is open on the server, read one item at a time using `SmolDbClient::next_stream_item`.
+/// Other requests can be sent with the same client while the stream is open, and any number of streams can be read in turn.
+/// A handle is only valid on the connection it was opened on, streams are closed by the server when the client disconnects.
+/// Created using `SmolDbClient::open_stream`
+pub struct StreamHandle(pub(crate) u64);
+
+impl StreamHandle {
+    /// Returns the id the server gave the stream
+    pub fn id(&self) -> u64 {
+        self.0
+    }
+}
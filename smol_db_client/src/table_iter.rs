@@ -1,72 +1,193 @@
-use crate::prelude::SmolDbClient;
 #[cfg(not(feature = "async"))]
-use smol_db_common::{
-    prelude::DBPacketResponseError,
-    prelude::DBSuccessResponse
+use crate::client_error::ClientError;
+#[cfg(not(feature = "async"))]
+use crate::client_error::ClientError::{
+    BadPacket, DBResponseError, PacketDeserializationError, PacketSerializationError,
+    SocketReadError, SocketWriteError,
 };
+use crate::prelude::SmolDbClient;
+#[cfg(not(feature = "async"))]
+use smol_db_common::db_packets::packet_checksum::deserialize_response;
 use smol_db_common::prelude::DBPacket;
 #[cfg(not(feature = "async"))]
-use std::io::{Read, Write};
-use tracing::{debug, info};
+use smol_db_common::prelude::StreamItem;
+#[cfg(not(feature = "async"))]
+use std::io::{Error, Write};
+#[cfg(not(feature = "async"))]
+use std::iter::FusedIterator;
+use tracing::debug;
+#[cfg(not(feature = "async"))]
+use tracing::info;
 
 /// `TableIter` stops the stream to the DB when it is dropped or runs out of values in the DB automatically
-pub struct TableIter<'a>(pub(crate) &'a mut SmolDbClient);
+/// Use `close` to stop the stream and find out if that failed, dropping the iter ignores the error.
+/// Each pair is returned as a `Result`, a failure to read the stream is returned once, after which the iter ends,
+/// and `failed` tells whether the pairs read were the whole table or only the part before the failure.
+pub struct TableIter<'a> {
+    client: &'a mut SmolDbClient,
+    /// True once the stream has ended, either by the server, by the client, or by a failure
+    ended: bool,
+    /// Number of pairs left to read, known when the server sent the length of the table as the stream started
+    #[cfg_attr(feature = "async", allow(dead_code))]
+    remaining: Option<usize>,
+    /// True if the stream ended with an error rather than at the end of the table
+    #[cfg_attr(feature = "async", allow(dead_code))]
+    failed: bool,
+}
 
 impl Drop for TableIter<'_> {
     fn drop(&mut self) {
         debug!("Table iter dropped");
-        #[allow(clippy::let_underscore_future)] // this never happens if async feature is enabled
-        let _ = self.0.send_packet(&DBPacket::EndStreamRead); // attempt to end the read stream when the table iter is dropped
-                                                              // we don't care if this fails, it's just nice if it doesn't
+        if self.ended {
+            // the stream already ended, so there is nothing left to stop
+            return;
+        }
+        #[cfg(not(feature = "async"))]
+        let _ = self.end_stream(); // attempt to end the read stream when the table iter is dropped
+                                   // we don't care if this fails, it's just nice if it doesn't
+                                   // this never happens if async feature is enabled
+        #[cfg(feature = "async")]
+        #[allow(clippy::let_underscore_future)]
+        let _ = self.client.send_packet(&DBPacket::EndStreamRead);
     }
 }
 
 #[cfg(not(feature = "async"))]
-impl Iterator for TableIter<'_> {
-    type Item = (String, String);
+impl<'a> TableIter<'a> {
+    /// Creates an iter over a stream the server has started, with the number of pairs it will send if the server sent it
+    pub(crate) fn new(client: &'a mut SmolDbClient, len: Option<usize>) -> Self {
+        Self {
+            client,
+            ended: false,
+            remaining: len,
+            failed: false,
+        }
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let mut buf: [u8; 1024] = [0; 1024];
+    /// Returns true if the stream ended with an error, rather than at the end of the table or by the client,
+    /// in which case the pairs already read are only part of the table.
+    pub fn failed(&self) -> bool {
+        self.failed
+    }
 
-        let request_new_packet = serde_json::to_string(&DBPacket::ReadyForNextItem).unwrap();
+    /// Stops the stream, waiting for the server to acknowledge it, after which the client can be used normally again.
+    /// Does nothing if the stream already ended.
+    /// ```
+    /// use smol_db_client::prelude::*;
+    ///
+    /// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    ///
+    /// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+    /// let _ = client.create_db("doctest_table_iter_close",DBSettings::default()).unwrap();
+    /// for key in 0..5 {
+    ///     let _ = client.write_db("doctest_table_iter_close",&key.to_string(),"value").unwrap();
+    /// }
+    ///
+    /// let mut table_iter = client.stream_table("doctest_table_iter_close").unwrap();
+    /// assert_eq!(table_iter.size_hint(), (1, Some(6)));
+    /// assert!(table_iter.next().unwrap().is_ok());
+    /// assert!(!table_iter.failed());
+    /// table_iter.close().unwrap();
+    ///
+    /// assert_eq!(client.read_db("doctest_table_iter_close","0").unwrap(), SuccessReply("value".to_string()));
+    /// let _ = client.delete_db("doctest_table_iter_close").unwrap();
+    /// ```
+    pub fn close(mut self) -> Result<(), ClientError> {
+        self.end_stream()
+    }
 
-        let _ = self
-            .0
+    /// Sends `EndStreamRead` and reads the acknowledgement of the server.
+    /// Sent as is rather than with `send_packet`, as the server reads packets in the middle of a stream without encryption.
+    fn end_stream(&mut self) -> Result<(), ClientError> {
+        if self.ended {
+            return Ok(());
+        }
+        // marked first, so a failed attempt is not repeated when the iter is dropped
+        self.ended = true;
+
+        let packet = DBPacket::EndStreamRead
+            .serialize_packet()
+            .map_err(|err| PacketSerializationError(Error::from(err)))?;
+        self.client
             .get_socket()
-            .write(request_new_packet.as_bytes())
-            .ok()?;
+            .write_all(packet.as_bytes())
+            .map_err(SocketWriteError)?;
 
-        debug!("Reading from sockets");
+        let response = self.client.read_message().map_err(SocketReadError)?;
+        match deserialize_response(&response) {
+            Ok(Ok(_)) => Ok(()),
+            Ok(Err(err)) => Err(DBResponseError(err)),
+            Err(err) => Err(PacketDeserializationError(Error::from(err))),
+        }
+    }
 
-        let read_len1 = self.0.get_socket().read(&mut buf).ok()?;
+    /// Ends the stream with the error, which is returned so it can be passed on as the last item
+    fn fail(&mut self, err: ClientError) -> ClientError {
+        self.ended = true;
+        self.failed = true;
+        err
+    }
+}
 
-        let key = String::from_utf8(buf[0..read_len1].to_vec()).unwrap();
+#[cfg(not(feature = "async"))]
+impl Iterator for TableIter<'_> {
+    type Item = Result<(String, String), ClientError>;
 
-        if serde_json::from_str::<Result<DBSuccessResponse<String>, DBPacketResponseError>>(
-            &key[0..read_len1],
-        )
-        .is_ok()
-        {
-            info!("Table iter returned none in key read");
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.ended {
             return None;
         }
 
-        let mut buf: [u8; 1024] = [0; 1024];
-
-        let read_len2 = self.0.get_socket().read(&mut buf).ok()?;
-
-        let value = String::from_utf8(buf[0..read_len2].to_vec()).unwrap();
-        if serde_json::from_str::<Result<DBSuccessResponse<String>, DBPacketResponseError>>(
-            &value[0..read_len2],
-        )
-        .is_ok()
+        let request_new_packet = match DBPacket::ReadyForNextItem.serialize_packet() {
+            Ok(packet) => packet,
+            Err(err) => return Some(Err(self.fail(PacketSerializationError(Error::from(err))))),
+        };
+        if let Err(err) = self
+            .client
+            .get_socket()
+            .write_all(request_new_packet.as_bytes())
         {
-            info!("Table iter returned none in value read");
-            return None;
+            return Some(Err(self.fail(SocketWriteError(err))));
         }
 
-        debug!("{:?}", (&key, &value));
+        debug!("Reading from sockets");
 
-        Some((key, value))
+        let message = match self.client.read_message() {
+            Ok(message) => message,
+            Err(err) => return Some(Err(self.fail(SocketReadError(err)))),
+        };
+        match StreamItem::deserialize_item(&message) {
+            Ok(StreamItem::Item(key, value)) => {
+                debug!("{:?}", (&key, &value));
+                self.remaining = self.remaining.map(|remaining| remaining.saturating_sub(1));
+                Some(Ok((key, value)))
+            }
+            Ok(StreamItem::StreamEnd) => {
+                info!("Table iter reached the end of the stream");
+                self.ended = true;
+                None
+            }
+            Err(_) => {
+                // the server sent something other than an item, such as an error response, which ends the stream
+                info!("Table iter stream ended by server");
+                let err = match deserialize_response(&message) {
+                    Ok(Err(err)) => DBResponseError(err),
+                    _ => BadPacket,
+                };
+                Some(Err(self.fail(err)))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match (self.ended, self.remaining) {
+            (true, _) => (0, Some(0)),
+            // reading the end of the stream can still fail once every pair is read, returning one more item than the pairs left
+            (false, Some(remaining)) => (remaining.min(1), Some(remaining + 1)),
+            (false, None) => (0, None),
+        }
     }
 }
+
+#[cfg(not(feature = "async"))]
+impl FusedIterator for TableIter<'_> {}
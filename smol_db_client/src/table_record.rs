@@ -0,0 +1,108 @@
+//! Contains `SmolDbTable`, a struct stored as a key in a database, which can be derived with the `derive` feature
+use crate::client_error::ClientError;
+use crate::prelude::SmolDbClient;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "derive")]
+pub use smol_db_derive::SmolDbTable;
+
+/// `SmolDbTable` stores each value of a struct serialized at its own key, all within the same database.
+/// Derived with `#[derive(SmolDbTable)]` when the `derive` feature is enabled, marking the key field with `#[smol_db(key)]`,
+/// and naming the database with `#[smol_db(table = "name")]`, which is the name of the struct if not given.
+/// ```
+/// # #[cfg(not(feature = "async"))]
+/// # fn main() {
+/// use serde::{Deserialize, Serialize};
+/// use smol_db_client::prelude::*;
+/// use smol_db_client::table_record::SmolDbTable;
+///
+/// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// struct Player {
+///     name: String,
+///     points: u32,
+/// }
+///
+/// impl SmolDbTable for Player {
+///     const TABLE_NAME: &'static str = "doctest_smol_db_table";
+///
+///     fn key(&self) -> String {
+///         self.name.clone()
+///     }
+/// }
+///
+/// let mut client = SmolDbClient::new("localhost:8222").unwrap();
+///
+/// let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+/// let _ = client.create_db(Player::TABLE_NAME,DBSettings::default()).unwrap();
+///
+/// let player = Player { name: "bob".to_string(), points: 10 };
+/// assert_eq!(player.save(&mut client).unwrap(), None);
+/// assert_eq!(Player::load(&mut client, "bob").unwrap(), Some(player));
+/// assert_eq!(Player::all(&mut client).unwrap().len(), 1);
+///
+/// let _ = client.delete_db(Player::TABLE_NAME).unwrap();
+/// # }
+/// # #[cfg(feature = "async")]
+/// # fn main() {}
+/// ```
+pub trait SmolDbTable
+where
+    Self: Sized,
+    for<'a> Self: Serialize + Deserialize<'a>,
+{
+    /// Name of the database every value is stored in
+    const TABLE_NAME: &'static str;
+
+    /// Returns the key the value is stored at
+    fn key(&self) -> String;
+
+    /// Writes the value to its key, returning the value it replaced, or `None` if the key had no value.
+    /// Requires permissions to write to the database
+    #[cfg(not(feature = "async"))]
+    fn save(&self, client: &mut SmolDbClient) -> Result<Option<Self>, ClientError> {
+        client.set(Self::TABLE_NAME, &self.key(), self)
+    }
+
+    /// Reads the value stored at the key, or `None` if the key has no value.
+    /// Requires permissions to read from the database
+    #[cfg(not(feature = "async"))]
+    fn load(client: &mut SmolDbClient, key: &str) -> Result<Option<Self>, ClientError> {
+        client.get(Self::TABLE_NAME, key)
+    }
+
+    /// Reads every value stored in the database.
+    /// Requires permissions to list the database
+    #[cfg(not(feature = "async"))]
+    fn all(client: &mut SmolDbClient) -> Result<Vec<Self>, ClientError> {
+        client
+            .list_db_contents_generic::<Self>(Self::TABLE_NAME)
+            .map(|contents| contents.into_values().collect())
+    }
+
+    /// Writes the value to its key, returning the value it replaced, or `None` if the key had no value.
+    /// Requires permissions to write to the database
+    #[cfg(feature = "async")]
+    #[allow(async_fn_in_trait)]
+    async fn save(&self, client: &mut SmolDbClient) -> Result<Option<Self>, ClientError> {
+        client.set(Self::TABLE_NAME, &self.key(), self).await
+    }
+
+    /// Reads the value stored at the key, or `None` if the key has no value.
+    /// Requires permissions to read from the database
+    #[cfg(feature = "async")]
+    #[allow(async_fn_in_trait)]
+    async fn load(client: &mut SmolDbClient, key: &str) -> Result<Option<Self>, ClientError> {
+        client.get(Self::TABLE_NAME, key).await
+    }
+
+    /// Reads every value stored in the database.
+    /// Requires permissions to list the database
+    #[cfg(feature = "async")]
+    #[allow(async_fn_in_trait)]
+    async fn all(client: &mut SmolDbClient) -> Result<Vec<Self>, ClientError> {
+        client
+            .list_db_contents_generic::<Self>(Self::TABLE_NAME)
+            .await
+            .map(|contents| contents.into_values().collect())
+    }
+}
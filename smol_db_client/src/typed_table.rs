@@ -0,0 +1,99 @@
+//! Contains `TypedTable`, a handle to a single database on the server with a fixed serde type for its values
+use crate::client_error::ClientError;
+use crate::prelude::SmolDbClient;
+use serde::{Deserialize, Serialize};
+use smol_db_common::prelude::DBSuccessResponse;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+/// `TypedTable` wraps a `SmolDbClient` and a database name, reading and writing every value in the table as `T`.
+/// Created using `SmolDbClient::table`
+pub struct TypedTable<'a, T> {
+    client: &'a mut SmolDbClient,
+    db_name: String,
+    phantom: PhantomData<T>,
+}
+
+impl<'a, T> TypedTable<'a, T>
+where
+    for<'de> T: Serialize + Deserialize<'de>,
+{
+    pub(crate) fn new(client: &'a mut SmolDbClient, db_name: &str) -> Self {
+        Self {
+            client,
+            db_name: db_name.to_string(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the name of the database this table operates on
+    pub fn get_db_name(&self) -> &str {
+        &self.db_name
+    }
+
+    /// Reads the value at the given location, deserializing it to `T`
+    #[cfg(not(feature = "async"))]
+    pub fn read(&mut self, db_location: &str) -> Result<DBSuccessResponse<T>, ClientError> {
+        self.client.read_db_generic::<T>(&self.db_name, db_location)
+    }
+
+    /// Reads the value at the given location, deserializing it to `T`
+    #[cfg(feature = "async")]
+    pub async fn read(&mut self, db_location: &str) -> Result<DBSuccessResponse<T>, ClientError> {
+        self.client
+            .read_db_generic::<T>(&self.db_name, db_location)
+            .await
+    }
+
+    /// Writes the given value to the location, returning the previous value at the location if there was one
+    #[cfg(not(feature = "async"))]
+    pub fn write(
+        &mut self,
+        db_location: &str,
+        data: T,
+    ) -> Result<DBSuccessResponse<T>, ClientError> {
+        self.client
+            .write_db_generic::<T>(&self.db_name, db_location, data)
+    }
+
+    /// Writes the given value to the location, returning the previous value at the location if there was one
+    #[cfg(feature = "async")]
+    pub async fn write(
+        &mut self,
+        db_location: &str,
+        data: T,
+    ) -> Result<DBSuccessResponse<T>, ClientError> {
+        self.client
+            .write_db_generic::<T>(&self.db_name, db_location, data)
+            .await
+    }
+
+    /// Lists the contents of the table, deserializing every value to `T`
+    #[cfg(not(feature = "async"))]
+    pub fn list_contents(&mut self) -> Result<HashMap<String, T>, ClientError> {
+        self.client.list_db_contents_generic::<T>(&self.db_name)
+    }
+
+    /// Lists the contents of the table, deserializing every value to `T`
+    #[cfg(feature = "async")]
+    pub async fn list_contents(&mut self) -> Result<HashMap<String, T>, ClientError> {
+        self.client
+            .list_db_contents_generic::<T>(&self.db_name)
+            .await
+    }
+
+    /// Deletes the value at the given location
+    #[cfg(not(feature = "async"))]
+    pub fn delete(&mut self, db_location: &str) -> Result<DBSuccessResponse<String>, ClientError> {
+        self.client.delete_data(&self.db_name, db_location)
+    }
+
+    /// Deletes the value at the given location
+    #[cfg(feature = "async")]
+    pub async fn delete(
+        &mut self,
+        db_location: &str,
+    ) -> Result<DBSuccessResponse<String>, ClientError> {
+        self.client.delete_data(&self.db_name, db_location).await
+    }
+}
@@ -0,0 +1,69 @@
+use crate::prelude::SmolDbClient;
+use serde::de::DeserializeOwned;
+use smol_db_common::db_packets::packet_checksum::deserialize_response;
+use smol_db_common::prelude::{ChangeEvent, DBPacket, DBSuccessResponse};
+use std::io::Write;
+use std::marker::PhantomData;
+use tracing::{debug, info};
+
+/// `WatchIter` blocks on each call to `next` until the server sends the next item, either a changed key, a published message, or a value of a list.
+/// The watch is ended when it is dropped, after which the client can be used normally again.
+/// Created using `SmolDbClient::watch`, `SmolDbClient::subscribe`, or `SmolDbClient::stream_list`
+pub struct WatchIter<'a, T = ChangeEvent> {
+    client: &'a mut SmolDbClient,
+    /// Set once the server has stopped the watch, in which case there is nothing to end when dropped
+    finished: bool,
+    phantom: PhantomData<T>,
+}
+
+impl<'a, T> WatchIter<'a, T> {
+    pub(crate) fn new(client: &'a mut SmolDbClient) -> Self {
+        Self {
+            client,
+            finished: false,
+            phantom: PhantomData,
+        }
+    }
+
+    fn send_and_receive(&mut self, packet: &DBPacket) -> Option<DBSuccessResponse<String>> {
+        let ser = packet.serialize_packet().ok()?;
+        self.client.get_socket().write_all(ser.as_bytes()).ok()?;
+
+        let message = self.client.read_message().ok()?;
+
+        deserialize_response(&message).ok()?.ok()
+    }
+}
+
+impl<T> Drop for WatchIter<'_, T> {
+    fn drop(&mut self) {
+        debug!("Watch iter dropped");
+        if self.finished {
+            return;
+        }
+        // wait for the server to acknowledge the end of the watch, so the next packet sent is not mistaken for part of the watch
+        let _ = self.send_and_receive(&DBPacket::EndStreamRead);
+    }
+}
+
+impl<T: DeserializeOwned> Iterator for WatchIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let event = match self.send_and_receive(&DBPacket::ReadyForNextItem) {
+            Some(DBSuccessResponse::SuccessReply(data)) => serde_json::from_str(&data).ok(),
+            _ => None,
+        };
+
+        if event.is_none() {
+            info!("Watch ended by server");
+            self.finished = true;
+        }
+
+        event
+    }
+}
@@ -1,22 +1,23 @@
 #[cfg(test)]
 #[cfg(feature = "async")]
 mod tests {
-    use smol_db_client::SmolDbClient;
+    use smol_db_client::prelude::SmolDbClient;
     use smol_db_common::prelude::DBSettings;
+    use smol_db_test_harness::{spawn_server, ServerGuard};
     use std::time::Duration;
 
-    const TESTING_IP: &str = "localhost:8222";
     const TESTING_KEY: &str = "test_key_123";
 
-    async fn get_client_and_set_key() -> SmolDbClient {
-        let mut client = SmolDbClient::new(TESTING_IP).await.unwrap();
+    async fn get_client_and_set_key(server: &ServerGuard) -> SmolDbClient {
+        let mut client = SmolDbClient::new(server.address()).await.unwrap();
         assert!(client.set_access_key(TESTING_KEY.to_string()).await.is_ok());
         client
     }
 
     #[tokio::test]
     async fn test_client_connect() {
-        let mut client = get_client_and_set_key().await;
+        let server = spawn_server();
+        let mut client = get_client_and_set_key(&server).await;
 
         let f1 = client
             .create_db("async_connect", DBSettings::default())
@@ -33,7 +34,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_client_write_read_db() {
-        let mut client = get_client_and_set_key().await;
+        let server = spawn_server();
+        let mut client = get_client_and_set_key(&server).await;
 
         const DB_NAME: &str = "async_test_write_read";
 
@@ -63,7 +65,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_setup_encryption() {
-        let mut client = get_client_and_set_key().await;
+        let server = spawn_server();
+        let mut client = get_client_and_set_key(&server).await;
 
         const DB_NAME: &str = "async_test_encryption";
 
@@ -97,7 +100,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_reconnect() {
-        let mut client = get_client_and_set_key().await;
+        let server = spawn_server();
+        let mut client = get_client_and_set_key(&server).await;
 
         assert!(client.disconnect().await.is_ok());
 
@@ -106,7 +110,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_delete_data() {
-        let mut client = get_client_and_set_key().await;
+        let server = spawn_server();
+        let mut client = get_client_and_set_key(&server).await;
 
         const DB_NAME: &str = "async_test_delete_data";
 
@@ -145,7 +150,8 @@ mod tests {
     #[tokio::test]
     #[cfg(feature = "statistics")]
     async fn test_get_stats() {
-        let mut client = get_client_and_set_key().await;
+        let server = spawn_server();
+        let mut client = get_client_and_set_key(&server).await;
 
         const DB_NAME: &str = "async_test_stats";
 
@@ -165,7 +171,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_settings() {
-        let mut client = get_client_and_set_key().await;
+        let server = spawn_server();
+        let mut client = get_client_and_set_key(&server).await;
 
         const DB_NAME: &str = "async_test_settings";
 
@@ -198,7 +205,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_list_db() {
-        let mut client = get_client_and_set_key().await;
+        let server = spawn_server();
+        let mut client = get_client_and_set_key(&server).await;
 
         const DB_NAME: &str = "async_test_list_db";
 
@@ -207,7 +215,7 @@ mod tests {
             .await
             .is_ok());
 
-        assert!(client.list_db().await.unwrap().len() >= 1);
+        assert!(!client.list_db().await.unwrap().is_empty());
 
         assert!(client.write_db(DB_NAME, "loc1", "d1").await.is_ok());
         assert!(client.write_db(DB_NAME, "loc2", "d2").await.is_ok());
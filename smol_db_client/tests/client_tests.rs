@@ -4,6 +4,8 @@
 mod tests {
     use serde::{Deserialize, Serialize};
     use smol_db_client::prelude::*;
+    use smol_db_test_harness::spawn_server;
+    use std::collections::HashMap;
     use std::fs::read;
     use std::thread;
     use std::time::Duration;
@@ -11,7 +13,8 @@ mod tests {
 
     #[test]
     fn test_stream() {
-        let mut client = SmolDbClient::new("localhost:8222").unwrap();
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
 
         let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
         assert_eq!(set_key_response, SuccessNoData);
@@ -27,9 +30,19 @@ mod tests {
                 .unwrap();
         }
 
-        let table_iter = client.stream_table("stream_test").unwrap();
+        let mut table_iter = client.stream_table("stream_test").unwrap();
+        assert_eq!(table_iter.size_hint(), (1, Some(11)));
 
-        let list = table_iter.collect::<Vec<(String, String)>>();
+        let list = table_iter
+            .by_ref()
+            .collect::<Result<Vec<(String, String)>, client_error::ClientError>>()
+            .unwrap();
+
+        // the iter is left at the end of the stream rather than consumed, and remembers it ended without failing
+        assert!(!table_iter.failed());
+        assert_eq!(table_iter.size_hint(), (0, Some(0)));
+        assert!(table_iter.next().is_none());
+        drop(table_iter);
 
         assert_eq!(list.len(), 10);
 
@@ -41,9 +54,73 @@ mod tests {
         assert_eq!(delete_response, SuccessNoData);
     }
 
+    #[test]
+    fn test_oversized_packet_rejected() {
+        use smol_db_common::db_packets::packet_checksum::{deserialize_response, MAX_PACKET_SIZE};
+        use std::io::{Read, Write};
+        use std::net::TcpStream;
+
+        let server = spawn_server();
+        let mut socket = TcpStream::connect(server.address()).unwrap();
+
+        // the server answers as soon as the header is read, without waiting for the rest of the packet
+        let header = format!("crc32:00000000:{} ", MAX_PACKET_SIZE + 1);
+        socket.write_all(header.as_bytes()).unwrap();
+        let mut response = vec![];
+        socket.read_to_end(&mut response).unwrap();
+        assert_eq!(
+            deserialize_response(&response).unwrap(),
+            Err(PacketCorrupted)
+        );
+
+        // the server is still serving other clients
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+        assert_eq!(
+            client.set_access_key("test_key_123".to_string()).unwrap(),
+            SuccessNoData
+        );
+    }
+
+    #[test]
+    fn test_stream_table_framing() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+        let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+        let db_name = "test_stream_table_framing";
+        let _ = client.create_db(db_name, DBSettings::default()).unwrap();
+
+        // larger than a single read, and holding text that looks like a response
+        let large_value = "v".repeat(5000);
+        let tricky_value = r#"{"Ok":"SuccessNoData"}"#.to_string();
+        for (key, value) in [("large", &large_value), ("tricky", &tricky_value)] {
+            let _ = client.write_db(db_name, key, value).unwrap();
+        }
+
+        // every item is read whole, followed by the end of the stream
+        let items: HashMap<String, String> = client
+            .stream_table(db_name)
+            .unwrap()
+            .collect::<Result<_, client_error::ClientError>>()
+            .unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items["large"], large_value);
+        assert_eq!(items["tricky"], tricky_value);
+
+        // a stream ended early is acknowledged, so the next request reads its own response
+        let first = client.stream_table(db_name).unwrap().next();
+        assert!(matches!(first, Some(Ok(_))));
+        assert_eq!(
+            client.read_db(db_name, "tricky").unwrap(),
+            SuccessReply(tricky_value)
+        );
+
+        let _ = client.delete_db(db_name).unwrap();
+    }
+
     #[test]
     fn test_client() {
-        let mut client = SmolDbClient::new("localhost:8222").unwrap();
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
 
         let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
         assert_eq!(set_key_response, SuccessNoData);
@@ -61,7 +138,7 @@ mod tests {
             SuccessReply(response_data) => {
                 assert_eq!(&response_data, data);
             }
-            SuccessNoData => {
+            SuccessNoData | SuccessData(_) => {
                 panic!("data response was not as expected");
             }
         }
@@ -103,7 +180,8 @@ mod tests {
 
     #[test]
     fn test_missing_create_db_permissions() {
-        let mut client = SmolDbClient::new("localhost:8222").unwrap();
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
 
         let resp = client
             .create_db("not enough permissions", DBSettings::default())
@@ -121,7 +199,8 @@ mod tests {
 
     #[test]
     fn test_generics_client() {
-        let mut client = SmolDbClient::new("localhost:8222").unwrap();
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
 
         let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
         assert_eq!(set_key_response, SuccessNoData);
@@ -195,7 +274,8 @@ mod tests {
 
     #[test]
     fn test_list_db() {
-        let mut client = SmolDbClient::new("localhost:8222").unwrap();
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
 
         let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
         assert_eq!(set_key_response, SuccessNoData);
@@ -224,10 +304,98 @@ mod tests {
         assert_eq!(delete_db_response2, SuccessNoData);
     }
 
+    #[test]
+    fn test_list_accessible_db() {
+        let server = spawn_server();
+        let mut admin_client = SmolDbClient::new(server.address()).unwrap();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+        let _ = admin_client.set_access_key("test_key_123".to_string());
+        let _ = client.set_access_key("test_list_accessible_db_user".to_string());
+
+        let private_settings = DBSettings::new(
+            Duration::from_secs(30),
+            (false, false, false),
+            (true, true, true),
+            vec![],
+            vec!["test_list_accessible_db_user".to_string()],
+        );
+        assert_eq!(
+            admin_client
+                .create_db("test_accessible_1", private_settings)
+                .unwrap(),
+            SuccessNoData
+        );
+        let hidden_settings = DBSettings::new(
+            Duration::from_secs(30),
+            (false, false, false),
+            (false, false, false),
+            vec![],
+            vec![],
+        );
+        assert_eq!(
+            admin_client
+                .create_db("test_accessible_2", hidden_settings)
+                .unwrap(),
+            SuccessNoData
+        );
+
+        // every db is still listed by list_db, only the dbs the client can read or list are listed as accessible
+        assert_eq!(client.list_db().unwrap().len(), 2);
+        let accessible = client.list_accessible_db().unwrap();
+        assert_eq!(accessible.len(), 1);
+        assert_eq!(accessible[0].info, DBPacketInfo::new("test_accessible_1"));
+        assert_eq!(admin_client.list_accessible_db().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_get_effective_permissions() {
+        let server = spawn_server();
+        let mut admin_client = SmolDbClient::new(server.address()).unwrap();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+        let mut user_client = SmolDbClient::new(server.address()).unwrap();
+        let _ = admin_client.set_access_key("test_key_123".to_string());
+        let _ = client.set_access_key("test_effective_permissions_other".to_string());
+        let _ = user_client.set_access_key("test_effective_permissions_user".to_string());
+        let db_name = "test_get_effective_permissions";
+
+        let settings = DBSettings::new(
+            Duration::from_secs(30),
+            (true, false, true),
+            (true, true, true),
+            vec![],
+            vec!["test_effective_permissions_user".to_string()],
+        );
+        assert_eq!(
+            admin_client.create_db(db_name, settings).unwrap(),
+            SuccessNoData
+        );
+
+        let permissions = client.get_effective_permissions(db_name).unwrap();
+        assert_eq!(permissions.role, Other);
+        assert!(permissions.can(Permission::Read));
+        assert!(permissions.can(Permission::List));
+        assert!(!permissions.can(Permission::Write));
+        assert!(!permissions.can_write_key("key"));
+
+        // users of the db resolve to the permissions of users instead
+        let permissions = user_client.get_effective_permissions(db_name).unwrap();
+        assert_eq!(permissions.role, User);
+        assert!(permissions.can(Permission::Write));
+
+        assert_eq!(
+            admin_client
+                .get_effective_permissions(db_name)
+                .unwrap()
+                .role,
+            SuperAdmin
+        );
+    }
+
     #[test]
     #[cfg(feature = "statistics")]
     fn test_get_stats() {
-        let mut client = SmolDbClient::new("localhost:8222").unwrap();
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
 
         let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
         assert_eq!(set_key_response, SuccessNoData);
@@ -265,7 +433,7 @@ mod tests {
 
     // #[test]
     // fn test_empty_db_list() {
-    //     let mut client = SmolDbClient::new("localhost:8222").unwrap();
+    //     let mut client = SmolDbClient::new(server.address()).unwrap();
     //
     //     let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
     //     assert_eq!(set_key_response, SuccessNoData);
@@ -291,7 +459,8 @@ mod tests {
 
     #[test]
     fn test_list_db_contents() {
-        let mut client = SmolDbClient::new("localhost:8222").unwrap();
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
 
         let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
         assert_eq!(set_key_response, SuccessNoData);
@@ -318,7 +487,8 @@ mod tests {
 
     #[test]
     fn test_list_db_contents_empty() {
-        let mut client = SmolDbClient::new("localhost:8222").unwrap();
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
 
         let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
         assert_eq!(set_key_response, SuccessNoData);
@@ -338,7 +508,8 @@ mod tests {
 
     #[test]
     fn test_list_db_contents_generic() {
-        let mut client = SmolDbClient::new("localhost:8222").unwrap();
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
 
         let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
         assert_eq!(set_key_response, SuccessNoData);
@@ -386,7 +557,8 @@ mod tests {
 
     #[test]
     fn test_get_db_settings() {
-        let mut client = SmolDbClient::new("localhost:8222").unwrap();
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
         let db_settings_test = DBSettings::new(
             Duration::from_secs(29),
             (false, true, false),
@@ -414,7 +586,8 @@ mod tests {
 
     #[test]
     fn test_set_db_settings() {
-        let mut client = SmolDbClient::new("localhost:8222").unwrap();
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
         let db_settings_test = DBSettings::new(
             Duration::from_secs(27),
             (false, true, true),
@@ -462,7 +635,8 @@ mod tests {
 
     #[test]
     fn test_get_role() {
-        let mut client = SmolDbClient::new("localhost:8222").unwrap();
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
         let user_key = "this is a user key that works".to_string();
         let admin_key = "this is an admin key that works".to_string();
         let other_key = "this is not an admin, super admin, or user key".to_string();
@@ -509,7 +683,8 @@ mod tests {
 
     #[test]
     fn test_delete_data() {
-        let mut client = SmolDbClient::new("localhost:8222").unwrap();
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
         let db_settings_test = DBSettings::new(
             Duration::from_secs(21),
             (false, true, false),
@@ -565,4 +740,2089 @@ mod tests {
             assert_eq!(delete_response, SuccessNoData);
         }
     }
+
+    #[test]
+    fn test_typed_table() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+        let db_name = "test_typed_table";
+
+        let test_data1 = TestStruct {
+            a: 1,
+            b: true,
+            c: -1,
+            d: "typed_data1".to_string(),
+        };
+
+        let test_data2 = TestStruct {
+            a: 2,
+            b: false,
+            c: -2,
+            d: "typed_data2".to_string(),
+        };
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let create_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_response, SuccessNoData);
+
+        {
+            let mut table = client.table::<TestStruct>(db_name);
+            assert_eq!(table.get_db_name(), db_name);
+
+            let write_response1 = table.write("location1", test_data1.clone()).unwrap();
+            assert_eq!(write_response1, SuccessNoData);
+
+            let write_response2 = table.write("location1", test_data2.clone()).unwrap();
+            assert_eq!(write_response2, SuccessReply(test_data1.clone()));
+
+            let write_response3 = table.write("location2", test_data1.clone()).unwrap();
+            assert_eq!(write_response3, SuccessNoData);
+
+            let read_response = table.read("location1").unwrap();
+            assert_eq!(read_response, SuccessReply(test_data2.clone()));
+
+            let contents = table.list_contents().unwrap();
+            assert_eq!(contents.len(), 2);
+            assert_eq!(contents.get("location1").unwrap(), &test_data2);
+            assert_eq!(contents.get("location2").unwrap(), &test_data1);
+
+            let delete_response = table.delete("location2").unwrap();
+            assert!(matches!(delete_response, SuccessReply(_)));
+
+            let read_response2 = table.read("location2").unwrap_err();
+            assert_eq!(read_response2, DBResponseError(ValueNotFound));
+        }
+
+        let delete_response = client.delete_db(db_name).unwrap();
+        assert_eq!(delete_response, SuccessNoData);
+    }
+
+    #[test]
+    fn test_list_db_keys() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let db_name = "test_db_keys1";
+
+        let create_db_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+
+        assert!(client.list_db_keys(db_name).unwrap().is_empty());
+
+        let write_response1 = client.write_db(db_name, "location1", "123").unwrap();
+        assert_eq!(write_response1, SuccessNoData);
+
+        let write_response2 = client.write_db(db_name, "location2", "456").unwrap();
+        assert_eq!(write_response2, SuccessNoData);
+
+        let mut keys = client.list_db_keys(db_name).unwrap();
+        keys.sort();
+        assert_eq!(keys, vec!["location1".to_string(), "location2".to_string()]);
+
+        let delete_db_response = client.delete_db(db_name).unwrap();
+        assert_eq!(delete_db_response, SuccessNoData);
+    }
+
+    #[test]
+    fn test_list_db_lists() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let db_name = "test_db_lists1";
+
+        let create_db_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+
+        assert!(client.list_db_lists(db_name).unwrap().is_empty());
+
+        let write_response = client.write_db(db_name, "location1", "123").unwrap();
+        assert_eq!(write_response, SuccessNoData);
+
+        assert_eq!(client.list_push(db_name, "list2", "a").unwrap(), 1);
+        assert_eq!(client.list_push(db_name, "list1", "b").unwrap(), 1);
+
+        assert_eq!(
+            client.list_db_lists(db_name).unwrap(),
+            vec!["list1".to_string(), "list2".to_string()]
+        );
+
+        let delete_db_response = client.delete_db(db_name).unwrap();
+        assert_eq!(delete_db_response, SuccessNoData);
+    }
+
+    #[test]
+    fn test_read_prefix() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let db_name = "test_read_prefix1";
+
+        let create_db_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+
+        for location in ["user:1:name", "user:1:age", "user:2:name", "item:1"] {
+            let write_response = client.write_db(db_name, location, location).unwrap();
+            assert_eq!(write_response, SuccessNoData);
+        }
+
+        let user_one = client.read_prefix(db_name, "user:1:").unwrap();
+        assert_eq!(
+            user_one.keys().collect::<Vec<&String>>(),
+            vec!["user:1:age", "user:1:name"]
+        );
+        assert_eq!(user_one.get("user:1:name").unwrap(), "user:1:name");
+
+        assert_eq!(client.read_prefix(db_name, "user:").unwrap().len(), 3);
+        assert!(client.read_prefix(db_name, "nothing:").unwrap().is_empty());
+
+        let delete_db_response = client.delete_db(db_name).unwrap();
+        assert_eq!(delete_db_response, SuccessNoData);
+    }
+
+    #[test]
+    fn test_find_keys() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let db_name = "test_find_keys1";
+
+        let create_db_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+
+        for location in ["user:1", "user:2", "item:1"] {
+            let write_response = client.write_db(db_name, location, location).unwrap();
+            assert_eq!(write_response, SuccessNoData);
+        }
+
+        let mut users = client
+            .find_keys(db_name, KeyPattern::new_glob("user:*"))
+            .unwrap();
+        users.sort();
+        assert_eq!(users, vec!["user:1".to_string(), "user:2".to_string()]);
+
+        let ones = client
+            .find_keys(db_name, KeyPattern::new_regex(":1$"))
+            .unwrap();
+        assert_eq!(ones.len(), 2);
+
+        let invalid = client.find_keys(db_name, KeyPattern::new_regex("("));
+        assert_eq!(invalid.unwrap_err(), DBResponseError(InvalidKeyPattern));
+
+        let delete_db_response = client.delete_db(db_name).unwrap();
+        assert_eq!(delete_db_response, SuccessNoData);
+    }
+
+    #[test]
+    fn test_compare_and_swap() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let db_name = "test_compare_and_swap1";
+        let db_location = "location1";
+
+        let create_db_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+
+        let swap_response1 = client
+            .compare_and_swap(db_name, db_location, None, "1")
+            .unwrap();
+        assert_eq!(swap_response1, SuccessNoData);
+
+        let swap_response2 = client
+            .compare_and_swap(db_name, db_location, Some("1"), "2")
+            .unwrap();
+        assert_eq!(swap_response2, SuccessReply("1".to_string()));
+
+        match client.compare_and_swap(db_name, db_location, Some("1"), "3") {
+            Err(DBResponseError(ValueMismatch(current))) => {
+                assert_eq!(current, Some("2".to_string()));
+            }
+            other => {
+                panic!("compare and swap should have mismatched: {:?}", other);
+            }
+        }
+
+        let read_response = client.read_db(db_name, db_location).unwrap();
+        assert_eq!(read_response, SuccessReply("2".to_string()));
+
+        let delete_db_response = client.delete_db(db_name).unwrap();
+        assert_eq!(delete_db_response, SuccessNoData);
+    }
+
+    #[test]
+    fn test_increment() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let db_name = "test_increment1";
+        let db_location = "counter";
+
+        let create_db_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+
+        for i in 1..=10 {
+            assert_eq!(client.increment(db_name, db_location, 1).unwrap(), i);
+        }
+
+        assert_eq!(client.decrement(db_name, db_location, 15).unwrap(), -5);
+
+        let read_response = client.read_db(db_name, db_location).unwrap();
+        assert_eq!(read_response, SuccessReply("-5".to_string()));
+
+        let _ = client.write_db(db_name, db_location, "abc").unwrap();
+        assert_eq!(
+            client.increment(db_name, db_location, 1).unwrap_err(),
+            DBResponseError(ValueNotInteger)
+        );
+
+        let delete_db_response = client.delete_db(db_name).unwrap();
+        assert_eq!(delete_db_response, SuccessNoData);
+    }
+
+    #[test]
+    fn test_transaction() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let db_name = "test_transaction1";
+
+        let create_db_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+
+        let transaction = TransactionBuilder::new()
+            .write("from", "10")
+            .write("to", "0")
+            .read("from");
+        let results = client.transaction(db_name, transaction).unwrap();
+        assert_eq!(results, vec![None, None, Some("10".to_string())]);
+
+        let failing_transaction = TransactionBuilder::new()
+            .write("from", "0")
+            .write("to", "10")
+            .delete("does_not_exist");
+        let failed = client.transaction(db_name, failing_transaction);
+        assert_eq!(
+            failed.unwrap_err(),
+            DBResponseError(TransactionRolledBack {
+                op_index: 2,
+                error: Box::new(ValueNotFound),
+            })
+        );
+
+        let read_response = client.read_db(db_name, "from").unwrap();
+        assert_eq!(read_response, SuccessReply("10".to_string()));
+
+        // a transaction far larger than a single read from the socket, in both the request and the response
+        let value = "v".repeat(100);
+        let mut large_transaction = TransactionBuilder::new();
+        for i in 0..30 {
+            large_transaction = large_transaction.write(&format!("key_{}", i), &value);
+        }
+        for i in 0..30 {
+            large_transaction = large_transaction.read(&format!("key_{}", i));
+        }
+        let results = client.transaction(db_name, large_transaction).unwrap();
+        assert_eq!(results.len(), 60);
+        assert!(results[..30].iter().all(Option::is_none));
+        assert!(results[30..]
+            .iter()
+            .all(|result| result.as_ref() == Some(&value)));
+
+        let delete_db_response = client.delete_db(db_name).unwrap();
+        assert_eq!(delete_db_response, SuccessNoData);
+    }
+
+    #[test]
+    fn test_write_db_if_absent() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let db_name = "test_write_if_absent1";
+
+        let create_db_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+
+        let claim1 = client.write_db_if_absent(db_name, "lock", "client_1");
+        assert_eq!(claim1.unwrap(), SuccessNoData);
+
+        let claim2 = client.write_db_if_absent(db_name, "lock", "client_2");
+        assert_eq!(claim2.unwrap_err(), DBResponseError(ValueAlreadyExists));
+
+        let read_response = client.read_db(db_name, "lock").unwrap();
+        assert_eq!(read_response, SuccessReply("client_1".to_string()));
+
+        let delete_db_response = client.delete_db(db_name).unwrap();
+        assert_eq!(delete_db_response, SuccessNoData);
+    }
+
+    #[test]
+    fn test_watch() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let db_name = "test_watch1";
+
+        let create_db_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+
+        let mut watch = client
+            .watch(db_name, KeyPattern::new_glob("job_*"))
+            .unwrap();
+
+        let address = server.address().to_string();
+        let writer = thread::spawn(move || {
+            let mut writer = SmolDbClient::new(&address).unwrap();
+            let _ = writer.set_access_key("test_key_123".to_string()).unwrap();
+            let _ = writer.write_db(db_name, "unrelated", "1").unwrap();
+            let _ = writer.write_db(db_name, "job_1", "queued").unwrap();
+            let _ = writer.delete_data(db_name, "job_1").unwrap();
+        });
+
+        assert_eq!(
+            watch.next().unwrap(),
+            ChangeEvent::Written {
+                key: "job_1".to_string(),
+                value: "queued".to_string()
+            }
+        );
+        assert_eq!(
+            watch.next().unwrap(),
+            ChangeEvent::Deleted {
+                key: "job_1".to_string()
+            }
+        );
+        drop(watch);
+        writer.join().unwrap();
+
+        // the connection can be used normally once the watch is dropped
+        let read_response = client.read_db(db_name, "unrelated").unwrap();
+        assert_eq!(read_response, SuccessReply("1".to_string()));
+
+        let delete_db_response = client.delete_db(db_name).unwrap();
+        assert_eq!(delete_db_response, SuccessNoData);
+    }
+
+    #[test]
+    fn test_publish_subscribe() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+        let channel_name = "test_channel1";
+
+        let mut subscription = client.subscribe(channel_name).unwrap();
+
+        let address = server.address().to_string();
+        let publisher = thread::spawn(move || {
+            let mut publisher = SmolDbClient::new(&address).unwrap();
+            assert_eq!(publisher.publish(channel_name, "first").unwrap(), 1);
+            assert_eq!(publisher.publish(channel_name, "second").unwrap(), 1);
+            assert_eq!(publisher.publish("test_channel_nobody", "lost").unwrap(), 0);
+        });
+
+        assert_eq!(subscription.next().unwrap(), "first");
+        assert_eq!(subscription.next().unwrap(), "second");
+        drop(subscription);
+        publisher.join().unwrap();
+
+        // the connection can be used normally once the subscription is dropped
+        assert_eq!(client.publish(channel_name, "after").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_read_write_many() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let db_name = "test_read_write_many1";
+
+        let create_db_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+
+        let writes: Vec<(String, String)> = (0..10)
+            .map(|i| (format!("key_{}", i), format!("value_{}", i)))
+            .collect();
+        let writes_ref: Vec<(&str, &str)> = writes
+            .iter()
+            .map(|(location, value)| (location.as_str(), value.as_str()))
+            .collect();
+
+        let previous_values = client.write_many(db_name, &writes_ref).unwrap();
+        assert_eq!(previous_values, vec![None; 10]);
+
+        let locations: Vec<&str> = writes_ref.iter().map(|(location, _)| *location).collect();
+        let values = client.read_many(db_name, &locations).unwrap();
+        let expected: Vec<Option<String>> = writes
+            .iter()
+            .map(|(_, value)| Some(value.clone()))
+            .collect();
+        assert_eq!(values, expected);
+
+        // a batch far larger than a single read from the socket, in both the request and the response
+        let large_writes: Vec<(String, String)> = (0..200)
+            .map(|i| (format!("large_key_{}", i), format!("large_value_{}", i)))
+            .collect();
+        let large_writes_ref: Vec<(&str, &str)> = large_writes
+            .iter()
+            .map(|(location, value)| (location.as_str(), value.as_str()))
+            .collect();
+        let previous_values = client.write_many(db_name, &large_writes_ref).unwrap();
+        assert_eq!(previous_values, vec![None; 200]);
+        let locations: Vec<&str> = large_writes_ref
+            .iter()
+            .map(|(location, _)| *location)
+            .collect();
+        let values = client.read_many(db_name, &locations).unwrap();
+        assert_eq!(values.len(), 200);
+        assert_eq!(values[199], Some("large_value_199".to_string()));
+        assert_eq!(client.get_db_len(db_name).unwrap(), 210);
+
+        let delete_db_response = client.delete_db(db_name).unwrap();
+        assert_eq!(delete_db_response, SuccessNoData);
+    }
+
+    #[test]
+    fn test_large_packets() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+        let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+        let db_name = "test_large_packets1";
+        let _ = client.create_db(db_name, DBSettings::default()).unwrap();
+
+        // both the request and its response are larger than a single read from the socket
+        let value = "a".repeat(1500);
+        let _ = client.write_db(db_name, "location", &value).unwrap();
+        assert_eq!(
+            client.read_db(db_name, "location").unwrap().into_option(),
+            Some(value)
+        );
+
+        let keys: Vec<String> = (0..100).map(|i| format!("key{}", i)).collect();
+        for key in &keys {
+            let _ = client.write_db(db_name, key, "value").unwrap();
+        }
+        let mut listed = client.list_db_keys(db_name).unwrap();
+        listed.retain(|key| key != "location");
+        listed.sort();
+        let mut expected = keys.clone();
+        expected.sort();
+        assert_eq!(listed, expected);
+        assert_eq!(client.list_db_contents(db_name).unwrap().len(), 101);
+
+        // nothing was left unread to be mistaken for the response to the next packet
+        assert_eq!(client.setup_encryption().unwrap(), SuccessNoData);
+        assert_eq!(
+            client.read_db(db_name, "key0").unwrap(),
+            SuccessReply("value".to_string())
+        );
+
+        let _ = client.delete_db(db_name).unwrap();
+    }
+
+    #[test]
+    fn test_export_import_table() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+        let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+
+        let db_name = "test_export_import_table1";
+        let csv_db_name = "test_export_import_table2";
+        let json_db_name = "test_export_import_table3";
+        for name in [db_name, csv_db_name, json_db_name] {
+            assert_eq!(
+                client.create_db(name, DBSettings::default()).unwrap(),
+                SuccessNoData
+            );
+        }
+
+        // enough keys for more than one batch, with values that need quoting in csv
+        for i in 0..250 {
+            let _ = client
+                .write_db(
+                    db_name,
+                    &format!("key_{}", i),
+                    &format!("value, \"{}\"\n", i),
+                )
+                .unwrap();
+        }
+
+        let mut csv = vec![];
+        let _ = client.export_table_csv(db_name, &mut csv).unwrap();
+        assert_eq!(
+            client
+                .import_table_csv(csv_db_name, &mut csv.as_slice())
+                .unwrap(),
+            250
+        );
+
+        let mut json = vec![];
+        let _ = client.export_table_json(db_name, &mut json).unwrap();
+        assert_eq!(
+            client
+                .import_table_json(json_db_name, &mut json.as_slice())
+                .unwrap(),
+            250
+        );
+
+        // streamed, so each table is read a pair at a time rather than held in a single response
+        let mut contents = vec![];
+        for name in [db_name, csv_db_name, json_db_name] {
+            let mut table = client
+                .stream_table(name)
+                .unwrap()
+                .collect::<Result<Vec<(String, String)>, client_error::ClientError>>()
+                .unwrap();
+            table.sort();
+            contents.push(table);
+        }
+        assert_eq!(contents[0].len(), 250);
+        assert_eq!(contents[1], contents[0]);
+        assert_eq!(contents[2], contents[0]);
+
+        assert!(matches!(
+            client.import_table_csv(csv_db_name, &mut "name,value\r\n".as_bytes()),
+            Err(client_error::ClientError::ValueStreamError(_))
+        ));
+        assert!(matches!(
+            client.import_table_csv(csv_db_name, &mut "key,value\r\n\"open".as_bytes()),
+            Err(client_error::ClientError::ValueStreamError(_))
+        ));
+        assert!(matches!(
+            client.import_table_json(json_db_name, &mut "[1,2]".as_bytes()),
+            Err(client_error::ClientError::ValueStreamError(_))
+        ));
+
+        for name in [db_name, csv_db_name, json_db_name] {
+            assert_eq!(client.delete_db(name).unwrap(), SuccessNoData);
+        }
+    }
+
+    #[test]
+    fn test_clear_db() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let db_name = "test_clear_db1";
+        let db_settings = DBSettings::new(
+            Duration::from_secs(30),
+            (false, false, false),
+            (true, true, true),
+            vec![],
+            vec!["some_user".to_string()],
+        );
+
+        let create_db_response = client.create_db(db_name, db_settings.clone()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+        // the user key is stored as a hash, so the settings are compared to the ones the server holds
+        let db_settings = client.get_db_settings(db_name).unwrap();
+        assert_ne!(db_settings.users, vec!["some_user".to_string()]);
+
+        let _ = client.write_db(db_name, "a", "1").unwrap();
+        let _ = client.write_db(db_name, "b", "2").unwrap();
+
+        let clear_response = client.clear_db(db_name).unwrap();
+        assert_eq!(clear_response, SuccessNoData);
+
+        assert!(client.list_db_contents(db_name).unwrap().is_empty());
+        assert_eq!(client.get_db_settings(db_name).unwrap(), db_settings);
+
+        let delete_db_response = client.delete_db(db_name).unwrap();
+        assert_eq!(delete_db_response, SuccessNoData);
+    }
+
+    #[test]
+    fn test_rename_db() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let db_name = "test_rename_db1";
+        let new_db_name = "test_rename_db2";
+
+        let create_db_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+
+        let _ = client.write_db(db_name, "key", "value").unwrap();
+
+        let rename_response = client.rename_db(db_name, new_db_name).unwrap();
+        assert_eq!(rename_response, SuccessNoData);
+
+        let db_list = client.list_db().unwrap();
+        assert!(db_list.contains(&DBPacketInfo::new(new_db_name)));
+        assert!(!db_list.contains(&DBPacketInfo::new(db_name)));
+
+        let read_response = client.read_db(new_db_name, "key").unwrap();
+        assert_eq!(read_response, SuccessReply("value".to_string()));
+
+        let read_old_response = client.read_db(db_name, "key");
+        assert_eq!(read_old_response.unwrap_err(), DBResponseError(DBNotFound));
+
+        let delete_db_response = client.delete_db(new_db_name).unwrap();
+        assert_eq!(delete_db_response, SuccessNoData);
+    }
+
+    #[test]
+    fn test_copy_db() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let db_name = "test_copy_db1";
+        let copy_db_name = "test_copy_db2";
+        let db_settings = DBSettings::new(
+            Duration::from_secs(30),
+            (false, false, false),
+            (true, true, true),
+            vec![],
+            vec!["some_user".to_string()],
+        );
+
+        let create_db_response = client.create_db(db_name, db_settings.clone()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+
+        let _ = client.write_db(db_name, "a", "1").unwrap();
+        let _ = client.write_db(db_name, "b", "2").unwrap();
+
+        let copy_response = client.copy_db(db_name, copy_db_name, true).unwrap();
+        assert_eq!(copy_response, SuccessNoData);
+
+        assert_eq!(
+            client.list_db_contents(copy_db_name).unwrap(),
+            client.list_db_contents(db_name).unwrap()
+        );
+        assert_eq!(
+            client.get_db_settings(copy_db_name).unwrap(),
+            client.get_db_settings(db_name).unwrap()
+        );
+
+        let copy_again = client.copy_db(db_name, copy_db_name, true);
+        assert_eq!(copy_again.unwrap_err(), DBResponseError(DBAlreadyExists));
+
+        assert_eq!(client.delete_db(db_name).unwrap(), SuccessNoData);
+        assert_eq!(client.delete_db(copy_db_name).unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_db_and_key_exists() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let db_name = "test_exists1";
+
+        assert!(!client.db_exists(db_name).unwrap());
+
+        let create_db_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+
+        assert!(client.db_exists(db_name).unwrap());
+        assert!(!client.key_exists(db_name, "key").unwrap());
+
+        let _ = client.write_db(db_name, "key", "value").unwrap();
+        assert!(client.key_exists(db_name, "key").unwrap());
+
+        let _ = client.delete_data(db_name, "key").unwrap();
+        assert!(!client.key_exists(db_name, "key").unwrap());
+
+        assert_eq!(
+            client
+                .key_exists("test_exists_missing_db", "key")
+                .unwrap_err(),
+            DBResponseError(DBNotFound)
+        );
+
+        let delete_db_response = client.delete_db(db_name).unwrap();
+        assert_eq!(delete_db_response, SuccessNoData);
+        assert!(!client.db_exists(db_name).unwrap());
+    }
+
+    #[test]
+    fn test_get_db_len() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let db_name = "test_db_len1";
+
+        let create_db_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+
+        assert_eq!(client.get_db_len(db_name).unwrap(), 0);
+
+        for i in 0..5 {
+            let _ = client
+                .write_db(db_name, &format!("key{}", i), "value")
+                .unwrap();
+        }
+        assert_eq!(client.get_db_len(db_name).unwrap(), 5);
+
+        let _ = client.delete_data(db_name, "key0").unwrap();
+        assert_eq!(client.get_db_len(db_name).unwrap(), 4);
+
+        assert_eq!(
+            client.get_db_len("test_db_len_missing").unwrap_err(),
+            DBResponseError(DBNotFound)
+        );
+
+        let delete_db_response = client.delete_db(db_name).unwrap();
+        assert_eq!(delete_db_response, SuccessNoData);
+    }
+
+    #[test]
+    fn test_keyed_list() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let db_name = "test_keyed_list1";
+
+        let create_db_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+
+        assert_eq!(client.list_len(db_name, "list").unwrap(), 0);
+        assert_eq!(client.list_push(db_name, "list", "b").unwrap(), 1);
+        assert_eq!(client.list_push(db_name, "list", "c").unwrap(), 2);
+        assert_eq!(client.list_insert(db_name, "list", 0, "a").unwrap(), 3);
+
+        assert_eq!(
+            client.list_get(db_name, "list", 2).unwrap(),
+            SuccessReply("c".to_string())
+        );
+        assert_eq!(
+            client.list_get(db_name, "list", 3).unwrap_err(),
+            DBResponseError(IndexOutOfBounds)
+        );
+
+        let streamed: Vec<String> = client.stream_list(db_name, "list").unwrap().collect();
+        assert_eq!(streamed, vec!["a", "b", "c"]);
+
+        // the client can be used normally after a stream is finished
+        assert_eq!(
+            client.list_remove(db_name, "list", 1).unwrap(),
+            SuccessReply("b".to_string())
+        );
+
+        {
+            // dropping a stream partway through ends it early
+            let mut stream = client.stream_list(db_name, "list").unwrap();
+            assert_eq!(stream.next().unwrap(), "a");
+        }
+
+        assert_eq!(client.list_len(db_name, "list").unwrap(), 2);
+
+        assert_eq!(client.list_clear(db_name, "list").unwrap(), SuccessNoData);
+        assert_eq!(client.list_len(db_name, "list").unwrap(), 0);
+        assert_eq!(
+            client.list_get(db_name, "list", 0).unwrap_err(),
+            DBResponseError(ValueNotFound)
+        );
+
+        let delete_db_response = client.delete_db(db_name).unwrap();
+        assert_eq!(delete_db_response, SuccessNoData);
+    }
+
+    #[test]
+    fn test_sets() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let db_name = "test_sets1";
+
+        let create_db_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+
+        assert!(client.set_members(db_name, "set").unwrap().is_empty());
+
+        assert!(client.set_add(db_name, "set", "c").unwrap());
+        assert!(client.set_add(db_name, "set", "a").unwrap());
+        assert!(client.set_add(db_name, "set", "b").unwrap());
+        assert!(!client.set_add(db_name, "set", "a").unwrap());
+
+        assert_eq!(
+            client.set_members(db_name, "set").unwrap(),
+            vec!["a", "b", "c"]
+        );
+        assert!(client.set_contains(db_name, "set", "b").unwrap());
+
+        assert!(client.set_remove(db_name, "set", "b").unwrap());
+        assert!(!client.set_contains(db_name, "set", "b").unwrap());
+        assert_eq!(client.set_members(db_name, "set").unwrap(), vec!["a", "c"]);
+
+        // sets are kept separate from the key value pairs
+        assert!(client.list_db_keys(db_name).unwrap().is_empty());
+
+        let delete_db_response = client.delete_db(db_name).unwrap();
+        assert_eq!(delete_db_response, SuccessNoData);
+    }
+
+    #[test]
+    fn test_sorted_list() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let db_name = "test_sorted_list1";
+
+        let create_db_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+
+        for (i, player) in ["p1", "p2", "p3", "p4", "p5"].iter().enumerate() {
+            let _ = client
+                .sorted_add(db_name, "leaderboard", player, (i * 10) as f64)
+                .unwrap();
+        }
+
+        assert_eq!(
+            client.sorted_rank(db_name, "leaderboard", "p3").unwrap(),
+            Some(2)
+        );
+        assert_eq!(
+            client
+                .sorted_add(db_name, "leaderboard", "p1", 100.0)
+                .unwrap(),
+            4
+        );
+
+        let top: Vec<String> = client
+            .sorted_top(db_name, "leaderboard", 3)
+            .unwrap()
+            .into_iter()
+            .map(|scored| scored.member)
+            .collect();
+        assert_eq!(top, vec!["p1", "p5", "p4"]);
+
+        let range = client
+            .sorted_range_by_score(db_name, "leaderboard", 15.0, 35.0)
+            .unwrap();
+        assert_eq!(
+            range,
+            vec![
+                ScoredMember::new("p3".to_string(), 20.0),
+                ScoredMember::new("p4".to_string(), 30.0)
+            ]
+        );
+
+        assert!(client.sorted_remove(db_name, "leaderboard", "p1").unwrap());
+        assert!(!client.sorted_remove(db_name, "leaderboard", "p1").unwrap());
+        assert_eq!(
+            client.sorted_rank(db_name, "leaderboard", "p1").unwrap(),
+            None
+        );
+
+        let delete_db_response = client.delete_db(db_name).unwrap();
+        assert_eq!(delete_db_response, SuccessNoData);
+    }
+
+    #[test]
+    fn test_queue() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let db_name = "test_queue1";
+
+        let create_db_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+
+        assert_eq!(client.queue_pop(db_name, "jobs").unwrap(), None);
+
+        assert_eq!(client.queue_push(db_name, "jobs", "job1").unwrap(), 1);
+        assert_eq!(client.queue_push(db_name, "jobs", "job2").unwrap(), 2);
+        assert_eq!(
+            client.queue_pop(db_name, "jobs").unwrap(),
+            Some("job1".to_string())
+        );
+
+        let address = server.address().to_string();
+        let pusher = thread::spawn(move || {
+            let mut pusher = SmolDbClient::new(&address).unwrap();
+            let _ = pusher.set_access_key("test_key_123".to_string()).unwrap();
+            thread::sleep(Duration::from_millis(200));
+            let _ = pusher.queue_push(db_name, "jobs", "job3").unwrap();
+        });
+
+        assert_eq!(
+            client
+                .queue_pop_blocking(db_name, "jobs", Duration::from_secs(5))
+                .unwrap(),
+            Some("job2".to_string())
+        );
+        // the queue is empty here, so this waits for the other client to push
+        assert_eq!(
+            client
+                .queue_pop_blocking(db_name, "jobs", Duration::from_secs(5))
+                .unwrap(),
+            Some("job3".to_string())
+        );
+        pusher.join().unwrap();
+
+        assert_eq!(
+            client
+                .queue_pop_blocking(db_name, "jobs", Duration::from_millis(100))
+                .unwrap(),
+            None
+        );
+
+        let delete_db_response = client.delete_db(db_name).unwrap();
+        assert_eq!(delete_db_response, SuccessNoData);
+    }
+
+    #[test]
+    fn test_db_bytes() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let db_name = "test_db_bytes1";
+
+        let create_db_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+
+        let data: Vec<u8> = (0..=255).collect();
+        assert_eq!(client.write_db_bytes(db_name, "blob", &data).unwrap(), None);
+        assert_eq!(client.read_db_bytes(db_name, "blob").unwrap(), data);
+
+        assert_eq!(
+            client.write_db_bytes(db_name, "blob", &[]).unwrap(),
+            Some(data)
+        );
+        assert!(client.read_db_bytes(db_name, "blob").unwrap().is_empty());
+
+        assert_eq!(
+            client.read_db_bytes(db_name, "missing").unwrap_err(),
+            DBResponseError(ValueNotFound)
+        );
+
+        let delete_db_response = client.delete_db(db_name).unwrap();
+        assert_eq!(delete_db_response, SuccessNoData);
+    }
+
+    #[test]
+    fn test_value_streaming() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let db_name = "test_stream_value1";
+
+        let create_db_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+
+        // far larger than a single packet
+        let data: Vec<u8> = (0..100_000).map(|i| (i % 251) as u8).collect();
+        assert_eq!(
+            client
+                .stream_write_db(db_name, "blob", &mut data.as_slice())
+                .unwrap(),
+            data.len()
+        );
+
+        let mut read_back = vec![];
+        assert_eq!(
+            client
+                .stream_read_db(db_name, "blob", &mut read_back)
+                .unwrap(),
+            data.len()
+        );
+        assert_eq!(read_back, data);
+
+        // the client can be used normally after streaming
+        let _ = client.write_db(db_name, "key", "value").unwrap();
+        assert_eq!(
+            client.read_db(db_name, "key").unwrap(),
+            SuccessReply("value".to_string())
+        );
+
+        assert_eq!(
+            client
+                .stream_read_db(db_name, "missing", &mut vec![])
+                .unwrap_err(),
+            DBResponseError(ValueNotFound)
+        );
+
+        let delete_db_response = client.delete_db(db_name).unwrap();
+        assert_eq!(delete_db_response, SuccessNoData);
+    }
+
+    #[test]
+    fn test_aborted_value_streaming() {
+        let server = spawn_server();
+        struct FailingReader(usize);
+
+        impl std::io::Read for FailingReader {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                if self.0 == 0 {
+                    return Err(std::io::Error::other("reader failed"));
+                }
+                self.0 -= 1;
+                buf[0] = 1;
+                Ok(1)
+            }
+        }
+
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+
+        let db_name = "test_stream_value2";
+
+        let create_db_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+
+        let err = client
+            .stream_write_db(db_name, "blob", &mut FailingReader(3))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            client_error::ClientError::ValueStreamError(_)
+        ));
+
+        // nothing is stored when the reader fails
+        assert_eq!(
+            client.read_db_bytes(db_name, "blob").unwrap_err(),
+            DBResponseError(ValueNotFound)
+        );
+
+        let delete_db_response = client.delete_db(db_name).unwrap();
+        assert_eq!(delete_db_response, SuccessNoData);
+    }
+
+    #[test]
+    fn test_ping() {
+        let server = spawn_server();
+        // no access key is needed to ping
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        let round_trip = client.ping().unwrap();
+        assert!(round_trip < Duration::from_secs(5));
+
+        let client = std::sync::Arc::new(std::sync::Mutex::new(client));
+        let heartbeat = Heartbeat::start(client.clone(), Duration::from_millis(10));
+        thread::sleep(Duration::from_millis(50));
+        assert!(heartbeat.is_alive());
+
+        // the client is usable while the heartbeat is running
+        assert!(client.lock().unwrap().ping().is_ok());
+        heartbeat.stop();
+    }
+
+    #[test]
+    fn test_user_accounts() {
+        let server = spawn_server();
+        let mut admin_client = SmolDbClient::new(server.address()).unwrap();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+        let _ = admin_client.set_access_key("test_key_123".to_string());
+        let db_name = "test_user_accounts";
+        let username = "test_user_accounts_user";
+
+        // the account is given a role by adding its account key to the db settings
+        let settings = DBSettings::new(
+            Duration::from_secs(30),
+            (false, false, false),
+            (false, false, false),
+            vec![UserRegistry::account_key(username)],
+            vec![],
+        );
+        let _ = admin_client.delete_db(db_name);
+        let _ = admin_client.create_user(username, "password");
+        let _ = admin_client.set_user_disabled(username, false);
+        assert_eq!(
+            admin_client.create_db(db_name, settings).unwrap(),
+            SuccessNoData
+        );
+        assert!(admin_client
+            .list_users()
+            .unwrap()
+            .contains(&(username.to_string(), false)));
+
+        // only the super admin can manage users
+        assert_eq!(
+            client
+                .create_user("test_user_accounts_other", "password")
+                .unwrap_err(),
+            DBResponseError(InvalidPermissions)
+        );
+        assert_eq!(
+            client.login(username, "wrong_password").unwrap_err(),
+            DBResponseError(InvalidPermissions)
+        );
+        assert_eq!(client.login(username, "password").unwrap(), SuccessNoData);
+        assert_eq!(client.get_role(db_name).unwrap(), Admin);
+        assert_eq!(
+            client.write_db(db_name, "key", "value").unwrap(),
+            SuccessNoData
+        );
+
+        // a disabled account is logged out, and can not be logged in to
+        assert_eq!(
+            admin_client.set_user_disabled(username, true).unwrap(),
+            SuccessNoData
+        );
+        assert_eq!(
+            client.read_db(db_name, "key").unwrap_err(),
+            DBResponseError(InvalidPermissions)
+        );
+        assert_eq!(client.get_role(db_name).unwrap(), Other);
+        assert_eq!(
+            client.login(username, "password").unwrap_err(),
+            DBResponseError(InvalidPermissions)
+        );
+
+        assert_eq!(admin_client.delete_db(db_name).unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_key_acls() {
+        let server = spawn_server();
+        let mut admin_client = SmolDbClient::new(server.address()).unwrap();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+        let _ = admin_client.set_access_key("test_key_123".to_string());
+        let _ = client.set_access_key("test_key_acls_worker".to_string());
+        let db_name = "test_key_acls";
+
+        let _ = admin_client.delete_db(db_name);
+        let settings = DBSettings::new(
+            Duration::from_secs(30),
+            (true, true, true),
+            (true, true, true),
+            vec![],
+            vec![],
+        );
+        assert_eq!(
+            admin_client.create_db(db_name, settings).unwrap(),
+            SuccessNoData
+        );
+        assert_eq!(
+            admin_client
+                .add_key_acl(db_name, "test_key_acls_worker", "jobs/", true, true)
+                .unwrap(),
+            SuccessNoData
+        );
+        // only admins can manage rules
+        assert_eq!(
+            client
+                .add_key_acl(db_name, "test_key_acls_worker", "", true, true)
+                .unwrap_err(),
+            DBResponseError(InvalidPermissions)
+        );
+        let rules = admin_client.get_key_acls(db_name).unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].prefix, "jobs/");
+        assert_ne!(rules[0].key, "test_key_acls_worker");
+
+        assert_eq!(
+            client.write_db(db_name, "jobs/1", "a").unwrap(),
+            SuccessNoData
+        );
+        assert_eq!(
+            client.write_db(db_name, "other", "a").unwrap_err(),
+            DBResponseError(InvalidPermissions)
+        );
+        assert_eq!(
+            client.list_db_contents(db_name).unwrap_err(),
+            DBResponseError(InvalidPermissions)
+        );
+
+        assert_eq!(
+            admin_client
+                .remove_key_acl(db_name, "test_key_acls_worker", "jobs/")
+                .unwrap(),
+            SuccessNoData
+        );
+        assert_eq!(
+            client.write_db(db_name, "other", "a").unwrap(),
+            SuccessNoData
+        );
+
+        assert_eq!(admin_client.delete_db(db_name).unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_add_and_remove_members() {
+        let server = spawn_server();
+        let mut admin_client = SmolDbClient::new(server.address()).unwrap();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+        let _ = admin_client.set_access_key("test_key_123".to_string());
+        let _ = client.set_access_key("test_members_user".to_string());
+        let db_name = "test_add_and_remove_members";
+
+        let settings = DBSettings::new(
+            Duration::from_secs(30),
+            (false, false, false),
+            (true, true, true),
+            vec![],
+            vec![],
+        );
+        assert_eq!(
+            admin_client.create_db(db_name, settings).unwrap(),
+            SuccessNoData
+        );
+        assert_eq!(
+            client.list_db_contents(db_name).unwrap_err(),
+            DBResponseError(InvalidPermissions)
+        );
+
+        assert_eq!(
+            admin_client.add_user(db_name, "test_members_user").unwrap(),
+            SuccessNoData
+        );
+        assert_eq!(client.get_role(db_name).unwrap(), User);
+        // only super admins can add admins
+        assert_eq!(
+            client.add_admin(db_name, "test_members_user").unwrap_err(),
+            DBResponseError(InvalidPermissions)
+        );
+
+        // the user is removed by the hash listed in the settings, as the key is not known from them
+        let user_hash = admin_client.get_db_settings(db_name).unwrap().users[0].clone();
+        assert_ne!(user_hash, "test_members_user");
+        assert_eq!(
+            admin_client.remove_user(db_name, &user_hash).unwrap(),
+            SuccessNoData
+        );
+        assert_eq!(client.get_role(db_name).unwrap(), Other);
+        assert_eq!(
+            admin_client
+                .remove_user(db_name, "test_members_user")
+                .unwrap_err(),
+            DBResponseError(UserNotFound)
+        );
+
+        assert_eq!(
+            admin_client
+                .add_admin(db_name, "test_members_user")
+                .unwrap(),
+            SuccessNoData
+        );
+        assert_eq!(client.get_role(db_name).unwrap(), Admin);
+        assert_eq!(
+            admin_client
+                .remove_admin(db_name, "test_members_user")
+                .unwrap(),
+            SuccessNoData
+        );
+        assert_eq!(client.get_role(db_name).unwrap(), Other);
+
+        assert_eq!(admin_client.delete_db(db_name).unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_namespaces() {
+        let server = spawn_server();
+        let mut admin_client = SmolDbClient::new(server.address()).unwrap();
+        let mut tenant_client = SmolDbClient::new(server.address()).unwrap();
+        let _ = admin_client.set_access_key("test_key_123".to_string());
+        let _ = tenant_client.set_access_key("test_namespaces_tenant".to_string());
+
+        let mut settings = NamespaceSettings::new(vec!["test_namespaces_tenant".to_string()]);
+        settings.max_databases = Some(1);
+        assert_eq!(
+            tenant_client
+                .set_namespace("tenant", settings.clone())
+                .unwrap_err(),
+            DBResponseError(InvalidPermissions)
+        );
+        assert_eq!(
+            admin_client.set_namespace("tenant", settings).unwrap(),
+            SuccessNoData
+        );
+        let saved = tenant_client.get_namespace("tenant").unwrap();
+        assert_eq!(saved.max_databases, Some(1));
+        assert_ne!(saved.super_admins[0], "test_namespaces_tenant");
+
+        // the tenant manages the dbs of its namespace, and no others
+        assert_eq!(
+            tenant_client
+                .create_db("tenant/db", DBSettings::default())
+                .unwrap(),
+            SuccessNoData
+        );
+        assert_eq!(
+            tenant_client.write_db("tenant/db", "key", "value").unwrap(),
+            SuccessNoData
+        );
+        assert_eq!(
+            tenant_client.read_db("tenant/db", "key").unwrap(),
+            SuccessReply("value".to_string())
+        );
+        assert_eq!(
+            tenant_client
+                .create_db("tenant/second", DBSettings::default())
+                .unwrap_err(),
+            DBResponseError(NamespaceFull)
+        );
+        assert_eq!(
+            tenant_client
+                .create_db("test_namespaces", DBSettings::default())
+                .unwrap_err(),
+            DBResponseError(InvalidPermissions)
+        );
+        assert_eq!(
+            admin_client
+                .create_db("tenant/", DBSettings::default())
+                .unwrap_err(),
+            DBResponseError(InvalidDBName)
+        );
+
+        assert_eq!(tenant_client.delete_db("tenant/db").unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_set_ip_filter() {
+        let server = spawn_server();
+        let mut admin_client = SmolDbClient::new(server.address()).unwrap();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+        let _ = admin_client.set_access_key("test_key_123".to_string());
+        let _ = client.set_access_key("test_set_ip_filter".to_string());
+
+        // only a super admin can change the filter
+        assert_eq!(
+            client.set_ip_filter(IpFilter::default()).unwrap_err(),
+            DBResponseError(InvalidPermissions)
+        );
+
+        // other addresses are denied, so the tests connecting from localhost are still accepted
+        let filter = IpFilter::new(vec![], vec!["203.0.113.0/24".parse().unwrap()]);
+        assert_eq!(admin_client.set_ip_filter(filter).unwrap(), SuccessNoData);
+        assert!(SmolDbClient::new(server.address()).unwrap().ping().is_ok());
+        assert_eq!(
+            admin_client.set_ip_filter(IpFilter::default()).unwrap(),
+            SuccessNoData
+        );
+    }
+
+    #[test]
+    fn test_sessions() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+        let mut other_client = SmolDbClient::new(server.address()).unwrap();
+
+        // a session needs a key to resume
+        assert_eq!(
+            client.create_session().unwrap_err(),
+            DBResponseError(InvalidPermissions)
+        );
+
+        let _ = client.set_access_key("test_key_123".to_string());
+        let token = client.create_session().unwrap();
+        assert_eq!(
+            other_client.resume_session("not_a_token").unwrap_err(),
+            DBResponseError(InvalidPermissions)
+        );
+        assert_eq!(other_client.resume_session(&token).unwrap(), SuccessNoData);
+        // the session resumed the super admin key
+        assert!(other_client.server_info().is_ok());
+
+        assert_eq!(client.end_session(&token).unwrap(), SuccessNoData);
+        let mut third_client = SmolDbClient::new(server.address()).unwrap();
+        assert_eq!(
+            third_client.resume_session(&token).unwrap_err(),
+            DBResponseError(InvalidPermissions)
+        );
+    }
+
+    #[test]
+    fn test_encryption_large_value() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+        let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+        let db_name = "test_encryption_large_value";
+
+        assert_eq!(client.setup_encryption().unwrap(), SuccessNoData);
+        assert!(client.is_encryption_enabled());
+
+        // larger than a single 2048-bit rsa block, which every packet used to be encrypted as
+        let data = "a".repeat(600);
+        let _ = client.create_db(db_name, DBSettings::default()).unwrap();
+        let _ = client.write_db(db_name, "location", &data).unwrap();
+        assert_eq!(
+            client.read_db(db_name, "location").unwrap().into_option(),
+            Some(data)
+        );
+
+        // larger than a single read from the socket once encrypted, in both the request and the response
+        let data = "b".repeat(4000);
+        let _ = client.write_db(db_name, "large", &data).unwrap();
+        assert_eq!(
+            client.read_db(db_name, "large").unwrap().into_option(),
+            Some(data)
+        );
+        assert_eq!(client.list_db_contents(db_name).unwrap().len(), 2);
+
+        let _ = client.delete_db(db_name).unwrap();
+    }
+
+    #[test]
+    fn test_pin_server_fingerprint() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+        assert_eq!(client.server_fingerprint(), None);
+        let _ = client.setup_encryption().unwrap();
+        let fingerprint = client.server_fingerprint().unwrap().to_string();
+        // the fingerprint printed by the server binary
+        assert_eq!(fingerprint, server.server().key_fingerprint());
+
+        // a client pinning the fingerprint the server presents sets up encryption as usual
+        let mut pinned_client = SmolDbClient::new(server.address()).unwrap();
+        pinned_client.pin_server_fingerprint(&fingerprint);
+        let _ = pinned_client.setup_encryption().unwrap();
+        assert_eq!(
+            pinned_client.server_fingerprint(),
+            Some(fingerprint.as_str())
+        );
+
+        let mut wrong_client = SmolDbClient::new(server.address()).unwrap();
+        wrong_client.pin_server_fingerprint("not_the_fingerprint");
+        assert!(matches!(
+            wrong_client.setup_encryption(),
+            Err(client_error::ClientError::ServerIdentityChanged { presented, .. }) if presented == fingerprint
+        ));
+        assert!(!wrong_client.is_encryption_enabled());
+    }
+
+    #[test]
+    fn test_known_servers() {
+        let server = spawn_server();
+        let path = std::env::temp_dir().join("smol_db_test_known_servers");
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+        let server = client.get_connected_ip().unwrap().to_string();
+        std::fs::write(&path, format!("{server} not_the_fingerprint\n")).unwrap();
+
+        // a known server presenting a different key is refused
+        let mut known_servers = KnownServers::load(&path).unwrap();
+        assert_eq!(
+            known_servers.get_fingerprint(&server),
+            Some("not_the_fingerprint")
+        );
+        client.set_known_servers(known_servers.clone());
+        assert_eq!(
+            client.setup_encryption().unwrap_err(),
+            client_error::ClientError::ServerIdentityChanged {
+                expected: String::new(),
+                presented: String::new(),
+            }
+        );
+        assert!(!client.is_encryption_enabled());
+
+        // once forgotten, the key is trusted on first use and saved
+        assert!(known_servers.forget(&server).unwrap());
+        client.set_known_servers(known_servers);
+        let _ = client.setup_encryption().unwrap();
+        let fingerprint = client.server_fingerprint().unwrap();
+        assert_eq!(
+            KnownServers::load(&path).unwrap().get_fingerprint(&server),
+            Some(fingerprint)
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_local_client() {
+        let mut client = LocalClient::new_in_memory();
+        let mut other_client = LocalClient::new(client.get_db_list());
+
+        // the first key set becomes the super admin
+        assert_eq!(
+            client.set_access_key("test_key_123".to_string()),
+            SuccessNoData
+        );
+        let _ = other_client.set_access_key("other_key".to_string());
+
+        let db_name = "test_local_client1";
+
+        assert_eq!(
+            other_client
+                .create_db(db_name, DBSettings::default())
+                .unwrap_err(),
+            DBResponseError(InvalidPermissions)
+        );
+
+        let create_db_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_db_response, SuccessNoData);
+        assert_eq!(client.list_db().unwrap(), vec![DBPacketInfo::new(db_name)]);
+        assert_eq!(client.get_role(db_name).unwrap(), SuperAdmin);
+
+        let _ = client.write_db(db_name, "location", "value").unwrap();
+        let read_response = client.read_db(db_name, "location").unwrap();
+        assert_eq!(read_response.as_option().unwrap(), "value");
+        assert_eq!(
+            other_client.read_db(db_name, "location").unwrap_err(),
+            DBResponseError(InvalidPermissions)
+        );
+        assert_eq!(
+            client.list_db_contents(db_name).unwrap(),
+            HashMap::from([("location".to_string(), "value".to_string())])
+        );
+
+        let _ = client.delete_data(db_name, "location").unwrap();
+        assert_eq!(
+            client.read_db(db_name, "location").unwrap_err(),
+            DBResponseError(ValueNotFound)
+        );
+
+        let new_settings = DBSettings::new(
+            Duration::from_secs(10),
+            (true, false, true),
+            (false, false, false),
+            vec![],
+            vec![],
+        );
+        let _ = client
+            .set_db_settings(db_name, new_settings.clone())
+            .unwrap();
+        assert_eq!(client.get_db_settings(db_name).unwrap(), new_settings);
+
+        let delete_db_response = client.delete_db(db_name).unwrap();
+        assert_eq!(delete_db_response, SuccessNoData);
+        assert!(client.list_db().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_open_streams() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+        let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+        let db_name = "test_open_streams";
+        let _ = client.delete_db(db_name);
+        let _ = client.create_db(db_name, DBSettings::default()).unwrap();
+
+        for i in 0..5 {
+            let _ = client
+                .write_db(db_name, &i.to_string(), &i.to_string())
+                .unwrap();
+        }
+
+        // two streams are read in turn, writing to the db in between, which does not change what is already streaming
+        let first = client.open_stream(db_name).unwrap();
+        let second = client.open_stream(db_name).unwrap();
+        assert_ne!(first, second);
+
+        let mut first_items = vec![];
+        let mut second_items = vec![];
+        for i in 0..5 {
+            first_items.push(client.next_stream_item(&first).unwrap().unwrap());
+            let _ = client.write_db(db_name, &format!("new{i}"), "new").unwrap();
+            second_items.push(client.next_stream_item(&second).unwrap().unwrap());
+        }
+        assert_eq!(client.next_stream_item(&first).unwrap(), None);
+        assert_eq!(client.next_stream_item(&second).unwrap(), None);
+
+        first_items.sort();
+        second_items.sort();
+        assert_eq!(first_items, second_items);
+        for i in 0..5 {
+            assert!(first_items.contains(&(i.to_string(), i.to_string())));
+        }
+
+        // a stream is closed once read to its end
+        assert_eq!(
+            client.next_stream_item(&first).unwrap_err(),
+            DBResponseError(StreamNotFound)
+        );
+
+        let third = client.open_stream(db_name).unwrap();
+        assert_eq!(client.close_stream(third).unwrap(), SuccessNoData);
+        assert_eq!(
+            client.close_stream(third).unwrap_err(),
+            DBResponseError(StreamNotFound)
+        );
+
+        // each connection has its own streams
+        let mut other_client = SmolDbClient::new(server.address()).unwrap();
+        let _ = other_client
+            .set_access_key("test_key_123".to_string())
+            .unwrap();
+        let fourth = client.open_stream(db_name).unwrap();
+        assert_eq!(
+            other_client.next_stream_item(&fourth).unwrap_err(),
+            DBResponseError(StreamNotFound)
+        );
+
+        let _ = client.delete_db(db_name).unwrap();
+    }
+
+    #[test]
+    fn test_client_error_classes() {
+        let server = spawn_server();
+        fn read_missing_db(address: &str) -> Result<String, Box<dyn std::error::Error>> {
+            let mut client = SmolDbClient::new(address)?;
+            let _ = client.set_access_key("test_key_123".to_string())?;
+            Ok(client
+                .read_db("test_client_error_classes_missing", "key")?
+                .into_option()
+                .unwrap_or_default())
+        }
+
+        let err = read_missing_db(server.address()).unwrap_err();
+        let err = err.downcast_ref::<client_error::ClientError>().unwrap();
+        assert_eq!(err, &DBResponseError(DBNotFound));
+        assert!(!err.is_retryable());
+        assert!(!err.is_permission_denied());
+        assert_eq!(
+            err.to_string(),
+            "the database does not exist, check its name or create it first"
+        );
+        assert!(std::error::Error::source(err).is_some());
+
+        let rolled_back = DBResponseError(TransactionRolledBack {
+            op_index: 2,
+            error: Box::new(InvalidPermissions),
+        });
+        assert!(rolled_back.is_permission_denied());
+        assert_eq!(
+            rolled_back.to_string(),
+            "the transaction was rolled back, operation 2 failed: the access key lacks the permissions for this operation"
+        );
+
+        assert!(client_error::ClientError::SocketReadError(
+            std::io::ErrorKind::ConnectionReset.into()
+        )
+        .is_retryable());
+        assert!(DBResponseError(RateLimited).is_retryable());
+        assert!(!DBResponseError(QuotaExceeded).is_retryable());
+    }
+
+    #[test]
+    fn test_claim_super_admin() {
+        use smol_db_test_harness::{spawn_unclaimed_server, BOOTSTRAP_TOKEN};
+
+        let server = spawn_unclaimed_server();
+
+        // setting the token as the access key does not make the client a super admin, nor can it be claimed for the token itself
+        let mut token_client = SmolDbClient::new(server.address()).unwrap();
+        let _ = token_client
+            .set_access_key(BOOTSTRAP_TOKEN.to_string())
+            .unwrap();
+        assert_eq!(
+            token_client.create_db("test_claim_super_admin", DBSettings::default()),
+            Err(DBResponseError(InvalidPermissions))
+        );
+        assert_eq!(
+            token_client.claim_super_admin(BOOTSTRAP_TOKEN),
+            Err(DBResponseError(InvalidPermissions))
+        );
+
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+        let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(
+            client.claim_super_admin("wrong_token"),
+            Err(DBResponseError(InvalidPermissions))
+        );
+        assert_eq!(
+            client.claim_super_admin(BOOTSTRAP_TOKEN).unwrap(),
+            SuccessNoData
+        );
+        assert_eq!(
+            client
+                .create_db("test_claim_super_admin", DBSettings::default())
+                .unwrap(),
+            SuccessNoData
+        );
+
+        // the token is used up once claimed
+        let mut other_client = SmolDbClient::new(server.address()).unwrap();
+        let _ = other_client
+            .set_access_key("test_claim_super_admin_other".to_string())
+            .unwrap();
+        assert_eq!(
+            other_client.claim_super_admin(BOOTSTRAP_TOKEN),
+            Err(DBResponseError(InvalidPermissions))
+        );
+    }
+
+    #[test]
+    fn test_set_key_rate_limited() {
+        let server = spawn_server();
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+
+        // each new key is hashed, which a client can only do a few times a second
+        let responses: Vec<_> = (0..20)
+            .map(|i| client.set_access_key(format!("test_set_key_rate_limited_{i}")))
+            .collect();
+        assert!(responses
+            .iter()
+            .any(|resp| resp == &Err(DBResponseError(RateLimited))));
+
+        // a key set earlier in the session is not hashed again
+        assert_eq!(
+            client
+                .set_access_key("test_set_key_rate_limited_0".to_string())
+                .unwrap(),
+            SuccessNoData
+        );
+
+        // the limit is for each connection
+        let mut other_client = SmolDbClient::new(server.address()).unwrap();
+        assert_eq!(
+            other_client
+                .set_access_key("test_key_123".to_string())
+                .unwrap(),
+            SuccessNoData
+        );
+    }
+
+    #[test]
+    fn test_offline_queue() {
+        let server = spawn_server();
+        use smol_db_client::offline_queue::{ConflictResolution, QueuedWrite, ReplayReport};
+
+        let db_name = "test_offline_queue";
+        let queue_path = std::env::temp_dir().join("smol_db_test_offline_queue.json");
+        let _ = std::fs::remove_file(&queue_path);
+
+        let mut client = SmolDbClient::new(server.address()).unwrap();
+        let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+        let _ = client.delete_db(db_name);
+        let _ = client.create_db(db_name, DBSettings::default()).unwrap();
+        let _ = client.write_db(db_name, "changed", "old").unwrap();
+        let _ = client.write_db(db_name, "removed", "value").unwrap();
+
+        client.set_offline_queue(OfflineQueue::load(&queue_path, 4).unwrap());
+        // writes go straight to the server while it can be reached
+        assert_eq!(
+            client.write_db(db_name, "online", "value").unwrap(),
+            SuccessNoData
+        );
+        assert!(client.offline_queue().unwrap().is_empty());
+
+        client.disconnect().unwrap();
+        let _ = client.write_db(db_name, "changed", "offline").unwrap();
+        let _ = client.write_db(db_name, "skipped", "offline").unwrap();
+        let _ = client.delete_data(db_name, "removed").unwrap();
+        let _ = client
+            .write_db("test_offline_queue_missing", "key", "value")
+            .unwrap();
+        assert_eq!(
+            client.write_db(db_name, "full", "value").unwrap_err(),
+            client_error::ClientError::OfflineQueueFull
+        );
+
+        // the queue is saved to its file, so it survives the client going away
+        let queue = OfflineQueue::load(&queue_path, 4).unwrap();
+        assert_eq!(queue, *client.offline_queue().unwrap());
+        assert_eq!(queue.len(), 4);
+
+        // another client changes a key while this one is disconnected
+        let mut other_client = SmolDbClient::new(server.address()).unwrap();
+        let _ = other_client
+            .set_access_key("test_key_123".to_string())
+            .unwrap();
+        let _ = other_client.write_db(db_name, "changed", "other").unwrap();
+
+        client.reconnect().unwrap();
+        let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+        let policy = |write: &QueuedWrite, server_value: Option<&str>| match write.key.as_str() {
+            "skipped" => ConflictResolution::Skip,
+            "changed" => ConflictResolution::Replace(Some(format!(
+                "{}+{}",
+                server_value.unwrap_or_default(),
+                write.value.as_deref().unwrap_or_default()
+            ))),
+            _ => ConflictResolution::Apply,
+        };
+        assert_eq!(
+            client.replay_offline_queue(&policy).unwrap(),
+            ReplayReport {
+                applied: 2,
+                skipped: 1,
+                rejected: vec![(
+                    QueuedWrite {
+                        db_name: "test_offline_queue_missing".to_string(),
+                        key: "key".to_string(),
+                        value: Some("value".to_string()),
+                    },
+                    DBNotFound
+                )],
+            }
+        );
+        assert!(client.offline_queue().unwrap().is_empty());
+        assert!(OfflineQueue::load(&queue_path, 4).unwrap().is_empty());
+
+        assert_eq!(
+            client.read_db(db_name, "changed").unwrap(),
+            SuccessReply("other+offline".to_string())
+        );
+        assert_eq!(
+            client.read_db(db_name, "skipped").unwrap_err(),
+            DBResponseError(ValueNotFound)
+        );
+        assert_eq!(
+            client.read_db(db_name, "removed").unwrap_err(),
+            DBResponseError(ValueNotFound)
+        );
+
+        let _ = client.delete_db(db_name).unwrap();
+        let _ = std::fs::remove_file(&queue_path);
+    }
+
+    #[test]
+    #[cfg(feature = "test-utils")]
+    fn test_mock_client() {
+        let mut client = MockSmolDbClient::new();
+        let db_name = "test_mock_client";
+
+        let set_key_response = client.set_access_key("test_key_123".to_string()).unwrap();
+        assert_eq!(set_key_response, SuccessNoData);
+        assert_eq!(client.db_exists(db_name).unwrap(), false);
+        let create_response = client.create_db(db_name, DBSettings::default()).unwrap();
+        assert_eq!(create_response, SuccessNoData);
+        assert_eq!(client.db_exists(db_name).unwrap(), true);
+        assert_eq!(client.get_role(db_name).unwrap(), SuperAdmin);
+
+        assert_eq!(client.write_db(db_name, "a", "1").unwrap(), SuccessNoData);
+        assert_eq!(
+            client.write_db(db_name, "a", "2").unwrap(),
+            SuccessReply("1".to_string())
+        );
+        assert_eq!(client.key_exists(db_name, "a").unwrap(), true);
+        assert_eq!(
+            client.compare_and_swap(db_name, "a", Some("1"), "3"),
+            Err(DBResponseError(ValueMismatch(Some("2".to_string()))))
+        );
+        let _ = client
+            .compare_and_swap(db_name, "a", Some("2"), "3")
+            .unwrap();
+        assert_eq!(client.increment(db_name, "count", 5).unwrap(), 5);
+        assert_eq!(client.decrement(db_name, "count", 2).unwrap(), 3);
+        assert_eq!(
+            client.write_db_if_absent(db_name, "count", "0"),
+            Err(DBResponseError(ValueAlreadyExists))
+        );
+        assert_eq!(client.get_db_len(db_name).unwrap(), 2);
+
+        let previous = client
+            .write_many(db_name, &[("a", "4"), ("b", "5")])
+            .unwrap();
+        assert_eq!(previous, vec![Some("3".to_string()), None]);
+        assert_eq!(
+            client.read_many(db_name, &["a", "b", "c"]).unwrap(),
+            vec![Some("4".to_string()), Some("5".to_string()), None]
+        );
+        let mut keys = client.list_db_keys(db_name).unwrap();
+        keys.sort();
+        assert_eq!(keys, vec!["a", "b", "count"]);
+
+        assert_eq!(client.set(db_name, "user:1", &10u32).unwrap(), None);
+        assert_eq!(client.get::<u32>(db_name, "user:1").unwrap(), Some(10));
+        assert_eq!(client.read_prefix(db_name, "user:").unwrap().len(), 1);
+        assert_eq!(
+            client.remove(db_name, "user:1").unwrap(),
+            Some("10".to_string())
+        );
+        assert_eq!(client.get::<u32>(db_name, "user:1").unwrap(), None);
+
+        // a second client sharing the db list, whose key has no permissions in the db
+        let mut other_client = MockSmolDbClient::with_db_list(client.get_db_list());
+        let _ = other_client
+            .set_access_key("other_key".to_string())
+            .unwrap();
+        assert_eq!(
+            other_client.read_db(db_name, "a"),
+            Err(DBResponseError(InvalidPermissions))
+        );
+        assert_eq!(
+            other_client.create_db("test_mock_client_2", DBSettings::default()),
+            Err(DBResponseError(InvalidPermissions))
+        );
+
+        let _ = client.clear_db(db_name).unwrap();
+        assert_eq!(client.get_db_len(db_name).unwrap(), 0);
+        let _ = client.delete_db(db_name).unwrap();
+        assert_eq!(
+            client.read_db(db_name, "a"),
+            Err(DBResponseError(DBNotFound))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "test-utils")]
+    fn test_mock_client_collections() {
+        let mut client = MockSmolDbClient::new();
+        let db_name = "test_mock_client_collections";
+
+        let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+        let _ = client.create_db(db_name, DBSettings::default()).unwrap();
+
+        assert_eq!(client.list_push(db_name, "list", "a").unwrap(), 1);
+        assert_eq!(client.list_push(db_name, "list", "c").unwrap(), 2);
+        assert_eq!(client.list_insert(db_name, "list", 1, "b").unwrap(), 3);
+        assert_eq!(
+            client.list_get(db_name, "list", 1).unwrap(),
+            SuccessReply("b".to_string())
+        );
+        assert_eq!(
+            client.list_get(db_name, "list", 3),
+            Err(DBResponseError(IndexOutOfBounds))
+        );
+        assert_eq!(
+            client
+                .stream_list(db_name, "list")
+                .unwrap()
+                .collect::<Vec<_>>(),
+            vec!["a", "b", "c"]
+        );
+        assert_eq!(
+            client.list_remove(db_name, "list", 0).unwrap(),
+            SuccessReply("a".to_string())
+        );
+        let _ = client.list_clear(db_name, "list").unwrap();
+        assert_eq!(client.list_len(db_name, "list").unwrap(), 0);
+
+        assert_eq!(client.set_add(db_name, "set", "x").unwrap(), true);
+        assert_eq!(client.set_add(db_name, "set", "x").unwrap(), false);
+        assert_eq!(client.set_contains(db_name, "set", "x").unwrap(), true);
+        assert_eq!(client.set_members(db_name, "set").unwrap(), vec!["x"]);
+        assert_eq!(client.set_remove(db_name, "set", "x").unwrap(), true);
+        assert_eq!(client.set_contains(db_name, "set", "x").unwrap(), false);
+
+        assert_eq!(
+            client.sorted_add(db_name, "scores", "bob", 20.0).unwrap(),
+            0
+        );
+        assert_eq!(
+            client.sorted_add(db_name, "scores", "alice", 10.0).unwrap(),
+            0
+        );
+        assert_eq!(
+            client.sorted_rank(db_name, "scores", "bob").unwrap(),
+            Some(1)
+        );
+        assert_eq!(
+            client.sorted_top(db_name, "scores", 1).unwrap(),
+            vec![ScoredMember::new("bob".to_string(), 20.0)]
+        );
+        assert_eq!(
+            client
+                .sorted_range_by_score(db_name, "scores", 0.0, 15.0)
+                .unwrap(),
+            vec![ScoredMember::new("alice".to_string(), 10.0)]
+        );
+        assert_eq!(
+            client.sorted_remove(db_name, "scores", "bob").unwrap(),
+            true
+        );
+        assert_eq!(client.sorted_rank(db_name, "scores", "bob").unwrap(), None);
+
+        assert_eq!(
+            client.write_db_bytes(db_name, "blob", &[1, 2]).unwrap(),
+            None
+        );
+        assert_eq!(
+            client.write_db_bytes(db_name, "blob", &[3]).unwrap(),
+            Some(vec![1, 2])
+        );
+        assert_eq!(client.read_db_bytes(db_name, "blob").unwrap(), vec![3]);
+
+        let _ = client.write_db(db_name, "user:1", "active").unwrap();
+        let _ = client.write_db(db_name, "user:2", "inactive").unwrap();
+        let mut pairs = client
+            .stream_table(db_name)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        pairs.sort();
+        assert_eq!(
+            pairs,
+            vec![
+                ("user:1".to_string(), "active".to_string()),
+                ("user:2".to_string(), "inactive".to_string())
+            ]
+        );
+        let inactive_users = client
+            .stream_table_filtered(db_name, "user:", "inactive")
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            inactive_users,
+            vec![("user:2".to_string(), "inactive".to_string())]
+        );
+        assert_eq!(client.stream_db_contents(db_name).unwrap().len(), 2);
+
+        assert_eq!(client.queue_push(db_name, "jobs", "job1").unwrap(), 1);
+        assert_eq!(
+            client.queue_pop(db_name, "jobs").unwrap(),
+            Some("job1".to_string())
+        );
+        assert_eq!(client.queue_pop(db_name, "jobs").unwrap(), None);
+        assert_eq!(
+            client
+                .queue_pop_blocking(db_name, "jobs", Duration::from_millis(50))
+                .unwrap(),
+            None
+        );
+
+        // a value pushed by another mock client on another thread wakes the waiting client
+        let mut pusher = MockSmolDbClient::with_db_list(client.get_db_list());
+        let _ = pusher.set_access_key("test_key_123".to_string()).unwrap();
+        let push_thread = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            pusher.queue_push(db_name, "jobs", "job2").unwrap()
+        });
+        assert_eq!(
+            client
+                .queue_pop_blocking(db_name, "jobs", Duration::from_secs(5))
+                .unwrap(),
+            Some("job2".to_string())
+        );
+        assert_eq!(push_thread.join().unwrap(), 1);
+
+        let _ = client.delete_db(db_name).unwrap();
+    }
 }
@@ -0,0 +1,194 @@
+//! Measures the requests per second of clients that each work on their own database,
+//! alone and while another client creates and deletes databases and loads a large database that is not cached.
+//! Requests to different databases should not wait on each other, so the second run should be close to the first.
+//!
+//! Run with `cargo bench -p smol_db_common --bench concurrent_access`
+use smol_db_common::prelude::*;
+use smol_db_common::write_ahead_log::WriteAheadLog;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const SUPER_ADMIN_KEY: &str = "bench_admin_key";
+const CLIENTS: usize = 8;
+const KEYS_PER_CLIENT: usize = 1_000;
+const LARGE_DB_KEYS: usize = 200_000;
+const RUN_TIME: Duration = Duration::from_secs(5);
+
+struct RunResult {
+    requests: usize,
+    slowest: Duration,
+    churn_loops: usize,
+}
+
+fn settings(invalidation_time: Duration) -> DBSettings {
+    DBSettings::new(
+        invalidation_time,
+        (true, true, true),
+        (true, true, true),
+        vec![],
+        vec![],
+    )
+}
+
+fn client_db_name(client: usize) -> String {
+    format!("bench_client_{}", client)
+}
+
+fn setup(data_dir: &PathBuf) -> DBList {
+    let _ = fs::remove_dir_all(data_dir);
+    fs::create_dir_all(data_dir).unwrap();
+
+    let db_list = DBList {
+        data_dir: data_dir.clone(),
+        wal: WriteAheadLog::new(data_dir),
+        ..Default::default()
+    };
+    db_list
+        .super_admin_hash_list
+        .write()
+        .unwrap()
+        .push(SUPER_ADMIN_KEY.to_string());
+    let admin_key = SUPER_ADMIN_KEY.to_string();
+
+    for client in 0..CLIENTS {
+        let db_name = client_db_name(client);
+        db_list
+            .create_db(&db_name, settings(Duration::from_secs(600)), &admin_key)
+            .unwrap();
+        for key in 0..KEYS_PER_CLIENT {
+            db_list
+                .write_db(
+                    &DBPacketInfo::new(&db_name),
+                    &DBLocation::new(&key.to_string()),
+                    &DBData::new("value".to_string()),
+                    &admin_key,
+                )
+                .unwrap();
+        }
+    }
+
+    // removed from the cache by every call to sleep_caches, so each read of it loads it from its file
+    db_list
+        .create_db(
+            "bench_large",
+            settings(Duration::from_millis(1)),
+            &admin_key,
+        )
+        .unwrap();
+    let large_value = "v".repeat(100);
+    for key in 0..LARGE_DB_KEYS {
+        db_list
+            .write_db(
+                &DBPacketInfo::new("bench_large"),
+                &DBLocation::new(&key.to_string()),
+                &DBData::new(large_value.clone()),
+                &admin_key,
+            )
+            .unwrap();
+    }
+    db_list.checkpoint_wal();
+    db_list
+}
+
+fn run(db_list: &Arc<DBList>, churn: bool) -> RunResult {
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let clients: Vec<_> = (0..CLIENTS)
+        .map(|client| {
+            let db_list = db_list.clone();
+            let stop = stop.clone();
+            thread::spawn(move || {
+                let admin_key = SUPER_ADMIN_KEY.to_string();
+                let db_info = DBPacketInfo::new(&client_db_name(client));
+                let mut requests = 0;
+                let mut slowest = Duration::ZERO;
+                while !stop.load(Ordering::Relaxed) {
+                    let location = DBLocation::new(&(requests % KEYS_PER_CLIENT).to_string());
+                    let start = Instant::now();
+                    if requests % 4 == 0 {
+                        db_list
+                            .write_db(
+                                &db_info,
+                                &location,
+                                &DBData::new("value".to_string()),
+                                &admin_key,
+                            )
+                            .unwrap();
+                    } else {
+                        db_list.read_db(&db_info, &location, &admin_key).unwrap();
+                    }
+                    slowest = slowest.max(start.elapsed());
+                    requests += 1;
+                }
+                (requests, slowest)
+            })
+        })
+        .collect();
+
+    let churner = churn.then(|| {
+        let db_list = db_list.clone();
+        let stop = stop.clone();
+        thread::spawn(move || {
+            let admin_key = SUPER_ADMIN_KEY.to_string();
+            let mut loops = 0;
+            while !stop.load(Ordering::Relaxed) {
+                db_list
+                    .get_db_len(&DBPacketInfo::new("bench_large"), &admin_key)
+                    .unwrap();
+                thread::sleep(Duration::from_millis(2));
+                db_list.sleep_caches();
+                db_list
+                    .create_db(
+                        "bench_churn",
+                        settings(Duration::from_secs(600)),
+                        &admin_key,
+                    )
+                    .unwrap();
+                db_list.delete_db("bench_churn", &admin_key).unwrap();
+                loops += 1;
+            }
+            loops
+        })
+    });
+
+    thread::sleep(RUN_TIME);
+    stop.store(true, Ordering::Relaxed);
+
+    let mut result = RunResult {
+        requests: 0,
+        slowest: Duration::ZERO,
+        churn_loops: churner.map_or(0, |churner| churner.join().unwrap()),
+    };
+    for client in clients {
+        let (requests, slowest) = client.join().unwrap();
+        result.requests += requests;
+        result.slowest = result.slowest.max(slowest);
+    }
+    result
+}
+
+fn report(name: &str, result: &RunResult) {
+    println!(
+        "{:<28} {:>10.0} requests/s, slowest request {:>9.2?}, {} churn loops",
+        name,
+        result.requests as f64 / RUN_TIME.as_secs_f64(),
+        result.slowest,
+        result.churn_loops
+    );
+}
+
+fn main() {
+    let data_dir = PathBuf::from("./data/bench_concurrent_access");
+    let db_list = Arc::new(setup(&data_dir));
+
+    println!("{} clients, each on their own database", CLIENTS);
+    report("alone", &run(&db_list, false));
+    report("with create, delete, load", &run(&db_list, true));
+
+    let _ = db_list.purge_trash(&SUPER_ADMIN_KEY.to_string());
+    fs::remove_dir_all(&data_dir).unwrap();
+}
@@ -0,0 +1,57 @@
+//! Contains `ChannelRegistry`, which broadcasts messages published to a named channel to every subscriber of that channel.
+use std::collections::HashMap;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::RwLock;
+use tracing::debug;
+
+#[derive(Debug, Default)]
+/// `ChannelRegistry` holds the subscribers of every pub/sub channel on the server.
+/// Channels are independent of databases, they exist as long as they have a subscriber.
+pub struct ChannelRegistry {
+    channels: RwLock<HashMap<String, Vec<Sender<String>>>>,
+}
+
+impl ChannelRegistry {
+    /// Subscribes to the given channel, returning the receiving end of the subscription.
+    #[tracing::instrument(skip(self))]
+    pub fn subscribe(&self, channel_name: &str) -> Receiver<String> {
+        let (sender, receiver) = channel();
+        self.channels
+            .write()
+            .unwrap()
+            .entry(channel_name.to_string())
+            .or_default()
+            .push(sender);
+        receiver
+    }
+
+    /// Sends the message to every subscriber of the channel, returning how many subscribers received it.
+    /// Subscribers whose receiver was dropped are removed, and the channel is removed once it has no subscribers.
+    #[tracing::instrument(skip(self))]
+    pub fn publish(&self, channel_name: &str, message: &str) -> usize {
+        let mut channels = self.channels.write().unwrap();
+
+        let Some(subscribers) = channels.get_mut(channel_name) else {
+            return 0;
+        };
+
+        subscribers.retain(|sender| sender.send(message.to_string()).is_ok());
+        let received_count = subscribers.len();
+
+        if subscribers.is_empty() {
+            debug!("Removing channel with no subscribers: {}", channel_name);
+            channels.remove(channel_name);
+        }
+
+        received_count
+    }
+
+    /// Returns the number of subscribers to the given channel
+    pub fn subscriber_count(&self, channel_name: &str) -> usize {
+        self.channels
+            .read()
+            .unwrap()
+            .get(channel_name)
+            .map_or(0, Vec::len)
+    }
+}
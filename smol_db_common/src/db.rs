@@ -1,7 +1,12 @@
 //! Contains the struct that represents specific databases.
 use crate::db::Role::{Admin, Other, SuperAdmin, User};
 use crate::db_content::DBContent;
+use crate::db_packets::db_metadata::DBMetadata;
+use crate::db_packets::db_packet_response::DBPacketResponseError;
 use crate::db_packets::db_settings::DBSettings;
+use crate::db_packets::effective_permissions::EffectivePermissions;
+use crate::db_packets::permissions::Permission;
+use crate::db_packets::storage_quota::StorageQuota;
 #[cfg(feature = "statistics")]
 use crate::statistics::DBStatistics;
 use serde::{Deserialize, Serialize};
@@ -18,6 +23,18 @@ pub struct DB {
     #[serde(default)]
     #[cfg(feature = "statistics")]
     statistics: DBStatistics,
+    #[serde(default)]
+    /// Description, tags and history of the db
+    metadata: DBMetadata,
+    #[serde(skip)]
+    /// Number of changes made to the content or settings since the db was last saved, access times are not counted
+    unsaved_changes: u64,
+    #[serde(skip)]
+    /// Bytes the db took up when it was last saved or loaded, `None` until then
+    serialized_size: Option<usize>,
+    #[serde(skip)]
+    /// True once the db was removed from the cache, changes to it after that would be lost
+    evicted: bool,
 }
 
 #[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Copy, Eq)]
@@ -45,6 +62,10 @@ impl Default for DB {
             db_settings: DBSettings::default(),
             #[cfg(feature = "statistics")]
             statistics: DBStatistics::default(),
+            metadata: DBMetadata::default(),
+            unsaved_changes: 0,
+            serialized_size: None,
+            evicted: false,
         }
     }
 }
@@ -52,10 +73,12 @@ impl Default for DB {
 impl DB {
     #[tracing::instrument]
     pub fn new_from_settings(db_settings: DBSettings) -> Self {
-        Self {
+        let mut db = Self {
             db_settings,
             ..Default::default()
-        }
+        };
+        db.db_content.set_sorted(db.db_settings.has_sorted_keys());
+        db
     }
 
     #[tracing::instrument(skip(self))]
@@ -65,19 +88,152 @@ impl DB {
 
     #[tracing::instrument(skip(self))]
     pub fn get_settings_mut(&mut self) -> &mut DBSettings {
+        self.mark_modified();
         &mut self.db_settings
     }
 
+    /// Returns the description, tags and history of the db.
+    #[tracing::instrument(skip(self))]
+    pub fn get_metadata(&self) -> &DBMetadata {
+        &self.metadata
+    }
+
+    /// Replaces the metadata of the db, which counts as an unsaved change but not as a modification of the db.
+    #[tracing::instrument(skip(self))]
+    pub fn set_metadata(&mut self, metadata: DBMetadata) {
+        self.unsaved_changes += 1;
+        self.metadata = metadata;
+    }
+
+    /// Counts an unsaved change, and records the current time as the time the db was last modified.
+    fn mark_modified(&mut self) {
+        self.unsaved_changes += 1;
+        self.metadata.modified = Some(SystemTime::now());
+    }
+
+    /// Returns `QuotaExceeded` if adding the entries and bytes would take the db past its own quota, or the server quota, whichever is stricter.
+    /// Changes that add nothing are always allowed, so a db that is already past a lowered quota can still be shrunk.
+    #[tracing::instrument(skip(self))]
+    pub fn check_quota(
+        &self,
+        server_quota: StorageQuota,
+        added_entries: usize,
+        added_bytes: usize,
+    ) -> Result<(), DBPacketResponseError> {
+        let quota = self.db_settings.get_quota().min(server_quota);
+
+        if let Some(max_entries) = quota.max_entries {
+            if added_entries > 0 && self.db_content.entry_count() + added_entries > max_entries {
+                return Err(DBPacketResponseError::QuotaExceeded);
+            }
+        }
+
+        if let Some(max_bytes) = quota.max_bytes {
+            if added_bytes > 0 && self.db_content.size_in_bytes() + added_bytes > max_bytes {
+                return Err(DBPacketResponseError::QuotaExceeded);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns an error if the key, or a value of the given size in bytes, is past the key limits of the db, see `KeyLimits`.
+    #[tracing::instrument(skip(self))]
+    pub fn check_key_limits(
+        &self,
+        key: &str,
+        value_size: usize,
+    ) -> Result<(), DBPacketResponseError> {
+        self.db_settings.get_key_limits().check(key, value_size)
+    }
+
+    /// Returns `SchemaViolation` if the db has a schema, and the value does not match it.
+    #[tracing::instrument(skip(self))]
+    pub fn check_schema(&self, value: &str) -> Result<(), DBPacketResponseError> {
+        match self.db_settings.get_schema() {
+            Some(schema) => schema.validate(value),
+            None => Ok(()),
+        }
+    }
+
     #[tracing::instrument(skip(self))]
     pub fn set_settings(&mut self, new_settings: DBSettings) {
+        self.mark_modified();
         self.db_settings = new_settings;
+        self.db_content
+            .set_sorted(self.db_settings.has_sorted_keys());
     }
 
+    /// Returns the content to be changed, which counts as an unsaved change.
+    /// The content keeps as many revisions of each key as the settings allow, and is kept ordered by key if the settings ask for it.
+    /// A change that may leave the content as it was, such as deleting a key that may not exist, should use `change_content` instead.
     #[tracing::instrument(skip(self))]
     pub fn get_content_mut(&mut self) -> &mut DBContent {
+        self.mark_modified();
+        self.content_to_change()
+    }
+
+    /// Runs the change on the content, returning its result.
+    /// The change returns whether it changed the content along with its result, and only counts as an unsaved change if it did,
+    /// so a db is not saved again after a request that left it as it was.
+    #[tracing::instrument(skip(self, change))]
+    pub fn change_content<T>(&mut self, change: impl FnOnce(&mut DBContent) -> (T, bool)) -> T {
+        let (result, changed) = change(self.content_to_change());
+        if changed {
+            self.mark_modified();
+        }
+        result
+    }
+
+    /// Returns the content with the revision and ordering settings of the db applied, as they may have changed since the content was last changed.
+    fn content_to_change(&mut self) -> &mut DBContent {
+        self.db_content
+            .history
+            .set_max_revisions(self.db_settings.get_max_revisions());
+        self.db_content
+            .set_sorted(self.db_settings.has_sorted_keys());
         &mut self.db_content
     }
 
+    /// Returns the number of times the content or settings were changed since the db was last saved.
+    pub const fn unsaved_changes(&self) -> u64 {
+        self.unsaved_changes
+    }
+
+    /// Returns true if the db has changes that have not been saved.
+    pub const fn is_dirty(&self) -> bool {
+        self.unsaved_changes > 0
+    }
+
+    /// Marks every change to the db as saved.
+    pub fn mark_saved(&mut self) {
+        self.unsaved_changes = 0;
+    }
+
+    /// Marks the db as removed from the cache, a request that looked up the db before it was removed has to look it up again.
+    pub fn mark_evicted(&mut self) {
+        self.evicted = true;
+    }
+
+    /// Returns true if the db was removed from the cache.
+    pub const fn is_evicted(&self) -> bool {
+        self.evicted
+    }
+
+    /// Records the number of bytes the db took up when it was saved or loaded.
+    pub fn set_serialized_size(&mut self, serialized_size: usize) {
+        self.serialized_size = Some(serialized_size);
+    }
+
+    /// Returns the approximate number of bytes the db takes up, which is what the cache memory budget of the server limits.
+    /// This is the size the db was last saved or loaded with, or the size of its keys and values when it has changed since.
+    pub fn approximate_size(&self) -> usize {
+        match self.serialized_size {
+            Some(serialized_size) if !self.is_dirty() => serialized_size,
+            _ => self.db_content.size_in_bytes(),
+        }
+    }
+
     #[tracing::instrument(skip(self))]
     pub fn get_content(&self) -> &DBContent {
         &self.db_content
@@ -89,6 +245,24 @@ impl DB {
         &self.statistics
     }
 
+    /// Clears the statistics of the db, which counts as an unsaved change so the reset is kept.
+    #[cfg(feature = "statistics")]
+    #[tracing::instrument(skip(self))]
+    pub fn reset_statistics(&mut self) {
+        self.unsaved_changes += 1;
+        self.statistics = DBStatistics::default();
+    }
+
+    /// Counts an access of the given key in the statistics, when the db settings track key hits.
+    #[allow(unused_variables)]
+    #[tracing::instrument(skip(self))]
+    pub fn record_key_hit(&mut self, key: &str) {
+        #[cfg(feature = "statistics")]
+        if self.db_settings.track_key_hits {
+            self.statistics.add_key_hit(key);
+        }
+    }
+
     #[tracing::instrument(skip(self))]
     pub fn update_access_time(&mut self) {
         info!("Updating access time of database to now");
@@ -123,36 +297,156 @@ impl DB {
         client_role
     }
 
-    /// Returns true if the given key has list permissions
-    /// Checks which role the user might fit into depending on `DBSettings`
+    /// Returns true if the given key has the permission on the whole db
+    /// Checks which role the user might fit into depending on `DBSettings`, admins have every permission, and a key limited by key acl rules has no db wide permissions
     #[tracing::instrument(skip(self, super_admin_list))]
-    pub fn has_list_permissions(&self, client_key: &String, super_admin_list: &[String]) -> bool {
+    pub fn has_permission(
+        &self,
+        permission: Permission,
+        client_key: &String,
+        super_admin_list: &[String],
+    ) -> bool {
         match self.get_role(client_key, super_admin_list) {
             Admin | SuperAdmin => true,
-            User => self.db_settings.get_user_rwx().2,
-            Other => self.db_settings.get_other_rwx().2,
+            _ if self.has_key_acls(client_key) => false,
+            User => self.db_settings.get_user_permissions().contains(permission),
+            Other => self
+                .db_settings
+                .get_other_permissions()
+                .contains(permission),
         }
     }
 
-    /// Returns true if the given key has read permissions
-    /// Checks which role the user might fit into depending on `DBSettings`
+    /// Returns the role and permissions the given key resolves to in the db, along with the key acl rules that limit it.
+    /// Admins and super admins are not limited by rules, so none are returned for them
     #[tracing::instrument(skip(self, super_admin_list))]
-    pub fn has_read_permissions(&self, client_key: &String, super_admin_list: &[String]) -> bool {
-        match self.get_role(client_key, super_admin_list) {
-            Admin | SuperAdmin => true,
-            User => self.db_settings.get_user_rwx().0,
-            Other => self.db_settings.get_other_rwx().0,
+    pub fn get_effective_permissions(
+        &self,
+        client_key: &String,
+        super_admin_list: &[String],
+    ) -> EffectivePermissions {
+        let role = self.get_role(client_key, super_admin_list);
+        let permissions = Permission::ALL
+            .into_iter()
+            .filter(|permission| match permission {
+                Permission::Stream => self.has_stream_permissions(client_key, super_admin_list),
+                _ => self.has_permission(*permission, client_key, super_admin_list),
+            })
+            .collect();
+        let key_acls = if role.is_admin() {
+            vec![]
+        } else {
+            self.db_settings
+                .get_key_acls_for(client_key)
+                .cloned()
+                .collect()
+        };
+
+        EffectivePermissions {
+            role,
+            permissions,
+            key_acls,
         }
     }
 
-    /// Returns true if the given key has write permissions
-    /// Checks which role the user might fit into depending on `DBSettings`
+    /// Returns true if the given key has list permissions
+    #[tracing::instrument(skip(self, super_admin_list))]
+    pub fn has_list_permissions(&self, client_key: &String, super_admin_list: &[String]) -> bool {
+        self.has_permission(Permission::List, client_key, super_admin_list)
+    }
+
+    /// Returns true if the given key has read permissions to the whole db
+    #[tracing::instrument(skip(self, super_admin_list))]
+    pub fn has_read_permissions(&self, client_key: &String, super_admin_list: &[String]) -> bool {
+        self.has_permission(Permission::Read, client_key, super_admin_list)
+    }
+
+    /// Returns true if the given key has write permissions to the whole db
     #[tracing::instrument(skip(self, super_admin_list))]
     pub fn has_write_permissions(&self, client_key: &String, super_admin_list: &[String]) -> bool {
-        match self.get_role(client_key, super_admin_list) {
-            Admin | SuperAdmin => true,
-            User => self.db_settings.get_user_rwx().1,
-            Other => self.db_settings.get_other_rwx().1,
+        self.has_permission(Permission::Write, client_key, super_admin_list)
+    }
+
+    /// Returns true if the given key can stream the whole db, which requires both read and stream permissions
+    #[tracing::instrument(skip(self, super_admin_list))]
+    pub fn has_stream_permissions(&self, client_key: &String, super_admin_list: &[String]) -> bool {
+        self.has_read_permissions(client_key, super_admin_list)
+            && self.has_permission(Permission::Stream, client_key, super_admin_list)
+    }
+
+    /// Returns true if the given key has read permissions to the entry with the given entry key
+    /// A key limited by key acl rules can read the entry if any of its rules allow reading it, otherwise the db wide permissions apply
+    #[tracing::instrument(skip(self, super_admin_list))]
+    pub fn has_key_read_permissions(
+        &self,
+        entry_key: &str,
+        client_key: &String,
+        super_admin_list: &[String],
+    ) -> bool {
+        if self.has_key_acls(client_key) && !super_admin_list.contains(client_key) {
+            return self.db_settings.is_admin(client_key)
+                || self
+                    .db_settings
+                    .get_key_acls_for(client_key)
+                    .any(|rule| rule.read && rule.matches(entry_key));
         }
+        self.has_read_permissions(client_key, super_admin_list)
+    }
+
+    /// Returns true if the given key has write permissions to the entry with the given entry key
+    /// A key limited by key acl rules can write the entry if any of its rules allow writing it, otherwise the db wide permissions apply
+    #[tracing::instrument(skip(self, super_admin_list))]
+    pub fn has_key_write_permissions(
+        &self,
+        entry_key: &str,
+        client_key: &String,
+        super_admin_list: &[String],
+    ) -> bool {
+        if self.has_key_acls(client_key) && !super_admin_list.contains(client_key) {
+            return self.db_settings.is_admin(client_key)
+                || self
+                    .db_settings
+                    .get_key_acls_for(client_key)
+                    .any(|rule| rule.write && rule.matches(entry_key));
+        }
+        self.has_write_permissions(client_key, super_admin_list)
+    }
+
+    /// Returns true if the given key has delete permissions to the entry with the given entry key
+    /// A key limited by key acl rules can delete the entry if any of its rules allow writing it, otherwise the db wide permissions apply
+    #[tracing::instrument(skip(self, super_admin_list))]
+    pub fn has_key_delete_permissions(
+        &self,
+        entry_key: &str,
+        client_key: &String,
+        super_admin_list: &[String],
+    ) -> bool {
+        if self.has_key_acls(client_key) && !super_admin_list.contains(client_key) {
+            return self.has_key_write_permissions(entry_key, client_key, super_admin_list);
+        }
+        self.has_permission(Permission::Delete, client_key, super_admin_list)
+    }
+
+    /// Returns true if the given key can stream the entry with the given entry key
+    /// A key limited by key acl rules can stream the entry if it can read it, otherwise both read and stream permissions are required
+    #[tracing::instrument(skip(self, super_admin_list))]
+    pub fn has_key_stream_permissions(
+        &self,
+        entry_key: &str,
+        client_key: &String,
+        super_admin_list: &[String],
+    ) -> bool {
+        if self.has_key_acls(client_key) && !super_admin_list.contains(client_key) {
+            return self.has_key_read_permissions(entry_key, client_key, super_admin_list);
+        }
+        self.has_stream_permissions(client_key, super_admin_list)
+    }
+
+    /// Returns true if any key acl rule applies to the given key
+    fn has_key_acls(&self, client_key: &str) -> bool {
+        self.db_settings
+            .get_key_acls_for(client_key)
+            .next()
+            .is_some()
     }
 }
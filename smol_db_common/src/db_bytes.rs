@@ -0,0 +1,64 @@
+//! Contains the struct for binary data points within a database, which are sent and stored as base64.
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Display, Formatter};
+
+/// The largest number of bytes sent in a single chunk when streaming a value, small enough that a base64 encoded chunk fits in a single packet.
+pub const STREAM_CHUNK_SIZE: usize = 512;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+/// A struct that contains binary data that is to be put into a database, such as an image or compressed data.
+/// Serialized as a base64 string, so it can contain any bytes including interior NULs.
+pub struct DBBytes {
+    data: Vec<u8>,
+}
+
+impl Display for DBBytes {
+    #[tracing::instrument(skip_all)]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} bytes", self.data.len())
+    }
+}
+
+impl DBBytes {
+    /// Function to create a new `DBBytes` struct for a `DBPacket::WriteBytes` packet.
+    pub const fn new(data: Vec<u8>) -> Self {
+        Self { data }
+    }
+
+    /// Getter function for the data inside the `DBBytes` struct.
+    pub fn get_data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the data encoded as base64, the form it is sent and stored as.
+    pub fn to_base64(&self) -> String {
+        STANDARD.encode(&self.data)
+    }
+
+    /// Decodes the base64 encoded data into a `DBBytes` struct.
+    pub fn from_base64(encoded: &str) -> Result<Self, base64::DecodeError> {
+        STANDARD.decode(encoded).map(Self::new)
+    }
+}
+
+impl From<Vec<u8>> for DBBytes {
+    fn from(data: Vec<u8>) -> Self {
+        Self::new(data)
+    }
+}
+
+impl Serialize for DBBytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_base64())
+    }
+}
+
+impl<'de> Deserialize<'de> for DBBytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        Self::from_base64(&encoded).map_err(D::Error::custom)
+    }
+}
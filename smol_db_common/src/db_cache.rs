@@ -0,0 +1,108 @@
+//! Contains `DBCache`, the databases loaded into memory, split into shards that are locked separately
+//! so loading or removing one database does not stop requests to databases in other shards.
+use crate::db::DB;
+use crate::db_packets::db_packet_info::DBPacketInfo;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, RwLock};
+
+/// Number of shards the cache is split into.
+pub const CACHE_SHARD_COUNT: usize = 16;
+
+type CacheShard = RwLock<HashMap<DBPacketInfo, Arc<RwLock<DB>>>>;
+
+#[derive(Debug)]
+/// The cached databases, each behind its own lock.
+/// A shard is only locked for as long as it takes to look up, insert or remove a database, never while a database is locked,
+/// so a request holds nothing but the lock of the database it works on.
+pub struct DBCache {
+    shards: Vec<CacheShard>,
+}
+
+impl Default for DBCache {
+    fn default() -> Self {
+        Self {
+            shards: (0..CACHE_SHARD_COUNT)
+                .map(|_| RwLock::new(HashMap::new()))
+                .collect(),
+        }
+    }
+}
+
+impl DBCache {
+    /// Returns the shard the database is kept in.
+    fn shard(&self, db_name: &DBPacketInfo) -> &CacheShard {
+        let mut hasher = DefaultHasher::new();
+        db_name.hash(&mut hasher);
+        &self.shards[hasher.finish() as usize % self.shards.len()]
+    }
+
+    /// Returns the cached database with the given name.
+    pub fn get(&self, db_name: &DBPacketInfo) -> Option<Arc<RwLock<DB>>> {
+        self.shard(db_name).read().unwrap().get(db_name).cloned()
+    }
+
+    /// Returns true if the database with the given name is cached.
+    pub fn contains_key(&self, db_name: &DBPacketInfo) -> bool {
+        self.shard(db_name).read().unwrap().contains_key(db_name)
+    }
+
+    /// Caches the database, unless a database with the name was cached first, returning whichever database is cached.
+    pub fn get_or_insert(&self, db_name: &DBPacketInfo, db: DB) -> Arc<RwLock<DB>> {
+        self.shard(db_name)
+            .write()
+            .unwrap()
+            .entry(db_name.clone())
+            .or_insert_with(|| Arc::new(RwLock::new(db)))
+            .clone()
+    }
+
+    /// Caches the database, returning the database it replaced.
+    pub fn insert(&self, db_name: DBPacketInfo, db: DB) -> Option<Arc<RwLock<DB>>> {
+        self.shard(&db_name)
+            .write()
+            .unwrap()
+            .insert(db_name, Arc::new(RwLock::new(db)))
+    }
+
+    /// Removes the database with the given name from the cache, returning it.
+    pub fn remove(&self, db_name: &DBPacketInfo) -> Option<Arc<RwLock<DB>>> {
+        self.shard(db_name).write().unwrap().remove(db_name)
+    }
+
+    /// Returns every cached database, shards are only locked while they are copied.
+    pub fn entries(&self) -> Vec<(DBPacketInfo, Arc<RwLock<DB>>)> {
+        self.shards
+            .iter()
+            .flat_map(|shard| {
+                shard
+                    .read()
+                    .unwrap()
+                    .iter()
+                    .map(|(db_name, db)| (db_name.clone(), db.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Returns the number of cached databases.
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.read().unwrap().len())
+            .sum()
+    }
+
+    /// Returns true if no database is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes every database from the cache.
+    pub fn clear(&self) {
+        for shard in &self.shards {
+            shard.write().unwrap().clear();
+        }
+    }
+}
@@ -1,11 +1,206 @@
-//! Contains the struct representing the content structure of a database, which is a hashmap.
+//! Contains the struct representing the content structure of a database, which is a hashmap, or a btree map when its keys are kept sorted.
+use crate::db_bytes::DBBytes;
+use crate::revision_history::RevisionHistory;
+use crate::sorted_list::SortedList;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{btree_map, hash_map, BTreeMap, BTreeSet, HashMap};
+use std::ops::Bound;
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+/// The key value pairs of a database, either unordered in a hash map, or ordered by key in a btree map.
+/// Both are serialized as the same json object, a btree map is serialized with its keys in order.
+pub enum ContentMap {
+    Unordered(HashMap<String, String>),
+    Sorted(BTreeMap<String, String>),
+}
+
+/// Iterator over the key value pairs of a `ContentMap`, ordered by key when the map is sorted.
+pub enum ContentIter<'a> {
+    Unordered(hash_map::Iter<'a, String, String>),
+    Sorted(btree_map::Iter<'a, String, String>),
+}
+
+impl<'a> Iterator for ContentIter<'a> {
+    type Item = (&'a String, &'a String);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Unordered(iter) => iter.next(),
+            Self::Sorted(iter) => iter.next(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a ContentMap {
+    type Item = (&'a String, &'a String);
+    type IntoIter = ContentIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Default for ContentMap {
+    fn default() -> Self {
+        Self::Unordered(HashMap::default())
+    }
+}
+
+impl ContentMap {
+    /// Returns true if the key value pairs are ordered by key.
+    pub const fn is_sorted(&self) -> bool {
+        matches!(self, Self::Sorted(_))
+    }
+
+    /// Returns the value at the given key.
+    pub fn get(&self, key: &str) -> Option<&String> {
+        match self {
+            Self::Unordered(map) => map.get(key),
+            Self::Sorted(map) => map.get(key),
+        }
+    }
+
+    /// Returns true if there is a value at the given key.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Inserts the value at the given key, returning the value previously stored at the key if there was one.
+    pub fn insert(&mut self, key: String, value: String) -> Option<String> {
+        match self {
+            Self::Unordered(map) => map.insert(key, value),
+            Self::Sorted(map) => map.insert(key, value),
+        }
+    }
+
+    /// Removes the value at the given key, returning it if there was one.
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        match self {
+            Self::Unordered(map) => map.remove(key),
+            Self::Sorted(map) => map.remove(key),
+        }
+    }
+
+    /// Returns the number of key value pairs.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Unordered(map) => map.len(),
+            Self::Sorted(map) => map.len(),
+        }
+    }
+
+    /// Returns true if there are no key value pairs.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns every key value pair, ordered by key when the map is sorted.
+    pub fn iter(&self) -> ContentIter<'_> {
+        match self {
+            Self::Unordered(map) => ContentIter::Unordered(map.iter()),
+            Self::Sorted(map) => ContentIter::Sorted(map.iter()),
+        }
+    }
+
+    /// Returns every key, ordered when the map is sorted.
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Returns every value, ordered by key when the map is sorted.
+    pub fn values(&self) -> impl Iterator<Item = &String> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Returns a copy of the key value pairs as a hash map.
+    pub fn to_hash_map(&self) -> HashMap<String, String> {
+        self.iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-/// Struct denoting the content structure itself of a database. Which is a hash map.
+#[serde(from = "DBContentData")]
+/// Struct denoting the content structure itself of a database. Which is a hash map, or a btree map when its keys are kept sorted.
 pub struct DBContent {
-    pub content: HashMap<String, String>,
+    /// The key value pairs of the database.
+    /// Modifications should go through `insert` and `remove` so the ordered key index stays up to date.
+    pub content: ContentMap,
+    /// Ordered index of every key in `content` while it is unordered, used for prefix scans. Rebuilt when the content is deserialized.
+    /// Left empty while the content is sorted, as it can be scanned directly.
+    #[serde(skip)]
+    key_index: BTreeSet<String>,
+    /// True when the key value pairs are kept ordered by key, see `DBSettings::with_sorted_keys`
+    /// Left out when false, so dbs with unordered keys are saved as they were before sorted keys existed
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    sorted: bool,
+    /// Keyed lists of values, stored separately from the key value pairs.
+    pub lists: HashMap<String, Vec<String>>,
+    /// Keyed sets of unique members, stored separately from the key value pairs.
+    pub sets: HashMap<String, BTreeSet<String>>,
+    /// Keyed lists of members ordered by score, stored separately from the key value pairs.
+    pub sorted_lists: HashMap<String, SortedList>,
+    /// Binary values, stored separately from the key value pairs.
+    pub binary: HashMap<String, DBBytes>,
+    /// Values that were overwritten or removed from the key value pairs, recorded when the db settings keep revisions.
+    /// Left out when empty, so dbs without revisions are saved as they were before revisions existed.
+    #[serde(skip_serializing_if = "RevisionHistory::is_empty")]
+    pub history: RevisionHistory,
+}
+
+#[derive(Deserialize)]
+/// The serialized form of a `DBContent`, the key index is not stored and is instead rebuilt from the content.
+struct DBContentData {
+    content: HashMap<String, String>,
+    /// Missing from dbs saved before sorted keys existed
+    #[serde(default)]
+    sorted: bool,
+    /// Missing from dbs saved before keyed lists existed
+    #[serde(default)]
+    lists: HashMap<String, Vec<String>>,
+    /// Missing from dbs saved before sets existed
+    #[serde(default)]
+    sets: HashMap<String, BTreeSet<String>>,
+    /// Missing from dbs saved before sorted lists existed
+    #[serde(default)]
+    sorted_lists: HashMap<String, SortedList>,
+    /// Missing from dbs saved before binary values existed
+    #[serde(default)]
+    binary: HashMap<String, DBBytes>,
+    /// Missing from dbs that never kept revisions
+    #[serde(default)]
+    history: RevisionHistory,
+}
+
+impl From<DBContentData> for DBContent {
+    fn from(value: DBContentData) -> Self {
+        let mut db_content = Self::from(value.content);
+        db_content.set_sorted(value.sorted);
+        db_content.lists = value.lists;
+        db_content.sets = value.sets;
+        db_content.sorted_lists = value.sorted_lists;
+        db_content.binary = value.binary;
+        db_content.history = value.history;
+        db_content
+    }
+}
+
+impl From<HashMap<String, String>> for DBContent {
+    fn from(content: HashMap<String, String>) -> Self {
+        let key_index = content.keys().cloned().collect();
+        Self {
+            content: ContentMap::Unordered(content),
+            key_index,
+            sorted: false,
+            lists: HashMap::default(),
+            sets: HashMap::default(),
+            sorted_lists: HashMap::default(),
+            binary: HashMap::default(),
+            history: RevisionHistory::default(),
+        }
+    }
 }
 
 impl DBContent {
@@ -20,6 +215,249 @@ impl DBContent {
     pub fn read_from_db(&self, key: &str) -> Option<&String> {
         self.content.get(key)
     }
+
+    /// Inserts the value at the given key, returning the value previously stored at the key if there was one.
+    /// The previous value is recorded as a revision of the key, unless it is the same as the new value.
+    #[tracing::instrument(skip(self))]
+    pub fn insert(&mut self, key: String, value: String) -> Option<String> {
+        if !self.sorted {
+            self.key_index.insert(key.clone());
+        }
+        let previous = self.content.insert(key.clone(), value);
+        if let Some(previous_value) = &previous {
+            if self.history.is_recording() && self.content.get(&key) != Some(previous_value) {
+                self.history.record(&key, previous_value.clone());
+            }
+        }
+        previous
+    }
+
+    /// Removes the value at the given key, returning it if there was one.
+    /// The removed value is recorded as a revision of the key.
+    #[tracing::instrument(skip(self))]
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        if !self.sorted {
+            self.key_index.remove(key);
+        }
+        let removed = self.content.remove(key);
+        if let Some(removed_value) = &removed {
+            if self.history.is_recording() {
+                self.history.record(key, removed_value.clone());
+            }
+        }
+        removed
+    }
+
+    /// Returns the number of key value pairs.
+    pub fn len(&self) -> usize {
+        self.content.len()
+    }
+
+    /// Returns true if the key value pairs are kept ordered by key.
+    pub const fn is_sorted(&self) -> bool {
+        self.sorted
+    }
+
+    /// Keeps the key value pairs ordered by key, or unordered, moving them into the matching map if the order changed.
+    /// Sorted content is scanned directly, so the key index is only kept while the content is unordered.
+    #[tracing::instrument(skip(self))]
+    pub fn set_sorted(&mut self, sorted: bool) {
+        if self.sorted == sorted {
+            return;
+        }
+        self.sorted = sorted;
+
+        let content = std::mem::take(&mut self.content);
+        self.content = if sorted {
+            self.key_index.clear();
+            ContentMap::Sorted(match content {
+                ContentMap::Unordered(map) => map.into_iter().collect(),
+                ContentMap::Sorted(map) => map,
+            })
+        } else {
+            self.key_index = content.keys().cloned().collect();
+            ContentMap::Unordered(match content {
+                ContentMap::Unordered(map) => map,
+                ContentMap::Sorted(map) => map.into_iter().collect(),
+            })
+        };
+    }
+
+    /// Returns true if there are no key value pairs.
+    pub fn is_empty(&self) -> bool {
+        self.content.is_empty()
+    }
+
+    /// Returns the number of key value pairs, lists, sets, sorted lists and binary values, which is what a storage quota limits.
+    pub fn entry_count(&self) -> usize {
+        self.content.len()
+            + self.lists.len()
+            + self.sets.len()
+            + self.sorted_lists.len()
+            + self.binary.len()
+    }
+
+    /// Returns the number of bytes taken up by every key and value, each score in a sorted list counts as 8 bytes.
+    /// Every value is visited, so this is only calculated when a storage quota limits the bytes of the db.
+    pub fn size_in_bytes(&self) -> usize {
+        let content: usize = self
+            .content
+            .iter()
+            .map(|(key, value)| key.len() + value.len())
+            .sum();
+        let lists: usize = self
+            .lists
+            .iter()
+            .map(|(name, values)| name.len() + values.iter().map(String::len).sum::<usize>())
+            .sum();
+        let sets: usize = self
+            .sets
+            .iter()
+            .map(|(name, members)| name.len() + members.iter().map(String::len).sum::<usize>())
+            .sum();
+        let sorted_lists: usize = self
+            .sorted_lists
+            .iter()
+            .map(|(name, list)| {
+                name.len()
+                    + list
+                        .iter()
+                        .map(|scored| scored.member.len() + size_of::<f64>())
+                        .sum::<usize>()
+            })
+            .sum();
+        let binary: usize = self
+            .binary
+            .iter()
+            .map(|(key, bytes)| key.len() + bytes.get_data().len())
+            .sum();
+        content + lists + sets + sorted_lists + binary
+    }
+
+    /// Removes every key value pair, returning the keys that were removed in order.
+    #[tracing::instrument(skip(self))]
+    pub fn clear(&mut self) -> Vec<String> {
+        let content = match &mut self.content {
+            ContentMap::Unordered(map) => std::mem::take(map).into_iter().collect(),
+            ContentMap::Sorted(map) => std::mem::take(map),
+        };
+        self.key_index.clear();
+        let mut keys = Vec::with_capacity(content.len());
+        for (key, value) in content {
+            self.history.record(&key, value);
+            keys.push(key);
+        }
+        keys
+    }
+
+    /// Returns every key value pair whose key starts with the given prefix, ordered by key.
+    #[tracing::instrument(skip(self))]
+    pub fn read_prefix(&self, prefix: &str) -> BTreeMap<String, String> {
+        self.prefix_pairs(prefix)
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+
+    /// Returns a copy of the key value pairs whose key starts with the given prefix and whose value contains the given text,
+    /// an empty prefix or text matches every pair. The copy keeps its keys sorted if this content does.
+    #[tracing::instrument(skip(self))]
+    pub fn filtered(&self, key_prefix: &str, value_contains: &str) -> Self {
+        let content: HashMap<String, String> = self
+            .prefix_pairs(key_prefix)
+            .filter(|(_, value)| value.contains(value_contains))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        let mut filtered = Self::from(content);
+        filtered.set_sorted(self.sorted);
+        filtered
+    }
+
+    /// Iterates the key value pairs whose key starts with the given prefix in order of their keys, using the key index when the content is unordered.
+    fn prefix_pairs<'a>(
+        &'a self,
+        prefix: &'a str,
+    ) -> Box<dyn Iterator<Item = (&'a String, &'a String)> + 'a> {
+        let range = (Bound::Included(prefix), Bound::Unbounded);
+        match &self.content {
+            ContentMap::Sorted(map) => Box::new(
+                map.range::<str, _>(range)
+                    .take_while(move |(key, _)| key.starts_with(prefix)),
+            ),
+            ContentMap::Unordered(map) => Box::new(
+                self.key_index
+                    .range::<str, _>(range)
+                    .take_while(move |key| key.starts_with(prefix))
+                    .filter_map(move |key| map.get_key_value(key)),
+            ),
+        }
+    }
+
+    /// Returns the list with the given name, if it has any values.
+    pub fn get_list(&self, list_name: &str) -> Option<&Vec<String>> {
+        self.lists.get(list_name)
+    }
+
+    /// Returns the list with the given name, creating an empty list if there was not one.
+    pub fn get_list_mut(&mut self, list_name: &str) -> &mut Vec<String> {
+        self.lists.entry(list_name.to_string()).or_default()
+    }
+
+    /// Removes the list with the given name, returning it if there was one.
+    pub fn remove_list(&mut self, list_name: &str) -> Option<Vec<String>> {
+        self.lists.remove(list_name)
+    }
+
+    /// Returns the set with the given name, if it has any members.
+    pub fn get_set(&self, set_name: &str) -> Option<&BTreeSet<String>> {
+        self.sets.get(set_name)
+    }
+
+    /// Adds the member to the set with the given name, creating the set if there was not one.
+    /// Returns true if the member was not already in the set.
+    pub fn set_add(&mut self, set_name: &str, member: String) -> bool {
+        self.sets
+            .entry(set_name.to_string())
+            .or_default()
+            .insert(member)
+    }
+
+    /// Removes the member from the set with the given name, removing the set once it has no members.
+    /// Returns true if the member was in the set.
+    pub fn set_remove(&mut self, set_name: &str, member: &str) -> bool {
+        let Some(set) = self.sets.get_mut(set_name) else {
+            return false;
+        };
+
+        let removed = set.remove(member);
+        if set.is_empty() {
+            self.sets.remove(set_name);
+        }
+        removed
+    }
+
+    /// Returns the sorted list with the given name, if it has any members.
+    pub fn get_sorted_list(&self, list_name: &str) -> Option<&SortedList> {
+        self.sorted_lists.get(list_name)
+    }
+
+    /// Returns the sorted list with the given name, creating an empty sorted list if there was not one.
+    pub fn get_sorted_list_mut(&mut self, list_name: &str) -> &mut SortedList {
+        self.sorted_lists.entry(list_name.to_string()).or_default()
+    }
+
+    /// Removes the member from the sorted list with the given name, removing the sorted list once it has no members.
+    /// Returns true if the member was in the sorted list.
+    pub fn sorted_list_remove(&mut self, list_name: &str, member: &str) -> bool {
+        let Some(sorted_list) = self.sorted_lists.get_mut(list_name) else {
+            return false;
+        };
+
+        let removed = sorted_list.remove(member);
+        if sorted_list.is_empty() {
+            self.sorted_lists.remove(list_name);
+        }
+        removed
+    }
 }
 
 #[allow(clippy::derivable_impls)] // This lint is allowed so we can later make default not simply have the default impl
@@ -28,7 +466,14 @@ impl Default for DBContent {
     #[tracing::instrument]
     fn default() -> Self {
         Self {
-            content: HashMap::default(),
+            content: ContentMap::default(),
+            key_index: BTreeSet::default(),
+            sorted: false,
+            lists: HashMap::default(),
+            sets: HashMap::default(),
+            sorted_lists: HashMap::default(),
+            binary: HashMap::default(),
+            history: RevisionHistory::default(),
         }
     }
 }
@@ -0,0 +1,19 @@
+//! Contains dumps, which hold a single database in a form that can be moved to another server.
+use crate::db::DB;
+use crate::db_packets::db_packet_info::DBPacketInfo;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+/// A database along with its name, exported from a server so it can be imported into another.
+pub struct DBDump {
+    /// Name of the database, which it is imported as
+    pub name: DBPacketInfo,
+    /// When the database was exported
+    pub exported_at: DateTime<Utc>,
+    #[serde(default)]
+    /// Version of the hashes of the keys in the settings of the database, 0 when it was exported before keys were hashed
+    pub key_hash_version: u32,
+    /// The content, settings and statistics of the database
+    pub db: DB,
+}
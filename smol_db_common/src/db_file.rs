@@ -0,0 +1,158 @@
+//! Contains functions for saving and loading the files in the data directory without leaving a partially written file behind.
+//! Files are written to a temporary file and renamed over the old file, and carry a checksum so corruption is detected when loaded.
+use serde::de::DeserializeOwned;
+use std::ffi::OsString;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::{error, warn};
+
+/// Start of the first line of a saved file, followed by the crc32 checksum of the rest of the file in hex.
+const CHECKSUM_HEADER: &str = "smol_db_crc32:";
+
+/// Extension added to the name of a file while it is being written.
+const TEMP_EXTENSION: &str = "tmp";
+
+/// Extension added to the name of the previous version of a file, which is loaded if the file is corrupt.
+const BACKUP_EXTENSION: &str = "bak";
+
+#[derive(Debug)]
+/// Represents the ways loading a saved file can fail.
+pub enum DBFileError {
+    /// Neither the file nor its backup exist
+    NotFound,
+    /// The file could not be read
+    Io(io::Error),
+    /// The file does not match its checksum, or could not be deserialized
+    Corrupt(String),
+}
+
+impl Display for DBFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "file not found"),
+            Self::Io(err) => write!(f, "{}", err),
+            Self::Corrupt(reason) => write!(f, "file is corrupt: {}", reason),
+        }
+    }
+}
+
+/// Returns the path with the extension added to the end of its file name.
+fn with_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut file_name = path.file_name().map(OsString::from).unwrap_or_default();
+    file_name.push(".");
+    file_name.push(extension);
+    path.with_file_name(file_name)
+}
+
+/// Returns the path the previous version of the file is kept at.
+pub fn backup_path(path: &Path) -> PathBuf {
+    with_extension(path, BACKUP_EXTENSION)
+}
+
+/// Replaces the contents of the file, writing them to a temporary file that is renamed over the file once it has reached the disk.
+/// The file either has its old contents or its new contents if the process stops part way through.
+#[tracing::instrument(skip(contents))]
+pub fn replace_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let temp_path = with_extension(path, TEMP_EXTENSION);
+
+    let mut temp_file = File::create(&temp_path)?;
+    temp_file.write_all(contents)?;
+    temp_file.sync_all()?;
+    drop(temp_file);
+
+    fs::rename(&temp_path, path)?;
+
+    // the rename itself only reaches the disk once the directory is synced, which is not possible on every platform
+    if let Some(dir) = path.parent() {
+        if let Ok(dir) = File::open(dir) {
+            let _ = dir.sync_all();
+        }
+    }
+    Ok(())
+}
+
+/// Saves the contents to the file with a checksum, keeping the previous version of the file as a backup.
+#[tracing::instrument(skip(contents))]
+pub fn save(path: &Path, contents: &str) -> io::Result<()> {
+    let backup = backup_path(path);
+    let _ = fs::remove_file(&backup);
+    match fs::hard_link(path, &backup) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        // some file systems do not support hard links
+        Err(_) => {
+            fs::copy(path, &backup)?;
+        }
+    }
+
+    let checksum = crc32fast::hash(contents.as_bytes());
+    let file_contents = format!("{}{:08x}\n{}", CHECKSUM_HEADER, checksum, contents);
+    replace_file(path, file_contents.as_bytes())
+}
+
+/// Reads the contents of a file written by `save`, checking them against the checksum.
+/// Files saved before checksums were added have no checksum, and are returned as they are.
+fn read_verified(path: &Path) -> Result<String, DBFileError> {
+    let file_contents = fs::read_to_string(path).map_err(|err| match err.kind() {
+        io::ErrorKind::NotFound => DBFileError::NotFound,
+        io::ErrorKind::InvalidData => DBFileError::Corrupt(err.to_string()),
+        _ => DBFileError::Io(err),
+    })?;
+
+    let Some(checksummed) = file_contents.strip_prefix(CHECKSUM_HEADER) else {
+        return Ok(file_contents);
+    };
+
+    let (checksum, contents) = checksummed
+        .split_once('\n')
+        .ok_or_else(|| DBFileError::Corrupt("checksum header was not finished".to_string()))?;
+    let checksum = u32::from_str_radix(checksum, 16)
+        .map_err(|_| DBFileError::Corrupt("checksum could not be read".to_string()))?;
+
+    if crc32fast::hash(contents.as_bytes()) != checksum {
+        return Err(DBFileError::Corrupt(
+            "contents do not match checksum".to_string(),
+        ));
+    }
+    Ok(contents.to_string())
+}
+
+/// Reads and deserializes a file written by `save`.
+fn load_verified<T: DeserializeOwned>(path: &Path) -> Result<T, DBFileError> {
+    let contents = read_verified(path)?;
+    serde_json::from_str(&contents).map_err(|err| DBFileError::Corrupt(err.to_string()))
+}
+
+/// Loads a file written by `save`, falling back to the previous version of the file when the file is missing or corrupt.
+#[tracing::instrument]
+pub fn load<T: DeserializeOwned>(path: &Path) -> Result<T, DBFileError> {
+    match load_verified(path) {
+        Ok(value) => Ok(value),
+        Err(DBFileError::Io(err)) => Err(DBFileError::Io(err)),
+        Err(err) => {
+            let backup = backup_path(path);
+            match load_verified(&backup) {
+                Ok(value) => {
+                    warn!(
+                        "Loaded backup {:?} because {:?} could not be loaded: {}",
+                        backup, path, err
+                    );
+                    Ok(value)
+                }
+                Err(backup_err) => {
+                    if !matches!(err, DBFileError::NotFound) {
+                        error!(
+                            "Unable to load {:?}: {}, and its backup: {}",
+                            path, err, backup_err
+                        );
+                    }
+                    Err(err)
+                }
+            }
+        }
+    }
+}
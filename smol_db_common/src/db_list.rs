@@ -1,576 +1,792 @@
 #![allow(clippy::expect_fun_call)]
 //! Contains structs and implementations for managing the active list of databases, that are both in filesystem, and in cache.
 //! Also handles what to do when packets are received that modify any database that does or does not exist.
+use crate::channel_registry::ChannelRegistry;
 use crate::db::Role::SuperAdmin;
 use crate::db::DB;
-use crate::db_content::DBContent;
+use crate::db_bytes::{DBBytes, STREAM_CHUNK_SIZE};
+use crate::db_cache::DBCache;
+use crate::db_content::{ContentMap, DBContent};
 use crate::db_data::DBData;
+use crate::db_dump::DBDump;
+use crate::db_file;
+use crate::db_file::DBFileError;
+use crate::db_packets::change_event::ChangeEvent;
 use crate::db_packets::db_location::DBLocation;
-use crate::db_packets::db_packet_info::DBPacketInfo;
+use crate::db_packets::db_metadata::{DBListing, DBMetadata};
+use crate::db_packets::db_packet_info::{DBPacketInfo, NAMESPACE_SEPARATOR};
 use crate::db_packets::db_packet_response::DBPacketResponseError::{
-    BadPacket, DBFileSystemError, DBNotFound, InvalidPermissions, SerializationError, UserNotFound,
-    ValueNotFound,
+    DBFileSystemError, DBNotFound, InvalidPermissions, SerializationError, UserAlreadyExists,
+    UserNotFound, ValueNotFound,
+};
+use crate::db_packets::db_packet_response::DBSuccessResponse::{
+    SuccessData, SuccessNoData, SuccessReply,
 };
-use crate::db_packets::db_packet_response::DBSuccessResponse::{SuccessNoData, SuccessReply};
 use crate::db_packets::db_packet_response::{DBPacketResponseError, DBSuccessResponse};
 use crate::db_packets::db_settings::DBSettings;
-use crate::encryption::server_encrypt::ServerKey;
-use crate::prelude::DBPacket;
+use crate::db_packets::key_acl::KeyAclRule;
+use crate::db_packets::key_pattern::KeyPattern;
+use crate::db_packets::namespace::NamespaceSettings;
+use crate::db_packets::permissions::Permission;
+use crate::db_packets::response_data::ResponseData;
+use crate::db_packets::server_info::ServerInfo;
+use crate::db_packets::stats_format::StatsFormat;
+use crate::db_packets::storage_quota::StorageQuota;
+use crate::db_packets::transaction::TransactionOp;
+use crate::db_packets::value_schema::ValueSchema;
+use crate::encryption::key_hash::{KeyHasher, KEY_HASH_VERSION};
+use crate::encryption::server_encrypt::{ServerKey, SERVER_KEY_FILE_NAME};
+use crate::notification_bus::NotificationBus;
+use crate::queue_waiters::QueueWaiters;
+use crate::replication::{ReplicationItem, ReplicationLog, ReplicationPosition};
+use crate::session_store::SessionStore;
+use crate::snapshot::{Snapshot, BACKUP_DIR_NAME};
+#[cfg(feature = "statistics")]
+use crate::statistics::{ServerStatistics, StatisticsExport};
+use crate::trash::{TrashEntry, DEFAULT_TRASH_RETENTION, TRASH_DIR_NAME};
+use crate::user_registry::UserRegistry;
+use crate::write_ahead_log::{WalEntry, WalOp, WriteAheadLog, WAL_FILE_NAME};
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::fs::File;
-use std::io::{Read, Write};
-use std::net::TcpStream;
-use std::sync::RwLock;
-use std::time::SystemTime;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, sync_channel, Receiver, SyncSender};
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+use std::thread;
+use std::time::{Duration, SystemTime};
 use tracing::{debug, error, info, warn};
 
+/// The directory databases are saved in when no other directory is configured.
+pub const DEFAULT_DATA_DIR: &str = "./data";
+
+/// File name the db list is saved as within the data directory.
+const DB_LIST_FILE_NAME: &str = "db_list.ser";
+
+fn default_data_dir() -> PathBuf {
+    PathBuf::from(DEFAULT_DATA_DIR)
+}
+
+const fn default_saving_enabled() -> bool {
+    true
+}
+
+const fn default_trash_retention() -> Duration {
+    DEFAULT_TRASH_RETENTION
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 /// `DBList` represents a server that takes requests and handles them on a given `smol_db` server.
 /// This struct can be used to create a local only database as well, by simply instantiating it and not listening for socket requests.
 pub struct DBList {
     /// Vector of DBPacketInfo's containing file names of the databases that are available to be read from.
+    /// Only locked to create, delete or list databases, and to load a database missing from the cache, requests to cached databases never lock it.
     pub list: RwLock<Vec<DBPacketInfo>>,
 
-    /// Hashmap that takes a DBPacketInfo and returns the database corresponding to the name in the given packet.
+    /// Cache that takes a DBPacketInfo and returns the database corresponding to the name in the given packet.
+    /// Each database has its own lock, so requests to different databases never wait on each other.
     #[serde(skip)]
-    pub cache: RwLock<HashMap<DBPacketInfo, RwLock<DB>>>,
+    pub cache: DBCache,
 
     /// Vector containing the list of super admins on the server. Super admins have non-restricted access to all parts of the server.
     pub super_admin_hash_list: RwLock<Vec<String>>,
 
+    #[serde(default)]
+    /// Settings of each namespace that has been configured, keyed by the name of the namespace.
+    /// Databases in a namespace that is not configured only have the super admins of the server
+    pub namespaces: RwLock<HashMap<String, NamespaceSettings>>,
+
+    #[serde(default)]
+    /// Hashes client keys with the salt of this server, so only hashes of keys are stored
+    pub key_hasher: RwLock<KeyHasher>,
+
+    #[serde(default)]
+    /// Version of the hashes the keys of the db list and its dbs were saved with, 0 when they were saved as plaintext.
+    /// Below `KEY_HASH_VERSION`, `migrate_plaintext_keys` hashes every key again
+    pub key_hash_version: u32,
+
+    #[serde(default)]
+    /// The named user accounts clients can log in to
+    pub user_registry: RwLock<UserRegistry>,
+
+    #[serde(skip)]
+    /// Session tokens clients can resume their key with, which expire after the time to live the server is configured with
+    pub sessions: SessionStore,
+
+    #[serde(skip)]
+    /// Hash of the token that claims the first super admin, set by the server when there are no super admins.
+    /// When no token is set, a super admin can not be claimed.
+    pub bootstrap_token: RwLock<Option<String>>,
+
+    #[serde(skip)]
+    /// Server key used for encryption when the user requests end to end encryption, a super admin can replace it with a new key
+    pub server_key: RwLock<ServerKey>,
+
+    #[serde(skip)]
+    /// Notification bus that changes to databases are published to, for clients that are watching a database
+    pub notification_bus: NotificationBus,
+
+    #[serde(skip)]
+    /// Registry of the pub/sub channels that clients have subscribed to
+    pub channels: ChannelRegistry,
+
+    #[serde(skip)]
+    /// Clients waiting for a value to be pushed to an empty queue, woken by every push to the queue
+    pub queue_waiters: QueueWaiters,
+
+    #[serde(skip, default = "default_data_dir")]
+    /// Directory the db list and every database file are saved in
+    pub data_dir: PathBuf,
+
+    #[serde(skip, default = "default_saving_enabled")]
+    /// When false, changes to databases are kept in the cache and never saved to the data directory
+    pub saving_enabled: bool,
+
+    #[serde(skip)]
+    /// Log of changes made since the databases were last saved, replayed when the db list is loaded
+    pub wal: WriteAheadLog,
+
+    #[serde(skip)]
+    /// Most recent changes, sent to replica servers, disabled unless the server keeps a replication log
+    pub replication: ReplicationLog,
+
+    #[serde(skip)]
+    /// Limits that apply to every database, a database with a stricter quota of its own uses that instead
+    pub quota: StorageQuota,
+
+    #[serde(skip, default = "default_trash_retention")]
+    /// How long deleted databases are kept in the trash directory before they are purged
+    pub trash_retention: Duration,
+
+    #[serde(skip)]
+    /// Approximate bytes the cached databases may take up, the least recently accessed databases are removed from the cache past it.
+    /// `None` leaves databases cached until their invalidation time
+    pub cache_memory_budget: Option<usize>,
+
+    #[cfg(feature = "statistics")]
     #[serde(skip)]
-    /// Server key used for encryption when the user requests end to end encryption
-    pub server_key: ServerKey,
+    /// Statistics of every request handled by the server, across all databases
+    pub server_statistics: RwLock<ServerStatistics>,
 }
 
 impl DBList {
-    #[tracing::instrument(skip(self, db_table))]
-    fn handle_stream(
+    /// Returns a copy of the content of the db to be streamed to a client, so no lock is held while the stream is open.
+    /// Requires read and stream permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn get_stream_table(
         &self,
-        client_stream: &mut TcpStream,
-        db_table: &DBContent,
-    ) -> Result<(), DBPacketResponseError> {
-        for item in &db_table.content {
-            let mut buf: [u8; 1024] = [0; 1024];
-            debug!("Waiting for client to await next item");
-            let read_len = client_stream.read(&mut buf).unwrap();
-
-            let read_client = String::from_utf8(buf.to_vec()).unwrap();
-
-            match serde_json::from_str::<DBPacket>(&read_client[0..read_len]) {
-                Ok(packet) => {
-                    debug!("Packet read: {:?}", packet);
-
-                    // two cases where packets come during a stream, ending the stream, and asking for the next item
-                    if matches!(packet, DBPacket::EndStreamRead) {
-                        info!("Stream ended early intentionally.");
-                        break;
-                    } else if !matches!(packet, DBPacket::ReadyForNextItem) {
-                        return Err(BadPacket);
-                    }
-                }
-                Err(err) => {
-                    error!("err: {} {}", read_client, err);
-                }
-            }
-
-            debug!("Client requested next item");
-
-            let _ = client_stream.write(item.0.as_bytes()).map_err(|err| {
-                error!("{}", err);
-                DBPacketResponseError::StreamClosedUnexpectedly
-            })?;
-            let _ = client_stream.write(item.1.as_bytes()).map_err(|err| {
-                error!("{}", err);
-                DBPacketResponseError::StreamClosedUnexpectedly
-            })?;
-            info!("Wrote key value pair to stream");
-        }
-        Ok(())
+        packet: &DBPacketInfo,
+        client_key: &String,
+    ) -> Result<DBContent, DBPacketResponseError> {
+        self.copy_stream_table(packet, client_key, |content| content.clone())
     }
 
+    /// Returns a copy of the key value pairs of the db whose key starts with the given prefix and whose value contains the given text,
+    /// to be streamed to a client, so pairs that do not match are neither copied nor sent. An empty prefix or text matches every pair.
+    /// Requires read and stream permissions on the given db
     #[tracing::instrument(skip(self))]
-    pub fn stream_table(
+    pub fn get_filtered_stream_table(
         &self,
         packet: &DBPacketInfo,
+        key_prefix: &str,
+        value_contains: &str,
         client_key: &String,
-        client_stream: &mut TcpStream,
-    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
-        let super_admin_list = self.get_super_admin_list();
-        let list_lock = self.list.read().unwrap();
+    ) -> Result<DBContent, DBPacketResponseError> {
+        self.copy_stream_table(packet, client_key, |content| {
+            content.filtered(key_prefix, value_contains)
+        })
+    }
 
-        if let Some(db) = self.cache.read().unwrap().get(packet) {
-            info!("DB Cache hit");
-            // cache was hit
-            db.write().unwrap().update_access_time();
+    /// Returns the copy made of the content of the db, if the client is able to stream the db.
+    fn copy_stream_table(
+        &self,
+        packet: &DBPacketInfo,
+        client_key: &String,
+        copy: impl FnOnce(&DBContent) -> DBContent,
+    ) -> Result<DBContent, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(packet);
 
-            let db_lock = db.read().unwrap();
+        self.with_db(packet, |db| {
+            if db.has_stream_permissions(client_key, &super_admin_list) {
+                Ok(copy(db.get_content()))
+            } else {
+                Err(InvalidPermissions)
+            }
+        })
+    }
 
-            return if db_lock.has_read_permissions(client_key, &super_admin_list) {
-                let db_table = db_lock.get_content().clone();
-                drop(db_lock);
+    /// Runs the given operation on the db with the given name, loading the db from the file system into the cache if it is not already cached.
+    /// The access time of the db is updated before the operation is run.
+    /// Only the lock of the db is held while the operation runs, so operations on other dbs never wait on it.
+    #[tracing::instrument(skip(self, operation))]
+    fn with_db<R>(
+        &self,
+        p_info: &DBPacketInfo,
+        operation: impl FnOnce(&mut DB) -> Result<R, DBPacketResponseError>,
+    ) -> Result<R, DBPacketResponseError> {
+        loop {
+            let db = self.cached_db(p_info)?;
+            let mut db_lock = db.write().unwrap();
 
-                let _ = self
-                    .send_stream_starting_packet(client_stream)
-                    .inspect_err(|err| error!("Error sending stream starting packet: {}", err));
+            if db_lock.is_evicted() {
+                // the db was removed from the cache after it was looked up, changes to it would be lost
+                info!("DB was evicted before it was locked, looking it up again");
+                continue;
+            }
 
-                self.handle_stream(client_stream, &db_table)?;
+            db_lock.update_access_time();
 
-                Ok(SuccessNoData)
-            } else {
-                Err(InvalidPermissions)
-            };
+            return operation(&mut db_lock);
         }
+    }
 
-        return if list_lock.contains(packet) {
-            info!("DB Cache missed");
-            // cache was missed but the db exists on the file system
+    /// Returns the cached db with the given name, loading it from the file system into the cache if it is not already cached.
+    /// The file is read without holding any lock, the list is only read locked while the loaded db is cached,
+    /// so a db deleted while it was being loaded is not cached again.
+    fn cached_db(&self, p_info: &DBPacketInfo) -> Result<Arc<RwLock<DB>>, DBPacketResponseError> {
+        if let Some(db) = self.cache.get(p_info) {
+            info!("DB Cache hit");
+            return Ok(db);
+        }
 
-            let mut db = Self::read_db_from_file(packet)?;
+        if !self.list.read().unwrap().contains(p_info) {
+            // cache was neither hit, nor did the db exist on the file system
+            info!("Database not found {}", p_info);
+            return Err(DBNotFound);
+        }
 
-            db.update_access_time();
+        info!("DB Cache missed");
+        let db = self.read_db_from_file(p_info);
 
-            if db.has_read_permissions(client_key, &super_admin_list) {
-                let db_table = db.get_content();
+        let list_lock = self.list.read().unwrap();
+        if !list_lock.contains(p_info) {
+            info!("Database was deleted while it was loaded {}", p_info);
+            return Err(DBNotFound);
+        }
+        Ok(self.cache.get_or_insert(p_info, db?))
+    }
 
-                let _ = self
-                    .send_stream_starting_packet(client_stream)
-                    .inspect_err(|err| error!("Error sending stream starting packet: {}", err));
+    /// Removes the db with the given name from the cache, returning it.
+    /// The db is saved first when `save` is true and it has unsaved changes, before it can be loaded from its file again.
+    /// The db is marked as evicted while it is write locked, so requests that looked it up before it was removed look it up again.
+    /// Callers may hold the list lock, but not the lock of any db.
+    fn uncache_db(&self, p_info: &DBPacketInfo, save: bool) -> Option<DB> {
+        let db = self.cache.get(p_info)?;
+        let mut db_lock = db.write().unwrap();
+        if db_lock.is_evicted() {
+            return None;
+        }
+        if save && self.saving_enabled && db_lock.is_dirty() {
+            self.save_db(p_info, &mut db_lock);
+        }
+        let removed = std::mem::take(&mut *db_lock);
+        db_lock.mark_evicted();
+        self.cache.remove(p_info);
+        Some(removed)
+    }
 
-                self.handle_stream(client_stream, db_table)?;
-            } else {
-                return Err(InvalidPermissions);
-            };
+    /// Returns the hasher of client keys, which is only replaced when a replica takes the salt of its primary
+    pub fn key_hasher(&self) -> RwLockReadGuard<'_, KeyHasher> {
+        self.key_hasher.read().unwrap()
+    }
 
-            self.cache
-                .write()
-                .unwrap()
-                .insert(packet.clone(), RwLock::from(db));
+    /// Hashes the keys of settings a client sent for the given db, leaving account keys and the hashes already in the settings of the db,
+    /// such as those the client read from them and sent back. Every other key is hashed, whatever it looks like.
+    #[tracing::instrument(skip_all)]
+    pub fn hash_settings_keys(&self, db_info: &DBPacketInfo, settings: &mut DBSettings) {
+        let stored = self.stored_role_keys(db_info);
+        self.key_hasher().hash_new_settings_keys(settings, &stored);
+    }
 
-            Ok(SuccessNoData)
+    /// Returns the key to remove from a role in the given db, the key itself if it is listed in the settings of the db,
+    /// such as a hash read from them, otherwise the hash of the key.
+    #[tracing::instrument(skip_all)]
+    pub fn removed_role_key(&self, db_info: &DBPacketInfo, key: &str) -> String {
+        if self
+            .stored_role_keys(db_info)
+            .iter()
+            .any(|stored| stored == key)
+        {
+            key.to_string()
         } else {
-            // cache was neither hit, nor did the db exist on the file system
-            Err(DBNotFound)
-        };
+            self.key_hasher().hash_role_key(key)
+        }
     }
 
-    fn send_stream_starting_packet(&self, client_stream: &mut TcpStream) -> std::io::Result<()> {
-        let s: Result<DBSuccessResponse<String>, DBPacketResponseError> = Ok(SuccessNoData);
-        let starting_packet = serde_json::to_string(&s)?;
-        let _ = client_stream.write(starting_packet.as_bytes())?;
-        Ok(())
+    /// Returns the keys in the admin and user lists and the key acl rules of the db, or none if the db does not exist.
+    fn stored_role_keys(&self, db_info: &DBPacketInfo) -> Vec<String> {
+        self.with_db(db_info, |db| {
+            let settings = db.get_settings();
+            Ok(settings
+                .admins
+                .iter()
+                .chain(&settings.users)
+                .chain(settings.key_acls.iter().map(|rule| &rule.key))
+                .cloned()
+                .collect())
+        })
+        .unwrap_or_default()
     }
 
-    // TODO: open stream function handler should be about here, it should return an iterator over an entire table,
-    //  probably cloning the table at the start so the lock on the table can be dropped quickly?
-    // TODO: we probably want a "streaming read" and a "streaming write" function and packet system
-
     /// Returns true if the given hash is a super admin hash
     #[tracing::instrument(skip(self))]
     pub fn is_super_admin(&self, hash: &String) -> bool {
         self.super_admin_hash_list.read().unwrap().contains(hash)
     }
 
-    /// Returns the super admin list
+    /// Returns true if the hash is a super admin of the server, or of the namespace the given db is in.
     #[tracing::instrument(skip(self))]
-    fn get_super_admin_list(&self) -> Vec<String> {
-        self.super_admin_hash_list.read().unwrap().clone()
+    pub fn is_super_admin_of(&self, hash: &String, db_info: &DBPacketInfo) -> bool {
+        self.is_super_admin(hash)
+            || self
+                .get_namespace_settings(db_info)
+                .is_some_and(|namespace| namespace.is_super_admin(hash))
     }
 
-    #[allow(unused_variables)]
-    #[allow(clippy::ptr_arg)]
-    /// Returns the db stats used for a given database when permissions allow the user to read them
+    /// Returns the super admins of the given db, the super admins of the server and of the namespace the db is in
     #[tracing::instrument(skip(self))]
-    pub fn get_stats(
-        &self,
-        p_info: &DBPacketInfo,
-        client_key: &String,
-    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
-        #[cfg(not(feature = "statistics"))]
-        {
-            warn!("Statistics packet received, however statistics is not enabled on this server");
-            return Err(BadPacket);
+    fn get_super_admin_list(&self, db_info: &DBPacketInfo) -> Vec<String> {
+        let mut super_admin_list = self.super_admin_hash_list.read().unwrap().clone();
+        if let Some(namespace) = self.get_namespace_settings(db_info) {
+            super_admin_list.extend(namespace.super_admins);
         }
+        super_admin_list
+    }
 
-        #[cfg(feature = "statistics")]
-        {
-            let super_admin_list = self.get_super_admin_list();
-
-            let list_lock = self.list.read().unwrap();
-            if let Some(db) = self.cache.read().unwrap().get(p_info) {
-                info!("DB Cache hit");
-                // cache was hit
-                let mut db_lock = db.write().unwrap();
+    /// Returns the settings of the namespace the given db is in, if the namespace has been configured
+    fn get_namespace_settings(&self, db_info: &DBPacketInfo) -> Option<NamespaceSettings> {
+        let namespace = db_info.get_namespace()?;
+        self.namespaces.read().unwrap().get(namespace).cloned()
+    }
 
-                db_lock.update_access_time();
+    /// Returns the quota the server applies to the given db, the stricter of the quota of the server and of the namespace the db is in
+    fn quota_for(&self, db_info: &DBPacketInfo) -> StorageQuota {
+        self.get_namespace_settings(db_info)
+            .map_or(self.quota, |namespace| namespace.quota.min(self.quota))
+    }
 
-                return if db_lock.get_role(client_key, &super_admin_list).is_admin() {
-                    serde_json::to_string(db_lock.get_statistics())
-                        .map(SuccessReply)
-                        .map_err(|_| SerializationError)
-                } else {
-                    Err(InvalidPermissions)
-                };
-            }
+    /// Returns `NamespaceFull` if the given db is in a namespace that already holds as many dbs as it allows.
+    /// The list must be locked by the caller, so no other db is added to the namespace in the meantime.
+    fn check_namespace_limit(
+        &self,
+        list: &[DBPacketInfo],
+        db_info: &DBPacketInfo,
+    ) -> Result<(), DBPacketResponseError> {
+        let Some(max_databases) = self
+            .get_namespace_settings(db_info)
+            .and_then(|namespace| namespace.max_databases)
+        else {
+            return Ok(());
+        };
 
-            return if list_lock.contains(p_info) {
-                info!("DB Cache missed");
-                // cache was missed but the db exists on the file system
+        let in_namespace = list
+            .iter()
+            .filter(|item| item.get_namespace() == db_info.get_namespace())
+            .count();
+        if in_namespace >= max_databases {
+            return Err(DBPacketResponseError::NamespaceFull);
+        }
+        Ok(())
+    }
 
-                let mut db = Self::read_db_from_file(p_info)?;
+    /// Replaces the settings of a namespace, hashing the keys of its super admins. The namespace does not need to hold any dbs yet.
+    /// Requires super admin privileges of the server
+    #[tracing::instrument(skip(self, settings))]
+    pub fn set_namespace(
+        &self,
+        namespace: &str,
+        mut settings: NamespaceSettings,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        if !self.is_super_admin(client_key) {
+            return Err(InvalidPermissions);
+        }
 
-                db.update_access_time();
+        if namespace.is_empty() || namespace.contains(NAMESPACE_SEPARATOR) {
+            return Err(DBPacketResponseError::InvalidDBName);
+        }
 
-                let resp = if db.get_role(client_key, &super_admin_list).is_admin() {
-                    serde_json::to_string(db.get_statistics())
-                        .map(SuccessReply)
-                        .map_err(|_| SerializationError)
-                } else {
-                    Err(InvalidPermissions)
-                };
+        let mut namespaces = self.namespaces.write().unwrap();
+        let stored = namespaces
+            .get(namespace)
+            .map(|stored| stored.super_admins.as_slice())
+            .unwrap_or_default();
+        self.key_hasher()
+            .hash_new_keys(&mut settings.super_admins, stored);
+        namespaces.insert(namespace.to_string(), settings);
+        info!("Successfully set the settings of namespace: {}", namespace);
+        Ok(SuccessNoData)
+    }
 
-                self.cache
-                    .write()
-                    .unwrap()
-                    .insert(p_info.clone(), RwLock::from(db));
+    /// Replies with the settings of a namespace, a namespace that has not been configured has no super admins and no limits.
+    /// Requires super admin privileges of the server, or of the namespace
+    #[tracing::instrument(skip(self))]
+    pub fn get_namespace(
+        &self,
+        namespace: &str,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let settings = self
+            .namespaces
+            .read()
+            .unwrap()
+            .get(namespace)
+            .cloned()
+            .unwrap_or_default();
 
-                resp
-            } else {
-                // cache was neither hit, nor did the db exist on the file system
-                info!("Database not found {}", p_info);
-                Err(DBNotFound)
-            };
+        if !(self.is_super_admin(client_key) || settings.is_super_admin(client_key)) {
+            return Err(InvalidPermissions);
         }
+
+        serde_json::to_string(&settings)
+            .map(SuccessReply)
+            .map_err(|_| SerializationError)
     }
 
-    /// Deletes the given data from a db if the user has write permissions
+    #[allow(unused_variables)]
+    #[allow(clippy::ptr_arg)]
+    /// Replies with the `n` most accessed keys of the given db and their number of accesses, most accessed first.
+    /// Keys are only counted when the db settings track key hits.
+    /// Requires admin privileges on the given db.
     #[tracing::instrument(skip(self))]
-    pub fn delete_data(
+    pub fn get_hot_keys(
         &self,
         p_info: &DBPacketInfo,
-        db_location: &DBLocation,
+        n: usize,
         client_key: &String,
     ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
-        let super_admin_list = self.get_super_admin_list();
-
-        let list_lock = self.list.read().unwrap();
-        if let Some(db) = self.cache.read().unwrap().get(p_info) {
-            info!("DB Cache hit");
-            // cache was hit
-            let mut db_lock = db.write().unwrap();
-
-            db_lock.update_access_time();
-
-            return if db_lock.has_write_permissions(client_key, &super_admin_list) {
-                db_lock
-                    .get_content_mut()
-                    .content
-                    .remove(db_location.as_key())
-                    .map(SuccessReply)
-                    .ok_or(ValueNotFound)
-            } else {
-                Err(InvalidPermissions)
-            };
+        #[cfg(not(feature = "statistics"))]
+        {
+            warn!("Hot keys packet received, however statistics is not enabled on this server");
+            Err(DBPacketResponseError::BadPacket)
         }
 
-        return if list_lock.contains(p_info) {
-            info!("DB Cache missed");
-            // cache was missed but the db exists on the file system
-
-            let mut db = Self::read_db_from_file(p_info)?;
+        #[cfg(feature = "statistics")]
+        {
+            let super_admin_list = self.get_super_admin_list(p_info);
 
-            db.update_access_time();
+            self.with_db(p_info, |db| {
+                if !db.get_role(client_key, &super_admin_list).is_admin() {
+                    return Err(InvalidPermissions);
+                }
 
-            let resp = if db.has_write_permissions(client_key, &super_admin_list) {
-                db.get_content_mut()
-                    .content
-                    .remove(db_location.as_key())
+                serde_json::to_string(&db.get_statistics().get_hot_keys(n))
                     .map(SuccessReply)
-                    .ok_or(ValueNotFound)
-            } else {
-                Err(InvalidPermissions)
-            };
-
-            self.cache
-                .write()
-                .unwrap()
-                .insert(p_info.clone(), RwLock::from(db));
-
-            resp
-        } else {
-            // cache was neither hit, nor did the db exist on the file system
-            info!("Database not found {}", p_info);
-            Err(DBNotFound)
-        };
+                    .map_err(|_| SerializationError)
+            })
+        }
     }
 
-    /// Responds with the role of the client key inside a given db, if they are a super admin, the result is always a super admin role.
+    #[allow(unused_variables)]
+    #[allow(clippy::ptr_arg)]
+    /// Clears the `DBStatistics` of the given db.
+    /// Requires super admin privileges.
     #[tracing::instrument(skip(self))]
-    pub fn get_role(
+    pub fn reset_stats(
         &self,
         p_info: &DBPacketInfo,
         client_key: &String,
     ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
-        let super_admin_list = self.get_super_admin_list();
-
-        if super_admin_list.contains(client_key) {
-            info!("User was super admin");
-            // early return super admin if their key is a super admin key.
-            return Ok(SuccessReply(serde_json::to_string(&SuperAdmin).unwrap()));
+        #[cfg(not(feature = "statistics"))]
+        {
+            warn!("Reset statistics packet received, however statistics is not enabled on this server");
+            Err(DBPacketResponseError::BadPacket)
         }
 
-        let list_lock = self.list.read().unwrap();
-
-        if let Some(db) = self.cache.read().unwrap().get(p_info) {
-            info!("DB Cache hit");
-            // cache was hit
-            let mut db_lock = db.write().unwrap();
-
-            db_lock.update_access_time();
-
-            let serialized_role =
-                serde_json::to_string(&db_lock.get_role(client_key, &super_admin_list)).unwrap();
+        #[cfg(feature = "statistics")]
+        {
+            if !self.is_super_admin_of(client_key, p_info) {
+                return Err(InvalidPermissions);
+            }
 
-            return Ok(SuccessReply(serialized_role));
+            self.with_db(p_info, |db| {
+                db.reset_statistics();
+                Ok(SuccessNoData)
+            })
         }
+    }
 
-        return if list_lock.contains(p_info) {
-            info!("DB Cache missed");
-            // cache was missed but the db exists on the file system
-
-            let mut db = Self::read_db_from_file(p_info)?;
+    #[allow(unused_variables)]
+    #[allow(clippy::ptr_arg)]
+    /// Replies with the `ServerStatistics` and the `DBStatistics` of every db, as a `StatisticsExport` in the given format.
+    /// Requires super admin privileges.
+    #[tracing::instrument(skip(self))]
+    pub fn export_stats(
+        &self,
+        format: StatsFormat,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        #[cfg(not(feature = "statistics"))]
+        {
+            warn!("Export statistics packet received, however statistics is not enabled on this server");
+            Err(DBPacketResponseError::BadPacket)
+        }
 
-            db.update_access_time();
+        #[cfg(feature = "statistics")]
+        {
+            if !self.is_super_admin(client_key) {
+                return Err(InvalidPermissions);
+            }
 
-            let serialized_role =
-                serde_json::to_string(&db.get_role(client_key, &super_admin_list)).unwrap();
+            let db_list = self.list.read().unwrap().clone();
+            let mut databases = BTreeMap::new();
+            for p_info in db_list {
+                let statistics = self.with_db(&p_info, |db| Ok(db.get_statistics().clone()))?;
+                databases.insert(p_info.get_db_name().to_string(), statistics);
+            }
 
-            self.cache
-                .write()
-                .unwrap()
-                .insert(p_info.clone(), RwLock::from(db));
+            let export = StatisticsExport {
+                server: self.server_statistics.read().unwrap().clone(),
+                databases,
+            };
 
-            Ok(SuccessReply(serialized_role))
-        } else {
-            // cache was neither hit, nor did the db exist on the file system
-            info!("Database not found {}", p_info);
-            Err(DBNotFound)
-        };
+            match format {
+                StatsFormat::Json => serde_json::to_string(&export)
+                    .map(SuccessReply)
+                    .map_err(|_| SerializationError),
+                StatsFormat::Csv => Ok(SuccessReply(export.to_csv())),
+            }
+        }
     }
 
-    /// Replaces `DBSettings` for a given DB, requires super admin privileges.
-    /// Returns `SuccessNoData` when successful
+    #[allow(unused_variables)]
+    #[allow(clippy::ptr_arg)]
+    /// Returns the `ServerStatistics` of every request the server has handled.
+    /// Requires super admin privileges.
     #[tracing::instrument(skip(self))]
-    pub fn change_db_settings(
+    pub fn get_server_stats(
         &self,
-        p_info: &DBPacketInfo,
-        new_db_settings: DBSettings,
         client_key: &String,
     ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
-        if !self.is_super_admin(client_key) {
-            // change settings requires super admin, early return if the user is not a super admin
-            info!("User was not super admin");
-            return Err(InvalidPermissions);
+        #[cfg(not(feature = "statistics"))]
+        {
+            warn!("Server statistics packet received, however statistics is not enabled on this server");
+            Err(DBPacketResponseError::BadPacket)
         }
 
-        let list_lock = self.list.read().unwrap();
-        if let Some(db) = self.cache.read().unwrap().get(p_info) {
-            info!("DB cache hit");
-            // cache was hit
-            let mut db_lock = db.write().unwrap();
-
-            db_lock.update_access_time();
+        #[cfg(feature = "statistics")]
+        {
+            if !self.is_super_admin(client_key) {
+                return Err(InvalidPermissions);
+            }
 
-            db_lock.set_settings(new_db_settings);
-            drop(db_lock);
-            return Ok(SuccessNoData);
+            Ok(SuccessData(ResponseData::ServerStats(
+                self.server_statistics.read().unwrap().clone(),
+            )))
         }
+    }
 
-        return if list_lock.contains(p_info) {
-            info!("DB cache missed");
-            // cache was missed but the db exists on the file system
-
-            let mut db = Self::read_db_from_file(p_info)?;
-
-            db.update_access_time();
-
-            self.cache
-                .write()
-                .unwrap()
-                .insert(p_info.clone(), RwLock::from(db));
-
-            Ok(SuccessNoData)
-        } else {
-            // cache was neither hit, nor did the db exist on the file system
-            info!("Database not found {}", p_info);
-            Err(DBNotFound)
-        };
+    /// Records a request handled by the server in the `ServerStatistics`, given the name of its packet type and the size of the packet and its response.
+    #[cfg(feature = "statistics")]
+    pub fn record_request(
+        &self,
+        packet_name: &str,
+        bytes_in: usize,
+        bytes_out: usize,
+        is_error: bool,
+    ) {
+        self.server_statistics.write().unwrap().record_request(
+            packet_name,
+            bytes_in,
+            bytes_out,
+            is_error,
+        );
     }
 
-    /// Returns the `DBSettings` serialized as a string
-    /// Only super admins can get the db settings
+    #[allow(unused_variables)]
+    #[allow(clippy::ptr_arg)]
+    /// Returns the db stats used for a given database when permissions allow the user to read them
     #[tracing::instrument(skip(self))]
-    pub fn get_db_settings(
+    pub fn get_stats(
         &self,
         p_info: &DBPacketInfo,
         client_key: &String,
     ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
-        if !self.is_super_admin(client_key) {
-            info!("Client is not super admin");
-            // change settings requires super admin, early return if the user is not a super admin
-            return Err(InvalidPermissions);
+        #[cfg(not(feature = "statistics"))]
+        {
+            warn!("Statistics packet received, however statistics is not enabled on this server");
+            Err(DBPacketResponseError::BadPacket)
         }
 
-        let list_lock = self.list.read().unwrap();
-        if let Some(db) = self.cache.read().unwrap().get(p_info) {
-            info!("DB Cache hit");
-
-            // cache was hit
-            let mut db_lock = db.write().unwrap();
-
-            db_lock.update_access_time();
+        #[cfg(feature = "statistics")]
+        {
+            let super_admin_list = self.get_super_admin_list(p_info);
 
-            return serde_json::to_string(&db_lock.get_settings())
-                .map(SuccessReply)
-                .map_err(|_| SerializationError);
+            self.with_db(p_info, |db| {
+                if db.get_role(client_key, &super_admin_list).is_admin() {
+                    Ok(SuccessData(ResponseData::Stats(
+                        db.get_statistics().clone(),
+                    )))
+                } else {
+                    Err(InvalidPermissions)
+                }
+            })
         }
+    }
 
-        return if list_lock.contains(p_info) {
-            info!("DB Cache missed");
-            // cache was missed but the db exists on the file system
-
-            let mut db = Self::read_db_from_file(p_info)?;
+    /// Deletes the given data from a db if the user has delete permissions
+    #[tracing::instrument(skip(self))]
+    pub fn delete_data(
+        &self,
+        p_info: &DBPacketInfo,
+        db_location: &DBLocation,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(p_info);
 
-            db.update_access_time();
+        self.with_db(p_info, |db| {
+            if !db.has_key_delete_permissions(db_location.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
 
-            let response = serde_json::to_string(&db.get_settings())
+            db.record_key_hit(db_location.as_key());
+            let resp = db
+                .change_content(|content| {
+                    let removed = content.remove(db_location.as_key());
+                    let changed = removed.is_some();
+                    (removed, changed)
+                })
                 .map(SuccessReply)
-                .map_err(|_| SerializationError);
-
-            self.cache
-                .write()
-                .unwrap()
-                .insert(p_info.clone(), RwLock::from(db));
-
-            response
-        } else {
-            // cache was neither hit, nor did the db exist on the file system
-            Err(DBNotFound)
-        };
+                .ok_or(ValueNotFound);
+            if resp.is_ok() {
+                self.notify_deleted(p_info, db_location.as_key());
+            }
+            resp
+        })
     }
 
-    /// Adds a user to a given DB, requires admin privileges or super admin privileges.
+    /// Responds with the role of the client key inside a given db, if they are a super admin, the result is always a super admin role.
     #[tracing::instrument(skip(self))]
-    pub fn add_user(
+    pub fn get_role(
         &self,
         p_info: &DBPacketInfo,
-        new_key: String,
         client_key: &String,
     ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
-        let list_lock = self.list.read().unwrap();
-        if let Some(db) = self.cache.read().unwrap().get(p_info) {
-            info!("DB Cache hit");
-            // cache was hit
-            let mut db_lock = db.write().unwrap();
+        let super_admin_list = self.get_super_admin_list(p_info);
 
-            return if db_lock.get_settings().is_admin(client_key) || self.is_super_admin(client_key)
-            {
-                db_lock.update_access_time();
-
-                db_lock.get_settings_mut().add_user(new_key);
-                Ok(SuccessNoData)
-            } else {
-                Err(InvalidPermissions)
-            };
+        if super_admin_list.contains(client_key) {
+            info!("User was super admin");
+            // early return super admin if their key is a super admin key.
+            return Ok(SuccessData(ResponseData::Role(SuperAdmin)));
         }
 
-        return if list_lock.contains(p_info) {
-            info!("DB Cache missed");
-            // cache was missed but the db exists on the file system
+        self.with_db(p_info, |db| {
+            Ok(SuccessData(ResponseData::Role(
+                db.get_role(client_key, &super_admin_list),
+            )))
+        })
+    }
 
-            let mut db = Self::read_db_from_file(p_info)?;
+    /// Responds with the role and permissions the client key resolves to in a given db, along with the key acl rules that limit it.
+    /// Requires no permissions, as it only describes what the client itself may do
+    #[tracing::instrument(skip(self))]
+    pub fn get_effective_permissions(
+        &self,
+        p_info: &DBPacketInfo,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(p_info);
 
-            db.update_access_time();
+        self.with_db(p_info, |db| {
+            Ok(SuccessData(ResponseData::EffectivePermissions(
+                db.get_effective_permissions(client_key, &super_admin_list),
+            )))
+        })
+    }
 
-            let response =
-                if db.get_settings().is_admin(client_key) || self.is_super_admin(client_key) {
-                    db.get_settings_mut().add_admin(new_key);
-                    Ok(SuccessNoData)
-                } else {
-                    Err(InvalidPermissions)
-                };
+    /// Replaces `DBSettings` for a given DB, requires manage settings permissions, which admins and super admins always have.
+    /// Returns `SuccessNoData` when successful
+    #[tracing::instrument(skip(self))]
+    pub fn change_db_settings(
+        &self,
+        p_info: &DBPacketInfo,
+        new_db_settings: DBSettings,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(p_info);
 
-            self.cache
-                .write()
-                .unwrap()
-                .insert(p_info.clone(), RwLock::from(db));
+        self.with_db(p_info, |db| {
+            if !db.has_permission(Permission::ManageSettings, client_key, &super_admin_list) {
+                info!("Client lacks manage settings permissions");
+                return Err(InvalidPermissions);
+            }
 
-            response
-        } else {
-            // cache was neither hit, nor did the db exist on the file system
-            Err(DBNotFound)
-        };
+            if let Some(schema) = new_db_settings.get_schema() {
+                schema.check()?;
+            }
+
+            db.set_settings(new_db_settings);
+            self.log_settings(p_info, db);
+            Ok(SuccessNoData)
+        })
     }
 
-    /// Removes a user from a given DB, requires admin privileges
+    /// Returns the `DBSettings` of the given db as `ResponseData::Settings`
+    /// Requires manage settings permissions, which admins and super admins always have.
     #[tracing::instrument(skip(self))]
-    pub fn remove_user(
+    pub fn get_db_settings(
         &self,
         p_info: &DBPacketInfo,
-        removed_key: &str,
         client_key: &String,
     ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
-        let list_lock = self.list.read().unwrap();
-        if let Some(db) = self.cache.read().unwrap().get(p_info) {
-            info!("DB Cache hit");
-            // cache was hit
-            let mut db_lock = db.write().unwrap();
+        let super_admin_list = self.get_super_admin_list(p_info);
 
-            return if db_lock.get_settings().is_admin(client_key) || self.is_super_admin(client_key)
-            {
-                db_lock.update_access_time();
+        self.with_db(p_info, |db| {
+            if !db.has_permission(Permission::ManageSettings, client_key, &super_admin_list) {
+                info!("Client lacks manage settings permissions");
+                return Err(InvalidPermissions);
+            }
 
-                if db_lock.get_settings_mut().remove_user(removed_key) {
-                    Ok(SuccessNoData)
-                } else {
-                    Err(UserNotFound)
-                }
-            } else {
-                Err(InvalidPermissions)
-            };
-        }
+            Ok(SuccessData(ResponseData::Settings(Box::new(
+                db.get_settings().clone(),
+            ))))
+        })
+    }
 
-        return if list_lock.contains(p_info) {
-            info!("DB Cache missed");
-            // cache was missed but the db exists on the file system
+    /// Adds a user to a given DB, requires manage users permissions, which admins and super admins always have.
+    #[tracing::instrument(skip(self))]
+    pub fn add_user(
+        &self,
+        p_info: &DBPacketInfo,
+        new_key: String,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(p_info);
 
-            let mut db = Self::read_db_from_file(p_info)?;
+        self.with_db(p_info, |db| {
+            if !db.has_permission(Permission::ManageUsers, client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
 
-            db.update_access_time();
+            db.get_settings_mut().add_user(new_key);
+            self.log_settings(p_info, db);
+            Ok(SuccessNoData)
+        })
+    }
 
-            let response =
-                if db.get_settings().is_admin(client_key) || self.is_super_admin(client_key) {
-                    if db.get_settings_mut().remove_user(removed_key) {
-                        Ok(SuccessNoData)
-                    } else {
-                        Err(UserNotFound)
-                    }
-                } else {
-                    Err(InvalidPermissions)
-                };
+    /// Removes a user from a given DB, requires manage users permissions, which admins and super admins always have.
+    #[tracing::instrument(skip(self))]
+    pub fn remove_user(
+        &self,
+        p_info: &DBPacketInfo,
+        removed_key: &str,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(p_info);
 
-            self.cache
-                .write()
-                .unwrap()
-                .insert(p_info.clone(), RwLock::from(db));
+        self.with_db(p_info, |db| {
+            if !db.has_permission(Permission::ManageUsers, client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
 
-            response
-        } else {
-            // cache was neither hit, nor did the db exist on the file system
-            Err(DBNotFound)
-        };
+            if db.get_settings_mut().remove_user(removed_key) {
+                self.log_settings(p_info, db);
+                Ok(SuccessNoData)
+            } else {
+                Err(UserNotFound)
+            }
+        })
     }
 
     /// Remove an admin from given DB, requires super admin permissions.
@@ -581,52 +797,19 @@ impl DBList {
         removed_key: &str,
         client_key: &String,
     ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
-        if !self.is_super_admin(client_key) {
+        if !self.is_super_admin_of(client_key, p_info) {
             // change settings requires super admin, early return if the user is not a super admin
             return Err(InvalidPermissions);
         }
 
-        let list_lock = self.list.read().unwrap();
-        if let Some(db) = self.cache.read().unwrap().get(p_info) {
-            info!("DB Cache hit");
-            // cache was hit
-            let mut db_lock = db.write().unwrap();
-
-            db_lock.update_access_time();
-
-            return if db_lock.get_settings_mut().remove_admin(removed_key) {
+        self.with_db(p_info, |db| {
+            if db.get_settings_mut().remove_admin(removed_key) {
+                self.log_settings(p_info, db);
                 Ok(SuccessNoData)
             } else {
                 Err(UserNotFound)
-            };
-        }
-
-        return if list_lock.contains(p_info) {
-            info!("DB Cache missed");
-            // cache was missed but the db exists on the file system
-
-            let mut db = Self::read_db_from_file(p_info)?;
-
-            db.update_access_time();
-
-            let response = {
-                if db.get_settings_mut().remove_admin(removed_key) {
-                    Ok(SuccessNoData)
-                } else {
-                    Err(UserNotFound)
-                }
-            };
-
-            self.cache
-                .write()
-                .unwrap()
-                .insert(p_info.clone(), RwLock::from(db));
-
-            response
-        } else {
-            // cache was neither hit, nor did the db exist on the file system
-            Err(DBNotFound)
-        };
+            }
+        })
     }
 
     /// Adds an admin to a given database, requires super admin permissions to perform.
@@ -637,156 +820,499 @@ impl DBList {
         hash: String,
         client_key: &String,
     ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
-        if !self.is_super_admin(client_key) {
+        if !self.is_super_admin_of(client_key, p_info) {
             info!("User is not a super admin");
             // to add an admin, you must be a super admin first, else you have invalid permissions
             return Err(InvalidPermissions);
         }
 
-        let list_lock = self.list.read().unwrap();
-        if let Some(db) = self.cache.read().unwrap().get(p_info) {
-            info!("DB Cache hit");
-            // cache was hit
-            let mut db_lock = db.write().unwrap();
-            db_lock.update_access_time();
+        self.with_db(p_info, |db| {
+            db.get_settings_mut().add_admin(hash);
+            self.log_settings(p_info, db);
+            Ok(SuccessNoData)
+        })
+    }
 
-            db_lock.get_settings_mut().add_admin(hash);
-            drop(db_lock);
-            return Ok(SuccessNoData);
-        }
+    /// Adds a rule limiting a key to the entries of the db under a prefix, replacing the rule with the same key and prefix.
+    /// Requires admin privileges on the given db.
+    #[tracing::instrument(skip(self))]
+    pub fn add_key_acl(
+        &self,
+        p_info: &DBPacketInfo,
+        rule: KeyAclRule,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        self.with_db(p_info, |db| {
+            if !(db.get_settings().is_admin(client_key)
+                || self.is_super_admin_of(client_key, p_info))
+            {
+                return Err(InvalidPermissions);
+            }
 
-        return if list_lock.contains(p_info) {
-            info!("DB Cache missed");
-            // cache was missed but the db exists on the file system
+            db.get_settings_mut().add_key_acl(rule);
+            self.log_settings(p_info, db);
+            Ok(SuccessNoData)
+        })
+    }
 
-            let mut db = Self::read_db_from_file(p_info)?;
+    /// Removes the rule with the given key hash and prefix from the db.
+    /// Requires admin privileges on the given db.
+    #[tracing::instrument(skip(self))]
+    pub fn remove_key_acl(
+        &self,
+        p_info: &DBPacketInfo,
+        key: &str,
+        prefix: &str,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        self.with_db(p_info, |db| {
+            if !(db.get_settings().is_admin(client_key)
+                || self.is_super_admin_of(client_key, p_info))
+            {
+                return Err(InvalidPermissions);
+            }
 
-            db.update_access_time();
-            db.get_settings_mut().add_admin(hash);
+            if db.get_settings_mut().remove_key_acl(key, prefix) {
+                self.log_settings(p_info, db);
+                Ok(SuccessNoData)
+            } else {
+                Err(UserNotFound)
+            }
+        })
+    }
 
-            self.cache
-                .write()
-                .unwrap()
-                .insert(p_info.clone(), RwLock::from(db));
+    /// Replies with every key acl rule of the db.
+    /// Requires admin privileges on the given db.
+    #[tracing::instrument(skip(self))]
+    pub fn get_key_acls(
+        &self,
+        p_info: &DBPacketInfo,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        self.with_db(p_info, |db| {
+            if !(db.get_settings().is_admin(client_key)
+                || self.is_super_admin_of(client_key, p_info))
+            {
+                return Err(InvalidPermissions);
+            }
 
-            Ok(SuccessNoData)
-        } else {
-            // cache was neither hit, nor did the db exist on the file system
-            Err(DBNotFound)
-        };
+            serde_json::to_string(db.get_settings().get_key_acls())
+                .map(SuccessReply)
+                .map_err(|_| SerializationError)
+        })
     }
 
-    /// Removes all caches which last access time exceeds their invalidation time.
-    /// Read locks the cache list, will Write lock the cache list if there are caches to be removed.
-    /// Returns the number of caches removed.
-    #[tracing::instrument(skip_all)]
-    pub fn sleep_caches(&self) -> usize {
-        // prepare a list of invalid caches
-        let invalid_cache_names: Vec<DBPacketInfo> = {
-            let read_lock = self.cache.read().unwrap();
-            read_lock
-                .iter()
-                // filter to keep only caches that have a last access duration greater than their invalidation time.
-                .filter(|(_, db)| {
-                    let db_lock = db.read().unwrap();
-                    let last_access_time = db_lock.get_access_time();
-                    let invalidation_time = db_lock.get_settings().get_invalidation_time();
-                    drop(db_lock);
+    /// Replaces the schema every value written to the db must match, or removes it when there is none.
+    /// Values already in the db are not checked against the new schema.
+    /// Requires manage settings permissions on the given db.
+    #[tracing::instrument(skip(self))]
+    pub fn set_db_schema(
+        &self,
+        p_info: &DBPacketInfo,
+        schema: Option<ValueSchema>,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(p_info);
 
-                    match SystemTime::now().duration_since(last_access_time) {
-                        // invalidate them based on their duration since access and invalidation time
-                        Ok(duration_since_access) => duration_since_access >= invalidation_time,
-                        // if there is some sort of duration error, simply don't invalidate them
-                        Err(_) => false,
-                    }
-                })
-                .map(|(db_name, _)| db_name.clone()) // there has to be a way to get rid of this clone -_-
-                .collect()
-        };
-        info!("DB sleep list: {:?}", invalid_cache_names);
-        info!("Putting {} databases to sleep", invalid_cache_names.len());
+        if let Some(schema) = &schema {
+            schema.check()?;
+        }
 
-        if !invalid_cache_names.is_empty() {
-            // only write lock the cache if we have caches to remove.
-            let mut write_lock = self.cache.write().unwrap();
-            for invalid_cache_name in &invalid_cache_names {
-                info!("DB being put to sleep: {}", invalid_cache_name);
-                write_lock.remove(invalid_cache_name);
+        self.with_db(p_info, |db| {
+            if !db.has_permission(Permission::ManageSettings, client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
             }
-        }
-        invalid_cache_names.len()
+
+            db.get_settings_mut().schema = schema;
+            self.log_settings(p_info, db);
+            Ok(SuccessNoData)
+        })
     }
 
-    /// Saves all db instances to a file.
-    #[tracing::instrument(skip_all)]
-    pub fn save_all_db(&self) {
-        info!("Saving all databases");
-        let list = self.cache.read().unwrap();
-        for (db_name, db) in list.iter() {
-            let mut db_file = match File::create(format!("./data/{}", db_name.get_db_name())) {
-                Ok(f) => {
-                    info!("DB file created for DB: {}", db_name);
-                    f
-                }
-                Err(e) => {
-                    let log_message =
-                        format!("Unable to create db file: {}, {}", db_name.get_db_name(), e);
-                    error!("{}", log_message);
-                    panic!("{}", log_message);
-                }
-            };
+    /// Replies with the schema of the db, or `SuccessNoData` if it has none.
+    /// Requires read or write permissions on the given db.
+    #[tracing::instrument(skip(self))]
+    pub fn get_db_schema(
+        &self,
+        p_info: &DBPacketInfo,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(p_info);
 
-            let db_lock = db.read().unwrap();
-            let ser = match serde_json::to_string(&db_lock.clone()) {
-                Ok(s) => {
-                    info!("Successfully serialized database");
-                    s
-                }
-                Err(e) => {
-                    let log_message = format!(
-                        "Unable to serialize db file: {}, {}",
-                        db_name.get_db_name(),
-                        e
-                    );
-                    error!("{}", log_message);
-                    panic!("{}", log_message)
-                }
+        self.with_db(p_info, |db| {
+            if !(db.has_read_permissions(client_key, &super_admin_list)
+                || db.has_write_permissions(client_key, &super_admin_list))
+            {
+                return Err(InvalidPermissions);
+            }
+
+            match db.get_settings().get_schema() {
+                None => Ok(SuccessNoData),
+                Some(schema) => serde_json::to_string(schema)
+                    .map(SuccessReply)
+                    .map_err(|_| SerializationError),
+            }
+        })
+    }
+
+    /// Replaces the description and tags of the db, the rest of its metadata is kept up to date by the server.
+    /// Requires manage settings permissions on the given db.
+    #[tracing::instrument(skip(self))]
+    pub fn set_db_metadata(
+        &self,
+        p_info: &DBPacketInfo,
+        description: String,
+        tags: Vec<String>,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(p_info);
+
+        self.with_db(p_info, |db| {
+            if !db.has_permission(Permission::ManageSettings, client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
+
+            let metadata = DBMetadata {
+                description,
+                tags,
+                ..db.get_metadata().clone()
             };
-            match db_file.write(ser.as_bytes()) {
-                Ok(len) => {
-                    info!("Successfully wrote {} to file with size: {}", db_name, len);
-                }
-                Err(e) => {
-                    let log_message = format!(
-                        "Unable to write to db file: {}, {}",
-                        db_name.get_db_name(),
-                        e
-                    );
-                    error!("{}", log_message);
-                    panic!("{}", log_message);
+            db.set_metadata(metadata.clone());
+            self.log_change(p_info, WalOp::Metadata(metadata));
+            Ok(SuccessNoData)
+        })
+    }
+
+    /// Replies with the metadata of the db in a serialized form of `DBMetadata`.
+    /// Requires list permissions on the given db.
+    #[tracing::instrument(skip(self))]
+    pub fn get_db_metadata(
+        &self,
+        p_info: &DBPacketInfo,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(p_info);
+
+        self.with_db(p_info, |db| {
+            if !db.has_list_permissions(client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
+
+            serde_json::to_string(db.get_metadata())
+                .map(SuccessReply)
+                .map_err(|_| SerializationError)
+        })
+    }
+
+    /// Removes all caches which last access time exceeds their invalidation time,
+    /// then the least recently accessed caches until the rest fit within the cache memory budget, if there is one.
+    /// Databases with unsaved changes are saved as they are removed.
+    /// Returns the number of caches removed.
+    #[tracing::instrument(skip_all)]
+    pub fn sleep_caches(&self) -> usize {
+        // prepare a list of invalid caches
+        let invalid_cache_names: Vec<DBPacketInfo> = {
+            self.cache
+                .entries()
+                .into_iter()
+                // filter to keep only caches that have a last access duration greater than their invalidation time.
+                .filter(|(_, db)| {
+                    let db_lock = db.read().unwrap();
+                    let last_access_time = db_lock.get_access_time();
+                    let invalidation_time = db_lock.get_settings().get_invalidation_time();
+                    drop(db_lock);
+
+                    match SystemTime::now().duration_since(last_access_time) {
+                        // invalidate them based on their duration since access and invalidation time
+                        Ok(duration_since_access) => duration_since_access >= invalidation_time,
+                        // if there is some sort of duration error, simply don't invalidate them
+                        Err(_) => false,
+                    }
+                })
+                .map(|(db_name, _)| db_name)
+                .collect()
+        };
+        info!("DB sleep list: {:?}", invalid_cache_names);
+        info!("Putting {} databases to sleep", invalid_cache_names.len());
+
+        for invalid_cache_name in &invalid_cache_names {
+            info!("DB being put to sleep: {}", invalid_cache_name);
+            self.uncache_db(invalid_cache_name, true);
+        }
+
+        let evicted_caches = match self.cache_memory_budget {
+            Some(budget) => self.evict_caches_over_budget(budget),
+            None => 0,
+        };
+        invalid_cache_names.len() + evicted_caches
+    }
+
+    /// Removes the least recently accessed caches until the approximate size of the cached databases is within the budget.
+    /// Databases with unsaved changes are saved as they are removed, or kept in the cache when saving is disabled so the changes are not lost.
+    /// Returns the number of caches removed.
+    #[tracing::instrument(skip(self))]
+    fn evict_caches_over_budget(&self, budget: usize) -> usize {
+        let evicted_cache_names: Vec<DBPacketInfo> = {
+            let mut total_size = 0;
+            let mut evictable = vec![];
+            for (db_name, db) in self.cache.entries() {
+                let db_lock = db.read().unwrap();
+                let size = db_lock.approximate_size();
+                total_size += size;
+                if self.saving_enabled || !db_lock.is_dirty() {
+                    evictable.push((db_name, db_lock.get_access_time(), size));
                 }
             }
+            info!(
+                "Cached databases take up about {} bytes of the {} byte budget",
+                total_size, budget
+            );
+
+            // least recently accessed first
+            evictable.sort_by_key(|(_, access_time, _)| *access_time);
+            evictable
+                .into_iter()
+                .take_while(|(_, _, size)| {
+                    if total_size <= budget {
+                        return false;
+                    }
+                    total_size -= size;
+                    true
+                })
+                .map(|(db_name, _, _)| db_name)
+                .collect()
+        };
+
+        for evicted_cache_name in &evicted_cache_names {
+            info!("DB being evicted from the cache: {}", evicted_cache_name);
+            self.uncache_db(evicted_cache_name, true);
+        }
+        evicted_cache_names.len()
+    }
+
+    /// Sets the token that claims the first super admin, a super admin can not be claimed until a token is set.
+    /// The token is kept only in memory, and only until it is claimed.
+    #[tracing::instrument(skip_all)]
+    pub fn set_bootstrap_token(&self, token: &str) {
+        *self.bootstrap_token.write().unwrap() = Some(self.key_hasher().hash_key(token));
+    }
+
+    /// Makes the given client key a super admin, if the token is the bootstrap token and no super admin has been claimed yet.
+    /// This is the only way a key becomes the first super admin, and the token itself can not be that key, as it is printed by the server.
+    /// The token can only be claimed once.
+    #[tracing::instrument(skip_all)]
+    pub fn claim_super_admin(
+        &self,
+        token: &str,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        // the token is only hashed while it can still be claimed, so claiming it again costs nothing
+        if client_key.is_empty()
+            || self.bootstrap_token.read().unwrap().is_none()
+            || !self.super_admin_hash_list.read().unwrap().is_empty()
+        {
+            warn!("Invalid or already claimed bootstrap token was used");
+            return Err(InvalidPermissions);
+        }
+        let claimed_hash = self.key_hasher().hash_key(token);
+
+        let mut super_admin_list_lock = self.super_admin_hash_list.write().unwrap();
+        let mut bootstrap_token_lock = self.bootstrap_token.write().unwrap();
+        match bootstrap_token_lock.as_deref() {
+            Some(token_hash)
+                if super_admin_list_lock.is_empty()
+                    && token_hash == claimed_hash
+                    && token_hash != client_key =>
+            {
+                info!("Bootstrap token was claimed, making the client key the super admin");
+                super_admin_list_lock.push(client_key.to_string());
+                *bootstrap_token_lock = None;
+                Ok(SuccessNoData)
+            }
+            _ => {
+                warn!("Invalid or already claimed bootstrap token was used");
+                Err(InvalidPermissions)
+            }
+        }
+    }
+
+    /// Creates a user account with the given username and password, which has no roles until its account key is given one.
+    /// Requires super admin privileges.
+    #[tracing::instrument(skip(self, password))]
+    pub fn create_user(
+        &self,
+        username: &str,
+        password: &str,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        if !self.is_super_admin(client_key) {
+            return Err(InvalidPermissions);
+        }
+
+        if self
+            .user_registry
+            .write()
+            .unwrap()
+            .create_user(username, password)
+        {
+            Ok(SuccessNoData)
+        } else {
+            Err(UserAlreadyExists)
+        }
+    }
+
+    /// Disables or enables the user account with the given username, a disabled account can not be logged in to, and its sessions lose its roles.
+    /// Requires super admin privileges.
+    #[tracing::instrument(skip(self))]
+    pub fn set_user_disabled(
+        &self,
+        username: &str,
+        disabled: bool,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        if !self.is_super_admin(client_key) {
+            return Err(InvalidPermissions);
+        }
+
+        if self
+            .user_registry
+            .write()
+            .unwrap()
+            .set_disabled(username, disabled)
+        {
+            Ok(SuccessNoData)
+        } else {
+            Err(UserNotFound)
+        }
+    }
+
+    /// Replies with the username of every user account, and whether it is disabled.
+    /// Requires super admin privileges.
+    #[tracing::instrument(skip(self))]
+    pub fn list_users(
+        &self,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        if !self.is_super_admin(client_key) {
+            return Err(InvalidPermissions);
+        }
+
+        let users = self.user_registry.read().unwrap().list_users();
+        match serde_json::to_string(&users) {
+            Ok(thing) => Ok(SuccessReply(thing)),
+            Err(_) => Err(SerializationError),
+        }
+    }
+
+    /// Returns the account key to use as the client key of the session, if the username and password are correct and the account is not disabled.
+    #[tracing::instrument(skip(self, password))]
+    pub fn login(&self, username: &str, password: &str) -> Result<String, DBPacketResponseError> {
+        self.user_registry
+            .read()
+            .unwrap()
+            .login(username, password)
+            .ok_or(InvalidPermissions)
+    }
+
+    /// Returns false if the client key is the key of a user account that has been disabled.
+    pub fn is_active_key(&self, client_key: &str) -> bool {
+        !UserRegistry::is_account_key(client_key)
+            || self.user_registry.read().unwrap().is_active_key(client_key)
+    }
+
+    /// Replies with a session token that resumes the client key on another connection, until it expires.
+    /// Requires a key to have been set.
+    #[tracing::instrument(skip_all)]
+    pub fn create_session(
+        &self,
+        client_key: &str,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        if client_key.is_empty() {
+            return Err(InvalidPermissions);
+        }
+
+        Ok(SuccessReply(self.sessions.create_session(client_key)))
+    }
+
+    /// Returns the client key the session token was created with, if the session has not expired or ended.
+    #[tracing::instrument(skip_all)]
+    pub fn resume_session(&self, token: &str) -> Result<String, DBPacketResponseError> {
+        self.sessions
+            .resume_session(token)
+            .ok_or(InvalidPermissions)
+    }
+
+    /// Ends the session the token belongs to, so it can no longer be resumed.
+    #[tracing::instrument(skip_all)]
+    pub fn end_session(
+        &self,
+        token: &str,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        if self.sessions.end_session(token) {
+            Ok(SuccessNoData)
+        } else {
+            Err(InvalidPermissions)
+        }
+    }
+
+    /// Hashes every key in the super admin list and in the settings of every db, which were saved as plaintext before keys were hashed.
+    /// Every key is hashed, whatever it looks like, as the keys are known to be plaintext from `key_hash_version`.
+    /// The db list is saved afterwards, so the salt the keys were hashed with is kept.
+    #[tracing::instrument(skip(self))]
+    pub fn migrate_plaintext_keys(&mut self) {
+        info!("Hashing keys that were saved as plaintext");
+        self.key_hasher()
+            .hash_plaintext_keys(&mut self.super_admin_hash_list.write().unwrap());
+
+        let db_names = self.list.read().unwrap().clone();
+        for db_name in db_names {
+            let migrated = self.with_db(&db_name, |db| {
+                let mut settings = db.get_settings().clone();
+                self.key_hasher()
+                    .hash_plaintext_settings_keys(&mut settings);
+                db.set_settings(settings);
+                Ok(())
+            });
+            if let Err(err) = migrated {
+                error!("Unable to hash the keys of \"{}\": {:?}", db_name, err);
+            }
+        }
+
+        self.key_hash_version = KEY_HASH_VERSION;
+        self.save_all_db();
+        self.save_db_list();
+    }
+
+    /// Saves every cached db that has unsaved changes to a file.
+    #[tracing::instrument(skip_all)]
+    pub fn save_all_db(&self) {
+        if !self.saving_enabled {
+            return;
+        }
+        info!("Saving all databases");
+        for (db_name, db) in self.cache.entries() {
+            let mut db_lock = db.write().unwrap();
+            // an evicted db was saved as it was removed, or deleted
+            if db_lock.is_dirty() && !db_lock.is_evicted() {
+                self.save_db(&db_name, &mut db_lock);
+            }
         }
     }
 
-    /// Saves a specific db by name to file.
+    /// Saves a specific db by name to file, if it has unsaved changes.
     /// Read locks the cache.
     #[tracing::instrument(skip(self))]
     pub fn save_specific_db(&self, db_name: &DBPacketInfo) {
-        let list = self.cache.read().unwrap();
-        match list.get(db_name) {
-            Some(db_lock) => {
-                info!("Database exists, saving to file");
-                let mut db_file = File::create(format!("./data/{}", db_name.get_db_name())).expect(
-                    &format!("Unable to create db file: {}", db_name.get_db_name()),
-                );
-                let db_clone = db_lock.read().unwrap().clone();
-                let ser = serde_json::to_string(&db_clone).unwrap();
-                let _ = db_file.write(ser.as_bytes()).expect(&format!(
-                    "Unable to write to db file: {}",
-                    db_name.get_db_name()
-                ));
-                info!("Database successfully saved");
+        if !self.saving_enabled {
+            return;
+        }
+        match self.cache.get(db_name) {
+            Some(db) => {
+                let mut db_lock = db.write().unwrap();
+                if db_lock.is_dirty() && !db_lock.is_evicted() {
+                    info!("Database exists, saving to file");
+                    self.save_db(db_name, &mut db_lock);
+                }
             }
             None => {
                 let log_message = format!(
@@ -799,384 +1325,2527 @@ impl DBList {
         }
     }
 
+    /// Saves a specific db by name to file once it has at least `save_after_changes` unsaved changes.
+    /// Changes below that are saved by the next call to `save_all_db`, and are kept safe until then by the write-ahead log.
+    /// Does nothing if the db is not cached, such as when the change failed because the db does not exist.
+    #[tracing::instrument(skip(self))]
+    pub fn save_db_if_due(&self, db_name: &DBPacketInfo, save_after_changes: u64) {
+        if !self.saving_enabled {
+            return;
+        }
+        if let Some(db) = self.cache.get(db_name) {
+            let mut db_lock = db.write().unwrap();
+            if db_lock.is_dirty()
+                && !db_lock.is_evicted()
+                && db_lock.unsaved_changes() >= save_after_changes
+            {
+                self.save_db(db_name, &mut db_lock);
+            }
+        }
+    }
+
+    /// Writes the db to its file and marks its changes as saved.
+    /// The db stays write locked while it is written, so an older version of the db can never be written over a newer one.
+    fn save_db(&self, db_name: &DBPacketInfo, db: &mut DB) {
+        let ser = match serde_json::to_string(db) {
+            Ok(s) => {
+                info!("Successfully serialized database");
+                s
+            }
+            Err(e) => {
+                let log_message = format!(
+                    "Unable to serialize db file: {}, {}",
+                    db_name.get_db_name(),
+                    e
+                );
+                error!("{}", log_message);
+                panic!("{}", log_message)
+            }
+        };
+        match db_file::save(&self.db_file_path(db_name.get_db_name()), &ser) {
+            Ok(()) => {
+                db.mark_saved();
+                db.set_serialized_size(ser.len());
+                info!(
+                    "Successfully wrote {} to file with size: {}",
+                    db_name,
+                    ser.len()
+                );
+            }
+            Err(e) => {
+                let log_message = format!(
+                    "Unable to write to db file: {}, {}",
+                    db_name.get_db_name(),
+                    e
+                );
+                error!("{}", log_message);
+                panic!("{}", log_message);
+            }
+        }
+    }
+
     /// Saves all db names to a file.
     #[tracing::instrument(skip_all)]
     pub fn save_db_list(&self) {
+        if !self.saving_enabled {
+            return;
+        }
         info!("Saving database list");
-        let mut db_list_file =
-            File::create("./data/db_list.ser").expect("Unable to save db_list.ser");
         let ser_data = serde_json::to_string(&self).expect("Unable to serialize self.");
-        let _ = db_list_file
-            .write(ser_data.as_bytes())
+        db_file::save(&self.data_dir.join(DB_LIST_FILE_NAME), &ser_data)
             .expect("Unable to write bytes to db_list.ser");
         info!("Successfully saved database list");
     }
 
-    /// Loads all db names from the db list file.
+    /// Loads all db names from the db list file in the default data directory.
     #[tracing::instrument]
     pub fn load_db_list() -> Self {
+        Self::load_db_list_from(Path::new(DEFAULT_DATA_DIR))
+    }
+
+    /// Loads all db names from the db list file in the given data directory.
+    /// The loaded db list saves to and reads its databases from the same directory.
+    #[tracing::instrument]
+    pub fn load_db_list_from(data_dir: &Path) -> Self {
         info!("Loading database list");
-        match File::open("./data/db_list.ser") {
-            Ok(mut f) => {
+        let mut db_list = match db_file::load::<Self>(&data_dir.join(DB_LIST_FILE_NAME)) {
+            Ok(db_list) => {
                 // file found, load from file data
-                let mut ser = String::new();
-                f.read_to_string(&mut ser)
-                    .expect("Unable to read db_list.ser to string");
-                let db_list: Self =
-                    serde_json::from_str(&ser).expect("Unable to deserialize db_list.ser");
                 info!("Successfully opened database list and deserialized");
                 db_list
             }
-            Err(e) => {
-                warn!("No database list found, making one. This could be an error or is the first startup of the server. {}",e);
-                // no file found, load default
-                Self::default()
+            Err(DBFileError::NotFound) => {
+                warn!("No database list found, making one. This could be an error or is the first startup of the server.");
+                // no file found, load default
+                Self::default()
+            }
+            Err(e) => panic!("Unable to load db_list.ser: {}", e),
+        };
+        db_list.data_dir = data_dir.to_path_buf();
+        db_list.wal = WriteAheadLog::new(data_dir);
+        db_list.replay_wal();
+        db_list
+    }
+
+    /// Applies every change in the write-ahead log to the databases it was made to, leaving the changed databases in the cache.
+    /// The changes are saved to the database files by the next checkpoint.
+    #[tracing::instrument(skip(self))]
+    fn replay_wal(&self) {
+        let entries = self.wal.read_entries();
+        if entries.is_empty() {
+            return;
+        }
+        info!(
+            "Replaying {} changes from the write-ahead log",
+            entries.len()
+        );
+
+        let mut replayed: HashMap<DBPacketInfo, DB> = HashMap::new();
+
+        for WalEntry { db: db_name, op } in entries {
+            match op {
+                WalOp::DeleteDB => {
+                    replayed.remove(&db_name);
+                    self.list.write().unwrap().retain(|item| *item != db_name);
+                }
+                WalOp::RenameDB(new_name) => {
+                    if let Some(db) = replayed.remove(&db_name) {
+                        replayed.insert(new_name.clone(), db);
+                    }
+                    for item in self
+                        .list
+                        .write()
+                        .unwrap()
+                        .iter_mut()
+                        .filter(|item| **item == db_name)
+                    {
+                        *item = new_name.clone();
+                    }
+                }
+                WalOp::CreateDB(db) => {
+                    replayed.insert(db_name.clone(), *db);
+                    let mut list_lock = self.list.write().unwrap();
+                    if !list_lock.contains(&db_name) {
+                        list_lock.push(db_name);
+                    }
+                }
+                op => {
+                    if !replayed.contains_key(&db_name) {
+                        match self.read_db_from_file(&db_name) {
+                            Ok(db) => {
+                                replayed.insert(db_name.clone(), db);
+                            }
+                            Err(err) => {
+                                warn!("Skipping change to {}, {}", db_name, err);
+                                continue;
+                            }
+                        }
+                        // a db created after the db list was last saved has its file but no entry in the list
+                        let mut list_lock = self.list.write().unwrap();
+                        if !list_lock.contains(&db_name) {
+                            list_lock.push(db_name.clone());
+                        }
+                    }
+                    if let Some(db) = replayed.get_mut(&db_name) {
+                        op.apply(db);
+                    }
+                }
+            }
+        }
+
+        for (db_name, mut db) in replayed {
+            info!("Replayed changes to {}", db_name);
+            // keeps the db in the cache until the next checkpoint saves it
+            db.update_access_time();
+            self.cache.insert(db_name, db);
+        }
+    }
+
+    /// Saves every cached database and the db list, then removes the changes they contain from the write-ahead log.
+    /// Changes made while the databases are being saved stay in the log for the next checkpoint.
+    #[tracing::instrument(skip_all)]
+    pub fn checkpoint_wal(&self) {
+        if !self.saving_enabled {
+            return;
+        }
+        let saved_len = self.wal.len();
+        self.save_all_db();
+        self.save_db_list();
+        match self.wal.truncate_saved(saved_len) {
+            Ok(()) => info!("Checkpointed write-ahead log"),
+            Err(err) => error!("Unable to truncate write-ahead log: {}", err),
+        }
+    }
+
+    /// Returns the path of the file the given db is saved to, dbs in a namespace are saved directly in the data directory as well.
+    fn db_file_path(&self, db_name: &str) -> PathBuf {
+        self.data_dir.join(DBPacketInfo::new(db_name).file_name())
+    }
+
+    /// Returns true if the given db exists.
+    #[tracing::instrument(skip(self))]
+    fn db_name_exists(&self, db_name: &str) -> bool {
+        self.list
+            .read()
+            .unwrap()
+            .contains(&DBPacketInfo::new(db_name))
+    }
+
+    /// Creates a DB given a name, the packet is not needed, only the name.
+    /// Requires super admin privileges
+    #[tracing::instrument(skip(self))]
+    pub fn create_db(
+        &self,
+        db_name: &str,
+        db_settings: DBSettings,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        if !self.is_super_admin_of(client_key, &DBPacketInfo::new(db_name)) {
+            // to create a db you must be a super admin of the server, or of the namespace of the db
+            return Err(InvalidPermissions);
+        }
+
+        if !DBPacketInfo::new(db_name).is_valid_name() {
+            return Err(DBPacketResponseError::InvalidDBName);
+        }
+
+        if self.db_name_exists(db_name) {
+            return Err(DBPacketResponseError::DBAlreadyExists);
+        }
+
+        if let Some(schema) = db_settings.get_schema() {
+            schema.check()?;
+        }
+
+        let mut db = DB::new_from_settings(db_settings);
+        db.set_metadata(DBMetadata::new_created_by(Some(client_key.clone())));
+
+        // the db only lives in the cache when saving is disabled, so no file is created for it
+        self.add_new_db(&DBPacketInfo::new(db_name), db)?;
+        info!("Successfully created DB");
+        Ok(SuccessNoData)
+    }
+
+    /// Handles deleting a db, given a name for the db. Removes the database given a name, and moves the corresponding file to the trash.
+    /// If the file is successfully moved, the db is also removed from the cache, and list. The db can be restored with `restore_db` until it is purged from the trash.
+    #[tracing::instrument(skip(self))]
+    pub fn delete_db(
+        &self,
+        db_name: &str,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        if !self.is_super_admin_of(client_key, &DBPacketInfo::new(db_name)) {
+            // to delete a db, you must be a super admin no matter what.
+            return Err(InvalidPermissions);
+        }
+
+        if !self.db_name_exists(db_name) {
+            return Err(DBNotFound);
+        }
+
+        self.remove_db(db_name)
+    }
+
+    /// Moves the db file to the trash and removes the db from the cache and list, without checking permissions.
+    /// A cached db is saved before it is moved, so changes that were not saved yet are kept in the trash as well.
+    fn remove_db(&self, db_name: &str) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let mut list_lock = self.list.write().unwrap();
+
+        let db_packet_info = DBPacketInfo::new(db_name);
+
+        let cached = self.uncache_db(&db_packet_info, false);
+
+        let remove_result = if self.saving_enabled {
+            self.move_to_trash(db_name, cached.as_ref())
+        } else {
+            Ok(())
+        };
+
+        match remove_result {
+            Ok(_) => {
+                let _ = fs::remove_file(db_file::backup_path(&self.db_file_path(db_name)));
+
+                let mut removed = false;
+                let it = list_lock.clone();
+                for (index, item) in it.into_iter().enumerate() {
+                    if db_packet_info.get_db_name() == item.get_db_name() {
+                        list_lock.remove(index);
+                        removed = true;
+                    }
+                }
+
+                if !removed {
+                    // if no db was removed from the list, then we should tell the user that this deletion failed in some way.
+                    return Err(DBFileSystemError);
+                }
+
+                self.log_change(&db_packet_info, WalOp::DeleteDB);
+                info!("Successfully deleted database: {}", db_name);
+                Ok(SuccessNoData)
+            }
+            Err(e) => {
+                error!("Unable to delete database file: {}", e);
+                if let Some(db) = cached {
+                    self.cache.insert(db_packet_info, db);
+                }
+                Err(DBFileSystemError)
+            }
+        }
+    }
+
+    /// Returns the directory deleted databases are moved to.
+    fn trash_dir(&self) -> PathBuf {
+        self.data_dir.join(TRASH_DIR_NAME)
+    }
+
+    /// Moves the db file into the trash, saving the cached db over it first if it is cached.
+    fn move_to_trash(&self, db_name: &str, cached: Option<&DB>) -> std::io::Result<()> {
+        let db_path = self.db_file_path(db_name);
+        if let Some(db) = cached {
+            let ser = serde_json::to_string(db)?;
+            db_file::save(&db_path, &ser)?;
+        }
+        fs::create_dir_all(self.trash_dir())?;
+        let trash_path = self.trash_dir().join(TrashEntry::new(db_name).file_name());
+        fs::rename(db_path, trash_path)
+    }
+
+    /// Returns the path and entry of every db in the trash, files that are not trash entries are left out.
+    fn trash_entries(&self) -> Vec<(PathBuf, TrashEntry)> {
+        let Ok(read_dir) = fs::read_dir(self.trash_dir()) else {
+            return vec![];
+        };
+        read_dir
+            .filter_map(|dir_entry| {
+                let path = dir_entry.ok()?.path();
+                let entry = TrashEntry::from_file_name(path.file_name()?.to_str()?)?;
+                Some((path, entry))
+            })
+            .collect()
+    }
+
+    /// Removes the dbs in the trash that match the filter, returning how many were removed.
+    fn purge_trash_where(&self, filter: impl Fn(&TrashEntry) -> bool) -> usize {
+        self.trash_entries()
+            .into_iter()
+            .filter(|(_, entry)| filter(entry))
+            .filter(|(path, entry)| match fs::remove_file(path) {
+                Ok(_) => {
+                    let _ = fs::remove_file(db_file::backup_path(path));
+                    true
+                }
+                Err(e) => {
+                    error!("Unable to purge {} from the trash: {}", entry.db_name, e);
+                    false
+                }
+            })
+            .count()
+    }
+
+    /// Restores the most recently deleted db with the given name from the trash.
+    /// Fails with `DBAlreadyExists` if a db with the name exists, or `DBNotFound` if no db with the name is in the trash.
+    /// Requires super admin privileges
+    #[tracing::instrument(skip(self))]
+    pub fn restore_db(
+        &self,
+        db_name: &str,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        if !self.is_super_admin_of(client_key, &DBPacketInfo::new(db_name)) {
+            return Err(InvalidPermissions);
+        }
+
+        if self.db_name_exists(db_name) {
+            return Err(DBPacketResponseError::DBAlreadyExists);
+        }
+
+        let (trash_path, entry) = self
+            .trash_entries()
+            .into_iter()
+            .filter(|(_, entry)| entry.db_name == db_name)
+            .max_by_key(|(_, entry)| entry.deleted_at)
+            .ok_or(DBNotFound)?;
+
+        let db: DB = db_file::load(&trash_path).map_err(|e| {
+            error!("Unable to read database from the trash: {}", e);
+            DBFileSystemError
+        })?;
+        self.add_new_db(&DBPacketInfo::new(db_name), db)?;
+
+        if let Err(e) = fs::remove_file(&trash_path) {
+            warn!("Unable to remove restored database from the trash: {}", e);
+        }
+        info!(
+            "Successfully restored database {} deleted at {}",
+            db_name, entry.deleted_at
+        );
+        Ok(SuccessNoData)
+    }
+
+    /// Removes every db from the trash, replying with how many were removed.
+    /// Requires super admin privileges
+    #[tracing::instrument(skip(self))]
+    pub fn purge_trash(
+        &self,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        if !self.is_super_admin(client_key) {
+            return Err(InvalidPermissions);
+        }
+
+        let purged = self.purge_trash_where(|_| true);
+        info!("Purged {} databases from the trash", purged);
+        Ok(SuccessReply(purged.to_string()))
+    }
+
+    /// Removes the dbs that have been in the trash for longer than the trash retention, returning how many were removed.
+    #[tracing::instrument(skip(self))]
+    pub fn purge_expired_trash(&self) -> usize {
+        self.purge_trash_where(|entry| entry.is_expired(self.trash_retention))
+    }
+
+    /// Renames a db, moving its file, its entry in the db list, and its cache entry while holding the list and cache locks.
+    /// Fails with `DBAlreadyExists` if a db with the new name already exists.
+    /// Requires super admin privileges
+    #[tracing::instrument(skip(self))]
+    pub fn rename_db(
+        &self,
+        from: &DBPacketInfo,
+        to: &DBPacketInfo,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        if !(self.is_super_admin_of(client_key, from) && self.is_super_admin_of(client_key, to)) {
+            // to rename a db, you must be a super admin no matter what.
+            return Err(InvalidPermissions);
+        }
+
+        if !to.is_valid_name() {
+            return Err(DBPacketResponseError::InvalidDBName);
+        }
+
+        self.move_db(from, to)
+    }
+
+    /// Moves the db file, its entry in the list, and its cache entry to the new name, without checking permissions.
+    fn move_db(
+        &self,
+        from: &DBPacketInfo,
+        to: &DBPacketInfo,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let mut list_lock = self.list.write().unwrap();
+
+        if !list_lock.contains(from) {
+            return Err(DBNotFound);
+        }
+
+        if list_lock.contains(to) || File::open(self.db_file_path(to.get_db_name())).is_ok() {
+            return Err(DBPacketResponseError::DBAlreadyExists);
+        }
+
+        if from.get_namespace() != to.get_namespace() {
+            self.check_namespace_limit(&list_lock, to)?;
+        }
+
+        let rename_result = if self.saving_enabled {
+            fs::rename(
+                self.db_file_path(from.get_db_name()),
+                self.db_file_path(to.get_db_name()),
+            )
+        } else {
+            Ok(())
+        };
+
+        match rename_result {
+            Ok(_) => {
+                // the backup of the old name would otherwise be loaded if a db with that name was created later
+                let _ =
+                    fs::remove_file(db_file::backup_path(&self.db_file_path(from.get_db_name())));
+                if let Some(db) = self.uncache_db(from, false) {
+                    self.cache.insert(to.clone(), db);
+                }
+
+                for item in list_lock.iter_mut().filter(|item| *item == from) {
+                    *item = to.clone();
+                }
+
+                self.log_change(from, WalOp::RenameDB(to.clone()));
+                info!("Successfully renamed database: {} to {}", from, to);
+                Ok(SuccessNoData)
+            }
+            Err(e) => {
+                error!("Unable to rename database file: {}", e);
+                Err(DBFileSystemError)
+            }
+        }
+    }
+
+    /// Creates a new db containing a copy of the content of the source db, copied while holding the source db write lock.
+    /// The settings of the source db are copied as well when `include_settings` is true, otherwise the new db uses the default settings.
+    /// Requires super admin privileges
+    #[tracing::instrument(skip(self))]
+    pub fn copy_db(
+        &self,
+        source: &DBPacketInfo,
+        dest: &DBPacketInfo,
+        include_settings: bool,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        if !(self.is_super_admin_of(client_key, source) && self.is_super_admin_of(client_key, dest))
+        {
+            // to create a db you must be a super admin
+            return Err(InvalidPermissions);
+        }
+
+        if !dest.is_valid_name() {
+            return Err(DBPacketResponseError::InvalidDBName);
+        }
+
+        if self.db_name_exists(dest.get_db_name()) {
+            return Err(DBPacketResponseError::DBAlreadyExists);
+        }
+
+        let db_copy = self.with_db(source, |db| {
+            let mut db_copy = if include_settings {
+                DB::new_from_settings(db.get_settings().clone())
+            } else {
+                DB::default()
+            };
+            *db_copy.get_content_mut() = db.get_content().clone();
+            db_copy.set_metadata(DBMetadata::new_created_by(Some(client_key.clone())));
+            Ok(db_copy)
+        })?;
+
+        self.add_new_db(dest, db_copy)?;
+        info!("Successfully copied database: {} to {}", source, dest);
+        Ok(SuccessNoData)
+    }
+
+    /// Saves the db to a new file and adds it to the cache and the db list.
+    /// Fails with `DBAlreadyExists` if a db with the name already exists, or `NamespaceFull` if its namespace holds as many dbs as it allows,
+    /// both checked while the list is write locked.
+    fn add_new_db(&self, db_info: &DBPacketInfo, db: DB) -> Result<(), DBPacketResponseError> {
+        let mut list_write_lock = self.list.write().unwrap();
+
+        // check again now that the list is locked, in case the db was created while the new db was being prepared
+        if list_write_lock.contains(db_info)
+            || File::open(self.db_file_path(db_info.get_db_name())).is_ok()
+        {
+            return Err(DBPacketResponseError::DBAlreadyExists);
+        }
+
+        self.check_namespace_limit(&list_write_lock, db_info)?;
+
+        self.insert_db(&mut list_write_lock, db_info, db)
+    }
+
+    /// Saves the db to its file and adds it to the cache and the list, replacing any db with the same name.
+    /// The list must be write locked by the caller.
+    fn insert_db(
+        &self,
+        list: &mut Vec<DBPacketInfo>,
+        db_info: &DBPacketInfo,
+        mut db: DB,
+    ) -> Result<(), DBPacketResponseError> {
+        self.log_change(db_info, WalOp::CreateDB(Box::new(db.clone())));
+        if self.saving_enabled {
+            let ser = serde_json::to_string(&db).map_err(|_| SerializationError)?;
+            if let Err(e) = db_file::save(&self.db_file_path(db_info.get_db_name()), &ser) {
+                error!("Unable to write new DB file: {}", e);
+                return Err(DBFileSystemError);
+            }
+        }
+        db.mark_saved();
+
+        if let Some(replaced) = self.cache.insert(db_info.clone(), db) {
+            replaced.write().unwrap().mark_evicted();
+        }
+        if !list.contains(db_info) {
+            list.push(db_info.clone());
+        }
+        Ok(())
+    }
+
+    /// Returns a receiver holding a dump of the db in chunks, the receiver disconnects once every chunk has been received.
+    /// The dump holds the content, settings and statistics of the db, and can be imported into another server using `import_db`.
+    /// Requires super admin privileges, as the dump contains the settings of the db
+    #[tracing::instrument(skip(self))]
+    pub fn export_db(
+        &self,
+        db_info: &DBPacketInfo,
+        client_key: &String,
+    ) -> Result<Receiver<DBBytes>, DBPacketResponseError> {
+        if !self.is_super_admin_of(client_key, db_info) {
+            return Err(InvalidPermissions);
+        }
+
+        let dump = self.with_db(db_info, |db| {
+            Ok(DBDump {
+                name: db_info.clone(),
+                exported_at: Utc::now(),
+                key_hash_version: KEY_HASH_VERSION,
+                db: db.clone(),
+            })
+        })?;
+        let ser = serde_json::to_vec(&dump).map_err(|_| SerializationError)?;
+
+        let (sender, receiver) = channel();
+        for chunk in ser.chunks(STREAM_CHUNK_SIZE) {
+            let _ = sender.send(DBBytes::new(chunk.to_vec()));
+        }
+
+        info!("Exported database {} as {} bytes", db_info, ser.len());
+        Ok(receiver)
+    }
+
+    /// Creates a new db from a dump made by `export_db`, replying with the name of the db.
+    /// Fails with `DBAlreadyExists` if a db with the name in the dump already exists.
+    /// Requires super admin privileges
+    #[tracing::instrument(skip(self, dump))]
+    pub fn import_db(
+        &self,
+        dump: &[u8],
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        if !self.is_super_admin(client_key) {
+            return Err(InvalidPermissions);
+        }
+
+        let dump: DBDump = serde_json::from_slice(dump)
+            .map_err(|_| DBPacketResponseError::DeserializationError)?;
+
+        if self.db_name_exists(dump.name.get_db_name()) {
+            return Err(DBPacketResponseError::DBAlreadyExists);
+        }
+
+        let mut db = dump.db;
+        if dump.key_hash_version < KEY_HASH_VERSION {
+            // exported before keys were hashed, so every key in the settings is plaintext
+            let mut settings = db.get_settings().clone();
+            self.key_hasher()
+                .hash_plaintext_settings_keys(&mut settings);
+            db.set_settings(settings);
+        }
+
+        self.add_new_db(&dump.name, db)?;
+        info!(
+            "Successfully imported database {} exported at {}",
+            dump.name, dump.exported_at
+        );
+        Ok(SuccessReply(dump.name.get_db_name().to_string()))
+    }
+
+    /// Returns a receiver of every change made from the given position onward, for a replica server to apply in order.
+    /// Every database is sent first when there is no position, or the changes after the position are no longer in the replication log.
+    /// Requires super admin privileges
+    #[tracing::instrument(skip(self))]
+    pub fn replicate(
+        &self,
+        position: Option<ReplicationPosition>,
+        client_key: &String,
+    ) -> Result<Receiver<ReplicationItem>, DBPacketResponseError> {
+        if !self.is_super_admin(client_key) {
+            return Err(InvalidPermissions);
+        }
+
+        if !self.replication.is_enabled() {
+            return Err(DBPacketResponseError::ReplicationDisabled);
+        }
+
+        let (sender, receiver) = channel();
+
+        if let Some(position) = position {
+            if position.log_id == self.replication.log_id()
+                && self
+                    .replication
+                    .subscribe_from(position.next_sequence, sender.clone())
+            {
+                info!("Replica resumed from {:?}", position);
+                return Ok(receiver);
+            }
+        }
+
+        loop {
+            {
+                // no db can be created, deleted or renamed while the list is locked,
+                // changes to the content of a db that are made while it is copied are sent again after it, which is harmless
+                let list_lock = self.list.read().unwrap();
+                let from_sequence = self.replication.next_sequence();
+
+                let _ = sender.send(ReplicationItem::Resync {
+                    log_id: self.replication.log_id(),
+                    databases: list_lock.clone(),
+                    key_salt: self.key_hasher().get_salt().to_string(),
+                    super_admins: self.super_admin_hash_list.read().unwrap().clone(),
+                    users: self.user_registry.read().unwrap().clone(),
+                });
+
+                for db_name in list_lock.iter() {
+                    let db = match self.cache.get(db_name) {
+                        Some(db) => Ok(db.read().unwrap().clone()),
+                        None => self.read_db_from_file(db_name),
+                    };
+                    match db {
+                        Ok(db) => {
+                            let _ = sender
+                                .send(ReplicationItem::Database(db_name.clone(), Box::new(db)));
+                        }
+                        Err(err) => warn!("Unable to send {} to replica: {}", db_name, err),
+                    }
+                }
+
+                if self
+                    .replication
+                    .subscribe_from(from_sequence, sender.clone())
+                {
+                    info!("Replica was sent every database");
+                    return Ok(receiver);
+                }
+            }
+            warn!(
+                "Replication log filled while databases were sent to a replica, sending them again"
+            );
+        }
+    }
+
+    /// Applies an item sent by the primary server, items must be applied in the order they were sent.
+    /// Changes are logged as if they were made on this server, so they are saved and sent on to any replica of this server.
+    #[tracing::instrument(skip_all)]
+    pub fn apply_replicated(&self, item: ReplicationItem) -> Result<(), DBPacketResponseError> {
+        match item {
+            ReplicationItem::Resync { databases, .. } => {
+                let removed: Vec<DBPacketInfo> = self
+                    .list
+                    .read()
+                    .unwrap()
+                    .iter()
+                    .filter(|db_name| !databases.contains(db_name))
+                    .cloned()
+                    .collect();
+                for db_name in removed {
+                    self.remove_db(db_name.get_db_name())?;
+                }
+                Ok(())
+            }
+            ReplicationItem::Database(db_name, db) => {
+                self.insert_db(&mut self.list.write().unwrap(), &db_name, *db)
+            }
+            ReplicationItem::Change(_, WalEntry { db: db_name, op }) => match op {
+                WalOp::DeleteDB => self.remove_db(db_name.get_db_name()).map(|_| ()),
+                WalOp::RenameDB(to) => self.move_db(&db_name, &to).map(|_| ()),
+                WalOp::CreateDB(db) => {
+                    self.insert_db(&mut self.list.write().unwrap(), &db_name, *db)
+                }
+                op => self.with_db(&db_name, |db| {
+                    op.clone().apply(db);
+                    match op {
+                        WalOp::Write { key, value } => self.notify_written(&db_name, &key, &value),
+                        WalOp::Delete { key } => self.notify_deleted(&db_name, &key),
+                        op => self.log_change(&db_name, op),
+                    }
+                    Ok(())
+                }),
+            },
+        }
+    }
+
+    /// Saves a snapshot of every database and the db list to a new file in the backups directory, replying with the name of the file.
+    /// Every cached database is read locked at once, so the snapshot holds the databases as they were at a single point in time,
+    /// databases that are not cached are read from their files, which hold every change made before they were removed from the cache.
+    /// Snapshots are saved even when saving is disabled, as they are the only way to keep databases that live in memory.
+    /// Requires super admin privileges
+    #[tracing::instrument(skip(self))]
+    pub fn create_snapshot(
+        &self,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        if !self.is_super_admin(client_key) {
+            return Err(InvalidPermissions);
+        }
+
+        let mut db_list = serde_json::to_value(self).map_err(|_| SerializationError)?;
+        let db_names: Vec<DBPacketInfo> =
+            serde_json::from_value(db_list["list"].clone()).map_err(|_| SerializationError)?;
+
+        let mut databases = BTreeMap::new();
+        {
+            let cached = self.cache.entries();
+            let db_locks: Vec<_> = cached
+                .iter()
+                .filter(|(db_name, _)| db_names.contains(db_name))
+                .map(|(db_name, db)| (db_name, db.read().unwrap()))
+                .filter(|(_, db)| !db.is_evicted())
+                .collect();
+            for (db_name, db) in &db_locks {
+                databases.insert(db_name.get_db_name().to_string(), (**db).clone());
+            }
+            for db_name in &db_names {
+                if !databases.contains_key(db_name.get_db_name()) {
+                    match self.read_db_from_file(db_name) {
+                        Ok(db) => {
+                            databases.insert(db_name.get_db_name().to_string(), db);
+                        }
+                        // the db was deleted after the db list was serialized
+                        Err(_) => warn!("Leaving {} out of the snapshot", db_name),
+                    }
+                }
+            }
+        }
+        let snapshot_names: Vec<DBPacketInfo> = db_names
+            .into_iter()
+            .filter(|db_name| databases.contains_key(db_name.get_db_name()))
+            .collect();
+        db_list["list"] = serde_json::to_value(snapshot_names).map_err(|_| SerializationError)?;
+
+        let snapshot = Snapshot {
+            created_at: Utc::now(),
+            db_list,
+            databases,
+        };
+        let ser = serde_json::to_string(&snapshot).map_err(|_| SerializationError)?;
+
+        let backup_dir = self.data_dir.join(BACKUP_DIR_NAME);
+        let file_name = snapshot.file_name();
+        fs::create_dir_all(&backup_dir)
+            .and_then(|()| db_file::save(&backup_dir.join(&file_name), &ser))
+            .map_err(|e| {
+                error!("Unable to save snapshot: {}", e);
+                DBFileSystemError
+            })?;
+
+        info!("Successfully saved snapshot {}", file_name);
+        Ok(SuccessReply(file_name))
+    }
+
+    /// Replaces the server key with the given newly generated key, replying with the fingerprint of the new key.
+    /// Generating a key takes a few seconds, so it is generated by the caller, such as on a blocking thread, and only swapped in here.
+    /// The new key is saved to the data directory before it is used, so a failed save leaves the old key in place.
+    /// Clients that have already setup encryption keep their session key, only encryption setup afterwards uses the new key.
+    /// Requires super admin privileges.
+    #[tracing::instrument(skip(self, new_key))]
+    pub fn rotate_server_key(
+        &self,
+        client_key: &String,
+        new_key: ServerKey,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        if !self.is_super_admin(client_key) {
+            return Err(InvalidPermissions);
+        }
+
+        if self.saving_enabled {
+            new_key
+                .save(&self.data_dir.join(SERVER_KEY_FILE_NAME))
+                .map_err(|err| {
+                    error!("Unable to save rotated server key: {}", err);
+                    DBFileSystemError
+                })?;
+        }
+        let fingerprint = new_key.fingerprint();
+        *self.server_key.write().unwrap() = new_key;
+        info!("Rotated server key, new fingerprint: {}", fingerprint);
+        Ok(SuccessReply(fingerprint))
+    }
+
+    /// Replies with the state of the server as a `ServerInfo`, given the version, uptime and features of the server software.
+    /// Requires super admin privileges.
+    #[tracing::instrument(skip(self))]
+    pub fn server_info(
+        &self,
+        client_key: &String,
+        version: &str,
+        uptime: Duration,
+        features: &[&str],
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        if !self.is_super_admin(client_key) {
+            return Err(InvalidPermissions);
+        }
+
+        let db_count = self.list.read().unwrap().len();
+        let (cached_db_count, memory_estimate) = {
+            let cached = self.cache.entries();
+            let memory_estimate = cached
+                .iter()
+                .map(|(_, db)| db.read().unwrap().get_content().size_in_bytes())
+                .sum();
+            (cached.len(), memory_estimate)
+        };
+
+        let server_info = ServerInfo {
+            version: version.to_string(),
+            uptime,
+            features: features.iter().map(|feature| feature.to_string()).collect(),
+            db_count,
+            cached_db_count,
+            memory_estimate,
+        };
+
+        serde_json::to_string(&server_info)
+            .map(SuccessReply)
+            .map_err(|_| SerializationError)
+    }
+
+    /// Replaces the saved db list and databases in the data directory with the ones in the snapshot file.
+    /// Must be called before the db list is loaded, the write-ahead log is removed as its changes were made to the replaced databases.
+    /// Databases saved in the data directory that are not in the snapshot are left in place, but are no longer in the db list.
+    #[tracing::instrument]
+    pub fn restore_snapshot(data_dir: &Path, snapshot_path: &Path) -> Result<(), DBFileError> {
+        let snapshot: Snapshot = db_file::load(snapshot_path)?;
+        info!(
+            "Restoring snapshot created at {} with {} databases",
+            snapshot.created_at,
+            snapshot.databases.len()
+        );
+
+        fs::create_dir_all(data_dir).map_err(DBFileError::Io)?;
+        for (db_name, db) in &snapshot.databases {
+            let ser = serde_json::to_string(db).map_err(|e| DBFileError::Corrupt(e.to_string()))?;
+            db_file::save(&data_dir.join(DBPacketInfo::new(db_name).file_name()), &ser)
+                .map_err(DBFileError::Io)?;
+        }
+        db_file::save(
+            &data_dir.join(DB_LIST_FILE_NAME),
+            &snapshot.db_list.to_string(),
+        )
+        .map_err(DBFileError::Io)?;
+
+        match fs::remove_file(data_dir.join(WAL_FILE_NAME)) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(DBFileError::Io(e)),
+        }
+
+        info!("Successfully restored snapshot");
+        Ok(())
+    }
+
+    /// Reads a db from a db packet info.
+    /// Falls back to the backup of the db file when the db file is corrupt.
+    /// Err on db not existing as a file, or both the file and its backup being corrupt: `DBFileSystemError`
+    #[tracing::instrument(skip(self))]
+    fn read_db_from_file(&self, p_info: &DBPacketInfo) -> Result<DB, DBPacketResponseError> {
+        let db_path = self.db_file_path(p_info.get_db_name());
+        let mut db: DB = db_file::load(&db_path).map_err(|e| {
+            error!("Unable to read database from file: {}", e);
+            // db file system error when no file was able to be loaded, should never happen due to the db file being in a list of known working db files.
+            DBFileSystemError
+        })?;
+        if let Ok(file_metadata) = fs::metadata(&db_path) {
+            db.set_serialized_size(file_metadata.len() as usize);
+        }
+        Ok(db)
+    }
+
+    /// Reads a database given a packet, returns the value if it was found.
+    #[tracing::instrument(skip(self))]
+    pub fn read_db(
+        &self,
+        p_info: &DBPacketInfo,
+        p_location: &DBLocation,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(p_info);
+
+        self.with_db(p_info, |db| {
+            if !db.has_key_read_permissions(p_location.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
+
+            db.record_key_hit(p_location.as_key());
+            db.get_content()
+                .read_from_db(p_location.as_key())
+                .map(|value| SuccessReply(value.to_string()))
+                .ok_or(ValueNotFound)
+        })
+    }
+
+    /// Writes to a db given a `DBPacket`
+    #[tracing::instrument(skip(self))]
+    pub fn write_db(
+        &self,
+        db_info: &DBPacketInfo,
+        db_location: &DBLocation,
+        db_data: &DBData,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if !db.has_key_write_permissions(db_location.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
+
+            db.record_key_hit(db_location.as_key());
+            db.check_key_limits(db_location.as_key(), db_data.get_data().len())?;
+            db.check_schema(db_data.get_data())?;
+            self.check_write_quota(db_info, db, db_location.as_key(), db_data.get_data())?;
+            let returned_value = db
+                .get_content_mut()
+                .insert(
+                    db_location.as_key().to_string(),
+                    db_data.get_data().to_string(),
+                )
+                .map_or(SuccessNoData, SuccessReply);
+            self.notify_written(db_info, db_location.as_key(), db_data.get_data());
+            Ok(returned_value)
+        })
+    }
+
+    /// Replies with true if a db with the given name exists, without loading the db
+    #[tracing::instrument(skip(self))]
+    pub fn db_exists(
+        &self,
+        db_info: &DBPacketInfo,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        Ok(SuccessReply(
+            self.db_name_exists(db_info.get_db_name()).to_string(),
+        ))
+    }
+
+    /// Replies with true if the location in the given db has a value, without sending the value itself.
+    /// Requires read permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn key_exists(
+        &self,
+        db_info: &DBPacketInfo,
+        db_location: &DBLocation,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if db.has_key_read_permissions(db_location.as_key(), client_key, &super_admin_list) {
+                let exists = db
+                    .get_content()
+                    .read_from_db(db_location.as_key())
+                    .is_some();
+                Ok(SuccessReply(exists.to_string()))
+            } else {
+                Err(InvalidPermissions)
+            }
+        })
+    }
+
+    /// Replies with the number of key value pairs in the given db, without sending the content itself.
+    /// Requires list permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn get_db_len(
+        &self,
+        db_info: &DBPacketInfo,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if db.has_list_permissions(client_key, &super_admin_list) {
+                Ok(SuccessReply(db.get_content().len().to_string()))
+            } else {
+                Err(InvalidPermissions)
+            }
+        })
+    }
+
+    /// Returns the db list as `ResponseData::DBList`, each db listed with its metadata.
+    /// Databases that are not cached are loaded to read their metadata.
+    #[tracing::instrument(skip(self))]
+    pub fn list_db(&self) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let list = self.list.read().unwrap().clone();
+        let listings: Vec<DBListing> = list
+            .into_iter()
+            .map(|info| {
+                let metadata = self.with_db(&info, |db| Ok(db.get_metadata().clone())).ok();
+                DBListing { info, metadata }
+            })
+            .collect();
+        Ok(SuccessData(ResponseData::DBList(listings)))
+    }
+
+    /// Returns the dbs the given key has read or list permissions on as `ResponseData::DBList`, each db listed with its metadata,
+    /// so a client can not learn the names of dbs it has no access to.
+    /// Databases that are not cached are loaded to check their permissions.
+    #[tracing::instrument(skip(self))]
+    pub fn list_accessible_db(
+        &self,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let list = self.list.read().unwrap().clone();
+        let listings: Vec<DBListing> = list
+            .into_iter()
+            .filter_map(|info| {
+                let super_admin_list = self.get_super_admin_list(&info);
+                let metadata = self
+                    .with_db(&info, |db| {
+                        if db.has_read_permissions(client_key, &super_admin_list)
+                            || db.has_list_permissions(client_key, &super_admin_list)
+                        {
+                            Ok(db.get_metadata().clone())
+                        } else {
+                            Err(InvalidPermissions)
+                        }
+                    })
+                    .ok()?;
+                Some(DBListing {
+                    info,
+                    metadata: Some(metadata),
+                })
+            })
+            .collect();
+        Ok(SuccessData(ResponseData::DBList(listings)))
+    }
+
+    /// Returns the db contents in a serialized form of HashMap<String, String>, ordered by key if the db keeps its keys sorted
+    #[tracing::instrument(skip(self))]
+    pub fn list_db_contents(
+        &self,
+        db_info: &DBPacketInfo,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        let content = self.copy_db_contents(db_info, client_key, &super_admin_list)?;
+
+        // serialized after the db lock is released, copying the content is much faster than serializing it
+        serde_json::to_string(&content)
+            .map(SuccessReply)
+            .map_err(|_| SerializationError)
+    }
+
+    /// Returns a receiver holding the contents of the db as a serialized HashMap<String, String>, in chunks of bytes,
+    /// so the whole serialized db is never held in memory at once. The receiver disconnects once every chunk has been received.
+    /// The contents are copied when called, so changes made afterward are not seen, and serialized as the chunks are received.
+    /// Requires list permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn stream_db_contents(
+        &self,
+        db_info: &DBPacketInfo,
+        client_key: &String,
+    ) -> Result<Receiver<DBBytes>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        let content = self.copy_db_contents(db_info, client_key, &super_admin_list)?;
+
+        Ok(stream_json_object(content))
+    }
+
+    /// Returns a copy of the key value pairs of the db, so the db lock is only held while they are copied.
+    /// Requires list permissions on the given db
+    fn copy_db_contents(
+        &self,
+        db_info: &DBPacketInfo,
+        client_key: &String,
+        super_admin_list: &[String],
+    ) -> Result<ContentMap, DBPacketResponseError> {
+        self.with_db(db_info, |db| {
+            if db.has_list_permissions(client_key, super_admin_list)
+                || self.is_super_admin_of(client_key, db_info)
+            {
+                Ok(db.get_content().content.clone())
+            } else {
+                Err(InvalidPermissions)
+            }
+        })
+    }
+
+    /// Returns the keys of a db in a serialized form of Vec<String>, in order if the db keeps its keys sorted
+    /// Requires list permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn list_db_keys(
+        &self,
+        db_info: &DBPacketInfo,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if db.has_list_permissions(client_key, &super_admin_list) {
+                let keys: Vec<&String> = db.get_content().content.keys().collect();
+                serde_json::to_string(&keys)
+                    .map(SuccessReply)
+                    .map_err(|_| SerializationError)
+            } else {
+                Err(InvalidPermissions)
+            }
+        })
+    }
+
+    /// Returns the names of the keyed lists of a db in a serialized form of Vec<String>, ordered by name
+    /// Requires list permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn list_db_lists(
+        &self,
+        db_info: &DBPacketInfo,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if db.has_list_permissions(client_key, &super_admin_list) {
+                let mut list_names: Vec<&String> = db.get_content().lists.keys().collect();
+                list_names.sort();
+                serde_json::to_string(&list_names)
+                    .map(SuccessReply)
+                    .map_err(|_| SerializationError)
+            } else {
+                Err(InvalidPermissions)
+            }
+        })
+    }
+
+    /// Returns the revisions kept of the value at the key in a serialized form of Vec<RevisionInfo>, oldest first.
+    /// Requires read permissions on the given key
+    #[tracing::instrument(skip(self))]
+    pub fn list_revisions(
+        &self,
+        db_info: &DBPacketInfo,
+        db_location: &DBLocation,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if !db.has_key_read_permissions(db_location.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
+
+            serde_json::to_string(&db.get_content().history.list(db_location.as_key()))
+                .map(SuccessReply)
+                .map_err(|_| SerializationError)
+        })
+    }
+
+    /// Returns the revision of the value at the key in a serialized form of `Revision`, or `ValueNotFound` if the revision is not kept.
+    /// Requires read permissions on the given key
+    #[tracing::instrument(skip(self))]
+    pub fn read_revision(
+        &self,
+        db_info: &DBPacketInfo,
+        db_location: &DBLocation,
+        revision: u64,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if !db.has_key_read_permissions(db_location.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
+
+            let revision = db
+                .get_content()
+                .history
+                .get(db_location.as_key(), revision)
+                .ok_or(ValueNotFound)?;
+            serde_json::to_string(revision)
+                .map(SuccessReply)
+                .map_err(|_| SerializationError)
+        })
+    }
+
+    /// Returns every key value pair in a db whose key starts with the given prefix, in a serialized form of BTreeMap<String, String>
+    /// Requires read permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn read_prefix(
+        &self,
+        db_info: &DBPacketInfo,
+        prefix: &str,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if db.has_read_permissions(client_key, &super_admin_list) {
+                serde_json::to_string(&db.get_content().read_prefix(prefix))
+                    .map(SuccessReply)
+                    .map_err(|_| SerializationError)
+            } else {
+                Err(InvalidPermissions)
+            }
+        })
+    }
+
+    /// Returns the keys of a db that match the given pattern in a serialized form of Vec<String>
+    /// Requires list permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn find_keys(
+        &self,
+        db_info: &DBPacketInfo,
+        pattern: &KeyPattern,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if db.has_list_permissions(client_key, &super_admin_list) {
+                let keys = pattern.filter_keys(db.get_content().content.keys())?;
+                serde_json::to_string(&keys)
+                    .map(SuccessReply)
+                    .map_err(|_| SerializationError)
+            } else {
+                Err(InvalidPermissions)
+            }
+        })
+    }
+
+    /// Writes the new data to a location in a db only if the current value at the location is the expected value, atomically under the db write lock.
+    /// Returns the previous value on success, or `ValueMismatch` containing the current value when it did not match.
+    /// Requires read and write permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn compare_and_swap(
+        &self,
+        db_info: &DBPacketInfo,
+        db_location: &DBLocation,
+        expected: Option<&DBData>,
+        new_data: &DBData,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if !(db.has_key_read_permissions(db_location.as_key(), client_key, &super_admin_list)
+                && db.has_key_write_permissions(
+                    db_location.as_key(),
+                    client_key,
+                    &super_admin_list,
+                ))
+            {
+                return Err(InvalidPermissions);
+            }
+
+            let current_value = db.get_content().read_from_db(db_location.as_key());
+
+            if current_value.map(String::as_str) != expected.map(DBData::get_data) {
+                return Err(DBPacketResponseError::ValueMismatch(current_value.cloned()));
+            }
+
+            db.check_key_limits(db_location.as_key(), new_data.get_data().len())?;
+            db.check_schema(new_data.get_data())?;
+            self.check_write_quota(db_info, db, db_location.as_key(), new_data.get_data())?;
+
+            let previous_value = db.get_content_mut().insert(
+                db_location.as_key().to_string(),
+                new_data.get_data().to_string(),
+            );
+
+            self.notify_written(db_info, db_location.as_key(), new_data.get_data());
+
+            Ok(previous_value.map_or(SuccessNoData, SuccessReply))
+        })
+    }
+
+    /// Adds the delta to the integer stored at a location in a db, atomically under the db write lock.
+    /// A location with no value is treated as 0, the new value is returned.
+    /// Requires read and write permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn increment(
+        &self,
+        db_info: &DBPacketInfo,
+        db_location: &DBLocation,
+        delta: i64,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if !(db.has_key_read_permissions(db_location.as_key(), client_key, &super_admin_list)
+                && db.has_key_write_permissions(
+                    db_location.as_key(),
+                    client_key,
+                    &super_admin_list,
+                ))
+            {
+                return Err(InvalidPermissions);
+            }
+
+            let current_value = match db.get_content().read_from_db(db_location.as_key()) {
+                None => 0,
+                Some(value) => value
+                    .trim()
+                    .parse::<i64>()
+                    .map_err(|_| DBPacketResponseError::ValueNotInteger)?,
+            };
+
+            let new_value = current_value
+                .checked_add(delta)
+                .ok_or(DBPacketResponseError::IntegerOverflow)?;
+
+            db.check_key_limits(db_location.as_key(), new_value.to_string().len())?;
+            db.check_schema(&new_value.to_string())?;
+            self.check_write_quota(db_info, db, db_location.as_key(), &new_value.to_string())?;
+
+            db.get_content_mut()
+                .insert(db_location.as_key().to_string(), new_value.to_string());
+
+            self.notify_written(db_info, db_location.as_key(), &new_value.to_string());
+
+            Ok(SuccessReply(new_value.to_string()))
+        })
+    }
+
+    /// Applies every operation to a db in order while holding the db write lock, so no other client sees a partially applied transaction.
+    /// The result of each operation is replied as a json list, if any operation fails, the db content is restored to how it was before the transaction.
+    /// Requires read permissions to each location the transaction reads, write permissions to each location it writes, and delete permissions to each location it deletes.
+    #[tracing::instrument(skip(self))]
+    pub fn transaction(
+        &self,
+        db_info: &DBPacketInfo,
+        ops: &[TransactionOp],
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            let needs_write = ops.iter().any(TransactionOp::is_write);
+            let permitted = ops.iter().all(|op| match op {
+                TransactionOp::Read(location) => {
+                    db.has_key_read_permissions(location.as_key(), client_key, &super_admin_list)
+                }
+                TransactionOp::Write(location, _) => {
+                    db.has_key_write_permissions(location.as_key(), client_key, &super_admin_list)
+                }
+                TransactionOp::Delete(location) => {
+                    db.has_key_delete_permissions(location.as_key(), client_key, &super_admin_list)
+                }
+            });
+
+            if !permitted {
+                return Err(InvalidPermissions);
+            }
+
+            for op in ops {
+                if let TransactionOp::Write(location, data) = op {
+                    db.check_key_limits(location.as_key(), data.get_data().len())?;
+                    db.check_schema(data.get_data())?;
+                }
+            }
+
+            let (added_entries, added_bytes) = ops
+                .iter()
+                .filter_map(|op| match op {
+                    TransactionOp::Write(location, data) => {
+                        Some(value_growth(db, location.as_key(), data.get_data()))
+                    }
+                    TransactionOp::Read(_) | TransactionOp::Delete(_) => None,
+                })
+                .fold((0, 0), |(entries, bytes), (added_entries, added_bytes)| {
+                    (entries + added_entries, bytes + added_bytes)
+                });
+            db.check_quota(self.quota_for(db_info), added_entries, added_bytes)?;
+
+            let content_backup = needs_write.then(|| db.get_content().clone());
+
+            let mut results: Vec<Option<String>> = Vec::with_capacity(ops.len());
+
+            for (op_index, op) in ops.iter().enumerate() {
+                let result = match op {
+                    TransactionOp::Read(location) => {
+                        Ok(db.get_content().read_from_db(location.as_key()).cloned())
+                    }
+                    TransactionOp::Write(location, data) => Ok(db
+                        .get_content_mut()
+                        .insert(location.as_key().to_string(), data.get_data().to_string())),
+                    TransactionOp::Delete(location) => db
+                        .change_content(|content| {
+                            let removed = content.remove(location.as_key());
+                            let changed = removed.is_some();
+                            (removed, changed)
+                        })
+                        .map(Some)
+                        .ok_or(ValueNotFound),
+                };
+
+                match result {
+                    Ok(value) => results.push(value),
+                    Err(err) => {
+                        if let Some(content) = content_backup {
+                            *db.get_content_mut() = content;
+                        }
+                        return Err(DBPacketResponseError::TransactionRolledBack {
+                            op_index,
+                            error: Box::new(err),
+                        });
+                    }
+                }
+            }
+
+            // only notify watchers once every operation succeeded, so rolled back changes are never seen
+            for op in ops {
+                match op {
+                    TransactionOp::Read(_) => {}
+                    TransactionOp::Write(location, data) => {
+                        self.notify_written(db_info, location.as_key(), data.get_data());
+                    }
+                    TransactionOp::Delete(location) => {
+                        self.notify_deleted(db_info, location.as_key());
+                    }
+                }
+            }
+
+            serde_json::to_string(&results)
+                .map(SuccessReply)
+                .map_err(|_| DBPacketResponseError::SerializationError)
+        })
+    }
+
+    /// Writes the data to a location in a db only if the location has no value, otherwise `ValueAlreadyExists` is returned.
+    /// The check and the write happen under the same db write lock, so only one client can ever claim a given location.
+    /// Requires write permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn write_db_if_absent(
+        &self,
+        db_info: &DBPacketInfo,
+        db_location: &DBLocation,
+        db_data: &DBData,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if !db.has_key_write_permissions(db_location.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
+
+            if db
+                .get_content()
+                .read_from_db(db_location.as_key())
+                .is_some()
+            {
+                return Err(DBPacketResponseError::ValueAlreadyExists);
+            }
+
+            db.check_key_limits(db_location.as_key(), db_data.get_data().len())?;
+            db.check_schema(db_data.get_data())?;
+            self.check_write_quota(db_info, db, db_location.as_key(), db_data.get_data())?;
+
+            db.get_content_mut().insert(
+                db_location.as_key().to_string(),
+                db_data.get_data().to_string(),
+            );
+
+            self.notify_written(db_info, db_location.as_key(), db_data.get_data());
+
+            Ok(SuccessNoData)
+        })
+    }
+
+    /// Inserts the value into the list at the given index, or pushes it onto the end of the list if there is no index.
+    /// Replies with the new length of the list, and wakes the clients waiting for a value to be pushed to the list as a queue.
+    /// Requires write permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn add_to_list(
+        &self,
+        db_info: &DBPacketInfo,
+        list_name: &DBLocation,
+        db_data: &DBData,
+        index: Option<usize>,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        let resp = self.with_db(db_info, |db| {
+            if !db.has_key_write_permissions(list_name.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
+
+            db.check_key_limits(list_name.as_key(), db_data.get_data().len())?;
+
+            let list_len = db
+                .get_content()
+                .get_list(list_name.as_key())
+                .map_or(0, Vec::len);
+            let index = index.unwrap_or(list_len);
+            if index > list_len {
+                return Err(DBPacketResponseError::IndexOutOfBounds);
+            }
+
+            let new_list = db.get_content().get_list(list_name.as_key()).is_none();
+            db.check_quota(
+                self.quota_for(db_info),
+                usize::from(new_list),
+                db_data.get_data().len()
+                    + if new_list {
+                        list_name.as_key().len()
+                    } else {
+                        0
+                    },
+            )?;
+
+            db.get_content_mut()
+                .get_list_mut(list_name.as_key())
+                .insert(index, db_data.get_data().to_string());
+            self.log_list(db_info, db, list_name);
+
+            Ok(SuccessReply((list_len + 1).to_string()))
+        });
+
+        // woken once the db is unlocked, so the woken clients are able to pop the value right away
+        if resp.is_ok() {
+            self.queue_waiters.notify_push(db_info, list_name.as_key());
+        }
+
+        resp
+    }
+
+    /// Replies with the value at the index of the list.
+    /// Requires read permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn read_from_list(
+        &self,
+        db_info: &DBPacketInfo,
+        list_name: &DBLocation,
+        index: usize,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if !db.has_key_read_permissions(list_name.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
+
+            let list = db
+                .get_content()
+                .get_list(list_name.as_key())
+                .ok_or(ValueNotFound)?;
+
+            list.get(index)
+                .map(|value| SuccessReply(value.clone()))
+                .ok_or(DBPacketResponseError::IndexOutOfBounds)
+        })
+    }
+
+    /// Removes the value at the index of the list, replying with the removed value.
+    /// The list is removed once its last value is removed.
+    /// Requires write permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn remove_from_list(
+        &self,
+        db_info: &DBPacketInfo,
+        list_name: &DBLocation,
+        index: usize,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if !db.has_key_write_permissions(list_name.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
+
+            let list_len = db
+                .get_content()
+                .get_list(list_name.as_key())
+                .ok_or(ValueNotFound)?
+                .len();
+            if index >= list_len {
+                return Err(DBPacketResponseError::IndexOutOfBounds);
+            }
+
+            let content = db.get_content_mut();
+            let removed = content.get_list_mut(list_name.as_key()).remove(index);
+            if list_len == 1 {
+                content.remove_list(list_name.as_key());
+            }
+            self.log_list(db_info, db, list_name);
+
+            Ok(SuccessReply(removed))
+        })
+    }
+
+    /// Removes every value from the list, clearing a list that does not exist does nothing.
+    /// Requires write permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn clear_list(
+        &self,
+        db_info: &DBPacketInfo,
+        list_name: &DBLocation,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if !db.has_key_write_permissions(list_name.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
+
+            let cleared = db.change_content(|content| {
+                let cleared = content.remove_list(list_name.as_key()).is_some();
+                (cleared, cleared)
+            });
+            if cleared {
+                self.log_list(db_info, db, list_name);
+            }
+
+            Ok(SuccessNoData)
+        })
+    }
+
+    /// Replies with the number of values in the list, a list that does not exist has no values.
+    /// Requires read permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn get_list_length(
+        &self,
+        db_info: &DBPacketInfo,
+        list_name: &DBLocation,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if !db.has_key_read_permissions(list_name.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
+
+            let list_len = db
+                .get_content()
+                .get_list(list_name.as_key())
+                .map_or(0, Vec::len);
+
+            Ok(SuccessReply(list_len.to_string()))
+        })
+    }
+
+    /// Returns a receiver holding every value of the list in order, the receiver disconnects once every value has been received.
+    /// The values are copied when called, so changes made to the list afterward are not seen.
+    /// Requires read and stream permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn stream_list(
+        &self,
+        db_info: &DBPacketInfo,
+        list_name: &DBLocation,
+        client_key: &String,
+    ) -> Result<Receiver<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if !db.has_key_stream_permissions(list_name.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
+
+            let (sender, receiver) = channel();
+            for value in db
+                .get_content()
+                .get_list(list_name.as_key())
+                .into_iter()
+                .flatten()
+            {
+                let _ = sender.send(value.clone());
+            }
+
+            Ok(receiver)
+        })
+    }
+
+    /// Adds the member to the set, replying with true if it was not already a member.
+    /// Requires write permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn set_add(
+        &self,
+        db_info: &DBPacketInfo,
+        set_name: &DBLocation,
+        member: &DBData,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if !db.has_key_write_permissions(set_name.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
+
+            db.check_key_limits(set_name.as_key(), member.get_data().len())?;
+
+            let set = db.get_content().get_set(set_name.as_key());
+            if set.is_none_or(|set| !set.contains(member.get_data())) {
+                db.check_quota(
+                    self.quota_for(db_info),
+                    usize::from(set.is_none()),
+                    member.get_data().len()
+                        + if set.is_none() {
+                            set_name.as_key().len()
+                        } else {
+                            0
+                        },
+                )?;
+            }
+
+            let added = db.change_content(|content| {
+                let added = content.set_add(set_name.as_key(), member.get_data().to_string());
+                (added, added)
+            });
+            if added {
+                self.log_set(db_info, db, set_name);
+            }
+
+            Ok(SuccessReply(added.to_string()))
+        })
+    }
+
+    /// Removes the member from the set, replying with true if it was a member.
+    /// Requires write permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn set_remove(
+        &self,
+        db_info: &DBPacketInfo,
+        set_name: &DBLocation,
+        member: &DBData,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if !db.has_key_write_permissions(set_name.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
+
+            let removed = db.change_content(|content| {
+                let removed = content.set_remove(set_name.as_key(), member.get_data());
+                (removed, removed)
+            });
+            if removed {
+                self.log_set(db_info, db, set_name);
+            }
+
+            Ok(SuccessReply(removed.to_string()))
+        })
+    }
+
+    /// Replies with true if the member is in the set, a set that does not exist has no members.
+    /// Requires read permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn set_contains(
+        &self,
+        db_info: &DBPacketInfo,
+        set_name: &DBLocation,
+        member: &DBData,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if !db.has_key_read_permissions(set_name.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
+
+            let contains = db
+                .get_content()
+                .get_set(set_name.as_key())
+                .is_some_and(|set| set.contains(member.get_data()));
+
+            Ok(SuccessReply(contains.to_string()))
+        })
+    }
+
+    /// Replies with every member of the set in order, serialized as a Vec of members.
+    /// Requires read permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn set_members(
+        &self,
+        db_info: &DBPacketInfo,
+        set_name: &DBLocation,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if !db.has_key_read_permissions(set_name.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
+
+            let members: Vec<&String> = db
+                .get_content()
+                .get_set(set_name.as_key())
+                .into_iter()
+                .flatten()
+                .collect();
+
+            serde_json::to_string(&members)
+                .map(SuccessReply)
+                .map_err(|_| SerializationError)
+        })
+    }
+
+    /// Adds the member to the sorted list with the given score, replacing its score if it was already a member.
+    /// Replies with the rank of the member after it was added.
+    /// Requires write permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn sorted_add(
+        &self,
+        db_info: &DBPacketInfo,
+        list_name: &DBLocation,
+        member: &DBData,
+        score: f64,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if !db.has_key_write_permissions(list_name.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
+
+            db.check_key_limits(list_name.as_key(), member.get_data().len())?;
+
+            let list = db.get_content().get_sorted_list(list_name.as_key());
+            if list.is_none_or(|list| list.rank(member.get_data()).is_none()) {
+                db.check_quota(
+                    self.quota_for(db_info),
+                    usize::from(list.is_none()),
+                    member.get_data().len()
+                        + size_of::<f64>()
+                        + if list.is_none() {
+                            list_name.as_key().len()
+                        } else {
+                            0
+                        },
+                )?;
+            }
+
+            let rank = db
+                .get_content_mut()
+                .get_sorted_list_mut(list_name.as_key())
+                .insert(member.get_data().to_string(), score);
+            self.log_sorted_list(db_info, db, list_name);
+
+            Ok(SuccessReply(rank.to_string()))
+        })
+    }
+
+    /// Removes the member from the sorted list, replying with true if it was a member.
+    /// Requires write permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn sorted_remove(
+        &self,
+        db_info: &DBPacketInfo,
+        list_name: &DBLocation,
+        member: &DBData,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if !db.has_key_write_permissions(list_name.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
+
+            let removed = db.change_content(|content| {
+                let removed = content.sorted_list_remove(list_name.as_key(), member.get_data());
+                (removed, removed)
+            });
+            if removed {
+                self.log_sorted_list(db_info, db, list_name);
             }
-        }
+
+            Ok(SuccessReply(removed.to_string()))
+        })
     }
 
-    /// Returns true if the given db exists.
+    /// Replies with the rank of the member in ascending order of score, serialized as an `Option<usize>` that is `None` if it is not a member.
+    /// Requires read permissions on the given db
     #[tracing::instrument(skip(self))]
-    fn db_name_exists(&self, db_name: &str) -> bool {
-        self.list
-            .read()
-            .unwrap()
-            .contains(&DBPacketInfo::new(db_name))
+    pub fn sorted_rank(
+        &self,
+        db_info: &DBPacketInfo,
+        list_name: &DBLocation,
+        member: &DBData,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if !db.has_key_read_permissions(list_name.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
+
+            let rank = db
+                .get_content()
+                .get_sorted_list(list_name.as_key())
+                .and_then(|sorted_list| sorted_list.rank(member.get_data()));
+
+            serde_json::to_string(&rank)
+                .map(SuccessReply)
+                .map_err(|_| SerializationError)
+        })
     }
 
-    /// Creates a DB given a name, the packet is not needed, only the name.
-    /// Requires super admin privileges
+    /// Replies with every member with a score between min and max inclusive in ascending order of score, serialized as a Vec of `ScoredMember`.
+    /// Requires read permissions on the given db
     #[tracing::instrument(skip(self))]
-    pub fn create_db(
+    pub fn sorted_range_by_score(
         &self,
-        db_name: &str,
-        db_settings: DBSettings,
+        db_info: &DBPacketInfo,
+        list_name: &DBLocation,
+        min: f64,
+        max: f64,
         client_key: &String,
     ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
-        if !self.is_super_admin(client_key) {
-            // to create a db you must be a super admin
-            return Err(InvalidPermissions);
-        }
+        let super_admin_list = self.get_super_admin_list(db_info);
 
-        if self.db_name_exists(db_name) {
-            return Err(DBPacketResponseError::DBAlreadyExists);
-        }
+        self.with_db(db_info, |db| {
+            if !db.has_key_read_permissions(list_name.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
 
-        let mut list_write_lock = self.list.write().unwrap();
+            let members = db
+                .get_content()
+                .get_sorted_list(list_name.as_key())
+                .map_or(&[][..], |sorted_list| sorted_list.range_by_score(min, max));
 
-        return match File::open(format!("./data/{}", db_name)) {
-            Ok(_) => {
-                // db file was found and should not have been, because this db already exists
-
-                Err(DBPacketResponseError::DBAlreadyExists)
-            }
-            Err(_) => {
-                // db file was not found
-                match File::create(format!("./data/{}", db_name)) {
-                    Ok(mut file) => {
-                        let mut cache_write_lock = self.cache.write().unwrap();
-                        let db_packet_info = DBPacketInfo::new(db_name);
-                        let db = DB::new_from_settings(db_settings);
-                        let ser = serde_json::to_string(&db).unwrap();
-                        let _ = file
-                            .write(ser.as_ref())
-                            .expect(&format!("Unable to write db to file. {}", db_name));
-                        cache_write_lock.insert(db_packet_info.clone(), RwLock::from(db));
-                        list_write_lock.push(db_packet_info);
-                        drop(cache_write_lock);
-                        info!("Successfully created DB file");
-                        Ok(SuccessNoData)
-                    }
-                    Err(e) => {
-                        // db file was unable to be created
-                        error!("Unable to create DB file: {}", e);
-                        Err(DBFileSystemError)
-                    }
-                }
-            }
-        };
+            serde_json::to_string(members)
+                .map(SuccessReply)
+                .map_err(|_| SerializationError)
+        })
     }
 
-    /// Handles deleting a db, given a name for the db. Removes the database given a name, and deletes the corresponding file.
-    /// If the file is successfully removed, the db is also removed from the cache, and list.
+    /// Replies with up to count of the highest scoring members in descending order of score, serialized as a Vec of `ScoredMember`.
+    /// Requires read permissions on the given db
     #[tracing::instrument(skip(self))]
-    pub fn delete_db(
+    pub fn sorted_top(
         &self,
-        db_name: &str,
+        db_info: &DBPacketInfo,
+        list_name: &DBLocation,
+        count: usize,
         client_key: &String,
     ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
-        if !self.is_super_admin(client_key) {
-            // to delete a db, you must be a super admin no matter what.
-            return Err(InvalidPermissions);
-        }
+        let super_admin_list = self.get_super_admin_list(db_info);
 
-        if !self.db_name_exists(db_name) {
-            return Err(DBNotFound);
-        }
+        self.with_db(db_info, |db| {
+            if !db.has_key_read_permissions(list_name.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
 
-        let mut list_lock = self.list.write().unwrap();
+            let members = db
+                .get_content()
+                .get_sorted_list(list_name.as_key())
+                .map(|sorted_list| sorted_list.top(count))
+                .unwrap_or_default();
 
-        let mut cache_lock = self.cache.write().unwrap();
+            serde_json::to_string(&members)
+                .map(SuccessReply)
+                .map_err(|_| SerializationError)
+        })
+    }
 
-        match fs::remove_file(format!("./data/{}", db_name)) {
-            Ok(_) => {
-                let db_packet_info = DBPacketInfo::new(db_name);
-                cache_lock.remove(&db_packet_info);
+    /// Pushes the value onto the back of the queue, replying with the new length of the queue.
+    /// Requires write permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn queue_push(
+        &self,
+        db_info: &DBPacketInfo,
+        queue_name: &DBLocation,
+        db_data: &DBData,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        self.add_to_list(db_info, queue_name, db_data, None, client_key)
+    }
 
-                let mut removed = false;
-                let it = list_lock.clone();
-                for (index, item) in it.into_iter().enumerate() {
-                    if db_packet_info.get_db_name() == item.get_db_name() {
-                        list_lock.remove(index);
-                        removed = true;
-                    }
-                }
+    /// Removes and replies with the value at the front of the queue, replying with `SuccessNoData` if the queue is empty.
+    /// Requires write permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn queue_pop(
+        &self,
+        db_info: &DBPacketInfo,
+        queue_name: &DBLocation,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
 
-                if !removed {
-                    // if no db was removed from the list, then we should tell the user that this deletion failed in some way.
-                    return Err(DBFileSystemError);
-                }
+        self.with_db(db_info, |db| {
+            if !db.has_key_write_permissions(queue_name.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
 
-                info!("Successfully deleted database: {}", db_name);
-                Ok(SuccessNoData)
+            // checked before borrowing the content mutably, so popping from an empty queue does not count as a change to the db
+            if db.get_content().get_list(queue_name.as_key()).is_none() {
+                return Ok(SuccessNoData);
             }
-            Err(e) => {
-                error!("Unable to delete database file: {}", e);
-                Err(DBFileSystemError)
+
+            let content = db.get_content_mut();
+            let queue = content.get_list_mut(queue_name.as_key());
+            let head = queue.remove(0);
+            if queue.is_empty() {
+                content.remove_list(queue_name.as_key());
             }
-        }
+            self.log_list(db_info, db, queue_name);
+
+            Ok(SuccessReply(head))
+        })
     }
 
-    /// Reads a db from a db packet info.
-    /// Err on db not existing as a file: `DBFileSystemError`
-    #[tracing::instrument]
-    fn read_db_from_file(p_info: &DBPacketInfo) -> Result<DB, DBPacketResponseError> {
-        let mut db_file = match File::open(format!("./data/{}", p_info.get_db_name())) {
-            Ok(f) => f,
-            Err(e) => {
-                error!("Unable to read database from file: {}", e);
-                // early return db file system error when no file was able to be opened, should never happen due to the db file being in a list of known working db files.
-                return Err(DBFileSystemError);
+    /// Writes the binary data to the location, replying with the previous binary data at the location encoded as base64 if there was any.
+    /// Requires write permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn write_db_bytes(
+        &self,
+        db_info: &DBPacketInfo,
+        db_location: &DBLocation,
+        db_bytes: &DBBytes,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if !db.has_key_write_permissions(db_location.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
             }
-        };
 
-        let mut db_content_string = String::new();
-        db_file
-            .read_to_string(&mut db_content_string)
-            .expect("TODO: panic message");
-        let db: DB = serde_json::from_str(&db_content_string).unwrap_or_default();
-        Ok(db)
+            db.check_key_limits(db_location.as_key(), db_bytes.get_data().len())?;
+
+            let (added_entries, added_bytes) =
+                match db.get_content().binary.get(db_location.as_key()) {
+                    Some(previous) => (
+                        0,
+                        db_bytes
+                            .get_data()
+                            .len()
+                            .saturating_sub(previous.get_data().len()),
+                    ),
+                    None => (1, db_location.as_key().len() + db_bytes.get_data().len()),
+                };
+            db.check_quota(self.quota_for(db_info), added_entries, added_bytes)?;
+            db.record_key_hit(db_location.as_key());
+
+            let previous = db
+                .get_content_mut()
+                .binary
+                .insert(db_location.as_key().to_string(), db_bytes.clone());
+            self.log_change(
+                db_info,
+                WalOp::Bytes {
+                    key: db_location.as_key().to_string(),
+                    bytes: Some(db_bytes.clone()),
+                },
+            );
+
+            Ok(previous.map_or(SuccessNoData, |bytes| SuccessReply(bytes.to_base64())))
+        })
     }
 
-    /// Reads a database given a packet, returns the value if it was found.
+    /// Replies with the binary data at the location encoded as base64.
+    /// Requires read permissions on the given db
     #[tracing::instrument(skip(self))]
-    pub fn read_db(
+    pub fn read_db_bytes(
         &self,
-        p_info: &DBPacketInfo,
-        p_location: &DBLocation,
+        db_info: &DBPacketInfo,
+        db_location: &DBLocation,
         client_key: &String,
     ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
-        let super_admin_list = self.get_super_admin_list();
+        let super_admin_list = self.get_super_admin_list(db_info);
 
-        let list_lock = self.list.read().unwrap();
+        self.with_db(db_info, |db| {
+            if !db.has_key_read_permissions(db_location.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
 
-        if let Some(db) = self.cache.read().unwrap().get(p_info) {
-            info!("DB Cache hit");
-            // cache was hit
-            db.write().unwrap().update_access_time();
+            db.record_key_hit(db_location.as_key());
+            db.get_content()
+                .binary
+                .get(db_location.as_key())
+                .map(|bytes| SuccessReply(bytes.to_base64()))
+                .ok_or(ValueNotFound)
+        })
+    }
 
-            let db_lock = db.read().unwrap();
+    /// Replies with `SuccessNoData` if the client is able to write to the given db, used before receiving a streamed value.
+    #[tracing::instrument(skip(self))]
+    pub fn check_write_permissions(
+        &self,
+        db_info: &DBPacketInfo,
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
 
-            return if db_lock.has_read_permissions(client_key, &super_admin_list) {
-                db_lock
-                    .get_content()
-                    .read_from_db(p_location.as_key())
-                    .map(|value| SuccessReply(value.to_string()))
-                    .ok_or(ValueNotFound)
+        self.with_db(db_info, |db| {
+            if db.has_write_permissions(client_key, &super_admin_list) {
+                Ok(SuccessNoData)
             } else {
                 Err(InvalidPermissions)
-            };
-        }
+            }
+        })
+    }
+
+    /// Returns a receiver holding the binary value at the location split into chunks, the receiver disconnects once every chunk has been received.
+    /// Requires read and stream permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn stream_db_bytes(
+        &self,
+        db_info: &DBPacketInfo,
+        db_location: &DBLocation,
+        client_key: &String,
+    ) -> Result<Receiver<DBBytes>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        self.with_db(db_info, |db| {
+            if !db.has_key_stream_permissions(db_location.as_key(), client_key, &super_admin_list) {
+                return Err(InvalidPermissions);
+            }
 
-        if list_lock.contains(p_info) {
-            info!("DB Cache missed");
-            // cache was missed but the db exists on the file system
+            let bytes = db
+                .get_content()
+                .binary
+                .get(db_location.as_key())
+                .ok_or(ValueNotFound)?;
 
-            let mut db = Self::read_db_from_file(p_info)?;
+            let (sender, receiver) = channel();
+            for chunk in bytes.get_data().chunks(STREAM_CHUNK_SIZE) {
+                let _ = sender.send(DBBytes::new(chunk.to_vec()));
+            }
 
-            db.update_access_time();
+            Ok(receiver)
+        })
+    }
 
-            let response = if db.has_read_permissions(client_key, &super_admin_list) {
-                let return_value = db
-                    .get_content()
-                    .read_from_db(p_location.as_key())
-                    .expect("RETURN VALUE DID NOT EXIST")
-                    .clone();
-                Ok(SuccessReply(return_value))
+    /// Subscribes to changes made to keys matching the pattern in the given db.
+    /// Every write or delete to a matching key is sent to the returned receiver, until it is dropped.
+    /// Requires read and stream permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn subscribe(
+        &self,
+        db_info: &DBPacketInfo,
+        pattern: KeyPattern,
+        client_key: &String,
+    ) -> Result<Receiver<ChangeEvent>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
+
+        // make sure the pattern is usable before subscribing, so the subscriber is not left waiting on a pattern that never matches
+        pattern.is_match("")?;
+
+        self.with_db(db_info, |db| {
+            if db.has_stream_permissions(client_key, &super_admin_list) {
+                Ok(self.notification_bus.subscribe(db_info, pattern))
             } else {
                 Err(InvalidPermissions)
-            };
+            }
+        })
+    }
 
-            self.cache
-                .write()
-                .unwrap()
-                .insert(p_info.clone(), RwLock::from(db));
+    /// Publishes the message to every client subscribed to the channel, replying with how many subscribers received it.
+    /// Channels are not tied to a db, so any client may publish or subscribe to any channel.
+    #[tracing::instrument(skip(self))]
+    pub fn publish(
+        &self,
+        channel_name: &str,
+        message: &str,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let received_count = self.channels.publish(channel_name, message);
+        Ok(SuccessReply(received_count.to_string()))
+    }
 
-            response
-        } else {
-            // cache was neither hit, nor did the db exist on the file system
-            Err(DBNotFound)
-        }
+    /// Subscribes to the channel, every message published to it is sent to the returned receiver until it is dropped.
+    #[tracing::instrument(skip(self))]
+    pub fn subscribe_channel(&self, channel_name: &str) -> Receiver<String> {
+        self.channels.subscribe(channel_name)
     }
 
-    /// Writes to a db given a `DBPacket`
+    /// Reads every location from a db while holding the db lock once, replying with a json list of the value at each location, in order.
+    /// Locations with no value are `null` in the list.
+    /// Requires read permissions on the given db
     #[tracing::instrument(skip(self))]
-    pub fn write_db(
+    pub fn read_many(
         &self,
         db_info: &DBPacketInfo,
-        db_location: &DBLocation,
-        db_data: &DBData,
+        db_locations: &[DBLocation],
         client_key: &String,
     ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
-        let super_admin_list = self.get_super_admin_list();
+        let super_admin_list = self.get_super_admin_list(db_info);
 
-        let list_lock = self.list.read().unwrap();
+        self.with_db(db_info, |db| {
+            if !db_locations.iter().all(|location| {
+                db.has_key_read_permissions(location.as_key(), client_key, &super_admin_list)
+            }) {
+                return Err(InvalidPermissions);
+            }
 
-        {
-            // scope the cache lock so it goes out of scope faster, allowing us to get a write lock later.
-            let cache_lock = self.cache.read().unwrap();
+            let values: Vec<Option<&String>> = db_locations
+                .iter()
+                .map(|location| db.get_content().read_from_db(location.as_key()))
+                .collect();
 
-            if let Some(db) = cache_lock.get(db_info) {
-                info!("DB Cache hit");
-                // cache is hit, db is currently loaded
+            serde_json::to_string(&values)
+                .map(SuccessReply)
+                .map_err(|_| SerializationError)
+        })
+    }
 
-                let mut db_lock = db.write().unwrap();
+    /// Writes every location and value pair to a db while holding the db lock once, replying with a json list of the previous value at each location, in order.
+    /// Requires write permissions on the given db
+    #[tracing::instrument(skip(self))]
+    pub fn write_many(
+        &self,
+        db_info: &DBPacketInfo,
+        writes: &[(DBLocation, DBData)],
+        client_key: &String,
+    ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+        let super_admin_list = self.get_super_admin_list(db_info);
 
-                return if db_lock.has_write_permissions(client_key, &super_admin_list) {
-                    db_lock.update_access_time();
-                    Ok(db_lock
-                        .get_content_mut()
-                        .content
-                        .insert(
-                            db_location.as_key().to_string(),
-                            db_data.get_data().to_string(),
-                        )
-                        .map_or(SuccessNoData, SuccessReply))
-                } else {
-                    Err(InvalidPermissions)
-                };
+        self.with_db(db_info, |db| {
+            if !writes.iter().all(|(location, _)| {
+                db.has_key_write_permissions(location.as_key(), client_key, &super_admin_list)
+            }) {
+                return Err(InvalidPermissions);
             }
-        }
-
-        if list_lock.contains(db_info) {
-            info!("DB Cache missed");
-            // cache was missed, but the requested database did in fact exist
 
-            let mut cache_lock = self.cache.write().unwrap();
-
-            let mut db = Self::read_db_from_file(db_info)?;
+            for (location, data) in writes {
+                db.check_key_limits(location.as_key(), data.get_data().len())?;
+                db.check_schema(data.get_data())?;
+            }
 
-            db.update_access_time();
+            let (added_entries, added_bytes) = writes
+                .iter()
+                .map(|(location, data)| value_growth(db, location.as_key(), data.get_data()))
+                .fold((0, 0), |(entries, bytes), (added_entries, added_bytes)| {
+                    (entries + added_entries, bytes + added_bytes)
+                });
+            db.check_quota(self.quota_for(db_info), added_entries, added_bytes)?;
 
-            if db.has_write_permissions(client_key, &super_admin_list) {
-                let returned_value = db
-                    .get_content_mut()
-                    .content
-                    .insert(
-                        db_location.as_key().to_string(),
-                        db_data.get_data().to_string(),
-                    )
-                    .map_or(SuccessNoData, SuccessReply);
-
-                cache_lock.insert(db_info.clone(), RwLock::from(db));
-
-                Ok(returned_value)
-            } else {
-                cache_lock.insert(db_info.clone(), RwLock::from(db));
-                Err(InvalidPermissions)
-            }
-        } else {
-            Err(DBNotFound)
-        }
-    }
+            let previous_values: Vec<Option<String>> = writes
+                .iter()
+                .map(|(location, data)| {
+                    let previous_value = db
+                        .get_content_mut()
+                        .insert(location.as_key().to_string(), data.get_data().to_string());
+                    self.notify_written(db_info, location.as_key(), data.get_data());
+                    previous_value
+                })
+                .collect();
 
-    /// Returns the db list in a serialized form of Vec : `DBPacketInfo`
-    #[tracing::instrument(skip(self))]
-    pub fn list_db(&self) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
-        let list = self.list.read().unwrap();
-        serde_json::to_string(&list.clone())
-            .map(SuccessReply)
-            .map_err(|_| SerializationError)
+            serde_json::to_string(&previous_values)
+                .map(SuccessReply)
+                .map_err(|_| SerializationError)
+        })
     }
 
-    /// Returns the db contents in a serialized form of HashMap<String, String>
+    /// Removes every key and value from a db, keeping the settings of the db intact.
+    /// Requires the client to be an admin of the db, or a super admin
     #[tracing::instrument(skip(self))]
-    pub fn list_db_contents(
+    pub fn clear_db(
         &self,
         db_info: &DBPacketInfo,
         client_key: &String,
     ) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
-        if !self.db_name_exists(db_info.get_db_name()) {
-            return Err(DBNotFound);
-        }
-
-        let super_admin_list = self.get_super_admin_list();
-
-        let list_lock = self.list.read().unwrap();
+        let super_admin_list = self.get_super_admin_list(db_info);
 
-        {
-            // scope the cache lock so it goes out of scope faster, allowing us to get a write lock later.
-            let cache_lock = self.cache.read().unwrap();
+        self.with_db(db_info, |db| {
+            if !db.get_role(client_key, &super_admin_list).is_admin() {
+                return Err(InvalidPermissions);
+            }
 
-            if let Some(db) = cache_lock.get(db_info) {
-                info!("DB Cache hit");
-                // cache is hit, db is currently loaded
+            let cleared_keys = db.change_content(|content| {
+                let cleared_keys = content.clear();
+                let changed = !cleared_keys.is_empty();
+                (cleared_keys, changed)
+            });
+            for key in cleared_keys {
+                self.notify_deleted(db_info, &key);
+            }
 
-                let mut db_lock = db.write().unwrap();
+            Ok(SuccessNoData)
+        })
+    }
 
-                return if db_lock.has_list_permissions(client_key, &super_admin_list)
-                    || self.is_super_admin(client_key)
-                {
-                    db_lock.update_access_time();
+    /// Returns `QuotaExceeded` if writing the value to the key would take the db past its quota, or the server quota.
+    fn check_write_quota(
+        &self,
+        db_info: &DBPacketInfo,
+        db: &DB,
+        key: &str,
+        value: &str,
+    ) -> Result<(), DBPacketResponseError> {
+        let (added_entries, added_bytes) = value_growth(db, key, value);
+        db.check_quota(self.quota_for(db_info), added_entries, added_bytes)
+    }
 
-                    serde_json::to_string(&db_lock.get_content().content)
-                        .map(SuccessReply)
-                        .map_err(|_| SerializationError)
-                } else {
-                    Err(InvalidPermissions)
-                };
+    /// Appends the change to the write-ahead log and the replication log, called while the db lock is held so changes to a db are logged in order.
+    fn log_change(&self, db_info: &DBPacketInfo, op: WalOp) {
+        let entry = WalEntry {
+            db: db_info.clone(),
+            op,
+        };
+        if self.saving_enabled {
+            if let Err(err) = self.wal.append(&entry) {
+                error!("Unable to append to write-ahead log: {}", err);
             }
         }
+        self.replication.push(entry);
+    }
 
-        if list_lock.contains(db_info) {
-            info!("DB Cache missed");
-            // cache was missed, but the requested database did in fact exist
-
-            let mut cache_lock = self.cache.write().unwrap();
+    /// Logs the current value of the list, which is empty if the list was removed.
+    fn log_list(&self, db_info: &DBPacketInfo, db: &DB, list_name: &DBLocation) {
+        let values = db
+            .get_content()
+            .get_list(list_name.as_key())
+            .cloned()
+            .unwrap_or_default();
+        self.log_change(
+            db_info,
+            WalOp::List {
+                name: list_name.as_key().to_string(),
+                values,
+            },
+        );
+    }
 
-            let mut db = Self::read_db_from_file(db_info)?;
+    /// Logs the current members of the set, which is empty if the set was removed.
+    fn log_set(&self, db_info: &DBPacketInfo, db: &DB, set_name: &DBLocation) {
+        let members = db
+            .get_content()
+            .get_set(set_name.as_key())
+            .cloned()
+            .unwrap_or_default();
+        self.log_change(
+            db_info,
+            WalOp::Set {
+                name: set_name.as_key().to_string(),
+                members,
+            },
+        );
+    }
 
-            if db.has_list_permissions(client_key, &super_admin_list) {
-                db.update_access_time();
+    /// Logs the current members of the sorted list, which is empty if the sorted list was removed.
+    fn log_sorted_list(&self, db_info: &DBPacketInfo, db: &DB, list_name: &DBLocation) {
+        let list = db
+            .get_content()
+            .get_sorted_list(list_name.as_key())
+            .cloned()
+            .unwrap_or_default();
+        self.log_change(
+            db_info,
+            WalOp::SortedList {
+                name: list_name.as_key().to_string(),
+                list,
+            },
+        );
+    }
 
-                let returned_value = &db.get_content().content;
+    /// Logs the current settings of the db.
+    fn log_settings(&self, db_info: &DBPacketInfo, db: &DB) {
+        self.log_change(db_info, WalOp::Settings(db.get_settings().clone()));
+    }
 
-                let output_response = serde_json::to_string(returned_value)
-                    .map(SuccessReply)
-                    .map_err(|_| SerializationError);
-                cache_lock.insert(db_info.clone(), RwLock::from(db));
+    /// Logs the write and notifies watchers of the db.
+    fn notify_written(&self, db_info: &DBPacketInfo, key: &str, value: &str) {
+        self.log_change(
+            db_info,
+            WalOp::Write {
+                key: key.to_string(),
+                value: value.to_string(),
+            },
+        );
+        self.notification_bus.notify(
+            db_info,
+            ChangeEvent::Written {
+                key: key.to_string(),
+                value: value.to_string(),
+            },
+        );
+    }
 
-                output_response
-            } else {
-                db.update_access_time();
+    /// Logs the deletion and notifies watchers of the db.
+    fn notify_deleted(&self, db_info: &DBPacketInfo, key: &str) {
+        self.log_change(
+            db_info,
+            WalOp::Delete {
+                key: key.to_string(),
+            },
+        );
+        self.notification_bus.notify(
+            db_info,
+            ChangeEvent::Deleted {
+                key: key.to_string(),
+            },
+        );
+    }
+}
 
-                cache_lock.insert(db_info.clone(), RwLock::from(db));
+/// Chunks of a streamed json object that are serialized ahead of the client requesting them.
+const SERIALIZED_CHUNKS_AHEAD: usize = 16;
+
+/// Serializes the key value pairs as a json object on a new thread, sending it in chunks of `STREAM_CHUNK_SIZE` bytes.
+/// Pairs are serialized only as the client requests the chunks, in order of their keys if the content is sorted,
+/// and serializing stops if the receiver is dropped.
+fn stream_json_object(entries: ContentMap) -> Receiver<DBBytes> {
+    let (sender, receiver) = sync_channel(SERIALIZED_CHUNKS_AHEAD);
+
+    thread::spawn(move || {
+        /// Sends every full chunk at the start of the buffer, returning false if the receiver was dropped.
+        fn send_full_chunks(sender: &SyncSender<DBBytes>, buffer: &mut Vec<u8>) -> bool {
+            while buffer.len() >= STREAM_CHUNK_SIZE {
+                let rest = buffer.split_off(STREAM_CHUNK_SIZE);
+                if sender
+                    .send(DBBytes::new(std::mem::replace(buffer, rest)))
+                    .is_err()
+                {
+                    return false;
+                }
+            }
+            true
+        }
 
-                Err(InvalidPermissions)
+        let mut buffer = vec![b'{'];
+        for (index, (key, value)) in entries.iter().enumerate() {
+            if index > 0 {
+                buffer.push(b',');
+            }
+            // a string always serializes
+            let _ = serde_json::to_writer(&mut buffer, key);
+            buffer.push(b':');
+            let _ = serde_json::to_writer(&mut buffer, value);
+            if !send_full_chunks(&sender, &mut buffer) {
+                debug!("Receiver of the streamed contents was dropped");
+                return;
             }
-        } else {
-            Err(DBNotFound)
         }
+        buffer.push(b'}');
+        if send_full_chunks(&sender, &mut buffer) && !buffer.is_empty() {
+            let _ = sender.send(DBBytes::new(buffer));
+        }
+    });
+
+    receiver
+}
+
+/// Returns the number of entries and bytes the db would grow by if the value was written to the key.
+fn value_growth(db: &DB, key: &str, value: &str) -> (usize, usize) {
+    match db.get_content().read_from_db(key) {
+        Some(previous) => (0, value.len().saturating_sub(previous.len())),
+        None => (1, key.len() + value.len()),
     }
 }
 
@@ -1185,9 +3854,27 @@ impl Default for DBList {
     fn default() -> Self {
         Self {
             list: RwLock::new(vec![]),
-            cache: RwLock::new(HashMap::new()),
+            cache: DBCache::default(),
             super_admin_hash_list: RwLock::new(vec![]),
-            server_key: ServerKey::new().unwrap(),
+            namespaces: RwLock::default(),
+            server_key: RwLock::new(ServerKey::new().unwrap()),
+            notification_bus: NotificationBus::default(),
+            channels: ChannelRegistry::default(),
+            queue_waiters: QueueWaiters::default(),
+            data_dir: default_data_dir(),
+            saving_enabled: default_saving_enabled(),
+            wal: WriteAheadLog::default(),
+            replication: ReplicationLog::default(),
+            quota: StorageQuota::default(),
+            trash_retention: default_trash_retention(),
+            cache_memory_budget: None,
+            key_hasher: RwLock::new(KeyHasher::default()),
+            key_hash_version: KEY_HASH_VERSION,
+            bootstrap_token: RwLock::default(),
+            user_registry: RwLock::default(),
+            sessions: SessionStore::default(),
+            #[cfg(feature = "statistics")]
+            server_statistics: RwLock::default(),
         }
     }
 }
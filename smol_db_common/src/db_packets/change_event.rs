@@ -0,0 +1,31 @@
+//! Module containing `ChangeEvent`, the notification sent to clients watching a database for changes.
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+/// A change that happened to a key within a watched database.
+pub enum ChangeEvent {
+    /// The key was written to, contains the new value at the key.
+    Written { key: String, value: String },
+    /// The key was deleted.
+    Deleted { key: String },
+}
+
+impl ChangeEvent {
+    /// Returns the key that the change happened to
+    pub fn get_key(&self) -> &str {
+        match self {
+            ChangeEvent::Written { key, .. } | ChangeEvent::Deleted { key } => key,
+        }
+    }
+}
+
+impl Display for ChangeEvent {
+    #[tracing::instrument(skip_all)]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangeEvent::Written { key, value } => write!(f, "Written: {} = {}", key, value),
+            ChangeEvent::Deleted { key } => write!(f, "Deleted: {}", key),
+        }
+    }
+}
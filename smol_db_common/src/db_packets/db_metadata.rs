@@ -0,0 +1,53 @@
+//! Module containing `DBMetadata`, the description and history of a database, and `DBListing`, the entries of a listing of databases.
+use crate::db_packets::db_packet_info::DBPacketInfo;
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+/// Describes a database, so servers with many databases are discoverable and auditable.
+/// The description and tags are set by clients, the rest is kept up to date by the server.
+pub struct DBMetadata {
+    /// Description of what the database holds
+    #[serde(default)]
+    pub description: String,
+    /// Tags the database can be found by
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Time the database was created, `None` for databases created before metadata was kept
+    #[serde(default)]
+    pub created: Option<SystemTime>,
+    /// Time the content or settings of the database were last changed, `None` if they were not changed since metadata was kept
+    #[serde(default)]
+    pub modified: Option<SystemTime>,
+    /// Hash of the key of the client that created the database, `None` if the database was not created by a client
+    #[serde(default)]
+    pub creator: Option<String>,
+}
+
+impl DBMetadata {
+    /// Returns new metadata for a database created now by the given key
+    pub fn new_created_by(creator: Option<String>) -> Self {
+        Self {
+            created: Some(SystemTime::now()),
+            creator,
+            ..Default::default()
+        }
+    }
+
+    /// Returns true if the database has the given tag
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|own_tag| own_tag == tag)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+/// A database in a listing of the databases on a server.
+/// Serialized as the `DBPacketInfo` with a metadata field, so clients that only read the `DBPacketInfo` can still read listings.
+pub struct DBListing {
+    #[serde(flatten)]
+    /// Name of the database
+    pub info: DBPacketInfo,
+    #[serde(default)]
+    /// Metadata of the database, `None` if the database could not be loaded
+    pub metadata: Option<DBMetadata>,
+}
@@ -1,10 +1,25 @@
+use crate::db_bytes::DBBytes;
 use crate::db_data::DBData;
 use crate::db_packets::db_location::DBLocation;
 use crate::db_packets::db_packet_info::DBPacketInfo;
+use crate::db_packets::db_packet_response::DBPacketResponseError;
 use crate::db_packets::db_settings::DBSettings;
+use crate::db_packets::ip_filter::IpFilter;
+use crate::db_packets::key_acl::KeyAclRule;
+use crate::db_packets::key_pattern::KeyPattern;
+use crate::db_packets::namespace::NamespaceSettings;
+use crate::db_packets::packet_checksum::{add_checksum, verify_checksum};
+use crate::db_packets::stats_format::StatsFormat;
+use crate::db_packets::transaction::TransactionOp;
+use crate::db_packets::value_schema::ValueSchema;
 use crate::encryption::encrypted_data::EncryptedData;
-use rsa::RsaPublicKey;
+use crate::replication::ReplicationPosition;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::warn;
+
+/// Reply the server responds to a `Ping` packet with.
+pub const PONG: &str = "Pong";
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 /// A packet denoting the operation from client->server that the client wishes to do.
@@ -30,32 +45,240 @@ pub enum DBPacket {
     CreateDB(DBPacketInfo, DBSettings),
     /// DeleteDB(db to delete)
     DeleteDB(DBPacketInfo),
+    /// RestoreDB(db to restore from the trash)
+    RestoreDB(DBPacketInfo),
+    /// Removes every deleted db from the trash
+    PurgeTrash,
     /// ListDB
     ListDB,
+    /// Lists the databases the client has read or list permissions on
+    ListAccessibleDB,
     /// ListDBContents(db to read from)
     ListDBContents(DBPacketInfo),
+    /// StreamDBContents(db to read from), streams the contents of the db serialized as `ListDBContents` would in chunks of bytes
+    StreamDBContents(DBPacketInfo),
+    /// ListDBKeys(db to read the keys from)
+    ListDBKeys(DBPacketInfo),
+    /// ListDBLists(db to read the names of the keyed lists from)
+    ListDBLists(DBPacketInfo),
+    /// ReadPrefix(db to read from, prefix that every returned key starts with)
+    ReadPrefix(DBPacketInfo, String),
+    /// FindKeys(db to search, pattern the returned keys match)
+    FindKeys(DBPacketInfo, KeyPattern),
+    /// Atomically writes the new data to the location only if the current value at the location matches the expected value.
+    /// An expected value of `None` means the location is expected to be empty.
+    CompareAndSwap {
+        db: DBPacketInfo,
+        location: DBLocation,
+        expected: Option<DBData>,
+        new: DBData,
+    },
+    /// Atomically adds the delta to the integer stored at the location, a missing value is treated as 0.
+    Increment {
+        db: DBPacketInfo,
+        location: DBLocation,
+        delta: i64,
+    },
+    /// Writes the data to the location only if there is currently no value at the location.
+    WriteIfAbsent(DBPacketInfo, DBLocation, DBData),
+    /// Keeps the connection open, sending a `ChangeEvent` to the client each time a key matching the pattern is written or deleted.
+    /// The client requests each event with `ReadyForNextItem`, and ends the subscription with `EndStreamRead`, which the server acknowledges.
+    Subscribe(DBPacketInfo, KeyPattern),
+    /// Publishes the message to every client subscribed to the channel.
+    Publish(String, String),
+    /// Keeps the connection open, sending each message published to the channel to the client.
+    /// Uses the same `ReadyForNextItem` and `EndStreamRead` flow as `Subscribe`.
+    SubscribeChannel(String),
+    /// Renames a database, keeping its content and settings.
+    RenameDB {
+        from: DBPacketInfo,
+        to: DBPacketInfo,
+    },
+    /// Creates a new database containing a copy of the source database's content, and optionally its settings.
+    CopyDB {
+        source: DBPacketInfo,
+        dest: DBPacketInfo,
+        include_settings: bool,
+    },
+    /// Removes every key and value from the database, keeping its settings.
+    ClearDB(DBPacketInfo),
+    /// Responds with whether a database with the given name exists.
+    DBExists(DBPacketInfo),
+    /// Responds with whether the location in the database has a value, without sending the value.
+    KeyExists(DBPacketInfo, DBLocation),
+    /// Responds with the number of key value pairs in the database.
+    GetDBLength(DBPacketInfo),
+    /// Inserts the data into the list at the given index, or pushes it onto the end of the list if there is no index.
+    /// The list is created if it does not exist.
+    AddToList(DBPacketInfo, DBLocation, DBData, Option<usize>),
+    /// Reads the value at the index of the list.
+    ReadFromList(DBPacketInfo, DBLocation, usize),
+    /// Removes the value at the index of the list, responding with the removed value.
+    RemoveFromList(DBPacketInfo, DBLocation, usize),
+    /// Removes every value from the list.
+    ClearList(DBPacketInfo, DBLocation),
+    /// Responds with the number of values in the list.
+    GetListLength(DBPacketInfo, DBLocation),
+    /// Keeps the connection open, sending each value of the list to the client in order.
+    /// Uses the same `ReadyForNextItem` and `EndStreamRead` flow as `Subscribe`, ending with `SuccessNoData` once every value has been sent.
+    StreamList(DBPacketInfo, DBLocation),
+    /// Adds the member to the set, responding with whether it was not already a member.
+    /// The set is created if it does not exist.
+    SetAdd(DBPacketInfo, DBLocation, DBData),
+    /// Removes the member from the set, responding with whether it was a member.
+    SetRemove(DBPacketInfo, DBLocation, DBData),
+    /// Responds with whether the member is in the set.
+    SetContains(DBPacketInfo, DBLocation, DBData),
+    /// Responds with every member of the set, in order.
+    SetMembers(DBPacketInfo, DBLocation),
+    /// Adds the member to the sorted list with the given score, responding with the rank of the member.
+    /// A member already in the sorted list has its score replaced.
+    SortedAdd(DBPacketInfo, DBLocation, DBData, f64),
+    /// Removes the member from the sorted list, responding with whether it was a member.
+    SortedRemove(DBPacketInfo, DBLocation, DBData),
+    /// Responds with the rank of the member, its index in ascending order of score, if it is in the sorted list.
+    SortedRank(DBPacketInfo, DBLocation, DBData),
+    /// SortedRangeByScore(db, sorted list, min score, max score), responds with every member with a score in the range inclusive, in ascending order.
+    SortedRangeByScore(DBPacketInfo, DBLocation, f64, f64),
+    /// Responds with up to the given number of the highest scoring members, in descending order.
+    SortedTop(DBPacketInfo, DBLocation, usize),
+    /// Pushes the data onto the back of the queue, responding with the new length of the queue.
+    /// Queues are keyed lists, so they can also be read using the list packets.
+    QueuePush(DBPacketInfo, DBLocation, DBData),
+    /// Removes and responds with the value at the front of the queue, or `SuccessNoData` if the queue is empty.
+    /// If a timeout is given, the server waits up to the timeout for a value to be pushed to an empty queue.
+    QueuePop(DBPacketInfo, DBLocation, Option<Duration>),
+    /// Writes the binary data to the location, binary values are stored separately from string values.
+    WriteBytes(DBPacketInfo, DBLocation, DBBytes),
+    /// Reads the binary data at the location, responding with it encoded as base64.
+    ReadBytes(DBPacketInfo, DBLocation),
+    /// Begins writing a binary value to the location in chunks, so a large value is not sent as a single packet.
+    /// The client then sends each `StreamChunk`, which the server acknowledges, followed by `EndStreamWrite` to store the value.
+    BeginStreamWrite(DBPacketInfo, DBLocation),
+    /// A chunk of the binary value being written, only valid after `BeginStreamWrite`.
+    StreamChunk(DBBytes),
+    /// Stores every chunk sent since `BeginStreamWrite` as the binary value at the location.
+    EndStreamWrite,
+    /// Discards every chunk sent since `BeginStreamWrite` without storing anything.
+    AbortStreamWrite,
+    /// Begins reading the binary value at the location in chunks.
+    /// Uses the same `ReadyForNextItem` and `EndStreamRead` flow as `Subscribe`, ending with `SuccessNoData` once every chunk has been sent.
+    BeginStreamRead(DBPacketInfo, DBLocation),
+    /// Reads every location in the database in one request, under a single lock acquisition.
+    ReadMany(DBPacketInfo, Vec<DBLocation>),
+    /// Writes every location and value pair to the database in one request, under a single lock acquisition.
+    WriteMany(DBPacketInfo, Vec<(DBLocation, DBData)>),
+    /// Applies every operation to the database atomically, either every operation succeeds or none of them are applied.
+    Transaction(DBPacketInfo, Vec<TransactionOp>),
+    /// Saves a snapshot of every database to the backups directory of the server, responding with the file name of the snapshot.
+    CreateSnapshot,
+    /// Begins sending a dump of the database in chunks, which holds its content, settings and statistics.
+    /// Uses the same `ReadyForNextItem` and `EndStreamRead` flow as `Subscribe`, ending with `SuccessNoData` once every chunk has been sent.
+    ExportDB(DBPacketInfo),
+    /// Begins receiving a dump made by `ExportDB` in chunks, using the same `StreamChunk` and `EndStreamWrite` flow as `BeginStreamWrite`.
+    /// The database is created from the dump once it has been received, responding with the name of the database.
+    ImportDB,
+    /// Sent by a replica server to begin receiving every change made on this server, from the given position onward.
+    /// Every database is sent first when there is no position, or the changes after it are no longer kept.
+    /// Uses the same `ReadyForNextItem` and `EndStreamRead` flow as `Subscribe`, each item is a `ReplicationItem`.
+    ReplicaHandshake(Option<ReplicationPosition>),
+    /// Responds with the state of the server as a `ServerInfo`, such as its version, uptime and number of databases.
+    ServerInfo,
+    /// Responds with `PONG` without touching any database, used to measure round trip time and keep idle connections open.
+    Ping,
     /// Adds an admin to the database with the given hash
     AddAdmin(DBPacketInfo, String),
     /// Adds a user to the database with the given hash
     AddUser(DBPacketInfo, String),
+    /// Removes an admin from the database, by its key or the hash of it listed in the db settings
+    RemoveAdmin(DBPacketInfo, String),
+    /// Removes a user from the database, by its key or the hash of it listed in the db settings
+    RemoveUser(DBPacketInfo, String),
+    /// Adds a rule limiting a key to the entries of the database under a prefix, the key of the rule is hashed by the server
+    AddKeyAcl(DBPacketInfo, KeyAclRule),
+    /// RemoveKeyAcl(db, key, prefix), removes the rule with the given key and prefix from the database
+    RemoveKeyAcl(DBPacketInfo, String, String),
+    /// Lists the key acl rules of the database
+    GetKeyAcls(DBPacketInfo),
+    /// Replaces the json schema every value written to the database must match, or removes it when there is none
+    SetDBSchema(DBPacketInfo, Option<ValueSchema>),
+    /// Responds with the json schema of the database, if it has one
+    GetDBSchema(DBPacketInfo),
+    /// SetDBMetadata(db, description, tags), replaces the description and tags of the database
+    SetDBMetadata(DBPacketInfo, String, Vec<String>),
+    /// Responds with the metadata of the database
+    GetDBMetadata(DBPacketInfo),
+    /// Lists the revisions kept of the value at the key
+    ListRevisions(DBPacketInfo, DBLocation),
+    /// ReadRevision(db, key, revision), reads a revision of the value at the key
+    ReadRevision(DBPacketInfo, DBLocation, u64),
     /// Sets the clients key to the given hash
     SetKey(String),
+    /// Makes the key the client has set the first super admin, given the bootstrap token printed by the server on its first startup
+    ClaimSuperAdmin(String),
+    /// Login(username, password), logs in to a user account, making the key of the account the key of the client
+    Login(String, String),
+    /// CreateUser(username, password), creates a user account
+    CreateUser(String, String),
+    /// SetUserDisabled(username, disabled), disables or enables a user account
+    SetUserDisabled(String, bool),
+    /// Lists the username of every user account, and whether it is disabled
+    ListUsers,
+    /// Replaces the ip allow and deny lists of the server until it restarts
+    SetIpFilter(IpFilter),
+    /// SetNamespace(namespace, settings), replaces the super admins and limits of the databases named `namespace/name`, the keys of the super admins are hashed by the server
+    SetNamespace(String, NamespaceSettings),
+    /// Responds with the settings of the namespace
+    GetNamespace(String),
+    /// Creates a session token for the key the client has set, which resumes the key on another connection until it expires
+    CreateSession,
+    /// Sets the key of the client to the key the session token was created with
+    ResumeSession(String),
+    /// Ends the session the token belongs to
+    EndSession(String),
+    /// Replaces the rsa key of the server with a newly generated one, responding with the fingerprint of the new key
+    RotateServerKey,
     /// Returns the DBSettings struct within the given db
     GetDBSettings(DBPacketInfo),
     /// Sets the DBSettings struct within the given db to the new settings struct.
     ChangeDBSettings(DBPacketInfo, DBSettings),
     /// GetRole(db to read role from)
     GetRole(DBPacketInfo),
+    /// GetEffectivePermissions(db to read permissions from), responds with the role and permissions the client resolves to in the db
+    GetEffectivePermissions(DBPacketInfo),
     /// GetStats gets the statistics object if the feature is compiled
     GetStats(DBPacketInfo),
+    /// Responds with the `ServerStatistics` of every request made to the server if the feature is compiled, requires super admin privileges.
+    GetServerStats,
+    /// GetHotKeys(db, n) responds with the `n` most accessed keys of the db and their number of accesses if the statistics feature is compiled, requires admin privileges.
+    GetHotKeys(DBPacketInfo, usize),
+    /// Resets the `DBStatistics` of the given db if the statistics feature is compiled, requires super admin privileges.
+    ResetStats(DBPacketInfo),
+    /// Responds with the server statistics and the statistics of every db in the given format if the statistics feature is compiled, requires super admin privileges.
+    ExportStats(StatsFormat),
     /// Encrypted packet, used to allow the server to identify when data needs to be decrypted
     Encrypted(EncryptedData),
-    /// Packet used in establishing end to end encryption, requests the server to store the sent public key
-    PubKey(RsaPublicKey),
+    /// Packet used in establishing end to end encryption, sends the session key encrypted with the servers public key.
+    /// Every packet after it is encrypted with the session key, in both directions.
+    SessionKey(EncryptedData),
     /// Request the server to setup end to end encryption
     SetupEncryption,
     /// Request the server to begin streaming values from a given DB to the user
     StreamReadDb(DBPacketInfo),
+    /// StreamReadDbFiltered(db to read from, key prefix, value substring)
+    /// Streams like `StreamReadDb`, skipping the pairs whose key does not start with the prefix or whose value does not contain the substring,
+    /// an empty prefix or substring matches every pair.
+    StreamReadDbFiltered(DBPacketInfo, String, String),
+    /// OpenStream(db to read from)
+    /// Opens a stream of the db that is read by its id, responding with the id.
+    /// Unlike `StreamReadDb`, the stream does not take over the connection, so other requests can be sent in between reading its items.
+    OpenStream(DBPacketInfo),
+    /// ReadStreamItem(stream id)
+    /// Responds with the next `StreamItem` of the open stream, the stream is closed once it responds with `StreamItem::StreamEnd`.
+    ReadStreamItem(u64),
+    /// CloseStream(stream id)
+    /// Closes the open stream before it is read to its end.
+    CloseStream(u64),
     /// Request the next item in the stream, if one is open
     ReadyForNextItem,
     /// Tell the server that the client wants to stop streaming values from a DB
@@ -67,11 +290,59 @@ impl DBPacket {
         Self::StreamReadDb(DBPacketInfo::new(dbname))
     }
 
+    /// Creates a `StreamReadDbFiltered` packet.
+    /// When sent to the server, streams the pairs of the db whose key starts with `key_prefix` and whose value contains `value_contains`.
+    pub fn new_stream_table_filtered(dbname: &str, key_prefix: &str, value_contains: &str) -> Self {
+        Self::StreamReadDbFiltered(
+            DBPacketInfo::new(dbname),
+            key_prefix.to_string(),
+            value_contains.to_string(),
+        )
+    }
+
+    /// Creates an `OpenStream` packet.
+    /// When sent to the server, opens a stream of the db and responds with its id, requires stream permissions on the db.
+    pub fn new_open_stream(dbname: &str) -> Self {
+        Self::OpenStream(DBPacketInfo::new(dbname))
+    }
+
+    /// Creates a `ReadStreamItem` packet.
+    /// When sent to the server, responds with the next item of the open stream with the given id.
+    pub fn new_read_stream_item(stream_id: u64) -> Self {
+        Self::ReadStreamItem(stream_id)
+    }
+
+    /// Creates a `CloseStream` packet.
+    /// When sent to the server, closes the open stream with the given id.
+    pub fn new_close_stream(stream_id: u64) -> Self {
+        Self::CloseStream(stream_id)
+    }
+
     #[cfg(feature = "statistics")]
     pub fn new_get_stats(dbname: &str) -> Self {
         Self::GetStats(DBPacketInfo::new(dbname))
     }
 
+    #[cfg(feature = "statistics")]
+    pub fn new_get_server_stats() -> Self {
+        Self::GetServerStats
+    }
+
+    #[cfg(feature = "statistics")]
+    pub fn new_get_hot_keys(dbname: &str, n: usize) -> Self {
+        Self::GetHotKeys(DBPacketInfo::new(dbname), n)
+    }
+
+    #[cfg(feature = "statistics")]
+    pub fn new_reset_stats(dbname: &str) -> Self {
+        Self::ResetStats(DBPacketInfo::new(dbname))
+    }
+
+    #[cfg(feature = "statistics")]
+    pub const fn new_export_stats(format: StatsFormat) -> Self {
+        Self::ExportStats(format)
+    }
+
     /// Creates a new Read `DBPacket` from a name of a database and location string to read from.
     pub fn new_read(dbname: &str, location: &str) -> Self {
         Self::Read(DBPacketInfo::new(dbname), DBLocation::new(location))
@@ -87,6 +358,12 @@ impl DBPacket {
         Self::GetRole(DBPacketInfo::new(dbname))
     }
 
+    /// Creates a new `GetEffectivePermissions` packet, this packet when sent to the server will request the role and permissions the client resolves to in the given db,
+    /// along with the key acl rules that limit the client.
+    pub fn new_get_effective_permissions(dbname: &str) -> Self {
+        Self::GetEffectivePermissions(DBPacketInfo::new(dbname))
+    }
+
     /// Creates a new `GetDBSettings` packet, this packet when sent to the server will request the db settings of a database, requires super admin privileges.
     pub fn new_get_db_settings(dbname: &str) -> Self {
         Self::GetDBSettings(DBPacketInfo::new(dbname))
@@ -125,25 +402,846 @@ impl DBPacket {
         Self::DeleteDB(DBPacketInfo::new(dbname))
     }
 
+    /// Creates a new `RestoreDB` `DBPacket` from a name of a database.
+    /// Restores the most recently deleted db with the given name from the trash, requires super admin privileges.
+    pub fn new_restore_db(dbname: &str) -> Self {
+        Self::RestoreDB(DBPacketInfo::new(dbname))
+    }
+
+    /// Creates a `PurgeTrash` packet.
+    /// When sent to the server, removes every deleted db from the trash so it can no longer be restored, requires super admin privileges.
+    pub const fn new_purge_trash() -> Self {
+        Self::PurgeTrash
+    }
+
     /// Creates a `ListDB` packet.
     /// When sent to the server, lists the databases contained on the server
     pub const fn new_list_db() -> Self {
         Self::ListDB
     }
 
+    /// Creates a `ListAccessibleDB` packet.
+    /// When sent to the server, lists the databases the client has read or list permissions on
+    pub const fn new_list_accessible_db() -> Self {
+        Self::ListAccessibleDB
+    }
+
     /// Creates a `ListDBContents` packet
     /// When sent to the server, lists the contents of a given db, requires permission to do so, which depends on the given database.
     pub fn new_list_db_contents(db_name: &str) -> Self {
         Self::ListDBContents(DBPacketInfo::new(db_name))
     }
 
-    /// Serializes a `DBPacket` into a string to be sent over the internet.
+    /// Creates a `StreamDBContents` packet
+    /// When sent to the server, begins streaming the serialized contents of the given db in chunks, requires list permissions on the db.
+    pub fn new_stream_db_contents(db_name: &str) -> Self {
+        Self::StreamDBContents(DBPacketInfo::new(db_name))
+    }
+
+    /// Creates a `ListDBKeys` packet
+    /// When sent to the server, lists only the keys of a given db, requires list permissions on the given database.
+    pub fn new_list_db_keys(db_name: &str) -> Self {
+        Self::ListDBKeys(DBPacketInfo::new(db_name))
+    }
+
+    /// Creates a `ListDBLists` packet
+    /// When sent to the server, lists the names of the keyed lists of a given db, requires list permissions on the given database.
+    pub fn new_list_db_lists(db_name: &str) -> Self {
+        Self::ListDBLists(DBPacketInfo::new(db_name))
+    }
+
+    /// Creates a `ReadPrefix` packet
+    /// When sent to the server, reads every key value pair whose key starts with the given prefix, requires read permissions on the given database.
+    pub fn new_read_prefix(db_name: &str, prefix: &str) -> Self {
+        Self::ReadPrefix(DBPacketInfo::new(db_name), prefix.to_string())
+    }
+
+    /// Creates a `FindKeys` packet
+    /// When sent to the server, lists the keys of a given db that match the given pattern, requires list permissions on the given database.
+    pub fn new_find_keys(db_name: &str, pattern: KeyPattern) -> Self {
+        Self::FindKeys(DBPacketInfo::new(db_name), pattern)
+    }
+
+    /// Creates a `CompareAndSwap` packet
+    /// When sent to the server, writes the new data to the location only if the value currently stored there is the expected value, requires read and write permissions.
+    pub fn new_compare_and_swap(
+        dbname: &str,
+        location: &str,
+        expected: Option<&str>,
+        new: &str,
+    ) -> Self {
+        Self::CompareAndSwap {
+            db: DBPacketInfo::new(dbname),
+            location: DBLocation::new(location),
+            expected: expected.map(|data| DBData::new(data.to_string())),
+            new: DBData::new(new.to_string()),
+        }
+    }
+
+    /// Creates an `Increment` packet
+    /// When sent to the server, adds the delta to the integer stored at the location and responds with the new value, requires read and write permissions.
+    pub fn new_increment(dbname: &str, location: &str, delta: i64) -> Self {
+        Self::Increment {
+            db: DBPacketInfo::new(dbname),
+            location: DBLocation::new(location),
+            delta,
+        }
+    }
+
+    /// Creates a `Transaction` packet
+    /// When sent to the server, applies each operation in order under a single write lock, responding with the result of each operation, or rolling back every operation if one fails.
+    pub fn new_transaction(dbname: &str, ops: Vec<TransactionOp>) -> Self {
+        Self::Transaction(DBPacketInfo::new(dbname), ops)
+    }
+
+    /// Creates a `WriteIfAbsent` packet
+    /// When sent to the server, writes the data to the location only if the location is empty, requires write permissions.
+    pub fn new_write_if_absent(dbname: &str, location: &str, data: &str) -> Self {
+        Self::WriteIfAbsent(
+            DBPacketInfo::new(dbname),
+            DBLocation::new(location),
+            DBData::new(data.to_string()),
+        )
+    }
+
+    /// Creates a `Subscribe` packet
+    /// When sent to the server, the connection is used to watch for changes to keys matching the pattern, requires read permissions.
+    pub fn new_subscribe(dbname: &str, pattern: KeyPattern) -> Self {
+        Self::Subscribe(DBPacketInfo::new(dbname), pattern)
+    }
+
+    /// Creates a `Publish` packet
+    /// When sent to the server, the message is sent to every subscriber of the channel, responding with the number of subscribers that received it.
+    pub fn new_publish(channel_name: &str, message: &str) -> Self {
+        Self::Publish(channel_name.to_string(), message.to_string())
+    }
+
+    /// Creates a `SubscribeChannel` packet
+    /// When sent to the server, the connection is used to receive messages published to the channel.
+    pub fn new_subscribe_channel(channel_name: &str) -> Self {
+        Self::SubscribeChannel(channel_name.to_string())
+    }
+
+    /// Creates a `ReadMany` packet
+    /// When sent to the server, responds with the value at each location in order, requires read permissions.
+    pub fn new_read_many(dbname: &str, locations: &[&str]) -> Self {
+        Self::ReadMany(
+            DBPacketInfo::new(dbname),
+            locations
+                .iter()
+                .map(|location| DBLocation::new(location))
+                .collect(),
+        )
+    }
+
+    /// Creates a `WriteMany` packet
+    /// When sent to the server, writes each value to its location, responding with the previous value at each location in order, requires write permissions.
+    pub fn new_write_many(dbname: &str, writes: &[(&str, &str)]) -> Self {
+        Self::WriteMany(
+            DBPacketInfo::new(dbname),
+            writes
+                .iter()
+                .map(|(location, data)| (DBLocation::new(location), DBData::new(data.to_string())))
+                .collect(),
+        )
+    }
+
+    /// Creates a `ClearDB` packet
+    /// When sent to the server, removes all content from the database while keeping its settings, requires admin permissions.
+    pub fn new_clear_db(dbname: &str) -> Self {
+        Self::ClearDB(DBPacketInfo::new(dbname))
+    }
+
+    /// Creates a `RenameDB` packet
+    /// When sent to the server, renames the database file and its entry in the db list, requires super admin privileges.
+    pub fn new_rename_db(from: &str, to: &str) -> Self {
+        Self::RenameDB {
+            from: DBPacketInfo::new(from),
+            to: DBPacketInfo::new(to),
+        }
+    }
+
+    /// Creates a `CopyDB` packet
+    /// When sent to the server, creates the destination database with the content of the source database, requires super admin privileges.
+    /// When `include_settings` is false, the destination database uses the default settings.
+    pub fn new_copy_db(source: &str, dest: &str, include_settings: bool) -> Self {
+        Self::CopyDB {
+            source: DBPacketInfo::new(source),
+            dest: DBPacketInfo::new(dest),
+            include_settings,
+        }
+    }
+
+    /// Creates a `CreateSnapshot` packet
+    /// When sent to the server, saves a snapshot of every database to the backups directory, requires super admin privileges.
+    pub fn new_create_snapshot() -> Self {
+        Self::CreateSnapshot
+    }
+
+    /// Creates an `ExportDB` packet
+    /// When sent to the server, begins sending a dump of the database in chunks, requires super admin privileges.
+    pub fn new_export_db(dbname: &str) -> Self {
+        Self::ExportDB(DBPacketInfo::new(dbname))
+    }
+
+    /// Creates an `ImportDB` packet
+    /// When sent to the server, begins receiving a dump of a database in chunks, requires super admin privileges.
+    pub fn new_import_db() -> Self {
+        Self::ImportDB
+    }
+
+    /// Creates a `ReplicaHandshake` packet
+    /// When sent to the server, begins sending every change made to its databases, requires super admin privileges.
+    pub fn new_replica_handshake(position: Option<ReplicationPosition>) -> Self {
+        Self::ReplicaHandshake(position)
+    }
+
+    /// Creates an `AddAdmin` packet
+    /// When sent to the server, adds the key as an admin of the db, requires super admin privileges.
+    pub fn new_add_admin(dbname: &str, key: &str) -> Self {
+        Self::AddAdmin(DBPacketInfo::new(dbname), key.to_string())
+    }
+
+    /// Creates an `AddUser` packet
+    /// When sent to the server, adds the key as a user of the db, requires manage users permissions on the db.
+    pub fn new_add_user(dbname: &str, key: &str) -> Self {
+        Self::AddUser(DBPacketInfo::new(dbname), key.to_string())
+    }
+
+    /// Creates a `RemoveAdmin` packet
+    /// When sent to the server, removes the key or listed hash from the admins of the db, requires super admin privileges.
+    pub fn new_remove_admin(dbname: &str, key: &str) -> Self {
+        Self::RemoveAdmin(DBPacketInfo::new(dbname), key.to_string())
+    }
+
+    /// Creates a `RemoveUser` packet
+    /// When sent to the server, removes the key or listed hash from the users of the db, requires manage users permissions on the db.
+    pub fn new_remove_user(dbname: &str, key: &str) -> Self {
+        Self::RemoveUser(DBPacketInfo::new(dbname), key.to_string())
+    }
+
+    /// Creates an `AddKeyAcl` packet
+    /// When sent to the server, limits the key to reading and or writing the entries of the db under the prefix, requires admin privileges on the db.
+    pub fn new_add_key_acl(dbname: &str, key: &str, prefix: &str, read: bool, write: bool) -> Self {
+        Self::AddKeyAcl(
+            DBPacketInfo::new(dbname),
+            KeyAclRule::new(key, prefix, read, write),
+        )
+    }
+
+    /// Creates a `RemoveKeyAcl` packet
+    /// When sent to the server, removes the rule with the given key and prefix from the db, requires admin privileges on the db.
+    pub fn new_remove_key_acl(dbname: &str, key: &str, prefix: &str) -> Self {
+        Self::RemoveKeyAcl(
+            DBPacketInfo::new(dbname),
+            key.to_string(),
+            prefix.to_string(),
+        )
+    }
+
+    /// Creates a `GetKeyAcls` packet
+    /// When sent to the server, responds with the key acl rules of the db, requires admin privileges on the db.
+    pub fn new_get_key_acls(dbname: &str) -> Self {
+        Self::GetKeyAcls(DBPacketInfo::new(dbname))
+    }
+
+    /// Creates a `SetDBSchema` packet
+    /// When sent to the server, writes of values that do not match the schema are rejected with `SchemaViolation`, requires manage settings permissions on the db.
+    pub fn new_set_db_schema(dbname: &str, schema: Option<ValueSchema>) -> Self {
+        Self::SetDBSchema(DBPacketInfo::new(dbname), schema)
+    }
+
+    /// Creates a `GetDBSchema` packet
+    /// When sent to the server, responds with the schema of the db, or no data if it has none, requires read or write permissions on the db.
+    pub fn new_get_db_schema(dbname: &str) -> Self {
+        Self::GetDBSchema(DBPacketInfo::new(dbname))
+    }
+
+    /// Creates a `SetDBMetadata` packet
+    /// When sent to the server, replaces the description and tags of the db, requires manage settings permissions on the db.
+    pub fn new_set_db_metadata(dbname: &str, description: &str, tags: Vec<String>) -> Self {
+        Self::SetDBMetadata(DBPacketInfo::new(dbname), description.to_string(), tags)
+    }
+
+    /// Creates a `GetDBMetadata` packet
+    /// When sent to the server, responds with the description, tags, creation and modification times, and creator of the db, requires list permissions on the db.
+    pub fn new_get_db_metadata(dbname: &str) -> Self {
+        Self::GetDBMetadata(DBPacketInfo::new(dbname))
+    }
+
+    /// Creates a `ListRevisions` packet
+    /// When sent to the server, responds with the number and replacement time of each revision kept of the value at the key, requires read permissions on the key.
+    pub fn new_list_revisions(dbname: &str, key: &str) -> Self {
+        Self::ListRevisions(DBPacketInfo::new(dbname), DBLocation::new(key))
+    }
+
+    /// Creates a `ReadRevision` packet
+    /// When sent to the server, responds with the revision of the value at the key, or `ValueNotFound` if it is no longer kept, requires read permissions on the key.
+    pub fn new_read_revision(dbname: &str, key: &str, revision: u64) -> Self {
+        Self::ReadRevision(DBPacketInfo::new(dbname), DBLocation::new(key), revision)
+    }
+
+    /// Creates a `Login` packet
+    /// When sent to the server, logs in to the user account, making the key of the account the key of the client for the rest of the session.
+    pub fn new_login(username: &str, password: &str) -> Self {
+        Self::Login(username.to_string(), password.to_string())
+    }
+
+    /// Creates a `CreateUser` packet
+    /// When sent to the server, creates a user account with the given username and password, requires super admin privileges.
+    pub fn new_create_user(username: &str, password: &str) -> Self {
+        Self::CreateUser(username.to_string(), password.to_string())
+    }
+
+    /// Creates a `SetUserDisabled` packet
+    /// When sent to the server, disables or enables the user account, requires super admin privileges.
+    pub fn new_set_user_disabled(username: &str, disabled: bool) -> Self {
+        Self::SetUserDisabled(username.to_string(), disabled)
+    }
+
+    /// Creates a `ListUsers` packet
+    /// When sent to the server, responds with the username of every user account and whether it is disabled, requires super admin privileges.
+    pub const fn new_list_users() -> Self {
+        Self::ListUsers
+    }
+
+    /// Creates a `SetIpFilter` packet
+    /// When sent to the server, replaces the lists of addresses it accepts connections from until it restarts, requires super admin privileges.
+    pub const fn new_set_ip_filter(ip_filter: IpFilter) -> Self {
+        Self::SetIpFilter(ip_filter)
+    }
+
+    /// Creates a `SetNamespace` packet
+    /// When sent to the server, replaces the super admins and limits of the databases in the namespace, requires super admin privileges.
+    pub fn new_set_namespace(namespace: &str, settings: NamespaceSettings) -> Self {
+        Self::SetNamespace(namespace.to_string(), settings)
+    }
+
+    /// Creates a `GetNamespace` packet
+    /// When sent to the server, responds with the settings of the namespace, requires super admin privileges of the server or of the namespace.
+    pub fn new_get_namespace(namespace: &str) -> Self {
+        Self::GetNamespace(namespace.to_string())
+    }
+
+    /// Creates a `CreateSession` packet
+    /// When sent to the server after setting a key, responds with a session token that resumes the key until it expires.
+    pub const fn new_create_session() -> Self {
+        Self::CreateSession
+    }
+
+    /// Creates a `ResumeSession` packet
+    /// When sent to the server, sets the key of the client to the key the session was created with, if it has not expired.
+    pub fn new_resume_session(token: &str) -> Self {
+        Self::ResumeSession(token.to_string())
+    }
+
+    /// Creates an `EndSession` packet
+    /// When sent to the server, ends the session so the token can no longer be resumed.
+    pub fn new_end_session(token: &str) -> Self {
+        Self::EndSession(token.to_string())
+    }
+
+    /// Creates a `RotateServerKey` packet
+    /// When sent to the server, replaces its rsa key and responds with the fingerprint of the new key, requires super admin privileges.
+    pub const fn new_rotate_server_key() -> Self {
+        Self::RotateServerKey
+    }
+
+    /// Creates a `ClaimSuperAdmin` packet
+    /// When sent to the server after setting a key, makes the key the first super admin if the token is the bootstrap token of the server.
+    pub fn new_claim_super_admin(token: &str) -> Self {
+        Self::ClaimSuperAdmin(token.to_string())
+    }
+
+    /// Creates a `ServerInfo` packet
+    /// When sent to the server, responds with the state of the server, requires super admin privileges.
+    pub fn new_server_info() -> Self {
+        Self::ServerInfo
+    }
+
+    /// Creates a `Ping` packet
+    /// When sent to the server, responds with `PONG`, requires no privileges.
+    pub fn new_ping() -> Self {
+        Self::Ping
+    }
+
+    /// Returns the name of the packet type, such as `Read` or `CreateDB`, without any of the data it holds.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Read(..) => "Read",
+            Self::Write(..) => "Write",
+            Self::DeleteData(..) => "DeleteData",
+            Self::CreateDB(..) => "CreateDB",
+            Self::DeleteDB(..) => "DeleteDB",
+            Self::RestoreDB(..) => "RestoreDB",
+            Self::PurgeTrash => "PurgeTrash",
+            Self::ListDB => "ListDB",
+            Self::ListAccessibleDB => "ListAccessibleDB",
+            Self::ListDBContents(..) => "ListDBContents",
+            Self::StreamDBContents(..) => "StreamDBContents",
+            Self::ListDBKeys(..) => "ListDBKeys",
+            Self::ListDBLists(..) => "ListDBLists",
+            Self::ReadPrefix(..) => "ReadPrefix",
+            Self::FindKeys(..) => "FindKeys",
+            Self::CompareAndSwap { .. } => "CompareAndSwap",
+            Self::Increment { .. } => "Increment",
+            Self::WriteIfAbsent(..) => "WriteIfAbsent",
+            Self::Subscribe(..) => "Subscribe",
+            Self::Publish(..) => "Publish",
+            Self::SubscribeChannel(..) => "SubscribeChannel",
+            Self::RenameDB { .. } => "RenameDB",
+            Self::CopyDB { .. } => "CopyDB",
+            Self::ClearDB(..) => "ClearDB",
+            Self::DBExists(..) => "DBExists",
+            Self::KeyExists(..) => "KeyExists",
+            Self::GetDBLength(..) => "GetDBLength",
+            Self::AddToList(..) => "AddToList",
+            Self::ReadFromList(..) => "ReadFromList",
+            Self::RemoveFromList(..) => "RemoveFromList",
+            Self::ClearList(..) => "ClearList",
+            Self::GetListLength(..) => "GetListLength",
+            Self::StreamList(..) => "StreamList",
+            Self::SetAdd(..) => "SetAdd",
+            Self::SetRemove(..) => "SetRemove",
+            Self::SetContains(..) => "SetContains",
+            Self::SetMembers(..) => "SetMembers",
+            Self::SortedAdd(..) => "SortedAdd",
+            Self::SortedRemove(..) => "SortedRemove",
+            Self::SortedRank(..) => "SortedRank",
+            Self::SortedRangeByScore(..) => "SortedRangeByScore",
+            Self::SortedTop(..) => "SortedTop",
+            Self::QueuePush(..) => "QueuePush",
+            Self::QueuePop(..) => "QueuePop",
+            Self::WriteBytes(..) => "WriteBytes",
+            Self::ReadBytes(..) => "ReadBytes",
+            Self::BeginStreamWrite(..) => "BeginStreamWrite",
+            Self::StreamChunk(..) => "StreamChunk",
+            Self::EndStreamWrite => "EndStreamWrite",
+            Self::AbortStreamWrite => "AbortStreamWrite",
+            Self::BeginStreamRead(..) => "BeginStreamRead",
+            Self::ReadMany(..) => "ReadMany",
+            Self::WriteMany(..) => "WriteMany",
+            Self::Transaction(..) => "Transaction",
+            Self::CreateSnapshot => "CreateSnapshot",
+            Self::ExportDB(..) => "ExportDB",
+            Self::ImportDB => "ImportDB",
+            Self::ReplicaHandshake(..) => "ReplicaHandshake",
+            Self::ServerInfo => "ServerInfo",
+            Self::Ping => "Ping",
+            Self::AddAdmin(..) => "AddAdmin",
+            Self::AddUser(..) => "AddUser",
+            Self::RemoveAdmin(..) => "RemoveAdmin",
+            Self::RemoveUser(..) => "RemoveUser",
+            Self::AddKeyAcl(..) => "AddKeyAcl",
+            Self::RemoveKeyAcl(..) => "RemoveKeyAcl",
+            Self::GetKeyAcls(..) => "GetKeyAcls",
+            Self::SetDBSchema(..) => "SetDBSchema",
+            Self::GetDBSchema(..) => "GetDBSchema",
+            Self::SetDBMetadata(..) => "SetDBMetadata",
+            Self::GetDBMetadata(..) => "GetDBMetadata",
+            Self::ListRevisions(..) => "ListRevisions",
+            Self::ReadRevision(..) => "ReadRevision",
+            Self::SetKey(..) => "SetKey",
+            Self::ClaimSuperAdmin(..) => "ClaimSuperAdmin",
+            Self::Login(..) => "Login",
+            Self::CreateUser(..) => "CreateUser",
+            Self::SetUserDisabled(..) => "SetUserDisabled",
+            Self::ListUsers => "ListUsers",
+            Self::SetIpFilter(..) => "SetIpFilter",
+            Self::SetNamespace(..) => "SetNamespace",
+            Self::GetNamespace(..) => "GetNamespace",
+            Self::CreateSession => "CreateSession",
+            Self::ResumeSession(..) => "ResumeSession",
+            Self::EndSession(..) => "EndSession",
+            Self::RotateServerKey => "RotateServerKey",
+            Self::GetDBSettings(..) => "GetDBSettings",
+            Self::ChangeDBSettings(..) => "ChangeDBSettings",
+            Self::GetRole(..) => "GetRole",
+            Self::GetEffectivePermissions(..) => "GetEffectivePermissions",
+            Self::GetStats(..) => "GetStats",
+            Self::GetServerStats => "GetServerStats",
+            Self::GetHotKeys(..) => "GetHotKeys",
+            Self::ResetStats(..) => "ResetStats",
+            Self::ExportStats(..) => "ExportStats",
+            Self::Encrypted(..) => "Encrypted",
+            Self::SessionKey(..) => "SessionKey",
+            Self::SetupEncryption => "SetupEncryption",
+            Self::StreamReadDb(..) => "StreamReadDb",
+            Self::StreamReadDbFiltered(..) => "StreamReadDbFiltered",
+            Self::OpenStream(..) => "OpenStream",
+            Self::ReadStreamItem(..) => "ReadStreamItem",
+            Self::CloseStream(..) => "CloseStream",
+            Self::ReadyForNextItem => "ReadyForNextItem",
+            Self::EndStreamRead => "EndStreamRead",
+        }
+    }
+
+    /// Returns true if the packet changes a database, users, or settings when handled, such packets are rejected by replicas.
+    pub const fn is_mutation(&self) -> bool {
+        matches!(
+            self,
+            Self::Write(..)
+                | Self::DeleteData(..)
+                | Self::CreateDB(..)
+                | Self::DeleteDB(..)
+                | Self::RestoreDB(..)
+                | Self::PurgeTrash
+                | Self::CompareAndSwap { .. }
+                | Self::Increment { .. }
+                | Self::WriteIfAbsent(..)
+                | Self::RenameDB { .. }
+                | Self::CopyDB { .. }
+                | Self::ClearDB(..)
+                | Self::AddToList(..)
+                | Self::RemoveFromList(..)
+                | Self::ClearList(..)
+                | Self::SetAdd(..)
+                | Self::SetRemove(..)
+                | Self::SortedAdd(..)
+                | Self::SortedRemove(..)
+                | Self::QueuePush(..)
+                | Self::QueuePop(..)
+                | Self::WriteBytes(..)
+                | Self::BeginStreamWrite(..)
+                | Self::WriteMany(..)
+                | Self::Transaction(..)
+                | Self::ImportDB
+                | Self::AddAdmin(..)
+                | Self::AddUser(..)
+                | Self::RemoveAdmin(..)
+                | Self::RemoveUser(..)
+                | Self::ClaimSuperAdmin(..)
+                | Self::CreateUser(..)
+                | Self::AddKeyAcl(..)
+                | Self::RemoveKeyAcl(..)
+                | Self::SetDBSchema(..)
+                | Self::SetDBMetadata(..)
+                | Self::SetUserDisabled(..)
+                | Self::SetNamespace(..)
+                | Self::ChangeDBSettings(..)
+        )
+    }
+
+    /// Returns the database the packet works on, or the source database of a rename or copy,
+    /// `None` for packets that do not work on a single database.
+    pub const fn db_info(&self) -> Option<&DBPacketInfo> {
+        match self {
+            Self::Read(db, ..)
+            | Self::Write(db, ..)
+            | Self::DeleteData(db, ..)
+            | Self::CreateDB(db, ..)
+            | Self::DeleteDB(db)
+            | Self::RestoreDB(db)
+            | Self::ListDBContents(db)
+            | Self::StreamDBContents(db)
+            | Self::ListDBKeys(db)
+            | Self::ListDBLists(db)
+            | Self::ReadPrefix(db, ..)
+            | Self::FindKeys(db, ..)
+            | Self::CompareAndSwap { db, .. }
+            | Self::Increment { db, .. }
+            | Self::WriteIfAbsent(db, ..)
+            | Self::Subscribe(db, ..)
+            | Self::RenameDB { from: db, .. }
+            | Self::CopyDB { source: db, .. }
+            | Self::ClearDB(db)
+            | Self::DBExists(db)
+            | Self::KeyExists(db, ..)
+            | Self::GetDBLength(db)
+            | Self::AddToList(db, ..)
+            | Self::ReadFromList(db, ..)
+            | Self::RemoveFromList(db, ..)
+            | Self::ClearList(db, ..)
+            | Self::GetListLength(db, ..)
+            | Self::StreamList(db, ..)
+            | Self::SetAdd(db, ..)
+            | Self::SetRemove(db, ..)
+            | Self::SetContains(db, ..)
+            | Self::SetMembers(db, ..)
+            | Self::SortedAdd(db, ..)
+            | Self::SortedRemove(db, ..)
+            | Self::SortedRank(db, ..)
+            | Self::SortedRangeByScore(db, ..)
+            | Self::SortedTop(db, ..)
+            | Self::QueuePush(db, ..)
+            | Self::QueuePop(db, ..)
+            | Self::WriteBytes(db, ..)
+            | Self::ReadBytes(db, ..)
+            | Self::BeginStreamWrite(db, ..)
+            | Self::BeginStreamRead(db, ..)
+            | Self::ReadMany(db, ..)
+            | Self::WriteMany(db, ..)
+            | Self::Transaction(db, ..)
+            | Self::ExportDB(db)
+            | Self::AddAdmin(db, ..)
+            | Self::AddUser(db, ..)
+            | Self::RemoveAdmin(db, ..)
+            | Self::RemoveUser(db, ..)
+            | Self::AddKeyAcl(db, ..)
+            | Self::RemoveKeyAcl(db, ..)
+            | Self::GetKeyAcls(db)
+            | Self::SetDBSchema(db, ..)
+            | Self::GetDBSchema(db)
+            | Self::SetDBMetadata(db, ..)
+            | Self::GetDBMetadata(db)
+            | Self::ListRevisions(db, ..)
+            | Self::ReadRevision(db, ..)
+            | Self::GetDBSettings(db)
+            | Self::ChangeDBSettings(db, ..)
+            | Self::GetRole(db)
+            | Self::GetEffectivePermissions(db)
+            | Self::GetStats(db)
+            | Self::GetHotKeys(db, ..)
+            | Self::ResetStats(db)
+            | Self::StreamReadDb(db)
+            | Self::StreamReadDbFiltered(db, ..)
+            | Self::OpenStream(db) => Some(db),
+            Self::PurgeTrash
+            | Self::ListDB
+            | Self::ListAccessibleDB
+            | Self::Publish(..)
+            | Self::SubscribeChannel(..)
+            | Self::StreamChunk(..)
+            | Self::EndStreamWrite
+            | Self::AbortStreamWrite
+            | Self::CreateSnapshot
+            | Self::ImportDB
+            | Self::ReplicaHandshake(..)
+            | Self::ServerInfo
+            | Self::Ping
+            | Self::SetKey(..)
+            | Self::ClaimSuperAdmin(..)
+            | Self::Login(..)
+            | Self::CreateUser(..)
+            | Self::SetUserDisabled(..)
+            | Self::ListUsers
+            | Self::SetIpFilter(..)
+            | Self::SetNamespace(..)
+            | Self::GetNamespace(..)
+            | Self::CreateSession
+            | Self::ResumeSession(..)
+            | Self::EndSession(..)
+            | Self::RotateServerKey
+            | Self::GetServerStats
+            | Self::ExportStats(..)
+            | Self::Encrypted(..)
+            | Self::SessionKey(..)
+            | Self::SetupEncryption
+            | Self::ReadStreamItem(..)
+            | Self::CloseStream(..)
+            | Self::ReadyForNextItem
+            | Self::EndStreamRead => None,
+        }
+    }
+
+    /// Creates a `DBExists` packet
+    /// When sent to the server, responds with true if a database with the given name exists.
+    pub fn new_db_exists(dbname: &str) -> Self {
+        Self::DBExists(DBPacketInfo::new(dbname))
+    }
+
+    /// Creates a `KeyExists` packet
+    /// When sent to the server, responds with true if the location in the database has a value, requires read permissions.
+    pub fn new_key_exists(dbname: &str, location: &str) -> Self {
+        Self::KeyExists(DBPacketInfo::new(dbname), DBLocation::new(location))
+    }
+
+    /// Creates a `GetDBLength` packet
+    /// When sent to the server, responds with the number of key value pairs in the database, requires list permissions.
+    pub fn new_get_db_length(dbname: &str) -> Self {
+        Self::GetDBLength(DBPacketInfo::new(dbname))
+    }
+
+    /// Creates an `AddToList` packet
+    /// When sent to the server, inserts the value into the list at the index, or at the end if there is no index.
+    pub fn new_add_to_list(
+        dbname: &str,
+        list_name: &str,
+        value: &str,
+        index: Option<usize>,
+    ) -> Self {
+        Self::AddToList(
+            DBPacketInfo::new(dbname),
+            DBLocation::new(list_name),
+            DBData::new(value.to_string()),
+            index,
+        )
+    }
+
+    /// Creates a `ReadFromList` packet
+    /// When sent to the server, responds with the value at the index of the list.
+    pub fn new_read_from_list(dbname: &str, list_name: &str, index: usize) -> Self {
+        Self::ReadFromList(DBPacketInfo::new(dbname), DBLocation::new(list_name), index)
+    }
+
+    /// Creates a `RemoveFromList` packet
+    /// When sent to the server, removes and responds with the value at the index of the list.
+    pub fn new_remove_from_list(dbname: &str, list_name: &str, index: usize) -> Self {
+        Self::RemoveFromList(DBPacketInfo::new(dbname), DBLocation::new(list_name), index)
+    }
+
+    /// Creates a `ClearList` packet
+    /// When sent to the server, removes every value from the list.
+    pub fn new_clear_list(dbname: &str, list_name: &str) -> Self {
+        Self::ClearList(DBPacketInfo::new(dbname), DBLocation::new(list_name))
+    }
+
+    /// Creates a `GetListLength` packet
+    /// When sent to the server, responds with the number of values in the list.
+    pub fn new_get_list_length(dbname: &str, list_name: &str) -> Self {
+        Self::GetListLength(DBPacketInfo::new(dbname), DBLocation::new(list_name))
+    }
+
+    /// Creates a `StreamList` packet
+    /// When sent to the server, begins streaming each value of the list to the client.
+    pub fn new_stream_list(dbname: &str, list_name: &str) -> Self {
+        Self::StreamList(DBPacketInfo::new(dbname), DBLocation::new(list_name))
+    }
+
+    /// Creates a `SetAdd` packet
+    /// When sent to the server, adds the member to the set, responding with true if it was not already a member.
+    pub fn new_set_add(dbname: &str, set_name: &str, member: &str) -> Self {
+        Self::SetAdd(
+            DBPacketInfo::new(dbname),
+            DBLocation::new(set_name),
+            DBData::new(member.to_string()),
+        )
+    }
+
+    /// Creates a `SetRemove` packet
+    /// When sent to the server, removes the member from the set, responding with true if it was a member.
+    pub fn new_set_remove(dbname: &str, set_name: &str, member: &str) -> Self {
+        Self::SetRemove(
+            DBPacketInfo::new(dbname),
+            DBLocation::new(set_name),
+            DBData::new(member.to_string()),
+        )
+    }
+
+    /// Creates a `SetContains` packet
+    /// When sent to the server, responds with true if the member is in the set.
+    pub fn new_set_contains(dbname: &str, set_name: &str, member: &str) -> Self {
+        Self::SetContains(
+            DBPacketInfo::new(dbname),
+            DBLocation::new(set_name),
+            DBData::new(member.to_string()),
+        )
+    }
+
+    /// Creates a `SetMembers` packet
+    /// When sent to the server, responds with every member of the set.
+    pub fn new_set_members(dbname: &str, set_name: &str) -> Self {
+        Self::SetMembers(DBPacketInfo::new(dbname), DBLocation::new(set_name))
+    }
+
+    /// Creates a `SortedAdd` packet
+    /// When sent to the server, adds the member to the sorted list with the given score, responding with its rank.
+    pub fn new_sorted_add(dbname: &str, list_name: &str, member: &str, score: f64) -> Self {
+        Self::SortedAdd(
+            DBPacketInfo::new(dbname),
+            DBLocation::new(list_name),
+            DBData::new(member.to_string()),
+            score,
+        )
+    }
+
+    /// Creates a `SortedRemove` packet
+    /// When sent to the server, removes the member from the sorted list, responding with true if it was a member.
+    pub fn new_sorted_remove(dbname: &str, list_name: &str, member: &str) -> Self {
+        Self::SortedRemove(
+            DBPacketInfo::new(dbname),
+            DBLocation::new(list_name),
+            DBData::new(member.to_string()),
+        )
+    }
+
+    /// Creates a `SortedRank` packet
+    /// When sent to the server, responds with the rank of the member in the sorted list.
+    pub fn new_sorted_rank(dbname: &str, list_name: &str, member: &str) -> Self {
+        Self::SortedRank(
+            DBPacketInfo::new(dbname),
+            DBLocation::new(list_name),
+            DBData::new(member.to_string()),
+        )
+    }
+
+    /// Creates a `SortedRangeByScore` packet
+    /// When sent to the server, responds with every member of the sorted list with a score between min and max inclusive.
+    pub fn new_sorted_range_by_score(dbname: &str, list_name: &str, min: f64, max: f64) -> Self {
+        Self::SortedRangeByScore(
+            DBPacketInfo::new(dbname),
+            DBLocation::new(list_name),
+            min,
+            max,
+        )
+    }
+
+    /// Creates a `SortedTop` packet
+    /// When sent to the server, responds with up to count of the highest scoring members of the sorted list.
+    pub fn new_sorted_top(dbname: &str, list_name: &str, count: usize) -> Self {
+        Self::SortedTop(DBPacketInfo::new(dbname), DBLocation::new(list_name), count)
+    }
+
+    /// Creates a `QueuePush` packet
+    /// When sent to the server, pushes the value onto the back of the queue.
+    pub fn new_queue_push(dbname: &str, queue_name: &str, value: &str) -> Self {
+        Self::QueuePush(
+            DBPacketInfo::new(dbname),
+            DBLocation::new(queue_name),
+            DBData::new(value.to_string()),
+        )
+    }
+
+    /// Creates a `QueuePop` packet
+    /// When sent to the server, removes and responds with the value at the front of the queue, waiting up to the timeout if the queue is empty.
+    pub fn new_queue_pop(dbname: &str, queue_name: &str, timeout: Option<Duration>) -> Self {
+        Self::QueuePop(
+            DBPacketInfo::new(dbname),
+            DBLocation::new(queue_name),
+            timeout,
+        )
+    }
+
+    /// Creates a `WriteBytes` packet
+    /// When sent to the server, writes the binary data to the location.
+    pub fn new_write_bytes(dbname: &str, location: &str, data: &[u8]) -> Self {
+        Self::WriteBytes(
+            DBPacketInfo::new(dbname),
+            DBLocation::new(location),
+            DBBytes::new(data.to_vec()),
+        )
+    }
+
+    /// Creates a `ReadBytes` packet
+    /// When sent to the server, responds with the binary data at the location encoded as base64.
+    pub fn new_read_bytes(dbname: &str, location: &str) -> Self {
+        Self::ReadBytes(DBPacketInfo::new(dbname), DBLocation::new(location))
+    }
+
+    /// Creates a `BeginStreamWrite` packet
+    /// When sent to the server, begins receiving a binary value to write to the location in chunks.
+    pub fn new_begin_stream_write(dbname: &str, location: &str) -> Self {
+        Self::BeginStreamWrite(DBPacketInfo::new(dbname), DBLocation::new(location))
+    }
+
+    /// Creates a `BeginStreamRead` packet
+    /// When sent to the server, begins sending the binary value at the location in chunks.
+    pub fn new_begin_stream_read(dbname: &str, location: &str) -> Self {
+        Self::BeginStreamRead(DBPacketInfo::new(dbname), DBLocation::new(location))
+    }
+
+    /// Serializes a `DBPacket` into a string to be sent over the internet, with a checksum so the server can detect it being truncated or modified.
     pub fn serialize_packet(&self) -> serde_json::Result<String> {
-        serde_json::to_string(&self)
+        serde_json::to_string(&self).map(|ser| add_checksum(&ser))
     }
 
     /// Deserialize a `DBPacket` from a buf.
-    pub fn deserialize_packet(buf: &[u8]) -> serde_json::Result<Self> {
-        serde_json::from_slice(buf)
+    /// Errors with `PacketCorrupted` if the packet does not match its checksum, or `BadPacket` if it could not be deserialized.
+    pub fn deserialize_packet(buf: &[u8]) -> Result<Self, DBPacketResponseError> {
+        let packet = verify_checksum(buf)?;
+        serde_json::from_slice(packet).map_err(|err| {
+            warn!("Unable to deserialize packet: {}", err);
+            DBPacketResponseError::BadPacket
+        })
     }
 }
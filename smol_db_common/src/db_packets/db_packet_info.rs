@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 
+/// Separates the namespace of a database from its name, such as `tenant/db_name`
+pub const NAMESPACE_SEPARATOR: char = '/';
+
+/// Replaces the namespace separator in the file name of a database, so every database is saved directly in the data directory
+const FILE_NAME_SEPARATOR: &str = "%2F";
+
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 /// A struct that describes the name of a database to be searched through.
 pub struct DBPacketInfo {
@@ -26,4 +32,38 @@ impl DBPacketInfo {
     pub fn get_db_name(&self) -> &str {
         &self.dbname
     }
+
+    /// Creates a `DBPacketInfo` for the database with the given name within the namespace, such as `tenant/db_name`
+    pub fn new_in_namespace(namespace: &str, dbname: &str) -> Self {
+        Self::new(&format!("{namespace}{NAMESPACE_SEPARATOR}{dbname}"))
+    }
+
+    /// Returns the namespace of the database, the part of its name before the first `/`, or `None` if it is not in a namespace
+    pub fn get_namespace(&self) -> Option<&str> {
+        self.dbname
+            .split_once(NAMESPACE_SEPARATOR)
+            .map(|(namespace, _)| namespace)
+    }
+
+    /// Returns true if the name can be given to a new database.
+    /// A name in a namespace must have a namespace and a name that are both non-empty, separated by a single `/`.
+    pub fn is_valid_name(&self) -> bool {
+        match self.dbname.split_once(NAMESPACE_SEPARATOR) {
+            None => !self.dbname.is_empty(),
+            Some((namespace, name)) => {
+                !namespace.is_empty() && !name.is_empty() && !name.contains(NAMESPACE_SEPARATOR)
+            }
+        }
+    }
+
+    /// Returns the name of the file the database is saved as, which can not contain the namespace separator
+    pub fn file_name(&self) -> String {
+        self.dbname
+            .replace(NAMESPACE_SEPARATOR, FILE_NAME_SEPARATOR)
+    }
+
+    /// Returns the `DBPacketInfo` of the database saved with the given file name, the reverse of `file_name`
+    pub fn from_file_name(file_name: &str) -> Self {
+        Self::new(&file_name.replace(FILE_NAME_SEPARATOR, &NAMESPACE_SEPARATOR.to_string()))
+    }
 }
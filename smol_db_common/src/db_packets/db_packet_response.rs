@@ -1,4 +1,5 @@
 #![allow(deprecated)]
+use crate::db_packets::response_data::ResponseData;
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 
@@ -14,20 +15,22 @@ pub enum DBPacketResponse<T> {
     Error(DBPacketResponseError),
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 /// Represents the various types of successful responses that accessing the database can be.
 pub enum DBSuccessResponse<T> {
     /// SuccessNoData represents when the operation was successful, but no response data was necessary to be replied back.
     SuccessNoData,
     /// SuccessReply represents when the operation was successful, and there is data to be replied back
     SuccessReply(T),
+    /// SuccessData represents when the operation was successful, and there is typed data to be replied back
+    SuccessData(ResponseData),
 }
 
 impl<T> From<DBSuccessResponse<T>> for Option<T> {
     #[tracing::instrument(skip_all)]
     fn from(value: DBSuccessResponse<T>) -> Self {
         match value {
-            DBSuccessResponse::SuccessNoData => None,
+            DBSuccessResponse::SuccessNoData | DBSuccessResponse::SuccessData(_) => None,
             DBSuccessResponse::SuccessReply(data) => Some(data),
         }
     }
@@ -38,7 +41,7 @@ impl<T> DBSuccessResponse<T> {
     #[tracing::instrument(skip_all)]
     pub fn into_option(self) -> Option<T> {
         match self {
-            Self::SuccessNoData => None,
+            Self::SuccessNoData | Self::SuccessData(_) => None,
             Self::SuccessReply(data) => Some(data),
         }
     }
@@ -46,7 +49,7 @@ impl<T> DBSuccessResponse<T> {
     #[tracing::instrument(skip_all)]
     pub fn as_option(&self) -> Option<&T> {
         match self {
-            Self::SuccessNoData => None,
+            Self::SuccessNoData | Self::SuccessData(_) => None,
             Self::SuccessReply(data) => Some(data),
         }
     }
@@ -54,10 +57,19 @@ impl<T> DBSuccessResponse<T> {
     #[tracing::instrument(skip_all)]
     pub fn as_option_mut(&mut self) -> Option<&mut T> {
         match self {
-            Self::SuccessNoData => None,
+            Self::SuccessNoData | Self::SuccessData(_) => None,
             Self::SuccessReply(data) => Some(data),
         }
     }
+
+    /// Returns the typed data of a `SuccessData` response
+    #[tracing::instrument(skip_all)]
+    pub fn into_data(self) -> Option<ResponseData> {
+        match self {
+            Self::SuccessData(data) => Some(data),
+            Self::SuccessNoData | Self::SuccessReply(_) => None,
+        }
+    }
 }
 
 impl<T> Display for DBSuccessResponse<T>
@@ -73,6 +85,9 @@ where
             Self::SuccessReply(reply) => {
                 write!(f, "SuccessReply: {}", reply)
             }
+            Self::SuccessData(data) => {
+                write!(f, "SuccessData: {:?}", data)
+            }
         }
     }
 }
@@ -101,7 +116,51 @@ where
 impl Display for DBPacketResponseError {
     #[tracing::instrument(skip_all)]
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            Self::BadPacket => write!(f, "the server could not handle the packet, this is a bug and should be reported"),
+            Self::DBNotFound => write!(f, "the database does not exist, check its name or create it first"),
+            Self::DBFileSystemError => write!(f, "the server could not read or write the file of the database, check the server logs"),
+            Self::ValueNotFound => write!(f, "no value is stored at the given key"),
+            Self::DBAlreadyExists => write!(f, "a database with that name already exists"),
+            Self::SerializationError => write!(f, "the server could not serialize its response"),
+            Self::DeserializationError => write!(f, "the server could not deserialize the request, the packet may be malformed or from an incompatible client version"),
+            Self::InvalidPermissions => write!(f, "the access key lacks the permissions for this operation"),
+            Self::UserNotFound => write!(f, "the user does not exist in the database"),
+            Self::StreamClosedUnexpectedly => write!(f, "the stream was closed before it finished, it can be started again"),
+            Self::InvalidKeyPattern => write!(f, "the key pattern is not valid"),
+            Self::ValueMismatch(Some(found)) => write!(f, "the value at the key was not the expected value, found {}", found),
+            Self::ValueMismatch(None) => write!(f, "the value at the key was not the expected value, the key has no value"),
+            Self::ValueNotInteger => write!(f, "the value at the key is not an integer"),
+            Self::IntegerOverflow => write!(f, "the operation would overflow the integer stored at the key"),
+            Self::ValueAlreadyExists => write!(f, "the key already has a value"),
+            Self::IndexOutOfBounds => write!(f, "the index is past the end of the list"),
+            Self::TransactionRolledBack { op_index, error } => write!(f, "the transaction was rolled back, operation {} failed: {}", op_index, error),
+            Self::TooManyConnections => write!(f, "the server has too many connections, try connecting again later"),
+            Self::RateLimited => write!(f, "too many requests were sent, wait before sending the request again"),
+            Self::ReplicationDisabled => write!(f, "the server does not keep a replication log, enable it in the server config"),
+            Self::ReadOnlyReplica => write!(f, "the server is a read only replica, send writes to the primary server"),
+            Self::QuotaExceeded => write!(f, "the write would exceed the quota of the database or server"),
+            Self::UserAlreadyExists => write!(f, "a user with that name already exists, or the name is empty"),
+            Self::PacketCorrupted => write!(f, "the packet was corrupted or truncated on the way, it can be sent again"),
+            Self::InvalidSchema => write!(f, "the schema is not a valid json schema"),
+            Self::SchemaViolation(reason) => write!(f, "the value does not match the schema of the database: {}", reason),
+            Self::KeyTooLong => write!(f, "the key is longer than the database allows"),
+            Self::ValueTooLarge => write!(f, "the value is larger than the database allows"),
+            Self::InvalidKeyCharacter(c) => write!(f, "the key contains the character {:?}, which the database does not allow", c),
+            Self::StreamNotFound => write!(f, "the stream is not open, it was never opened, or was already closed or read to its end"),
+            Self::TooManyStreams => write!(f, "too many streams are open, close one before opening another"),
+            Self::InvalidDBName => write!(f, "the database name is empty, or its namespace or name within the namespace is empty"),
+            Self::NamespaceFull => write!(f, "the namespace already holds as many databases as it allows"),
+        }
+    }
+}
+
+impl std::error::Error for DBPacketResponseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::TransactionRolledBack { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
     }
 }
 
@@ -128,6 +187,79 @@ pub enum DBPacketResponseError {
     UserNotFound,
 
     StreamClosedUnexpectedly,
+    /// A key pattern was given that could not be used, such as a regex that failed to compile
+    InvalidKeyPattern,
+    /// A conditional write did not happen because the value at the location was not the expected value, contains the value that was found instead
+    ValueMismatch(Option<String>),
+    /// A numeric operation was attempted on a value that could not be parsed as an integer
+    ValueNotInteger,
+    /// A numeric operation would have overflowed the integer stored at the location
+    IntegerOverflow,
+    /// A write that required the location to be empty did not happen because the location already had a value
+    ValueAlreadyExists,
+    /// An index was given that was past the end of the list
+    IndexOutOfBounds,
+    /// An operation within a transaction failed, so none of the transaction was applied, contains the index of the operation that failed and why it failed
+    TransactionRolledBack {
+        op_index: usize,
+        error: Box<DBPacketResponseError>,
+    },
+    /// The server or the clients ip address already has as many connections as it allows, the connection is closed after this response
+    TooManyConnections,
+    /// The client made too many requests in a short time, the request was not handled and can be sent again later
+    RateLimited,
+    /// A replica asked to replicate from a server that does not keep a replication log
+    ReplicationDisabled,
+    /// The server is a replica, which only serves requests that do not change a database
+    ReadOnlyReplica,
+    /// The write would take the database past the number of entries or bytes its quota, or the server, allows
+    QuotaExceeded,
+    /// A user account was attempted to be created with a username that is empty or already taken
+    UserAlreadyExists,
+    /// The packet did not match the checksum it was sent with, or was shorter than the length it was sent with, meaning it was truncated or modified on the way
+    PacketCorrupted,
+    /// A schema was given that is not a valid json schema
+    InvalidSchema,
+    /// The value written is not json matching the schema of the database, contains the reason it does not match
+    SchemaViolation(String),
+    /// The key written is longer than the key limits of the database allow
+    KeyTooLong,
+    /// The value written is larger than the key limits of the database allow
+    ValueTooLarge,
+    /// The key written contains a character the key limits of the database do not allow, contains the character
+    InvalidKeyCharacter(char),
+    /// The stream id is not one of the streams the client has open, it was never opened, or it was already closed or read to its end
+    StreamNotFound,
+    /// The client already has as many streams open as the server allows, one has to be closed before another is opened
+    TooManyStreams,
+    /// The database name is empty, or names a namespace without a database such as `tenant/`
+    InvalidDBName,
+    /// The namespace already holds as many databases as it allows
+    NamespaceFull,
+}
+
+impl DBPacketResponseError {
+    /// Returns true if the same request could succeed when sent again later, without changing it,
+    /// such as when the server was too busy, or the packet was corrupted on the way.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::TooManyConnections
+            | Self::RateLimited
+            | Self::PacketCorrupted
+            | Self::StreamClosedUnexpectedly => true,
+            Self::TransactionRolledBack { error, .. } => error.is_retryable(),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the request failed because the access key lacks the permissions for it
+    pub fn is_permission_denied(&self) -> bool {
+        match self {
+            Self::InvalidPermissions => true,
+            Self::TransactionRolledBack { error, .. } => error.is_permission_denied(),
+            _ => false,
+        }
+    }
 }
 
 #[allow(deprecated)]
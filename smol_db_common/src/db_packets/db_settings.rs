@@ -1,4 +1,10 @@
 //! Module containing a `DBSettings` struct, a struct that represents the various settings a database has.
+use crate::db_packets::db_settings_builder::{DBSettingsBuilder, DBSettingsError};
+use crate::db_packets::key_acl::KeyAclRule;
+use crate::db_packets::key_limits::KeyLimits;
+use crate::db_packets::permissions::Permissions;
+use crate::db_packets::storage_quota::StorageQuota;
+use crate::db_packets::value_schema::ValueSchema;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use tracing::info;
@@ -8,18 +14,52 @@ use tracing::info;
 pub struct DBSettings {
     /// The duration to wait before removing the given db from the cache.
     pub invalidation_time: Duration,
-    /// Triple of the permissions others have to (read,write,list)
-    pub can_others_rwx: (bool, bool, bool),
-    /// Triple of the permissions users have to (read,write,list)
-    pub can_users_rwx: (bool, bool, bool),
+    /// Permissions of keys that are neither admins nor users, read from `can_others_rwx` in dbs saved before permissions were split up
+    #[serde(alias = "can_others_rwx")]
+    pub others_permissions: Permissions,
+    /// Permissions of users, read from `can_users_rwx` in dbs saved before permissions were split up
+    #[serde(alias = "can_users_rwx")]
+    pub users_permissions: Permissions,
     /// Admin list of hashes
     pub admins: Vec<String>,
     /// User list of hashes
     pub users: Vec<String>,
+    /// Limits on how much data the database may hold, missing from dbs saved before quotas existed
+    #[serde(default)]
+    pub quota: StorageQuota,
+    /// When true, and the server is built with the `statistics` feature, the number of times each key is accessed is counted
+    /// Left out when false, so settings still fit within a single encrypted packet
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub track_key_hits: bool,
+    /// Rules limiting keys to the entries under a prefix, see `KeyAclRule`
+    /// Left out when empty, so settings still fit within a single encrypted packet
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub key_acls: Vec<KeyAclRule>,
+    /// Json schema every value written to the database must match, see `ValueSchema`
+    /// Left out when there is none, so settings still fit within a single encrypted packet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<ValueSchema>,
+    /// Limits on the keys and values that may be written to the database, see `KeyLimits`
+    /// Left out when unlimited, so settings still fit within a single encrypted packet
+    #[serde(default, skip_serializing_if = "KeyLimits::is_unlimited")]
+    pub key_limits: KeyLimits,
+    /// Number of overwritten or removed values kept for each key, see `RevisionHistory`, revisions are not kept when zero
+    /// Left out when zero, so settings still fit within a single encrypted packet
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub max_revisions: usize,
+    /// When true, the key value pairs are kept ordered by key, so listing them and scanning them by prefix returns them in order
+    /// Left out when false, so settings still fit within a single encrypted packet
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sorted_keys: bool,
+}
+
+const fn is_zero(value: &usize) -> bool {
+    *value == 0
 }
 
 impl DBSettings {
-    /// Returns a new `DBSettings` given a duration
+    /// Returns a new `DBSettings` given a duration, and the (read,write,list) permissions of others and users, see `Permissions::from_rwx`
+    /// Use `DBSettings::builder` to give permissions beyond read, write and list.
     pub const fn new(
         invalidation_time: Duration,
         can_others_rwx: (bool, bool, bool),
@@ -29,13 +69,131 @@ impl DBSettings {
     ) -> Self {
         Self {
             invalidation_time,
-            can_others_rwx,
-            can_users_rwx,
+            others_permissions: Permissions::from_rwx(
+                can_others_rwx.0,
+                can_others_rwx.1,
+                can_others_rwx.2,
+            ),
+            users_permissions: Permissions::from_rwx(
+                can_users_rwx.0,
+                can_users_rwx.1,
+                can_users_rwx.2,
+            ),
             admins,
             users,
+            quota: StorageQuota::new(None, None),
+            track_key_hits: false,
+            key_acls: vec![],
+            schema: None,
+            key_limits: KeyLimits::new(None, None, None),
+            max_revisions: 0,
+            sorted_keys: false,
         }
     }
 
+    /// Returns a builder used to create settings with named permissions, see `DBSettingsBuilder`
+    pub fn builder() -> DBSettingsBuilder {
+        DBSettingsBuilder::new()
+    }
+
+    /// Returns an error if the settings contradict themselves:
+    /// a zero invalidation time, others having a permission users do not have,
+    /// a key that is both an admin and a user, or a key acl rule on an admin.
+    pub fn validate(&self) -> Result<(), DBSettingsError> {
+        if self.invalidation_time.is_zero() {
+            return Err(DBSettingsError::ZeroInvalidationTime);
+        }
+        if !self.others_permissions.is_subset(&self.users_permissions) {
+            return Err(DBSettingsError::OthersExceedUsers);
+        }
+        if let Some(key) = self.admins.iter().find(|key| self.users.contains(key)) {
+            return Err(DBSettingsError::AdminIsUser(key.clone()));
+        }
+        if let Some(rule) = self
+            .key_acls
+            .iter()
+            .find(|rule| self.admins.contains(&rule.key))
+        {
+            return Err(DBSettingsError::KeyAclOnAdmin(rule.key.clone()));
+        }
+        Ok(())
+    }
+
+    /// Returns the settings with the given storage quota
+    pub const fn with_quota(mut self, quota: StorageQuota) -> Self {
+        self.quota = quota;
+        self
+    }
+
+    /// Returns the settings with counting of each key access turned on or off, the counts are returned by `GetHotKeys`
+    pub const fn with_key_hit_tracking(mut self, track_key_hits: bool) -> Self {
+        self.track_key_hits = track_key_hits;
+        self
+    }
+
+    /// Returns the settings with the given schema every written value must match
+    pub fn with_schema(mut self, schema: ValueSchema) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Returns the settings with the given limits on keys and values
+    pub fn with_key_limits(mut self, key_limits: KeyLimits) -> Self {
+        self.key_limits = key_limits;
+        self
+    }
+
+    /// Returns the settings keeping the given number of overwritten or removed values for each key, zero keeps none
+    pub const fn with_max_revisions(mut self, max_revisions: usize) -> Self {
+        self.max_revisions = max_revisions;
+        self
+    }
+
+    /// Returns the settings keeping the key value pairs ordered by key, or unordered
+    pub const fn with_sorted_keys(mut self, sorted_keys: bool) -> Self {
+        self.sorted_keys = sorted_keys;
+        self
+    }
+
+    /// Returns the settings with the given rule added, see `add_key_acl`
+    pub fn with_key_acl(mut self, rule: KeyAclRule) -> Self {
+        self.add_key_acl(rule);
+        self
+    }
+
+    /// Adds a rule limiting a key to the entries under a prefix, replacing the rule with the same key and prefix if there is one
+    #[tracing::instrument]
+    pub fn add_key_acl(&mut self, rule: KeyAclRule) {
+        info!("Adding key acl rule to db settings");
+        self.remove_key_acl(&rule.key, &rule.prefix);
+        self.key_acls.push(rule);
+    }
+
+    /// Removes the rule with the given key and prefix
+    /// Returns true if the rule was removed, false if there was no such rule
+    #[tracing::instrument]
+    pub fn remove_key_acl(&mut self, hash: &str, prefix: &str) -> bool {
+        let len_old = self.key_acls.len();
+        self.key_acls
+            .retain(|rule| rule.key.ne(hash) || rule.prefix.ne(prefix));
+        len_old > self.key_acls.len()
+    }
+
+    /// Returns every key acl rule of the database
+    pub fn get_key_acls(&self) -> &Vec<KeyAclRule> {
+        &self.key_acls
+    }
+
+    /// Returns the key acl rules that apply to the given key
+    pub fn get_key_acls_for<'a>(
+        &'a self,
+        client_key: &'a str,
+    ) -> impl Iterator<Item = &'a KeyAclRule> + 'a {
+        self.key_acls
+            .iter()
+            .filter(move |rule| rule.key == client_key)
+    }
+
     /// Get a list of the keys who are marked as admins of this database, admins have permission to change any piece of data in the database, and view all of it.
     pub fn get_admin_list(&self) -> &Vec<String> {
         &self.admins
@@ -95,29 +253,61 @@ impl DBSettings {
     }
 
     /// Returns the permissions of the database regarding the users
-    pub fn get_user_rwx(&self) -> (bool, bool, bool) {
-        self.can_users_rwx
+    pub fn get_user_permissions(&self) -> Permissions {
+        self.users_permissions
     }
 
     /// Returns the permissions of the database regarding the others
-    pub fn get_other_rwx(&self) -> (bool, bool, bool) {
-        self.can_others_rwx
+    pub fn get_other_permissions(&self) -> Permissions {
+        self.others_permissions
     }
 
     /// Returns the invalidation time duration
     pub fn get_invalidation_time(&self) -> Duration {
         self.invalidation_time
     }
+
+    /// Returns the limits on how much data the database may hold
+    pub fn get_quota(&self) -> StorageQuota {
+        self.quota
+    }
+
+    /// Returns the limits on the keys and values that may be written to the database
+    pub fn get_key_limits(&self) -> &KeyLimits {
+        &self.key_limits
+    }
+
+    /// Returns the number of overwritten or removed values kept for each key
+    pub fn get_max_revisions(&self) -> usize {
+        self.max_revisions
+    }
+
+    /// Returns true if the key value pairs are kept ordered by key
+    pub const fn has_sorted_keys(&self) -> bool {
+        self.sorted_keys
+    }
+
+    /// Returns the schema every value written to the database must match, if there is one
+    pub fn get_schema(&self) -> Option<&ValueSchema> {
+        self.schema.as_ref()
+    }
 }
 
 impl Default for DBSettings {
     fn default() -> Self {
         Self {
             invalidation_time: Duration::from_secs(30),
-            can_others_rwx: (false, false, false),
-            can_users_rwx: (true, true, true),
+            others_permissions: Permissions::none(),
+            users_permissions: Permissions::from_rwx(true, true, true),
             admins: vec![],
             users: vec![],
+            quota: StorageQuota::default(),
+            track_key_hits: false,
+            key_acls: vec![],
+            schema: None,
+            key_limits: KeyLimits::default(),
+            max_revisions: 0,
+            sorted_keys: false,
         }
     }
 }
@@ -0,0 +1,216 @@
+//! Module containing `DBSettingsBuilder`, a builder used to create `DBSettings` with named permissions, that rejects contradictory settings.
+use crate::db_packets::db_settings::DBSettings;
+use crate::db_packets::key_acl::KeyAclRule;
+use crate::db_packets::key_limits::KeyLimits;
+use crate::db_packets::permissions::Permission;
+use crate::db_packets::storage_quota::StorageQuota;
+use crate::db_packets::value_schema::ValueSchema;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Reasons `DBSettings` can be rejected as contradictory.
+pub enum DBSettingsError {
+    /// The invalidation time is zero, so the database would be removed from the cache as soon as it is loaded.
+    ZeroInvalidationTime,
+    /// Others are given a permission users do not have, users are meant to have at least the permissions of others.
+    OthersExceedUsers,
+    /// The key is both an admin and a user of the database.
+    AdminIsUser(String),
+    /// The key is an admin and has a key acl rule, admins are not limited by rules so the rule would never apply.
+    KeyAclOnAdmin(String),
+}
+
+impl Display for DBSettingsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DBSettingsError::ZeroInvalidationTime => {
+                write!(f, "invalidation time must be greater than zero")
+            }
+            DBSettingsError::OthersExceedUsers => {
+                write!(f, "others can not have a permission users do not have")
+            }
+            DBSettingsError::AdminIsUser(key) => {
+                write!(f, "key {} is both an admin and a user", key)
+            }
+            DBSettingsError::KeyAclOnAdmin(key) => {
+                write!(
+                    f,
+                    "key {} is an admin and can not be limited by a key acl rule",
+                    key
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// Builder used to create `DBSettings` without passing permissions as unnamed tuples.
+/// Starts from `DBSettings::default()`, and `build` rejects contradictory settings, see `DBSettings::validate`.
+/// ```
+/// use smol_db_common::db_packets::db_settings_builder::DBSettingsBuilder;
+/// use smol_db_common::db_packets::permissions::Permission;
+/// use std::time::Duration;
+///
+/// let settings = DBSettingsBuilder::new()
+///     .invalidation(Duration::from_secs(10))
+///     .others_can_read(true)
+///     .add_admin("admin_key")
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(settings.get_other_permissions().iter().collect::<Vec<_>>(), vec![Permission::Read]);
+/// assert!(settings.is_admin(&"admin_key".to_string()));
+///
+/// // users can list the database, but not delete from it
+/// let settings = DBSettingsBuilder::new()
+///     .users_can(Permission::Delete, false)
+///     .build()
+///     .unwrap();
+///
+/// assert!(settings.get_user_permissions().contains(Permission::List));
+/// assert!(!settings.get_user_permissions().contains(Permission::Delete));
+///
+/// // others may not have a permission users lack
+/// assert!(DBSettingsBuilder::new()
+///     .users_can_write(false)
+///     .others_can_write(true)
+///     .build()
+///     .is_err());
+/// ```
+pub struct DBSettingsBuilder {
+    settings: DBSettings,
+}
+
+impl DBSettingsBuilder {
+    /// Creates a new `DBSettingsBuilder` starting from the default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the duration to wait before removing the database from the cache
+    pub fn invalidation(mut self, invalidation_time: Duration) -> Self {
+        self.settings.invalidation_time = invalidation_time;
+        self
+    }
+
+    /// Sets if others have the given permission
+    pub fn others_can(mut self, permission: Permission, allowed: bool) -> Self {
+        self.settings.others_permissions.set(permission, allowed);
+        self
+    }
+
+    /// Sets if users have the given permission
+    pub fn users_can(mut self, permission: Permission, allowed: bool) -> Self {
+        self.settings.users_permissions.set(permission, allowed);
+        self
+    }
+
+    /// Sets if others can read from the database
+    pub fn others_can_read(mut self, can_read: bool) -> Self {
+        self.settings
+            .others_permissions
+            .set(Permission::Read, can_read);
+        self
+    }
+
+    /// Sets if others can write to the database
+    pub fn others_can_write(mut self, can_write: bool) -> Self {
+        self.settings
+            .others_permissions
+            .set(Permission::Write, can_write);
+        self
+    }
+
+    /// Sets if others can list the contents of the database
+    pub fn others_can_list(mut self, can_list: bool) -> Self {
+        self.settings
+            .others_permissions
+            .set(Permission::List, can_list);
+        self
+    }
+
+    /// Sets if users can read from the database
+    pub fn users_can_read(mut self, can_read: bool) -> Self {
+        self.settings
+            .users_permissions
+            .set(Permission::Read, can_read);
+        self
+    }
+
+    /// Sets if users can write to the database
+    pub fn users_can_write(mut self, can_write: bool) -> Self {
+        self.settings
+            .users_permissions
+            .set(Permission::Write, can_write);
+        self
+    }
+
+    /// Sets if users can list the contents of the database
+    pub fn users_can_list(mut self, can_list: bool) -> Self {
+        self.settings
+            .users_permissions
+            .set(Permission::List, can_list);
+        self
+    }
+
+    /// Adds the key as an admin of the database
+    pub fn add_admin(mut self, key: &str) -> Self {
+        self.settings.admins.push(key.to_string());
+        self
+    }
+
+    /// Adds the key as a user of the database
+    pub fn add_user(mut self, key: &str) -> Self {
+        self.settings.users.push(key.to_string());
+        self
+    }
+
+    /// Sets the limits on how much data the database may hold
+    pub fn quota(mut self, quota: StorageQuota) -> Self {
+        self.settings.quota = quota;
+        self
+    }
+
+    /// Sets if the number of times each key is accessed is counted
+    pub fn track_key_hits(mut self, track_key_hits: bool) -> Self {
+        self.settings.track_key_hits = track_key_hits;
+        self
+    }
+
+    /// Adds a rule limiting a key to the entries under a prefix, replacing the rule with the same key and prefix if there is one
+    pub fn key_acl(mut self, rule: KeyAclRule) -> Self {
+        self.settings.add_key_acl(rule);
+        self
+    }
+
+    /// Sets the limits on the keys and values that may be written to the database
+    pub fn key_limits(mut self, key_limits: KeyLimits) -> Self {
+        self.settings.key_limits = key_limits;
+        self
+    }
+
+    /// Sets the number of overwritten or removed values kept for each key, zero keeps none
+    pub fn max_revisions(mut self, max_revisions: usize) -> Self {
+        self.settings.max_revisions = max_revisions;
+        self
+    }
+
+    /// Sets whether the key value pairs are kept ordered by key
+    pub fn sorted_keys(mut self, sorted_keys: bool) -> Self {
+        self.settings.sorted_keys = sorted_keys;
+        self
+    }
+
+    /// Sets the json schema every value written to the database must match
+    pub fn schema(mut self, schema: ValueSchema) -> Self {
+        self.settings.schema = Some(schema);
+        self
+    }
+
+    /// Returns the settings that were built, or the reason they are contradictory
+    pub fn build(self) -> Result<DBSettings, DBSettingsError> {
+        self.settings.validate()?;
+        Ok(self.settings)
+    }
+}
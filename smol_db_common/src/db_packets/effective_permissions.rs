@@ -0,0 +1,41 @@
+//! Module containing `EffectivePermissions`, what a key is allowed to do in a database once its role, the settings and key acl rules are applied.
+use crate::db::Role;
+use crate::db_packets::key_acl::KeyAclRule;
+use crate::db_packets::permissions::{Permission, Permissions};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+/// The permissions a key resolves to in a database, so a client can tell which actions it is allowed to take without trying them.
+pub struct EffectivePermissions {
+    /// Role of the key in the database
+    pub role: Role,
+    /// Permissions the key has on the whole database, admins and super admins have every permission.
+    /// Stream is only included along with read, as streaming requires both
+    pub permissions: Permissions,
+    /// Rules limiting the key to the entries under their prefixes, a key with rules has no db wide permissions
+    pub key_acls: Vec<KeyAclRule>,
+}
+
+impl EffectivePermissions {
+    /// Returns true if the key has the permission on the whole database
+    pub const fn can(&self, permission: Permission) -> bool {
+        self.permissions.contains(permission)
+    }
+
+    /// Returns true if the key can read the entry with the given key, through its db wide permissions or a key acl rule
+    pub fn can_read_key(&self, entry_key: &str) -> bool {
+        self.can(Permission::Read) || self.key_acls_allow(entry_key, |rule| rule.read)
+    }
+
+    /// Returns true if the key can write the entry with the given key, through its db wide permissions or a key acl rule
+    pub fn can_write_key(&self, entry_key: &str) -> bool {
+        self.can(Permission::Write) || self.key_acls_allow(entry_key, |rule| rule.write)
+    }
+
+    /// Returns true if any key acl rule for the entry key allows the action
+    fn key_acls_allow(&self, entry_key: &str, allows: impl Fn(&KeyAclRule) -> bool) -> bool {
+        self.key_acls
+            .iter()
+            .any(|rule| allows(rule) && rule.matches(entry_key))
+    }
+}
@@ -0,0 +1,29 @@
+//! Module containing `IpFilter`, the CIDR allow and deny lists a server checks before accepting a connection.
+pub use ipnet::IpNet;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+/// Lists of CIDR blocks a server accepts or rejects connections from.
+/// An address in any deny block is rejected, otherwise it is accepted if the allow list is empty or it is in any allow block.
+pub struct IpFilter {
+    /// Blocks connections are accepted from, an empty list accepts any address that is not denied
+    pub allow: Vec<IpNet>,
+    /// Blocks connections are always rejected from
+    pub deny: Vec<IpNet>,
+}
+
+impl IpFilter {
+    /// Returns a new `IpFilter` with the given allow and deny lists
+    pub const fn new(allow: Vec<IpNet>, deny: Vec<IpNet>) -> Self {
+        Self { allow, deny }
+    }
+
+    /// Returns true if a connection from the given address is accepted
+    pub fn is_allowed(&self, ip_address: IpAddr) -> bool {
+        // ipv4 clients connecting to an ipv6 socket are seen as ipv4 mapped ipv6 addresses
+        let ip_address = ip_address.to_canonical();
+        !self.deny.iter().any(|block| block.contains(&ip_address))
+            && (self.allow.is_empty() || self.allow.iter().any(|block| block.contains(&ip_address)))
+    }
+}
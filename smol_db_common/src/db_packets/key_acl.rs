@@ -0,0 +1,34 @@
+//! Module containing `KeyAclRule`, a rule limiting a key to the entries of a database under a prefix.
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+/// A rule giving a key read and or write access to the entries of a database whose key starts with the prefix.
+/// A key with any rules in a database is limited to the entries its rules allow, and loses the db wide permissions it has as a user or other.
+/// Admins and super admins are not limited by rules.
+pub struct KeyAclRule {
+    /// Hash of the key the rule applies to
+    pub key: String,
+    /// Prefix of the entry keys the rule applies to, an empty prefix applies to every entry
+    pub prefix: String,
+    /// If the key can read entries under the prefix
+    pub read: bool,
+    /// If the key can write and delete entries under the prefix
+    pub write: bool,
+}
+
+impl KeyAclRule {
+    /// Returns a new rule for the given key hash and prefix
+    pub fn new(key: &str, prefix: &str, read: bool, write: bool) -> Self {
+        Self {
+            key: key.to_string(),
+            prefix: prefix.to_string(),
+            read,
+            write,
+        }
+    }
+
+    /// Returns true if the rule applies to the given entry key
+    pub fn matches(&self, entry_key: &str) -> bool {
+        entry_key.starts_with(&self.prefix)
+    }
+}
@@ -0,0 +1,85 @@
+//! Module containing `KeyLimits`, the limits on the keys and values that may be written to a database.
+use crate::db_packets::db_packet_response::DBPacketResponseError;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+/// The characters keys of a database may be made of.
+pub enum KeyCharset {
+    /// Ascii letters and digits, and `_`, `-`, `.`, `:` and `/`
+    Alphanumeric,
+    /// Printable ascii characters, not including spaces
+    PrintableAscii,
+    /// Only the characters in the string
+    Custom(String),
+}
+
+impl KeyCharset {
+    /// Returns true if the character is allowed in a key
+    pub fn allows(&self, character: char) -> bool {
+        match self {
+            KeyCharset::Alphanumeric => {
+                character.is_ascii_alphanumeric() || "_-.:/".contains(character)
+            }
+            KeyCharset::PrintableAscii => character.is_ascii_graphic(),
+            KeyCharset::Custom(allowed) => allowed.contains(character),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+/// Limits on the keys and values written to a database, a limit of `None` is unlimited.
+/// Writes of keys or values past the limits are rejected with `KeyTooLong`, `ValueTooLarge` or `InvalidKeyCharacter`, so one client can not insert keys that break tooling reading the database.
+/// Keys and values already in the database are not checked when the limits change.
+pub struct KeyLimits {
+    /// Longest key in bytes that may be written, including the names of lists, sets and sorted lists
+    pub max_key_length: Option<usize>,
+    /// Largest value in bytes that may be written, including members of lists, sets and sorted lists, and binary values
+    pub max_value_size: Option<usize>,
+    /// Characters keys may be made of
+    pub key_charset: Option<KeyCharset>,
+}
+
+impl KeyLimits {
+    /// Returns new `KeyLimits` with the given limits
+    pub const fn new(
+        max_key_length: Option<usize>,
+        max_value_size: Option<usize>,
+        key_charset: Option<KeyCharset>,
+    ) -> Self {
+        Self {
+            max_key_length,
+            max_value_size,
+            key_charset,
+        }
+    }
+
+    /// Returns true if nothing is limited.
+    pub fn is_unlimited(&self) -> bool {
+        self.max_key_length.is_none() && self.max_value_size.is_none() && self.key_charset.is_none()
+    }
+
+    /// Returns an error if the key, or a value of the given size in bytes, is past the limits.
+    pub fn check(&self, key: &str, value_size: usize) -> Result<(), DBPacketResponseError> {
+        if self
+            .max_key_length
+            .is_some_and(|max_key_length| key.len() > max_key_length)
+        {
+            return Err(DBPacketResponseError::KeyTooLong);
+        }
+
+        if self
+            .max_value_size
+            .is_some_and(|max_value_size| value_size > max_value_size)
+        {
+            return Err(DBPacketResponseError::ValueTooLarge);
+        }
+
+        if let Some(charset) = &self.key_charset {
+            if let Some(character) = key.chars().find(|character| !charset.allows(*character)) {
+                return Err(DBPacketResponseError::InvalidKeyCharacter(character));
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+//! Module containing `KeyPattern`, a pattern used to filter the keys of a database on the server.
+use crate::db_packets::db_packet_response::DBPacketResponseError;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+/// A pattern that keys within a database can be matched against.
+pub enum KeyPattern {
+    /// A glob pattern, `*` matches any number of characters, and `?` matches exactly one character.
+    Glob(String),
+    /// A regular expression, the key matches if the expression matches anywhere within the key.
+    Regex(String),
+}
+
+/// A `KeyPattern` that has been prepared to match keys against, so a regex only has to be compiled once.
+enum KeyMatcher<'a> {
+    Glob(&'a str),
+    Regex(Regex),
+}
+
+impl KeyMatcher<'_> {
+    fn is_match(&self, key: &str) -> bool {
+        match self {
+            KeyMatcher::Glob(pattern) => glob_match(pattern, key),
+            KeyMatcher::Regex(regex) => regex.is_match(key),
+        }
+    }
+}
+
+impl KeyPattern {
+    /// Creates a new glob `KeyPattern`
+    pub fn new_glob(pattern: &str) -> Self {
+        Self::Glob(pattern.to_string())
+    }
+
+    /// Creates a new regex `KeyPattern`
+    pub fn new_regex(pattern: &str) -> Self {
+        Self::Regex(pattern.to_string())
+    }
+
+    /// Returns true if the given key matches this pattern.
+    /// Returns `InvalidKeyPattern` if the pattern is a regex that could not be compiled.
+    pub fn is_match(&self, key: &str) -> Result<bool, DBPacketResponseError> {
+        Ok(self.matcher()?.is_match(key))
+    }
+
+    /// Returns every key from the given keys that matches this pattern.
+    /// Returns `InvalidKeyPattern` if the pattern is a regex that could not be compiled.
+    pub fn filter_keys<'a>(
+        &self,
+        keys: impl IntoIterator<Item = &'a String>,
+    ) -> Result<Vec<String>, DBPacketResponseError> {
+        let matcher = self.matcher()?;
+        Ok(keys
+            .into_iter()
+            .filter(|key| matcher.is_match(key))
+            .cloned()
+            .collect())
+    }
+
+    fn matcher(&self) -> Result<KeyMatcher<'_>, DBPacketResponseError> {
+        match self {
+            Self::Glob(pattern) => Ok(KeyMatcher::Glob(pattern)),
+            Self::Regex(pattern) => Regex::new(pattern).map(KeyMatcher::Regex).map_err(|err| {
+                error!("Unable to compile key pattern regex: {}", err);
+                DBPacketResponseError::InvalidKeyPattern
+            }),
+        }
+    }
+}
+
+/// Matches the entire key against a glob pattern supporting `*` and `?`
+fn glob_match(pattern: &str, key: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let key: Vec<char> = key.chars().collect();
+
+    let (mut pattern_index, mut key_index) = (0, 0);
+    // position of the last `*` seen in the pattern, and the key index it was matched at, used for backtracking
+    let mut last_star: Option<(usize, usize)> = None;
+
+    while key_index < key.len() {
+        match pattern.get(pattern_index) {
+            Some('*') => {
+                last_star = Some((pattern_index, key_index));
+                pattern_index += 1;
+            }
+            Some('?') => {
+                pattern_index += 1;
+                key_index += 1;
+            }
+            Some(c) if *c == key[key_index] => {
+                pattern_index += 1;
+                key_index += 1;
+            }
+            _ => match last_star {
+                Some((star_index, star_key_index)) => {
+                    // let the last star consume one more character and try again
+                    pattern_index = star_index + 1;
+                    key_index = star_key_index + 1;
+                    last_star = Some((star_index, key_index));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[pattern_index..].iter().all(|c| *c == '*')
+}
@@ -1,6 +1,24 @@
 //! Sub-module that contains the modules for all the various packet types and implementations.
+pub mod change_event;
 pub mod db_location;
+pub mod db_metadata;
 pub mod db_packet;
 pub mod db_packet_info;
 pub mod db_packet_response;
 pub mod db_settings;
+pub mod db_settings_builder;
+pub mod effective_permissions;
+pub mod ip_filter;
+pub mod key_acl;
+pub mod key_limits;
+pub mod key_pattern;
+pub mod namespace;
+pub mod packet_checksum;
+pub mod permissions;
+pub mod response_data;
+pub mod server_info;
+pub mod stats_format;
+pub mod storage_quota;
+pub mod stream_item;
+pub mod transaction;
+pub mod value_schema;
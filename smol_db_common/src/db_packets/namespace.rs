@@ -0,0 +1,32 @@
+//! Module containing `NamespaceSettings`, the super admins and limits of a namespace of databases.
+use crate::db_packets::storage_quota::StorageQuota;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+/// Settings of a namespace, the databases named `namespace/name`, so a server can host the databases of several teams.
+/// The super admins of a namespace have the privileges of a super admin, but only on the databases in the namespace,
+/// so they can create and delete them, but can not see or change the databases of other namespaces.
+/// Only super admins of the server can change the settings of a namespace.
+pub struct NamespaceSettings {
+    /// Keys that are super admins of every database in the namespace, hashed by the server
+    pub super_admins: Vec<String>,
+    /// Limits each database in the namespace, on top of the quota of the server and of the database itself
+    pub quota: StorageQuota,
+    /// Most databases the namespace may hold, `None` allows any number
+    pub max_databases: Option<usize>,
+}
+
+impl NamespaceSettings {
+    /// Returns settings for a namespace with the given super admins, and no limits
+    pub fn new(super_admins: Vec<String>) -> Self {
+        Self {
+            super_admins,
+            ..Default::default()
+        }
+    }
+
+    /// Returns true if the hash of a key is a super admin of the namespace
+    pub fn is_super_admin(&self, hash: &String) -> bool {
+        self.super_admins.contains(hash)
+    }
+}
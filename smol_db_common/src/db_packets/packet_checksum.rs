@@ -0,0 +1,110 @@
+//! Contains the checksum packets and responses are sent with, so a packet that was truncated or modified on the way is detected before it is deserialized.
+use crate::db_packets::db_packet_response::DBPacketResponseError::PacketCorrupted;
+use crate::db_packets::db_packet_response::{DBPacketResponseError, DBSuccessResponse};
+
+/// Start of a packet sent with a checksum, followed by the crc32 checksum of the rest of the packet in hex, a `:`, the length of the rest of the packet, and a space.
+/// Every packet and response is sent with it, data without it is rejected as `PacketCorrupted`.
+pub const PACKET_CHECKSUM_HEADER: &str = "crc32:";
+
+/// Largest packet, not counting its header, that is read from a connection.
+/// A header giving a larger length is rejected before the rest of the packet is read, so a peer can not make the reader allocate without bound.
+pub const MAX_PACKET_SIZE: usize = 64 * 1024 * 1024;
+
+/// Longest a header can be, the prefix, 8 hex digits of checksum, a `:`, the digits of the largest length, and a space.
+const MAX_HEADER_LEN: usize = PACKET_CHECKSUM_HEADER.len() + 8 + 1 + 20 + 1;
+
+/// Returns the serialized packet with its checksum and length prepended.
+pub fn add_checksum(packet: &str) -> String {
+    format!("{}{}", checksum_header(packet.as_bytes()), packet)
+}
+
+/// Returns the data with its checksum and length prepended, used for encrypted responses, which are not valid utf8.
+pub fn add_checksum_bytes(data: &[u8]) -> Vec<u8> {
+    let mut packet = checksum_header(data).into_bytes();
+    packet.extend_from_slice(data);
+    packet
+}
+
+/// Returns the header sent before the data, holding its checksum and length
+fn checksum_header(data: &[u8]) -> String {
+    format!(
+        "{}{:08x}:{} ",
+        PACKET_CHECKSUM_HEADER,
+        crc32fast::hash(data),
+        data.len()
+    )
+}
+
+/// Splits the header from the packet, returning the checksum, the length, and the rest of the packet.
+/// Returns None if the header is not complete.
+fn split_header(data: &[u8]) -> Option<(u32, usize, &[u8])> {
+    let rest = data.strip_prefix(PACKET_CHECKSUM_HEADER.as_bytes())?;
+    let space = rest.iter().position(|byte| *byte == b' ')?;
+    let (checksum, len) = std::str::from_utf8(&rest[..space]).ok()?.split_once(':')?;
+    Some((
+        u32::from_str_radix(checksum, 16).ok()?,
+        len.parse().ok()?,
+        &rest[space + 1..],
+    ))
+}
+
+/// Returns true if the data read so far is the start of a packet, that is shorter than the length it was sent with.
+/// Used to know if more of a packet needs to be read before it is complete.
+/// Fails with `PacketCorrupted` as soon as the data can no longer become a packet: it does not start with a header,
+/// the header is malformed or gives a length larger than `MAX_PACKET_SIZE`, or more data was read than the length it gives.
+pub fn is_incomplete(data: &[u8]) -> Result<bool, DBPacketResponseError> {
+    let prefix = PACKET_CHECKSUM_HEADER.as_bytes();
+    if data.len() < prefix.len() {
+        return if prefix.starts_with(data) {
+            Ok(true)
+        } else {
+            Err(PacketCorrupted)
+        };
+    }
+    if !data.starts_with(prefix) {
+        return Err(PacketCorrupted);
+    }
+    if !data.iter().take(MAX_HEADER_LEN).any(|byte| *byte == b' ') {
+        return if data.len() < MAX_HEADER_LEN {
+            Ok(true)
+        } else {
+            Err(PacketCorrupted)
+        };
+    }
+    match split_header(data) {
+        Some((_, len, packet)) if len <= MAX_PACKET_SIZE && packet.len() <= len => {
+            Ok(packet.len() < len)
+        }
+        _ => Err(PacketCorrupted),
+    }
+}
+
+/// Returns the packet without its checksum, or `PacketCorrupted` if it does not match its checksum or length, or was sent without one.
+pub fn verify_checksum(data: &[u8]) -> Result<&[u8], DBPacketResponseError> {
+    match split_header(data) {
+        Some((checksum, len, packet))
+            if packet.len() == len && crc32fast::hash(packet) == checksum =>
+        {
+            Ok(packet)
+        }
+        _ => Err(PacketCorrupted),
+    }
+}
+
+/// Serializes the response with a checksum, the form every response is sent to a client in.
+pub fn serialize_response(
+    response: &Result<DBSuccessResponse<String>, DBPacketResponseError>,
+) -> serde_json::Result<String> {
+    serde_json::to_string(response).map(|ser| add_checksum(&ser))
+}
+
+/// Deserializes a response sent by `serialize_response`.
+/// A response that does not match its checksum is deserialized as a `PacketCorrupted` error.
+pub fn deserialize_response(
+    data: &[u8],
+) -> serde_json::Result<Result<DBSuccessResponse<String>, DBPacketResponseError>> {
+    match verify_checksum(data) {
+        Ok(response) => serde_json::from_slice(response),
+        Err(err) => Ok(Err(err)),
+    }
+}
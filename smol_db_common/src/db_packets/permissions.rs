@@ -0,0 +1,156 @@
+//! Module containing `Permission` and `Permissions`, the actions users and others of a database are allowed to take.
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// A single action a role can be allowed to take on a database.
+pub enum Permission {
+    /// Read values from the database
+    Read,
+    /// Write values to the database, and change lists, sets and sorted lists within it
+    Write,
+    /// List the contents and keys of the database
+    List,
+    /// Delete values from the database
+    Delete,
+    /// Add and remove users of the database
+    ManageUsers,
+    /// Get and replace the settings of the database
+    ManageSettings,
+    /// Stream the database, its lists and binary values, and subscribe to its changes, also requires `Read`
+    Stream,
+}
+
+impl Permission {
+    /// Every permission, in the order they are listed in
+    pub const ALL: [Permission; 7] = [
+        Permission::Read,
+        Permission::Write,
+        Permission::List,
+        Permission::Delete,
+        Permission::ManageUsers,
+        Permission::ManageSettings,
+        Permission::Stream,
+    ];
+
+    const fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(from = "PermissionsRepr", into = "Vec<Permission>")]
+/// The set of permissions a role has on a database.
+/// Serialized as a list of permissions, and deserialized from either a list,
+/// or the `(read, write, list)` triple databases were saved with before permissions were split up, see `Permissions::from_rwx`.
+pub struct Permissions {
+    bits: u8,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+/// Forms `Permissions` can be deserialized from
+enum PermissionsRepr {
+    List(Vec<Permission>),
+    Rwx(bool, bool, bool),
+}
+
+impl From<PermissionsRepr> for Permissions {
+    fn from(repr: PermissionsRepr) -> Self {
+        match repr {
+            PermissionsRepr::List(list) => list.into_iter().collect(),
+            PermissionsRepr::Rwx(read, write, list) => Self::from_rwx(read, write, list),
+        }
+    }
+}
+
+impl From<Permissions> for Vec<Permission> {
+    fn from(permissions: Permissions) -> Self {
+        permissions.iter().collect()
+    }
+}
+
+impl FromIterator<Permission> for Permissions {
+    fn from_iter<T: IntoIterator<Item = Permission>>(iter: T) -> Self {
+        iter.into_iter()
+            .fold(Self::none(), |permissions, permission| {
+                permissions.with(permission)
+            })
+    }
+}
+
+impl Debug for Permissions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl Permissions {
+    /// Returns a set with no permissions
+    pub const fn none() -> Self {
+        Self { bits: 0 }
+    }
+
+    /// Returns a set with every permission
+    pub const fn all() -> Self {
+        Self {
+            bits: (1 << Permission::ALL.len()) - 1,
+        }
+    }
+
+    /// Returns the permissions equivalent to a `(read, write, list)` triple,
+    /// read also allows streaming, and write also allows deleting, as they did before permissions were split up.
+    pub const fn from_rwx(read: bool, write: bool, list: bool) -> Self {
+        let mut permissions = Self::none();
+        if read {
+            permissions = permissions.with(Permission::Read).with(Permission::Stream);
+        }
+        if write {
+            permissions = permissions.with(Permission::Write).with(Permission::Delete);
+        }
+        if list {
+            permissions = permissions.with(Permission::List);
+        }
+        permissions
+    }
+
+    /// Returns the set with the permission added
+    pub const fn with(self, permission: Permission) -> Self {
+        Self {
+            bits: self.bits | permission.bit(),
+        }
+    }
+
+    /// Returns the set with the permission removed
+    pub const fn without(self, permission: Permission) -> Self {
+        Self {
+            bits: self.bits & !permission.bit(),
+        }
+    }
+
+    /// Adds or removes the permission from the set
+    pub fn set(&mut self, permission: Permission, allowed: bool) {
+        *self = if allowed {
+            self.with(permission)
+        } else {
+            self.without(permission)
+        };
+    }
+
+    /// Returns true if the set contains the permission
+    pub const fn contains(&self, permission: Permission) -> bool {
+        self.bits & permission.bit() != 0
+    }
+
+    /// Returns true if every permission in this set is also in the other set
+    pub const fn is_subset(&self, other: &Permissions) -> bool {
+        self.bits & !other.bits == 0
+    }
+
+    /// Returns an iterator over the permissions in the set
+    pub fn iter(&self) -> impl Iterator<Item = Permission> + '_ {
+        Permission::ALL
+            .into_iter()
+            .filter(|permission| self.contains(*permission))
+    }
+}
@@ -0,0 +1,28 @@
+//! Contains `ResponseData`, the typed data a response can carry instead of a serialized string.
+use crate::db::Role;
+use crate::db_packets::db_metadata::DBListing;
+use crate::db_packets::db_settings::DBSettings;
+use crate::db_packets::effective_permissions::EffectivePermissions;
+#[cfg(feature = "statistics")]
+use crate::statistics::{DBStatistics, ServerStatistics};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// Data sent in a `SuccessData` response, serialized as part of the response itself,
+/// so it is not serialized to a string first and then again as the string of a `SuccessReply`.
+pub enum ResponseData {
+    /// The role of the client in a database, sent in response to `GetRole`
+    Role(Role),
+    /// The role and permissions of the client in a database, sent in response to `GetEffectivePermissions`
+    EffectivePermissions(EffectivePermissions),
+    /// The settings of a database, sent in response to `GetDBSettings`
+    Settings(Box<DBSettings>),
+    /// The databases on the server with their metadata, sent in response to `ListDB`
+    DBList(Vec<DBListing>),
+    /// The statistics of a database, sent in response to `GetStats`
+    #[cfg(feature = "statistics")]
+    Stats(DBStatistics),
+    /// The statistics of the server, sent in response to `GetServerStats`
+    #[cfg(feature = "statistics")]
+    ServerStats(ServerStatistics),
+}
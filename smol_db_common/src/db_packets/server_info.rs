@@ -0,0 +1,20 @@
+//! Module containing `ServerInfo`, the state of a server as reported by the `ServerInfo` packet.
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+/// The state of a server, for monitoring tools to display.
+pub struct ServerInfo {
+    /// Version of the server software
+    pub version: String,
+    /// Time since the server started
+    pub uptime: Duration,
+    /// Features the server was built with, such as `statistics`
+    pub features: Vec<String>,
+    /// Number of databases on the server
+    pub db_count: usize,
+    /// Number of databases held in memory, the rest are loaded from the data directory when they are next used
+    pub cached_db_count: usize,
+    /// Estimate of the bytes of keys and values held in memory by the cached databases
+    pub memory_estimate: usize,
+}
@@ -0,0 +1,12 @@
+//! Module containing `StatsFormat`, the formats statistics can be exported in by the `ExportStats` packet.
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+/// The format statistics are exported in.
+pub enum StatsFormat {
+    /// A json object holding the server statistics, and the statistics of each database keyed by name
+    #[default]
+    Json,
+    /// Csv with a `database,metric,key,value` header row, the database is empty for server wide metrics
+    Csv,
+}
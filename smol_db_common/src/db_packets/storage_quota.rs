@@ -0,0 +1,39 @@
+//! Module containing `StorageQuota`, the limits on how much data a database may hold.
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+/// Limits on how much data a database may hold, a limit of `None` is unlimited.
+/// Writes that would take a database past its quota are rejected with `QuotaExceeded`, writes that do not grow the database are always allowed.
+pub struct StorageQuota {
+    /// Most entries the database may hold, each key value pair, list, set, sorted list and binary value is one entry
+    pub max_entries: Option<usize>,
+    /// Most bytes the keys and values of the database may take up
+    pub max_bytes: Option<usize>,
+}
+
+impl StorageQuota {
+    /// Returns a new `StorageQuota` with the given limits
+    pub const fn new(max_entries: Option<usize>, max_bytes: Option<usize>) -> Self {
+        Self {
+            max_entries,
+            max_bytes,
+        }
+    }
+
+    /// Returns true if neither the entries nor the bytes are limited.
+    pub const fn is_unlimited(&self) -> bool {
+        self.max_entries.is_none() && self.max_bytes.is_none()
+    }
+
+    /// Returns a quota with the stricter of each limit of the two quotas.
+    pub fn min(self, other: Self) -> Self {
+        let stricter = |a: Option<usize>, b: Option<usize>| match (a, b) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        Self {
+            max_entries: stricter(self.max_entries, other.max_entries),
+            max_bytes: stricter(self.max_bytes, other.max_bytes),
+        }
+    }
+}
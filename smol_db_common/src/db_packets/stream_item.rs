@@ -0,0 +1,27 @@
+//! Contains `StreamItem`, a single message sent to a client while a table is streamed to it.
+use crate::db_packets::db_packet_response::DBPacketResponseError;
+use crate::db_packets::packet_checksum::{add_checksum, verify_checksum};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+/// A message sent in reply to each `ReadyForNextItem` while a table is streamed.
+/// Each message is sent with its checksum and length prepended, so a key or value of any length is read as a whole, and never together with the next message.
+pub enum StreamItem {
+    /// A key and its value.
+    Item(String, String),
+    /// Sent instead of an item once every item has been sent, after which the client can send packets normally again.
+    StreamEnd,
+}
+
+impl StreamItem {
+    /// Serializes the item with its checksum and length prepended, the form it is sent to a client in.
+    pub fn serialize_item(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self).map(|ser| add_checksum(&ser))
+    }
+
+    /// Deserializes an item sent by `serialize_item`.
+    /// Returns `PacketCorrupted` if it does not match its checksum or length, and `BadPacket` if it is not an item, such as an error response sent instead of one.
+    pub fn deserialize_item(data: &[u8]) -> Result<Self, DBPacketResponseError> {
+        serde_json::from_slice(verify_checksum(data)?).map_err(|_| DBPacketResponseError::BadPacket)
+    }
+}
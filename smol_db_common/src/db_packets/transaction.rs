@@ -0,0 +1,77 @@
+//! Module containing the operations that make up a transaction, and a builder to create a list of them.
+use crate::db_data::DBData;
+use crate::db_packets::db_location::DBLocation;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+/// A single operation within a transaction, every operation in a transaction is applied to the same database.
+pub enum TransactionOp {
+    /// Reads the value at the location, the result is the value if there was one.
+    Read(DBLocation),
+    /// Writes the value to the location, the result is the previous value if there was one.
+    Write(DBLocation, DBData),
+    /// Deletes the value at the location, the result is the removed value. Fails the transaction if there was no value.
+    Delete(DBLocation),
+}
+
+impl TransactionOp {
+    /// Returns true if this operation modifies the database
+    pub fn is_write(&self) -> bool {
+        match self {
+            TransactionOp::Read(_) => false,
+            TransactionOp::Write(_, _) | TransactionOp::Delete(_) => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// Builder used to create the list of operations for a transaction.
+/// ```
+/// use smol_db_common::db_packets::transaction::TransactionBuilder;
+///
+/// let ops = TransactionBuilder::new()
+///     .read("balance_a")
+///     .write("balance_a", "5")
+///     .delete("pending")
+///     .build();
+///
+/// assert_eq!(ops.len(), 3);
+/// ```
+pub struct TransactionBuilder {
+    ops: Vec<TransactionOp>,
+}
+
+impl TransactionBuilder {
+    /// Creates a new empty `TransactionBuilder`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a read of the given location to the transaction
+    pub fn read(mut self, location: &str) -> Self {
+        self.ops
+            .push(TransactionOp::Read(DBLocation::new(location)));
+        self
+    }
+
+    /// Adds a write of the given data to the location to the transaction
+    pub fn write(mut self, location: &str, data: &str) -> Self {
+        self.ops.push(TransactionOp::Write(
+            DBLocation::new(location),
+            DBData::new(data.to_string()),
+        ));
+        self
+    }
+
+    /// Adds a delete of the given location to the transaction
+    pub fn delete(mut self, location: &str) -> Self {
+        self.ops
+            .push(TransactionOp::Delete(DBLocation::new(location)));
+        self
+    }
+
+    /// Returns the list of operations that were added to the builder, in the order they were added
+    pub fn build(self) -> Vec<TransactionOp> {
+        self.ops
+    }
+}
@@ -0,0 +1,67 @@
+//! Module containing `ValueSchema`, a json schema every value written to a database must match.
+use crate::db_packets::db_packet_response::DBPacketResponseError;
+use jsonschema::JSONSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(transparent)]
+/// A json schema attached to a database through its `DBSettings`, writes of values that are not json matching the schema are rejected with `SchemaViolation`.
+/// Only the key value pairs of a database are checked, lists, sets, sorted lists and binary values are not.
+/// ```
+/// use smol_db_common::db_packets::value_schema::ValueSchema;
+/// use serde_json::json;
+///
+/// let schema = ValueSchema::new(json!({
+///     "type": "object",
+///     "properties": { "name": { "type": "string" } },
+///     "required": ["name"]
+/// }))
+/// .unwrap();
+///
+/// assert!(schema.validate(r#"{"name": "cory"}"#).is_ok());
+/// assert!(schema.validate(r#"{"age": 5}"#).is_err());
+/// assert!(schema.validate("not json").is_err());
+/// ```
+pub struct ValueSchema {
+    schema: Value,
+}
+
+// json values parsed from text never contain NaN, so equality is reflexive
+impl Eq for ValueSchema {}
+
+impl ValueSchema {
+    /// Returns a new `ValueSchema`, or `InvalidSchema` if the given json is not a valid json schema
+    pub fn new(schema: Value) -> Result<Self, DBPacketResponseError> {
+        let value_schema = Self { schema };
+        value_schema.check()?;
+        Ok(value_schema)
+    }
+
+    /// Returns the json schema
+    pub fn get_schema(&self) -> &Value {
+        &self.schema
+    }
+
+    /// Returns `InvalidSchema` if the json is not a valid json schema, schemas that were deserialized are not checked until they are used
+    pub fn check(&self) -> Result<(), DBPacketResponseError> {
+        self.compile().map(|_| ())
+    }
+
+    /// Returns `SchemaViolation` with the reason, if the value is not json or does not match the schema
+    pub fn validate(&self, value: &str) -> Result<(), DBPacketResponseError> {
+        let compiled = self.compile()?;
+        let instance: Value = serde_json::from_str(value).map_err(|err| {
+            DBPacketResponseError::SchemaViolation(format!("value is not json: {}", err))
+        })?;
+        compiled.validate(&instance).map_err(|mut errors| {
+            DBPacketResponseError::SchemaViolation(
+                errors.next().map(|err| err.to_string()).unwrap_or_default(),
+            )
+        })
+    }
+
+    fn compile(&self) -> Result<JSONSchema, DBPacketResponseError> {
+        JSONSchema::compile(&self.schema).map_err(|_| DBPacketResponseError::InvalidSchema)
+    }
+}
@@ -2,54 +2,53 @@
 
 use crate::db_packets::db_packet::DBPacket;
 use crate::encryption::encrypted_data::EncryptedData;
-use crate::encryption::{decrypt, EncryptionError, BIT_LENGTH};
+use crate::encryption::session_cipher::SessionCipher;
+use crate::encryption::{key_fingerprint, EncryptionError};
 use crate::prelude::{DBPacketResponseError, DBSuccessResponse};
 use rsa::rand_core::OsRng;
-use rsa::{Pkcs1v15Encrypt, RsaPrivateKey, RsaPublicKey};
-use tracing::{error, info};
+use rsa::RsaPublicKey;
+use tracing::info;
 
 #[derive(Debug)]
-/// A client rsa key pair, along with a server public key used for end to end encryption
+/// A client session cipher, along with its key encrypted with the server public key, used for end to end encryption
 pub struct ClientKey {
-    pri_key: RsaPrivateKey,
-    pub_key: RsaPublicKey,
-    server_pub_key: RsaPublicKey,
-    rng: OsRng,
+    session_cipher: SessionCipher,
+    encrypted_session_key: EncryptedData,
+    server_fingerprint: String,
 }
 
 impl ClientKey {
+    /// Generates a session key, and encrypts it with the servers public key so only the server can read it
     #[tracing::instrument]
     pub fn new(server_pub_key: RsaPublicKey) -> Result<Self, rsa::Error> {
-        info!("Generating client key from server public key");
-        let mut rng = OsRng;
-        let pri_key = RsaPrivateKey::new(&mut rng, BIT_LENGTH)?;
-        let pub_key = pri_key.to_public_key();
+        info!("Generating client session key");
+        let session_cipher = SessionCipher::generate();
+        let encrypted_session_key =
+            crate::encryption::encrypt(&server_pub_key, &mut OsRng, session_cipher.get_key())?;
 
         Ok(Self {
-            pri_key,
-            pub_key,
-            server_pub_key,
-            rng,
+            session_cipher,
+            encrypted_session_key: EncryptedData::new(&encrypted_session_key),
+            server_fingerprint: key_fingerprint(&server_pub_key),
         })
     }
 
-    /// Get the clients public key
+    /// Returns the fingerprint of the server public key the session key was encrypted with
     #[tracing::instrument]
-    pub fn get_pub_key(&self) -> &RsaPublicKey {
-        &self.pub_key
+    pub fn get_server_fingerprint(&self) -> &str {
+        &self.server_fingerprint
+    }
+
+    /// Returns the packet that sends the session key to the server, which is the only packet not encrypted with the session key
+    #[tracing::instrument]
+    pub fn session_key_packet(&self) -> DBPacket {
+        DBPacket::SessionKey(self.encrypted_session_key.clone())
     }
 
     /// Encrypt a packet to be sent to the server
     #[tracing::instrument]
-    pub fn encrypt_packet(&mut self, packet: &DBPacket) -> Result<DBPacket, EncryptionError> {
-        let serialized_data = packet
-            .serialize_packet()
-            .map_err(|_| EncryptionError::SerializationError)?;
-        let encrypted_data = self
-            .encrypt(serialized_data.as_bytes())
-            .map_err(EncryptionError::RSAError)?;
-        let enc_struct = EncryptedData::new(encrypted_data.as_slice());
-        Ok(DBPacket::Encrypted(enc_struct))
+    pub fn encrypt_packet(&self, packet: &DBPacket) -> Result<DBPacket, EncryptionError> {
+        self.session_cipher.encrypt_packet(packet)
     }
 
     /// Decrypt a packet received from the server on the client
@@ -58,30 +57,7 @@ impl ClientKey {
         &self,
         server_db_response: &[u8],
     ) -> Result<Result<DBSuccessResponse<String>, DBPacketResponseError>, EncryptionError> {
-        let msg = decrypt(&self.pri_key, server_db_response).map_err(EncryptionError::RSAError)?;
-        match serde_json::from_slice(&msg) {
-            Ok(packet) => {
-                info!("Successfully decrypted packet");
-                Ok(packet)
-            }
-            Err(e) => {
-                error!("Error deserializing encrypted packet from server: {}", e);
-                Err(EncryptionError::SerializationError)
-            }
-        }
-    }
-
-    /// Decrypt data sent from the server encrypted with client public key using client private key
-    /// This function is used when decrypting data sent from server -> client
-    #[tracing::instrument]
-    pub fn decrypt(&self, msg: &[u8]) -> rsa::Result<Vec<u8>> {
-        self.pri_key.decrypt(Pkcs1v15Encrypt, msg)
-    }
-
-    /// Encrypt data to be sent to the server using the servers public key
-    /// This function is used when encrypting data sent from client -> server
-    #[tracing::instrument]
-    pub fn encrypt(&mut self, msg: &[u8]) -> rsa::Result<Vec<u8>> {
-        crate::encryption::encrypt(&self.server_pub_key, &mut self.rng, msg)
+        self.session_cipher
+            .decrypt_server_packet(server_db_response)
     }
 }
@@ -1,8 +1,12 @@
 //! Simple encrypted data struct module
-use serde::{Deserialize, Serialize};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Clone, Debug)]
 /// Struct representing encrypted data, used simply for organization
+/// Serialized as a base64 string, so encrypted packets are not inflated by serializing each byte as a number.
 pub struct EncryptedData {
     data: Vec<u8>,
 }
@@ -20,3 +24,19 @@ impl EncryptedData {
         self.data.as_slice()
     }
 }
+
+impl Serialize for EncryptedData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(&self.data))
+    }
+}
+
+impl<'de> Deserialize<'de> for EncryptedData {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        STANDARD
+            .decode(encoded)
+            .map(|data| Self { data })
+            .map_err(D::Error::custom)
+    }
+}
@@ -0,0 +1,142 @@
+//! Contains `KeyHasher`, which hashes client keys with a salt unique to the server, so the server never stores a client key as plaintext.
+use crate::db_packets::db_settings::DBSettings;
+use crate::user_registry::UserRegistry;
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Version of the hashes made by `KeyHasher`, saved alongside hashed keys so keys saved by an older version are hashed again.
+/// Version 0 is keys saved as plaintext, before keys were hashed.
+pub const KEY_HASH_VERSION: u32 = 1;
+
+/// Memory in KiB each hash takes to compute, so keys can not be guessed quickly from a leaked hash.
+const ARGON2_MEMORY_KIB: u32 = 19 * 1024;
+
+/// Passes made over the memory of each hash.
+const ARGON2_ITERATIONS: u32 = 2;
+
+/// Lanes each hash is computed with.
+const ARGON2_PARALLELISM: u32 = 1;
+
+/// Length in bytes of a hash, before it is hex encoded.
+const HASH_LENGTH: usize = 32;
+
+/// Length of the salts and tokens generated for a new server
+const TOKEN_LENGTH: usize = 32;
+
+/// Returns a random alphanumeric string, used for salts and bootstrap tokens.
+pub fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+/// Hashes client keys with the salt of the server, using argon2id.
+/// The hash of a key is the same every time, so a key is hashed once when a client sets it, and compared to the hashes in the admin, user and super admin lists.
+pub struct KeyHasher {
+    salt: String,
+}
+
+impl KeyHasher {
+    /// Returns a hasher with the given salt, used when the salt is shared with another server such as a replica.
+    /// The salt must be at least 8 bytes long.
+    pub fn new(salt: String) -> Self {
+        Self { salt }
+    }
+
+    /// Returns the salt keys are hashed with
+    pub fn get_salt(&self) -> &str {
+        &self.salt
+    }
+
+    /// Hashes the key, even if it looks like a hash already, so a client can not log in using a hash it has seen.
+    /// The hash starts with the algorithm and parameters it was made with.
+    pub fn hash_key(&self, key: &str) -> String {
+        let params = Params::new(
+            ARGON2_MEMORY_KIB,
+            ARGON2_ITERATIONS,
+            ARGON2_PARALLELISM,
+            Some(HASH_LENGTH),
+        )
+        .expect("argon2 parameters are valid");
+        let mut hash = [0; HASH_LENGTH];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(key.as_bytes(), self.salt.as_bytes(), &mut hash)
+            .expect("salt of the key hasher is long enough for argon2");
+        let hash: String = hash.iter().map(|byte| format!("{byte:02x}")).collect();
+        format!(
+            "$argon2id$v=19$m={ARGON2_MEMORY_KIB},t={ARGON2_ITERATIONS},p={ARGON2_PARALLELISM}${hash}"
+        )
+    }
+
+    /// Hashes a key that is being given a role, leaving account keys as they are, so an account is given a role by its account key.
+    pub fn hash_role_key(&self, key: &str) -> String {
+        if UserRegistry::is_account_key(key) {
+            key.to_string()
+        } else {
+            self.hash_key(key)
+        }
+    }
+
+    /// Hashes every key in the list, which were all saved as plaintext before keys were hashed.
+    pub fn hash_plaintext_keys(&self, keys: &mut [String]) {
+        for key in keys.iter_mut() {
+            *key = self.hash_key(key);
+        }
+    }
+
+    /// Hashes every key in the admin and user lists and the key acl rules of the settings, which were all saved as plaintext before keys were hashed.
+    pub fn hash_plaintext_settings_keys(&self, settings: &mut DBSettings) {
+        self.hash_plaintext_keys(&mut settings.admins);
+        self.hash_plaintext_keys(&mut settings.users);
+        for rule in settings.key_acls.iter_mut() {
+            rule.key = self.hash_key(&rule.key);
+        }
+    }
+
+    /// Hashes each key in a list sent by a client, leaving account keys and the keys in `stored`,
+    /// the hashes already saved where the list is saved, such as those the client read and sent back.
+    /// Returns true if any key was hashed.
+    pub fn hash_new_keys(&self, keys: &mut [String], stored: &[String]) -> bool {
+        let mut changed = false;
+        for key in keys
+            .iter_mut()
+            .filter(|key| !stored.contains(key) && !UserRegistry::is_account_key(key))
+        {
+            *key = self.hash_key(key);
+            changed = true;
+        }
+        changed
+    }
+
+    /// Hashes each key in the admin and user lists and the key acl rules of settings sent by a client,
+    /// leaving account keys and the keys in `stored`, the hashes already saved in the settings being replaced.
+    /// Returns true if any key was hashed.
+    pub fn hash_new_settings_keys(&self, settings: &mut DBSettings, stored: &[String]) -> bool {
+        let admins_changed = self.hash_new_keys(&mut settings.admins, stored);
+        let users_changed = self.hash_new_keys(&mut settings.users, stored);
+        let mut acls_changed = false;
+        for rule in settings
+            .key_acls
+            .iter_mut()
+            .filter(|rule| !stored.contains(&rule.key) && !UserRegistry::is_account_key(&rule.key))
+        {
+            rule.key = self.hash_key(&rule.key);
+            acls_changed = true;
+        }
+        admins_changed || users_changed || acls_changed
+    }
+}
+
+impl Default for KeyHasher {
+    /// Returns a hasher with a newly generated salt
+    fn default() -> Self {
+        Self {
+            salt: generate_token(),
+        }
+    }
+}
@@ -1,22 +1,48 @@
 //! Encryption module for `smol_db`, used in `smol_db_client` and `smol_db_server`
 
+use rsa::pkcs8::EncodePublicKey;
 use rsa::rand_core::OsRng;
 use rsa::{Pkcs1v15Encrypt, RsaPrivateKey, RsaPublicKey};
+use sha2::{Digest, Sha256};
+use std::fmt::{Display, Formatter};
 
 /// The length of bits an rsa key will be
 const BIT_LENGTH: usize = 2048;
 pub mod client_encrypt;
 pub mod encrypted_data;
+pub mod key_hash;
 pub mod server_encrypt;
+pub mod session_cipher;
 
 #[derive(Debug)]
 /// Error enum detailing types of encryption error
 pub enum EncryptionError {
     SerializationError,
     RSAError(rsa::Error),
+    /// Encrypting or decrypting with the session key failed, or the session key was not a valid AES-256 key
+    CipherError,
 }
 
-/// Encrypt a piece of data using a public key
+impl Display for EncryptionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SerializationError => write!(f, "the data to encrypt could not be serialized"),
+            Self::RSAError(err) => write!(f, "{}", err),
+            Self::CipherError => write!(f, "encrypting or decrypting with the session key failed"),
+        }
+    }
+}
+
+impl std::error::Error for EncryptionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::RSAError(err) => Some(err),
+            Self::SerializationError | Self::CipherError => None,
+        }
+    }
+}
+
+/// Encrypt a piece of data using a public key, only used to send a session key to the server
 fn encrypt(key: &RsaPublicKey, mut rng: &mut OsRng, msg: &[u8]) -> rsa::Result<Vec<u8>> {
     key.encrypt(&mut rng, Pkcs1v15Encrypt, msg)
 }
@@ -25,3 +51,16 @@ fn encrypt(key: &RsaPublicKey, mut rng: &mut OsRng, msg: &[u8]) -> rsa::Result<V
 fn decrypt(pri_key: &RsaPrivateKey, enc_data: &[u8]) -> rsa::Result<Vec<u8>> {
     pri_key.decrypt(Pkcs1v15Encrypt, enc_data)
 }
+
+/// Returns the fingerprint of a public key, the sha256 hash of its DER encoding as lowercase hex.
+/// Clients compare the fingerprint of the key a server presents to the one they expect, to know they are talking to the same server.
+pub fn key_fingerprint(key: &RsaPublicKey) -> String {
+    // encoding a valid rsa public key does not fail
+    let der = key
+        .to_public_key_der()
+        .expect("Unable to encode public key");
+    Sha256::digest(der.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
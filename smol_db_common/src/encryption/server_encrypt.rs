@@ -1,17 +1,24 @@
 //! Server encryption module
-use crate::db_packets::db_packet::DBPacket;
 use crate::encryption::encrypted_data::EncryptedData;
-use crate::encryption::{decrypt, EncryptionError, BIT_LENGTH};
+use crate::encryption::session_cipher::SessionCipher;
+use crate::encryption::{decrypt, key_fingerprint, EncryptionError, BIT_LENGTH};
+use rsa::pkcs8::{DecodePrivateKey, EncodePrivateKey, LineEnding};
 use rsa::rand_core::OsRng;
 use rsa::{RsaPrivateKey, RsaPublicKey};
-use tracing::info;
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use tracing::{info, warn};
+
+/// Name of the file in the data directory the server key is saved in, so clients see the same key after the server restarts
+pub const SERVER_KEY_FILE_NAME: &str = "server_key.pem";
 
 #[derive(Debug)]
-/// Struct containing a server encryption key pair used to encrypt data sent from the server and to the server for end to end encryption
+/// Struct containing a server encryption key pair, used by clients to send the server their session key for end to end encryption
 pub struct ServerKey {
     pri_key: RsaPrivateKey,
     pub_key: RsaPublicKey,
-    rng: OsRng,
 }
 
 impl Default for ServerKey {
@@ -26,57 +33,98 @@ impl ServerKey {
     #[tracing::instrument]
     pub fn new() -> Result<Self, rsa::Error> {
         info!("Generating server key");
-        let mut rng = OsRng;
-        let pri_key = RsaPrivateKey::new(&mut rng, BIT_LENGTH)?;
+        let pri_key = RsaPrivateKey::new(&mut OsRng, BIT_LENGTH)?;
         let pub_key = pri_key.to_public_key();
-        Ok(Self {
-            pri_key,
-            pub_key,
-            rng,
-        })
+        Ok(Self { pri_key, pub_key })
     }
 
-    /// Gets public key of server
+    /// Loads the server key saved at the path, generating and saving a new key if there is no file at the path.
     #[tracing::instrument]
-    pub fn get_pub_key(&self) -> &RsaPublicKey {
-        &self.pub_key
+    pub fn load_or_create(path: &Path) -> io::Result<Self> {
+        if path.exists() {
+            Self::load(path)
+        } else {
+            warn!(
+                "No server key found at {}, generating a new one",
+                path.display()
+            );
+            let key = Self::new().map_err(io::Error::other)?;
+            key.save(path)?;
+            Ok(key)
+        }
     }
 
-    /// Encrypt data using the clients public key
-    /// This function is used when encrypting data sent from server -> client
+    /// Loads the server key saved at the path as a PKCS#8 PEM file.
+    /// Errors if the file can be read by users other than its owner, as anyone who can read the key can decrypt the session keys sent to the server.
     #[tracing::instrument]
-    fn encrypt(&mut self, client_pub_key: &RsaPublicKey, msg: &[u8]) -> rsa::Result<Vec<u8>> {
-        crate::encryption::encrypt(client_pub_key, &mut self.rng, msg)
+    pub fn load(path: &Path) -> io::Result<Self> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(path)?.permissions().mode();
+            if mode & 0o077 != 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    format!(
+                        "Server key {} can be accessed by other users (mode {:o}), restrict it with chmod 600",
+                        path.display(),
+                        mode & 0o777
+                    ),
+                ));
+            }
+        }
+        let pem = fs::read_to_string(path)?;
+        let pri_key = RsaPrivateKey::from_pkcs8_pem(&pem)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+        let pub_key = pri_key.to_public_key();
+        info!("Loaded server key from {}", path.display());
+        Ok(Self { pri_key, pub_key })
     }
 
-    /// Encrypt a packet that has already been serialized into a string
-    /// The client will receive an error if the packet is not serialized properly BEFORE encryption
+    /// Saves the server key to the path as a PKCS#8 PEM file only its owner can access.
+    /// The key is written to a temporary file that replaces the file at the path, so the saved key is never left half written.
     #[tracing::instrument]
-    pub fn encrypt_packet(
-        &mut self,
-        packet: &String,
-        client_pub_key: &RsaPublicKey,
-    ) -> Result<EncryptedData, EncryptionError> {
-        let encrypted_data = self
-            .encrypt(client_pub_key, packet.as_bytes())
-            .map_err(EncryptionError::RSAError)?;
-        let enc_struct = EncryptedData::new(encrypted_data.as_slice());
-        Ok(enc_struct)
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let pem = self
+            .pri_key
+            .to_pkcs8_pem(LineEnding::LF)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+        let temp_path = path.with_extension("tmp");
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&temp_path)?;
+        file.write_all(pem.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    }
+
+    /// Gets public key of server
+    #[tracing::instrument]
+    pub fn get_pub_key(&self) -> &RsaPublicKey {
+        &self.pub_key
     }
 
-    /// Decrypt a packet send from the client to the server on the server side
-    /// converts encrypted data into a db packet
+    /// Returns the fingerprint of the public key of the server, which clients can pin to detect the key changing
     #[tracing::instrument]
-    pub fn decrypt_client_packet(
+    pub fn fingerprint(&self) -> String {
+        key_fingerprint(&self.pub_key)
+    }
+
+    /// Decrypts the session key a client sent, returning the cipher the rest of the connection is encrypted with
+    #[tracing::instrument]
+    pub fn decrypt_session_key(
         &self,
-        client_packet: &EncryptedData,
-    ) -> Result<DBPacket, EncryptionError> {
-        let msg =
-            decrypt(&self.pri_key, client_packet.get_data()).map_err(EncryptionError::RSAError)?;
-        match serde_json::from_slice::<DBPacket>(&msg) {
-            Ok(packet) => Ok(packet),
-            Err(_) => Err(EncryptionError::SerializationError),
-        }
+        encrypted_key: &EncryptedData,
+    ) -> Result<SessionCipher, EncryptionError> {
+        let key = self
+            .decrypt(encrypted_key.get_data())
+            .map_err(EncryptionError::RSAError)?;
+        SessionCipher::from_key(&key)
     }
 
     /// Decrypt data using the servers private key encrypted with the servers public key
@@ -0,0 +1,124 @@
+//! Contains `SessionCipher`, the AES-256-GCM cipher every packet is encrypted with once a client has setup encryption.
+use crate::db_packets::db_packet::DBPacket;
+use crate::db_packets::packet_checksum::{
+    add_checksum_bytes, deserialize_response, verify_checksum,
+};
+use crate::encryption::encrypted_data::EncryptedData;
+use crate::encryption::EncryptionError;
+use crate::prelude::{DBPacketResponseError, DBSuccessResponse};
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use std::fmt::{Debug, Formatter};
+use tracing::error;
+
+/// Length in bytes of the nonce prepended to each encrypted message
+const NONCE_LENGTH: usize = 12;
+
+#[derive(Clone)]
+/// An AES-256-GCM cipher shared by a client and the server for the rest of a connection.
+/// The client generates the key, and sends it to the server encrypted with the servers rsa public key,
+/// so rsa is only used once per connection, and packets are not limited to the size of an rsa block.
+pub struct SessionCipher {
+    key: Key<Aes256Gcm>,
+    cipher: Aes256Gcm,
+}
+
+impl Debug for SessionCipher {
+    /// Formats the cipher without its key
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionCipher").finish_non_exhaustive()
+    }
+}
+
+impl SessionCipher {
+    /// Returns a cipher with a newly generated key
+    pub fn generate() -> Self {
+        let key = Aes256Gcm::generate_key(OsRng);
+        Self {
+            cipher: Aes256Gcm::new(&key),
+            key,
+        }
+    }
+
+    /// Returns a cipher using the given key, erroring if the key is not 32 bytes long
+    pub fn from_key(key: &[u8]) -> Result<Self, EncryptionError> {
+        let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| EncryptionError::CipherError)?;
+        Ok(Self {
+            key: *Key::<Aes256Gcm>::from_slice(key),
+            cipher,
+        })
+    }
+
+    /// Returns the key of the cipher, which the client sends to the server encrypted with rsa
+    pub fn get_key(&self) -> &[u8] {
+        self.key.as_slice()
+    }
+
+    /// Encrypts the message with a new random nonce, returning the nonce followed by the encrypted message
+    pub fn encrypt(&self, msg: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        let nonce = Aes256Gcm::generate_nonce(OsRng);
+        let encrypted = self
+            .cipher
+            .encrypt(&nonce, msg)
+            .map_err(|_| EncryptionError::CipherError)?;
+        let mut data = nonce.to_vec();
+        data.extend(encrypted);
+        Ok(data)
+    }
+
+    /// Decrypts data encrypted by `SessionCipher::encrypt`, erroring if it was not encrypted with the same key or has been modified
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        if data.len() < NONCE_LENGTH {
+            return Err(EncryptionError::CipherError);
+        }
+        let (nonce, encrypted) = data.split_at(NONCE_LENGTH);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), encrypted)
+            .map_err(|_| EncryptionError::CipherError)
+    }
+
+    /// Encrypt a packet to be sent to the server
+    #[tracing::instrument]
+    pub fn encrypt_packet(&self, packet: &DBPacket) -> Result<DBPacket, EncryptionError> {
+        let serialized_data = packet
+            .serialize_packet()
+            .map_err(|_| EncryptionError::SerializationError)?;
+        let encrypted_data = self.encrypt(serialized_data.as_bytes())?;
+        Ok(DBPacket::Encrypted(EncryptedData::new(&encrypted_data)))
+    }
+
+    /// Decrypt a packet send from the client to the server on the server side
+    /// converts encrypted data into a db packet
+    #[tracing::instrument]
+    pub fn decrypt_client_packet(
+        &self,
+        client_packet: &EncryptedData,
+    ) -> Result<DBPacket, EncryptionError> {
+        let msg = self.decrypt(client_packet.get_data())?;
+        DBPacket::deserialize_packet(&msg).map_err(|_| EncryptionError::SerializationError)
+    }
+
+    /// Encrypt a serialized response to be sent to the client, with a checksum header so the client knows how much to read
+    #[tracing::instrument(skip_all)]
+    pub fn encrypt_response(&self, response: &str) -> Result<Vec<u8>, EncryptionError> {
+        self.encrypt(response.as_bytes())
+            .map(|data| add_checksum_bytes(&data))
+    }
+
+    /// Decrypt a response sent by `encrypt_response`, received from the server on the client
+    #[tracing::instrument(skip_all)]
+    pub fn decrypt_server_packet(
+        &self,
+        server_db_response: &[u8],
+    ) -> Result<Result<DBSuccessResponse<String>, DBPacketResponseError>, EncryptionError> {
+        let encrypted = match verify_checksum(server_db_response) {
+            Ok(encrypted) => encrypted,
+            Err(err) => return Ok(Err(err)),
+        };
+        let msg = self.decrypt(encrypted)?;
+        deserialize_response(&msg).map_err(|e| {
+            error!("Error deserializing encrypted packet from server: {}", e);
+            EncryptionError::SerializationError
+        })
+    }
+}
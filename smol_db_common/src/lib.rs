@@ -1,31 +1,67 @@
 //! Common library between the client and server for `smol_db`
 
+pub mod channel_registry;
 pub mod db;
+pub mod db_bytes;
+pub mod db_cache;
 pub mod db_content;
 pub mod db_data;
+pub mod db_dump;
+pub mod db_file;
 pub mod db_list;
 pub mod db_packets;
 pub mod encryption;
+pub mod logging;
+pub mod notification_bus;
+pub mod queue_waiters;
+pub mod replication;
+pub mod revision_history;
+pub mod session_store;
+pub mod snapshot;
+pub mod sorted_list;
 #[cfg(feature = "statistics")]
 pub mod statistics;
+pub mod trash;
+pub mod user_registry;
+pub mod write_ahead_log;
 
 pub mod prelude {
     pub use crate::db::Role;
     pub use crate::db::Role::{Admin, Other, SuperAdmin, User};
     pub use crate::db::DB;
+    pub use crate::db_bytes::DBBytes;
     pub use crate::db_data::DBData;
     pub use crate::db_list::DBList;
+    pub use crate::db_packets::change_event::ChangeEvent;
     pub use crate::db_packets::db_location::DBLocation;
+    pub use crate::db_packets::db_metadata::{DBListing, DBMetadata};
     pub use crate::db_packets::db_packet::*;
     pub use crate::db_packets::db_packet_info::DBPacketInfo;
     pub use crate::db_packets::db_packet_response::DBPacketResponseError::{
         DBAlreadyExists, DBNotFound, InvalidPermissions, UserNotFound, ValueNotFound,
     };
     pub use crate::db_packets::db_packet_response::DBSuccessResponse::{
-        SuccessNoData, SuccessReply,
+        SuccessData, SuccessNoData, SuccessReply,
     };
     pub use crate::db_packets::db_packet_response::{DBPacketResponseError, DBSuccessResponse};
     pub use crate::db_packets::db_settings::DBSettings;
+    pub use crate::db_packets::db_settings_builder::{DBSettingsBuilder, DBSettingsError};
+    pub use crate::db_packets::effective_permissions::EffectivePermissions;
+    pub use crate::db_packets::ip_filter::{IpFilter, IpNet};
+    pub use crate::db_packets::key_acl::KeyAclRule;
+    pub use crate::db_packets::key_limits::{KeyCharset, KeyLimits};
+    pub use crate::db_packets::key_pattern::KeyPattern;
+    pub use crate::db_packets::namespace::NamespaceSettings;
+    pub use crate::db_packets::permissions::{Permission, Permissions};
+    pub use crate::db_packets::response_data::ResponseData;
+    pub use crate::db_packets::server_info::ServerInfo;
+    pub use crate::db_packets::stats_format::StatsFormat;
+    pub use crate::db_packets::storage_quota::StorageQuota;
+    pub use crate::db_packets::stream_item::StreamItem;
+    pub use crate::db_packets::transaction::{TransactionBuilder, TransactionOp};
+    pub use crate::db_packets::value_schema::ValueSchema;
+    pub use crate::revision_history::{Revision, RevisionInfo};
+    pub use crate::sorted_list::ScoredMember;
     pub use rsa::Error;
     pub use rsa::RsaPublicKey;
 }
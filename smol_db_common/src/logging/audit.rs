@@ -0,0 +1,189 @@
+//! Contains the audit log, which records every request that changes a database, who made it, and whether it succeeded.
+//! Each entry is a line of json, so the log can be searched with `AuditLog::read_entries` or any tool that reads json lines.
+use crate::db_packets::db_packet::DBPacket;
+use crate::db_packets::db_packet_response::{DBPacketResponseError, DBSuccessResponse};
+use crate::db_packets::transaction::TransactionOp;
+use crate::logging::rotating_file::RotatingFile;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, BufReader};
+use std::net::SocketAddr;
+use std::path::Path;
+use tracing::{error, warn};
+
+/// File name the audit log is saved as within the data directory.
+pub const AUDIT_LOG_FILE_NAME: &str = "audit.log";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+/// What a request that changes a database was made to, taken from the packet before it is handled.
+pub struct AuditTarget {
+    /// Name of the packet, such as `Write` or `DeleteDB`
+    pub operation: String,
+    /// The database the request was made to, if it was made to one
+    pub db: Option<String>,
+    /// The keys the request changes, or the other database for renames and copies
+    pub keys: Vec<String>,
+}
+
+impl AuditTarget {
+    /// Describes the packet, which is expected to be a packet that changes a database.
+    pub fn new(packet: &DBPacket) -> Self {
+        let (operation, db, keys) = match packet {
+            DBPacket::Write(db, location, _) => ("Write", Some(db), vec![location.as_key()]),
+            DBPacket::DeleteData(db, location) => ("DeleteData", Some(db), vec![location.as_key()]),
+            DBPacket::CreateDB(db, _) => ("CreateDB", Some(db), vec![]),
+            DBPacket::DeleteDB(db) => ("DeleteDB", Some(db), vec![]),
+            DBPacket::RestoreDB(db) => ("RestoreDB", Some(db), vec![]),
+            DBPacket::PurgeTrash => ("PurgeTrash", None, vec![]),
+            DBPacket::CompareAndSwap { db, location, .. } => {
+                ("CompareAndSwap", Some(db), vec![location.as_key()])
+            }
+            DBPacket::Increment { db, location, .. } => {
+                ("Increment", Some(db), vec![location.as_key()])
+            }
+            DBPacket::WriteIfAbsent(db, location, _) => {
+                ("WriteIfAbsent", Some(db), vec![location.as_key()])
+            }
+            DBPacket::RenameDB { from, to } => ("RenameDB", Some(from), vec![to.get_db_name()]),
+            DBPacket::CopyDB { source, dest, .. } => {
+                ("CopyDB", Some(source), vec![dest.get_db_name()])
+            }
+            DBPacket::ClearDB(db) => ("ClearDB", Some(db), vec![]),
+            DBPacket::AddToList(db, list, ..) => ("AddToList", Some(db), vec![list.as_key()]),
+            DBPacket::RemoveFromList(db, list, _) => {
+                ("RemoveFromList", Some(db), vec![list.as_key()])
+            }
+            DBPacket::ClearList(db, list) => ("ClearList", Some(db), vec![list.as_key()]),
+            DBPacket::SetAdd(db, set, _) => ("SetAdd", Some(db), vec![set.as_key()]),
+            DBPacket::SetRemove(db, set, _) => ("SetRemove", Some(db), vec![set.as_key()]),
+            DBPacket::SortedAdd(db, list, ..) => ("SortedAdd", Some(db), vec![list.as_key()]),
+            DBPacket::SortedRemove(db, list, _) => ("SortedRemove", Some(db), vec![list.as_key()]),
+            DBPacket::QueuePush(db, queue, _) => ("QueuePush", Some(db), vec![queue.as_key()]),
+            DBPacket::QueuePop(db, queue, _) => ("QueuePop", Some(db), vec![queue.as_key()]),
+            DBPacket::WriteBytes(db, location, _) => {
+                ("WriteBytes", Some(db), vec![location.as_key()])
+            }
+            DBPacket::BeginStreamWrite(db, location) => {
+                ("BeginStreamWrite", Some(db), vec![location.as_key()])
+            }
+            DBPacket::WriteMany(db, writes) => (
+                "WriteMany",
+                Some(db),
+                writes
+                    .iter()
+                    .map(|(location, _)| location.as_key())
+                    .collect(),
+            ),
+            DBPacket::Transaction(db, ops) => (
+                "Transaction",
+                Some(db),
+                ops.iter()
+                    .filter_map(|op| match op {
+                        TransactionOp::Read(_) => None,
+                        TransactionOp::Write(location, _) | TransactionOp::Delete(location) => {
+                            Some(location.as_key())
+                        }
+                    })
+                    .collect(),
+            ),
+            DBPacket::ImportDB => ("ImportDB", None, vec![]),
+            // the keys being added are not recorded, as they are secrets
+            DBPacket::AddAdmin(db, _) => ("AddAdmin", Some(db), vec![]),
+            DBPacket::AddUser(db, _) => ("AddUser", Some(db), vec![]),
+            DBPacket::RemoveAdmin(db, _) => ("RemoveAdmin", Some(db), vec![]),
+            DBPacket::RemoveUser(db, _) => ("RemoveUser", Some(db), vec![]),
+            DBPacket::ChangeDBSettings(db, _) => ("ChangeDBSettings", Some(db), vec![]),
+            DBPacket::AddKeyAcl(db, rule) => ("AddKeyAcl", Some(db), vec![rule.prefix.as_str()]),
+            DBPacket::RemoveKeyAcl(db, _, prefix) => {
+                ("RemoveKeyAcl", Some(db), vec![prefix.as_str()])
+            }
+            _ => ("Other", None, vec![]),
+        };
+
+        Self {
+            operation: operation.to_string(),
+            db: db.map(|db| db.get_db_name().to_string()),
+            keys: keys.into_iter().map(str::to_string).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+/// A single request recorded in the audit log.
+pub struct AuditEntry {
+    /// When the request was handled
+    pub time: DateTime<Utc>,
+    /// Address of the client that made the request
+    pub client: SocketAddr,
+    /// Key the client had set when it made the request
+    pub client_key: String,
+    #[serde(flatten)]
+    pub target: AuditTarget,
+    /// Whether the request succeeded, or the error it was responded to with
+    pub result: Result<(), DBPacketResponseError>,
+}
+
+#[derive(Debug)]
+/// Log of every request that changes a database, rotated once it reaches its size limit.
+pub struct AuditLog {
+    file: RotatingFile,
+}
+
+impl AuditLog {
+    /// Creates an audit log in the given data directory, which is rotated before it grows past `max_bytes`, keeping up to `max_rotated_files` rotated files.
+    pub fn new(data_dir: &Path, max_bytes: u64, max_rotated_files: usize) -> Self {
+        Self {
+            file: RotatingFile::new(
+                &data_dir.join(AUDIT_LOG_FILE_NAME),
+                max_bytes,
+                max_rotated_files,
+            ),
+        }
+    }
+
+    /// Appends an entry for the request to the log.
+    #[tracing::instrument(skip(self, response))]
+    pub fn record(
+        &self,
+        client: SocketAddr,
+        client_key: &str,
+        target: &AuditTarget,
+        response: &Result<DBSuccessResponse<String>, DBPacketResponseError>,
+    ) {
+        let entry = AuditEntry {
+            time: Utc::now(),
+            client,
+            client_key: client_key.to_string(),
+            target: target.clone(),
+            result: response.as_ref().map(|_| ()).map_err(Clone::clone),
+        };
+
+        let line = match serde_json::to_string(&entry) {
+            Ok(line) => line,
+            Err(err) => {
+                error!("Unable to serialize audit entry: {}", err);
+                return;
+            }
+        };
+        if let Err(err) = self.file.write_line(&line) {
+            error!("Unable to write to audit log: {}", err);
+        }
+    }
+
+    /// Reads every entry from the oldest rotated file to the newest entry, skipping any line that can not be read.
+    #[tracing::instrument(skip(self))]
+    pub fn read_entries(&self) -> io::Result<Vec<AuditEntry>> {
+        let mut entries = vec![];
+        for path in self.file.existing_paths() {
+            for line in BufReader::new(File::open(&path)?).lines() {
+                match serde_json::from_str(&line?) {
+                    Ok(entry) => entries.push(entry),
+                    Err(err) => warn!("Skipping unreadable audit entry in {:?}: {}", path, err),
+                }
+            }
+        }
+        Ok(entries)
+    }
+}
@@ -0,0 +1,96 @@
+//! Contains the server log file, which the server writes its log entries to alongside printing them, rotated once it reaches its size limit.
+use crate::logging::rotating_file::RotatingFile;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use tracing::Level;
+
+/// File name the server log is saved as within the data directory.
+pub const LOG_FILE_NAME: &str = "log.log";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+/// The least severe level of log entry that is written, from only errors to every entry.
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub const fn as_tracing_level(self) -> Level {
+        match self {
+            Self::Error => Level::ERROR,
+            Self::Warn => Level::WARN,
+            Self::Info => Level::INFO,
+            Self::Debug => Level::DEBUG,
+            Self::Trace => Level::TRACE,
+        }
+    }
+}
+
+impl Display for LogLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Error => "error",
+            Self::Warn => "warn",
+            Self::Info => "info",
+            Self::Debug => "debug",
+            Self::Trace => "trace",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Ok(Self::Error),
+            "warn" => Ok(Self::Warn),
+            "info" => Ok(Self::Info),
+            "debug" => Ok(Self::Debug),
+            "trace" => Ok(Self::Trace),
+            _ => Err(format!(
+                "unknown log level \"{}\", expected one of error, warn, info, debug or trace",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug)]
+/// The server log file, each write is appended as it is given, so a formatted log entry is never split across rotated files.
+pub struct Logger {
+    file: RotatingFile,
+}
+
+impl Logger {
+    /// Creates a log in the given data directory, which is rotated before it grows past `max_bytes`, keeping up to `max_rotated_files` rotated files.
+    pub fn new(data_dir: &Path, max_bytes: u64, max_rotated_files: usize) -> Self {
+        Self {
+            file: RotatingFile::new(&data_dir.join(LOG_FILE_NAME), max_bytes, max_rotated_files),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+}
+
+impl io::Write for &Logger {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.append(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
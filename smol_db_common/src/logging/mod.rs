@@ -0,0 +1,4 @@
+//! Contains the log files the server writes alongside its databases, and the writer they share that rotates a file once it grows too large.
+pub mod audit;
+pub mod logger;
+pub mod rotating_file;
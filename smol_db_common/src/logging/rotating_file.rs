@@ -0,0 +1,107 @@
+//! Contains `RotatingFile`, an append only file of lines that is moved aside once it reaches a size limit.
+use std::fs;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+#[derive(Debug)]
+/// An append only file of lines, which is renamed to `<name>.1` once it would grow past its size limit.
+/// Previously rotated files are renamed to `<name>.2`, `<name>.3` and so on, and the oldest is removed once there are more than the limit.
+pub struct RotatingFile {
+    path: PathBuf,
+    max_bytes: u64,
+    max_rotated_files: usize,
+    /// The open file and its length, opened by the first write
+    file: Mutex<Option<(File, u64)>>,
+}
+
+impl RotatingFile {
+    /// Creates a file at the given path, which is rotated before a line would take it past `max_bytes`, keeping up to `max_rotated_files` rotated files.
+    /// The file is only created once the first line is written.
+    pub fn new(path: &Path, max_bytes: u64, max_rotated_files: usize) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            max_bytes,
+            max_rotated_files,
+            file: Mutex::new(None),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends the line to the file followed by a new line, rotating the file first if the line would not fit.
+    pub fn write_line(&self, line: &str) -> io::Result<()> {
+        let mut contents = String::with_capacity(line.len() + 1);
+        contents.push_str(line);
+        contents.push('\n');
+        self.append(contents.as_bytes())
+    }
+
+    /// Appends the bytes to the file, rotating the file first if they would not fit.
+    /// Bytes longer than the size limit are still written, to a file of their own.
+    pub fn append(&self, bytes: &[u8]) -> io::Result<()> {
+        let mut file_lock = self.file.lock().unwrap();
+
+        let bytes_len = bytes.len() as u64;
+        if let Some((_, len)) = file_lock.as_ref() {
+            if *len > 0 && *len + bytes_len > self.max_bytes {
+                // the handle refers to the file that is about to be renamed
+                *file_lock = None;
+                self.rotate()?;
+            }
+        }
+
+        let (file, len) = match file_lock.as_mut() {
+            Some(file) => file,
+            None => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)?;
+                let len = file.metadata()?.len();
+                file_lock.insert((file, len))
+            }
+        };
+
+        file.write_all(bytes)?;
+        *len += bytes_len;
+        Ok(())
+    }
+
+    /// Returns the path of the rotated file with the given number, 1 being the most recently rotated.
+    fn rotated_path(&self, number: usize) -> PathBuf {
+        let mut file_name = self.path.file_name().unwrap_or_default().to_os_string();
+        file_name.push(format!(".{}", number));
+        self.path.with_file_name(file_name)
+    }
+
+    /// Shifts every rotated file up by one, removing the oldest, then moves the file to `<name>.1`.
+    fn rotate(&self) -> io::Result<()> {
+        if self.max_rotated_files == 0 {
+            return fs::remove_file(&self.path);
+        }
+
+        let _ = fs::remove_file(self.rotated_path(self.max_rotated_files));
+        for number in (1..self.max_rotated_files).rev() {
+            let from = self.rotated_path(number);
+            if from.exists() {
+                fs::rename(from, self.rotated_path(number + 1))?;
+            }
+        }
+        fs::rename(&self.path, self.rotated_path(1))
+    }
+
+    /// Returns the path of every file that exists, from the oldest rotated file to the file currently written to.
+    pub fn existing_paths(&self) -> Vec<PathBuf> {
+        (1..=self.max_rotated_files)
+            .rev()
+            .map(|number| self.rotated_path(number))
+            .chain([self.path.clone()])
+            .filter(|path| path.exists())
+            .collect()
+    }
+}
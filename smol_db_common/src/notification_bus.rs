@@ -0,0 +1,69 @@
+//! Contains `NotificationBus`, which fans out changes made to databases to every subscriber watching them.
+use crate::db_packets::change_event::ChangeEvent;
+use crate::db_packets::db_packet_info::DBPacketInfo;
+use crate::db_packets::key_pattern::KeyPattern;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::RwLock;
+use tracing::debug;
+
+#[derive(Debug)]
+/// A single subscription to the changes of keys matching a pattern within a database.
+struct Subscriber {
+    db: DBPacketInfo,
+    pattern: KeyPattern,
+    sender: Sender<ChangeEvent>,
+}
+
+#[derive(Debug, Default)]
+/// `NotificationBus` holds every active subscription on the server.
+/// Subscriptions are removed automatically once their receiver is dropped.
+pub struct NotificationBus {
+    subscribers: RwLock<Vec<Subscriber>>,
+}
+
+impl NotificationBus {
+    /// Subscribes to changes of keys matching the pattern in the given db, returning the receiving end of the subscription.
+    /// The pattern is expected to have already been validated, a pattern that fails to match is treated as not matching.
+    #[tracing::instrument(skip(self))]
+    pub fn subscribe(&self, db: &DBPacketInfo, pattern: KeyPattern) -> Receiver<ChangeEvent> {
+        let (sender, receiver) = channel();
+        self.subscribers.write().unwrap().push(Subscriber {
+            db: db.clone(),
+            pattern,
+            sender,
+        });
+        receiver
+    }
+
+    /// Sends the change to every subscriber of the db whose pattern matches the changed key.
+    #[tracing::instrument(skip(self))]
+    pub fn notify(&self, db: &DBPacketInfo, event: ChangeEvent) {
+        if self.subscribers.read().unwrap().is_empty() {
+            return;
+        }
+
+        let mut subscribers = self.subscribers.write().unwrap();
+
+        subscribers.retain(|subscriber| {
+            if &subscriber.db != db
+                || !subscriber
+                    .pattern
+                    .is_match(event.get_key())
+                    .unwrap_or(false)
+            {
+                return true;
+            }
+            // a failed send means the receiver was dropped, so the subscription is removed
+            let sent = subscriber.sender.send(event.clone()).is_ok();
+            if !sent {
+                debug!("Removing closed subscription to {}", subscriber.db);
+            }
+            sent
+        });
+    }
+
+    /// Returns the number of active subscriptions
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.read().unwrap().len()
+    }
+}
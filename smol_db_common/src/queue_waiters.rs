@@ -0,0 +1,60 @@
+//! Contains `QueueWaiters`, which wakes the clients waiting for a value to be pushed to an empty queue.
+use crate::db_packets::db_packet_info::DBPacketInfo;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex, Weak};
+
+/// Called once a value is pushed to the queue a client is waiting on.
+/// It is called while the queue is being pushed to, so it should only signal the waiting client, such as by notifying it.
+pub type QueueWaker = dyn Fn() + Send + Sync;
+
+/// A queue, keyed by its db and its name within the db
+type QueueKey = (DBPacketInfo, String);
+
+#[derive(Default)]
+/// `QueueWaiters` holds the wakers of every client waiting on an empty queue, so a client waiting on a queue is woken as soon as a value is pushed,
+/// instead of checking the queue over and over.
+/// Only a weak reference to each waker is kept, so a client stops waiting by dropping its waker.
+pub struct QueueWaiters {
+    waiters: Mutex<HashMap<QueueKey, Vec<Weak<QueueWaker>>>>,
+}
+
+impl QueueWaiters {
+    /// Calls the waker the next time a value is pushed to the queue in the given db, unless the waker was dropped by then.
+    /// A waker is called at most once for each time it is added, so a client that is still waiting after being woken adds it again.
+    pub fn wait_for_push(&self, db: &DBPacketInfo, queue_name: &str, waker: &Arc<QueueWaker>) {
+        let mut waiters = self.waiters.lock().unwrap();
+        let queue_waiters = waiters
+            .entry((db.clone(), queue_name.to_string()))
+            .or_default();
+        // wakers of clients that stopped waiting are removed here as well as when a value is pushed, so they do not pile up on a queue that is never pushed to
+        queue_waiters.retain(|waker| waker.strong_count() > 0);
+        queue_waiters.push(Arc::downgrade(waker));
+    }
+
+    /// Wakes every client waiting on the queue in the given db, returning how many were woken.
+    pub fn notify_push(&self, db: &DBPacketInfo, queue_name: &str) -> usize {
+        let Some(queue_waiters) = self
+            .waiters
+            .lock()
+            .unwrap()
+            .remove(&(db.clone(), queue_name.to_string()))
+        else {
+            return 0;
+        };
+
+        queue_waiters
+            .iter()
+            .filter_map(Weak::upgrade)
+            .map(|waker| waker())
+            .count()
+    }
+}
+
+impl Debug for QueueWaiters {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueueWaiters")
+            .field("queues", &self.waiters.lock().unwrap().len())
+            .finish()
+    }
+}
@@ -0,0 +1,142 @@
+//! Contains the replication log, which holds the most recent changes made on a primary server so replica servers can apply them in the same order.
+//! A replica that has fallen further behind than the log reaches is sent every database instead, followed by the changes made since.
+use crate::db::DB;
+use crate::db_packets::db_packet_info::DBPacketInfo;
+use crate::user_registry::UserRegistry;
+use crate::write_ahead_log::WalEntry;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::mpsc::Sender;
+use std::sync::Mutex;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+/// How far a replica has applied the replication log of a primary, sent when the replica connects so only newer changes are sent.
+pub struct ReplicationPosition {
+    /// Identifies the replication log, which starts over each time the primary starts
+    pub log_id: u64,
+    /// Sequence number of the next change the replica has not applied
+    pub next_sequence: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+/// An item sent from a primary to a replica.
+pub enum ReplicationItem {
+    /// Begins sending every database, the replica deletes each of its databases that is not named.
+    /// The databases follow as `Database` items, then the changes made since as `Change` items.
+    /// The salt, super admins and user accounts of the primary are sent too, as the settings of each database hold key hashes made with that salt.
+    Resync {
+        log_id: u64,
+        databases: Vec<DBPacketInfo>,
+        key_salt: String,
+        super_admins: Vec<String>,
+        #[serde(default)]
+        users: UserRegistry,
+    },
+    /// A database as it is on the primary, replacing the database on the replica
+    Database(DBPacketInfo, Box<DB>),
+    /// A change made on the primary, numbered in the order the changes were made
+    Change(u64, WalEntry),
+}
+
+#[derive(Debug, Default)]
+struct ReplicationLogState {
+    /// Sequence number given to the next change
+    next_sequence: u64,
+    /// The most recent changes, oldest first
+    entries: VecDeque<(u64, WalEntry)>,
+    /// Replicas that are sent each change as it is made
+    subscribers: Vec<Sender<ReplicationItem>>,
+}
+
+#[derive(Debug)]
+/// In memory log of the most recent changes, which replicas subscribe to.
+/// A log with a capacity of 0 is disabled, and keeps nothing.
+pub struct ReplicationLog {
+    log_id: u64,
+    capacity: usize,
+    state: Mutex<ReplicationLogState>,
+}
+
+impl Default for ReplicationLog {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl ReplicationLog {
+    /// Creates a log keeping up to `capacity` changes for replicas that reconnect to catch up with.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            log_id: Utc::now()
+                .timestamp_nanos_opt()
+                .unwrap_or_default()
+                .unsigned_abs(),
+            capacity,
+            state: Mutex::new(ReplicationLogState::default()),
+        }
+    }
+
+    /// Returns true if the log keeps changes, so replicas can subscribe to it.
+    pub const fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    pub const fn log_id(&self) -> u64 {
+        self.log_id
+    }
+
+    /// Sequence number the next change will be given.
+    pub fn next_sequence(&self) -> u64 {
+        self.state.lock().unwrap().next_sequence
+    }
+
+    /// Adds the change to the log and sends it to every subscribed replica, dropping the oldest change once the log is full.
+    pub fn push(&self, entry: WalEntry) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        let sequence = state.next_sequence;
+        state.next_sequence += 1;
+
+        // replicas that disconnected have dropped their receiver
+        state.subscribers.retain(|subscriber| {
+            subscriber
+                .send(ReplicationItem::Change(sequence, entry.clone()))
+                .is_ok()
+        });
+
+        state.entries.push_back((sequence, entry));
+        while state.entries.len() > self.capacity {
+            state.entries.pop_front();
+        }
+    }
+
+    /// Sends every change from `from_sequence` onward that is still in the log, then subscribes the sender to every new change.
+    /// Returns false without subscribing if changes from `from_sequence` onward are no longer in the log, or were never made.
+    pub fn subscribe_from(&self, from_sequence: u64, sender: Sender<ReplicationItem>) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let oldest_sequence = state.next_sequence - state.entries.len() as u64;
+        if from_sequence < oldest_sequence || from_sequence > state.next_sequence {
+            return false;
+        }
+
+        for (sequence, entry) in state
+            .entries
+            .iter()
+            .filter(|(sequence, _)| *sequence >= from_sequence)
+        {
+            if sender
+                .send(ReplicationItem::Change(*sequence, entry.clone()))
+                .is_err()
+            {
+                // the replica disconnected, so there is nothing left to subscribe
+                return true;
+            }
+        }
+
+        state.subscribers.push(sender);
+        true
+    }
+}
@@ -0,0 +1,101 @@
+//! Contains `RevisionHistory`, the values that were overwritten or removed from each key of a database, kept when the database settings turn on revision history.
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::SystemTime;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+/// A value that was stored at a key, until it was overwritten or removed.
+pub struct Revision {
+    /// Number of the revision, counting up from 1 for each key
+    pub revision: u64,
+    /// The value that was stored at the key
+    pub value: String,
+    /// Time the value was overwritten or removed
+    pub replaced_at: SystemTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+/// A revision of a key without its value, as listed by `ListRevisions`.
+pub struct RevisionInfo {
+    /// Number of the revision, counting up from 1 for each key
+    pub revision: u64,
+    /// Time the value was overwritten or removed
+    pub replaced_at: SystemTime,
+}
+
+impl From<&Revision> for RevisionInfo {
+    fn from(revision: &Revision) -> Self {
+        Self {
+            revision: revision.revision,
+            replaced_at: revision.replaced_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+/// The most recent revisions of each key, oldest first.
+/// Nothing is recorded while the maximum number of revisions is zero, which it is until the db sets it from its settings.
+pub struct RevisionHistory {
+    #[serde(skip)]
+    max_revisions: usize,
+    revisions: HashMap<String, VecDeque<Revision>>,
+}
+
+impl RevisionHistory {
+    /// Returns true if no key has any revisions.
+    pub fn is_empty(&self) -> bool {
+        self.revisions.is_empty()
+    }
+
+    /// Returns true if replaced values are being recorded.
+    pub const fn is_recording(&self) -> bool {
+        self.max_revisions > 0
+    }
+
+    /// Sets the number of revisions kept for each key, dropping the oldest revisions of keys that have more.
+    pub fn set_max_revisions(&mut self, max_revisions: usize) {
+        if self.max_revisions == max_revisions {
+            return;
+        }
+        self.max_revisions = max_revisions;
+        self.revisions.retain(|_, revisions| {
+            while revisions.len() > max_revisions {
+                revisions.pop_front();
+            }
+            !revisions.is_empty()
+        });
+    }
+
+    /// Records the value that was replaced at the key, dropping the oldest revision of the key if it has too many.
+    pub fn record(&mut self, key: &str, replaced: String) {
+        if !self.is_recording() {
+            return;
+        }
+        let revisions = self.revisions.entry(key.to_string()).or_default();
+        let revision = revisions.back().map_or(1, |last| last.revision + 1);
+        revisions.push_back(Revision {
+            revision,
+            value: replaced,
+            replaced_at: SystemTime::now(),
+        });
+        if revisions.len() > self.max_revisions {
+            revisions.pop_front();
+        }
+    }
+
+    /// Returns the revisions of the key, oldest first.
+    pub fn list(&self, key: &str) -> Vec<RevisionInfo> {
+        self.revisions
+            .get(key)
+            .map(|revisions| revisions.iter().map(RevisionInfo::from).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the revision of the key with the given number, if it is still kept.
+    pub fn get(&self, key: &str, revision: u64) -> Option<&Revision> {
+        self.revisions
+            .get(key)?
+            .iter()
+            .find(|kept| kept.revision == revision)
+    }
+}
@@ -0,0 +1,91 @@
+//! Contains `SessionStore`, the short lived session tokens a client can resume its identity with instead of sending its key again.
+use crate::encryption::key_hash::{generate_token, KeyHasher};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// How long a session token is valid for when no other time to live is configured
+pub const DEFAULT_SESSION_TTL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone)]
+struct Session {
+    /// Key hash the session was created with, which resuming the session sets as the client key
+    client_key: String,
+    expires: Instant,
+}
+
+#[derive(Debug)]
+/// Session tokens of a server, kept only in memory, so every token is invalidated when the server restarts.
+/// Only the hash of each token is kept, so the tokens can not be read back out of the store.
+pub struct SessionStore {
+    ttl: Duration,
+    hasher: KeyHasher,
+    sessions: RwLock<HashMap<String, Session>>,
+}
+
+impl SessionStore {
+    /// Returns an empty store whose tokens expire after the given time to live
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            hasher: KeyHasher::default(),
+            sessions: RwLock::default(),
+        }
+    }
+
+    /// Returns how long a token is valid for after it is created
+    pub const fn get_ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Creates a session for the client key, returning the token that resumes it.
+    pub fn create_session(&self, client_key: &str) -> String {
+        let token = generate_token();
+        self.sessions.write().unwrap().insert(
+            self.hasher.hash_key(&token),
+            Session {
+                client_key: client_key.to_string(),
+                expires: Instant::now() + self.ttl,
+            },
+        );
+        token
+    }
+
+    /// Returns the client key of the session the token belongs to, if the session has not expired.
+    pub fn resume_session(&self, token: &str) -> Option<String> {
+        let token_hash = self.hasher.hash_key(token);
+        let mut sessions = self.sessions.write().unwrap();
+        match sessions.get(&token_hash) {
+            Some(session) if session.expires > Instant::now() => Some(session.client_key.clone()),
+            Some(_) => {
+                sessions.remove(&token_hash);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Ends the session the token belongs to, returning true if there was one.
+    pub fn end_session(&self, token: &str) -> bool {
+        self.sessions
+            .write()
+            .unwrap()
+            .remove(&self.hasher.hash_key(token))
+            .is_some()
+    }
+
+    /// Removes every expired session, returning the number removed.
+    pub fn remove_expired(&self) -> usize {
+        let now = Instant::now();
+        let mut sessions = self.sessions.write().unwrap();
+        let len_old = sessions.len();
+        sessions.retain(|_, session| session.expires > now);
+        len_old - sessions.len()
+    }
+}
+
+impl Default for SessionStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_SESSION_TTL)
+    }
+}
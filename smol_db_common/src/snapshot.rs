@@ -0,0 +1,30 @@
+//! Contains snapshots, which hold every database and the db list as they were at a single point in time.
+//! Snapshots are saved to the backups directory within the data directory, and can be restored when the server starts.
+use crate::db::DB;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Name of the directory within the data directory that snapshots are saved in.
+pub const BACKUP_DIR_NAME: &str = "backups";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+/// Every database on the server, along with the db list they belong to.
+pub struct Snapshot {
+    /// When the snapshot was created
+    pub created_at: DateTime<Utc>,
+    /// The db list, serialized the same way it is saved to the data directory
+    pub db_list: serde_json::Value,
+    /// Every database in the db list, keyed by name
+    pub databases: BTreeMap<String, DB>,
+}
+
+impl Snapshot {
+    /// The file name the snapshot is saved as, which sorts in the order snapshots were created.
+    pub fn file_name(&self) -> String {
+        format!(
+            "snapshot_{}.json",
+            self.created_at.format("%Y-%m-%dT%H-%M-%S%.3fZ")
+        )
+    }
+}
@@ -0,0 +1,89 @@
+//! Contains `SortedList`, a collection of unique members ordered by their score, used for leaderboard style data.
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// A member of a `SortedList` along with the score it is ordered by.
+pub struct ScoredMember {
+    pub member: String,
+    pub score: f64,
+}
+
+impl ScoredMember {
+    pub fn new(member: String, score: f64) -> Self {
+        Self { member, score }
+    }
+
+    /// Orders by score, then by member so members with equal scores have a stable order.
+    fn cmp_order(&self, score: f64, member: &str) -> Ordering {
+        self.score
+            .total_cmp(&score)
+            .then_with(|| self.member.as_str().cmp(member))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+/// A collection of unique members, kept in ascending order of score.
+pub struct SortedList {
+    entries: Vec<ScoredMember>,
+}
+
+impl SortedList {
+    /// Inserts the member with the given score, replacing the score of the member if it was already in the list.
+    /// Returns the rank of the member after it was inserted.
+    pub fn insert(&mut self, member: String, score: f64) -> usize {
+        self.remove(&member);
+        let rank = self
+            .entries
+            .partition_point(|entry| entry.cmp_order(score, &member) == Ordering::Less);
+        self.entries.insert(rank, ScoredMember::new(member, score));
+        rank
+    }
+
+    /// Removes the member, returning true if it was in the list.
+    pub fn remove(&mut self, member: &str) -> bool {
+        match self.rank(member) {
+            Some(rank) => {
+                self.entries.remove(rank);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the rank of the member, which is its index in ascending order of score.
+    pub fn rank(&self, member: &str) -> Option<usize> {
+        self.entries.iter().position(|entry| entry.member == member)
+    }
+
+    /// Returns every member with a score between min and max inclusive, in ascending order of score.
+    pub fn range_by_score(&self, min: f64, max: f64) -> &[ScoredMember] {
+        let start = self
+            .entries
+            .partition_point(|entry| entry.score.total_cmp(&min) == Ordering::Less);
+        let end = self
+            .entries
+            .partition_point(|entry| entry.score.total_cmp(&max) != Ordering::Greater);
+        self.entries.get(start..end).unwrap_or_default()
+    }
+
+    /// Returns up to `count` of the highest scoring members, in descending order of score.
+    pub fn top(&self, count: usize) -> Vec<ScoredMember> {
+        self.entries.iter().rev().take(count).cloned().collect()
+    }
+
+    /// Returns the number of members in the list.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if there are no members in the list.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over every member, in ascending order of score.
+    pub fn iter(&self) -> impl Iterator<Item = &ScoredMember> {
+        self.entries.iter()
+    }
+}
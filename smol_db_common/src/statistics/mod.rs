@@ -3,13 +3,19 @@ use crate::statistics::previous_time_diff::PreviousTimeDifferences;
 use crate::statistics::time_of_usage::UsageTimeList;
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
-use std::time::SystemTime;
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
 
 mod previous_time_diff;
+mod server_statistics;
+mod statistics_export;
 mod time_of_usage;
+
+pub use server_statistics::ServerStatistics;
+pub use statistics_export::StatisticsExport;
 const MIN_TIME_DIFFERENCE: f32 = 0.25;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[non_exhaustive]
 /// A struct representing the statistics stored from a `DB`
 /// Uses rolling average for access time
@@ -22,6 +28,9 @@ pub struct DBStatistics {
     /// List of system times recorded at each request, stores a maximum number of system times, but does not have a `MIN_TIME_DIFFERENCE`
     #[serde(default)]
     usage_time_list: UsageTimeList,
+    /// Number of times each key was accessed, only counted when the `DB` settings track key hits
+    #[serde(default)]
+    key_hits: HashMap<String, u64>,
 }
 
 impl DBStatistics {
@@ -31,6 +40,7 @@ impl DBStatistics {
             total_requests: 0,
             rolling_average: PreviousTimeDifferences::new(rolling_average_length),
             usage_time_list: UsageTimeList::new(usage_list_length),
+            key_hits: HashMap::new(),
         }
     }
 
@@ -40,6 +50,12 @@ impl DBStatistics {
         self.rolling_average.get_rolling_average()
     }
 
+    /// Returns the times between requests that make up the average access time, the most recent at the end of the list
+    #[tracing::instrument]
+    pub fn get_access_time_gaps(&self) -> &Vec<Duration> {
+        self.rolling_average.get_list()
+    }
+
     /// Returns the total number of requests the given `DB` has
     #[tracing::instrument]
     pub fn get_total_req(&self) -> u64 {
@@ -52,6 +68,33 @@ impl DBStatistics {
         self.usage_time_list.get_list()
     }
 
+    /// Returns the number of times each key was accessed
+    #[tracing::instrument]
+    pub fn get_key_hits(&self) -> &HashMap<String, u64> {
+        &self.key_hits
+    }
+
+    /// Returns the `n` most accessed keys and their number of accesses, most accessed first
+    #[tracing::instrument]
+    pub fn get_hot_keys(&self, n: usize) -> Vec<(String, u64)> {
+        let mut hot_keys: Vec<(String, u64)> = self
+            .key_hits
+            .iter()
+            .map(|(key, hits)| (key.clone(), *hits))
+            .collect();
+        hot_keys.sort_by(|(key_a, hits_a), (key_b, hits_b)| {
+            hits_b.cmp(hits_a).then_with(|| key_a.cmp(key_b))
+        });
+        hot_keys.truncate(n);
+        hot_keys
+    }
+
+    /// Counts an access of the given key
+    #[tracing::instrument]
+    pub fn add_key_hit(&mut self, key: &str) {
+        *self.key_hits.entry(key.to_string()).or_insert(0) += 1;
+    }
+
     /// Adds the given system time to the average, provided it is below the `MIN_TIME_DIFFERENCE`
     /// If so, the `current_average_time` is updated as well as the `total_requests`
     #[tracing::instrument]
@@ -72,6 +115,7 @@ impl Default for DBStatistics {
             total_requests: 0,
             rolling_average: PreviousTimeDifferences::default(),
             usage_time_list: UsageTimeList::default(),
+            key_hits: HashMap::new(),
         }
     }
 }
@@ -105,4 +149,23 @@ mod tests {
             assert_eq!(s.get_total_req(), (index + 1) as u64);
         }
     }
+
+    #[test]
+    fn test_access_time_gaps() {
+        let mut s = DBStatistics::new(3, 10);
+
+        for secs in [1, 0, 2, 3, 4] {
+            s.rolling_average.add_new_time(Duration::from_secs(secs));
+        }
+
+        // gaps below the minimum time difference are not recorded, and only the most recent gaps are kept
+        assert_eq!(
+            s.get_access_time_gaps(),
+            &vec![
+                Duration::from_secs(2),
+                Duration::from_secs(3),
+                Duration::from_secs(4)
+            ]
+        );
+    }
 }
@@ -3,7 +3,7 @@ use crate::statistics::MIN_TIME_DIFFERENCE;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub(super) struct PreviousTimeDifferences {
     list: Vec<Duration>,
     rolling_average_max: u32,
@@ -34,6 +34,12 @@ impl PreviousTimeDifferences {
         }
     }
 
+    /// Returns the times between requests that are averaged, the most recent at the end of the list
+    #[tracing::instrument]
+    pub fn get_list(&self) -> &Vec<Duration> {
+        &self.list
+    }
+
     #[tracing::instrument]
     pub fn get_rolling_average(&self) -> f32 {
         if self.list.is_empty() {
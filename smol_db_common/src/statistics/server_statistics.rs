@@ -0,0 +1,66 @@
+//! Module containing `ServerStatistics`, statistics of every request made to a server across all databases
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+/// A struct representing the statistics of every request made to a server, in addition to the `DBStatistics` of each `DB`
+pub struct ServerStatistics {
+    /// The total number of requests the server has handled
+    total_requests: u64,
+    /// Number of requests handled of each packet type, keyed by the name of the packet
+    packet_counts: HashMap<String, u64>,
+    /// Number of requests that were responded to with an error
+    error_count: u64,
+    /// Bytes of packets read from clients
+    bytes_in: u64,
+    /// Bytes of responses written to clients
+    bytes_out: u64,
+}
+
+impl ServerStatistics {
+    /// Returns the total number of requests the server has handled
+    pub const fn get_total_req(&self) -> u64 {
+        self.total_requests
+    }
+
+    /// Returns the number of requests handled of each packet type, keyed by the name of the packet
+    pub const fn get_packet_counts(&self) -> &HashMap<String, u64> {
+        &self.packet_counts
+    }
+
+    /// Returns the number of requests that were responded to with an error
+    pub const fn get_error_count(&self) -> u64 {
+        self.error_count
+    }
+
+    /// Returns the bytes of packets read from clients
+    pub const fn get_bytes_in(&self) -> u64 {
+        self.bytes_in
+    }
+
+    /// Returns the bytes of responses written to clients
+    pub const fn get_bytes_out(&self) -> u64 {
+        self.bytes_out
+    }
+
+    /// Records a handled request of the given packet type, and the size of the packet and its response.
+    pub fn record_request(
+        &mut self,
+        packet_name: &str,
+        bytes_in: usize,
+        bytes_out: usize,
+        is_error: bool,
+    ) {
+        self.total_requests += 1;
+        *self
+            .packet_counts
+            .entry(packet_name.to_string())
+            .or_insert(0) += 1;
+        if is_error {
+            self.error_count += 1;
+        }
+        self.bytes_in += bytes_in as u64;
+        self.bytes_out += bytes_out as u64;
+    }
+}
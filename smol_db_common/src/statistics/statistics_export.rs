@@ -0,0 +1,62 @@
+//! Module containing `StatisticsExport`, every statistic of a server gathered in one struct by the `ExportStats` packet
+use crate::statistics::{DBStatistics, ServerStatistics};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+/// The statistics of a server, and of each of its databases keyed by name
+pub struct StatisticsExport {
+    pub server: ServerStatistics,
+    pub databases: BTreeMap<String, DBStatistics>,
+}
+
+impl StatisticsExport {
+    /// Returns the statistics as csv with a `database,metric,key,value` header row.
+    /// The database is empty for server wide metrics, and the key is only set for metrics counted per packet type or per db key.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("database,metric,key,value\r\n");
+        let mut row = |database: &str, metric: &str, key: &str, value: &dyn std::fmt::Display| {
+            let _ = write!(
+                csv,
+                "{},{},{},{}\r\n",
+                csv_field(database),
+                metric,
+                csv_field(key),
+                value
+            );
+        };
+
+        row("", "total_requests", "", &self.server.get_total_req());
+        row("", "error_count", "", &self.server.get_error_count());
+        row("", "bytes_in", "", &self.server.get_bytes_in());
+        row("", "bytes_out", "", &self.server.get_bytes_out());
+        let packet_counts: BTreeMap<_, _> = self.server.get_packet_counts().iter().collect();
+        for (packet_name, count) in packet_counts {
+            row("", "packet_count", packet_name, count);
+        }
+
+        for (db_name, statistics) in &self.databases {
+            row(db_name, "total_requests", "", &statistics.get_total_req());
+            row(db_name, "average_time", "", &statistics.get_avg_time());
+            if let Some(last_used) = statistics.get_usage_time_list().last() {
+                row(db_name, "last_used", "", &last_used.to_rfc3339());
+            }
+            let key_hits: BTreeMap<_, _> = statistics.get_key_hits().iter().collect();
+            for (key, hits) in key_hits {
+                row(db_name, "key_hits", key, hits);
+            }
+        }
+
+        csv
+    }
+}
+
+/// Quotes the field if it contains a comma, quote, or line break, doubling any quotes inside it.
+fn csv_field(field: &str) -> std::borrow::Cow<'_, str> {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\"")).into()
+    } else {
+        field.into()
+    }
+}
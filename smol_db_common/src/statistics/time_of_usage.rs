@@ -5,7 +5,7 @@ use std::time::SystemTime;
 
 const MIN_TIME_DIFFERENCE: i64 = 1;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 /// A list of times that the database carrying this statistics struct has had users connect at
 /// The most recent connection time is at the end of the list
 pub(super) struct UsageTimeList {
@@ -0,0 +1,60 @@
+//! Contains the trash, which deleted databases are moved to so they can be restored until their retention period ends.
+use crate::db_packets::db_packet_info::DBPacketInfo;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::time::Duration;
+
+/// Name of the directory within the data directory that deleted databases are moved to.
+pub const TRASH_DIR_NAME: &str = ".trash";
+
+/// How long a deleted database is kept in the trash when no other retention period is configured
+pub const DEFAULT_TRASH_RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H-%M-%S%.3fZ";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A database in the trash, saved as `<timestamp>_<name>` within the trash directory.
+pub struct TrashEntry {
+    /// Name of the database that was deleted
+    pub db_name: String,
+    /// When the database was deleted
+    pub deleted_at: DateTime<Utc>,
+}
+
+impl TrashEntry {
+    /// Returns an entry for the database deleted now
+    pub fn new(db_name: &str) -> Self {
+        Self {
+            db_name: db_name.to_string(),
+            deleted_at: Utc::now(),
+        }
+    }
+
+    /// The file name the database is saved as in the trash, which sorts in the order databases were deleted.
+    pub fn file_name(&self) -> String {
+        format!(
+            "{}_{}",
+            self.deleted_at.format(TIMESTAMP_FORMAT),
+            DBPacketInfo::new(&self.db_name).file_name()
+        )
+    }
+
+    /// Reads the entry from a file name in the trash, the timestamp holds no `_` so the name is everything after the first one.
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        let (timestamp, db_name) = file_name.split_once('_')?;
+        let deleted_at = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()?;
+        Some(Self {
+            db_name: DBPacketInfo::from_file_name(db_name)
+                .get_db_name()
+                .to_string(),
+            deleted_at: deleted_at.and_utc(),
+        })
+    }
+
+    /// Returns true if the entry was deleted longer ago than the retention period.
+    pub fn is_expired(&self, retention: Duration) -> bool {
+        Utc::now()
+            .signed_duration_since(self.deleted_at)
+            .to_std()
+            .is_ok_and(|age| age > retention)
+    }
+}
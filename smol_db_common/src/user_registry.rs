@@ -0,0 +1,109 @@
+//! Contains `UserRegistry`, the named user accounts of a server, which a client logs in to with a username and password instead of setting a key.
+use crate::encryption::key_hash::KeyHasher;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Prefix of the key a client is given when logging in to an account, followed by the username.
+/// A key set by a client is always hashed, so it can never start with this prefix.
+pub const ACCOUNT_KEY_PREFIX: &str = "account$";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+/// A named user account, with a salted hash of its password.
+pub struct UserAccount {
+    password_salt: String,
+    password_hash: String,
+    disabled: bool,
+}
+
+impl UserAccount {
+    /// Returns a new enabled account with the given password
+    fn new(password: &str) -> Self {
+        let hasher = KeyHasher::default();
+        Self {
+            password_hash: hasher.hash_key(password),
+            password_salt: hasher.get_salt().to_string(),
+            disabled: false,
+        }
+    }
+
+    /// Returns true if the password is the password of this account
+    fn is_password(&self, password: &str) -> bool {
+        KeyHasher::new(self.password_salt.clone()).hash_key(password) == self.password_hash
+    }
+
+    /// Returns true if the account has been disabled, and can not be logged in to
+    pub const fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+/// The user accounts of a server, keyed by username.
+/// Logging in to an account gives the client the key of the account, which is bound to roles the same way as any other key,
+/// by adding it to the admin or user list of a database, or to the super admin list.
+pub struct UserRegistry {
+    users: BTreeMap<String, UserAccount>,
+}
+
+impl UserRegistry {
+    /// Returns the key a client is given when logging in to the account with the given username
+    pub fn account_key(username: &str) -> String {
+        format!("{ACCOUNT_KEY_PREFIX}{username}")
+    }
+
+    /// Returns true if the key is the key of an account, rather than a key set by a client
+    pub fn is_account_key(key: &str) -> bool {
+        key.starts_with(ACCOUNT_KEY_PREFIX)
+    }
+
+    /// Creates an account with the given username and password.
+    /// Returns false if the username is empty or already taken.
+    pub fn create_user(&mut self, username: &str, password: &str) -> bool {
+        if username.is_empty() || self.users.contains_key(username) {
+            return false;
+        }
+        self.users
+            .insert(username.to_string(), UserAccount::new(password));
+        true
+    }
+
+    /// Disables or enables the account with the given username.
+    /// Returns false if there is no account with the username.
+    pub fn set_disabled(&mut self, username: &str, disabled: bool) -> bool {
+        match self.users.get_mut(username) {
+            Some(account) => {
+                account.disabled = disabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the key of the account if the password is correct and the account is not disabled.
+    pub fn login(&self, username: &str, password: &str) -> Option<String> {
+        self.users
+            .get(username)
+            .filter(|account| !account.is_disabled() && account.is_password(password))
+            .map(|_| Self::account_key(username))
+    }
+
+    /// Returns false if the key is the key of an account that has been disabled or no longer exists.
+    /// Keys that are not account keys are always active.
+    pub fn is_active_key(&self, key: &str) -> bool {
+        match key.strip_prefix(ACCOUNT_KEY_PREFIX) {
+            Some(username) => self
+                .users
+                .get(username)
+                .is_some_and(|account| !account.is_disabled()),
+            None => true,
+        }
+    }
+
+    /// Returns the username of every account, and whether it is disabled
+    pub fn list_users(&self) -> Vec<(String, bool)> {
+        self.users
+            .iter()
+            .map(|(username, account)| (username.clone(), account.is_disabled()))
+            .collect()
+    }
+}
@@ -0,0 +1,213 @@
+//! Contains the write-ahead log, which records every change made to a database before the change is acknowledged.
+//! Changes made since the last save are replayed from the log when the db list is loaded, so they survive a crash.
+use crate::db::DB;
+use crate::db_bytes::DBBytes;
+use crate::db_file;
+use crate::db_list::DEFAULT_DATA_DIR;
+use crate::db_packets::db_metadata::DBMetadata;
+use crate::db_packets::db_packet_info::DBPacketInfo;
+use crate::db_packets::db_settings::DBSettings;
+use crate::sorted_list::SortedList;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+/// File name the write-ahead log is saved as within the data directory.
+pub const WAL_FILE_NAME: &str = "wal.log";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+/// A single change to a database.
+/// Changes to lists, sets, sorted lists and settings record the whole value after the change, so replaying a change more than once is harmless.
+pub enum WalOp {
+    /// A value was written to a key
+    Write { key: String, value: String },
+    /// The value at a key was removed
+    Delete { key: String },
+    /// A list was changed, an empty list means the list was removed
+    List { name: String, values: Vec<String> },
+    /// A set was changed, an empty set means the set was removed
+    Set {
+        name: String,
+        members: BTreeSet<String>,
+    },
+    /// A sorted list was changed, an empty sorted list means the sorted list was removed
+    SortedList { name: String, list: SortedList },
+    /// A binary value was written to a key, or removed when there is no value
+    Bytes { key: String, bytes: Option<DBBytes> },
+    /// The settings of the database were changed
+    Settings(DBSettings),
+    /// The description or tags of the database were changed
+    Metadata(DBMetadata),
+    /// The database was created, or replaced, with the given content and settings
+    CreateDB(Box<DB>),
+    /// The database was deleted
+    DeleteDB,
+    /// The database was renamed to the given name
+    RenameDB(DBPacketInfo),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+/// A change to the database with the given name, stored as a single line in the log.
+pub struct WalEntry {
+    pub db: DBPacketInfo,
+    pub op: WalOp,
+}
+
+impl WalOp {
+    /// Applies the change to the database, database level changes such as `DeleteDB` do nothing here.
+    pub fn apply(self, db: &mut DB) {
+        let content = db.get_content_mut();
+        match self {
+            Self::Write { key, value } => {
+                content.insert(key, value);
+            }
+            Self::Delete { key } => {
+                content.remove(&key);
+            }
+            Self::List { name, values } => {
+                if values.is_empty() {
+                    content.remove_list(&name);
+                } else {
+                    content.lists.insert(name, values);
+                }
+            }
+            Self::Set { name, members } => {
+                if members.is_empty() {
+                    content.sets.remove(&name);
+                } else {
+                    content.sets.insert(name, members);
+                }
+            }
+            Self::SortedList { name, list } => {
+                if list.is_empty() {
+                    content.sorted_lists.remove(&name);
+                } else {
+                    content.sorted_lists.insert(name, list);
+                }
+            }
+            Self::Bytes { key, bytes } => match bytes {
+                Some(bytes) => {
+                    content.binary.insert(key, bytes);
+                }
+                None => {
+                    content.binary.remove(&key);
+                }
+            },
+            Self::Settings(settings) => db.set_settings(settings),
+            Self::Metadata(metadata) => db.set_metadata(metadata),
+            Self::DeleteDB | Self::RenameDB(_) | Self::CreateDB(_) => {}
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Append only log of database changes, the file is only created once the first change is appended.
+pub struct WriteAheadLog {
+    path: PathBuf,
+    file: Mutex<Option<File>>,
+}
+
+impl Default for WriteAheadLog {
+    fn default() -> Self {
+        Self::new(Path::new(DEFAULT_DATA_DIR))
+    }
+}
+
+impl WriteAheadLog {
+    /// Creates a log stored in the given data directory.
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            path: data_dir.join(WAL_FILE_NAME),
+            file: Mutex::new(None),
+        }
+    }
+
+    /// Appends the entry to the log, and waits for it to reach the disk.
+    #[tracing::instrument(skip(self))]
+    pub fn append(&self, entry: &WalEntry) -> io::Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+
+        let mut file_lock = self.file.lock().unwrap();
+        let file = match file_lock.as_mut() {
+            Some(file) => file,
+            None => file_lock.insert(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .read(true)
+                    .open(&self.path)?,
+            ),
+        };
+
+        file.write_all(line.as_bytes())?;
+        file.sync_data()
+    }
+
+    /// The number of bytes in the log, used to mark which entries a checkpoint has saved.
+    pub fn len(&self) -> u64 {
+        match self.file.lock().unwrap().as_ref() {
+            Some(file) => file.metadata().map_or(0, |metadata| metadata.len()),
+            None => self.path.metadata().map_or(0, |metadata| metadata.len()),
+        }
+    }
+
+    /// Returns true if the log has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Reads every entry in the log in the order they were appended.
+    /// An entry that was only partially written, such as when the server crashed while appending it, ends the log.
+    #[tracing::instrument(skip(self))]
+    pub fn read_entries(&self) -> Vec<WalEntry> {
+        let Ok(file) = File::open(&self.path) else {
+            return vec![];
+        };
+
+        let mut entries = vec![];
+        for line in BufReader::new(file).lines() {
+            let entry = line
+                .map_err(|err| err.to_string())
+                .and_then(|line| serde_json::from_str(&line).map_err(|err| err.to_string()));
+            match entry {
+                Ok(entry) => entries.push(entry),
+                Err(err) => {
+                    warn!("Write-ahead log ended with an unreadable entry: {}", err);
+                    break;
+                }
+            }
+        }
+        info!("Read {} entries from the write-ahead log", entries.len());
+        entries
+    }
+
+    /// Removes the first `saved_len` bytes of the log, which hold entries that have been saved to their database files.
+    /// Entries appended after `saved_len` was taken are kept.
+    /// The remaining entries are written to a new file that replaces the log, so no entry is lost if the process stops part way through.
+    #[tracing::instrument(skip(self))]
+    pub fn truncate_saved(&self, saved_len: u64) -> io::Result<()> {
+        let mut file_lock = self.file.lock().unwrap();
+
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            // there is nothing to truncate if no entry was ever appended
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err),
+        };
+
+        let mut remaining = vec![];
+        file.seek(SeekFrom::Start(saved_len))?;
+        file.read_to_end(&mut remaining)?;
+
+        db_file::replace_file(&self.path, &remaining)?;
+        // the old file handle still refers to the replaced file, the new file is opened by the next append
+        *file_lock = None;
+        Ok(())
+    }
+}
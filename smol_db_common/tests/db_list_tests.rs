@@ -2,8 +2,18 @@
 #[allow(unused_imports, clippy::bool_assert_comparison)]
 mod tests {
 
+    use smol_db_common::encryption::key_hash::{KeyHasher, KEY_HASH_VERSION};
+    use smol_db_common::encryption::server_encrypt::{ServerKey, SERVER_KEY_FILE_NAME};
+    use smol_db_common::logging::audit::{AuditLog, AuditTarget};
+    use smol_db_common::logging::logger::{LogLevel, Logger};
     use smol_db_common::prelude::*;
-    use std::collections::HashMap;
+    use smol_db_common::queue_waiters::QueueWaker;
+    use smol_db_common::replication::{ReplicationLog, ReplicationPosition};
+    use smol_db_common::session_store::SessionStore;
+    use smol_db_common::trash::{DEFAULT_TRASH_RETENTION, TRASH_DIR_NAME};
+    use smol_db_common::user_registry::UserRegistry;
+    use smol_db_common::write_ahead_log::WriteAheadLog;
+    use std::collections::{BTreeMap, HashMap};
     use std::fs::File;
     use std::hash::Hash;
     use std::path::PathBuf;
@@ -27,9 +37,27 @@ mod tests {
     fn get_db_list_for_testing() -> DBList {
         DBList {
             list: RwLock::new(vec![]),
-            cache: RwLock::new(HashMap::new()),
+            cache: Default::default(),
             super_admin_hash_list: RwLock::new(vec![]),
+            namespaces: Default::default(),
             server_key: Default::default(),
+            notification_bus: Default::default(),
+            channels: Default::default(),
+            queue_waiters: Default::default(),
+            data_dir: PathBuf::from("./data"),
+            saving_enabled: true,
+            wal: WriteAheadLog::new(&PathBuf::from("./data")),
+            replication: Default::default(),
+            quota: Default::default(),
+            trash_retention: DEFAULT_TRASH_RETENTION,
+            cache_memory_budget: None,
+            key_hasher: Default::default(),
+            key_hash_version: KEY_HASH_VERSION,
+            bootstrap_token: Default::default(),
+            user_registry: Default::default(),
+            sessions: Default::default(),
+            #[cfg(feature = "statistics")]
+            server_statistics: Default::default(),
         }
     }
 
@@ -178,7 +206,7 @@ mod tests {
             .unwrap();
 
         match write_response2 {
-            SuccessNoData => {
+            SuccessNoData | SuccessData(_) => {
                 panic!("Bad response from write db");
             }
             SuccessReply(data) => {
@@ -194,7 +222,7 @@ mod tests {
             )
             .unwrap();
         match read_response {
-            SuccessNoData => {
+            SuccessNoData | SuccessData(_) => {
                 panic!("No data read from location");
             }
             SuccessReply(data) => {
@@ -206,7 +234,7 @@ mod tests {
             .read_db(&db_pack_info, &db_location, &TEST_USER_KEY.to_string())
             .unwrap();
         match read_user_perms_response {
-            SuccessNoData => {
+            SuccessNoData | SuccessData(_) => {
                 panic!("Unable to read with user perms");
             }
             SuccessReply(data) => {
@@ -281,7 +309,7 @@ mod tests {
         let read_with_new_user_response =
             db_list.read_db(&db_pack_info, &db_location, &new_user_key.to_string());
         match read_with_new_user_response.unwrap() {
-            SuccessNoData => {
+            SuccessNoData | SuccessData(_) => {
                 panic!("No data read from read with new user");
             }
             SuccessReply(data) => {
@@ -416,13 +444,12 @@ mod tests {
         {
             let db_list_response = db_list.list_db();
             match db_list_response.unwrap() {
-                SuccessNoData => {
-                    panic!("Unexpected db response");
-                }
-                SuccessReply(data) => {
-                    let v = serde_json::from_str::<Vec<DBPacketInfo>>(&data).unwrap();
+                SuccessData(ResponseData::DBList(v)) => {
                     assert_eq!(v.len(), 0);
                 }
+                _ => {
+                    panic!("Unexpected db response");
+                }
             }
         }
 
@@ -436,11 +463,12 @@ mod tests {
         {
             let db_list_response = db_list.list_db();
             match db_list_response.unwrap() {
-                SuccessNoData => {}
-                SuccessReply(data) => {
-                    let v = serde_json::from_str::<Vec<DBPacketInfo>>(&data).unwrap();
+                SuccessData(ResponseData::DBList(v)) => {
                     assert_eq!(v.len(), 1);
                 }
+                _ => {
+                    panic!("Unexpected db response");
+                }
             }
         }
 
@@ -448,6 +476,158 @@ mod tests {
         assert_eq!(delete_response.unwrap(), SuccessNoData);
     }
 
+    #[test]
+    fn test_list_accessible_db() {
+        let admin_key = TEST_SUPER_ADMIN_KEY.to_string();
+        let user_key = TEST_USER_KEY.to_string();
+        let other_key = "test_other_key".to_string();
+
+        let mut db_list = get_db_list_for_testing();
+        db_list.saving_enabled = false;
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(admin_key.clone());
+
+        // only users of the private db can see it, anyone can see the public db, and nobody but super admins can see the write only db
+        let private_settings = get_db_test_settings();
+        let public_settings = DBSettings::new(
+            Duration::from_secs(30),
+            (true, false, false),
+            (true, false, false),
+            vec![],
+            vec![],
+        );
+        let write_only_settings = DBSettings::new(
+            Duration::from_secs(30),
+            (false, true, false),
+            (false, true, false),
+            vec![],
+            vec![user_key.clone()],
+        );
+        db_list
+            .create_db("test_accessible_private", private_settings, &admin_key)
+            .unwrap();
+        db_list
+            .create_db("test_accessible_public", public_settings, &admin_key)
+            .unwrap();
+        db_list
+            .create_db(
+                "test_accessible_write_only",
+                write_only_settings,
+                &admin_key,
+            )
+            .unwrap();
+
+        let accessible_names = |key: &String| -> Vec<String> {
+            let SuccessData(ResponseData::DBList(listings)) =
+                db_list.list_accessible_db(key).unwrap()
+            else {
+                panic!("Unexpected db response");
+            };
+            let mut names: Vec<String> = listings
+                .into_iter()
+                .map(|listing| {
+                    assert!(listing.metadata.is_some());
+                    listing.info.get_db_name().to_string()
+                })
+                .collect();
+            names.sort();
+            names
+        };
+
+        assert_eq!(
+            accessible_names(&admin_key),
+            vec![
+                "test_accessible_private",
+                "test_accessible_public",
+                "test_accessible_write_only"
+            ]
+        );
+        assert_eq!(
+            accessible_names(&user_key),
+            vec!["test_accessible_private", "test_accessible_public"]
+        );
+        assert_eq!(accessible_names(&other_key), vec!["test_accessible_public"]);
+    }
+
+    #[test]
+    fn test_effective_permissions() {
+        let admin_key = TEST_SUPER_ADMIN_KEY.to_string();
+        let user_key = TEST_USER_KEY.to_string();
+        let other_key = "test_other_key".to_string();
+        let limited_key = "test_limited_key".to_string();
+
+        let mut db_list = get_db_list_for_testing();
+        db_list.saving_enabled = false;
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(admin_key.clone());
+
+        // others can only read, which also lets them stream, users can read, write and list
+        let db_info = DBPacketInfo::new("test_effective_permissions");
+        let settings = DBSettings::new(
+            Duration::from_secs(30),
+            (true, false, false),
+            (true, true, true),
+            vec![],
+            vec![user_key.clone(), limited_key.clone()],
+        );
+        db_list
+            .create_db(db_info.get_db_name(), settings, &admin_key)
+            .unwrap();
+        db_list
+            .add_key_acl(
+                &db_info,
+                KeyAclRule::new(&limited_key, "jobs/", true, false),
+                &admin_key,
+            )
+            .unwrap();
+
+        let effective = |key: &String| -> EffectivePermissions {
+            let SuccessData(ResponseData::EffectivePermissions(permissions)) =
+                db_list.get_effective_permissions(&db_info, key).unwrap()
+            else {
+                panic!("Unexpected db response");
+            };
+            permissions
+        };
+
+        let admin = effective(&admin_key);
+        assert_eq!(admin.role, SuperAdmin);
+        assert_eq!(admin.permissions, Permissions::all());
+
+        let user = effective(&user_key);
+        assert_eq!(user.role, User);
+        assert_eq!(user.permissions, Permissions::from_rwx(true, true, true));
+        assert!(!user.can(Permission::ManageSettings));
+        assert!(user.key_acls.is_empty());
+
+        let other = effective(&other_key);
+        assert_eq!(other.role, Other);
+        assert!(other.can(Permission::Read) && other.can(Permission::Stream));
+        assert!(!other.can(Permission::Write) && !other.can(Permission::List));
+
+        // a key limited by rules has no db wide permissions, only the entries its rules allow
+        let limited = effective(&limited_key);
+        assert_eq!(limited.role, User);
+        assert_eq!(limited.permissions, Permissions::none());
+        assert_eq!(limited.key_acls.len(), 1);
+        assert!(limited.can_read_key("jobs/1"));
+        assert!(!limited.can_write_key("jobs/1"));
+        assert!(!limited.can_read_key("other"));
+
+        assert_eq!(
+            db_list
+                .get_effective_permissions(&DBPacketInfo::new("test_missing_db"), &user_key)
+                .unwrap_err(),
+            DBPacketResponseError::DBNotFound
+        );
+    }
+
     #[test]
     fn test_list_db_contents() {
         let db_list = get_db_list_for_testing();
@@ -477,7 +657,7 @@ mod tests {
         let list_db_contents_invalid_perms2 =
             db_list.list_db_contents(&db_pack_info, &TEST_USER_KEY.to_string());
         match list_db_contents_invalid_perms2.unwrap() {
-            SuccessNoData => {
+            SuccessNoData | SuccessData(_) => {
                 panic!("No data received from db contents? Bad packet possibly?");
             }
             SuccessReply(data) => match serde_json::from_str::<HashMap<String, String>>(&data) {
@@ -492,7 +672,7 @@ mod tests {
         let list_db_contents_valid_perms =
             db_list.list_db_contents(&db_pack_info, &TEST_SUPER_ADMIN_KEY.to_string());
         match list_db_contents_valid_perms.unwrap() {
-            SuccessNoData => {
+            SuccessNoData | SuccessData(_) => {
                 panic!("No data received from db contents? Bad packet possibly?");
             }
             SuccessReply(data) => match serde_json::from_str::<HashMap<String, String>>(&data) {
@@ -515,7 +695,7 @@ mod tests {
         let list_db_contents_valid_perms =
             db_list.list_db_contents(&db_pack_info, &TEST_SUPER_ADMIN_KEY.to_string());
         match list_db_contents_valid_perms.unwrap() {
-            SuccessNoData => {
+            SuccessNoData | SuccessData(_) => {
                 panic!("No data received from db contents? Bad packet possibly?");
             }
             SuccessReply(data) => match serde_json::from_str::<HashMap<String, String>>(&data) {
@@ -577,13 +757,11 @@ mod tests {
             let original_db_settings =
                 db_list.get_db_settings(&db_pack_info, &TEST_SUPER_ADMIN_KEY.to_string());
             match original_db_settings.unwrap() {
-                SuccessNoData => {
-                    unreachable!()
+                SuccessData(ResponseData::Settings(received_original_db_settings)) => {
+                    assert_eq!(*received_original_db_settings, get_db_test_settings());
                 }
-                SuccessReply(data) => {
-                    let received_original_db_settings: DBSettings =
-                        serde_json::from_str(&data).unwrap();
-                    assert_eq!(received_original_db_settings, get_db_test_settings());
+                _ => {
+                    unreachable!()
                 }
             }
         }
@@ -631,13 +809,11 @@ mod tests {
                 db_list.get_db_settings(&db_pack_info, &TEST_SUPER_ADMIN_KEY.to_string());
 
             match original_db_settings.unwrap() {
-                SuccessNoData => {
-                    unreachable!()
+                SuccessData(ResponseData::Settings(received_original_db_settings)) => {
+                    assert_eq!(*received_original_db_settings, new_db_settings.clone());
                 }
-                SuccessReply(data) => {
-                    let received_original_db_settings: DBSettings =
-                        serde_json::from_str(&data).unwrap();
-                    assert_eq!(received_original_db_settings, new_db_settings.clone());
+                _ => {
+                    unreachable!()
                 }
             }
         }
@@ -672,76 +848,97 @@ mod tests {
 
         {
             let role = db_list.get_role(&db_pack_info, &TEST_SUPER_ADMIN_KEY.to_string());
-            match role.unwrap() {
-                SuccessNoData => {
-                    panic!("bad response from get role")
-                }
-                SuccessReply(role_ser) => match serde_json::from_str::<Role>(&role_ser) {
-                    Ok(role_deser) => {
-                        assert_eq!(role_deser, SuperAdmin)
-                    }
-                    Err(err) => {
-                        panic!("{:?}", err)
-                    }
-                },
-            }
+            assert_eq!(role.unwrap(), SuccessData(ResponseData::Role(SuperAdmin)));
         }
 
         {
             let role = db_list.get_role(&db_pack_info, &new_admin_key);
-            match role.unwrap() {
-                SuccessNoData => {
-                    panic!("bad response from get role")
-                }
-                SuccessReply(role_ser) => match serde_json::from_str::<Role>(&role_ser) {
-                    Ok(role_deser) => {
-                        assert_eq!(role_deser, Admin)
-                    }
-                    Err(err) => {
-                        panic!("{:?}", err)
-                    }
-                },
-            }
+            assert_eq!(role.unwrap(), SuccessData(ResponseData::Role(Admin)));
         }
 
         {
             let role = db_list.get_role(&db_pack_info, &user_key);
-            match role.unwrap() {
-                SuccessNoData => {
-                    panic!("bad response from get role")
-                }
-                SuccessReply(role_ser) => match serde_json::from_str::<Role>(&role_ser) {
-                    Ok(role_deser) => {
-                        assert_eq!(role_deser, User)
-                    }
-                    Err(err) => {
-                        panic!("{:?}", err)
-                    }
-                },
-            }
+            assert_eq!(role.unwrap(), SuccessData(ResponseData::Role(User)));
         }
 
         {
             let role = db_list.get_role(&db_pack_info, &"not a key at all!!?!".to_string());
-            match role.unwrap() {
-                SuccessNoData => {
-                    panic!("bad response from get role")
-                }
-                SuccessReply(role_ser) => match serde_json::from_str::<Role>(&role_ser) {
-                    Ok(role_deser) => {
-                        assert_eq!(role_deser, Other)
-                    }
-                    Err(err) => {
-                        panic!("{:?}", err)
-                    }
-                },
-            }
+            assert_eq!(role.unwrap(), SuccessData(ResponseData::Role(Other)));
         }
 
         let delete_response = db_list.delete_db(db_name, &TEST_SUPER_ADMIN_KEY.to_string());
         assert_eq!(delete_response.unwrap(), SuccessNoData);
     }
 
+    #[test]
+    fn test_unchanged_db_stays_clean() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_unchanged_db_stays_clean";
+        let db_pack_info = DBPacketInfo::new(db_name);
+        let super_admin_key = TEST_SUPER_ADMIN_KEY.to_string();
+
+        let create_resp = db_list.create_db(db_name, get_db_test_settings(), &super_admin_key);
+        assert_eq!(create_resp.unwrap(), SuccessNoData);
+        let db = db_list.cache.get(&db_pack_info).unwrap();
+        db.write().unwrap().mark_saved();
+
+        // requests that find nothing to change leave the db clean, so it is not saved again
+        let delete_resp =
+            db_list.delete_data(&db_pack_info, &DBLocation::new("missing"), &super_admin_key);
+        assert_eq!(delete_resp.unwrap_err(), ValueNotFound);
+        let set_remove_resp = db_list.set_remove(
+            &db_pack_info,
+            &DBLocation::new("set"),
+            &DBData::new("missing".to_string()),
+            &super_admin_key,
+        );
+        assert_eq!(set_remove_resp.unwrap(), SuccessReply("false".to_string()));
+        let sorted_remove_resp = db_list.sorted_remove(
+            &db_pack_info,
+            &DBLocation::new("sorted"),
+            &DBData::new("missing".to_string()),
+            &super_admin_key,
+        );
+        assert_eq!(
+            sorted_remove_resp.unwrap(),
+            SuccessReply("false".to_string())
+        );
+        let clear_list_resp =
+            db_list.clear_list(&db_pack_info, &DBLocation::new("list"), &super_admin_key);
+        assert_eq!(clear_list_resp.unwrap(), SuccessNoData);
+        let clear_resp = db_list.clear_db(&db_pack_info, &super_admin_key);
+        assert_eq!(clear_resp.unwrap(), SuccessNoData);
+        assert_eq!(db.read().unwrap().is_dirty(), false);
+
+        let set_add_resp = db_list.set_add(
+            &db_pack_info,
+            &DBLocation::new("set"),
+            &DBData::new("member".to_string()),
+            &super_admin_key,
+        );
+        assert_eq!(set_add_resp.unwrap(), SuccessReply("true".to_string()));
+        assert_eq!(db.read().unwrap().is_dirty(), true);
+        db.write().unwrap().mark_saved();
+
+        // adding a member that is already in the set does not change it
+        let set_add_resp = db_list.set_add(
+            &db_pack_info,
+            &DBLocation::new("set"),
+            &DBData::new("member".to_string()),
+            &super_admin_key,
+        );
+        assert_eq!(set_add_resp.unwrap(), SuccessReply("false".to_string()));
+        assert_eq!(db.read().unwrap().is_dirty(), false);
+
+        let delete_response = db_list.delete_db(db_name, &super_admin_key);
+        assert_eq!(delete_response.unwrap(), SuccessNoData);
+    }
+
     #[test]
     fn test_delete_data() {
         let db_list = get_db_list_for_testing();
@@ -841,4 +1038,3608 @@ mod tests {
             assert_eq!(delete_response.unwrap(), SuccessNoData);
         }
     }
+
+    #[test]
+    fn test_list_db_lists() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_dblist_1_list_db_lists";
+        let db_pack_info = DBPacketInfo::new(db_name);
+        let db_data = DBData::new("this is data".to_string());
+
+        let create_response = db_list.create_db(
+            db_name,
+            get_db_test_settings(),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(create_response.unwrap(), SuccessNoData);
+
+        // key value pairs are not lists
+        let write_response = db_list.write_db(
+            &db_pack_info,
+            &DBLocation::new("location1"),
+            &db_data,
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(write_response.unwrap(), SuccessNoData);
+
+        for list_name in ["list2", "list1"] {
+            let add_response = db_list.add_to_list(
+                &db_pack_info,
+                &DBLocation::new(list_name),
+                &db_data,
+                None,
+                &TEST_SUPER_ADMIN_KEY.to_string(),
+            );
+            assert!(add_response.is_ok());
+        }
+
+        let list_db_lists_invalid_perms =
+            db_list.list_db_lists(&db_pack_info, &"not a valid key most likely".to_string());
+        assert_eq!(list_db_lists_invalid_perms.unwrap_err(), InvalidPermissions);
+
+        let list_db_lists_response = db_list
+            .list_db_lists(&db_pack_info, &TEST_USER_KEY.to_string())
+            .unwrap();
+        assert_eq!(
+            list_db_lists_response,
+            SuccessReply(r#"["list1","list2"]"#.to_string())
+        );
+
+        let list_db_lists_not_found = db_list.list_db_lists(
+            &DBPacketInfo::new("db that does not exist"),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(list_db_lists_not_found.unwrap_err(), DBNotFound);
+
+        let delete_response = db_list.delete_db(db_name, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(delete_response.unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_list_db_keys() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_dblist_1_list_db_keys";
+        let db_pack_info = DBPacketInfo::new(db_name);
+        let db_data = DBData::new("this is data".to_string());
+
+        let create_response = db_list.create_db(
+            db_name,
+            get_db_test_settings(),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(create_response.unwrap(), SuccessNoData);
+
+        for location in ["location1", "location2"] {
+            let write_response = db_list.write_db(
+                &db_pack_info,
+                &DBLocation::new(location),
+                &db_data,
+                &TEST_SUPER_ADMIN_KEY.to_string(),
+            );
+            assert_eq!(write_response.unwrap(), SuccessNoData);
+        }
+
+        let list_db_keys_invalid_perms =
+            db_list.list_db_keys(&db_pack_info, &"not a valid key most likely".to_string());
+        assert_eq!(list_db_keys_invalid_perms.unwrap_err(), InvalidPermissions);
+
+        let list_db_keys_response = db_list
+            .list_db_keys(&db_pack_info, &TEST_USER_KEY.to_string())
+            .unwrap();
+        match list_db_keys_response {
+            SuccessNoData | SuccessData(_) => {
+                panic!("No data received from db keys? Bad packet possibly?");
+            }
+            SuccessReply(data) => {
+                let mut keys = serde_json::from_str::<Vec<String>>(&data).unwrap();
+                keys.sort();
+                assert_eq!(keys, vec!["location1".to_string(), "location2".to_string()]);
+            }
+        }
+
+        let list_db_keys_not_found = db_list.list_db_keys(
+            &DBPacketInfo::new("db that does not exist"),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(list_db_keys_not_found.unwrap_err(), DBNotFound);
+
+        let delete_response = db_list.delete_db(db_name, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(delete_response.unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_read_prefix() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_dblist_1_read_prefix";
+        let db_pack_info = DBPacketInfo::new(db_name);
+
+        let create_response = db_list.create_db(
+            db_name,
+            get_db_test_settings(),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(create_response.unwrap(), SuccessNoData);
+
+        for location in ["user:1:name", "user:1:age", "user:10:name", "user:2:name"] {
+            let write_response = db_list.write_db(
+                &db_pack_info,
+                &DBLocation::new(location),
+                &DBData::new(location.to_string()),
+                &TEST_SUPER_ADMIN_KEY.to_string(),
+            );
+            assert_eq!(write_response.unwrap(), SuccessNoData);
+        }
+
+        let read_prefix_invalid_perms = db_list.read_prefix(
+            &db_pack_info,
+            "user:1:",
+            &"not a valid key most likely".to_string(),
+        );
+        assert_eq!(read_prefix_invalid_perms.unwrap_err(), InvalidPermissions);
+
+        let read_prefix_response = db_list
+            .read_prefix(&db_pack_info, "user:1:", &TEST_USER_KEY.to_string())
+            .unwrap();
+        match read_prefix_response {
+            SuccessNoData | SuccessData(_) => {
+                panic!("No data received from read prefix? Bad packet possibly?");
+            }
+            SuccessReply(data) => {
+                let pairs =
+                    serde_json::from_str::<std::collections::BTreeMap<String, String>>(&data)
+                        .unwrap();
+                let keys: Vec<&str> = pairs.keys().map(|key| key.as_str()).collect();
+                assert_eq!(keys, vec!["user:1:age", "user:1:name"]);
+            }
+        }
+
+        // removed keys no longer show up in a prefix scan
+        let delete_response = db_list.delete_data(
+            &db_pack_info,
+            &DBLocation::new("user:1:age"),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(
+            delete_response.unwrap(),
+            SuccessReply("user:1:age".to_string())
+        );
+
+        let read_prefix_after_delete = db_list
+            .read_prefix(&db_pack_info, "user:1", &TEST_SUPER_ADMIN_KEY.to_string())
+            .unwrap();
+        let pairs = serde_json::from_str::<std::collections::BTreeMap<String, String>>(
+            read_prefix_after_delete.as_option().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(pairs.len(), 2);
+        assert!(pairs.contains_key("user:1:name"));
+        assert!(pairs.contains_key("user:10:name"));
+
+        let delete_response = db_list.delete_db(db_name, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(delete_response.unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_find_keys() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_dblist_1_find_keys";
+        let db_pack_info = DBPacketInfo::new(db_name);
+        let db_data = DBData::new("this is data".to_string());
+
+        let create_response = db_list.create_db(
+            db_name,
+            get_db_test_settings(),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(create_response.unwrap(), SuccessNoData);
+
+        for location in ["user:1:name", "user:22:name", "user:3:age", "item:1"] {
+            let write_response = db_list.write_db(
+                &db_pack_info,
+                &DBLocation::new(location),
+                &db_data,
+                &TEST_SUPER_ADMIN_KEY.to_string(),
+            );
+            assert_eq!(write_response.unwrap(), SuccessNoData);
+        }
+
+        let find_keys = |pattern: KeyPattern| -> Vec<String> {
+            let response = db_list
+                .find_keys(&db_pack_info, &pattern, &TEST_USER_KEY.to_string())
+                .unwrap();
+            let mut keys =
+                serde_json::from_str::<Vec<String>>(response.as_option().unwrap()).unwrap();
+            keys.sort();
+            keys
+        };
+
+        assert_eq!(
+            find_keys(KeyPattern::new_glob("user:*:name")),
+            vec!["user:1:name", "user:22:name"]
+        );
+        assert_eq!(
+            find_keys(KeyPattern::new_glob("user:?:*")),
+            vec!["user:1:name", "user:3:age"]
+        );
+        assert_eq!(find_keys(KeyPattern::new_glob("item:1")), vec!["item:1"]);
+        assert!(find_keys(KeyPattern::new_glob("item")).is_empty());
+        assert_eq!(
+            find_keys(KeyPattern::new_regex("^user:[0-9]{2}:")),
+            vec!["user:22:name"]
+        );
+        assert_eq!(find_keys(KeyPattern::new_regex("age$")), vec!["user:3:age"]);
+
+        let find_keys_invalid_regex = db_list.find_keys(
+            &db_pack_info,
+            &KeyPattern::new_regex("user:(["),
+            &TEST_USER_KEY.to_string(),
+        );
+        assert_eq!(
+            find_keys_invalid_regex.unwrap_err(),
+            DBPacketResponseError::InvalidKeyPattern
+        );
+
+        let find_keys_invalid_perms = db_list.find_keys(
+            &db_pack_info,
+            &KeyPattern::new_glob("*"),
+            &"not a valid key most likely".to_string(),
+        );
+        assert_eq!(find_keys_invalid_perms.unwrap_err(), InvalidPermissions);
+
+        let delete_response = db_list.delete_db(db_name, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(delete_response.unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_compare_and_swap() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_dblist_1_compare_and_swap";
+        let db_pack_info = DBPacketInfo::new(db_name);
+        let db_location = DBLocation::new("location1");
+        let data1 = DBData::new("data1".to_string());
+        let data2 = DBData::new("data2".to_string());
+
+        let create_response = db_list.create_db(
+            db_name,
+            get_db_test_settings(),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(create_response.unwrap(), SuccessNoData);
+
+        let cas_invalid_perms = db_list.compare_and_swap(
+            &db_pack_info,
+            &db_location,
+            None,
+            &data1,
+            &"not a valid key most likely".to_string(),
+        );
+        assert_eq!(cas_invalid_perms.unwrap_err(), InvalidPermissions);
+
+        // swap into an empty location
+        let cas_empty = db_list.compare_and_swap(
+            &db_pack_info,
+            &db_location,
+            None,
+            &data1,
+            &TEST_USER_KEY.to_string(),
+        );
+        assert_eq!(cas_empty.unwrap(), SuccessNoData);
+
+        // location is no longer empty
+        let cas_not_empty = db_list.compare_and_swap(
+            &db_pack_info,
+            &db_location,
+            None,
+            &data2,
+            &TEST_USER_KEY.to_string(),
+        );
+        assert_eq!(
+            cas_not_empty.unwrap_err(),
+            DBPacketResponseError::ValueMismatch(Some("data1".to_string()))
+        );
+
+        let cas_match = db_list.compare_and_swap(
+            &db_pack_info,
+            &db_location,
+            Some(&data1),
+            &data2,
+            &TEST_USER_KEY.to_string(),
+        );
+        assert_eq!(cas_match.unwrap(), SuccessReply("data1".to_string()));
+
+        let cas_mismatch = db_list.compare_and_swap(
+            &db_pack_info,
+            &db_location,
+            Some(&data1),
+            &data1,
+            &TEST_USER_KEY.to_string(),
+        );
+        assert_eq!(
+            cas_mismatch.unwrap_err(),
+            DBPacketResponseError::ValueMismatch(Some("data2".to_string()))
+        );
+
+        let read_response =
+            db_list.read_db(&db_pack_info, &db_location, &TEST_USER_KEY.to_string());
+        assert_eq!(read_response.unwrap(), SuccessReply("data2".to_string()));
+
+        let delete_response = db_list.delete_db(db_name, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(delete_response.unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_increment() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_dblist_1_increment";
+        let db_pack_info = DBPacketInfo::new(db_name);
+        let db_location = DBLocation::new("counter");
+
+        let create_response = db_list.create_db(
+            db_name,
+            get_db_test_settings(),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(create_response.unwrap(), SuccessNoData);
+
+        let increment_invalid_perms = db_list.increment(
+            &db_pack_info,
+            &db_location,
+            1,
+            &"not a valid key most likely".to_string(),
+        );
+        assert_eq!(increment_invalid_perms.unwrap_err(), InvalidPermissions);
+
+        let increment_response1 =
+            db_list.increment(&db_pack_info, &db_location, 5, &TEST_USER_KEY.to_string());
+        assert_eq!(increment_response1.unwrap(), SuccessReply("5".to_string()));
+
+        let increment_response2 =
+            db_list.increment(&db_pack_info, &db_location, -7, &TEST_USER_KEY.to_string());
+        assert_eq!(increment_response2.unwrap(), SuccessReply("-2".to_string()));
+
+        let write_response = db_list.write_db(
+            &db_pack_info,
+            &db_location,
+            &DBData::new(i64::MAX.to_string()),
+            &TEST_USER_KEY.to_string(),
+        );
+        assert_eq!(write_response.unwrap(), SuccessReply("-2".to_string()));
+
+        let increment_overflow =
+            db_list.increment(&db_pack_info, &db_location, 1, &TEST_USER_KEY.to_string());
+        assert_eq!(
+            increment_overflow.unwrap_err(),
+            DBPacketResponseError::IntegerOverflow
+        );
+
+        let write_response = db_list.write_db(
+            &db_pack_info,
+            &db_location,
+            &DBData::new("not a number".to_string()),
+            &TEST_USER_KEY.to_string(),
+        );
+        assert!(write_response.is_ok());
+
+        let increment_not_integer =
+            db_list.increment(&db_pack_info, &db_location, 1, &TEST_USER_KEY.to_string());
+        assert_eq!(
+            increment_not_integer.unwrap_err(),
+            DBPacketResponseError::ValueNotInteger
+        );
+
+        let delete_response = db_list.delete_db(db_name, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(delete_response.unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_transaction() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_dblist_1_transaction";
+        let db_pack_info = DBPacketInfo::new(db_name);
+
+        let create_response = db_list.create_db(
+            db_name,
+            get_db_test_settings(),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(create_response.unwrap(), SuccessNoData);
+
+        let ops = TransactionBuilder::new()
+            .write("a", "1")
+            .write("b", "2")
+            .read("a")
+            .write("a", "3")
+            .build();
+
+        let transaction_invalid_perms = db_list.transaction(
+            &db_pack_info,
+            &ops,
+            &"not a valid key most likely".to_string(),
+        );
+        assert_eq!(transaction_invalid_perms.unwrap_err(), InvalidPermissions);
+
+        let transaction_response = db_list
+            .transaction(&db_pack_info, &ops, &TEST_USER_KEY.to_string())
+            .unwrap();
+        let results =
+            serde_json::from_str::<Vec<Option<String>>>(transaction_response.as_option().unwrap())
+                .unwrap();
+        assert_eq!(
+            results,
+            vec![None, None, Some("1".to_string()), Some("1".to_string())]
+        );
+
+        // the delete of "missing" fails, so the write to "a" and delete of "b" should both be rolled back
+        let failing_ops = TransactionBuilder::new()
+            .write("a", "100")
+            .delete("b")
+            .delete("missing")
+            .build();
+        let rollback_response =
+            db_list.transaction(&db_pack_info, &failing_ops, &TEST_USER_KEY.to_string());
+        assert_eq!(
+            rollback_response.unwrap_err(),
+            DBPacketResponseError::TransactionRolledBack {
+                op_index: 2,
+                error: Box::new(ValueNotFound),
+            }
+        );
+
+        let read_a = db_list.read_db(
+            &db_pack_info,
+            &DBLocation::new("a"),
+            &TEST_USER_KEY.to_string(),
+        );
+        assert_eq!(read_a.unwrap(), SuccessReply("3".to_string()));
+        let read_b = db_list.read_db(
+            &db_pack_info,
+            &DBLocation::new("b"),
+            &TEST_USER_KEY.to_string(),
+        );
+        assert_eq!(read_b.unwrap(), SuccessReply("2".to_string()));
+
+        let delete_response = db_list.delete_db(db_name, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(delete_response.unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_write_db_if_absent() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_dblist_1_write_if_absent";
+        let db_pack_info = DBPacketInfo::new(db_name);
+        let db_location = DBLocation::new("lock");
+
+        let create_response = db_list.create_db(
+            db_name,
+            get_db_test_settings(),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(create_response.unwrap(), SuccessNoData);
+
+        let write_invalid_perms = db_list.write_db_if_absent(
+            &db_pack_info,
+            &db_location,
+            &DBData::new("owner_1".to_string()),
+            &"not a valid key most likely".to_string(),
+        );
+        assert_eq!(write_invalid_perms.unwrap_err(), InvalidPermissions);
+
+        let write_response1 = db_list.write_db_if_absent(
+            &db_pack_info,
+            &db_location,
+            &DBData::new("owner_1".to_string()),
+            &TEST_USER_KEY.to_string(),
+        );
+        assert_eq!(write_response1.unwrap(), SuccessNoData);
+
+        let write_response2 = db_list.write_db_if_absent(
+            &db_pack_info,
+            &db_location,
+            &DBData::new("owner_2".to_string()),
+            &TEST_USER_KEY.to_string(),
+        );
+        assert_eq!(
+            write_response2.unwrap_err(),
+            DBPacketResponseError::ValueAlreadyExists
+        );
+
+        let read_response =
+            db_list.read_db(&db_pack_info, &db_location, &TEST_USER_KEY.to_string());
+        assert_eq!(read_response.unwrap(), SuccessReply("owner_1".to_string()));
+
+        let delete_data_response =
+            db_list.delete_data(&db_pack_info, &db_location, &TEST_USER_KEY.to_string());
+        assert!(delete_data_response.is_ok());
+
+        let write_response3 = db_list.write_db_if_absent(
+            &db_pack_info,
+            &db_location,
+            &DBData::new("owner_2".to_string()),
+            &TEST_USER_KEY.to_string(),
+        );
+        assert_eq!(write_response3.unwrap(), SuccessNoData);
+
+        let delete_response = db_list.delete_db(db_name, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(delete_response.unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_subscribe() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_dblist_1_subscribe";
+        let db_pack_info = DBPacketInfo::new(db_name);
+
+        let create_response = db_list.create_db(
+            db_name,
+            get_db_test_settings(),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(create_response.unwrap(), SuccessNoData);
+
+        let subscribe_invalid_perms = db_list.subscribe(
+            &db_pack_info,
+            KeyPattern::new_glob("*"),
+            &"not a valid key most likely".to_string(),
+        );
+        assert_eq!(subscribe_invalid_perms.unwrap_err(), InvalidPermissions);
+
+        let subscribe_invalid_pattern = db_list.subscribe(
+            &db_pack_info,
+            KeyPattern::new_regex("(unclosed"),
+            &TEST_USER_KEY.to_string(),
+        );
+        assert_eq!(
+            subscribe_invalid_pattern.unwrap_err(),
+            DBPacketResponseError::InvalidKeyPattern
+        );
+
+        let receiver = db_list
+            .subscribe(
+                &db_pack_info,
+                KeyPattern::new_glob("user_*"),
+                &TEST_USER_KEY.to_string(),
+            )
+            .unwrap();
+        assert_eq!(db_list.notification_bus.subscriber_count(), 1);
+
+        let _ = db_list.write_db(
+            &db_pack_info,
+            &DBLocation::new("other"),
+            &DBData::new("ignored".to_string()),
+            &TEST_USER_KEY.to_string(),
+        );
+        let _ = db_list.write_db(
+            &db_pack_info,
+            &DBLocation::new("user_1"),
+            &DBData::new("bob".to_string()),
+            &TEST_USER_KEY.to_string(),
+        );
+        let _ = db_list.delete_data(
+            &db_pack_info,
+            &DBLocation::new("user_1"),
+            &TEST_USER_KEY.to_string(),
+        );
+
+        let events: Vec<ChangeEvent> = receiver.try_iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                ChangeEvent::Written {
+                    key: "user_1".to_string(),
+                    value: "bob".to_string()
+                },
+                ChangeEvent::Deleted {
+                    key: "user_1".to_string()
+                },
+            ]
+        );
+
+        // dropping the receiver removes the subscription on the next matching change
+        drop(receiver);
+        let _ = db_list.write_db(
+            &db_pack_info,
+            &DBLocation::new("user_2"),
+            &DBData::new("alice".to_string()),
+            &TEST_USER_KEY.to_string(),
+        );
+        assert_eq!(db_list.notification_bus.subscriber_count(), 0);
+
+        let delete_response = db_list.delete_db(db_name, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(delete_response.unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_publish_subscribe_channel() {
+        let db_list = get_db_list_for_testing();
+        let channel_name = "test_dblist_1_channel";
+
+        let publish_no_subscribers = db_list.publish(channel_name, "nobody is listening");
+        assert_eq!(
+            publish_no_subscribers.unwrap(),
+            SuccessReply("0".to_string())
+        );
+
+        let receiver1 = db_list.subscribe_channel(channel_name);
+        let receiver2 = db_list.subscribe_channel(channel_name);
+        let other_channel_receiver = db_list.subscribe_channel("some_other_channel");
+
+        let publish_response = db_list.publish(channel_name, "hello");
+        assert_eq!(publish_response.unwrap(), SuccessReply("2".to_string()));
+
+        assert_eq!(receiver1.try_recv().unwrap(), "hello");
+        assert_eq!(receiver2.try_recv().unwrap(), "hello");
+        assert!(other_channel_receiver.try_recv().is_err());
+
+        drop(receiver1);
+        let publish_response = db_list.publish(channel_name, "goodbye");
+        assert_eq!(publish_response.unwrap(), SuccessReply("1".to_string()));
+        assert_eq!(receiver2.try_recv().unwrap(), "goodbye");
+        assert_eq!(db_list.channels.subscriber_count(channel_name), 1);
+    }
+
+    #[test]
+    fn test_read_write_many() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_dblist_1_read_write_many";
+        let db_pack_info = DBPacketInfo::new(db_name);
+
+        let create_response = db_list.create_db(
+            db_name,
+            get_db_test_settings(),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(create_response.unwrap(), SuccessNoData);
+
+        let writes = vec![
+            (DBLocation::new("a"), DBData::new("1".to_string())),
+            (DBLocation::new("b"), DBData::new("2".to_string())),
+            (DBLocation::new("a"), DBData::new("3".to_string())),
+        ];
+
+        let write_invalid_perms = db_list.write_many(
+            &db_pack_info,
+            &writes,
+            &"not a valid key most likely".to_string(),
+        );
+        assert_eq!(write_invalid_perms.unwrap_err(), InvalidPermissions);
+
+        let write_response = db_list
+            .write_many(&db_pack_info, &writes, &TEST_USER_KEY.to_string())
+            .unwrap();
+        assert_eq!(
+            write_response,
+            SuccessReply(r#"[null,null,"1"]"#.to_string())
+        );
+
+        let locations = vec![
+            DBLocation::new("a"),
+            DBLocation::new("missing"),
+            DBLocation::new("b"),
+        ];
+
+        let read_invalid_perms = db_list.read_many(
+            &db_pack_info,
+            &locations,
+            &"not a valid key most likely".to_string(),
+        );
+        assert_eq!(read_invalid_perms.unwrap_err(), InvalidPermissions);
+
+        let read_response = db_list
+            .read_many(&db_pack_info, &locations, &TEST_USER_KEY.to_string())
+            .unwrap();
+        assert_eq!(read_response, SuccessReply(r#"["3",null,"2"]"#.to_string()));
+
+        let delete_response = db_list.delete_db(db_name, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(delete_response.unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_clear_db() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_dblist_1_clear_db";
+        let db_pack_info = DBPacketInfo::new(db_name);
+
+        let create_response = db_list.create_db(
+            db_name,
+            get_db_test_settings(),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(create_response.unwrap(), SuccessNoData);
+
+        for i in 0..5 {
+            let _ = db_list.write_db(
+                &db_pack_info,
+                &DBLocation::new(&format!("key_{}", i)),
+                &DBData::new(i.to_string()),
+                &TEST_USER_KEY.to_string(),
+            );
+        }
+
+        // users can write, but only admins can clear the db
+        let clear_invalid_perms = db_list.clear_db(&db_pack_info, &TEST_USER_KEY.to_string());
+        assert_eq!(clear_invalid_perms.unwrap_err(), InvalidPermissions);
+
+        let clear_response = db_list.clear_db(&db_pack_info, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(clear_response.unwrap(), SuccessNoData);
+
+        let list_response = db_list
+            .list_db_contents(&db_pack_info, &TEST_USER_KEY.to_string())
+            .unwrap();
+        let contents: HashMap<String, String> =
+            serde_json::from_str(list_response.as_option().unwrap()).unwrap();
+        assert!(contents.is_empty());
+
+        let prefix_response = db_list
+            .read_prefix(&db_pack_info, "key_", &TEST_USER_KEY.to_string())
+            .unwrap();
+        assert_eq!(prefix_response, SuccessReply("{}".to_string()));
+
+        let settings_response = db_list
+            .get_db_settings(&db_pack_info, &TEST_SUPER_ADMIN_KEY.to_string())
+            .unwrap();
+        assert_eq!(
+            settings_response,
+            SuccessData(ResponseData::Settings(Box::new(get_db_test_settings())))
+        );
+
+        let delete_response = db_list.delete_db(db_name, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(delete_response.unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_rename_db() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_dblist_1_rename_from";
+        let new_db_name = "test_dblist_1_rename_to";
+        let taken_db_name = "test_dblist_1_rename_taken";
+        let from = DBPacketInfo::new(db_name);
+        let to = DBPacketInfo::new(new_db_name);
+
+        for name in [db_name, taken_db_name] {
+            let create_response = db_list.create_db(
+                name,
+                get_db_test_settings(),
+                &TEST_SUPER_ADMIN_KEY.to_string(),
+            );
+            assert_eq!(create_response.unwrap(), SuccessNoData);
+        }
+
+        let write_response = db_list.write_db(
+            &from,
+            &DBLocation::new("key"),
+            &DBData::new("value".to_string()),
+            &TEST_USER_KEY.to_string(),
+        );
+        assert!(write_response.is_ok());
+
+        let rename_invalid_perms = db_list.rename_db(&from, &to, &TEST_USER_KEY.to_string());
+        assert_eq!(rename_invalid_perms.unwrap_err(), InvalidPermissions);
+
+        let rename_taken = db_list.rename_db(
+            &from,
+            &DBPacketInfo::new(taken_db_name),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(rename_taken.unwrap_err(), DBAlreadyExists);
+
+        let rename_missing = db_list.rename_db(
+            &DBPacketInfo::new("test_dblist_1_rename_missing"),
+            &to,
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(rename_missing.unwrap_err(), DBNotFound);
+
+        let rename_response = db_list.rename_db(&from, &to, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(rename_response.unwrap(), SuccessNoData);
+
+        assert!(!db_list.list.read().unwrap().contains(&from));
+        assert!(db_list.list.read().unwrap().contains(&to));
+        assert!(!PathBuf::from(format!("./data/{}", db_name)).exists());
+        assert!(PathBuf::from(format!("./data/{}", new_db_name)).exists());
+
+        let read_response =
+            db_list.read_db(&to, &DBLocation::new("key"), &TEST_USER_KEY.to_string());
+        assert_eq!(read_response.unwrap(), SuccessReply("value".to_string()));
+
+        for name in [new_db_name, taken_db_name] {
+            let delete_response = db_list.delete_db(name, &TEST_SUPER_ADMIN_KEY.to_string());
+            assert_eq!(delete_response.unwrap(), SuccessNoData);
+        }
+    }
+
+    #[test]
+    fn test_copy_db() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_dblist_1_copy_source";
+        let copy_with_settings = "test_dblist_1_copy_with_settings";
+        let copy_without_settings = "test_dblist_1_copy_without_settings";
+        let source = DBPacketInfo::new(db_name);
+
+        let create_response = db_list.create_db(
+            db_name,
+            get_db_test_settings(),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(create_response.unwrap(), SuccessNoData);
+
+        let write_response = db_list.write_db(
+            &source,
+            &DBLocation::new("key"),
+            &DBData::new("value".to_string()),
+            &TEST_USER_KEY.to_string(),
+        );
+        assert!(write_response.is_ok());
+
+        let copy_invalid_perms = db_list.copy_db(
+            &source,
+            &DBPacketInfo::new(copy_with_settings),
+            true,
+            &TEST_USER_KEY.to_string(),
+        );
+        assert_eq!(copy_invalid_perms.unwrap_err(), InvalidPermissions);
+
+        let copy_onto_existing =
+            db_list.copy_db(&source, &source, true, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(copy_onto_existing.unwrap_err(), DBAlreadyExists);
+
+        let copy_response = db_list.copy_db(
+            &source,
+            &DBPacketInfo::new(copy_with_settings),
+            true,
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(copy_response.unwrap(), SuccessNoData);
+
+        let copy_response = db_list.copy_db(
+            &source,
+            &DBPacketInfo::new(copy_without_settings),
+            false,
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(copy_response.unwrap(), SuccessNoData);
+
+        // the copy with settings keeps the test user, so the user can read the copied value
+        let read_response = db_list.read_db(
+            &DBPacketInfo::new(copy_with_settings),
+            &DBLocation::new("key"),
+            &TEST_USER_KEY.to_string(),
+        );
+        assert_eq!(read_response.unwrap(), SuccessReply("value".to_string()));
+
+        let settings_response = db_list
+            .get_db_settings(
+                &DBPacketInfo::new(copy_without_settings),
+                &TEST_SUPER_ADMIN_KEY.to_string(),
+            )
+            .unwrap();
+        assert_eq!(
+            settings_response,
+            SuccessData(ResponseData::Settings(Box::default()))
+        );
+
+        let read_response = db_list.read_db(
+            &DBPacketInfo::new(copy_without_settings),
+            &DBLocation::new("key"),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(read_response.unwrap(), SuccessReply("value".to_string()));
+
+        // the copy is independent of the source
+        let _ = db_list.write_db(
+            &source,
+            &DBLocation::new("key"),
+            &DBData::new("changed".to_string()),
+            &TEST_USER_KEY.to_string(),
+        );
+        let read_response = db_list.read_db(
+            &DBPacketInfo::new(copy_with_settings),
+            &DBLocation::new("key"),
+            &TEST_USER_KEY.to_string(),
+        );
+        assert_eq!(read_response.unwrap(), SuccessReply("value".to_string()));
+
+        for name in [db_name, copy_with_settings, copy_without_settings] {
+            let delete_response = db_list.delete_db(name, &TEST_SUPER_ADMIN_KEY.to_string());
+            assert_eq!(delete_response.unwrap(), SuccessNoData);
+        }
+    }
+
+    #[test]
+    fn test_db_and_key_exists() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_dblist_1_exists";
+        let db_pack_info = DBPacketInfo::new(db_name);
+
+        let exists_before = db_list.db_exists(&db_pack_info);
+        assert_eq!(exists_before.unwrap(), SuccessReply("false".to_string()));
+
+        let create_response = db_list.create_db(
+            db_name,
+            get_db_test_settings(),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(create_response.unwrap(), SuccessNoData);
+
+        let exists_after = db_list.db_exists(&db_pack_info);
+        assert_eq!(exists_after.unwrap(), SuccessReply("true".to_string()));
+
+        let _ = db_list.write_db(
+            &db_pack_info,
+            &DBLocation::new("key"),
+            &DBData::new("value".to_string()),
+            &TEST_USER_KEY.to_string(),
+        );
+
+        let key_exists_invalid_perms = db_list.key_exists(
+            &db_pack_info,
+            &DBLocation::new("key"),
+            &"not a valid key most likely".to_string(),
+        );
+        assert_eq!(key_exists_invalid_perms.unwrap_err(), InvalidPermissions);
+
+        let key_exists = db_list.key_exists(
+            &db_pack_info,
+            &DBLocation::new("key"),
+            &TEST_USER_KEY.to_string(),
+        );
+        assert_eq!(key_exists.unwrap(), SuccessReply("true".to_string()));
+
+        let key_missing = db_list.key_exists(
+            &db_pack_info,
+            &DBLocation::new("missing"),
+            &TEST_USER_KEY.to_string(),
+        );
+        assert_eq!(key_missing.unwrap(), SuccessReply("false".to_string()));
+
+        let delete_response = db_list.delete_db(db_name, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(delete_response.unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_get_db_len() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_dblist_1_len";
+        let db_pack_info = DBPacketInfo::new(db_name);
+
+        let create_response = db_list.create_db(
+            db_name,
+            get_db_test_settings(),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(create_response.unwrap(), SuccessNoData);
+
+        let empty_len = db_list.get_db_len(&db_pack_info, &TEST_USER_KEY.to_string());
+        assert_eq!(empty_len.unwrap(), SuccessReply("0".to_string()));
+
+        for i in 0..3 {
+            let _ = db_list.write_db(
+                &db_pack_info,
+                &DBLocation::new(&format!("key{}", i)),
+                &DBData::new("value".to_string()),
+                &TEST_USER_KEY.to_string(),
+            );
+        }
+
+        let len = db_list.get_db_len(&db_pack_info, &TEST_USER_KEY.to_string());
+        assert_eq!(len.unwrap(), SuccessReply("3".to_string()));
+
+        let len_invalid_perms =
+            db_list.get_db_len(&db_pack_info, &"not a valid key most likely".to_string());
+        assert_eq!(len_invalid_perms.unwrap_err(), InvalidPermissions);
+
+        let delete_response = db_list.delete_db(db_name, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(delete_response.unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_keyed_list() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_dblist_1_keyed_list";
+        let db_pack_info = DBPacketInfo::new(db_name);
+        let list_name = DBLocation::new("list");
+        let user_key = TEST_USER_KEY.to_string();
+
+        let create_response = db_list.create_db(
+            db_name,
+            get_db_test_settings(),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(create_response.unwrap(), SuccessNoData);
+
+        let missing_read = db_list.read_from_list(&db_pack_info, &list_name, 0, &user_key);
+        assert_eq!(missing_read.unwrap_err(), ValueNotFound);
+
+        for (i, value) in ["b", "c"].iter().enumerate() {
+            let push = db_list.add_to_list(
+                &db_pack_info,
+                &list_name,
+                &DBData::new(value.to_string()),
+                None,
+                &user_key,
+            );
+            assert_eq!(push.unwrap(), SuccessReply((i + 1).to_string()));
+        }
+
+        let insert = db_list.add_to_list(
+            &db_pack_info,
+            &list_name,
+            &DBData::new("a".to_string()),
+            Some(0),
+            &user_key,
+        );
+        assert_eq!(insert.unwrap(), SuccessReply("3".to_string()));
+
+        let insert_out_of_bounds = db_list.add_to_list(
+            &db_pack_info,
+            &list_name,
+            &DBData::new("z".to_string()),
+            Some(10),
+            &user_key,
+        );
+        assert_eq!(
+            insert_out_of_bounds.unwrap_err(),
+            DBPacketResponseError::IndexOutOfBounds
+        );
+
+        let read = db_list.read_from_list(&db_pack_info, &list_name, 0, &user_key);
+        assert_eq!(read.unwrap(), SuccessReply("a".to_string()));
+
+        let read_out_of_bounds = db_list.read_from_list(&db_pack_info, &list_name, 3, &user_key);
+        assert_eq!(
+            read_out_of_bounds.unwrap_err(),
+            DBPacketResponseError::IndexOutOfBounds
+        );
+
+        let streamed: Vec<String> = db_list
+            .stream_list(&db_pack_info, &list_name, &user_key)
+            .unwrap()
+            .iter()
+            .collect();
+        assert_eq!(streamed, vec!["a", "b", "c"]);
+
+        let removed = db_list.remove_from_list(&db_pack_info, &list_name, 1, &user_key);
+        assert_eq!(removed.unwrap(), SuccessReply("b".to_string()));
+
+        let len = db_list.get_list_length(&db_pack_info, &list_name, &user_key);
+        assert_eq!(len.unwrap(), SuccessReply("2".to_string()));
+
+        let invalid_perms = db_list.get_list_length(
+            &db_pack_info,
+            &list_name,
+            &"not a valid key most likely".to_string(),
+        );
+        assert_eq!(invalid_perms.unwrap_err(), InvalidPermissions);
+
+        let clear = db_list.clear_list(&db_pack_info, &list_name, &user_key);
+        assert_eq!(clear.unwrap(), SuccessNoData);
+
+        let len_after_clear = db_list.get_list_length(&db_pack_info, &list_name, &user_key);
+        assert_eq!(len_after_clear.unwrap(), SuccessReply("0".to_string()));
+
+        let delete_response = db_list.delete_db(db_name, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(delete_response.unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_sets() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_dblist_1_sets";
+        let db_pack_info = DBPacketInfo::new(db_name);
+        let set_name = DBLocation::new("set");
+        let user_key = TEST_USER_KEY.to_string();
+
+        let create_response = db_list.create_db(
+            db_name,
+            get_db_test_settings(),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(create_response.unwrap(), SuccessNoData);
+
+        for (member, newly_added) in [("b", true), ("a", true), ("b", false)] {
+            let add = db_list.set_add(
+                &db_pack_info,
+                &set_name,
+                &DBData::new(member.to_string()),
+                &user_key,
+            );
+            assert_eq!(add.unwrap(), SuccessReply(newly_added.to_string()));
+        }
+
+        let members = db_list.set_members(&db_pack_info, &set_name, &user_key);
+        assert_eq!(
+            members.unwrap(),
+            SuccessReply(serde_json::to_string(&vec!["a", "b"]).unwrap())
+        );
+
+        let contains = db_list.set_contains(
+            &db_pack_info,
+            &set_name,
+            &DBData::new("a".to_string()),
+            &user_key,
+        );
+        assert_eq!(contains.unwrap(), SuccessReply("true".to_string()));
+
+        let removed = db_list.set_remove(
+            &db_pack_info,
+            &set_name,
+            &DBData::new("a".to_string()),
+            &user_key,
+        );
+        assert_eq!(removed.unwrap(), SuccessReply("true".to_string()));
+
+        let removed_again = db_list.set_remove(
+            &db_pack_info,
+            &set_name,
+            &DBData::new("a".to_string()),
+            &user_key,
+        );
+        assert_eq!(removed_again.unwrap(), SuccessReply("false".to_string()));
+
+        let not_contains = db_list.set_contains(
+            &db_pack_info,
+            &set_name,
+            &DBData::new("a".to_string()),
+            &user_key,
+        );
+        assert_eq!(not_contains.unwrap(), SuccessReply("false".to_string()));
+
+        let invalid_perms = db_list.set_add(
+            &db_pack_info,
+            &set_name,
+            &DBData::new("c".to_string()),
+            &"not a valid key most likely".to_string(),
+        );
+        assert_eq!(invalid_perms.unwrap_err(), InvalidPermissions);
+
+        let delete_response = db_list.delete_db(db_name, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(delete_response.unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_sorted_list() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_dblist_1_sorted_list";
+        let db_pack_info = DBPacketInfo::new(db_name);
+        let list_name = DBLocation::new("scores");
+        let user_key = TEST_USER_KEY.to_string();
+        let member = |name: &str| DBData::new(name.to_string());
+
+        let create_response = db_list.create_db(
+            db_name,
+            get_db_test_settings(),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(create_response.unwrap(), SuccessNoData);
+
+        for (name, score, rank) in [("b", 20.0, 0), ("a", 10.0, 0), ("c", 30.0, 2)] {
+            let add =
+                db_list.sorted_add(&db_pack_info, &list_name, &member(name), score, &user_key);
+            assert_eq!(add.unwrap(), SuccessReply(rank.to_string()));
+        }
+
+        // re-adding a member replaces its score
+        let update = db_list.sorted_add(&db_pack_info, &list_name, &member("a"), 40.0, &user_key);
+        assert_eq!(update.unwrap(), SuccessReply("2".to_string()));
+
+        let rank = db_list.sorted_rank(&db_pack_info, &list_name, &member("b"), &user_key);
+        assert_eq!(rank.unwrap(), SuccessReply("0".to_string()));
+
+        let missing_rank = db_list.sorted_rank(&db_pack_info, &list_name, &member("z"), &user_key);
+        assert_eq!(missing_rank.unwrap(), SuccessReply("null".to_string()));
+
+        let range = db_list.sorted_range_by_score(&db_pack_info, &list_name, 20.0, 30.0, &user_key);
+        let expected_range = vec![
+            ScoredMember::new("b".to_string(), 20.0),
+            ScoredMember::new("c".to_string(), 30.0),
+        ];
+        assert_eq!(
+            range.unwrap(),
+            SuccessReply(serde_json::to_string(&expected_range).unwrap())
+        );
+
+        let top = db_list.sorted_top(&db_pack_info, &list_name, 2, &user_key);
+        let expected_top = vec![
+            ScoredMember::new("a".to_string(), 40.0),
+            ScoredMember::new("c".to_string(), 30.0),
+        ];
+        assert_eq!(
+            top.unwrap(),
+            SuccessReply(serde_json::to_string(&expected_top).unwrap())
+        );
+
+        let removed = db_list.sorted_remove(&db_pack_info, &list_name, &member("a"), &user_key);
+        assert_eq!(removed.unwrap(), SuccessReply("true".to_string()));
+
+        let invalid_perms = db_list.sorted_top(
+            &db_pack_info,
+            &list_name,
+            1,
+            &"not a valid key most likely".to_string(),
+        );
+        assert_eq!(invalid_perms.unwrap_err(), InvalidPermissions);
+
+        let delete_response = db_list.delete_db(db_name, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(delete_response.unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_queue() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_dblist_1_queue";
+        let db_pack_info = DBPacketInfo::new(db_name);
+        let queue_name = DBLocation::new("jobs");
+        let user_key = TEST_USER_KEY.to_string();
+
+        let create_response = db_list.create_db(
+            db_name,
+            get_db_test_settings(),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(create_response.unwrap(), SuccessNoData);
+
+        let db = db_list.cache.get(&db_pack_info).unwrap();
+        db.write().unwrap().mark_saved();
+        let empty_pop = db_list.queue_pop(&db_pack_info, &queue_name, &user_key);
+        assert_eq!(empty_pop.unwrap(), SuccessNoData);
+        // a blocking pop tries an empty queue each time it is woken, which should not leave the db to be saved again
+        assert_eq!(db.read().unwrap().is_dirty(), false);
+
+        for (i, job) in ["job1", "job2"].iter().enumerate() {
+            let push = db_list.queue_push(
+                &db_pack_info,
+                &queue_name,
+                &DBData::new(job.to_string()),
+                &user_key,
+            );
+            assert_eq!(push.unwrap(), SuccessReply((i + 1).to_string()));
+        }
+
+        let pop_invalid_perms = db_list.queue_pop(
+            &db_pack_info,
+            &queue_name,
+            &"not a valid key most likely".to_string(),
+        );
+        assert_eq!(pop_invalid_perms.unwrap_err(), InvalidPermissions);
+
+        for job in ["job1", "job2"] {
+            let pop = db_list.queue_pop(&db_pack_info, &queue_name, &user_key);
+            assert_eq!(pop.unwrap(), SuccessReply(job.to_string()));
+        }
+
+        let len = db_list.get_list_length(&db_pack_info, &queue_name, &user_key);
+        assert_eq!(len.unwrap(), SuccessReply("0".to_string()));
+
+        let delete_response = db_list.delete_db(db_name, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(delete_response.unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_queue_push_wakes_waiters() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_dblist_1_queue_waiters";
+        let db_pack_info = DBPacketInfo::new(db_name);
+        let queue_name = DBLocation::new("jobs");
+        let user_key = TEST_USER_KEY.to_string();
+
+        let create_response = db_list.create_db(
+            db_name,
+            get_db_test_settings(),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(create_response.unwrap(), SuccessNoData);
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let waker: std::sync::Arc<QueueWaker> = std::sync::Arc::new(move || {
+            let _ = sender.send(());
+        });
+        db_list
+            .queue_waiters
+            .wait_for_push(&db_pack_info, queue_name.as_key(), &waker);
+
+        // pushing to another queue, or failing to push, does not wake the waiter
+        let other_push = db_list.queue_push(
+            &db_pack_info,
+            &DBLocation::new("other_jobs"),
+            &DBData::new("job0".to_string()),
+            &user_key,
+        );
+        assert_eq!(other_push.unwrap(), SuccessReply("1".to_string()));
+        let push_invalid_perms = db_list.queue_push(
+            &db_pack_info,
+            &queue_name,
+            &DBData::new("job0".to_string()),
+            &"not a valid key most likely".to_string(),
+        );
+        assert_eq!(push_invalid_perms.unwrap_err(), InvalidPermissions);
+        assert!(receiver.try_recv().is_err());
+
+        let push = db_list.queue_push(
+            &db_pack_info,
+            &queue_name,
+            &DBData::new("job1".to_string()),
+            &user_key,
+        );
+        assert_eq!(push.unwrap(), SuccessReply("1".to_string()));
+        assert!(receiver.try_recv().is_ok());
+
+        // a waker is only woken once for each time it waits, and not at all once dropped
+        let push = db_list.queue_push(
+            &db_pack_info,
+            &queue_name,
+            &DBData::new("job2".to_string()),
+            &user_key,
+        );
+        assert_eq!(push.unwrap(), SuccessReply("2".to_string()));
+        assert!(receiver.try_recv().is_err());
+        db_list
+            .queue_waiters
+            .wait_for_push(&db_pack_info, queue_name.as_key(), &waker);
+        drop(waker);
+        assert_eq!(
+            db_list
+                .queue_waiters
+                .notify_push(&db_pack_info, queue_name.as_key()),
+            0
+        );
+
+        let delete_response = db_list.delete_db(db_name, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(delete_response.unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_db_bytes() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_dblist_1_bytes";
+        let db_pack_info = DBPacketInfo::new(db_name);
+        let location = DBLocation::new("blob");
+        let user_key = TEST_USER_KEY.to_string();
+        let data = DBBytes::new(vec![0, 255, 0, 10, 13]);
+
+        let create_response = db_list.create_db(
+            db_name,
+            get_db_test_settings(),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(create_response.unwrap(), SuccessNoData);
+
+        let write = db_list.write_db_bytes(&db_pack_info, &location, &data, &user_key);
+        assert_eq!(write.unwrap(), SuccessNoData);
+
+        let read = db_list.read_db_bytes(&db_pack_info, &location, &user_key);
+        assert_eq!(read.unwrap(), SuccessReply(data.to_base64()));
+
+        // binary values do not overlap with string values
+        let read_string = db_list.read_db(&db_pack_info, &location, &user_key);
+        assert_eq!(read_string.unwrap_err(), ValueNotFound);
+
+        let overwrite =
+            db_list.write_db_bytes(&db_pack_info, &location, &DBBytes::new(vec![1]), &user_key);
+        assert_eq!(overwrite.unwrap(), SuccessReply(data.to_base64()));
+
+        let invalid_perms = db_list.read_db_bytes(
+            &db_pack_info,
+            &location,
+            &"not a valid key most likely".to_string(),
+        );
+        assert_eq!(invalid_perms.unwrap_err(), InvalidPermissions);
+
+        let missing = db_list.read_db_bytes(&db_pack_info, &DBLocation::new("missing"), &user_key);
+        assert_eq!(missing.unwrap_err(), ValueNotFound);
+
+        let delete_response = db_list.delete_db(db_name, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(delete_response.unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_db_bytes_streaming() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let db_name = "test_dblist_1_stream_bytes";
+        let db_pack_info = DBPacketInfo::new(db_name);
+        let location = DBLocation::new("blob");
+        let user_key = TEST_USER_KEY.to_string();
+        let data: Vec<u8> = (0..2000).map(|i| (i % 256) as u8).collect();
+
+        let create_response = db_list.create_db(
+            db_name,
+            get_db_test_settings(),
+            &TEST_SUPER_ADMIN_KEY.to_string(),
+        );
+        assert_eq!(create_response.unwrap(), SuccessNoData);
+
+        let check = db_list.check_write_permissions(&db_pack_info, &user_key);
+        assert_eq!(check.unwrap(), SuccessNoData);
+
+        let check_invalid_perms = db_list
+            .check_write_permissions(&db_pack_info, &"not a valid key most likely".to_string());
+        assert_eq!(check_invalid_perms.unwrap_err(), InvalidPermissions);
+
+        let _ = db_list.write_db_bytes(
+            &db_pack_info,
+            &location,
+            &DBBytes::new(data.clone()),
+            &user_key,
+        );
+
+        let chunks: Vec<DBBytes> = db_list
+            .stream_db_bytes(&db_pack_info, &location, &user_key)
+            .unwrap()
+            .iter()
+            .collect();
+        assert!(chunks.len() > 1);
+        let streamed: Vec<u8> = chunks
+            .iter()
+            .flat_map(|chunk| chunk.get_data().to_vec())
+            .collect();
+        assert_eq!(streamed, data);
+
+        let missing =
+            db_list.stream_db_bytes(&db_pack_info, &DBLocation::new("missing"), &user_key);
+        assert_eq!(missing.unwrap_err(), ValueNotFound);
+
+        let delete_response = db_list.delete_db(db_name, &TEST_SUPER_ADMIN_KEY.to_string());
+        assert_eq!(delete_response.unwrap(), SuccessNoData);
+    }
+
+    #[test]
+    fn test_wal_replay() {
+        let data_dir = PathBuf::from("./data/test_wal_replay");
+        let _ = fs::remove_dir_all(&data_dir);
+        fs::create_dir_all(&data_dir).unwrap();
+
+        let mut db_list = get_db_list_for_testing();
+        db_list.data_dir = data_dir.clone();
+        db_list.wal = WriteAheadLog::new(&data_dir);
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+
+        let db_name = "test_wal_replay_db";
+        let db_info = DBPacketInfo::new(db_name);
+        let user_key = TEST_USER_KEY.to_string();
+        db_list
+            .create_db(
+                db_name,
+                get_db_test_settings(),
+                &TEST_SUPER_ADMIN_KEY.to_string(),
+            )
+            .unwrap();
+        db_list
+            .write_db(
+                &db_info,
+                &DBLocation::new("key1"),
+                &DBData::new("value1".to_string()),
+                &user_key,
+            )
+            .unwrap();
+        db_list
+            .write_db(
+                &db_info,
+                &DBLocation::new("key2"),
+                &DBData::new("value2".to_string()),
+                &user_key,
+            )
+            .unwrap();
+        db_list
+            .delete_data(&db_info, &DBLocation::new("key2"), &user_key)
+            .unwrap();
+        db_list
+            .queue_push(
+                &db_info,
+                &DBLocation::new("queue"),
+                &DBData::new("item".to_string()),
+                &user_key,
+            )
+            .unwrap();
+        assert!(!db_list.wal.is_empty());
+
+        // the db list is dropped without saving, as if the server crashed
+        drop(db_list);
+
+        let db_list = DBList::load_db_list_from(&data_dir);
+        assert!(db_list.list.read().unwrap().contains(&db_info));
+        assert_eq!(
+            db_list
+                .read_db(&db_info, &DBLocation::new("key1"), &user_key)
+                .unwrap(),
+            SuccessReply("value1".to_string())
+        );
+        assert_eq!(
+            db_list
+                .read_db(&db_info, &DBLocation::new("key2"), &user_key)
+                .unwrap_err(),
+            ValueNotFound
+        );
+        assert_eq!(
+            db_list
+                .read_from_list(&db_info, &DBLocation::new("queue"), 0, &user_key)
+                .unwrap(),
+            SuccessReply("item".to_string())
+        );
+
+        db_list.checkpoint_wal();
+        assert!(db_list.wal.is_empty());
+
+        // clean up unit test files
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt_db_file_loads_backup() {
+        let data_dir = PathBuf::from("./data/test_corrupt_db_file");
+        let _ = fs::remove_dir_all(&data_dir);
+        fs::create_dir_all(&data_dir).unwrap();
+
+        let mut db_list = get_db_list_for_testing();
+        db_list.data_dir = data_dir.clone();
+        db_list.wal = WriteAheadLog::new(&data_dir);
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+
+        let db_name = "test_corrupt_db";
+        let db_info = DBPacketInfo::new(db_name);
+        let db_location = DBLocation::new("key");
+        let user_key = TEST_USER_KEY.to_string();
+        db_list
+            .create_db(
+                db_name,
+                get_db_test_settings(),
+                &TEST_SUPER_ADMIN_KEY.to_string(),
+            )
+            .unwrap();
+        db_list
+            .write_db(
+                &db_info,
+                &db_location,
+                &DBData::new("old".to_string()),
+                &user_key,
+            )
+            .unwrap();
+        db_list.save_specific_db(&db_info);
+        db_list
+            .write_db(
+                &db_info,
+                &db_location,
+                &DBData::new("new".to_string()),
+                &user_key,
+            )
+            .unwrap();
+        db_list.save_specific_db(&db_info);
+
+        // damage the saved db so it no longer matches its checksum
+        let db_path = data_dir.join(db_name);
+        let mut contents = fs::read(&db_path).unwrap();
+        let last = contents.len() - 2;
+        contents[last] = b'#';
+        fs::write(&db_path, contents).unwrap();
+
+        db_list.cache.clear();
+        assert_eq!(
+            db_list.read_db(&db_info, &db_location, &user_key).unwrap(),
+            SuccessReply("old".to_string())
+        );
+
+        // clean up unit test files
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let data_dir = PathBuf::from("./data/test_snapshot");
+        let restore_dir = PathBuf::from("./data/test_snapshot_restore");
+        let _ = fs::remove_dir_all(&data_dir);
+        let _ = fs::remove_dir_all(&restore_dir);
+        fs::create_dir_all(&data_dir).unwrap();
+
+        let mut db_list = get_db_list_for_testing();
+        db_list.data_dir = data_dir.clone();
+        db_list.wal = WriteAheadLog::new(&data_dir);
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+
+        let db_name = "test_snapshot_db";
+        let db_info = DBPacketInfo::new(db_name);
+        let db_location = DBLocation::new("key");
+        let user_key = TEST_USER_KEY.to_string();
+        db_list
+            .create_db(
+                db_name,
+                get_db_test_settings(),
+                &TEST_SUPER_ADMIN_KEY.to_string(),
+            )
+            .unwrap();
+        db_list
+            .write_db(
+                &db_info,
+                &db_location,
+                &DBData::new("value".to_string()),
+                &user_key,
+            )
+            .unwrap();
+
+        assert_eq!(
+            db_list.create_snapshot(&user_key).unwrap_err(),
+            InvalidPermissions
+        );
+        let snapshot_name = db_list
+            .create_snapshot(&TEST_SUPER_ADMIN_KEY.to_string())
+            .unwrap()
+            .into_option()
+            .unwrap();
+
+        DBList::restore_snapshot(&restore_dir, &data_dir.join("backups").join(snapshot_name))
+            .unwrap();
+        let restored = DBList::load_db_list_from(&restore_dir);
+        assert!(restored.list.read().unwrap().contains(&db_info));
+        assert!(restored.is_super_admin(&TEST_SUPER_ADMIN_KEY.to_string()));
+        assert_eq!(
+            restored.read_db(&db_info, &db_location, &user_key).unwrap(),
+            SuccessReply("value".to_string())
+        );
+
+        // clean up unit test files
+        fs::remove_dir_all(&data_dir).unwrap();
+        fs::remove_dir_all(&restore_dir).unwrap();
+    }
+
+    #[test]
+    fn test_replication() {
+        let admin_key = TEST_SUPER_ADMIN_KEY.to_string();
+        let user_key = TEST_USER_KEY.to_string();
+
+        let mut primary = get_db_list_for_testing();
+        primary.saving_enabled = false;
+        primary
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(admin_key.clone());
+        let mut replica = get_db_list_for_testing();
+        replica.saving_enabled = false;
+
+        assert_eq!(
+            primary.replicate(None, &admin_key).unwrap_err(),
+            DBPacketResponseError::ReplicationDisabled
+        );
+        primary.replication = ReplicationLog::new(100);
+        assert_eq!(
+            primary.replicate(None, &user_key).unwrap_err(),
+            InvalidPermissions
+        );
+
+        let db_info = DBPacketInfo::new("test_replication_db");
+        let renamed_info = DBPacketInfo::new("test_replication_db_renamed");
+        let location = DBLocation::new("key");
+        primary
+            .create_db(db_info.get_db_name(), get_db_test_settings(), &admin_key)
+            .unwrap();
+        primary
+            .write_db(
+                &db_info,
+                &location,
+                &DBData::new("first".to_string()),
+                &user_key,
+            )
+            .unwrap();
+
+        // a replica without a position is sent every database
+        let receiver = primary.replicate(None, &admin_key).unwrap();
+        for item in receiver.try_iter() {
+            replica.apply_replicated(item).unwrap();
+        }
+        assert_eq!(
+            replica.read_db(&db_info, &location, &user_key).unwrap(),
+            SuccessReply("first".to_string())
+        );
+
+        primary
+            .write_db(
+                &db_info,
+                &location,
+                &DBData::new("second".to_string()),
+                &user_key,
+            )
+            .unwrap();
+        primary
+            .rename_db(&db_info, &renamed_info, &admin_key)
+            .unwrap();
+        for item in receiver.try_iter() {
+            replica.apply_replicated(item).unwrap();
+        }
+        assert_eq!(
+            replica.read_db(&db_info, &location, &user_key).unwrap_err(),
+            DBNotFound
+        );
+        assert_eq!(
+            replica
+                .read_db(&renamed_info, &location, &user_key)
+                .unwrap(),
+            SuccessReply("second".to_string())
+        );
+
+        // a replica that is caught up is only sent new changes
+        drop(receiver);
+        let position = ReplicationPosition {
+            log_id: primary.replication.log_id(),
+            next_sequence: primary.replication.next_sequence(),
+        };
+        let receiver = primary.replicate(Some(position), &admin_key).unwrap();
+        assert!(receiver.try_recv().is_err());
+        primary
+            .delete_db(renamed_info.get_db_name(), &admin_key)
+            .unwrap();
+        for item in receiver.try_iter() {
+            replica.apply_replicated(item).unwrap();
+        }
+        assert!(replica.list.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_storage_quota() {
+        let admin_key = TEST_SUPER_ADMIN_KEY.to_string();
+        let user_key = TEST_USER_KEY.to_string();
+
+        let mut db_list = get_db_list_for_testing();
+        db_list.saving_enabled = false;
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(admin_key.clone());
+
+        let db_info = DBPacketInfo::new("test_storage_quota_db");
+        db_list
+            .create_db(
+                db_info.get_db_name(),
+                get_db_test_settings().with_quota(StorageQuota::new(Some(2), None)),
+                &admin_key,
+            )
+            .unwrap();
+
+        let write = |db_list: &DBList, key: &str, value: &str| {
+            db_list.write_db(
+                &db_info,
+                &DBLocation::new(key),
+                &DBData::new(value.to_string()),
+                &user_key,
+            )
+        };
+
+        write(&db_list, "a", "1").unwrap();
+        db_list
+            .add_to_list(
+                &db_info,
+                &DBLocation::new("list"),
+                &DBData::new("1".to_string()),
+                None,
+                &user_key,
+            )
+            .unwrap();
+        assert_eq!(
+            write(&db_list, "b", "1").unwrap_err(),
+            DBPacketResponseError::QuotaExceeded
+        );
+        // writes to existing entries do not add entries
+        write(&db_list, "a", "2").unwrap();
+        db_list
+            .add_to_list(
+                &db_info,
+                &DBLocation::new("list"),
+                &DBData::new("2".to_string()),
+                None,
+                &user_key,
+            )
+            .unwrap();
+        assert_eq!(
+            db_list
+                .write_many(
+                    &db_info,
+                    &[(DBLocation::new("c"), DBData::new("1".to_string()))],
+                    &user_key
+                )
+                .unwrap_err(),
+            DBPacketResponseError::QuotaExceeded
+        );
+
+        // the server quota applies when it is stricter than the quota of the db
+        db_list.quota = StorageQuota::new(None, Some(8));
+        assert_eq!(
+            write(&db_list, "a", "too long").unwrap_err(),
+            DBPacketResponseError::QuotaExceeded
+        );
+        write(&db_list, "a", "3").unwrap();
+        assert_eq!(
+            db_list
+                .set_add(
+                    &db_info,
+                    &DBLocation::new("list"),
+                    &DBData::new("member".to_string()),
+                    &user_key
+                )
+                .unwrap_err(),
+            DBPacketResponseError::QuotaExceeded
+        );
+
+        // a db past a lowered quota can still be shrunk
+        db_list.quota = StorageQuota::new(Some(1), Some(1));
+        write(&db_list, "a", "").unwrap();
+        db_list
+            .delete_data(&db_info, &DBLocation::new("a"), &user_key)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_audit_log() {
+        let data_dir = PathBuf::from("./data/test_audit_log");
+        let _ = fs::remove_dir_all(&data_dir);
+        fs::create_dir_all(&data_dir).unwrap();
+
+        // small enough that every entry rotates the log
+        let audit_log = AuditLog::new(&data_dir, 10, 2);
+        let client = "127.0.0.1:8222".parse().unwrap();
+
+        for (index, key) in ["a", "b", "c", "d"].into_iter().enumerate() {
+            let packet = DBPacket::new_write("audit_db", key, "value");
+            let response = if index == 3 {
+                Err(InvalidPermissions)
+            } else {
+                Ok(SuccessNoData)
+            };
+            audit_log.record(client, "audit_key", &AuditTarget::new(&packet), &response);
+        }
+
+        // the oldest entry was removed once more than 2 rotated logs were kept
+        let entries = audit_log.read_entries().unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].target.keys, vec!["b".to_string()]);
+        assert_eq!(entries[2].target.operation, "Write");
+        assert_eq!(entries[2].target.db, Some("audit_db".to_string()));
+        assert_eq!(entries[2].client_key, "audit_key");
+        assert_eq!(entries[2].result, Err(InvalidPermissions));
+
+        // clean up unit test files
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn test_logger() {
+        use std::io::Write;
+
+        let data_dir = PathBuf::from("./data/test_logger");
+        let _ = fs::remove_dir_all(&data_dir);
+        fs::create_dir_all(&data_dir).unwrap();
+
+        let logger = Logger::new(&data_dir, 20, 1);
+        (&logger).write_all(b"first entry\n").unwrap();
+        (&logger).write_all(b"second entry\n").unwrap();
+        (&logger).write_all(b"third entry\n").unwrap();
+
+        // each entry is kept whole, and only one rotated file is kept
+        assert_eq!(fs::read_to_string(logger.path()).unwrap(), "third entry\n");
+        assert_eq!(
+            fs::read_to_string(data_dir.join("log.log.1")).unwrap(),
+            "second entry\n"
+        );
+        assert!(!data_dir.join("log.log.2").exists());
+
+        assert_eq!("DEBUG".parse::<LogLevel>(), Ok(LogLevel::Debug));
+        assert!("verbose".parse::<LogLevel>().is_err());
+        assert_eq!(LogLevel::Warn.as_tracing_level(), tracing::Level::WARN);
+
+        // clean up unit test files
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn test_server_info() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let super_admin_key = TEST_SUPER_ADMIN_KEY.to_string();
+
+        db_list
+            .create_db(
+                "test_server_info_db",
+                get_db_test_settings(),
+                &super_admin_key,
+            )
+            .unwrap();
+        db_list
+            .write_db(
+                &DBPacketInfo::new("test_server_info_db"),
+                &DBLocation::new("key"),
+                &DBData::new("value".to_string()),
+                &super_admin_key,
+            )
+            .unwrap();
+
+        assert_eq!(
+            db_list.server_info(&TEST_USER_KEY.to_string(), "1.0.0", Duration::ZERO, &[]),
+            Err(InvalidPermissions)
+        );
+
+        let response = db_list
+            .server_info(
+                &super_admin_key,
+                "1.0.0",
+                Duration::from_secs(5),
+                &["statistics"],
+            )
+            .unwrap();
+        let server_info: ServerInfo = serde_json::from_str(response.as_option().unwrap()).unwrap();
+        assert_eq!(server_info.version, "1.0.0");
+        assert_eq!(server_info.uptime, Duration::from_secs(5));
+        assert_eq!(server_info.features, vec!["statistics".to_string()]);
+        assert_eq!(server_info.db_count, 1);
+        assert_eq!(server_info.cached_db_count, 1);
+        assert_eq!(server_info.memory_estimate, "key".len() + "value".len());
+
+        db_list
+            .delete_db("test_server_info_db", &super_admin_key)
+            .unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "statistics")]
+    fn test_server_stats() {
+        use smol_db_common::statistics::ServerStatistics;
+
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+
+        db_list.record_request(DBPacket::new_list_db().name(), 10, 20, false);
+        db_list.record_request("ListDB", 10, 20, false);
+        db_list.record_request("Read", 5, 30, true);
+
+        assert_eq!(
+            db_list.get_server_stats(&TEST_USER_KEY.to_string()),
+            Err(InvalidPermissions)
+        );
+
+        let response = db_list
+            .get_server_stats(&TEST_SUPER_ADMIN_KEY.to_string())
+            .unwrap();
+        let Some(ResponseData::ServerStats(stats)) = response.into_data() else {
+            panic!("server stats should be returned");
+        };
+        assert_eq!(stats.get_total_req(), 3);
+        assert_eq!(stats.get_packet_counts().get("ListDB"), Some(&2));
+        assert_eq!(stats.get_packet_counts().get("Read"), Some(&1));
+        assert_eq!(stats.get_error_count(), 1);
+        assert_eq!(stats.get_bytes_in(), 25);
+        assert_eq!(stats.get_bytes_out(), 70);
+    }
+
+    #[test]
+    #[cfg(feature = "statistics")]
+    fn test_hot_keys() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let super_admin_key = TEST_SUPER_ADMIN_KEY.to_string();
+        let tracked_db = DBPacketInfo::new("test_hot_keys_db1");
+        let untracked_db = DBPacketInfo::new("test_hot_keys_db2");
+
+        db_list
+            .create_db(
+                tracked_db.get_db_name(),
+                get_db_test_settings().with_key_hit_tracking(true),
+                &super_admin_key,
+            )
+            .unwrap();
+        db_list
+            .create_db(
+                untracked_db.get_db_name(),
+                get_db_test_settings(),
+                &super_admin_key,
+            )
+            .unwrap();
+
+        for db in [&tracked_db, &untracked_db] {
+            for key in ["cold", "warm", "hot"] {
+                db_list
+                    .write_db(
+                        db,
+                        &DBLocation::new(key),
+                        &DBData::new("value".to_string()),
+                        &super_admin_key,
+                    )
+                    .unwrap();
+            }
+            for _ in 0..3 {
+                db_list
+                    .read_db(db, &DBLocation::new("hot"), &super_admin_key)
+                    .unwrap();
+            }
+            db_list
+                .read_db(db, &DBLocation::new("warm"), &super_admin_key)
+                .unwrap();
+        }
+
+        assert_eq!(
+            db_list.get_hot_keys(&tracked_db, 2, &TEST_USER_KEY.to_string()),
+            Err(InvalidPermissions)
+        );
+
+        let response = db_list
+            .get_hot_keys(&tracked_db, 2, &super_admin_key)
+            .unwrap();
+        let hot_keys: Vec<(String, u64)> =
+            serde_json::from_str(response.as_option().unwrap()).unwrap();
+        assert_eq!(
+            hot_keys,
+            vec![("hot".to_string(), 4), ("warm".to_string(), 2)]
+        );
+
+        let response = db_list
+            .get_hot_keys(&untracked_db, 2, &super_admin_key)
+            .unwrap();
+        let hot_keys: Vec<(String, u64)> =
+            serde_json::from_str(response.as_option().unwrap()).unwrap();
+        assert!(hot_keys.is_empty());
+
+        db_list
+            .delete_db(tracked_db.get_db_name(), &super_admin_key)
+            .unwrap();
+        db_list
+            .delete_db(untracked_db.get_db_name(), &super_admin_key)
+            .unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "statistics")]
+    fn test_reset_and_export_stats() {
+        use smol_db_common::statistics::StatisticsExport;
+
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let super_admin_key = TEST_SUPER_ADMIN_KEY.to_string();
+        let db_info = DBPacketInfo::new("test_export_stats_db");
+
+        db_list
+            .create_db(
+                db_info.get_db_name(),
+                get_db_test_settings().with_key_hit_tracking(true),
+                &super_admin_key,
+            )
+            .unwrap();
+        db_list
+            .write_db(
+                &db_info,
+                &DBLocation::new("key, with comma"),
+                &DBData::new("value".to_string()),
+                &super_admin_key,
+            )
+            .unwrap();
+        db_list.record_request("Write", 10, 20, false);
+
+        assert_eq!(
+            db_list.export_stats(StatsFormat::Json, &TEST_USER_KEY.to_string()),
+            Err(InvalidPermissions)
+        );
+        assert_eq!(
+            db_list.reset_stats(&db_info, &TEST_USER_KEY.to_string()),
+            Err(InvalidPermissions)
+        );
+
+        let response = db_list
+            .export_stats(StatsFormat::Json, &super_admin_key)
+            .unwrap();
+        let export: StatisticsExport = serde_json::from_str(response.as_option().unwrap()).unwrap();
+        assert_eq!(export.server.get_total_req(), 1);
+        assert_eq!(
+            export.databases[db_info.get_db_name()]
+                .get_key_hits()
+                .get("key, with comma"),
+            Some(&1)
+        );
+
+        let response = db_list
+            .export_stats(StatsFormat::Csv, &super_admin_key)
+            .unwrap();
+        let csv = response.as_option().unwrap();
+        assert!(csv.starts_with("database,metric,key,value\r\n,total_requests,,1\r\n"));
+        assert!(csv.contains(",packet_count,Write,1\r\n"));
+        assert!(csv.contains("test_export_stats_db,key_hits,\"key, with comma\",1\r\n"));
+
+        assert_eq!(
+            db_list.reset_stats(&db_info, &super_admin_key),
+            Ok(SuccessNoData)
+        );
+        let response = db_list
+            .export_stats(StatsFormat::Json, &super_admin_key)
+            .unwrap();
+        let export: StatisticsExport = serde_json::from_str(response.as_option().unwrap()).unwrap();
+        assert!(export.databases[db_info.get_db_name()]
+            .get_key_hits()
+            .is_empty());
+
+        db_list
+            .delete_db(db_info.get_db_name(), &super_admin_key)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_migrate_plaintext_keys() {
+        let mut db_list = get_db_list_for_testing();
+        db_list.saving_enabled = false;
+        db_list.key_hash_version = 0;
+        // a plaintext key that looks like a hash is still hashed
+        let hash_like_key = KeyHasher::default().hash_key("other_key");
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .extend([TEST_SUPER_ADMIN_KEY.to_string(), hash_like_key.clone()]);
+        let super_admin_key = TEST_SUPER_ADMIN_KEY.to_string();
+        let db_info = DBPacketInfo::new("test_migrate_plaintext_keys_db");
+
+        db_list
+            .create_db(
+                db_info.get_db_name(),
+                get_db_test_settings(),
+                &super_admin_key,
+            )
+            .unwrap();
+
+        db_list.migrate_plaintext_keys();
+        assert_eq!(db_list.key_hash_version, KEY_HASH_VERSION);
+
+        // the plaintext keys no longer work, only their hashes do
+        let super_admin_hash = db_list.key_hasher().hash_key(TEST_SUPER_ADMIN_KEY);
+        let user_hash = db_list.key_hasher().hash_key(TEST_USER_KEY);
+        assert!(super_admin_hash.starts_with("$argon2id$v=19$"));
+        assert!(!db_list.is_super_admin(&super_admin_key));
+        assert!(db_list.is_super_admin(&super_admin_hash));
+        assert!(!db_list.is_super_admin(&hash_like_key));
+        assert!(db_list.is_super_admin(&db_list.key_hasher().hash_key(&hash_like_key)));
+        assert_eq!(
+            db_list
+                .get_role(&db_info, &TEST_USER_KEY.to_string())
+                .unwrap(),
+            SuccessData(ResponseData::Role(Role::Other))
+        );
+        assert_eq!(
+            db_list.get_role(&db_info, &user_hash).unwrap(),
+            SuccessData(ResponseData::Role(Role::User))
+        );
+
+        // keys sent by a client are hashed unless they are hashes already stored, whatever they look like
+        let account_key = UserRegistry::account_key("account");
+        let mut keys = vec![
+            user_hash.clone(),
+            hash_like_key.clone(),
+            account_key.clone(),
+        ];
+        assert!(db_list
+            .key_hasher()
+            .hash_new_keys(&mut keys, std::slice::from_ref(&user_hash)));
+        assert_eq!(
+            keys,
+            vec![
+                user_hash.clone(),
+                db_list.key_hasher().hash_key(&hash_like_key),
+                account_key
+            ]
+        );
+
+        // settings sent back with the hashes read from them keep those hashes, and hash the keys added to them
+        let mut settings = db_list
+            .cache
+            .get(&db_info)
+            .unwrap()
+            .read()
+            .unwrap()
+            .get_settings()
+            .clone();
+        settings.users.push("new_user_key".to_string());
+        db_list.hash_settings_keys(&db_info, &mut settings);
+        assert_eq!(
+            settings.users,
+            vec![
+                user_hash.clone(),
+                db_list.key_hasher().hash_key("new_user_key")
+            ]
+        );
+
+        // a role is removed by the hash listed in the settings, or by the key itself
+        assert_eq!(db_list.removed_role_key(&db_info, &user_hash), user_hash);
+        assert_eq!(db_list.removed_role_key(&db_info, TEST_USER_KEY), user_hash);
+
+        // a different salt hashes keys differently
+        assert_ne!(KeyHasher::default().hash_key(TEST_USER_KEY), user_hash);
+    }
+
+    #[test]
+    fn test_bootstrap_super_admin() {
+        let db_list = get_db_list_for_testing();
+        let token = "test_bootstrap_token";
+        let token_hash = db_list.key_hasher().hash_key(token);
+        let first_key = db_list.key_hasher().hash_key("first_client_key");
+        let claiming_key = db_list.key_hasher().hash_key(TEST_SUPER_ADMIN_KEY);
+        db_list.set_bootstrap_token(token);
+
+        // a wrong token, a session without a key, or the token set as the key, can not claim it
+        assert_eq!(
+            db_list.claim_super_admin("wrong_token", &claiming_key),
+            Err(InvalidPermissions)
+        );
+        assert_eq!(
+            db_list.claim_super_admin(token, &String::new()),
+            Err(InvalidPermissions)
+        );
+        assert_eq!(
+            db_list.claim_super_admin(token, &token_hash),
+            Err(InvalidPermissions)
+        );
+        assert!(!db_list.is_super_admin(&token_hash));
+
+        assert_eq!(
+            db_list.claim_super_admin(token, &claiming_key),
+            Ok(SuccessNoData)
+        );
+        assert!(db_list.is_super_admin(&claiming_key));
+
+        // the token is consumed once claimed
+        assert_eq!(
+            db_list.claim_super_admin(token, &first_key),
+            Err(InvalidPermissions)
+        );
+        assert!(db_list.bootstrap_token.read().unwrap().is_none());
+        assert!(!db_list.is_super_admin(&first_key));
+
+        // without a token, a super admin can not be claimed at all
+        let db_list = get_db_list_for_testing();
+        assert_eq!(
+            db_list.claim_super_admin(token, &first_key),
+            Err(InvalidPermissions)
+        );
+        assert!(db_list.super_admin_hash_list.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_user_accounts() {
+        let db_list = get_db_list_for_testing();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(TEST_SUPER_ADMIN_KEY.to_string());
+        let super_admin_key = TEST_SUPER_ADMIN_KEY.to_string();
+        let user_key = TEST_USER_KEY.to_string();
+        let account_key = UserRegistry::account_key("test_user");
+
+        // only a super admin can manage users
+        assert_eq!(
+            db_list.create_user("test_user", "password", &user_key),
+            Err(InvalidPermissions)
+        );
+        assert_eq!(
+            db_list.create_user("test_user", "password", &super_admin_key),
+            Ok(SuccessNoData)
+        );
+        assert_eq!(
+            db_list.create_user("test_user", "other_password", &super_admin_key),
+            Err(DBPacketResponseError::UserAlreadyExists)
+        );
+        assert_eq!(
+            db_list.create_user("", "password", &super_admin_key),
+            Err(DBPacketResponseError::UserAlreadyExists)
+        );
+        assert_eq!(
+            db_list.list_users(&super_admin_key).unwrap(),
+            SuccessReply(serde_json::to_string(&vec![("test_user", false)]).unwrap())
+        );
+
+        assert_eq!(
+            db_list.login("test_user", "password"),
+            Ok(account_key.clone())
+        );
+        assert_eq!(
+            db_list.login("test_user", "wrong_password"),
+            Err(InvalidPermissions)
+        );
+        assert_eq!(
+            db_list.login("missing_user", "password"),
+            Err(InvalidPermissions)
+        );
+        assert!(db_list.is_active_key(&account_key));
+        assert!(db_list.is_active_key(&user_key));
+
+        // account keys are given roles as they are, rather than being hashed
+        assert_eq!(
+            db_list.key_hasher().hash_role_key(&account_key),
+            account_key
+        );
+        assert_ne!(db_list.key_hasher().hash_role_key(&user_key), user_key);
+
+        assert_eq!(
+            db_list.set_user_disabled("test_user", true, &super_admin_key),
+            Ok(SuccessNoData)
+        );
+        assert_eq!(
+            db_list.set_user_disabled("missing_user", true, &super_admin_key),
+            Err(UserNotFound)
+        );
+        assert_eq!(
+            db_list.login("test_user", "password"),
+            Err(InvalidPermissions)
+        );
+        assert!(!db_list.is_active_key(&account_key));
+        assert!(!db_list.is_active_key(&UserRegistry::account_key("missing_user")));
+
+        assert_eq!(
+            db_list.set_user_disabled("test_user", false, &super_admin_key),
+            Ok(SuccessNoData)
+        );
+        assert_eq!(db_list.login("test_user", "password"), Ok(account_key));
+    }
+
+    #[test]
+    fn test_sessions() {
+        let mut db_list = get_db_list_for_testing();
+        db_list.sessions = SessionStore::new(Duration::from_millis(200));
+        let client_key = TEST_USER_KEY.to_string();
+
+        assert_eq!(db_list.create_session(""), Err(InvalidPermissions));
+        let SuccessReply(token) = db_list.create_session(&client_key).unwrap() else {
+            panic!("create session did not reply with a token");
+        };
+        let SuccessReply(ended_token) = db_list.create_session(&client_key).unwrap() else {
+            panic!("create session did not reply with a token");
+        };
+        assert_ne!(token, ended_token);
+
+        assert_eq!(db_list.resume_session(&token), Ok(client_key.clone()));
+        assert_eq!(
+            db_list.resume_session("not_a_token"),
+            Err(InvalidPermissions)
+        );
+        assert_eq!(db_list.end_session(&ended_token), Ok(SuccessNoData));
+        assert_eq!(
+            db_list.resume_session(&ended_token),
+            Err(InvalidPermissions)
+        );
+        assert_eq!(db_list.end_session(&ended_token), Err(InvalidPermissions));
+
+        // sessions expire after the time to live
+        let SuccessReply(expiring_token) = db_list.create_session(&client_key).unwrap() else {
+            panic!("create session did not reply with a token");
+        };
+        thread::sleep(Duration::from_millis(300));
+        assert_eq!(db_list.resume_session(&token), Err(InvalidPermissions));
+        assert_eq!(db_list.sessions.remove_expired(), 1);
+        assert_eq!(
+            db_list.resume_session(&expiring_token),
+            Err(InvalidPermissions)
+        );
+    }
+
+    #[test]
+    fn test_rotate_server_key() {
+        let data_dir = PathBuf::from("./data/test_rotate_server_key");
+        let _ = fs::remove_dir_all(&data_dir);
+        fs::create_dir_all(&data_dir).unwrap();
+        let key_path = data_dir.join(SERVER_KEY_FILE_NAME);
+
+        let mut db_list = get_db_list_for_testing();
+        db_list.data_dir = data_dir.clone();
+        let super_admin = TEST_SUPER_ADMIN_KEY.to_string();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(super_admin.clone());
+        let old_fingerprint = db_list.server_key.read().unwrap().fingerprint();
+
+        assert_eq!(
+            db_list.rotate_server_key(&TEST_USER_KEY.to_string(), ServerKey::default()),
+            Err(InvalidPermissions)
+        );
+
+        let new_key = ServerKey::default();
+        let expected_fingerprint = new_key.fingerprint();
+        let new_fingerprint = db_list
+            .rotate_server_key(&super_admin, new_key)
+            .unwrap()
+            .into_option()
+            .unwrap();
+        assert_eq!(new_fingerprint, expected_fingerprint);
+        assert_ne!(new_fingerprint, old_fingerprint);
+        assert_eq!(
+            db_list.server_key.read().unwrap().fingerprint(),
+            new_fingerprint
+        );
+
+        // the rotated key is the one loaded after a restart
+        let loaded = ServerKey::load_or_create(&key_path).unwrap();
+        assert_eq!(loaded.fingerprint(), new_fingerprint);
+
+        // a key other users can read is refused
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&key_path, fs::Permissions::from_mode(0o644)).unwrap();
+            assert_eq!(
+                ServerKey::load(&key_path).unwrap_err().kind(),
+                std::io::ErrorKind::PermissionDenied
+            );
+        }
+
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn test_db_schema() {
+        let admin_key = TEST_SUPER_ADMIN_KEY.to_string();
+        let user_key = TEST_USER_KEY.to_string();
+
+        let mut db_list = get_db_list_for_testing();
+        db_list.saving_enabled = false;
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(admin_key.clone());
+
+        let db_info = DBPacketInfo::new("test_db_schema_db");
+        db_list
+            .create_db(db_info.get_db_name(), get_db_test_settings(), &admin_key)
+            .unwrap();
+
+        let write = |db_list: &DBList, key: &str, value: &str| {
+            db_list.write_db(
+                &db_info,
+                &DBLocation::new(key),
+                &DBData::new(value.to_string()),
+                &user_key,
+            )
+        };
+
+        // any value can be written without a schema
+        write(&db_list, "a", "not json").unwrap();
+        assert_eq!(
+            db_list.get_db_schema(&db_info, &user_key).unwrap(),
+            SuccessNoData
+        );
+
+        let schema = ValueSchema::new(serde_json::json!({
+            "type": "object",
+            "properties": { "age": { "type": "integer", "minimum": 0 } },
+            "required": ["age"]
+        }))
+        .unwrap();
+        assert_eq!(
+            ValueSchema::new(serde_json::json!({ "type": 5 })).unwrap_err(),
+            DBPacketResponseError::InvalidSchema
+        );
+
+        // users can not manage settings by default
+        assert_eq!(
+            db_list
+                .set_db_schema(&db_info, Some(schema.clone()), &user_key)
+                .unwrap_err(),
+            InvalidPermissions
+        );
+        db_list
+            .set_db_schema(&db_info, Some(schema.clone()), &admin_key)
+            .unwrap();
+        let received: ValueSchema = serde_json::from_str(
+            &db_list
+                .get_db_schema(&db_info, &user_key)
+                .unwrap()
+                .into_option()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(received, schema);
+
+        write(&db_list, "b", r#"{"age": 5}"#).unwrap();
+        assert!(matches!(
+            write(&db_list, "b", r#"{"age": -1}"#),
+            Err(DBPacketResponseError::SchemaViolation(_))
+        ));
+        assert!(matches!(
+            write(&db_list, "b", "not json"),
+            Err(DBPacketResponseError::SchemaViolation(_))
+        ));
+
+        // every write within a batch is checked before any is applied
+        let writes = vec![
+            (
+                DBLocation::new("c"),
+                DBData::new(r#"{"age": 1}"#.to_string()),
+            ),
+            (
+                DBLocation::new("d"),
+                DBData::new(r#"{"name": "d"}"#.to_string()),
+            ),
+        ];
+        assert!(matches!(
+            db_list.write_many(&db_info, &writes, &user_key),
+            Err(DBPacketResponseError::SchemaViolation(_))
+        ));
+        assert_eq!(
+            db_list
+                .read_db(&db_info, &DBLocation::new("c"), &user_key)
+                .unwrap_err(),
+            ValueNotFound
+        );
+
+        // removing the schema allows any value again
+        db_list.set_db_schema(&db_info, None, &admin_key).unwrap();
+        write(&db_list, "b", "not json").unwrap();
+    }
+
+    #[test]
+    fn test_key_limits() {
+        let admin_key = TEST_SUPER_ADMIN_KEY.to_string();
+        let user_key = TEST_USER_KEY.to_string();
+
+        let mut db_list = get_db_list_for_testing();
+        db_list.saving_enabled = false;
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(admin_key.clone());
+
+        let db_info = DBPacketInfo::new("test_key_limits_db");
+        db_list
+            .create_db(
+                db_info.get_db_name(),
+                get_db_test_settings().with_key_limits(KeyLimits::new(
+                    Some(8),
+                    Some(4),
+                    Some(KeyCharset::Alphanumeric),
+                )),
+                &admin_key,
+            )
+            .unwrap();
+
+        let write = |db_list: &DBList, key: &str, value: &str| {
+            db_list.write_db(
+                &db_info,
+                &DBLocation::new(key),
+                &DBData::new(value.to_string()),
+                &user_key,
+            )
+        };
+
+        write(&db_list, "jobs/1", "1234").unwrap();
+        assert_eq!(
+            write(&db_list, "jobs/1234", "1").unwrap_err(),
+            DBPacketResponseError::KeyTooLong
+        );
+        assert_eq!(
+            write(&db_list, "jobs/1", "12345").unwrap_err(),
+            DBPacketResponseError::ValueTooLarge
+        );
+        assert_eq!(
+            write(&db_list, "jobs 1", "1").unwrap_err(),
+            DBPacketResponseError::InvalidKeyCharacter(' ')
+        );
+
+        // list names and members are limited the same as keys and values
+        assert_eq!(
+            db_list
+                .add_to_list(
+                    &db_info,
+                    &DBLocation::new("list\n"),
+                    &DBData::new("1".to_string()),
+                    None,
+                    &user_key,
+                )
+                .unwrap_err(),
+            DBPacketResponseError::InvalidKeyCharacter('\n')
+        );
+        assert_eq!(
+            db_list
+                .set_add(
+                    &db_info,
+                    &DBLocation::new("set"),
+                    &DBData::new("12345".to_string()),
+                    &user_key,
+                )
+                .unwrap_err(),
+            DBPacketResponseError::ValueTooLarge
+        );
+        db_list
+            .add_to_list(
+                &db_info,
+                &DBLocation::new("list"),
+                &DBData::new("1".to_string()),
+                None,
+                &user_key,
+            )
+            .unwrap();
+
+        // limits are not saved when there are none
+        assert!(!serde_json::to_string(&get_db_test_settings())
+            .unwrap()
+            .contains("key_limits"));
+    }
+
+    #[test]
+    fn test_db_metadata() {
+        let admin_key = TEST_SUPER_ADMIN_KEY.to_string();
+        let user_key = TEST_USER_KEY.to_string();
+        let other_key = "test_other_key".to_string();
+
+        let mut db_list = get_db_list_for_testing();
+        db_list.saving_enabled = false;
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(admin_key.clone());
+
+        let db_info = DBPacketInfo::new("test_db_metadata_db");
+        db_list
+            .create_db(db_info.get_db_name(), get_db_test_settings(), &admin_key)
+            .unwrap();
+
+        let get_metadata = |db_list: &DBList| -> DBMetadata {
+            match db_list.get_db_metadata(&db_info, &user_key).unwrap() {
+                SuccessReply(data) => serde_json::from_str(&data).unwrap(),
+                SuccessNoData | SuccessData(_) => panic!("metadata should be returned"),
+            }
+        };
+
+        let metadata = get_metadata(&db_list);
+        assert!(metadata.created.is_some());
+        assert_eq!(metadata.creator, Some(admin_key.clone()));
+        assert!(metadata.description.is_empty());
+
+        db_list
+            .write_db(
+                &db_info,
+                &DBLocation::new("key"),
+                &DBData::new("value".to_string()),
+                &user_key,
+            )
+            .unwrap();
+        let modified = get_metadata(&db_list).modified.unwrap();
+        assert!(modified >= metadata.created.unwrap());
+
+        // users can not manage settings, so they can not describe the db either
+        assert_eq!(
+            db_list
+                .set_db_metadata(&db_info, "nope".to_string(), vec![], &user_key)
+                .unwrap_err(),
+            DBPacketResponseError::InvalidPermissions
+        );
+        db_list
+            .set_db_metadata(
+                &db_info,
+                "a test db".to_string(),
+                vec!["test".to_string()],
+                &admin_key,
+            )
+            .unwrap();
+
+        let metadata = get_metadata(&db_list);
+        assert_eq!(metadata.description, "a test db");
+        assert!(metadata.has_tag("test"));
+        assert_eq!(metadata.modified, Some(modified));
+        assert_eq!(metadata.creator, Some(admin_key.clone()));
+
+        // others can not list the db, so they can not see its metadata
+        assert_eq!(
+            db_list.get_db_metadata(&db_info, &other_key).unwrap_err(),
+            DBPacketResponseError::InvalidPermissions
+        );
+
+        // the listing holds the metadata, and can still be read as a list of db names
+        let SuccessData(ResponseData::DBList(listings)) = db_list.list_db().unwrap() else {
+            panic!("listing should be returned");
+        };
+        assert_eq!(listings.len(), 1);
+        assert_eq!(listings[0].info, db_info);
+        assert_eq!(listings[0].metadata, Some(metadata));
+        let names: Vec<DBPacketInfo> =
+            serde_json::from_str(&serde_json::to_string(&listings).unwrap()).unwrap();
+        assert_eq!(names, vec![db_info.clone()]);
+    }
+
+    #[test]
+    fn test_revision_history() {
+        let admin_key = TEST_SUPER_ADMIN_KEY.to_string();
+        let user_key = TEST_USER_KEY.to_string();
+
+        let mut db_list = get_db_list_for_testing();
+        db_list.saving_enabled = false;
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(admin_key.clone());
+
+        let db_info = DBPacketInfo::new("test_revision_history_db");
+        db_list
+            .create_db(
+                db_info.get_db_name(),
+                get_db_test_settings().with_max_revisions(2),
+                &admin_key,
+            )
+            .unwrap();
+
+        let location = DBLocation::new("key");
+        let write = |db_list: &DBList, value: &str| {
+            db_list
+                .write_db(
+                    &db_info,
+                    &location,
+                    &DBData::new(value.to_string()),
+                    &user_key,
+                )
+                .unwrap();
+        };
+        let list_revisions = |db_list: &DBList| -> Vec<RevisionInfo> {
+            match db_list
+                .list_revisions(&db_info, &location, &user_key)
+                .unwrap()
+            {
+                SuccessReply(data) => serde_json::from_str(&data).unwrap(),
+                SuccessNoData | SuccessData(_) => panic!("revisions should be returned"),
+            }
+        };
+        let read_revision = |db_list: &DBList, revision: u64| {
+            db_list
+                .read_revision(&db_info, &location, revision, &user_key)
+                .map(|response| {
+                    serde_json::from_str::<Revision>(&response.into_option().unwrap()).unwrap()
+                })
+        };
+
+        write(&db_list, "1");
+        assert!(list_revisions(&db_list).is_empty());
+
+        write(&db_list, "2");
+        // writing the same value again is not a revision
+        write(&db_list, "2");
+        write(&db_list, "3");
+        db_list.delete_data(&db_info, &location, &user_key).unwrap();
+
+        // only the two most recent revisions are kept
+        let revisions = list_revisions(&db_list);
+        assert_eq!(
+            revisions
+                .iter()
+                .map(|revision| revision.revision)
+                .collect::<Vec<u64>>(),
+            vec![2, 3]
+        );
+        assert_eq!(read_revision(&db_list, 2).unwrap().value, "2");
+        assert_eq!(read_revision(&db_list, 3).unwrap().value, "3");
+        assert_eq!(
+            read_revision(&db_list, 1).unwrap_err(),
+            DBPacketResponseError::ValueNotFound
+        );
+
+        // others can not read the db, so they can not read its revisions either
+        assert_eq!(
+            db_list
+                .list_revisions(&db_info, &location, &"test_other_key".to_string())
+                .unwrap_err(),
+            DBPacketResponseError::InvalidPermissions
+        );
+
+        // turning revisions off drops the revisions that were kept
+        db_list
+            .change_db_settings(&db_info, get_db_test_settings(), &admin_key)
+            .unwrap();
+        write(&db_list, "4");
+        write(&db_list, "5");
+        assert!(list_revisions(&db_list).is_empty());
+    }
+
+    #[test]
+    fn test_trash_restore() {
+        let data_dir = PathBuf::from("./data/test_trash");
+        let _ = fs::remove_dir_all(&data_dir);
+        fs::create_dir_all(&data_dir).unwrap();
+
+        let admin_key = TEST_SUPER_ADMIN_KEY.to_string();
+        let user_key = TEST_USER_KEY.to_string();
+
+        let mut db_list = get_db_list_for_testing();
+        db_list.data_dir = data_dir.clone();
+        db_list.wal = WriteAheadLog::new(&data_dir);
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(admin_key.clone());
+
+        let db_name = "test_trash_db";
+        let db_info = DBPacketInfo::new(db_name);
+        let db_location = DBLocation::new("key");
+        db_list
+            .create_db(db_name, get_db_test_settings(), &admin_key)
+            .unwrap();
+        db_list
+            .write_db(
+                &db_info,
+                &db_location,
+                &DBData::new("value".to_string()),
+                &user_key,
+            )
+            .unwrap();
+
+        // the unsaved write is kept in the trash along with the db
+        db_list.delete_db(db_name, &admin_key).unwrap();
+        assert!(!data_dir.join(db_name).exists());
+        assert_eq!(
+            fs::read_dir(data_dir.join(TRASH_DIR_NAME)).unwrap().count(),
+            1
+        );
+        assert_eq!(
+            db_list
+                .read_db(&db_info, &db_location, &user_key)
+                .unwrap_err(),
+            DBPacketResponseError::DBNotFound
+        );
+
+        assert_eq!(
+            db_list.restore_db(db_name, &user_key).unwrap_err(),
+            DBPacketResponseError::InvalidPermissions
+        );
+        db_list.restore_db(db_name, &admin_key).unwrap();
+        assert_eq!(
+            db_list.read_db(&db_info, &db_location, &user_key).unwrap(),
+            SuccessReply("value".to_string())
+        );
+        assert_eq!(
+            fs::read_dir(data_dir.join(TRASH_DIR_NAME)).unwrap().count(),
+            0
+        );
+        assert_eq!(
+            db_list.restore_db(db_name, &admin_key).unwrap_err(),
+            DBPacketResponseError::DBAlreadyExists
+        );
+
+        // dbs in the trash are only purged once they are past the retention
+        db_list.delete_db(db_name, &admin_key).unwrap();
+        assert_eq!(db_list.purge_expired_trash(), 0);
+        assert_eq!(
+            db_list.purge_trash(&admin_key).unwrap(),
+            SuccessReply("1".to_string())
+        );
+        assert_eq!(
+            db_list.restore_db(db_name, &admin_key).unwrap_err(),
+            DBPacketResponseError::DBNotFound
+        );
+
+        // clean up unit test files
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn test_namespaces() {
+        let data_dir = PathBuf::from("./data/test_namespaces");
+        let _ = fs::remove_dir_all(&data_dir);
+        fs::create_dir_all(&data_dir).unwrap();
+
+        let admin_key = TEST_SUPER_ADMIN_KEY.to_string();
+        let user_key = TEST_USER_KEY.to_string();
+
+        let mut db_list = get_db_list_for_testing();
+        db_list.data_dir = data_dir.clone();
+        db_list.wal = WriteAheadLog::new(&data_dir);
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(admin_key.clone());
+        let tenant_key = db_list.key_hasher().hash_key("tenant_admin_key");
+
+        // only super admins of the server can configure a namespace, the keys of its super admins are hashed
+        let mut settings = NamespaceSettings::new(vec!["tenant_admin_key".to_string()]);
+        settings.quota = StorageQuota::new(Some(1), None);
+        settings.max_databases = Some(2);
+        assert_eq!(
+            db_list
+                .set_namespace("tenant", settings.clone(), &tenant_key)
+                .unwrap_err(),
+            DBPacketResponseError::InvalidPermissions
+        );
+        assert_eq!(
+            db_list
+                .set_namespace("ten/ant", settings.clone(), &admin_key)
+                .unwrap_err(),
+            DBPacketResponseError::InvalidDBName
+        );
+        db_list
+            .set_namespace("tenant", settings, &admin_key)
+            .unwrap();
+        let SuccessReply(saved) = db_list.get_namespace("tenant", &tenant_key).unwrap() else {
+            panic!("namespace settings are sent as a reply");
+        };
+        let saved: NamespaceSettings = serde_json::from_str(&saved).unwrap();
+        assert_eq!(saved.super_admins, vec![tenant_key.clone()]);
+        assert_eq!(
+            db_list.get_namespace("other", &tenant_key).unwrap_err(),
+            DBPacketResponseError::InvalidPermissions
+        );
+
+        // the super admin of a namespace can only create dbs within it, and the dbs are saved directly in the data directory
+        let db_info = DBPacketInfo::new_in_namespace("tenant", "db");
+        assert_eq!(db_info.get_namespace(), Some("tenant"));
+        db_list
+            .create_db(db_info.get_db_name(), get_db_test_settings(), &tenant_key)
+            .unwrap();
+        assert!(data_dir.join("tenant%2Fdb").exists());
+        assert_eq!(
+            db_list
+                .create_db("other/db", get_db_test_settings(), &tenant_key)
+                .unwrap_err(),
+            DBPacketResponseError::InvalidPermissions
+        );
+        assert_eq!(
+            db_list
+                .create_db("top_level_db", get_db_test_settings(), &tenant_key)
+                .unwrap_err(),
+            DBPacketResponseError::InvalidPermissions
+        );
+        for invalid_name in ["tenant/", "/db", "tenant/db/nested", ""] {
+            assert_eq!(
+                db_list
+                    .create_db(invalid_name, get_db_test_settings(), &admin_key)
+                    .unwrap_err(),
+                DBPacketResponseError::InvalidDBName
+            );
+        }
+
+        // the super admin of the namespace has full access to its dbs, which are limited by the quota of the namespace
+        db_list
+            .write_db(
+                &db_info,
+                &DBLocation::new("a"),
+                &DBData::new("1".to_string()),
+                &tenant_key,
+            )
+            .unwrap();
+        assert_eq!(
+            db_list
+                .write_db(
+                    &db_info,
+                    &DBLocation::new("b"),
+                    &DBData::new("2".to_string()),
+                    &user_key,
+                )
+                .unwrap_err(),
+            DBPacketResponseError::QuotaExceeded
+        );
+
+        // the namespace holds at most two dbs, however dbs are added to it
+        db_list
+            .create_db("tenant/second", get_db_test_settings(), &tenant_key)
+            .unwrap();
+        assert_eq!(
+            db_list
+                .copy_db(
+                    &db_info,
+                    &DBPacketInfo::new("tenant/third"),
+                    false,
+                    &tenant_key
+                )
+                .unwrap_err(),
+            DBPacketResponseError::NamespaceFull
+        );
+        db_list
+            .create_db("top_level_db", get_db_test_settings(), &admin_key)
+            .unwrap();
+        assert_eq!(
+            db_list
+                .rename_db(
+                    &DBPacketInfo::new("top_level_db"),
+                    &DBPacketInfo::new("tenant/third"),
+                    &admin_key
+                )
+                .unwrap_err(),
+            DBPacketResponseError::NamespaceFull
+        );
+        db_list
+            .rename_db(
+                &DBPacketInfo::new("tenant/second"),
+                &DBPacketInfo::new("tenant/renamed"),
+                &tenant_key,
+            )
+            .unwrap();
+
+        // dbs in a namespace are moved to the trash and restored like any other db
+        db_list.delete_db("tenant/renamed", &tenant_key).unwrap();
+        assert!(!data_dir.join("tenant%2Frenamed").exists());
+        db_list.restore_db("tenant/renamed", &tenant_key).unwrap();
+        assert!(data_dir.join("tenant%2Frenamed").exists());
+        assert_eq!(
+            db_list.delete_db("top_level_db", &tenant_key).unwrap_err(),
+            DBPacketResponseError::InvalidPermissions
+        );
+
+        // clean up unit test files
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn test_cache_memory_budget() {
+        let data_dir = PathBuf::from("./data/test_cache_budget");
+        let _ = fs::remove_dir_all(&data_dir);
+        fs::create_dir_all(&data_dir).unwrap();
+
+        let admin_key = TEST_SUPER_ADMIN_KEY.to_string();
+        let user_key = TEST_USER_KEY.to_string();
+
+        let mut db_list = get_db_list_for_testing();
+        db_list.data_dir = data_dir.clone();
+        db_list.wal = WriteAheadLog::new(&data_dir);
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(admin_key.clone());
+
+        let db_names = ["test_budget_a", "test_budget_b", "test_budget_c"];
+        let db_location = DBLocation::new("key");
+        let value = "v".repeat(1000);
+        for db_name in db_names {
+            db_list
+                .create_db(db_name, get_db_test_settings(), &admin_key)
+                .unwrap();
+            db_list
+                .write_db(
+                    &DBPacketInfo::new(db_name),
+                    &db_location,
+                    &DBData::new(value.clone()),
+                    &user_key,
+                )
+                .unwrap();
+            // access times need to differ for the least recently accessed db to be evicted first
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        // every db fits without a budget, and none have reached their invalidation time
+        assert_eq!(db_list.sleep_caches(), 0);
+        db_list.cache_memory_budget = Some(3000);
+        assert_eq!(db_list.sleep_caches(), 1);
+        {
+            let cache = &db_list.cache;
+            assert!(!cache.contains_key(&DBPacketInfo::new("test_budget_a")));
+            assert!(cache.contains_key(&DBPacketInfo::new("test_budget_b")));
+            assert!(cache.contains_key(&DBPacketInfo::new("test_budget_c")));
+        }
+        assert_eq!(db_list.sleep_caches(), 0);
+
+        // the evicted db was saved before it was removed, and reading it evicts the next least recently accessed db
+        assert_eq!(
+            db_list
+                .read_db(&DBPacketInfo::new("test_budget_a"), &db_location, &user_key)
+                .unwrap(),
+            SuccessReply(value.clone())
+        );
+        assert_eq!(db_list.sleep_caches(), 1);
+        assert!(!db_list
+            .cache
+            .contains_key(&DBPacketInfo::new("test_budget_b")));
+
+        // clean up unit test files
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn test_writes_during_cache_eviction() {
+        let data_dir = PathBuf::from("./data/test_eviction_writes");
+        let _ = fs::remove_dir_all(&data_dir);
+        fs::create_dir_all(&data_dir).unwrap();
+
+        let admin_key = TEST_SUPER_ADMIN_KEY.to_string();
+
+        let mut db_list = get_db_list_for_testing();
+        db_list.data_dir = data_dir.clone();
+        db_list.wal = WriteAheadLog::new(&data_dir);
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(admin_key.clone());
+
+        // removed from the cache by every call to sleep_caches
+        let mut settings = get_db_test_settings();
+        settings.invalidation_time = Duration::from_millis(1);
+        db_list
+            .create_db("test_eviction_writes", settings, &admin_key)
+            .unwrap();
+        let db_info = DBPacketInfo::new("test_eviction_writes");
+
+        let writers = 4;
+        let writes_per_writer = 100;
+        thread::scope(|scope| {
+            let handles: Vec<_> = (0..writers)
+                .map(|writer| {
+                    let db_list = &db_list;
+                    let db_info = &db_info;
+                    let admin_key = &admin_key;
+                    scope.spawn(move || {
+                        for write in 0..writes_per_writer {
+                            db_list
+                                .write_db(
+                                    db_info,
+                                    &DBLocation::new(&format!("{}_{}", writer, write)),
+                                    &DBData::new("value".to_string()),
+                                    admin_key,
+                                )
+                                .unwrap();
+                        }
+                    })
+                })
+                .collect();
+            while !handles.iter().all(|handle| handle.is_finished()) {
+                db_list.sleep_caches();
+            }
+        });
+
+        // every write was made to the cached db, or saved before the db was removed from the cache
+        // wait past the invalidation time, the last write may have been made just before
+        thread::sleep(Duration::from_millis(10));
+        db_list.sleep_caches();
+        assert!(!db_list.cache.contains_key(&db_info));
+        assert_eq!(
+            db_list.get_db_len(&db_info, &admin_key).unwrap(),
+            SuccessReply((writers * writes_per_writer).to_string())
+        );
+
+        // clean up unit test files
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn test_stream_db_contents() {
+        let db_list = get_db_list_for_testing();
+        let admin_key = TEST_SUPER_ADMIN_KEY.to_string();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(admin_key.clone());
+
+        let db_info = DBPacketInfo::new("test_stream_contents");
+        db_list
+            .create_db("test_stream_contents", get_db_test_settings(), &admin_key)
+            .unwrap();
+
+        // values that are escaped when serialized, and large enough to span several chunks
+        for key in 0..50 {
+            db_list
+                .write_db(
+                    &db_info,
+                    &DBLocation::new(&format!("key \"{}\"", key)),
+                    &DBData::new(format!("{}\n\u{e9}", "v".repeat(key * 10))),
+                    &admin_key,
+                )
+                .unwrap();
+        }
+
+        let chunks: Vec<DBBytes> = db_list
+            .stream_db_contents(&db_info, &admin_key)
+            .unwrap()
+            .iter()
+            .collect();
+        assert!(chunks.len() > 1);
+        let streamed: Vec<u8> = chunks
+            .iter()
+            .flat_map(|chunk| chunk.get_data().to_vec())
+            .collect();
+        let streamed: HashMap<String, String> = serde_json::from_slice(&streamed).unwrap();
+
+        let listed: HashMap<String, String> =
+            match db_list.list_db_contents(&db_info, &admin_key).unwrap() {
+                SuccessReply(data) => serde_json::from_str(&data).unwrap(),
+                SuccessNoData | SuccessData(_) => panic!("contents were not listed"),
+            };
+        assert_eq!(streamed.len(), 50);
+        assert_eq!(streamed, listed);
+
+        assert_eq!(
+            db_list
+                .stream_db_contents(&db_info, &"not_a_user_key".to_string())
+                .unwrap_err(),
+            DBPacketResponseError::InvalidPermissions
+        );
+
+        // an empty db streams an empty object
+        db_list
+            .create_db(
+                "test_stream_contents_empty",
+                get_db_test_settings(),
+                &admin_key,
+            )
+            .unwrap();
+        let streamed: Vec<u8> = db_list
+            .stream_db_contents(&DBPacketInfo::new("test_stream_contents_empty"), &admin_key)
+            .unwrap()
+            .iter()
+            .flat_map(|chunk| chunk.get_data().to_vec())
+            .collect();
+        assert_eq!(streamed, b"{}".to_vec());
+
+        db_list
+            .delete_db("test_stream_contents", &admin_key)
+            .unwrap();
+        db_list
+            .delete_db("test_stream_contents_empty", &admin_key)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_sorted_keys() {
+        let db_list = get_db_list_for_testing();
+        let admin_key = TEST_SUPER_ADMIN_KEY.to_string();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(admin_key.clone());
+        let db_name = "test_sorted_keys";
+        let db_info = DBPacketInfo::new(db_name);
+
+        db_list
+            .create_db(
+                db_name,
+                get_db_test_settings().with_sorted_keys(true),
+                &admin_key,
+            )
+            .unwrap();
+        let mut written: Vec<String> = (0..40).rev().map(|key| format!("key_{:02}", key)).collect();
+        for key in &written {
+            db_list
+                .write_db(
+                    &db_info,
+                    &DBLocation::new(key),
+                    &DBData::new(format!("value of {}", key)),
+                    &admin_key,
+                )
+                .unwrap();
+        }
+        written.sort();
+
+        let assert_sorted = |written: &[String]| {
+            let keys: Vec<String> = match db_list.list_db_keys(&db_info, &admin_key).unwrap() {
+                SuccessReply(data) => serde_json::from_str(&data).unwrap(),
+                SuccessNoData | SuccessData(_) => panic!("keys were not listed"),
+            };
+            assert_eq!(keys, written);
+
+            // the serialized object keeps the order of the keys
+            let listed = match db_list.list_db_contents(&db_info, &admin_key).unwrap() {
+                SuccessReply(data) => data,
+                SuccessNoData | SuccessData(_) => panic!("contents were not listed"),
+            };
+            let positions: Vec<usize> = written
+                .iter()
+                .map(|key| listed.find(&format!("\"{}\"", key)).unwrap())
+                .collect();
+            assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+
+            let prefixed: BTreeMap<String, String> =
+                match db_list.read_prefix(&db_info, "key_1", &admin_key).unwrap() {
+                    SuccessReply(data) => serde_json::from_str(&data).unwrap(),
+                    SuccessNoData | SuccessData(_) => panic!("prefix was not read"),
+                };
+            assert_eq!(
+                prefixed.keys().collect::<Vec<_>>(),
+                written
+                    .iter()
+                    .filter(|key| key.starts_with("key_1"))
+                    .collect::<Vec<_>>()
+            );
+        };
+        assert_sorted(&written);
+
+        // the order is kept when the db is loaded from its file
+        db_list.save_specific_db(&db_info);
+        db_list.cache.clear();
+        assert_sorted(&written);
+
+        // removing keys keeps the rest in order
+        db_list
+            .delete_data(&db_info, &DBLocation::new("key_05"), &admin_key)
+            .unwrap();
+        written.retain(|key| key != "key_05");
+        assert_sorted(&written);
+
+        db_list.delete_db(db_name, &admin_key).unwrap();
+    }
+
+    #[test]
+    fn test_filtered_stream_table() {
+        let db_list = get_db_list_for_testing();
+        let admin_key = TEST_SUPER_ADMIN_KEY.to_string();
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(admin_key.clone());
+
+        for (db_name, sorted) in [
+            ("test_filtered_stream", false),
+            ("test_filtered_stream_sorted", true),
+        ] {
+            let db_info = DBPacketInfo::new(db_name);
+            db_list
+                .create_db(
+                    db_name,
+                    get_db_test_settings().with_sorted_keys(sorted),
+                    &admin_key,
+                )
+                .unwrap();
+            for (key, value) in [
+                ("user:1", "active"),
+                ("user:2", "inactive"),
+                ("user:10", "active"),
+                ("group:1", "active"),
+                ("use", "active"),
+            ] {
+                db_list
+                    .write_db(
+                        &db_info,
+                        &DBLocation::new(key),
+                        &DBData::new(value.to_string()),
+                        &admin_key,
+                    )
+                    .unwrap();
+            }
+
+            let filtered_keys = |key_prefix: &str, value_contains: &str| {
+                let table = db_list
+                    .get_filtered_stream_table(&db_info, key_prefix, value_contains, &admin_key)
+                    .unwrap();
+                assert_eq!(table.is_sorted(), sorted);
+                let mut keys: Vec<String> = table.content.keys().cloned().collect();
+                keys.sort();
+                keys
+            };
+
+            assert_eq!(
+                filtered_keys("user:", ""),
+                vec!["user:1", "user:10", "user:2"]
+            );
+            assert_eq!(filtered_keys("", "inactive"), vec!["user:2"]);
+            assert_eq!(filtered_keys("user:1", "active"), vec!["user:1", "user:10"]);
+            assert_eq!(filtered_keys("", "").len(), 5);
+            assert!(filtered_keys("missing:", "").is_empty());
+
+            assert_eq!(
+                db_list
+                    .get_filtered_stream_table(&db_info, "user:", "", &"not_a_user_key".to_string())
+                    .unwrap_err(),
+                InvalidPermissions
+            );
+
+            db_list.delete_db(db_name, &admin_key).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_locked_db_does_not_block_other_dbs() {
+        let data_dir = PathBuf::from("./data/test_locked_db_contention");
+        let _ = fs::remove_dir_all(&data_dir);
+        fs::create_dir_all(&data_dir).unwrap();
+
+        let admin_key = TEST_SUPER_ADMIN_KEY.to_string();
+
+        let mut db_list = get_db_list_for_testing();
+        db_list.data_dir = data_dir.clone();
+        db_list.wal = WriteAheadLog::new(&data_dir);
+        db_list
+            .super_admin_hash_list
+            .write()
+            .unwrap()
+            .push(admin_key.clone());
+
+        let locked_info = DBPacketInfo::new("test_contention_locked");
+        let free_info = DBPacketInfo::new("test_contention_free");
+        for db_info in [&locked_info, &free_info] {
+            db_list
+                .create_db(db_info.get_db_name(), get_db_test_settings(), &admin_key)
+                .unwrap();
+        }
+
+        let locked_db = db_list.cache.get(&locked_info).unwrap();
+        let db_lock = locked_db.write().unwrap();
+
+        thread::scope(|scope| {
+            // waits on the lock of the locked db
+            let blocked = scope.spawn(|| {
+                db_list.write_db(
+                    &locked_info,
+                    &DBLocation::new("location"),
+                    &DBData::new("blocked".to_string()),
+                    &admin_key,
+                )
+            });
+
+            // requests to other dbs, and creating and deleting dbs, are served while it waits
+            let other_requests = scope.spawn(|| {
+                let location = DBLocation::new("location");
+                db_list
+                    .write_db(
+                        &free_info,
+                        &location,
+                        &DBData::new("free".to_string()),
+                        &admin_key,
+                    )
+                    .unwrap();
+                assert_eq!(
+                    db_list.read_db(&free_info, &location, &admin_key).unwrap(),
+                    SuccessReply("free".to_string())
+                );
+                db_list
+                    .create_db(
+                        "test_contention_created",
+                        get_db_test_settings(),
+                        &admin_key,
+                    )
+                    .unwrap();
+                db_list
+                    .delete_db("test_contention_created", &admin_key)
+                    .unwrap();
+            });
+
+            let start = std::time::Instant::now();
+            while !other_requests.is_finished() {
+                assert!(
+                    start.elapsed() < Duration::from_secs(10),
+                    "requests to other dbs waited on the locked db"
+                );
+                thread::sleep(Duration::from_millis(1));
+            }
+            other_requests.join().unwrap();
+            assert!(!blocked.is_finished());
+
+            drop(db_lock);
+            assert_eq!(blocked.join().unwrap(), Ok(SuccessNoData));
+        });
+
+        assert_eq!(
+            db_list
+                .read_db(&locked_info, &DBLocation::new("location"), &admin_key)
+                .unwrap(),
+            SuccessReply("blocked".to_string())
+        );
+
+        // clean up unit test files
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
 }
@@ -259,4 +259,326 @@ mod tests {
             SuperAdmin
         );
     }
+
+    #[test]
+    fn test_unsaved_changes() {
+        let mut db = DB::default();
+        assert_eq!(db.is_dirty(), false);
+
+        db.update_access_time();
+        assert_eq!(db.is_dirty(), false);
+
+        db.get_content_mut()
+            .insert("key".to_string(), "value".to_string());
+        db.get_settings_mut().add_user("user".to_string());
+        assert_eq!(db.unsaved_changes(), 2);
+        assert_eq!(db.is_dirty(), true);
+
+        db.mark_saved();
+        assert_eq!(db.unsaved_changes(), 0);
+        assert_eq!(db.is_dirty(), false);
+
+        // a change that leaves the content as it was is not counted
+        let removed = db.change_content(|content| {
+            let removed = content.remove("missing");
+            let changed = removed.is_some();
+            (removed, changed)
+        });
+        assert_eq!(removed, None);
+        assert_eq!(db.is_dirty(), false);
+    }
+
+    #[test]
+    fn test_key_acl_permissions() {
+        let admin_key = "test_admin_123".to_string();
+        let user_key = "test_user_123".to_string();
+        let worker_key = "test_worker_123".to_string();
+        let super_admin_list: Vec<String> = vec![];
+        let db = DB::new_from_settings(
+            DBSettings::new(
+                Duration::from_secs(30),
+                (false, false, false),
+                (true, true, true),
+                vec![admin_key.clone()],
+                vec![user_key.clone(), worker_key.clone()],
+            )
+            .with_key_acl(KeyAclRule::new(&worker_key, "jobs/", true, true))
+            .with_key_acl(KeyAclRule::new(&worker_key, "config/", true, false))
+            // admins are not limited by rules
+            .with_key_acl(KeyAclRule::new(&admin_key, "jobs/", false, false)),
+        );
+
+        // the worker is limited to its prefixes, despite being a user with rwx
+        assert!(db.has_key_read_permissions("jobs/1", &worker_key, &super_admin_list));
+        assert!(db.has_key_write_permissions("jobs/1", &worker_key, &super_admin_list));
+        assert!(db.has_key_read_permissions("config/a", &worker_key, &super_admin_list));
+        assert!(!db.has_key_write_permissions("config/a", &worker_key, &super_admin_list));
+        assert!(!db.has_key_read_permissions("secrets", &worker_key, &super_admin_list));
+        assert!(!db.has_key_write_permissions("secrets", &worker_key, &super_admin_list));
+        assert!(!db.has_read_permissions(&worker_key, &super_admin_list));
+        assert!(!db.has_write_permissions(&worker_key, &super_admin_list));
+        assert!(!db.has_list_permissions(&worker_key, &super_admin_list));
+
+        // keys without rules keep their db wide permissions
+        assert!(db.has_key_write_permissions("secrets", &user_key, &super_admin_list));
+        assert!(db.has_read_permissions(&user_key, &super_admin_list));
+        assert!(db.has_key_write_permissions("jobs/1", &admin_key, &super_admin_list));
+        assert!(db.has_write_permissions(&admin_key, &super_admin_list));
+
+        // adding a rule with the same key and prefix replaces it
+        let mut settings = db.get_settings().clone();
+        settings.add_key_acl(KeyAclRule::new(&worker_key, "config/", true, true));
+        assert_eq!(settings.get_key_acls().len(), 3);
+        assert!(settings.remove_key_acl(&worker_key, "config/"));
+        assert!(!settings.remove_key_acl(&worker_key, "config/"));
+        assert_eq!(settings.get_key_acls_for(&worker_key).count(), 1);
+    }
+
+    #[test]
+    fn test_ip_filter() {
+        let ip = |address: &str| address.parse::<std::net::IpAddr>().unwrap();
+        let block = |block: &str| block.parse::<IpNet>().unwrap();
+
+        // an empty filter accepts every address
+        assert!(IpFilter::default().is_allowed(ip("203.0.113.7")));
+
+        let filter = IpFilter::new(
+            vec![block("10.0.0.0/8"), block("fd00::/8")],
+            vec![block("10.0.5.0/24")],
+        );
+        assert!(filter.is_allowed(ip("10.1.2.3")));
+        assert!(filter.is_allowed(ip("fd00::1")));
+        assert!(!filter.is_allowed(ip("203.0.113.7")));
+        // deny takes priority over allow
+        assert!(!filter.is_allowed(ip("10.0.5.9")));
+        // ipv4 mapped ipv6 addresses are checked as ipv4
+        assert!(filter.is_allowed(ip("::ffff:10.1.2.3")));
+        assert!(!filter.is_allowed(ip("::ffff:10.0.5.9")));
+    }
+
+    #[test]
+    fn test_packet_checksum() {
+        use smol_db_common::db_packets::packet_checksum::{
+            add_checksum, deserialize_response, is_incomplete, serialize_response, MAX_PACKET_SIZE,
+        };
+
+        let packet = DBPacket::new_read("db", "location");
+        let ser = packet.serialize_packet().unwrap();
+        assert!(matches!(
+            DBPacket::deserialize_packet(ser.as_bytes()),
+            Ok(DBPacket::Read(..))
+        ));
+
+        // a packet without a checksum is rejected, as soon as it is read
+        let plain = serde_json::to_string(&packet).unwrap();
+        assert_eq!(
+            DBPacket::deserialize_packet(plain.as_bytes()).unwrap_err(),
+            DBPacketResponseError::PacketCorrupted
+        );
+        assert_eq!(
+            is_incomplete(plain.as_bytes()),
+            Err(DBPacketResponseError::PacketCorrupted)
+        );
+
+        // truncated or modified packets are detected instead of failing to deserialize
+        let truncated = &ser.as_bytes()[..ser.len() - 3];
+        assert_eq!(is_incomplete(truncated), Ok(true));
+        assert_eq!(is_incomplete(&ser.as_bytes()[..3]), Ok(true));
+        assert_eq!(
+            DBPacket::deserialize_packet(truncated).unwrap_err(),
+            DBPacketResponseError::PacketCorrupted
+        );
+        let modified = ser.replace("location", "locatiom");
+        assert_eq!(is_incomplete(modified.as_bytes()), Ok(false));
+        assert_eq!(
+            DBPacket::deserialize_packet(modified.as_bytes()).unwrap_err(),
+            DBPacketResponseError::PacketCorrupted
+        );
+        assert_eq!(
+            DBPacket::deserialize_packet(add_checksum("{not a packet").as_bytes()).unwrap_err(),
+            DBPacketResponseError::BadPacket
+        );
+
+        // data read past the length of the packet is not handed to the deserializer
+        let trailing = format!("{ser}{ser}");
+        assert_eq!(
+            is_incomplete(trailing.as_bytes()),
+            Err(DBPacketResponseError::PacketCorrupted)
+        );
+        assert_eq!(
+            DBPacket::deserialize_packet(trailing.as_bytes()).unwrap_err(),
+            DBPacketResponseError::PacketCorrupted
+        );
+
+        // a header giving a length over the limit, or that never ends, is rejected before the packet is read
+        let oversized = format!("crc32:00000000:{} ", MAX_PACKET_SIZE + 1);
+        assert_eq!(
+            is_incomplete(oversized.as_bytes()),
+            Err(DBPacketResponseError::PacketCorrupted)
+        );
+        let endless_header = format!("crc32:{}", "0".repeat(64));
+        assert_eq!(
+            is_incomplete(endless_header.as_bytes()),
+            Err(DBPacketResponseError::PacketCorrupted)
+        );
+        assert_eq!(
+            is_incomplete(b"crc32:zzzzzzzz:5 "),
+            Err(DBPacketResponseError::PacketCorrupted)
+        );
+
+        let response = Ok(SuccessReply("value".to_string()));
+        let ser = serialize_response(&response).unwrap();
+        assert_eq!(deserialize_response(ser.as_bytes()).unwrap(), response);
+        let modified = ser.replace("value", "valve");
+        assert_eq!(
+            deserialize_response(modified.as_bytes()).unwrap(),
+            Err(DBPacketResponseError::PacketCorrupted)
+        );
+    }
+
+    #[test]
+    fn test_stream_item_framing() {
+        use smol_db_common::db_packets::packet_checksum::{is_incomplete, serialize_response};
+
+        // a key or value can hold anything, including what would otherwise look like a response
+        let response_like = serialize_response(&Ok(SuccessNoData)).unwrap();
+        let item = StreamItem::Item("key".to_string(), response_like);
+        let ser = item.serialize_item().unwrap();
+        assert_eq!(StreamItem::deserialize_item(ser.as_bytes()).unwrap(), item);
+
+        let end = StreamItem::StreamEnd.serialize_item().unwrap();
+        assert_eq!(
+            StreamItem::deserialize_item(end.as_bytes()).unwrap(),
+            StreamItem::StreamEnd
+        );
+
+        // an item split across reads is known to be incomplete until the rest of it is read
+        let truncated = &ser.as_bytes()[..ser.len() / 2];
+        assert_eq!(is_incomplete(truncated), Ok(true));
+        assert_eq!(
+            StreamItem::deserialize_item(truncated).unwrap_err(),
+            DBPacketResponseError::PacketCorrupted
+        );
+
+        // a response sent in place of an item is not mistaken for one
+        let error_response = serialize_response(&Err(DBPacketResponseError::BadPacket)).unwrap();
+        assert_eq!(
+            StreamItem::deserialize_item(error_response.as_bytes()).unwrap_err(),
+            DBPacketResponseError::BadPacket
+        );
+    }
+
+    #[test]
+    fn test_db_settings_builder() {
+        let settings = DBSettings::builder()
+            .invalidation(Duration::from_secs(10))
+            .others_can_read(true)
+            .users_can_write(false)
+            .add_admin("admin")
+            .add_user("user")
+            .quota(StorageQuota::new(Some(5), None))
+            .key_acl(KeyAclRule::new("user", "public/", true, true))
+            .build()
+            .unwrap();
+        let mut expected = DBSettings::new(
+            Duration::from_secs(10),
+            (false, false, false),
+            (true, true, true),
+            vec!["admin".to_string()],
+            vec!["user".to_string()],
+        )
+        .with_quota(StorageQuota::new(Some(5), None))
+        .with_key_acl(KeyAclRule::new("user", "public/", true, true));
+        expected.others_permissions = Permissions::none().with(Permission::Read);
+        expected.users_permissions.set(Permission::Write, false);
+        assert_eq!(settings, expected);
+        assert!(DBSettings::default().validate().is_ok());
+
+        assert_eq!(
+            DBSettings::builder()
+                .invalidation(Duration::ZERO)
+                .build()
+                .unwrap_err(),
+            DBSettingsError::ZeroInvalidationTime
+        );
+        assert_eq!(
+            DBSettings::builder()
+                .users_can_list(false)
+                .others_can_list(true)
+                .build()
+                .unwrap_err(),
+            DBSettingsError::OthersExceedUsers
+        );
+        assert_eq!(
+            DBSettings::builder()
+                .add_admin("key")
+                .add_user("key")
+                .build()
+                .unwrap_err(),
+            DBSettingsError::AdminIsUser("key".to_string())
+        );
+        assert_eq!(
+            DBSettings::builder()
+                .add_admin("key")
+                .key_acl(KeyAclRule::new("key", "", true, false))
+                .build()
+                .unwrap_err(),
+            DBSettingsError::KeyAclOnAdmin("key".to_string())
+        );
+    }
+
+    #[test]
+    fn test_granular_permissions() {
+        let user_key = "test_user_123".to_string();
+        let other_key = "".to_string();
+        let super_admin_list: Vec<String> = vec![];
+        let db = DB::new_from_settings(
+            DBSettings::builder()
+                .users_can(Permission::Delete, false)
+                .users_can(Permission::ManageUsers, true)
+                .others_can(Permission::List, true)
+                .add_user(&user_key)
+                .build()
+                .unwrap(),
+        );
+
+        // users can list and write, but not delete
+        assert!(db.has_list_permissions(&user_key, &super_admin_list));
+        assert!(db.has_key_write_permissions("a", &user_key, &super_admin_list));
+        assert!(!db.has_key_delete_permissions("a", &user_key, &super_admin_list));
+        assert!(db.has_permission(Permission::ManageUsers, &user_key, &super_admin_list));
+        assert!(!db.has_permission(Permission::ManageSettings, &user_key, &super_admin_list));
+        assert!(db.has_stream_permissions(&user_key, &super_admin_list));
+
+        // others can only list
+        assert!(db.has_list_permissions(&other_key, &super_admin_list));
+        assert!(!db.has_read_permissions(&other_key, &super_admin_list));
+        assert!(!db.has_stream_permissions(&other_key, &super_admin_list));
+
+        // read write list triples from dbs saved before permissions were split up are migrated
+        let old_settings = r#"{"invalidation_time":{"secs":30,"nanos":0},"can_others_rwx":[true,false,false],"can_users_rwx":[true,true,true],"admins":[],"users":[]}"#;
+        let migrated: DBSettings = serde_json::from_str(old_settings).unwrap();
+        assert_eq!(
+            migrated,
+            DBSettings::new(
+                Duration::from_secs(30),
+                (true, false, false),
+                (true, true, true),
+                vec![],
+                vec![],
+            )
+        );
+        assert!(migrated
+            .get_other_permissions()
+            .contains(Permission::Stream));
+        assert!(!migrated
+            .get_other_permissions()
+            .contains(Permission::Delete));
+        assert!(migrated.get_user_permissions().contains(Permission::Delete));
+
+        // permissions are saved as a list, and read back the same
+        let ser = serde_json::to_string(&migrated).unwrap();
+        assert!(ser.contains(r#""others_permissions":["Read","Stream"]"#));
+        assert_eq!(serde_json::from_str::<DBSettings>(&ser).unwrap(), migrated);
+    }
 }
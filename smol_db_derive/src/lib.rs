@@ -0,0 +1,92 @@
+//! Derive macro for `smol_db_client::table_record::SmolDbTable`, which stores each value of a struct as a key in a database.
+//! Used through the `derive` feature of `smol_db_client`, which re-exports it.
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr};
+
+/// Implements `SmolDbTable` for a struct with named fields, one of which is marked as the key with `#[smol_db(key)]`.
+/// The key field can be any type that implements `ToString`.
+/// The database is named by `#[smol_db(table = "name")]` on the struct, or is the name of the struct if not given.
+#[proc_macro_derive(SmolDbTable, attributes(smol_db))]
+pub fn derive_smol_db_table(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut table_name = LitStr::new(&name.to_string(), name.span());
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("smol_db"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("table") {
+                table_name = meta.value()?.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("expected `table = \"name\"`"))
+            }
+        })?;
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    name,
+                    "SmolDbTable can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                name,
+                "SmolDbTable can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut key_field = None;
+    for field in fields {
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("smol_db"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if !meta.path.is_ident("key") {
+                    return Err(meta.error("expected `key`"));
+                }
+                if key_field.is_some() {
+                    return Err(meta.error("only one field can be the key"));
+                }
+                key_field = field.ident.clone();
+                Ok(())
+            })?;
+        }
+    }
+    let key_field = key_field.ok_or_else(|| {
+        Error::new(
+            Span::call_site(),
+            "SmolDbTable needs a field marked with #[smol_db(key)]",
+        )
+    })?;
+
+    Ok(quote! {
+        impl #impl_generics ::smol_db_client::table_record::SmolDbTable for #name #ty_generics #where_clause {
+            const TABLE_NAME: &'static str = #table_name;
+
+            fn key(&self) -> ::std::string::String {
+                ::std::string::ToString::to_string(&self.#key_field)
+            }
+        }
+    })
+}
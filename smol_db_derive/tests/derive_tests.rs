@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use smol_db_client::prelude::*;
+    use smol_db_client::table_record::SmolDbTable;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq, SmolDbTable)]
+    #[smol_db(table = "test_derive_players")]
+    struct Player {
+        #[smol_db(key)]
+        id: u32,
+        name: String,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq, SmolDbTable)]
+    struct TestDeriveDefaultName {
+        #[smol_db(key)]
+        key: String,
+    }
+
+    #[test]
+    fn test_derive_table_name() {
+        assert_eq!(Player::TABLE_NAME, "test_derive_players");
+        assert_eq!(TestDeriveDefaultName::TABLE_NAME, "TestDeriveDefaultName");
+        assert_eq!(
+            Player {
+                id: 7,
+                name: "bob".to_string()
+            }
+            .key(),
+            "7"
+        );
+    }
+
+    #[test]
+    fn test_derive_save_load_all() {
+        let mut client = SmolDbClient::new("localhost:8222").unwrap();
+        let _ = client.set_access_key("test_key_123".to_string()).unwrap();
+        let _ = client.delete_db(Player::TABLE_NAME);
+        let _ = client
+            .create_db(Player::TABLE_NAME, DBSettings::default())
+            .unwrap();
+
+        let bob = Player {
+            id: 1,
+            name: "bob".to_string(),
+        };
+        let alice = Player {
+            id: 2,
+            name: "alice".to_string(),
+        };
+        assert_eq!(bob.save(&mut client).unwrap(), None);
+        assert_eq!(alice.save(&mut client).unwrap(), None);
+
+        let renamed = Player {
+            id: 1,
+            name: "robert".to_string(),
+        };
+        assert_eq!(renamed.save(&mut client).unwrap(), Some(bob));
+
+        assert_eq!(Player::load(&mut client, "1").unwrap(), Some(renamed));
+        assert_eq!(Player::load(&mut client, "3").unwrap(), None);
+
+        let mut all = Player::all(&mut client).unwrap();
+        all.sort_by_key(|player| player.id);
+        assert_eq!(
+            all.iter()
+                .map(|player| player.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["robert", "alice"]
+        );
+
+        let _ = client.delete_db(Player::TABLE_NAME).unwrap();
+    }
+}
@@ -0,0 +1,462 @@
+//! C compatible library that wraps `SmolDbClient`, so a `smol_db` server can be used from languages other than rust.
+//! The header for these functions is `bindings.h`, generated with cbindgen.
+//! Functions that make a request return `OK_STATE`, `ERROR_STATE`, or `DATA_NOT_FOUND_STATE`,
+//! or an `FFIResult` holding the state and the data responded with, if there was any.
+//! Lists, contents, roles, and settings are returned as json.
+//! When a function fails, the reason can be read with `smol_db_client_last_error`.
+use smol_db_client::client_error::ClientError;
+use smol_db_client::db_settings::DBSettings;
+use smol_db_client::prelude::{DBResponseError, SmolDbClient};
+use smol_db_client::{DBPacketResponseError, DBSuccessResponse};
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::fmt::Display;
+use std::ptr;
+
+/// The request succeeded
+pub const OK_STATE: i32 = 0;
+/// The request failed, or an argument was null or not valid, the reason is given by `smol_db_client_last_error`
+pub const ERROR_STATE: i32 = 1;
+/// The request was made, but there was no data at the given location or db
+pub const DATA_NOT_FOUND_STATE: i32 = 2;
+
+/// A client connected to a server, made with `smol_db_client_new` and freed with `smol_db_client_free`
+pub struct FFISmolDBClient {
+    client: SmolDbClient,
+}
+
+impl FFISmolDBClient {
+    fn new(ip: &str) -> Result<Self, ClientError> {
+        Ok(Self {
+            client: SmolDbClient::new(ip)?,
+        })
+    }
+}
+
+thread_local! {
+    /// The reason the last function that failed on this thread failed
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Sets the error returned by `smol_db_client_last_error` on this thread
+fn set_last_error(error: impl Display) {
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(error.to_string()));
+}
+
+/// Sets the last error to say the arguments were not valid, returning `ERROR_STATE`
+fn invalid_arguments() -> i32 {
+    set_last_error("an argument was null or not valid utf8");
+    ERROR_STATE
+}
+
+/// Parses the settings json, setting the last error if it is not valid
+fn parse_settings(settings_json: &str) -> Option<DBSettings> {
+    serde_json::from_str(settings_json)
+        .map_err(|err| set_last_error(format!("the settings are not valid json: {}", err)))
+        .ok()
+}
+
+/// Returns the str the pointer points to, or none if it is null or not valid utf8
+///
+/// # Safety
+/// The pointer must be null or point to a nul terminated string
+unsafe fn str_from_ptr<'a>(ptr: *const c_char) -> Option<&'a str> {
+    if ptr.is_null() {
+        return None;
+    }
+    CStr::from_ptr(ptr).to_str().ok()
+}
+
+/// Returns the state a result of a request represents, setting the last error if it failed
+fn state_of<T>(result: &Result<T, ClientError>) -> i32 {
+    if let Err(err) = result {
+        set_last_error(err);
+    }
+    match result {
+        Ok(_) => OK_STATE,
+        Err(DBResponseError(
+            DBPacketResponseError::ValueNotFound | DBPacketResponseError::DBNotFound,
+        )) => DATA_NOT_FOUND_STATE,
+        Err(_) => ERROR_STATE,
+    }
+}
+
+/// The result of a request that can respond with data.
+/// `data_ptr` points to the nul terminated response, and `data_len` is its length in bytes without the nul,
+/// it is null when the request failed or there was no data to respond with.
+/// The data is owned by the caller, and is freed with `smol_db_free_string`
+#[repr(C)]
+pub struct FFIResult {
+    /// `OK_STATE`, `ERROR_STATE`, or `DATA_NOT_FOUND_STATE`
+    pub code: i32,
+    pub data_ptr: *mut c_char,
+    pub data_len: usize,
+}
+
+impl FFIResult {
+    /// A result of a request that could not be made, because an argument was null or not valid utf8
+    fn invalid_arguments() -> Self {
+        Self {
+            code: invalid_arguments(),
+            data_ptr: ptr::null_mut(),
+            data_len: 0,
+        }
+    }
+
+    fn from_result(result: Result<String, ClientError>) -> Self {
+        Self::from_optional_result(result.map(Some))
+    }
+
+    fn from_optional_result(result: Result<Option<String>, ClientError>) -> Self {
+        match result {
+            Ok(Some(data)) => {
+                let data_len = data.len();
+                // the response can not hold a nul byte in a c string, so it is reported as an error
+                match CString::new(data) {
+                    Ok(data) => Self {
+                        code: OK_STATE,
+                        data_ptr: data.into_raw(),
+                        data_len,
+                    },
+                    Err(err) => {
+                        set_last_error(err);
+                        Self {
+                            code: ERROR_STATE,
+                            data_ptr: ptr::null_mut(),
+                            data_len: 0,
+                        }
+                    }
+                }
+            }
+            Ok(None) => Self {
+                code: OK_STATE,
+                data_ptr: ptr::null_mut(),
+                data_len: 0,
+            },
+            Err(_) => Self {
+                code: state_of(&result),
+                data_ptr: ptr::null_mut(),
+                data_len: 0,
+            },
+        }
+    }
+}
+
+/// Frees the data of an `FFIResult`, or a string returned by `smol_db_client_last_error`
+///
+/// # Safety
+/// `data_ptr` must be null or a string returned by this library, and not used again after this
+#[no_mangle]
+pub unsafe extern "C" fn smol_db_free_string(data_ptr: *mut c_char) {
+    if !data_ptr.is_null() {
+        drop(CString::from_raw(data_ptr));
+    }
+}
+
+/// Returns the reason the last function that failed on this thread failed, or null if none has.
+/// The string is owned by the caller, and is freed with `smol_db_free_string`
+#[no_mangle]
+pub extern "C" fn smol_db_client_last_error() -> *mut c_char {
+    LAST_ERROR.with(|last_error| {
+        last_error
+            .borrow()
+            .as_ref()
+            .and_then(|error| CString::new(error.as_str()).ok())
+            .map_or(ptr::null_mut(), CString::into_raw)
+    })
+}
+
+/// Connects to the server at the ip, such as `localhost:8222`, returning null if it could not connect
+///
+/// # Safety
+/// `ip` must point to a nul terminated string
+#[no_mangle]
+pub unsafe extern "C" fn smol_db_client_new(ip: *const c_char) -> *mut FFISmolDBClient {
+    let Some(ip) = str_from_ptr(ip) else {
+        let _ = invalid_arguments();
+        return ptr::null_mut();
+    };
+    match FFISmolDBClient::new(ip) {
+        Ok(client) => Box::into_raw(Box::new(client)),
+        Err(err) => {
+            set_last_error(err);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Frees the client, disconnecting it from the server
+///
+/// # Safety
+/// `client_ptr` must be null or made by `smol_db_client_new`, and not used again after this
+#[no_mangle]
+pub unsafe extern "C" fn smol_db_client_free(client_ptr: *mut FFISmolDBClient) {
+    if !client_ptr.is_null() {
+        drop(Box::from_raw(client_ptr));
+    }
+}
+
+/// Sets the access key of the client
+///
+/// # Safety
+/// `client_ptr` must be made by `smol_db_client_new`, and `key_ptr` must point to a nul terminated string
+#[no_mangle]
+pub unsafe extern "C" fn smol_db_client_set_key(
+    client_ptr: *mut FFISmolDBClient,
+    key_ptr: *const c_char,
+) -> i32 {
+    let (Some(client), Some(key)) = (client_ptr.as_mut(), str_from_ptr(key_ptr)) else {
+        return invalid_arguments();
+    };
+    state_of(&client.client.set_access_key(key.to_string()))
+}
+
+/// Switches the client to end to end encryption
+///
+/// # Safety
+/// `client_ptr` must be made by `smol_db_client_new`
+#[no_mangle]
+pub unsafe extern "C" fn smol_db_client_setup_encryption(client_ptr: *mut FFISmolDBClient) -> i32 {
+    let Some(client) = client_ptr.as_mut() else {
+        return invalid_arguments();
+    };
+    state_of(&client.client.setup_encryption())
+}
+
+/// Reconnects the client to the server it was connected to
+///
+/// # Safety
+/// `client_ptr` must be made by `smol_db_client_new`
+#[no_mangle]
+pub unsafe extern "C" fn smol_db_client_reconnect(client_ptr: *mut FFISmolDBClient) -> i32 {
+    let Some(client) = client_ptr.as_mut() else {
+        return invalid_arguments();
+    };
+    state_of(&client.client.reconnect())
+}
+
+/// Disconnects the client from the server, it can be connected again with `smol_db_client_reconnect`
+///
+/// # Safety
+/// `client_ptr` must be made by `smol_db_client_new`
+#[no_mangle]
+pub unsafe extern "C" fn smol_db_client_disconnect(client_ptr: *mut FFISmolDBClient) -> i32 {
+    let Some(client) = client_ptr.as_mut() else {
+        return invalid_arguments();
+    };
+    match client.client.disconnect() {
+        Ok(()) => OK_STATE,
+        Err(err) => {
+            set_last_error(err);
+            ERROR_STATE
+        }
+    }
+}
+
+/// Reads the value at the location in the db
+///
+/// # Safety
+/// `client_ptr` must be made by `smol_db_client_new`, and `name` and `location` must point to nul terminated strings
+#[no_mangle]
+pub unsafe extern "C" fn smol_db_client_read_db(
+    client_ptr: *mut FFISmolDBClient,
+    name: *const c_char,
+    location: *const c_char,
+) -> FFIResult {
+    let (Some(client), Some(name), Some(location)) = (
+        client_ptr.as_mut(),
+        str_from_ptr(name),
+        str_from_ptr(location),
+    ) else {
+        return FFIResult::invalid_arguments();
+    };
+    FFIResult::from_result(
+        client
+            .client
+            .read_db(name, location)
+            .map(|response| response.into_option().unwrap_or_default()),
+    )
+}
+
+/// Writes the data to the location in the db, responding with the value that was there before, which is null if there was none
+///
+/// # Safety
+/// `client_ptr` must be made by `smol_db_client_new`, and `name`, `location` and `data` must point to nul terminated strings
+#[no_mangle]
+pub unsafe extern "C" fn smol_db_client_write_db(
+    client_ptr: *mut FFISmolDBClient,
+    name: *const c_char,
+    location: *const c_char,
+    data: *const c_char,
+) -> FFIResult {
+    let (Some(client), Some(name), Some(location), Some(data)) = (
+        client_ptr.as_mut(),
+        str_from_ptr(name),
+        str_from_ptr(location),
+        str_from_ptr(data),
+    ) else {
+        return FFIResult::invalid_arguments();
+    };
+    FFIResult::from_optional_result(
+        client
+            .client
+            .write_db(name, location, data)
+            .map(DBSuccessResponse::into_option),
+    )
+}
+
+/// Deletes the value at the location in the db
+///
+/// # Safety
+/// `client_ptr` must be made by `smol_db_client_new`, and `name` and `location` must point to nul terminated strings
+#[no_mangle]
+pub unsafe extern "C" fn smol_db_client_delete_data(
+    client_ptr: *mut FFISmolDBClient,
+    name: *const c_char,
+    location: *const c_char,
+) -> i32 {
+    let (Some(client), Some(name), Some(location)) = (
+        client_ptr.as_mut(),
+        str_from_ptr(name),
+        str_from_ptr(location),
+    ) else {
+        return invalid_arguments();
+    };
+    state_of(&client.client.delete_data(name, location))
+}
+
+/// Creates a db with the settings, given as json in the form returned by `smol_db_client_get_db_settings`
+///
+/// # Safety
+/// `client_ptr` must be made by `smol_db_client_new`, and `name` and `settings_json` must point to nul terminated strings
+#[no_mangle]
+pub unsafe extern "C" fn smol_db_client_create_db(
+    client_ptr: *mut FFISmolDBClient,
+    name: *const c_char,
+    settings_json: *const c_char,
+) -> i32 {
+    let (Some(client), Some(name), Some(settings_json)) = (
+        client_ptr.as_mut(),
+        str_from_ptr(name),
+        str_from_ptr(settings_json),
+    ) else {
+        return invalid_arguments();
+    };
+    let Some(settings) = parse_settings(settings_json) else {
+        return ERROR_STATE;
+    };
+    state_of(&client.client.create_db(name, settings))
+}
+
+/// Deletes the db
+///
+/// # Safety
+/// `client_ptr` must be made by `smol_db_client_new`, and `name` must point to a nul terminated string
+#[no_mangle]
+pub unsafe extern "C" fn smol_db_client_delete_db(
+    client_ptr: *mut FFISmolDBClient,
+    name: *const c_char,
+) -> i32 {
+    let (Some(client), Some(name)) = (client_ptr.as_mut(), str_from_ptr(name)) else {
+        return invalid_arguments();
+    };
+    state_of(&client.client.delete_db(name))
+}
+
+/// Lists the names of the dbs on the server, as a json array
+///
+/// # Safety
+/// `client_ptr` must be made by `smol_db_client_new`
+#[no_mangle]
+pub unsafe extern "C" fn smol_db_client_list_db(client_ptr: *mut FFISmolDBClient) -> FFIResult {
+    let Some(client) = client_ptr.as_mut() else {
+        return FFIResult::invalid_arguments();
+    };
+    FFIResult::from_result(client.client.list_db().map(|list| {
+        let names: Vec<&str> = list.iter().map(|db| db.get_db_name()).collect();
+        serde_json::to_string(&names).unwrap_or_default()
+    }))
+}
+
+/// Lists every key and value in the db, as a json object
+///
+/// # Safety
+/// `client_ptr` must be made by `smol_db_client_new`, and `name` must point to a nul terminated string
+#[no_mangle]
+pub unsafe extern "C" fn smol_db_client_list_db_contents(
+    client_ptr: *mut FFISmolDBClient,
+    name: *const c_char,
+) -> FFIResult {
+    let (Some(client), Some(name)) = (client_ptr.as_mut(), str_from_ptr(name)) else {
+        return FFIResult::invalid_arguments();
+    };
+    FFIResult::from_result(
+        client
+            .client
+            .list_db_contents(name)
+            .map(|contents| serde_json::to_string(&contents).unwrap_or_default()),
+    )
+}
+
+/// Gets the role the client has in the db, as a json string such as `"SuperAdmin"`
+///
+/// # Safety
+/// `client_ptr` must be made by `smol_db_client_new`, and `name` must point to a nul terminated string
+#[no_mangle]
+pub unsafe extern "C" fn smol_db_client_get_role(
+    client_ptr: *mut FFISmolDBClient,
+    name: *const c_char,
+) -> FFIResult {
+    let (Some(client), Some(name)) = (client_ptr.as_mut(), str_from_ptr(name)) else {
+        return FFIResult::invalid_arguments();
+    };
+    FFIResult::from_result(
+        client
+            .client
+            .get_role(name)
+            .map(|role| serde_json::to_string(&role).unwrap_or_default()),
+    )
+}
+
+/// Gets the settings of the db, as json
+///
+/// # Safety
+/// `client_ptr` must be made by `smol_db_client_new`, and `name` must point to a nul terminated string
+#[no_mangle]
+pub unsafe extern "C" fn smol_db_client_get_db_settings(
+    client_ptr: *mut FFISmolDBClient,
+    name: *const c_char,
+) -> FFIResult {
+    let (Some(client), Some(name)) = (client_ptr.as_mut(), str_from_ptr(name)) else {
+        return FFIResult::invalid_arguments();
+    };
+    FFIResult::from_result(
+        client
+            .client
+            .get_db_settings(name)
+            .map(|settings| serde_json::to_string(&settings).unwrap_or_default()),
+    )
+}
+
+/// Sets the settings of the db, given as json in the form returned by `smol_db_client_get_db_settings`
+///
+/// # Safety
+/// `client_ptr` must be made by `smol_db_client_new`, and `name` and `settings_json` must point to nul terminated strings
+#[no_mangle]
+pub unsafe extern "C" fn smol_db_client_set_db_settings(
+    client_ptr: *mut FFISmolDBClient,
+    name: *const c_char,
+    settings_json: *const c_char,
+) -> i32 {
+    let (Some(client), Some(name), Some(settings_json)) = (
+        client_ptr.as_mut(),
+        str_from_ptr(name),
+        str_from_ptr(settings_json),
+    ) else {
+        return invalid_arguments();
+    };
+    let Some(settings) = parse_settings(settings_json) else {
+        return ERROR_STATE;
+    };
+    state_of(&client.client.set_db_settings(name, settings))
+}
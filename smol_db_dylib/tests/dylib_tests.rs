@@ -0,0 +1,145 @@
+#[cfg(test)]
+mod tests {
+    use smol_db_client::db_settings::DBSettings;
+    use smol_db_dylib::*;
+    use smol_db_test_harness::spawn_server;
+    use std::ffi::{CStr, CString};
+    use std::ptr;
+
+    /// Copies the data of a successful result into a rust string, freeing the data
+    fn take_string(result: FFIResult) -> String {
+        assert_eq!(result.code, OK_STATE);
+        let data = unsafe { CStr::from_ptr(result.data_ptr) }
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert_eq!(data.len(), result.data_len);
+        unsafe { smol_db_free_string(result.data_ptr) };
+        data
+    }
+
+    #[test]
+    fn test_manage_db() {
+        let server = spawn_server();
+        let address = CString::new(server.address()).unwrap();
+        let key = CString::new("test_key_123").unwrap();
+        let name = CString::new("test_dylib_manage_db").unwrap();
+        let location = CString::new("location").unwrap();
+        let data = CString::new("data").unwrap();
+        let settings =
+            CString::new(serde_json::to_string(&DBSettings::default()).unwrap()).unwrap();
+
+        unsafe {
+            let client = smol_db_client_new(address.as_ptr());
+            assert_eq!(smol_db_client_set_key(client, key.as_ptr()), OK_STATE);
+            assert_eq!(
+                smol_db_client_create_db(client, name.as_ptr(), settings.as_ptr()),
+                OK_STATE
+            );
+            assert_eq!(
+                take_string(smol_db_client_list_db(client)),
+                "[\"test_dylib_manage_db\"]"
+            );
+            assert_eq!(
+                take_string(smol_db_client_get_role(client, name.as_ptr())),
+                "\"SuperAdmin\""
+            );
+
+            // there was no value before the write to respond with
+            let written =
+                smol_db_client_write_db(client, name.as_ptr(), location.as_ptr(), data.as_ptr());
+            assert_eq!(written.code, OK_STATE);
+            assert_eq!(written.data_ptr, ptr::null_mut());
+            assert_eq!(
+                take_string(smol_db_client_write_db(
+                    client,
+                    name.as_ptr(),
+                    location.as_ptr(),
+                    data.as_ptr(),
+                )),
+                "data"
+            );
+            assert_eq!(
+                take_string(smol_db_client_read_db(
+                    client,
+                    name.as_ptr(),
+                    location.as_ptr()
+                )),
+                "data"
+            );
+            assert_eq!(
+                take_string(smol_db_client_list_db_contents(client, name.as_ptr())),
+                "{\"location\":\"data\"}"
+            );
+            assert_eq!(
+                smol_db_client_delete_data(client, name.as_ptr(), location.as_ptr()),
+                OK_STATE
+            );
+            let not_found = smol_db_client_read_db(client, name.as_ptr(), location.as_ptr());
+            assert_eq!(not_found.code, DATA_NOT_FOUND_STATE);
+            assert_eq!(not_found.data_ptr, ptr::null_mut());
+
+            // settings are set from the json returned when getting them
+            let mut db_settings: DBSettings = serde_json::from_str(&take_string(
+                smol_db_client_get_db_settings(client, name.as_ptr()),
+            ))
+            .unwrap();
+            db_settings.users.push("test_dylib_user".to_string());
+            let new_settings = CString::new(serde_json::to_string(&db_settings).unwrap()).unwrap();
+            assert_eq!(
+                smol_db_client_set_db_settings(client, name.as_ptr(), new_settings.as_ptr()),
+                OK_STATE
+            );
+            let read_settings: DBSettings = serde_json::from_str(&take_string(
+                smol_db_client_get_db_settings(client, name.as_ptr()),
+            ))
+            .unwrap();
+            assert_eq!(read_settings.users.len(), 1);
+
+            let invalid = CString::new("{").unwrap();
+            assert_eq!(
+                smol_db_client_set_db_settings(client, name.as_ptr(), invalid.as_ptr()),
+                ERROR_STATE
+            );
+            let last_error = smol_db_client_last_error();
+            assert!(CStr::from_ptr(last_error)
+                .to_str()
+                .unwrap()
+                .starts_with("the settings are not valid json"));
+            smol_db_free_string(last_error);
+
+            assert_eq!(smol_db_client_delete_db(client, name.as_ptr()), OK_STATE);
+            assert_eq!(
+                smol_db_client_delete_db(client, name.as_ptr()),
+                DATA_NOT_FOUND_STATE
+            );
+            assert_eq!(take_string(smol_db_client_list_db(client)), "[]");
+            smol_db_client_free(client);
+        }
+    }
+
+    #[test]
+    fn test_connection_error() {
+        let address = CString::new("not an address").unwrap();
+
+        unsafe {
+            assert_eq!(smol_db_client_new(address.as_ptr()), ptr::null_mut());
+            let last_error = smol_db_client_last_error();
+            assert_ne!(last_error, ptr::null_mut());
+            smol_db_free_string(last_error);
+
+            assert_eq!(smol_db_client_new(ptr::null()), ptr::null_mut());
+            let last_error = smol_db_client_last_error();
+            assert_eq!(
+                CStr::from_ptr(last_error).to_str(),
+                Ok("an argument was null or not valid utf8")
+            );
+            smol_db_free_string(last_error);
+
+            assert_eq!(
+                smol_db_client_set_key(ptr::null_mut(), ptr::null()),
+                ERROR_STATE
+            );
+        }
+    }
+}
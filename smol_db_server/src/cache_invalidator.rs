@@ -1,26 +1,44 @@
-use futures_time::task;
-use futures_time::time::Duration;
 use smol_db_common::prelude::DBList;
-use std::sync::{Arc, RwLock};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::spawn_blocking;
 use tracing::info;
 
 #[tracing::instrument(skip_all)]
-pub(crate) async fn cache_invalidator(db_list: Arc<RwLock<DBList>>) {
+pub(crate) async fn cache_invalidator(db_list: Arc<DBList>, interval: Duration) {
     info!("Cache invalidator spawned");
     loop {
-        let invalidated_caches = db_list.read().unwrap().sleep_caches();
+        // saving, truncating the write-ahead log and purging the trash all block on the disk, so they are done on a blocking thread
+        let db_list_clone = db_list.clone();
+        spawn_blocking(move || invalidate_caches(&db_list_clone))
+            .await
+            .expect("invalidating caches does not panic");
 
-        db_list.read().unwrap().save_all_db();
-        db_list.read().unwrap().save_db_list();
+        tokio::time::sleep(interval).await;
+    }
+}
+
+fn invalidate_caches(db_list: &DBList) {
+    // saving before sleeping caches keeps the write-ahead log from being truncated past changes to a db that is no longer cached
+    db_list.checkpoint_wal();
+
+    let invalidated_caches = db_list.sleep_caches();
 
-        if invalidated_caches > 0 {
-            let number_of_caches_remaining = db_list.read().unwrap().cache.read().unwrap().len();
-            info!(
-                "Slept {} caches, {} caches remain in cache.",
-                invalidated_caches, number_of_caches_remaining
-            );
-        }
+    let expired_sessions = db_list.sessions.remove_expired();
+    if expired_sessions > 0 {
+        info!("Removed {} expired sessions", expired_sessions);
+    }
+
+    let purged_databases = db_list.purge_expired_trash();
+    if purged_databases > 0 {
+        info!("Purged {} databases from the trash", purged_databases);
+    }
 
-        task::sleep(Duration::from_secs(10)).await;
+    if invalidated_caches > 0 {
+        let number_of_caches_remaining = db_list.cache.len();
+        info!(
+            "Slept {} caches, {} caches remain in cache.",
+            invalidated_caches, number_of_caches_remaining
+        );
     }
 }
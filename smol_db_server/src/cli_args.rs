@@ -0,0 +1,284 @@
+//! Contains the command line arguments of the server binary, which are applied on top of the config file
+use clap::Parser;
+use smol_db_common::db_packets::ip_filter::IpNet;
+use smol_db_common::logging::logger::LogLevel;
+use smol_db_server::ServerConfig;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+
+/// The config file that is loaded when no config file is given on the command line, if it exists.
+const DEFAULT_CONFIG_PATH: &str = "./smol_db_server.toml";
+
+#[derive(Parser, Debug)]
+#[command(version, about)]
+/// Command line arguments of the server, any argument given takes priority over the config file.
+pub(crate) struct CliArgs {
+    /// Path to a toml config file, defaults to ./smol_db_server.toml if it exists
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+
+    /// Address to listen for clients on
+    #[arg(short, long)]
+    bind_address: Option<IpAddr>,
+
+    /// Port to listen for clients on
+    #[arg(short, long)]
+    port: Option<u16>,
+
+    /// Address and port to listen for clients on, such as 0.0.0.0:0 to listen on a port picked by the operating system
+    #[arg(long, conflicts_with_all = ["bind_address", "port"])]
+    listen: Option<SocketAddr>,
+
+    /// Directory the db list and every database are saved in
+    #[arg(short, long)]
+    data_dir: Option<PathBuf>,
+
+    /// Seconds between each save of all databases and removal of old caches
+    #[arg(long)]
+    cache_invalidation_interval: Option<u64>,
+
+    /// Approximate bytes the cached databases may take up before the least recently accessed are removed from the cache
+    #[arg(long)]
+    cache_memory_budget: Option<usize>,
+
+    /// Number of threads used to handle clients, defaults to the number of cpus
+    #[arg(long)]
+    thread_pool_size: Option<usize>,
+
+    /// Seconds a client may go without sending a request before it is disconnected
+    #[arg(long)]
+    idle_timeout: Option<u64>,
+
+    /// Seconds a client may take to send the next packet in the middle of a stream before it is disconnected
+    #[arg(long)]
+    read_timeout: Option<u64>,
+
+    /// Seconds a client may take to read an item sent to it in the middle of a stream before it is disconnected
+    #[arg(long)]
+    write_timeout: Option<u64>,
+
+    /// Most clients that may be connected at once
+    #[arg(long)]
+    max_connections: Option<usize>,
+
+    /// Most clients that may be connected at once from a single ip address
+    #[arg(long)]
+    max_connections_per_ip: Option<usize>,
+
+    /// Requests each client may make per second
+    #[arg(long)]
+    requests_per_second: Option<u32>,
+
+    /// Requests a client may make at once after being idle, defaults to the requests per second
+    #[arg(long)]
+    request_burst: Option<u32>,
+
+    /// Unsaved changes a database may have before a write saves it, 1 saves after every write
+    #[arg(long)]
+    save_after_writes: Option<u64>,
+
+    /// Snapshot to restore into the data directory before starting, a file name alone refers to a snapshot in the backups directory
+    #[arg(long)]
+    restore_snapshot: Option<PathBuf>,
+
+    /// Most entries any database may hold
+    #[arg(long)]
+    max_db_entries: Option<usize>,
+
+    /// Most bytes of keys and values any database may hold
+    #[arg(long)]
+    max_db_bytes: Option<usize>,
+
+    /// Records every request that changes a database in audit.log in the data directory
+    #[arg(long)]
+    audit_log: bool,
+
+    /// Bytes the audit log may grow to before it is rotated
+    #[arg(long)]
+    audit_log_max_bytes: Option<u64>,
+
+    /// Rotated audit logs that are kept
+    #[arg(long)]
+    audit_log_rotated_files: Option<usize>,
+
+    /// Least severe level of log entry that is printed and written to the log file: error, warn, info, debug or trace
+    #[arg(long)]
+    log_level: Option<LogLevel>,
+
+    /// Disables writing log entries to log.log in the data directory
+    #[arg(long)]
+    no_log_file: bool,
+
+    /// Bytes the log file may grow to before it is rotated
+    #[arg(long)]
+    log_file_max_bytes: Option<u64>,
+
+    /// Rotated log files that are kept
+    #[arg(long)]
+    log_file_rotated_files: Option<usize>,
+
+    /// Changes kept in memory for replicas to catch up with after reconnecting, 0 disables replicating from this server
+    #[arg(long)]
+    replication_log_size: Option<usize>,
+
+    /// Address of the primary server to replicate from, making this server a read-only replica
+    #[arg(long)]
+    replicate_from: Option<String>,
+
+    /// Key of a super admin on the primary server, required to replicate from it
+    #[arg(long)]
+    replication_key: Option<String>,
+
+    /// Token that claims the first super admin while the server has none, generated and printed on startup when not given
+    #[arg(long, env = "SMOL_DB_BOOTSTRAP_TOKEN", hide_env_values = true)]
+    bootstrap_token: Option<String>,
+
+    /// Seconds a session token is valid for after it is created
+    #[arg(long)]
+    session_ttl: Option<u64>,
+
+    /// Seconds a deleted database is kept in the trash before it is purged
+    #[arg(long)]
+    trash_retention: Option<u64>,
+
+    /// Comma separated CIDR blocks connections are accepted from, any address that is not denied is accepted when not given
+    #[arg(long, value_delimiter = ',')]
+    ip_allow: Option<Vec<IpNet>>,
+
+    /// Comma separated CIDR blocks connections are always rejected from
+    #[arg(long, value_delimiter = ',')]
+    ip_deny: Option<Vec<IpNet>>,
+
+    /// Disables saving databases to the data directory, keeping them only in memory
+    #[arg(long)]
+    no_saving: bool,
+}
+
+impl CliArgs {
+    /// Loads the config file if there is one, then applies the command line arguments on top of it.
+    /// Exits the program with a message if the arguments or config file are invalid.
+    pub(crate) fn load_config() -> ServerConfig {
+        let args = Self::parse();
+
+        let mut config = match &args.config {
+            Some(path) => ServerConfig::from_file(path),
+            None if Path::new(DEFAULT_CONFIG_PATH).exists() => {
+                ServerConfig::from_file(Path::new(DEFAULT_CONFIG_PATH))
+            }
+            None => Ok(ServerConfig::default()),
+        }
+        .unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        });
+
+        args.apply_to(&mut config);
+        config
+    }
+
+    fn apply_to(self, config: &mut ServerConfig) {
+        if let Some(bind_address) = self.bind_address {
+            config.bind_address = bind_address;
+        }
+        if let Some(port) = self.port {
+            config.port = port;
+        }
+        if let Some(listen) = self.listen {
+            config.bind_address = listen.ip();
+            config.port = listen.port();
+        }
+        if let Some(data_dir) = self.data_dir {
+            config.data_dir = data_dir;
+        }
+        if let Some(interval) = self.cache_invalidation_interval {
+            config.cache_invalidation_interval = interval;
+        }
+        if self.cache_memory_budget.is_some() {
+            config.cache_memory_budget = self.cache_memory_budget;
+        }
+        if self.thread_pool_size.is_some() {
+            config.thread_pool_size = self.thread_pool_size;
+        }
+        if self.idle_timeout.is_some() {
+            config.idle_timeout = self.idle_timeout;
+        }
+        if self.read_timeout.is_some() {
+            config.read_timeout = self.read_timeout;
+        }
+        if self.write_timeout.is_some() {
+            config.write_timeout = self.write_timeout;
+        }
+        if self.max_connections.is_some() {
+            config.max_connections = self.max_connections;
+        }
+        if self.max_connections_per_ip.is_some() {
+            config.max_connections_per_ip = self.max_connections_per_ip;
+        }
+        if self.requests_per_second.is_some() {
+            config.requests_per_second = self.requests_per_second;
+        }
+        if self.request_burst.is_some() {
+            config.request_burst = self.request_burst;
+        }
+        if let Some(save_after_writes) = self.save_after_writes {
+            config.save_after_writes = save_after_writes;
+        }
+        if self.restore_snapshot.is_some() {
+            config.restore_snapshot = self.restore_snapshot;
+        }
+        if self.max_db_entries.is_some() {
+            config.max_db_entries = self.max_db_entries;
+        }
+        if self.max_db_bytes.is_some() {
+            config.max_db_bytes = self.max_db_bytes;
+        }
+        if self.audit_log {
+            config.audit_log = true;
+        }
+        if let Some(max_bytes) = self.audit_log_max_bytes {
+            config.audit_log_max_bytes = max_bytes;
+        }
+        if let Some(rotated_files) = self.audit_log_rotated_files {
+            config.audit_log_rotated_files = rotated_files;
+        }
+        if let Some(log_level) = self.log_level {
+            config.log_level = log_level;
+        }
+        if self.no_log_file {
+            config.log_file = false;
+        }
+        if let Some(max_bytes) = self.log_file_max_bytes {
+            config.log_file_max_bytes = max_bytes;
+        }
+        if let Some(rotated_files) = self.log_file_rotated_files {
+            config.log_file_rotated_files = rotated_files;
+        }
+        if let Some(replication_log_size) = self.replication_log_size {
+            config.replication_log_size = replication_log_size;
+        }
+        if self.replicate_from.is_some() {
+            config.replicate_from = self.replicate_from;
+        }
+        if self.replication_key.is_some() {
+            config.replication_key = self.replication_key;
+        }
+        if self.bootstrap_token.is_some() {
+            config.bootstrap_token = self.bootstrap_token;
+        }
+        if let Some(session_ttl) = self.session_ttl {
+            config.session_ttl = session_ttl;
+        }
+        if let Some(trash_retention) = self.trash_retention {
+            config.trash_retention = trash_retention;
+        }
+        if let Some(ip_allow) = self.ip_allow {
+            config.ip_allow = ip_allow;
+        }
+        if let Some(ip_deny) = self.ip_deny {
+            config.ip_deny = ip_deny;
+        }
+        if self.no_saving {
+            config.saving = false;
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug)]
+/// Counts the clients connected to the server, so connections past the configured limits can be rejected.
+pub(crate) struct ConnectionLimiter {
+    /// Most clients that may be connected at once, `None` allows any number
+    max_connections: Option<usize>,
+    /// Most clients that may be connected at once from a single ip address, `None` allows any number
+    max_connections_per_ip: Option<usize>,
+    /// Number of clients connected from each ip address, addresses with no clients are removed
+    connections: Mutex<HashMap<IpAddr, usize>>,
+}
+
+#[derive(Debug)]
+/// A slot for a connected client, which is given back to the limiter when dropped.
+pub(crate) struct ConnectionPermit {
+    limiter: Arc<ConnectionLimiter>,
+    ip_address: IpAddr,
+}
+
+impl ConnectionLimiter {
+    pub(crate) fn new(
+        max_connections: Option<usize>,
+        max_connections_per_ip: Option<usize>,
+    ) -> Self {
+        Self {
+            max_connections,
+            max_connections_per_ip,
+            connections: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes a slot for a client connecting from the given ip address.
+    /// Returns None if the server or the ip address already has as many clients as it is allowed.
+    pub(crate) fn try_acquire(self: &Arc<Self>, ip_address: IpAddr) -> Option<ConnectionPermit> {
+        let mut connections = self.connections.lock().unwrap();
+
+        let total: usize = connections.values().sum();
+        if self.max_connections.is_some_and(|max| total >= max) {
+            return None;
+        }
+
+        let from_ip = connections.entry(ip_address).or_insert(0);
+        if self
+            .max_connections_per_ip
+            .is_some_and(|max| *from_ip >= max)
+        {
+            // an address with no clients is never left in the map
+            if *from_ip == 0 {
+                connections.remove(&ip_address);
+            }
+            return None;
+        }
+        *from_ip += 1;
+
+        Some(ConnectionPermit {
+            limiter: self.clone(),
+            ip_address,
+        })
+    }
+
+    /// The number of clients currently connected
+    pub(crate) fn connection_count(&self) -> usize {
+        self.connections.lock().unwrap().values().sum()
+    }
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        let mut connections = self.limiter.connections.lock().unwrap();
+        if let Some(from_ip) = connections.get_mut(&self.ip_address) {
+            *from_ip -= 1;
+            if *from_ip == 0 {
+                connections.remove(&self.ip_address);
+            }
+        }
+    }
+}
@@ -1,56 +1,147 @@
-use crate::DBListThreadSafe;
-use smol_db_common::prelude::DBPacketResponseError::BadPacket;
-use smol_db_common::prelude::{DBPacket, RsaPublicKey, SuccessNoData, SuccessReply};
-use std::io::{Read, Write};
-use std::net::TcpStream;
+use crate::open_streams::OpenStreams;
+use crate::queue_handler::blocking_queue_pop;
+use crate::rate_limiter::RateLimiter;
+use crate::session_key_hasher::SessionKeyHasher;
+use crate::stream_read_handler::stream_table;
+use crate::stream_write_handler::receive_stream_write;
+use crate::timeouts::{read_packet_with_timeout, ConnectionTimeouts};
+use crate::watch_handler::{handle_watch, write_response};
+use crate::{DBListThreadSafe, Server};
+use smol_db_common::db_packets::ip_filter::IpFilter;
+use smol_db_common::db_packets::packet_checksum::serialize_response;
+use smol_db_common::encryption::server_encrypt::ServerKey;
+use smol_db_common::encryption::session_cipher::SessionCipher;
+use smol_db_common::logging::audit::{AuditLog, AuditTarget};
+use smol_db_common::prelude::DBPacketResponseError::{
+    BadPacket, InvalidPermissions, PacketCorrupted, RateLimited, ReadOnlyReplica,
+    StreamClosedUnexpectedly,
+};
+use smol_db_common::prelude::{
+    DBBytes, DBPacket, DBPacketResponseError, DBSuccessResponse, SuccessNoData, SuccessReply, PONG,
+};
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::sync::Semaphore;
+use tokio::task::{block_in_place, spawn_blocking};
 use tracing::{debug, error, info, warn};
 
-#[allow(clippy::let_and_return)]
-#[tracing::instrument(skip(db_list))]
-pub(crate) async fn handle_client(mut stream: TcpStream, db_list: DBListThreadSafe) {
+#[allow(clippy::let_and_return, clippy::too_many_arguments)]
+#[tracing::instrument(skip(db_list, rate_limiter, key_hash_permits, ip_filter, audit_log))]
+pub(crate) async fn handle_client(
+    mut stream: TcpStream,
+    db_list: DBListThreadSafe,
+    timeouts: ConnectionTimeouts,
+    rate_limiter: Arc<RateLimiter>,
+    key_hash_permits: Arc<Semaphore>,
+    ip_filter: Arc<RwLock<IpFilter>>,
+    save_after_writes: u64,
+    read_only: bool,
+    audit_log: Option<Arc<AuditLog>>,
+    started: Instant,
+) {
     info!("New client connected");
     let ip_address = stream.peer_addr().unwrap();
-    let mut buf: [u8; 1024] = [0; 1024];
     let mut client_key = String::new();
 
     let mut client_name = format!("Client [{}] [{}]:", ip_address, client_key);
 
-    let mut client_pub_key_opt: Option<RsaPublicKey> = None;
+    let mut session_cipher_opt: Option<SessionCipher> = None;
+
+    let mut session_key_hasher = SessionKeyHasher::new();
+
+    // streams read by id, in between the other requests of the client
+    let mut open_streams = OpenStreams::default();
 
     loop {
         // client loop
 
         info!("Awaiting packet information from: {}", client_name);
-        let read_result = stream.read(&mut buf);
+        let read_result = read_packet_with_timeout(&mut stream, timeouts.idle).await;
 
-        if let Ok(read) = read_result {
+        if let Ok(packet_data) = read_result {
+            let read = packet_data.len();
             if read != 0 {
                 debug!("Read size: {}", read);
-                let response = match DBPacket::deserialize_packet(&buf[0..read]) {
+                // named once the packet is read, as handling it consumes the packet
+                #[cfg(feature = "statistics")]
+                let mut packet_name = "Unknown";
+                let response = match DBPacket::deserialize_packet(&packet_data) {
                     Ok(mut pack) => {
                         debug!("Packet data: {:?}", pack);
 
                         // overwrite the packet with the unencrypted version if it is encrypted
+                        // a packet that can not be decrypted is left encrypted, and is responded to as a bad packet
                         if let DBPacket::Encrypted(data) = &pack {
                             debug!("Received encrypted data: {:?}", data);
-                            let unencrypted_data = db_list
-                                .read()
-                                .unwrap()
-                                .server_key
-                                .decrypt_client_packet(data)
-                                .unwrap();
-                            pack = unencrypted_data;
-
-                            debug!("Unencrypted data: {:?}", pack);
+                            match session_cipher_opt
+                                .as_ref()
+                                .map(|cipher| cipher.decrypt_client_packet(data))
+                            {
+                                Some(Ok(unencrypted_data)) => {
+                                    pack = unencrypted_data;
+                                    debug!("Unencrypted data: {:?}", pack);
+                                }
+                                Some(Err(err)) => {
+                                    warn!(
+                                        "{} sent a packet that could not be decrypted: {:?}",
+                                        client_name, err
+                                    );
+                                }
+                                None => {
+                                    warn!(
+                                        "{} sent an encrypted packet without setting up encryption",
+                                        client_name
+                                    );
+                                }
+                            }
+                        }
+
+                        #[cfg(feature = "statistics")]
+                        {
+                            packet_name = pack.name();
                         }
 
-                        match pack {
+                        // described before the packet is handled, as handling it consumes the packet
+                        let audit_target = audit_log
+                            .as_ref()
+                            .filter(|_| pack.is_mutation())
+                            .map(|_| AuditTarget::new(&pack));
+
+                        // changes are appended to the write-ahead log and saved as they are handled, which blocks on the disk,
+                        // so they are handled with block_in_place, letting the runtime move the other clients off this worker meanwhile
+                        let response = match pack {
                             DBPacket::EndStreamRead => {
                                 warn!("Client requested to end stream when no stream was active: {}, {:?}", client_name, pack);
                                 // its possible we receive this packet after a stream is read all the way to its end,
                                 // meaning the user didn't know the stream ended, this is perfectly ok, we just don't respond.
                                 continue;
                             }
+                            _ if !rate_limiter.try_request(&client_key, ip_address.ip()) => {
+                                warn!("{} was rate limited, {:?}", client_name, pack);
+                                Err(RateLimited)
+                            }
+                            _ if !db_list.is_active_key(&client_key) => {
+                                warn!(
+                                    "{} is logged in to a disabled account, logging out, {:?}",
+                                    client_name, pack
+                                );
+                                client_key.clear();
+                                client_name = format!("Client [{}] [{}]:", ip_address, client_key);
+                                Err(InvalidPermissions)
+                            }
+                            _ if read_only && pack.is_mutation() => {
+                                warn!("{} sent a change to a replica, {:?}", client_name, pack);
+                                Err(ReadOnlyReplica)
+                            }
+                            DBPacket::StreamChunk(_)
+                            | DBPacket::EndStreamWrite
+                            | DBPacket::AbortStreamWrite => {
+                                warn!("Client sent stream write packet when no stream write was active: {}, {:?}", client_name, pack);
+                                Err(BadPacket)
+                            }
                             DBPacket::ReadyForNextItem => {
                                 warn!("Client requested stream item when no stream was active: {}, {:?}", client_name, pack);
                                 // user requested next item when there was no item left in stream, this is ok it seems ?
@@ -58,21 +149,109 @@ pub(crate) async fn handle_client(mut stream: TcpStream, db_list: DBListThreadSa
                                 Err(BadPacket)
                             }
                             DBPacket::StreamReadDb(packet) => {
-                                let lock = db_list.read().unwrap();
                                 info!("Client beginning stream");
-                                let resp = lock.stream_table(&packet, &client_key, &mut stream);
+                                // the table is copied, so the db lock is not held while streaming
+                                let db_table = db_list.get_stream_table(&packet, &client_key);
+                                let resp = match db_table {
+                                    Ok(db_table) => {
+                                        stream_table(&mut stream, &db_table, timeouts).await
+                                    }
+                                    Err(err) => Err(err),
+                                };
                                 info!(
                                     "{} streamed \"{}\", response: {:?}",
                                     client_name, packet, resp
                                 );
 
+                                match resp {
+                                    // the stream already sent its last message
+                                    Ok(()) => continue,
+                                    Err(StreamClosedUnexpectedly) => {
+                                        // the copied table is dropped along with the connection
+                                        info!(
+                                            "{} dropped. Stopped following the stream. {:?}",
+                                            client_name, stream
+                                        );
+                                        break;
+                                    }
+                                    Err(err) => Err(err),
+                                }
+                            }
+                            DBPacket::StreamReadDbFiltered(packet, key_prefix, value_contains) => {
+                                info!("Client beginning filtered stream");
+                                // only the matching pairs are copied, so the db lock is not held while streaming
+                                let db_table = db_list.get_filtered_stream_table(
+                                    &packet,
+                                    &key_prefix,
+                                    &value_contains,
+                                    &client_key,
+                                );
+                                let resp = match db_table {
+                                    Ok(db_table) => {
+                                        stream_table(&mut stream, &db_table, timeouts).await
+                                    }
+                                    Err(err) => Err(err),
+                                };
+                                info!(
+                                    "{} streamed \"{}\" with keys starting with \"{}\" and values containing \"{}\", response: {:?}",
+                                    client_name, packet, key_prefix, value_contains, resp
+                                );
+
+                                match resp {
+                                    // the stream already sent its last message
+                                    Ok(()) => continue,
+                                    Err(StreamClosedUnexpectedly) => {
+                                        // the copied table is dropped along with the connection
+                                        info!(
+                                            "{} dropped. Stopped following the stream. {:?}",
+                                            client_name, stream
+                                        );
+                                        break;
+                                    }
+                                    Err(err) => Err(err),
+                                }
+                            }
+                            DBPacket::OpenStream(packet) => {
+                                // the table is copied, so the db lock is not held while the stream is open
+                                let db_table = db_list.get_stream_table(&packet, &client_key);
+                                let resp = db_table
+                                    .and_then(|db_table| open_streams.open(&db_table))
+                                    .map(|stream_id| SuccessReply(stream_id.to_string()));
+
+                                info!(
+                                    "{} opened a stream of \"{}\", response: {:?}",
+                                    client_name, packet, resp
+                                );
+
+                                resp
+                            }
+                            DBPacket::ReadStreamItem(stream_id) => {
+                                let resp = open_streams.next_item(stream_id).and_then(|item| {
+                                    serde_json::to_string(&item)
+                                        .map(SuccessReply)
+                                        .map_err(|_| DBPacketResponseError::SerializationError)
+                                });
+
+                                debug!(
+                                    "{} read an item of stream {}, response: {:?}",
+                                    client_name, stream_id, resp
+                                );
+
+                                resp
+                            }
+                            DBPacket::CloseStream(stream_id) => {
+                                let resp = open_streams.close(stream_id).map(|_| SuccessNoData);
+
+                                info!(
+                                    "{} closed stream {}, response: {:?}",
+                                    client_name, stream_id, resp
+                                );
+
                                 resp
                             }
-                            // TODO: handle a "open a stream" packet here, where we enter a special loop for this case specifically
-                            //  The end of the stream should return a special packet denoting that the stream has ended for its data sending
                             DBPacket::SetupEncryption => {
                                 // non standard conforming implementation of sending a response back, the client is expected to understand this given they requested to establish encryption
-                                let key = db_list.read().unwrap().server_key.get_pub_key().clone();
+                                let key = db_list.server_key.read().unwrap().get_pub_key().clone();
                                 let ser = serde_json::to_string(&key).unwrap();
                                 let resp = Ok(SuccessReply(ser));
                                 info!(
@@ -81,22 +260,32 @@ pub(crate) async fn handle_client(mut stream: TcpStream, db_list: DBListThreadSa
                                 );
                                 resp
                             }
-                            DBPacket::PubKey(key) => {
-                                let resp = Ok(SuccessNoData);
-                                info!(
-                                    "{} sent pub-key {:?} response: {:?}",
-                                    client_name, key, resp
-                                );
-                                client_pub_key_opt = Some(key);
+                            DBPacket::SessionKey(key) => {
+                                // the response is encrypted with the new session key, as the client starts using it once it sends the key
+                                let cipher =
+                                    db_list.server_key.read().unwrap().decrypt_session_key(&key);
+                                let resp = match cipher {
+                                    Ok(cipher) => {
+                                        session_cipher_opt = Some(cipher);
+                                        Ok(SuccessNoData)
+                                    }
+                                    Err(err) => {
+                                        warn!("{} sent a session key that could not be decrypted: {:?}", client_name, err);
+                                        Err(BadPacket)
+                                    }
+                                };
+                                info!("{} sent session key, response: {:?}", client_name, resp);
                                 resp
                             }
                             DBPacket::Encrypted(_) => {
-                                warn!("{} sent encrypted packet that was not handled properly, report this on github in the issues section of smol_db",client_name);
+                                warn!(
+                                    "{} sent encrypted packet that could not be decrypted",
+                                    client_name
+                                );
                                 Err(BadPacket)
                             }
                             DBPacket::Read(db_name, db_location) => {
-                                let lock = db_list.read().unwrap();
-                                let resp = lock.read_db(&db_name, &db_location, &client_key);
+                                let resp = db_list.read_db(&db_name, &db_location, &client_key);
                                 info!(
                                     "{} read \"{}\" in \"{}\", response: {:?}",
                                     client_name, db_location, db_name, resp
@@ -104,42 +293,42 @@ pub(crate) async fn handle_client(mut stream: TcpStream, db_list: DBListThreadSa
                                 resp
                             }
                             DBPacket::Write(db_name, db_location, db_write_value) => {
-                                let lock = db_list.read().unwrap();
-                                let resp = lock.write_db(
-                                    &db_name,
-                                    &db_location,
-                                    &db_write_value.clone(),
-                                    &client_key,
-                                );
+                                block_in_place(|| {
+                                    let resp = db_list.write_db(
+                                        &db_name,
+                                        &db_location,
+                                        &db_write_value.clone(),
+                                        &client_key,
+                                    );
 
-                                info!(
-                                    "{} wrote \"{}\" to \"{}\" in \"{}\", response: {:?}",
-                                    client_name, db_write_value, db_location, db_name, resp
-                                );
+                                    info!(
+                                        "{} wrote \"{}\" to \"{}\" in \"{}\", response: {:?}",
+                                        client_name, db_write_value, db_location, db_name, resp
+                                    );
 
-                                #[cfg(not(feature = "no-saving"))]
-                                db_list.read().unwrap().save_specific_db(&db_name);
-                                resp
+                                    #[cfg(not(feature = "no-saving"))]
+                                    db_list.save_db_if_due(&db_name, save_after_writes);
+                                    resp
+                                })
                             }
-                            DBPacket::CreateDB(db_name, db_settings) => {
-                                let lock = db_list.read().unwrap();
-                                let resp = lock.create_db(
+                            DBPacket::CreateDB(db_name, mut db_settings) => block_in_place(|| {
+                                db_list.hash_settings_keys(&db_name, &mut db_settings);
+                                let resp = db_list.create_db(
                                     db_name.get_db_name(),
                                     db_settings.clone(),
                                     &client_key,
                                 );
                                 #[cfg(not(feature = "no-saving"))]
-                                lock.save_db_list();
+                                db_list.save_db_list();
 
                                 info!("{} created database \"{}\" with settings \"{:?}\", response: {:?}",client_name,db_name,db_settings, resp);
 
                                 #[cfg(not(feature = "no-saving"))]
-                                lock.save_all_db();
+                                db_list.save_all_db();
                                 resp
-                            }
-                            DBPacket::DeleteDB(db_name) => {
-                                let lock = db_list.read().unwrap();
-                                let resp = lock.delete_db(db_name.get_db_name(), &client_key);
+                            }),
+                            DBPacket::DeleteDB(db_name) => block_in_place(|| {
+                                let resp = db_list.delete_db(db_name.get_db_name(), &client_key);
 
                                 info!(
                                     "{} deleted database \"{}\", response: {:?}",
@@ -147,20 +336,100 @@ pub(crate) async fn handle_client(mut stream: TcpStream, db_list: DBListThreadSa
                                 );
 
                                 #[cfg(not(feature = "no-saving"))]
-                                db_list.read().unwrap().save_db_list();
+                                db_list.save_db_list();
+                                resp
+                            }),
+                            DBPacket::RestoreDB(db_name) => block_in_place(|| {
+                                let resp = db_list.restore_db(db_name.get_db_name(), &client_key);
+
+                                info!(
+                                    "{} restored database \"{}\", response: {:?}",
+                                    client_name, db_name, resp
+                                );
+
+                                #[cfg(not(feature = "no-saving"))]
+                                db_list.save_db_list();
+                                resp
+                            }),
+                            DBPacket::PurgeTrash => block_in_place(|| {
+                                let resp = db_list.purge_trash(&client_key);
+
+                                info!("{} purged the trash, response: {:?}", client_name, resp);
+
+                                resp
+                            }),
+                            DBPacket::RenameDB { from, to } => block_in_place(|| {
+                                let resp = db_list.rename_db(&from, &to, &client_key);
+
+                                info!(
+                                    "{} renamed database \"{}\" to \"{}\", response: {:?}",
+                                    client_name, from, to, resp
+                                );
+
+                                #[cfg(not(feature = "no-saving"))]
+                                db_list.save_db_list();
+                                resp
+                            }),
+                            DBPacket::CopyDB {
+                                source,
+                                dest,
+                                include_settings,
+                            } => block_in_place(|| {
+                                let resp =
+                                    db_list.copy_db(&source, &dest, include_settings, &client_key);
+
+                                info!(
+                                        "{} copied database \"{}\" to \"{}\" (include settings: {}), response: {:?}",
+                                        client_name, source, dest, include_settings, resp
+                                    );
+
+                                #[cfg(not(feature = "no-saving"))]
+                                db_list.save_db_list();
+                                resp
+                            }),
+                            DBPacket::DBExists(db_name) => {
+                                let resp = db_list.db_exists(&db_name);
+                                info!(
+                                    "{} checked if database \"{}\" exists, response: {:?}",
+                                    client_name, db_name, resp
+                                );
+                                resp
+                            }
+                            DBPacket::KeyExists(db_name, db_location) => {
+                                let resp = db_list.key_exists(&db_name, &db_location, &client_key);
+                                info!(
+                                    "{} checked if \"{}\" exists in \"{}\", response: {:?}",
+                                    client_name, db_location, db_name, resp
+                                );
+                                resp
+                            }
+                            DBPacket::GetDBLength(db_name) => {
+                                let resp = db_list.get_db_len(&db_name, &client_key);
+                                info!(
+                                    "{} requested length of database \"{}\", response: {:?}",
+                                    client_name, db_name, resp
+                                );
                                 resp
                             }
                             DBPacket::ListDB => {
-                                let lock = db_list.read().unwrap();
-                                let resp = lock.list_db();
+                                let resp = db_list.list_db();
 
                                 info!("{} listed databases, response: {:?}", client_name, resp);
 
                                 resp
                             }
+                            DBPacket::ListAccessibleDB => {
+                                let resp = db_list.list_accessible_db(&client_key);
+
+                                info!(
+                                    "{} listed accessible databases, response: {:?}",
+                                    client_name, resp
+                                );
+
+                                resp
+                            }
                             DBPacket::ListDBContents(db_name) => {
-                                let lock = db_list.read().unwrap();
-                                let resp = lock.list_db_contents(&db_name, &client_key);
+                                let resp = db_list.list_db_contents(&db_name, &client_key);
 
                                 info!(
                                     "{} listed database contents of \"{}\", response: {:?}",
@@ -169,10 +438,684 @@ pub(crate) async fn handle_client(mut stream: TcpStream, db_list: DBListThreadSa
 
                                 resp
                             }
-                            DBPacket::AddAdmin(db_name, admin_hash) => {
-                                let lock = db_list.read().unwrap();
+                            DBPacket::ListDBKeys(db_name) => {
+                                let resp = db_list.list_db_keys(&db_name, &client_key);
+
+                                info!(
+                                    "{} listed database keys of \"{}\", response: {:?}",
+                                    client_name, db_name, resp
+                                );
+
+                                resp
+                            }
+                            DBPacket::ListDBLists(db_name) => {
+                                let resp = db_list.list_db_lists(&db_name, &client_key);
+
+                                info!(
+                                    "{} listed the lists of database \"{}\", response: {:?}",
+                                    client_name, db_name, resp
+                                );
+
+                                resp
+                            }
+                            DBPacket::ReadPrefix(db_name, prefix) => {
+                                let resp = db_list.read_prefix(&db_name, &prefix, &client_key);
+
+                                info!(
+                                    "{} read prefix \"{}\" in \"{}\", response: {:?}",
+                                    client_name, prefix, db_name, resp
+                                );
+
+                                resp
+                            }
+                            DBPacket::FindKeys(db_name, pattern) => {
+                                let resp = db_list.find_keys(&db_name, &pattern, &client_key);
+
+                                info!(
+                                    "{} searched keys of \"{}\" with pattern {:?}, response: {:?}",
+                                    client_name, db_name, pattern, resp
+                                );
+
+                                resp
+                            }
+                            DBPacket::CompareAndSwap {
+                                db: db_name,
+                                location: db_location,
+                                expected,
+                                new: db_write_value,
+                            } => block_in_place(|| {
+                                let resp = db_list.compare_and_swap(
+                                    &db_name,
+                                    &db_location,
+                                    expected.as_ref(),
+                                    &db_write_value,
+                                    &client_key,
+                                );
+
+                                info!(
+                                        "{} compare and swapped \"{:?}\" with \"{}\" at \"{}\" in \"{}\", response: {:?}",
+                                        client_name, expected, db_write_value, db_location, db_name, resp
+                                    );
+
+                                #[cfg(not(feature = "no-saving"))]
+                                db_list.save_db_if_due(&db_name, save_after_writes);
+                                resp
+                            }),
+                            DBPacket::Increment {
+                                db: db_name,
+                                location: db_location,
+                                delta,
+                            } => block_in_place(|| {
+                                let resp =
+                                    db_list.increment(&db_name, &db_location, delta, &client_key);
+
+                                info!(
+                                    "{} incremented \"{}\" in \"{}\" by {}, response: {:?}",
+                                    client_name, db_location, db_name, delta, resp
+                                );
+
+                                #[cfg(not(feature = "no-saving"))]
+                                db_list.save_db_if_due(&db_name, save_after_writes);
+                                resp
+                            }),
+                            DBPacket::Transaction(db_name, ops) => block_in_place(|| {
+                                let resp = db_list.transaction(&db_name, &ops, &client_key);
+
+                                info!(
+                                        "{} applied a transaction of {} operations to \"{}\", response: {:?}",
+                                        client_name,
+                                        ops.len(),
+                                        db_name,
+                                        resp
+                                    );
+
+                                #[cfg(not(feature = "no-saving"))]
+                                db_list.save_db_if_due(&db_name, save_after_writes);
+                                resp
+                            }),
+                            DBPacket::CreateSnapshot => {
+                                let resp = db_list.create_snapshot(&client_key);
+
+                                info!("{} created a snapshot, response: {:?}", client_name, resp);
+
+                                resp
+                            }
+                            DBPacket::ServerInfo => {
+                                let resp = db_list.server_info(
+                                    &client_key,
+                                    env!("CARGO_PKG_VERSION"),
+                                    started.elapsed(),
+                                    &Server::enabled_features(),
+                                );
+
+                                info!(
+                                    "{} requested server info, response: {:?}",
+                                    client_name, resp
+                                );
+
+                                resp
+                            }
+                            DBPacket::Ping => {
+                                debug!("{} pinged the server", client_name);
+                                Ok(SuccessReply(PONG.to_string()))
+                            }
+                            DBPacket::WriteIfAbsent(db_name, db_location, db_data) => {
+                                block_in_place(|| {
+                                    let resp = db_list.write_db_if_absent(
+                                        &db_name,
+                                        &db_location,
+                                        &db_data,
+                                        &client_key,
+                                    );
+
+                                    info!(
+                                        "{} wrote \"{}\" to \"{}\" in \"{}\" if absent, response: {:?}",
+                                        client_name, db_data, db_location, db_name, resp
+                                    );
+
+                                    #[cfg(not(feature = "no-saving"))]
+                                    db_list.save_db_if_due(&db_name, save_after_writes);
+                                    resp
+                                })
+                            }
+                            DBPacket::Subscribe(db_name, pattern) => {
+                                // the db lock must not be held while watching, so it is only held while subscribing
+                                let resp =
+                                    db_list.subscribe(&db_name, pattern.clone(), &client_key);
+                                info!(
+                                    "{} subscribed to {:?} in \"{}\", response: {:?}",
+                                    client_name, pattern, db_name, resp
+                                );
+
+                                match resp {
+                                    Ok(receiver) => {
+                                        handle_watch(&mut stream, receiver, timeouts.read).await;
+                                        info!("{} stopped watching \"{}\"", client_name, db_name);
+                                        continue;
+                                    }
+                                    Err(err) => Err(err),
+                                }
+                            }
+                            DBPacket::AddToList(db_name, list_name, db_data, index) => {
+                                block_in_place(|| {
+                                    let resp = db_list.add_to_list(
+                                        &db_name,
+                                        &list_name,
+                                        &db_data,
+                                        index,
+                                        &client_key,
+                                    );
+                                    info!(
+                                        "{} added \"{}\" to list \"{}\" at {:?} in \"{}\", response: {:?}",
+                                        client_name, db_data, list_name, index, db_name, resp
+                                    );
+
+                                    #[cfg(not(feature = "no-saving"))]
+                                    db_list.save_db_if_due(&db_name, save_after_writes);
+                                    resp
+                                })
+                            }
+                            DBPacket::ReadFromList(db_name, list_name, index) => {
+                                let resp = db_list.read_from_list(
+                                    &db_name,
+                                    &list_name,
+                                    index,
+                                    &client_key,
+                                );
+                                info!(
+                                    "{} read index {} of list \"{}\" in \"{}\", response: {:?}",
+                                    client_name, index, list_name, db_name, resp
+                                );
+                                resp
+                            }
+                            DBPacket::RemoveFromList(db_name, list_name, index) => {
+                                block_in_place(|| {
+                                    let resp = db_list.remove_from_list(
+                                        &db_name,
+                                        &list_name,
+                                        index,
+                                        &client_key,
+                                    );
+                                    info!(
+                                        "{} removed index {} of list \"{}\" in \"{}\", response: {:?}",
+                                        client_name, index, list_name, db_name, resp
+                                    );
+
+                                    #[cfg(not(feature = "no-saving"))]
+                                    db_list.save_db_if_due(&db_name, save_after_writes);
+                                    resp
+                                })
+                            }
+                            DBPacket::ClearList(db_name, list_name) => block_in_place(|| {
+                                let resp = db_list.clear_list(&db_name, &list_name, &client_key);
+                                info!(
+                                    "{} cleared list \"{}\" in \"{}\", response: {:?}",
+                                    client_name, list_name, db_name, resp
+                                );
+
+                                #[cfg(not(feature = "no-saving"))]
+                                db_list.save_db_if_due(&db_name, save_after_writes);
+                                resp
+                            }),
+                            DBPacket::GetListLength(db_name, list_name) => {
+                                let resp =
+                                    db_list.get_list_length(&db_name, &list_name, &client_key);
+                                info!(
+                                    "{} requested length of list \"{}\" in \"{}\", response: {:?}",
+                                    client_name, list_name, db_name, resp
+                                );
+                                resp
+                            }
+                            DBPacket::StreamDBContents(db_name) => {
+                                let resp = db_list.stream_db_contents(&db_name, &client_key);
+                                info!(
+                                    "{} began streaming the contents of \"{}\", response: {:?}",
+                                    client_name,
+                                    db_name,
+                                    resp.as_ref().map(|_| ())
+                                );
+
+                                match resp {
+                                    Ok(receiver) => {
+                                        handle_watch(&mut stream, receiver, timeouts.read).await;
+                                        info!(
+                                            "{} stopped streaming the contents of \"{}\"",
+                                            client_name, db_name
+                                        );
+                                        continue;
+                                    }
+                                    Err(err) => Err(err),
+                                }
+                            }
+                            DBPacket::StreamList(db_name, list_name) => {
+                                let resp = db_list.stream_list(&db_name, &list_name, &client_key);
+                                info!(
+                                    "{} began streaming list \"{}\" in \"{}\", response: {:?}",
+                                    client_name, list_name, db_name, resp
+                                );
+
+                                match resp {
+                                    Ok(receiver) => {
+                                        handle_watch(&mut stream, receiver, timeouts.read).await;
+                                        info!(
+                                            "{} stopped streaming list \"{}\"",
+                                            client_name, list_name
+                                        );
+                                        continue;
+                                    }
+                                    Err(err) => Err(err),
+                                }
+                            }
+                            DBPacket::SetAdd(db_name, set_name, member) => block_in_place(|| {
+                                let resp =
+                                    db_list.set_add(&db_name, &set_name, &member, &client_key);
+                                info!(
+                                        "{} added member \"{}\" in set \"{}\" in \"{}\", response: {:?}",
+                                        client_name, member, set_name, db_name, resp
+                                    );
+
+                                #[cfg(not(feature = "no-saving"))]
+                                db_list.save_db_if_due(&db_name, save_after_writes);
+                                resp
+                            }),
+                            DBPacket::SetRemove(db_name, set_name, member) => {
+                                block_in_place(|| {
+                                    let resp = db_list.set_remove(
+                                        &db_name,
+                                        &set_name,
+                                        &member,
+                                        &client_key,
+                                    );
+                                    info!(
+                                        "{} removed member \"{}\" in set \"{}\" in \"{}\", response: {:?}",
+                                        client_name, member, set_name, db_name, resp
+                                    );
+
+                                    #[cfg(not(feature = "no-saving"))]
+                                    db_list.save_db_if_due(&db_name, save_after_writes);
+                                    resp
+                                })
+                            }
+                            DBPacket::SetContains(db_name, set_name, member) => {
+                                let resp =
+                                    db_list.set_contains(&db_name, &set_name, &member, &client_key);
+                                info!(
+                                    "{} checked for member \"{}\" in set \"{}\" in \"{}\", response: {:?}",
+                                    client_name, member, set_name, db_name, resp
+                                );
+                                resp
+                            }
+                            DBPacket::SetMembers(db_name, set_name) => {
+                                let resp = db_list.set_members(&db_name, &set_name, &client_key);
+                                info!(
+                                    "{} read members of set \"{}\" in \"{}\", response: {:?}",
+                                    client_name, set_name, db_name, resp
+                                );
+                                resp
+                            }
+                            DBPacket::SortedAdd(db_name, list_name, member, score) => {
+                                block_in_place(|| {
+                                    let resp = db_list.sorted_add(
+                                        &db_name,
+                                        &list_name,
+                                        &member,
+                                        score,
+                                        &client_key,
+                                    );
+                                    info!(
+                                        "{} added \"{}\" with score {} to sorted list \"{}\" in \"{}\", response: {:?}",
+                                        client_name, member, score, list_name, db_name, resp
+                                    );
+
+                                    #[cfg(not(feature = "no-saving"))]
+                                    db_list.save_db_if_due(&db_name, save_after_writes);
+                                    resp
+                                })
+                            }
+                            DBPacket::SortedRemove(db_name, list_name, member) => {
+                                block_in_place(|| {
+                                    let resp = db_list.sorted_remove(
+                                        &db_name,
+                                        &list_name,
+                                        &member,
+                                        &client_key,
+                                    );
+                                    info!(
+                                        "{} removed \"{}\" from sorted list \"{}\" in \"{}\", response: {:?}",
+                                        client_name, member, list_name, db_name, resp
+                                    );
+
+                                    #[cfg(not(feature = "no-saving"))]
+                                    db_list.save_db_if_due(&db_name, save_after_writes);
+                                    resp
+                                })
+                            }
+                            DBPacket::SortedRank(db_name, list_name, member) => {
+                                let resp =
+                                    db_list.sorted_rank(&db_name, &list_name, &member, &client_key);
+                                info!(
+                                    "{} read rank of \"{}\" in sorted list \"{}\" in \"{}\", response: {:?}",
+                                    client_name, member, list_name, db_name, resp
+                                );
+                                resp
+                            }
+                            DBPacket::SortedRangeByScore(db_name, list_name, min, max) => {
+                                let resp = db_list.sorted_range_by_score(
+                                    &db_name,
+                                    &list_name,
+                                    min,
+                                    max,
+                                    &client_key,
+                                );
+                                info!(
+                                    "{} read scores {} to {} of sorted list \"{}\" in \"{}\", response: {:?}",
+                                    client_name, min, max, list_name, db_name, resp
+                                );
+                                resp
+                            }
+                            DBPacket::SortedTop(db_name, list_name, count) => {
+                                let resp =
+                                    db_list.sorted_top(&db_name, &list_name, count, &client_key);
+                                info!(
+                                    "{} read top {} of sorted list \"{}\" in \"{}\", response: {:?}",
+                                    client_name, count, list_name, db_name, resp
+                                );
+                                resp
+                            }
+                            DBPacket::QueuePush(db_name, queue_name, db_data) => {
+                                block_in_place(|| {
+                                    let resp = db_list.queue_push(
+                                        &db_name,
+                                        &queue_name,
+                                        &db_data,
+                                        &client_key,
+                                    );
+                                    info!(
+                                        "{} pushed \"{}\" to queue \"{}\" in \"{}\", response: {:?}",
+                                        client_name, db_data, queue_name, db_name, resp
+                                    );
+
+                                    #[cfg(not(feature = "no-saving"))]
+                                    db_list.save_db_if_due(&db_name, save_after_writes);
+                                    resp
+                                })
+                            }
+                            DBPacket::QueuePop(db_name, queue_name, timeout) => {
+                                let resp = match timeout {
+                                    Some(timeout) => {
+                                        blocking_queue_pop(
+                                            &db_list,
+                                            &db_name,
+                                            &queue_name,
+                                            timeout,
+                                            &client_key,
+                                        )
+                                        .await
+                                    }
+                                    None => block_in_place(|| {
+                                        db_list.queue_pop(&db_name, &queue_name, &client_key)
+                                    }),
+                                };
+                                info!(
+                                    "{} popped from queue \"{}\" in \"{}\", response: {:?}",
+                                    client_name, queue_name, db_name, resp
+                                );
+
+                                #[cfg(not(feature = "no-saving"))]
+                                if matches!(resp, Ok(SuccessReply(_))) {
+                                    block_in_place(|| {
+                                        db_list.save_db_if_due(&db_name, save_after_writes)
+                                    });
+                                }
+                                resp
+                            }
+                            DBPacket::WriteBytes(db_name, db_location, db_bytes) => {
+                                block_in_place(|| {
+                                    let resp = db_list.write_db_bytes(
+                                        &db_name,
+                                        &db_location,
+                                        &db_bytes,
+                                        &client_key,
+                                    );
+                                    info!(
+                                        "{} wrote {} to \"{}\" in \"{}\", response: {:?}",
+                                        client_name, db_bytes, db_location, db_name, resp
+                                    );
+
+                                    #[cfg(not(feature = "no-saving"))]
+                                    db_list.save_db_if_due(&db_name, save_after_writes);
+                                    resp
+                                })
+                            }
+                            DBPacket::ReadBytes(db_name, db_location) => {
+                                let resp =
+                                    db_list.read_db_bytes(&db_name, &db_location, &client_key);
+                                info!(
+                                    "{} read bytes \"{}\" in \"{}\", response: {:?}",
+                                    client_name, db_location, db_name, resp
+                                );
+                                resp
+                            }
+                            DBPacket::BeginStreamWrite(db_name, db_location) => {
+                                let resp = db_list.check_write_permissions(&db_name, &client_key);
+                                info!(
+                                    "{} began stream write to \"{}\" in \"{}\", response: {:?}",
+                                    client_name, db_location, db_name, resp
+                                );
+
+                                if resp.is_ok() {
+                                    // the stream is read without encryption, the same as every other stream
+                                    if write_response(&mut stream, resp).await.is_err() {
+                                        break;
+                                    }
+
+                                    let Some(value) =
+                                        receive_stream_write(&mut stream, timeouts.read).await
+                                    else {
+                                        info!("{} stopped stream write", client_name);
+                                        continue;
+                                    };
+
+                                    let resp = block_in_place(|| {
+                                        // the previous value could be too large to send back, so it is not replied with
+                                        let resp = value.and_then(|value| {
+                                            db_list
+                                                .write_db_bytes(
+                                                    &db_name,
+                                                    &db_location,
+                                                    &DBBytes::new(value),
+                                                    &client_key,
+                                                )
+                                                .map(|_| SuccessNoData)
+                                        });
+
+                                        #[cfg(not(feature = "no-saving"))]
+                                        db_list.save_db_if_due(&db_name, save_after_writes);
+                                        resp
+                                    });
+                                    info!(
+                                        "{} finished stream write to \"{}\" in \"{}\", response: {:?}",
+                                        client_name, db_location, db_name, resp
+                                    );
+                                    record_audit(
+                                        audit_log.as_deref(),
+                                        audit_target.as_ref(),
+                                        ip_address,
+                                        &client_key,
+                                        &resp,
+                                    );
+
+                                    if write_response(&mut stream, resp).await.is_err() {
+                                        break;
+                                    }
+                                    continue;
+                                }
+                                resp
+                            }
+                            DBPacket::BeginStreamRead(db_name, db_location) => {
                                 let resp =
-                                    lock.add_admin(&db_name, admin_hash.clone(), &client_key);
+                                    db_list.stream_db_bytes(&db_name, &db_location, &client_key);
+                                info!(
+                                    "{} began stream read of \"{}\" in \"{}\", response: {:?}",
+                                    client_name,
+                                    db_location,
+                                    db_name,
+                                    resp.as_ref().map(|_| ())
+                                );
+
+                                match resp {
+                                    Ok(receiver) => {
+                                        handle_watch(&mut stream, receiver, timeouts.read).await;
+                                        info!(
+                                            "{} stopped stream read of \"{}\"",
+                                            client_name, db_location
+                                        );
+                                        continue;
+                                    }
+                                    Err(err) => Err(err),
+                                }
+                            }
+                            DBPacket::ExportDB(db_name) => {
+                                let resp = db_list.export_db(&db_name, &client_key);
+                                info!(
+                                    "{} began export of \"{}\", response: {:?}",
+                                    client_name,
+                                    db_name,
+                                    resp.as_ref().map(|_| ())
+                                );
+
+                                match resp {
+                                    Ok(receiver) => {
+                                        handle_watch(&mut stream, receiver, timeouts.read).await;
+                                        info!("{} stopped export of \"{}\"", client_name, db_name);
+                                        continue;
+                                    }
+                                    Err(err) => Err(err),
+                                }
+                            }
+                            DBPacket::ReplicaHandshake(position) => {
+                                let resp = db_list.replicate(position, &client_key);
+                                info!(
+                                    "{} began replicating from {:?}, response: {:?}",
+                                    client_name,
+                                    position,
+                                    resp.as_ref().map(|_| ())
+                                );
+
+                                match resp {
+                                    Ok(receiver) => {
+                                        handle_watch(&mut stream, receiver, timeouts.read).await;
+                                        info!("{} stopped replicating", client_name);
+                                        continue;
+                                    }
+                                    Err(err) => Err(err),
+                                }
+                            }
+                            DBPacket::ImportDB => {
+                                let resp = if db_list.is_super_admin(&client_key) {
+                                    Ok(SuccessNoData)
+                                } else {
+                                    Err(InvalidPermissions)
+                                };
+                                info!("{} began import, response: {:?}", client_name, resp);
+
+                                if resp.is_ok() {
+                                    if write_response(&mut stream, resp).await.is_err() {
+                                        break;
+                                    }
+
+                                    let Some(dump) =
+                                        receive_stream_write(&mut stream, timeouts.read).await
+                                    else {
+                                        info!("{} stopped import", client_name);
+                                        continue;
+                                    };
+
+                                    let resp = block_in_place(|| {
+                                        let resp = dump
+                                            .and_then(|dump| db_list.import_db(&dump, &client_key));
+
+                                        #[cfg(not(feature = "no-saving"))]
+                                        db_list.save_db_list();
+                                        resp
+                                    });
+                                    info!("{} finished import, response: {:?}", client_name, resp);
+                                    record_audit(
+                                        audit_log.as_deref(),
+                                        audit_target.as_ref(),
+                                        ip_address,
+                                        &client_key,
+                                        &resp,
+                                    );
+
+                                    if write_response(&mut stream, resp).await.is_err() {
+                                        break;
+                                    }
+                                    continue;
+                                }
+                                resp
+                            }
+                            DBPacket::Publish(channel_name, message) => {
+                                let resp = db_list.publish(&channel_name, &message);
+                                info!(
+                                    "{} published \"{}\" to channel \"{}\", response: {:?}",
+                                    client_name, message, channel_name, resp
+                                );
+                                resp
+                            }
+                            DBPacket::SubscribeChannel(channel_name) => {
+                                let receiver = db_list.subscribe_channel(&channel_name);
+                                info!("{} subscribed to channel \"{}\"", client_name, channel_name);
+
+                                handle_watch(&mut stream, receiver, timeouts.read).await;
+                                info!(
+                                    "{} unsubscribed from channel \"{}\"",
+                                    client_name, channel_name
+                                );
+                                continue;
+                            }
+                            DBPacket::ReadMany(db_name, db_locations) => {
+                                let resp = db_list.read_many(&db_name, &db_locations, &client_key);
+                                info!(
+                                    "{} read {} locations in \"{}\", response: {:?}",
+                                    client_name,
+                                    db_locations.len(),
+                                    db_name,
+                                    resp
+                                );
+                                resp
+                            }
+                            DBPacket::WriteMany(db_name, writes) => block_in_place(|| {
+                                let resp = db_list.write_many(&db_name, &writes, &client_key);
+
+                                info!(
+                                    "{} wrote {} locations in \"{}\", response: {:?}",
+                                    client_name,
+                                    writes.len(),
+                                    db_name,
+                                    resp
+                                );
+
+                                #[cfg(not(feature = "no-saving"))]
+                                db_list.save_db_if_due(&db_name, save_after_writes);
+                                resp
+                            }),
+                            DBPacket::ClearDB(db_name) => block_in_place(|| {
+                                let resp = db_list.clear_db(&db_name, &client_key);
+
+                                info!(
+                                    "{} cleared \"{}\", response: {:?}",
+                                    client_name, db_name, resp
+                                );
+
+                                #[cfg(not(feature = "no-saving"))]
+                                db_list.save_db_if_due(&db_name, save_after_writes);
+                                resp
+                            }),
+                            DBPacket::AddAdmin(db_name, admin_key) => block_in_place(|| {
+                                let admin_hash = db_list.key_hasher().hash_role_key(&admin_key);
+                                let resp =
+                                    db_list.add_admin(&db_name, admin_hash.clone(), &client_key);
 
                                 info!(
                                     "{} added an admin \"{}\" to \"{}\", response: {:?}",
@@ -180,12 +1123,13 @@ pub(crate) async fn handle_client(mut stream: TcpStream, db_list: DBListThreadSa
                                 );
 
                                 #[cfg(not(feature = "no-saving"))]
-                                lock.save_specific_db(&db_name);
+                                db_list.save_db_if_due(&db_name, save_after_writes);
                                 resp
-                            }
-                            DBPacket::AddUser(db_name, user_hash) => {
-                                let lock = db_list.read().unwrap();
-                                let resp = lock.add_user(&db_name, user_hash.clone(), &client_key);
+                            }),
+                            DBPacket::AddUser(db_name, user_key) => block_in_place(|| {
+                                let user_hash = db_list.key_hasher().hash_role_key(&user_key);
+                                let resp =
+                                    db_list.add_user(&db_name, user_hash.clone(), &client_key);
 
                                 info!(
                                     "{} added an admin \"{}\" to \"{}\" response: {:?}",
@@ -193,55 +1137,364 @@ pub(crate) async fn handle_client(mut stream: TcpStream, db_list: DBListThreadSa
                                 );
 
                                 #[cfg(not(feature = "no-saving"))]
-                                lock.save_specific_db(&db_name);
+                                db_list.save_db_if_due(&db_name, save_after_writes);
+                                resp
+                            }),
+                            DBPacket::RemoveAdmin(db_name, admin_key) => block_in_place(|| {
+                                let admin_hash = db_list.removed_role_key(&db_name, &admin_key);
+                                let resp = db_list.remove_admin(&db_name, &admin_hash, &client_key);
+
+                                info!(
+                                    "{} removed an admin \"{}\" from \"{}\", response: {:?}",
+                                    client_name, admin_hash, db_name, resp
+                                );
+
+                                #[cfg(not(feature = "no-saving"))]
+                                db_list.save_db_if_due(&db_name, save_after_writes);
+                                resp
+                            }),
+                            DBPacket::RemoveUser(db_name, user_key) => block_in_place(|| {
+                                let user_hash = db_list.removed_role_key(&db_name, &user_key);
+                                let resp = db_list.remove_user(&db_name, &user_hash, &client_key);
+
+                                info!(
+                                    "{} removed a user \"{}\" from \"{}\", response: {:?}",
+                                    client_name, user_hash, db_name, resp
+                                );
+
+                                #[cfg(not(feature = "no-saving"))]
+                                db_list.save_db_if_due(&db_name, save_after_writes);
+                                resp
+                            }),
+                            DBPacket::AddKeyAcl(db_name, mut rule) => block_in_place(|| {
+                                rule.key = db_list.key_hasher().hash_role_key(&rule.key);
+                                let prefix = rule.prefix.clone();
+                                let resp = db_list.add_key_acl(&db_name, rule, &client_key);
+
+                                info!(
+                                    "{} added a key acl rule for \"{}\" to \"{}\", response: {:?}",
+                                    client_name, prefix, db_name, resp
+                                );
+
+                                #[cfg(not(feature = "no-saving"))]
+                                db_list.save_db_if_due(&db_name, save_after_writes);
+                                resp
+                            }),
+                            DBPacket::RemoveKeyAcl(db_name, key, prefix) => block_in_place(|| {
+                                let key_hash = db_list.key_hasher().hash_role_key(&key);
+                                let resp = db_list.remove_key_acl(
+                                    &db_name,
+                                    &key_hash,
+                                    &prefix,
+                                    &client_key,
+                                );
+
+                                info!(
+                                        "{} removed the key acl rule for \"{}\" from \"{}\", response: {:?}",
+                                        client_name, prefix, db_name, resp
+                                    );
+
+                                #[cfg(not(feature = "no-saving"))]
+                                db_list.save_db_if_due(&db_name, save_after_writes);
+                                resp
+                            }),
+                            DBPacket::GetKeyAcls(db_name) => {
+                                let resp = db_list.get_key_acls(&db_name, &client_key);
+
+                                info!(
+                                    "{} listed the key acl rules of \"{}\", response: {:?}",
+                                    client_name, db_name, resp
+                                );
+
                                 resp
                             }
-                            DBPacket::SetKey(key) => {
-                                let lock = db_list.read().unwrap();
-                                if lock.super_admin_hash_list.read().unwrap().is_empty() {
-                                    // if there are no super admins, the first person to log in is the super admin.
-                                    let mut super_admin_list_lock =
-                                        lock.super_admin_hash_list.write().unwrap();
-                                    super_admin_list_lock.push(key.clone());
-                                }
+                            DBPacket::SetDBSchema(db_name, schema) => block_in_place(|| {
+                                let resp = db_list.set_db_schema(&db_name, schema, &client_key);
 
-                                info!("{} set key to \"{}\"", client_name, key);
+                                info!(
+                                    "{} set the schema of \"{}\", response: {:?}",
+                                    client_name, db_name, resp
+                                );
 
-                                client_key = key;
-                                client_name = format!("Client [{}] [{}]:", ip_address, client_key);
-                                Ok(SuccessNoData)
+                                #[cfg(not(feature = "no-saving"))]
+                                db_list.save_db_if_due(&db_name, save_after_writes);
+                                resp
+                            }),
+                            DBPacket::GetDBSchema(db_name) => {
+                                let resp = db_list.get_db_schema(&db_name, &client_key);
+
+                                info!(
+                                    "{} got the schema of \"{}\", response: {:?}",
+                                    client_name, db_name, resp
+                                );
+
+                                resp
                             }
-                            DBPacket::GetDBSettings(db_name) => {
-                                let lock = db_list.read().unwrap();
-                                let resp = lock.get_db_settings(&db_name, &client_key);
+                            DBPacket::SetDBMetadata(db_name, description, tags) => {
+                                block_in_place(|| {
+                                    let resp = db_list.set_db_metadata(
+                                        &db_name,
+                                        description,
+                                        tags,
+                                        &client_key,
+                                    );
+
+                                    info!(
+                                        "{} set the metadata of \"{}\", response: {:?}",
+                                        client_name, db_name, resp
+                                    );
+
+                                    #[cfg(not(feature = "no-saving"))]
+                                    db_list.save_db_if_due(&db_name, save_after_writes);
+                                    resp
+                                })
+                            }
+                            DBPacket::GetDBMetadata(db_name) => {
+                                let resp = db_list.get_db_metadata(&db_name, &client_key);
 
                                 info!(
-                                    "{} got db settings from \"{}\", response: {:?}",
+                                    "{} got the metadata of \"{}\", response: {:?}",
                                     client_name, db_name, resp
                                 );
 
                                 resp
                             }
-                            DBPacket::ChangeDBSettings(db_name, db_settings) => {
-                                let lock = db_list.read().unwrap();
-                                let resp = lock.change_db_settings(
+                            DBPacket::ListRevisions(db_name, db_location) => {
+                                let resp =
+                                    db_list.list_revisions(&db_name, &db_location, &client_key);
+
+                                info!(
+                                    "{} listed the revisions of \"{}\" in \"{}\", response: {:?}",
+                                    client_name, db_location, db_name, resp
+                                );
+
+                                resp
+                            }
+                            DBPacket::ReadRevision(db_name, db_location, revision) => {
+                                let resp = db_list.read_revision(
                                     &db_name,
-                                    db_settings.clone(),
+                                    &db_location,
+                                    revision,
                                     &client_key,
                                 );
 
                                 info!(
-                                    "{} changed db settings of \"{}\" to \"{:?}\", response: {:?}",
-                                    client_name, db_name, db_settings, resp
+                                    "{} read revision {} of \"{}\" in \"{}\", response: {:?}",
+                                    client_name, revision, db_location, db_name, resp
+                                );
+
+                                resp
+                            }
+                            DBPacket::SetKey(key) => {
+                                // the key is hashed once, and only its hash is kept and compared for the rest of the session
+                                match session_key_hasher
+                                    .hash_key(&db_list, &key_hash_permits, key)
+                                    .await
+                                {
+                                    Ok(key_hash) => {
+                                        info!("{} set key to \"{}\"", client_name, key_hash);
+
+                                        client_key = key_hash;
+                                        client_name =
+                                            format!("Client [{}] [{}]:", ip_address, client_key);
+                                        Ok(SuccessNoData)
+                                    }
+                                    Err(err) => {
+                                        warn!("{} set keys too quickly", client_name);
+                                        Err(err)
+                                    }
+                                }
+                            }
+                            DBPacket::ClaimSuperAdmin(token) => {
+                                // the token is hashed to be compared, which is done off the async workers like the hashing of keys
+                                let resp = {
+                                    let _permit = key_hash_permits
+                                        .acquire()
+                                        .await
+                                        .expect("key hash permits are never closed");
+                                    let db_list = db_list.clone();
+                                    let client_key = client_key.clone();
+                                    spawn_blocking(move || {
+                                        let resp = db_list.claim_super_admin(&token, &client_key);
+                                        #[cfg(not(feature = "no-saving"))]
+                                        db_list.save_db_list();
+                                        resp
+                                    })
+                                    .await
+                                    .expect("claiming super admin does not panic")
+                                };
+
+                                info!("{} claimed super admin, response: {:?}", client_name, resp);
+                                resp
+                            }
+                            DBPacket::Login(username, password) => {
+                                let resp = db_list.login(&username, &password);
+
+                                info!(
+                                    "{} logged in as \"{}\", response: {:?}",
+                                    client_name,
+                                    username,
+                                    resp.as_ref().map(|_| ())
+                                );
+
+                                resp.map(|account_key| {
+                                    client_key = account_key;
+                                    client_name =
+                                        format!("Client [{}] [{}]:", ip_address, client_key);
+                                    SuccessNoData
+                                })
+                            }
+                            DBPacket::CreateUser(username, password) => block_in_place(|| {
+                                let resp = db_list.create_user(&username, &password, &client_key);
+
+                                info!(
+                                    "{} created user \"{}\", response: {:?}",
+                                    client_name, username, resp
+                                );
+
+                                #[cfg(not(feature = "no-saving"))]
+                                db_list.save_db_list();
+                                resp
+                            }),
+                            DBPacket::SetUserDisabled(username, disabled) => block_in_place(|| {
+                                let resp =
+                                    db_list.set_user_disabled(&username, disabled, &client_key);
+
+                                info!(
+                                    "{} set user \"{}\" disabled to {}, response: {:?}",
+                                    client_name, username, disabled, resp
                                 );
 
                                 #[cfg(not(feature = "no-saving"))]
-                                lock.save_specific_db(&db_name);
+                                db_list.save_db_list();
+                                resp
+                            }),
+                            DBPacket::CreateSession => {
+                                let resp = db_list.create_session(&client_key);
+
+                                info!(
+                                    "{} created a session, response: {:?}",
+                                    client_name,
+                                    resp.as_ref().map(|_| ())
+                                );
+
                                 resp
                             }
+                            DBPacket::ResumeSession(token) => {
+                                let resp = db_list.resume_session(&token);
+
+                                info!(
+                                    "{} resumed a session, response: {:?}",
+                                    client_name,
+                                    resp.as_ref().map(|_| ())
+                                );
+
+                                resp.map(|session_key| {
+                                    client_key = session_key;
+                                    client_name =
+                                        format!("Client [{}] [{}]:", ip_address, client_key);
+                                    SuccessNoData
+                                })
+                            }
+                            DBPacket::EndSession(token) => {
+                                let resp = db_list.end_session(&token);
+
+                                info!("{} ended a session, response: {:?}", client_name, resp);
+
+                                resp
+                            }
+                            DBPacket::RotateServerKey => {
+                                let resp = if db_list.is_super_admin(&client_key) {
+                                    // generating a key takes a few seconds, so it is generated on a blocking thread, then swapped in
+                                    let new_key = spawn_blocking(ServerKey::default)
+                                        .await
+                                        .expect("generating a server key does not panic");
+                                    block_in_place(|| {
+                                        db_list.rotate_server_key(&client_key, new_key)
+                                    })
+                                } else {
+                                    Err(InvalidPermissions)
+                                };
+
+                                info!(
+                                    "{} rotated the server key, response: {:?}",
+                                    client_name, resp
+                                );
+
+                                resp
+                            }
+                            DBPacket::SetIpFilter(new_filter) => {
+                                let resp = if db_list.is_super_admin(&client_key) {
+                                    *ip_filter.write().unwrap() = new_filter;
+                                    Ok(SuccessNoData)
+                                } else {
+                                    Err(InvalidPermissions)
+                                };
+
+                                info!("{} set the ip filter, response: {:?}", client_name, resp);
+
+                                resp
+                            }
+                            DBPacket::SetNamespace(namespace, settings) => block_in_place(|| {
+                                let resp = db_list.set_namespace(&namespace, settings, &client_key);
+
+                                info!(
+                                    "{} set the settings of namespace \"{}\", response: {:?}",
+                                    client_name, namespace, resp
+                                );
+
+                                #[cfg(not(feature = "no-saving"))]
+                                db_list.save_db_list();
+                                resp
+                            }),
+                            DBPacket::GetNamespace(namespace) => {
+                                let resp = db_list.get_namespace(&namespace, &client_key);
+
+                                info!(
+                                    "{} got the settings of namespace \"{}\", response: {:?}",
+                                    client_name, namespace, resp
+                                );
+
+                                resp
+                            }
+                            DBPacket::ListUsers => {
+                                let resp = db_list.list_users(&client_key);
+
+                                info!("{} listed users, response: {:?}", client_name, resp);
+
+                                resp
+                            }
+                            DBPacket::GetDBSettings(db_name) => {
+                                let resp = db_list.get_db_settings(&db_name, &client_key);
+
+                                info!(
+                                    "{} got db settings from \"{}\", response: {:?}",
+                                    client_name, db_name, resp
+                                );
+
+                                resp
+                            }
+                            DBPacket::ChangeDBSettings(db_name, mut db_settings) => {
+                                block_in_place(|| {
+                                    db_list.hash_settings_keys(&db_name, &mut db_settings);
+                                    let resp = db_list.change_db_settings(
+                                        &db_name,
+                                        db_settings.clone(),
+                                        &client_key,
+                                    );
+
+                                    info!(
+                                        "{} changed db settings of \"{}\" to \"{:?}\", response: {:?}",
+                                        client_name, db_name, db_settings, resp
+                                    );
+
+                                    #[cfg(not(feature = "no-saving"))]
+                                    db_list.save_db_if_due(&db_name, save_after_writes);
+                                    resp
+                                })
+                            }
                             DBPacket::GetRole(db_name) => {
-                                let lock = db_list.read().unwrap();
-                                let resp = lock.get_role(&db_name, &client_key);
+                                let resp = db_list.get_role(&db_name, &client_key);
 
                                 info!(
                                     "{} got role from \"{}\", response: {:?}",
@@ -250,9 +1503,18 @@ pub(crate) async fn handle_client(mut stream: TcpStream, db_list: DBListThreadSa
 
                                 resp
                             }
-                            DBPacket::DeleteData(db_name, db_location) => {
-                                let lock = db_list.read().unwrap();
-                                let resp = lock.delete_data(&db_name, &db_location, &client_key);
+                            DBPacket::GetEffectivePermissions(db_name) => {
+                                let resp = db_list.get_effective_permissions(&db_name, &client_key);
+
+                                info!(
+                                    "{} got effective permissions from \"{}\", response: {:?}",
+                                    client_name, db_name, resp
+                                );
+
+                                resp
+                            }
+                            DBPacket::DeleteData(db_name, db_location) => block_in_place(|| {
+                                let resp = db_list.delete_data(&db_name, &db_location, &client_key);
 
                                 info!(
                                     "{} deleted data from \"{}\" in \"{}\", response: {:?}",
@@ -260,26 +1522,57 @@ pub(crate) async fn handle_client(mut stream: TcpStream, db_list: DBListThreadSa
                                 );
 
                                 #[cfg(not(feature = "no-saving"))]
-                                lock.save_specific_db(&db_name);
+                                db_list.save_db_if_due(&db_name, save_after_writes);
                                 resp
+                            }),
+                            DBPacket::GetStats(db_name) => db_list.get_stats(&db_name, &client_key),
+                            DBPacket::GetServerStats => db_list.get_server_stats(&client_key),
+                            DBPacket::GetHotKeys(db_name, n) => {
+                                db_list.get_hot_keys(&db_name, n, &client_key)
                             }
-                            DBPacket::GetStats(db_name) => {
-                                db_list.read().unwrap().get_stats(&db_name, &client_key)
+                            DBPacket::ResetStats(db_name) => block_in_place(|| {
+                                let resp = db_list.reset_stats(&db_name, &client_key);
+
+                                info!(
+                                    "{} reset statistics of \"{}\", response: {:?}",
+                                    client_name, db_name, resp
+                                );
+
+                                #[cfg(not(feature = "no-saving"))]
+                                db_list.save_db_if_due(&db_name, save_after_writes);
+                                resp
+                            }),
+                            DBPacket::ExportStats(format) => {
+                                db_list.export_stats(format, &client_key)
                             }
-                        }
+                        };
+
+                        record_audit(
+                            audit_log.as_deref(),
+                            audit_target.as_ref(),
+                            ip_address,
+                            &client_key,
+                            &response,
+                        );
+                        response
                     }
                     Err(err) => {
-                        error!("packet serialization error: {}", err);
-                        Err(BadPacket)
-                        // continue;
+                        error!(
+                            "{} sent a packet that could not be read: {}",
+                            client_name, err
+                        );
+                        Err(err)
                     }
                 };
 
-                let ser = serde_json::to_string(&response).unwrap();
+                let ser = serialize_response(&response).unwrap();
+
+                #[cfg(feature = "statistics")]
+                db_list.record_request(packet_name, read, ser.len(), response.is_err());
 
                 // check if the client is using encryption in their communication
                 let write_result =
-                    write_to_client(&mut stream, client_pub_key_opt.as_ref(), ser, &db_list);
+                    write_to_client(&mut stream, session_cipher_opt.as_ref(), ser).await;
 
                 if write_result.is_err() {
                     info!(
@@ -296,6 +1589,12 @@ pub(crate) async fn handle_client(mut stream: TcpStream, db_list: DBListThreadSa
                 break;
             }
         } else {
+            if read_result.is_err_and(|err| err.kind() == std::io::ErrorKind::InvalidData) {
+                // the rest of the packet can not be told apart from the next one, so the client is told why before it is dropped
+                warn!("{} sent data that can not be a packet", client_name);
+                let ser = serialize_response(&Err(PacketCorrupted)).unwrap();
+                let _ = write_to_client(&mut stream, session_cipher_opt.as_ref(), ser).await;
+            }
             info!(
                 "{} dropped. Unable to read socket data. {:?}",
                 client_name, stream
@@ -305,26 +1604,35 @@ pub(crate) async fn handle_client(mut stream: TcpStream, db_list: DBListThreadSa
     }
 }
 
-fn write_to_client(
+/// Records the request in the audit log, if the server keeps one and the request changes a database.
+fn record_audit(
+    audit_log: Option<&AuditLog>,
+    audit_target: Option<&AuditTarget>,
+    client: SocketAddr,
+    client_key: &str,
+    response: &Result<DBSuccessResponse<String>, DBPacketResponseError>,
+) {
+    if let (Some(audit_log), Some(audit_target)) = (audit_log, audit_target) {
+        audit_log.record(client, client_key, audit_target, response);
+    }
+}
+
+async fn write_to_client(
     stream: &mut TcpStream,
-    client_pub_key_opt: Option<&RsaPublicKey>,
+    session_cipher_opt: Option<&SessionCipher>,
     ser: String,
-    db_list: &DBListThreadSafe,
-) -> std::io::Result<usize> {
-    match &client_pub_key_opt {
+) -> std::io::Result<()> {
+    match session_cipher_opt {
         None => {
             // client is not using encryption, send the raw bytes
-            stream.write(ser.as_bytes())
+            stream.write_all(ser.as_bytes()).await
         }
-        Some(key) => {
-            // client is using encryption, encrypt the packet then send the encrypted bytes
-            let ency_data = db_list
-                .write()
-                .unwrap()
-                .server_key
-                .encrypt_packet(&ser, key)
-                .unwrap();
-            stream.write(ency_data.get_data())
+        Some(cipher) => {
+            // client is using encryption, encrypt the packet with the session key then send the encrypted bytes
+            let ency_data = cipher.encrypt_response(&ser).map_err(|err| {
+                std::io::Error::other(format!("Unable to encrypt response: {err:?}"))
+            })?;
+            stream.write_all(&ency_data).await
         }
     }
 }
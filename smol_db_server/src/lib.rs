@@ -0,0 +1,27 @@
+//! Library for running a `smol_db` server within any application, such as for tests or bundled deployments.
+//! The `smol_db_server` binary is a thin wrapper around `Server`.
+use smol_db_common::db_list::DBList;
+use std::sync::Arc;
+
+mod cache_invalidator;
+mod connection_limiter;
+mod handle_client;
+mod new_user_handler;
+mod open_streams;
+mod queue_handler;
+mod rate_limiter;
+mod replica;
+pub mod server;
+pub mod server_config;
+mod session_key_hasher;
+mod stream_read_handler;
+mod stream_write_handler;
+mod timeouts;
+mod watch_handler;
+
+pub use server::Server;
+pub use server_config::ServerConfig;
+
+/// A db list that can be shared between the threads serving clients.
+/// The db list has no lock of its own, each database and list within it is locked separately, so clients working on different databases never wait on each other.
+pub type DBListThreadSafe = Arc<DBList>;
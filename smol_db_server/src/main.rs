@@ -1,99 +1,93 @@
 //! Binary application that runs a `smol_db` server instance
-#[cfg(not(feature = "no-saving"))]
-use crate::cache_invalidator::cache_invalidator;
-use crate::new_user_handler::user_listener;
-use futures::executor::ThreadPoolBuilder;
-use futures::join;
-use smol_db_common::db_list::DBList;
-#[cfg(not(feature = "no-saving"))]
+use crate::cli_args::CliArgs;
+use smol_db_common::logging::logger::Logger;
+use smol_db_server::{Server, ServerConfig};
 use std::fs;
-use std::net::TcpListener;
-use std::process::exit;
-use std::sync::{Arc, RwLock};
+use std::sync::Arc;
 use tracing::info;
-#[cfg(feature = "tracing")]
+use tracing_subscriber::filter::LevelFilter;
 use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::Layer;
 
-#[cfg(not(feature = "no-saving"))]
-mod cache_invalidator;
-mod handle_client;
-mod new_user_handler;
-
-type DBListThreadSafe = Arc<RwLock<DBList>>;
-
-#[allow(dead_code)]
-const LOG_FILE_PATH: &str = "./data/log.log";
+mod cli_args;
 
 fn main() {
-    #[cfg(feature = "tracing")]
-    tracing::subscriber::set_global_default(
-        tracing_subscriber::registry().with(tracing_tracy::TracyLayer::default()),
-    )
-    .expect("setup tracy layer");
-
-    #[cfg(not(feature = "tracing"))]
-    let _ = tracing_subscriber::fmt::try_init();
-
-    let listener = TcpListener::bind("0.0.0.0:8222").expect("Failed to bind to port 8222.");
-
-    let thread_pool = ThreadPoolBuilder::new()
-        .name_prefix("[Smol_DB]")
-        .create()
-        .unwrap();
-
-    {
-        print!("Features enabled:");
-        #[cfg(feature = "tracing")]
-        print!(" Tracing");
-        #[cfg(feature = "statistics")]
-        print!(" Statistics");
-        #[cfg(feature = "no-saving")]
-        print!(" No-Saving");
-        println!();
+    let config = CliArgs::load_config();
+
+    setup_logging(&config);
+
+    println!("Features enabled: {}", Server::enabled_features().join(" "));
+
+    let address = config.socket_address();
+    let server = Arc::new(
+        Server::bind(config)
+            .unwrap_or_else(|err| panic!("Failed to start server on {}: {}", address, err)),
+    );
+    // reported after binding, as port 0 is replaced with the port picked by the operating system
+    match server.local_addr() {
+        Ok(local_addr) => println!("Listening on {}", local_addr),
+        Err(err) => println!(
+            "Listening on {}, unable to read the bound address: {}",
+            address, err
+        ),
+    }
+    println!("Server key fingerprint: {}", server.key_fingerprint());
+    if let Some(token) = server.generated_bootstrap_token() {
+        println!("No super admin exists, claim it with the bootstrap token: {token}");
     }
-
-    let db_list: DBListThreadSafe = Arc::new(RwLock::new(DBList::load_db_list()));
-
-    #[cfg(not(feature = "no-saving"))]
-    let _ = fs::create_dir("./data");
-
-    #[cfg(not(feature = "no-saving"))]
-    fs::read_dir("./data").expect("Data directory ./data must exist"); // the data directory must exist, so we make sure this happens
 
     // control-c handler for saving things before the server shuts down.
-    setup_control_c_handler(db_list.clone());
-
-    // thread that continuously checks if caches need to be removed from cache when they get old.
-    #[cfg(not(feature = "no-saving"))]
-    let cache_invalidator_future = cache_invalidator(db_list.clone());
-
-    #[cfg(feature = "no-saving")]
-    let cache_invalidator_future = async {};
+    setup_control_c_handler(server.clone());
 
-    let user_listener = user_listener(listener, db_list, &thread_pool);
-
-    info!("Waiting for connections on port 8222");
-
-    futures::executor::block_on(async {
-        join!(cache_invalidator_future, user_listener,);
-    });
+    server.run();
 }
 
-#[tracing::instrument]
-fn setup_control_c_handler(db_list: DBListThreadSafe) {
+#[tracing::instrument(skip(server))]
+fn setup_control_c_handler(server: Arc<Server>) {
     ctrlc::set_handler(move || {
         info!("Received CTRL+C, gracefully shutting down program.");
-        let lock = db_list.read().unwrap();
-        info!("{:?}", lock.list.read().unwrap());
-
-        #[cfg(not(feature = "no-saving"))]
-        {
-            lock.save_db_list();
-            lock.save_all_db();
-            info!("Saved all db files and db list.");
-        }
-        info!("Saved all db files and db list.");
-        exit(0);
+        server.shutdown();
     })
     .unwrap();
 }
+
+/// Sets up log entries at the configured level to be written to the log file, and printed when tracy is not used.
+fn setup_logging(config: &ServerConfig) {
+    let level = LevelFilter::from_level(config.log_level.as_tracing_level());
+
+    let log_file = if config.log_file {
+        // the log file is kept in the data directory even when databases are not saved
+        match fs::create_dir_all(&config.data_dir) {
+            Ok(()) => Some(Arc::new(Logger::new(
+                &config.data_dir,
+                config.log_file_max_bytes,
+                config.log_file_rotated_files,
+            ))),
+            Err(err) => {
+                eprintln!(
+                    "Unable to create data directory {} for the log file: {}",
+                    config.data_dir.display(),
+                    err
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let file_layer = log_file.map(|logger| {
+        tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(logger)
+    });
+    let subscriber = tracing_subscriber::registry().with(file_layer.with_filter(level));
+
+    #[cfg(feature = "tracing")]
+    let subscriber = subscriber.with(tracing_tracy::TracyLayer::default());
+
+    #[cfg(not(feature = "tracing"))]
+    let subscriber = subscriber.with(tracing_subscriber::fmt::layer().with_filter(level));
+
+    tracing::subscriber::set_global_default(subscriber).expect("setup logging");
+}
@@ -1,34 +1,90 @@
 use crate::handle_client::handle_client;
-use futures::executor::ThreadPool;
-use futures::task::SpawnExt;
-use smol_db_common::prelude::DBList;
-use std::net::TcpListener;
-use std::sync::{Arc, RwLock};
-use tracing::{debug, info};
-
-#[tracing::instrument(skip(db_list))]
-pub(crate) async fn user_listener(
-    listener: TcpListener,
-    db_list: Arc<RwLock<DBList>>,
-    thread_pool: &ThreadPool,
-) {
+use crate::server::Server;
+use crate::watch_handler::write_response;
+use smol_db_common::prelude::DBPacketResponseError;
+use tokio::net::TcpListener;
+use tracing::{debug, error, info, warn};
+
+#[tracing::instrument(skip(server))]
+pub(crate) async fn user_listener(server: &Server) {
+    let listener = match server.listener.try_clone().and_then(TcpListener::from_std) {
+        Ok(listener) => listener,
+        Err(err) => {
+            error!("Unable to listen for users: {}", err);
+            return;
+        }
+    };
+
+    let mut shutdown = server.shutdown_sender.subscribe();
+
     info!("Listening for users");
-    for income in listener.incoming() {
-        let stream = income.expect("Failed to receive tcp stream");
+    loop {
+        let income = tokio::select! {
+            income = listener.accept() => income,
+            _ = shutdown.wait_for(|shutting_down| *shutting_down) => {
+                info!("Stopped listening for users");
+                return;
+            }
+        };
+
+        let (stream, peer_addr) = match income {
+            Ok(income) => income,
+            Err(err) => {
+                warn!("Failed to receive tcp stream: {}", err);
+                continue;
+            }
+        };
+
+        if !server.ip_filter.read().unwrap().is_allowed(peer_addr.ip()) {
+            // closed without a response, so a rejected address learns nothing about the server
+            warn!("Rejected client {}, its address is not allowed", peer_addr);
+            continue;
+        }
+
+        let Some(permit) = server.connection_limiter.try_acquire(peer_addr.ip()) else {
+            warn!(
+                "Rejected client {}, too many connections are open",
+                peer_addr
+            );
+            let mut stream = stream;
+            // the client reads this as the response to its first packet
+            let _ =
+                write_response(&mut stream, Err(DBPacketResponseError::TooManyConnections)).await;
+            continue;
+        };
 
         info!(
-            "New client connected: {}",
-            stream
-                .peer_addr()
-                .map(|socket| format!("{}", socket))
-                .map_err(|err| format!("{:?}", err))
-                .unwrap_or_else(|s| s)
+            "New client connected: {}, {} clients connected",
+            peer_addr,
+            server.connection_limiter.connection_count()
         );
 
-        let client_future = handle_client(stream, db_list.clone());
+        let client_future = handle_client(
+            stream,
+            server.db_list.clone(),
+            server.config().connection_timeouts(),
+            server.rate_limiter.clone(),
+            server.key_hash_permits.clone(),
+            server.ip_filter.clone(),
+            server.config().save_after_writes,
+            server.config().is_replica(),
+            server.audit_log.clone(),
+            server.started,
+        );
+        let mut client_shutdown = server.shutdown_sender.subscribe();
 
-        let spawn_res = thread_pool.spawn(client_future);
+        // the client is dropped when the server shuts down, closing its connection
+        let client_task = tokio::spawn(async move {
+            tokio::select! {
+                _ = client_future => {}
+                _ = client_shutdown.wait_for(|shutting_down| *shutting_down) => {
+                    info!("Disconnecting client {} for shutdown", peer_addr);
+                }
+            }
+            // the slot is given back once the client disconnects
+            drop(permit);
+        });
 
-        debug!("Spawned client in thread pool: {:?}", spawn_res);
+        debug!("Spawned client task: {:?}", client_task);
     }
 }
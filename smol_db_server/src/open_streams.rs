@@ -0,0 +1,63 @@
+use smol_db_common::db_content::DBContent;
+use smol_db_common::prelude::{DBPacketResponseError, StreamItem};
+use std::collections::HashMap;
+use std::vec::IntoIter;
+
+/// Most streams a single client may have open at once, each holds a copy of its table until it is closed
+const MAX_OPEN_STREAMS: usize = 16;
+
+#[derive(Debug, Default)]
+/// The streams a client opened with `OpenStream`, each read one item at a time by its id.
+/// They are dropped along with the connection, so a client that disconnects never leaves one open.
+pub(crate) struct OpenStreams {
+    /// The remaining key value pairs of each open stream, by stream id
+    streams: HashMap<u64, IntoIter<(String, String)>>,
+    /// Id given to the next stream opened, ids are never reused on the same connection
+    next_id: u64,
+}
+
+impl OpenStreams {
+    /// Opens a stream of the copied table, returning its id.
+    /// Returns `TooManyStreams` if the client already has as many streams open as it is allowed.
+    pub(crate) fn open(&mut self, db_table: &DBContent) -> Result<u64, DBPacketResponseError> {
+        if self.streams.len() >= MAX_OPEN_STREAMS {
+            return Err(DBPacketResponseError::TooManyStreams);
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let items: Vec<(String, String)> = db_table
+            .content
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        self.streams.insert(id, items.into_iter());
+
+        Ok(id)
+    }
+
+    /// Returns the next item of the stream with the given id, the stream is closed once it returns `StreamItem::StreamEnd`.
+    pub(crate) fn next_item(&mut self, id: u64) -> Result<StreamItem, DBPacketResponseError> {
+        let stream = self
+            .streams
+            .get_mut(&id)
+            .ok_or(DBPacketResponseError::StreamNotFound)?;
+
+        match stream.next() {
+            Some((key, value)) => Ok(StreamItem::Item(key, value)),
+            None => {
+                self.streams.remove(&id);
+                Ok(StreamItem::StreamEnd)
+            }
+        }
+    }
+
+    /// Closes the stream with the given id before it is read to its end.
+    pub(crate) fn close(&mut self, id: u64) -> Result<(), DBPacketResponseError> {
+        self.streams
+            .remove(&id)
+            .map(|_| ())
+            .ok_or(DBPacketResponseError::StreamNotFound)
+    }
+}
@@ -0,0 +1,49 @@
+use crate::DBListThreadSafe;
+use smol_db_common::prelude::{
+    DBLocation, DBPacketInfo, DBPacketResponseError, DBSuccessResponse, SuccessNoData,
+};
+use smol_db_common::queue_waiters::QueueWaker;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::task::block_in_place;
+use tokio::time::{timeout_at, Instant};
+use tracing::debug;
+
+/// Pops the head of the queue, waiting up to the timeout for a value to be pushed if the queue is empty.
+/// Responds with `SuccessNoData` if the queue was still empty once the timeout passed.
+#[tracing::instrument(skip(db_list))]
+pub(crate) async fn blocking_queue_pop(
+    db_list: &DBListThreadSafe,
+    db_info: &DBPacketInfo,
+    queue_name: &DBLocation,
+    timeout: Duration,
+    client_key: &String,
+) -> Result<DBSuccessResponse<String>, DBPacketResponseError> {
+    let deadline = Instant::now() + timeout;
+    let pushed = Arc::new(Notify::new());
+    let waker: Arc<QueueWaker> = {
+        let pushed = pushed.clone();
+        Arc::new(move || pushed.notify_one())
+    };
+
+    loop {
+        // waiting starts before popping, so a value pushed after finding the queue empty still wakes this client
+        db_list
+            .queue_waiters
+            .wait_for_push(db_info, queue_name.as_key(), &waker);
+
+        // the db is only locked while popping, not while waiting, so other clients are able to push to the queue
+        let resp = block_in_place(|| db_list.queue_pop(db_info, queue_name, client_key));
+
+        if !matches!(resp, Ok(SuccessNoData)) {
+            return resp;
+        }
+
+        debug!("Queue empty, waiting for a value to be pushed");
+        // another client may pop the pushed value first, in which case the queue is waited on again
+        if timeout_at(deadline, pushed.notified()).await.is_err() {
+            return resp;
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Number of buckets kept before buckets that have fully refilled are removed
+const BUCKET_CLEANUP_THRESHOLD: usize = 1024;
+
+#[derive(Debug)]
+/// Limits how many requests each client may make, using a token bucket for each client key or ip address.
+pub(crate) struct RateLimiter {
+    /// Tokens added to each bucket every second, `None` disables rate limiting
+    requests_per_second: Option<u32>,
+    /// Most tokens a bucket can hold, which is the number of requests a client can make at once after being idle
+    burst: u32,
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+#[derive(Debug)]
+/// Tokens a client has left to spend, refilled over time up to a burst.
+pub(crate) struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(requests_per_second: Option<u32>, burst: Option<u32>) -> Self {
+        Self {
+            requests_per_second,
+            burst: burst.or(requests_per_second).unwrap_or(0).max(1),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes a token from the bucket of the given client, returns false if the client has made too many requests.
+    /// Clients are limited by their key once they set one, and by their ip address before then.
+    pub(crate) fn try_request(&self, client_key: &str, ip_address: IpAddr) -> bool {
+        let Some(requests_per_second) = self.requests_per_second else {
+            return true;
+        };
+
+        let client = if client_key.is_empty() {
+            ip_address.to_string()
+        } else {
+            client_key.to_string()
+        };
+
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if !buckets.contains_key(&client) && buckets.len() >= BUCKET_CLEANUP_THRESHOLD {
+            self.remove_full_buckets(&mut buckets, requests_per_second, now);
+        }
+
+        buckets
+            .entry(client)
+            .or_insert_with(|| TokenBucket::full(self.burst, now))
+            .try_take(requests_per_second, self.burst, now)
+    }
+
+    /// Buckets that have refilled completely behave the same as a new bucket, so they do not need to be kept.
+    fn remove_full_buckets(
+        &self,
+        buckets: &mut HashMap<String, TokenBucket>,
+        requests_per_second: u32,
+        now: Instant,
+    ) {
+        buckets.retain(|_, bucket| {
+            bucket.refill(requests_per_second, self.burst, now);
+            bucket.tokens < f64::from(self.burst)
+        });
+    }
+}
+
+impl TokenBucket {
+    /// Returns a bucket holding the whole burst
+    pub(crate) fn full(burst: u32, now: Instant) -> Self {
+        Self {
+            tokens: f64::from(burst),
+            last_refill: now,
+        }
+    }
+
+    /// Refills the bucket and takes a token from it, returns false if it is empty.
+    pub(crate) fn try_take(&mut self, requests_per_second: u32, burst: u32, now: Instant) -> bool {
+        self.refill(requests_per_second, burst, now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn refill(&mut self, requests_per_second: u32, burst: u32, now: Instant) {
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens =
+            (self.tokens + elapsed * f64::from(requests_per_second)).min(f64::from(burst));
+        self.last_refill = now;
+    }
+}
@@ -0,0 +1,126 @@
+//! Contains the task a replica server runs to apply every change made on its primary server, reconnecting whenever the connection is lost.
+use crate::DBListThreadSafe;
+use smol_db_common::db_packets::packet_checksum::{deserialize_response, is_incomplete};
+use smol_db_common::encryption::key_hash::KeyHasher;
+use smol_db_common::prelude::{DBPacket, DBPacketResponseError, DBSuccessResponse, SuccessReply};
+use smol_db_common::replication::{ReplicationItem, ReplicationPosition};
+use std::io;
+use std::io::ErrorKind;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::task::block_in_place;
+use tracing::{debug, info, warn};
+
+/// How long to wait before reconnecting to the primary after the connection is lost
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Replicates every change made on the primary, never returning.
+#[tracing::instrument(skip(db_list, access_key))]
+pub(crate) async fn replicate_from(db_list: DBListThreadSafe, primary: &str, access_key: &str) {
+    info!("Replicating from {}", primary);
+    // kept between connections, so only the changes made while disconnected are sent again
+    let mut position: Option<ReplicationPosition> = None;
+    loop {
+        match replicate_until_disconnected(&db_list, primary, access_key, &mut position).await {
+            Ok(()) => info!("Primary {} ended replication", primary),
+            Err(err) => warn!("Replication from {} stopped: {}", primary, err),
+        }
+        tokio::time::sleep(RECONNECT_INTERVAL).await;
+    }
+}
+
+/// Connects to the primary and applies each item it sends, updating the position after each change is applied.
+async fn replicate_until_disconnected(
+    db_list: &DBListThreadSafe,
+    primary: &str,
+    access_key: &str,
+    position: &mut Option<ReplicationPosition>,
+) -> io::Result<()> {
+    let mut stream = TcpStream::connect(primary).await?;
+
+    send_packet(&mut stream, &DBPacket::SetKey(access_key.to_string())).await?;
+    read_response(&mut stream).await?.map_err(response_error)?;
+
+    send_packet(&mut stream, &DBPacket::new_replica_handshake(*position)).await?;
+    read_response(&mut stream).await?.map_err(response_error)?;
+    info!("Connected to primary {} from {:?}", primary, position);
+
+    let mut log_id = position.map(|position| position.log_id);
+    loop {
+        send_packet(&mut stream, &DBPacket::ReadyForNextItem).await?;
+        let SuccessReply(item) = read_response(&mut stream).await?.map_err(response_error)? else {
+            return Ok(());
+        };
+        let item: ReplicationItem = serde_json::from_str(&item)
+            .map_err(|err| io::Error::new(ErrorKind::InvalidData, err))?;
+
+        let sequence = match &item {
+            ReplicationItem::Resync {
+                log_id: new_log_id,
+                key_salt,
+                super_admins,
+                users,
+                ..
+            } => {
+                info!("Primary {} is sending every database", primary);
+                block_in_place(|| {
+                    // keys are hashed the same way as on the primary, so they match the hashes in the replicated settings
+                    *db_list.key_hasher.write().unwrap() = KeyHasher::new(key_salt.clone());
+                    *db_list.super_admin_hash_list.write().unwrap() = super_admins.clone();
+                    *db_list.user_registry.write().unwrap() = users.clone();
+                    db_list.save_db_list();
+                });
+                // the replica is not caught up until a change arrives after every database
+                log_id = Some(*new_log_id);
+                *position = None;
+                None
+            }
+            ReplicationItem::Database(..) => None,
+            ReplicationItem::Change(sequence, _) => Some(*sequence),
+        };
+
+        // applying a change appends it to the write-ahead log, which blocks on the disk
+        if let Err(err) = block_in_place(|| db_list.apply_replicated(item)) {
+            warn!("Unable to apply replicated change: {}", err);
+        }
+
+        if let (Some(log_id), Some(sequence)) = (log_id, sequence) {
+            *position = Some(ReplicationPosition {
+                log_id,
+                next_sequence: sequence + 1,
+            });
+        }
+    }
+}
+
+async fn send_packet(stream: &mut TcpStream, packet: &DBPacket) -> io::Result<()> {
+    let ser = packet.serialize_packet()?;
+    stream.write_all(ser.as_bytes()).await
+}
+
+/// Reads a single response, which can be larger than a single read when it holds a whole database.
+async fn read_response(
+    stream: &mut TcpStream,
+) -> io::Result<Result<DBSuccessResponse<String>, DBPacketResponseError>> {
+    let mut response = vec![];
+    let mut buf: [u8; 1024] = [0; 1024];
+    loop {
+        let read_len = stream.read(&mut buf).await?;
+        if read_len == 0 {
+            return Err(ErrorKind::UnexpectedEof.into());
+        }
+        response.extend_from_slice(&buf[0..read_len]);
+        if is_incomplete(&response).map_err(|err| io::Error::new(ErrorKind::InvalidData, err))? {
+            debug!("Read {} bytes of response so far", response.len());
+            continue;
+        }
+
+        return deserialize_response(&response)
+            .map_err(|err| io::Error::new(ErrorKind::InvalidData, err));
+    }
+}
+
+fn response_error(err: DBPacketResponseError) -> io::Error {
+    io::Error::other(format!("primary responded with {}", err))
+}
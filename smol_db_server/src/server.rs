@@ -0,0 +1,305 @@
+//! Contains `Server`, a smol_db server instance that can be run within any application
+use crate::cache_invalidator::cache_invalidator;
+use crate::connection_limiter::ConnectionLimiter;
+use crate::new_user_handler::user_listener;
+use crate::rate_limiter::RateLimiter;
+use crate::replica::replicate_from;
+use crate::server_config::ServerConfig;
+use crate::DBListThreadSafe;
+use smol_db_common::db_list::DBList;
+use smol_db_common::db_packets::ip_filter::IpFilter;
+use smol_db_common::encryption::key_hash::{generate_token, KEY_HASH_VERSION};
+use smol_db_common::encryption::server_encrypt::{ServerKey, SERVER_KEY_FILE_NAME};
+use smol_db_common::logging::audit::AuditLog;
+use smol_db_common::replication::ReplicationLog;
+use smol_db_common::session_store::SessionStore;
+use std::fs;
+use std::future::pending;
+use std::io;
+use std::net::{SocketAddr, TcpListener};
+use std::num::NonZeroUsize;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+use tokio::runtime::{Builder, Runtime};
+use tokio::sync::{watch, Semaphore};
+use tracing::info;
+
+/// A smol_db server instance, bound to an address and ready to serve clients.
+/// ```no_run
+/// use smol_db_server::{Server, ServerConfig};
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let config = ServerConfig {
+///     port: 0,
+///     saving: false,
+///     ..Default::default()
+/// };
+/// let server = Arc::new(Server::bind(config).unwrap());
+/// println!("Listening on {}", server.local_addr().unwrap());
+///
+/// let server_thread = {
+///     let server = server.clone();
+///     thread::spawn(move || server.run())
+/// };
+///
+/// // connect to the server with smol_db_client here
+///
+/// server.shutdown();
+/// server_thread.join().unwrap();
+/// ```
+pub struct Server {
+    pub(crate) listener: TcpListener,
+    pub(crate) db_list: DBListThreadSafe,
+    /// Set to true once the server is shutting down, which every client task and the listener wait on
+    pub(crate) shutdown_sender: watch::Sender<bool>,
+    /// Keeps track of connected clients, so connections past the configured limits are rejected
+    pub(crate) connection_limiter: Arc<ConnectionLimiter>,
+    /// Shared by every client, so a client can not avoid its limit by opening more connections
+    pub(crate) rate_limiter: Arc<RateLimiter>,
+    /// Keys hashed at once by every client together, so clients setting keys at the same time can not use up the memory of the server
+    pub(crate) key_hash_permits: Arc<Semaphore>,
+    /// Addresses connections are accepted from, which a super admin can replace while the server runs
+    pub(crate) ip_filter: Arc<RwLock<IpFilter>>,
+    /// Records every request that changes a database, when enabled in the config
+    pub(crate) audit_log: Option<Arc<AuditLog>>,
+    /// When the server was bound, which its uptime is measured from
+    pub(crate) started: Instant,
+    runtime: Runtime,
+    config: ServerConfig,
+    /// Bootstrap token generated when the server started without a super admin or a configured token
+    generated_bootstrap_token: Option<String>,
+}
+
+impl Server {
+    /// Binds to the configured address, creates the data directory if saving is enabled, and loads the db list from it.
+    /// Clients are not served until `run` is called.
+    #[tracing::instrument]
+    pub fn bind(config: ServerConfig) -> io::Result<Self> {
+        // bound before loading the db list, so clients that connect while the server key is generated wait in the backlog
+        let listener = TcpListener::bind(config.socket_address())?;
+        // the listener is used by the tokio runtime, which requires it to be non-blocking
+        listener.set_nonblocking(true)?;
+        info!("Listening on {}", listener.local_addr()?);
+
+        let saving_enabled = config.saving_enabled();
+
+        if saving_enabled {
+            fs::create_dir_all(&config.data_dir)?;
+            // the data directory must exist, so we make sure this happens
+            fs::read_dir(&config.data_dir)?;
+        } else {
+            info!("Saving is disabled, databases will only be kept in memory");
+        }
+
+        if let Some(snapshot_path) = config.restore_snapshot_path() {
+            if !saving_enabled {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "Restoring a snapshot requires saving to be enabled",
+                ));
+            }
+            info!("Restoring snapshot {}", snapshot_path.display());
+            DBList::restore_snapshot(&config.data_dir, &snapshot_path).map_err(|err| {
+                io::Error::other(format!(
+                    "Unable to restore snapshot {}: {}",
+                    snapshot_path.display(),
+                    err
+                ))
+            })?;
+        }
+
+        if config.is_replica() && config.replication_key.is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Replicating from a primary requires a replication key",
+            ));
+        }
+
+        let audit_log = if config.audit_log {
+            // the audit log is kept in the data directory even when databases are not saved
+            fs::create_dir_all(&config.data_dir)?;
+            Some(Arc::new(AuditLog::new(
+                &config.data_dir,
+                config.audit_log_max_bytes,
+                config.audit_log_rotated_files,
+            )))
+        } else {
+            None
+        };
+
+        let mut db_list = DBList::load_db_list_from(&config.data_dir);
+        db_list.saving_enabled = saving_enabled;
+        db_list.replication = ReplicationLog::new(config.replication_log_size);
+        db_list.quota = config.db_quota();
+        db_list.sessions = SessionStore::new(config.session_ttl());
+        db_list.trash_retention = config.trash_retention();
+        db_list.cache_memory_budget = config.cache_memory_budget;
+        if saving_enabled {
+            // the key is kept between restarts, so clients that pinned its fingerprint keep trusting the server
+            *db_list.server_key.get_mut().unwrap() =
+                ServerKey::load_or_create(&config.data_dir.join(SERVER_KEY_FILE_NAME))?;
+        }
+        if db_list.key_hash_version < KEY_HASH_VERSION {
+            db_list.migrate_plaintext_keys();
+        }
+        let mut generated_bootstrap_token = None;
+        if db_list.super_admin_hash_list.read().unwrap().is_empty() {
+            // the first super admin must be claimed with the token, rather than being whoever sets a key first
+            let token = config.bootstrap_token.clone().unwrap_or_else(|| {
+                info!("No super admin exists and no bootstrap token is configured, generating one");
+                generated_bootstrap_token.insert(generate_token()).clone()
+            });
+            db_list.set_bootstrap_token(&token);
+        }
+
+        let mut runtime_builder = Builder::new_multi_thread();
+        runtime_builder.thread_name("[Smol_DB]").enable_all();
+        if let Some(pool_size) = config.thread_pool_size {
+            runtime_builder.worker_threads(pool_size);
+        }
+        let runtime = runtime_builder.build()?;
+
+        Ok(Self {
+            listener,
+            db_list: Arc::new(db_list),
+            shutdown_sender: watch::channel(false).0,
+            connection_limiter: Arc::new(ConnectionLimiter::new(
+                config.max_connections,
+                config.max_connections_per_ip,
+            )),
+            rate_limiter: Arc::new(RateLimiter::new(
+                config.requests_per_second,
+                config.request_burst,
+            )),
+            key_hash_permits: Arc::new(Semaphore::new(
+                std::thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            )),
+            ip_filter: Arc::new(RwLock::new(config.ip_filter())),
+            audit_log,
+            started: Instant::now(),
+            runtime,
+            config,
+            generated_bootstrap_token,
+        })
+    }
+
+    /// Binds to the given address with the default config, keeping databases only in memory so several servers can run at once,
+    /// such as `0.0.0.0:0` to listen on every interface on a port picked by the operating system, which `local_addr` reports.
+    /// ```
+    /// use smol_db_server::Server;
+    ///
+    /// let server = Server::bind_address("127.0.0.1:0").unwrap();
+    /// assert_ne!(server.local_addr().unwrap().port(), 0);
+    /// ```
+    pub fn bind_address(address: &str) -> io::Result<Self> {
+        Self::bind(
+            ServerConfig {
+                saving: false,
+                ..ServerConfig::default()
+            }
+            .with_socket_address(address)?,
+        )
+    }
+
+    /// Names of the features the server was built with.
+    pub fn enabled_features() -> Vec<&'static str> {
+        let mut features = vec![];
+        if cfg!(feature = "tracing") {
+            features.push("tracing");
+        }
+        if cfg!(feature = "statistics") {
+            features.push("statistics");
+        }
+        if cfg!(feature = "no-saving") {
+            features.push("no-saving");
+        }
+        features
+    }
+
+    /// The address the server is listening on, useful when the server was bound to port 0.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    pub const fn config(&self) -> &ServerConfig {
+        &self.config
+    }
+
+    /// The fingerprint of the key the server presents to clients setting up encryption, which clients can pin to trust only this server.
+    pub fn key_fingerprint(&self) -> String {
+        self.db_list.server_key.read().unwrap().fingerprint()
+    }
+
+    /// The bootstrap token generated when the server started without a super admin or a configured token.
+    /// It is only kept in memory, so it must be shown to whoever runs the server for the first super admin to be claimed.
+    pub fn generated_bootstrap_token(&self) -> Option<&str> {
+        self.generated_bootstrap_token.as_deref()
+    }
+
+    /// The db list the server serves, which can be shared with a `LocalClient` to use the same databases without a socket.
+    pub fn db_list(&self) -> DBListThreadSafe {
+        self.db_list.clone()
+    }
+
+    /// Serves clients until `shutdown` is called, blocking the current thread.
+    /// Every database and the db list are saved once the server stops, if saving is enabled.
+    /// Must not be called from within a tokio runtime, as the server runs its own runtime.
+    #[tracing::instrument(skip(self))]
+    pub fn run(&self) {
+        let saving_enabled = self.config.saving_enabled();
+
+        self.runtime.block_on(async {
+            // task that continuously checks if caches need to be removed from cache when they get old.
+            // caches are never removed when saving is disabled, as they are the only copy of each database.
+            let cache_invalidator_future = async {
+                if saving_enabled {
+                    cache_invalidator(
+                        self.db_list.clone(),
+                        self.config.cache_invalidation_interval(),
+                    )
+                    .await;
+                }
+                pending::<()>().await;
+            };
+
+            info!("Waiting for connections on {:?}", self.local_addr());
+
+            // task that applies every change made on the primary, when this server is a replica
+            let replica_future = async {
+                match (&self.config.replicate_from, &self.config.replication_key) {
+                    (Some(primary), Some(key)) => {
+                        replicate_from(self.db_list.clone(), primary, key).await;
+                    }
+                    _ => pending::<()>().await,
+                }
+            };
+
+            tokio::select! {
+                _ = cache_invalidator_future => {}
+                _ = replica_future => {}
+                _ = user_listener(self) => {}
+            }
+        });
+
+        info!("Server stopped listening");
+
+        if self.db_list.saving_enabled {
+            self.db_list.checkpoint_wal();
+            info!("Saved all db files and db list.");
+        }
+    }
+
+    /// Stops the server, causing `run` to disconnect every client, save, and return.
+    /// Can be called from any thread, such as a control-c handler.
+    #[tracing::instrument(skip(self))]
+    pub fn shutdown(&self) {
+        info!("Shutting down server");
+        self.shutdown_sender.send_replace(true);
+    }
+
+    /// Returns true once `shutdown` has been called.
+    pub fn is_shutting_down(&self) -> bool {
+        *self.shutdown_sender.borrow()
+    }
+}
@@ -0,0 +1,220 @@
+//! Contains the configuration of a server instance, which can be loaded from a toml file
+use crate::timeouts::ConnectionTimeouts;
+use serde::Deserialize;
+use smol_db_common::db_list::DEFAULT_DATA_DIR;
+use smol_db_common::db_packets::ip_filter::{IpFilter, IpNet};
+use smol_db_common::logging::logger::LogLevel;
+use smol_db_common::prelude::StorageQuota;
+use smol_db_common::session_store::DEFAULT_SESSION_TTL;
+use smol_db_common::snapshot::BACKUP_DIR_NAME;
+use smol_db_common::trash::DEFAULT_TRASH_RETENTION;
+use std::fs;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::info;
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+/// Configuration of a server instance.
+pub struct ServerConfig {
+    /// Address to listen for clients on
+    pub bind_address: IpAddr,
+    /// Port to listen for clients on, 0 lets the operating system pick a free port
+    pub port: u16,
+    /// Directory the db list and every database are saved in
+    pub data_dir: PathBuf,
+    /// Seconds between each save of all databases and removal of old caches
+    pub cache_invalidation_interval: u64,
+    /// Approximate bytes the cached databases may take up, the least recently accessed databases are removed from the cache past it
+    /// each `cache_invalidation_interval`. `None` keeps databases cached until their invalidation time
+    pub cache_memory_budget: Option<usize>,
+    /// Number of threads used to handle clients, `None` uses the number of cpus
+    pub thread_pool_size: Option<usize>,
+    /// When false, databases are only kept in memory and never saved to the data directory
+    pub saving: bool,
+    /// Seconds a client may stay connected without sending a request before it is disconnected, `None` never disconnects idle clients
+    pub idle_timeout: Option<u64>,
+    /// Seconds a client may take to send the next packet in the middle of a stream before it is disconnected, `None` waits forever
+    pub read_timeout: Option<u64>,
+    /// Seconds a client may take to read an item sent to it in the middle of a stream before it is disconnected, `None` waits forever
+    pub write_timeout: Option<u64>,
+    /// Most clients that may be connected at once, `None` allows any number
+    pub max_connections: Option<usize>,
+    /// Most clients that may be connected at once from a single ip address, `None` allows any number
+    pub max_connections_per_ip: Option<usize>,
+    /// Requests each client key, or ip address for clients without a key, may make per second, `None` disables rate limiting
+    pub requests_per_second: Option<u32>,
+    /// Requests a client may make at once after being idle, `None` uses `requests_per_second`
+    pub request_burst: Option<u32>,
+    /// Unsaved changes a database may have before a write saves it, fewer changes are saved every `cache_invalidation_interval`.
+    /// 1 saves the database after every write
+    pub save_after_writes: u64,
+    /// Snapshot restored into the data directory when the server starts, replacing the saved db list and databases.
+    /// A file name alone refers to a snapshot in the backups directory of the data directory
+    pub restore_snapshot: Option<PathBuf>,
+    /// Most entries any database may hold, a database with a stricter quota in its settings uses that instead. `None` allows any number
+    pub max_db_entries: Option<usize>,
+    /// Most bytes of keys and values any database may hold, a database with a stricter quota in its settings uses that instead. `None` allows any number
+    pub max_db_bytes: Option<usize>,
+    /// When true, every request that changes a database is recorded in `audit.log` in the data directory
+    pub audit_log: bool,
+    /// Bytes the audit log may grow to before it is rotated to `audit.log.1`
+    pub audit_log_max_bytes: u64,
+    /// Rotated audit logs that are kept, older logs are removed
+    pub audit_log_rotated_files: usize,
+    /// Least severe level of log entry that is printed and written to the log file
+    pub log_level: LogLevel,
+    /// When true, log entries are also written to `log.log` in the data directory
+    pub log_file: bool,
+    /// Bytes the log file may grow to before it is rotated to `log.log.1`
+    pub log_file_max_bytes: u64,
+    /// Rotated log files that are kept, older log files are removed
+    pub log_file_rotated_files: usize,
+    /// Changes kept in memory for replicas to catch up with after reconnecting, 0 disables replicating from this server.
+    /// A replica that falls further behind is sent every database again
+    pub replication_log_size: usize,
+    /// Address of the primary server to replicate from, making this server a read-only replica of it
+    pub replicate_from: Option<String>,
+    /// Key of a super admin on the primary server, required to replicate from it
+    pub replication_key: Option<String>,
+    /// Token that claims the first super admin while the server has none, `None` generates a token and prints it on startup
+    pub bootstrap_token: Option<String>,
+    /// Seconds a session token is valid for after it is created
+    pub session_ttl: u64,
+    /// Seconds a deleted database is kept in the trash directory, where it can be restored from, before it is purged
+    pub trash_retention: u64,
+    /// CIDR blocks connections are accepted from, an empty list accepts any address that is not denied
+    pub ip_allow: Vec<IpNet>,
+    /// CIDR blocks connections are always rejected from
+    pub ip_deny: Vec<IpNet>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            bind_address: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            port: 8222,
+            data_dir: PathBuf::from(DEFAULT_DATA_DIR),
+            cache_invalidation_interval: 10,
+            cache_memory_budget: None,
+            thread_pool_size: None,
+            saving: true,
+            idle_timeout: None,
+            read_timeout: None,
+            write_timeout: None,
+            max_connections: None,
+            max_connections_per_ip: None,
+            requests_per_second: None,
+            request_burst: None,
+            save_after_writes: 100,
+            restore_snapshot: None,
+            max_db_entries: None,
+            max_db_bytes: None,
+            audit_log: false,
+            audit_log_max_bytes: 10 * 1024 * 1024,
+            audit_log_rotated_files: 5,
+            log_level: LogLevel::Info,
+            log_file: true,
+            log_file_max_bytes: 10 * 1024 * 1024,
+            log_file_rotated_files: 5,
+            replication_log_size: 0,
+            replicate_from: None,
+            replication_key: None,
+            bootstrap_token: None,
+            session_ttl: DEFAULT_SESSION_TTL.as_secs(),
+            trash_retention: DEFAULT_TRASH_RETENTION.as_secs(),
+            ip_allow: vec![],
+            ip_deny: vec![],
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Reads a config from the given toml file, any missing fields use their default value.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        info!("Loading server config from {}", path.display());
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("Unable to read config file {}: {}", path.display(), err))?;
+        toml::from_str(&contents)
+            .map_err(|err| format!("Unable to parse config file {}: {}", path.display(), err))
+    }
+
+    /// The address the server listens for clients on.
+    pub const fn socket_address(&self) -> SocketAddr {
+        SocketAddr::new(self.bind_address, self.port)
+    }
+
+    /// Sets the address and port to listen for clients on from an address such as `0.0.0.0:0` or `localhost:8222`,
+    /// where port 0 lets the operating system pick a free port.
+    /// Fails if the address can not be resolved.
+    pub fn with_socket_address(self, address: &str) -> io::Result<Self> {
+        let socket_address = address.to_socket_addrs()?.next().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{address} did not resolve to any address"),
+            )
+        })?;
+        Ok(Self {
+            bind_address: socket_address.ip(),
+            port: socket_address.port(),
+            ..self
+        })
+    }
+
+    pub const fn cache_invalidation_interval(&self) -> Duration {
+        Duration::from_secs(self.cache_invalidation_interval)
+    }
+
+    pub(crate) fn connection_timeouts(&self) -> ConnectionTimeouts {
+        ConnectionTimeouts {
+            idle: self.idle_timeout.map(Duration::from_secs),
+            read: self.read_timeout.map(Duration::from_secs),
+            write: self.write_timeout.map(Duration::from_secs),
+        }
+    }
+
+    /// The path of the snapshot to restore, looking in the backups directory when the path does not exist as given.
+    pub fn restore_snapshot_path(&self) -> Option<PathBuf> {
+        self.restore_snapshot.as_ref().map(|snapshot| {
+            let in_backups = self.data_dir.join(BACKUP_DIR_NAME).join(snapshot);
+            if !snapshot.exists() && in_backups.exists() {
+                in_backups
+            } else {
+                snapshot.clone()
+            }
+        })
+    }
+
+    /// The storage quota every database is limited by.
+    pub const fn db_quota(&self) -> StorageQuota {
+        StorageQuota::new(self.max_db_entries, self.max_db_bytes)
+    }
+
+    /// How long a session token is valid for after it is created.
+    pub const fn session_ttl(&self) -> Duration {
+        Duration::from_secs(self.session_ttl)
+    }
+
+    /// How long a deleted database is kept in the trash before it is purged.
+    pub const fn trash_retention(&self) -> Duration {
+        Duration::from_secs(self.trash_retention)
+    }
+
+    /// The lists of addresses the server accepts connections from when it starts.
+    pub fn ip_filter(&self) -> IpFilter {
+        IpFilter::new(self.ip_allow.clone(), self.ip_deny.clone())
+    }
+
+    /// Returns true if the server replicates from a primary server, and rejects requests that change a database.
+    pub const fn is_replica(&self) -> bool {
+        self.replicate_from.is_some()
+    }
+
+    /// Returns true if databases should be saved to the data directory.
+    /// Always false when the server is built with the `no-saving` feature.
+    pub const fn saving_enabled(&self) -> bool {
+        self.saving && cfg!(not(feature = "no-saving"))
+    }
+}
@@ -0,0 +1,81 @@
+use crate::rate_limiter::TokenBucket;
+use crate::DBListThreadSafe;
+use smol_db_common::prelude::DBPacketResponseError;
+use smol_db_common::prelude::DBPacketResponseError::RateLimited;
+use std::collections::HashMap;
+use std::time::Instant;
+use tokio::sync::Semaphore;
+use tokio::task::spawn_blocking;
+
+/// Keys a client can hash at once after being idle
+const SET_KEY_BURST: u32 = 8;
+
+/// Keys a client can hash every second once it has used its burst
+const SET_KEYS_PER_SECOND: u32 = 2;
+
+/// Keys and hashes kept for each session, after which the oldest hashes are forgotten
+const MAX_REMEMBERED_KEYS: usize = 16;
+
+#[derive(Debug)]
+/// Hashes the keys a client sets during its session.
+/// Each hash takes a lot of memory and time to compute, so it is computed on a blocking thread, only while holding one of the permits shared by every client,
+/// each key is only hashed once per session, and a client can only hash a few keys a second.
+pub(crate) struct SessionKeyHasher {
+    bucket: TokenBucket,
+    /// Salt the remembered hashes were made with, as a replica replaces its salt with that of its primary
+    salt: String,
+    /// Keys set earlier in the session, and their hashes
+    hashed: HashMap<String, String>,
+}
+
+impl SessionKeyHasher {
+    pub(crate) fn new() -> Self {
+        Self {
+            bucket: TokenBucket::full(SET_KEY_BURST, Instant::now()),
+            salt: String::new(),
+            hashed: HashMap::new(),
+        }
+    }
+
+    /// Returns the hash of the key, computing it unless the key was set earlier in the session.
+    /// Returns `RateLimited` if the client hashed too many keys too quickly.
+    pub(crate) async fn hash_key(
+        &mut self,
+        db_list: &DBListThreadSafe,
+        permits: &Semaphore,
+        key: String,
+    ) -> Result<String, DBPacketResponseError> {
+        let key_hasher = db_list.key_hasher().clone();
+        if key_hasher.get_salt() != self.salt {
+            self.salt = key_hasher.get_salt().to_string();
+            self.hashed.clear();
+        }
+        if let Some(hash) = self.hashed.get(&key) {
+            return Ok(hash.clone());
+        }
+
+        if !self
+            .bucket
+            .try_take(SET_KEYS_PER_SECOND, SET_KEY_BURST, Instant::now())
+        {
+            return Err(RateLimited);
+        }
+
+        let _permit = permits
+            .acquire()
+            .await
+            .expect("key hash permits are never closed");
+        let hash = {
+            let key = key.clone();
+            spawn_blocking(move || key_hasher.hash_key(&key))
+                .await
+                .expect("hashing a key does not panic")
+        };
+
+        if self.hashed.len() >= MAX_REMEMBERED_KEYS {
+            self.hashed.clear();
+        }
+        self.hashed.insert(key, hash.clone());
+        Ok(hash)
+    }
+}
@@ -0,0 +1,75 @@
+use crate::timeouts::{read_packet_with_timeout, write_with_timeout, ConnectionTimeouts};
+use smol_db_common::db_content::DBContent;
+use smol_db_common::db_packets::packet_checksum::serialize_response;
+use smol_db_common::prelude::{
+    DBPacket, DBPacketResponseError, StreamItem, SuccessNoData, SuccessReply,
+};
+use tokio::net::TcpStream;
+use tracing::{debug, error, info};
+
+/// Sends each key and value of the table to the client as it requests them with `ReadyForNextItem`, until the client sends `EndStreamRead` or the table ends.
+/// The stream starts with a `SuccessReply` holding the number of pairs in the table, and each key and value is sent together as a single `StreamItem`.
+/// Once the table runs out, the next request from the client is answered with `StreamItem::StreamEnd`,
+/// and an `EndStreamRead` from the client is answered with a `SuccessNoData` response, so nothing more needs to be sent once this returns `Ok`.
+/// Returns `StreamClosedUnexpectedly` if the client disconnects, or sends or reads nothing within the read or write timeout,
+/// in which case the connection should be dropped, as the client is no longer following the stream.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn stream_table(
+    stream: &mut TcpStream,
+    db_table: &DBContent,
+    timeouts: ConnectionTimeouts,
+) -> Result<(), DBPacketResponseError> {
+    let starting_packet =
+        serialize_response(&Ok(SuccessReply(db_table.content.len().to_string()))).unwrap();
+    write_with_timeout(stream, starting_packet.as_bytes(), timeouts.write)
+        .await
+        .map_err(|err| {
+            error!("Error sending stream starting packet: {}", err);
+            DBPacketResponseError::StreamClosedUnexpectedly
+        })?;
+
+    let mut items = db_table
+        .content
+        .iter()
+        .map(|(key, value)| StreamItem::Item(key.clone(), value.clone()));
+
+    loop {
+        debug!("Waiting for client to await next item");
+        let packet_data = match read_packet_with_timeout(stream, timeouts.read).await {
+            Ok(packet_data) if !packet_data.is_empty() => packet_data,
+            _ => return Err(DBPacketResponseError::StreamClosedUnexpectedly),
+        };
+
+        match DBPacket::deserialize_packet(&packet_data) {
+            Ok(DBPacket::EndStreamRead) => {
+                info!("Stream ended early intentionally.");
+                let acknowledgement = serialize_response(&Ok(SuccessNoData)).unwrap();
+                return write_with_timeout(stream, acknowledgement.as_bytes(), timeouts.write)
+                    .await
+                    .map_err(|_| DBPacketResponseError::StreamClosedUnexpectedly);
+            }
+            Ok(DBPacket::ReadyForNextItem) => {}
+            Ok(_) => return Err(DBPacketResponseError::BadPacket),
+            Err(err) => return Err(err),
+        }
+
+        debug!("Client requested next item");
+
+        let item = items.next().unwrap_or(StreamItem::StreamEnd);
+        let ser = item
+            .serialize_item()
+            .map_err(|_| DBPacketResponseError::SerializationError)?;
+        write_with_timeout(stream, ser.as_bytes(), timeouts.write)
+            .await
+            .map_err(|err| {
+                error!("{}", err);
+                DBPacketResponseError::StreamClosedUnexpectedly
+            })?;
+
+        if item == StreamItem::StreamEnd {
+            info!("Wrote end of stream");
+            return Ok(());
+        }
+        info!("Wrote key value pair to stream");
+    }
+}
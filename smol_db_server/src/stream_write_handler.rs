@@ -0,0 +1,50 @@
+use crate::timeouts::read_packet_with_timeout;
+use crate::watch_handler::write_response;
+use smol_db_common::prelude::{DBPacket, DBPacketResponseError, SuccessNoData};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tracing::{debug, info, warn};
+
+/// Receives each `StreamChunk` from the client until it sends `EndStreamWrite`, returning the assembled value.
+/// Each chunk is acknowledged with `SuccessNoData`, so the chunks are never merged together when read from the socket.
+/// Returns `None` if the client aborted the write with `AbortStreamWrite` or disconnected, in which case nothing should be stored.
+#[tracing::instrument(skip(stream))]
+pub(crate) async fn receive_stream_write(
+    stream: &mut TcpStream,
+    read_timeout: Option<Duration>,
+) -> Option<Result<Vec<u8>, DBPacketResponseError>> {
+    let mut value = vec![];
+
+    loop {
+        let packet_data = match read_packet_with_timeout(stream, read_timeout).await {
+            Ok(packet_data) if !packet_data.is_empty() => packet_data,
+            _ => {
+                info!("Client disconnected during stream write");
+                return None;
+            }
+        };
+
+        match DBPacket::deserialize_packet(&packet_data) {
+            Ok(DBPacket::StreamChunk(chunk)) => {
+                debug!("Received chunk of {}", chunk);
+                value.extend_from_slice(chunk.get_data());
+                if write_response(stream, Ok(SuccessNoData)).await.is_err() {
+                    return None;
+                }
+            }
+            Ok(DBPacket::EndStreamWrite) => {
+                info!("Client finished stream write of {} bytes", value.len());
+                return Some(Ok(value));
+            }
+            Ok(DBPacket::AbortStreamWrite) => {
+                info!("Client aborted stream write");
+                let _ = write_response(stream, Ok(SuccessNoData)).await;
+                return None;
+            }
+            other => {
+                warn!("Unexpected packet during stream write: {:?}", other);
+                return Some(Err(DBPacketResponseError::BadPacket));
+            }
+        }
+    }
+}
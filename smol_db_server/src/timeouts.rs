@@ -0,0 +1,90 @@
+use smol_db_common::db_packets::packet_checksum::is_incomplete;
+use std::io;
+use std::io::ErrorKind;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tracing::warn;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// How long a client may go without sending or receiving data before its connection is dropped, `None` waits forever.
+pub(crate) struct ConnectionTimeouts {
+    /// Used while waiting for the next packet from a client in between requests
+    pub(crate) idle: Option<Duration>,
+    /// Used while waiting for a packet from a client in the middle of a stream, such as the next chunk of a stream write
+    pub(crate) read: Option<Duration>,
+    /// Used while sending an item to a client in the middle of a stream, a client that stops reading would otherwise leave the write waiting forever
+    pub(crate) write: Option<Duration>,
+}
+
+/// Reads from the stream, failing with `ErrorKind::TimedOut` if the client sent nothing before the timeout passed.
+pub(crate) async fn read_with_timeout(
+    stream: &mut TcpStream,
+    buf: &mut [u8],
+    timeout: Option<Duration>,
+) -> io::Result<usize> {
+    let Some(timeout) = timeout else {
+        return stream.read(buf).await;
+    };
+
+    match tokio::time::timeout(timeout, stream.read(buf)).await {
+        Ok(read_result) => read_result,
+        Err(_) => {
+            warn!(
+                "Client {:?} sent no data for {:?}, dropping connection",
+                stream.peer_addr(),
+                timeout
+            );
+            Err(io::Error::from(ErrorKind::TimedOut))
+        }
+    }
+}
+
+/// Reads a whole packet from the stream, which can take more than one read when the packet is larger than the read buffer.
+/// Reads until the packet is as long as the length given in its checksum header, each read failing with `ErrorKind::TimedOut` if the client sent nothing before the timeout passed.
+/// Returns an empty packet if the client disconnected before sending anything,
+/// and fails with `ErrorKind::InvalidData` as soon as the data read can not be a packet, such as a header giving a length over `MAX_PACKET_SIZE`.
+pub(crate) async fn read_packet_with_timeout(
+    stream: &mut TcpStream,
+    timeout: Option<Duration>,
+) -> io::Result<Vec<u8>> {
+    let mut packet = vec![];
+    let mut buf: [u8; 1024] = [0; 1024];
+    loop {
+        let read_len = read_with_timeout(stream, &mut buf, timeout).await?;
+        if read_len == 0 {
+            return if packet.is_empty() {
+                Ok(packet)
+            } else {
+                Err(ErrorKind::UnexpectedEof.into())
+            };
+        }
+        packet.extend_from_slice(&buf[0..read_len]);
+        if !is_incomplete(&packet).map_err(|err| io::Error::new(ErrorKind::InvalidData, err))? {
+            return Ok(packet);
+        }
+    }
+}
+
+/// Writes all the data to the stream, failing with `ErrorKind::TimedOut` if the client did not read it before the timeout passed.
+pub(crate) async fn write_with_timeout(
+    stream: &mut TcpStream,
+    data: &[u8],
+    timeout: Option<Duration>,
+) -> io::Result<()> {
+    let Some(timeout) = timeout else {
+        return stream.write_all(data).await;
+    };
+
+    match tokio::time::timeout(timeout, stream.write_all(data)).await {
+        Ok(write_result) => write_result,
+        Err(_) => {
+            warn!(
+                "Client {:?} read nothing for {:?}, dropping connection",
+                stream.peer_addr(),
+                timeout
+            );
+            Err(io::Error::from(ErrorKind::TimedOut))
+        }
+    }
+}
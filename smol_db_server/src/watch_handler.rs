@@ -0,0 +1,111 @@
+use crate::timeouts::read_packet_with_timeout;
+use serde::Serialize;
+use smol_db_common::db_packets::packet_checksum::serialize_response;
+use smol_db_common::prelude::{
+    DBPacket, DBPacketResponseError, DBSuccessResponse, SuccessNoData, SuccessReply,
+};
+use std::io::ErrorKind;
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tracing::{debug, info, warn};
+
+/// How long in milliseconds to sleep between checks for a new item
+const CHANGE_POLL_INTERVAL_MS: u64 = 50;
+
+/// Sends each item from the receiver to the client as it requests them, returning once the client ends the watch or disconnects.
+/// Used both for watching a db for changes, and for subscribing to a pub/sub channel.
+/// The client requests each event with `ReadyForNextItem`, and ends the watch with `EndStreamRead`, which is acknowledged with `SuccessNoData`.
+/// Once the sending side of the receiver is dropped, such as after every value of a list was sent, the next request is answered with `SuccessNoData` and the watch ends.
+#[tracing::instrument(skip(stream, receiver))]
+pub(crate) async fn handle_watch<T: Serialize + Send>(
+    stream: &mut TcpStream,
+    mut receiver: Receiver<T>,
+    read_timeout: Option<Duration>,
+) {
+    if write_response(stream, Ok(SuccessNoData)).await.is_err() {
+        return;
+    }
+
+    loop {
+        debug!("Waiting for client to request next change");
+        let packet_data = match read_packet_with_timeout(stream, read_timeout).await {
+            Ok(packet_data) if !packet_data.is_empty() => packet_data,
+            _ => {
+                info!("Client disconnected during watch");
+                return;
+            }
+        };
+
+        match DBPacket::deserialize_packet(&packet_data) {
+            Ok(DBPacket::ReadyForNextItem) => {}
+            Ok(DBPacket::EndStreamRead) => {
+                info!("Client ended watch");
+                // acknowledge the end of the watch, so the client knows it can send its next packet
+                let _ = write_response(stream, Ok(SuccessNoData)).await;
+                return;
+            }
+            other => {
+                warn!("Unexpected packet during watch: {:?}", other);
+                return;
+            }
+        }
+
+        let Some(item) = wait_for_item(stream, &mut receiver).await else {
+            return;
+        };
+
+        let response = serde_json::to_string(&item)
+            .map(SuccessReply)
+            .map_err(|_| DBPacketResponseError::SerializationError);
+
+        if write_response(stream, response).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// Waits for the next item, periodically checking if the client ended the watch or disconnected in the meantime.
+async fn wait_for_item<T>(stream: &mut TcpStream, receiver: &mut Receiver<T>) -> Option<T> {
+    loop {
+        match receiver.try_recv() {
+            Ok(item) => return Some(item),
+            Err(TryRecvError::Empty) => {
+                if !client_still_watching(stream) {
+                    return None;
+                }
+                tokio::time::sleep(Duration::from_millis(CHANGE_POLL_INTERVAL_MS)).await;
+            }
+            Err(TryRecvError::Disconnected) => {
+                let _ = write_response(stream, Ok(SuccessNoData)).await;
+                return None;
+            }
+        }
+    }
+}
+
+/// Returns false if the client disconnected, or sent a packet (ending the watch) while it was waiting for an item.
+fn client_still_watching(stream: &TcpStream) -> bool {
+    let mut buf: [u8; 1024] = [0; 1024];
+    match stream.try_read(&mut buf) {
+        Err(err) if err.kind() == ErrorKind::WouldBlock => true,
+        Ok(0) | Err(_) => false,
+        Ok(read_len) => {
+            debug!(
+                "Client sent packet while waiting for a change: {:?}",
+                DBPacket::deserialize_packet(&buf[0..read_len])
+            );
+            false
+        }
+    }
+}
+
+/// Writes the response to the client without encryption, used for every packet sent during a stream.
+pub(crate) async fn write_response(
+    stream: &mut TcpStream,
+    response: Result<DBSuccessResponse<String>, DBPacketResponseError>,
+) -> std::io::Result<()> {
+    let ser = serialize_response(&response).unwrap();
+    stream.write_all(ser.as_bytes()).await
+}
@@ -0,0 +1,166 @@
+//! Library for spawning a `smol_db` server on a random port with its own temporary data directory,
+//! so integration tests do not depend on a server already running on `localhost:8222`, or share a data directory.
+use smol_db_common::db_packets::packet_checksum::{deserialize_response, is_incomplete};
+use smol_db_common::prelude::{DBPacket, SuccessNoData};
+use smol_db_server::{Server, ServerConfig};
+use std::io::{Read, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream};
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+use std::thread::JoinHandle;
+use tempfile::TempDir;
+
+/// Bootstrap token of every spawned server, claimed for `SUPER_ADMIN_KEY` before the server is returned.
+pub const BOOTSTRAP_TOKEN: &str = "test_bootstrap_token";
+
+/// Access key of the super admin of every spawned server.
+pub const SUPER_ADMIN_KEY: &str = "test_key_123";
+
+/// A server running on its own thread, which is shut down and has its data directory removed when dropped.
+/// ```
+/// use smol_db_test_harness::spawn_server;
+/// use std::net::TcpStream;
+///
+/// let server = spawn_server();
+/// assert_ne!(server.socket_addr().port(), 8222);
+/// assert!(server.data_dir().exists());
+///
+/// // connect to the server with smol_db_client here, setting SUPER_ADMIN_KEY as the access key to be a super admin
+/// let _stream = TcpStream::connect(server.address()).unwrap();
+/// ```
+pub struct ServerGuard {
+    /// Taken when the guard is dropped, so the server is dropped on another thread
+    server: Option<Arc<Server>>,
+    server_thread: Option<JoinHandle<()>>,
+    socket_addr: SocketAddr,
+    address: String,
+    /// Removed when the guard is dropped, after the server has stopped
+    data_dir: TempDir,
+}
+
+impl ServerGuard {
+    /// Address the server is bound to, in the form `SmolDbClient::new` takes
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Address the server is bound to
+    pub const fn socket_addr(&self) -> SocketAddr {
+        self.socket_addr
+    }
+
+    /// Temporary directory the server saves its databases in
+    pub fn data_dir(&self) -> &Path {
+        self.data_dir.path()
+    }
+
+    /// The running server, such as to share its db list with a `LocalClient`
+    pub fn server(&self) -> &Server {
+        self.server
+            .as_ref()
+            .expect("server is only taken when dropped")
+    }
+}
+
+impl Drop for ServerGuard {
+    fn drop(&mut self) {
+        let (Some(server), Some(server_thread)) = (self.server.take(), self.server_thread.take())
+        else {
+            return;
+        };
+        server.shutdown();
+        // the server owns a tokio runtime, which can not be dropped within an async test, so it is dropped on its own thread once it stopped.
+        // a panic in the server is already reported by its thread, and must not panic again while a failed test unwinds
+        let _ = thread::spawn(move || {
+            let _ = server_thread.join();
+            drop(server);
+        })
+        .join();
+    }
+}
+
+/// Spawns a server with the default config on a random port of localhost, saving to a new temporary data directory.
+/// `SUPER_ADMIN_KEY` is the access key of its super admin.
+/// Panics if the server can not be started, as a test can not continue without it.
+pub fn spawn_server() -> ServerGuard {
+    spawn_server_with_config(ServerConfig::default())
+}
+
+/// Spawns a server with the default config like `spawn_server`, without claiming its super admin,
+/// so a test can claim it with `BOOTSTRAP_TOKEN`.
+pub fn spawn_unclaimed_server() -> ServerGuard {
+    spawn_server_with_config(ServerConfig {
+        bootstrap_token: Some(BOOTSTRAP_TOKEN.to_string()),
+        ..ServerConfig::default()
+    })
+}
+
+/// Spawns a server with the given config, replacing its address, port and data directory so it runs on a random port of localhost
+/// with a new temporary data directory. If the config has no bootstrap token, `BOOTSTRAP_TOKEN` is used and claimed for `SUPER_ADMIN_KEY`,
+/// otherwise the super admin is left for the test to claim.
+/// Panics if the server can not be started, as a test can not continue without it.
+pub fn spawn_server_with_config(config: ServerConfig) -> ServerGuard {
+    let data_dir = tempfile::Builder::new()
+        .prefix("smol_db_test_")
+        .tempdir()
+        .expect("create temporary data directory");
+
+    let claim_super_admin = config.bootstrap_token.is_none();
+    let config = ServerConfig {
+        bind_address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+        port: 0,
+        data_dir: data_dir.path().to_path_buf(),
+        bootstrap_token: config
+            .bootstrap_token
+            .or_else(|| Some(BOOTSTRAP_TOKEN.to_string())),
+        ..config
+    };
+
+    let server = Arc::new(Server::bind(config).expect("start test server"));
+    let socket_addr = server.local_addr().expect("test server address");
+
+    let server_thread = {
+        let server = server.clone();
+        thread::spawn(move || server.run())
+    };
+
+    if claim_super_admin {
+        claim_super_admin_key(socket_addr);
+    }
+
+    ServerGuard {
+        server: Some(server),
+        server_thread: Some(server_thread),
+        socket_addr,
+        address: socket_addr.to_string(),
+        data_dir,
+    }
+}
+
+/// Claims the super admin of the server for `SUPER_ADMIN_KEY`, the same way a client does with `SmolDbClient::claim_super_admin`.
+fn claim_super_admin_key(socket_addr: SocketAddr) {
+    let mut stream = TcpStream::connect(socket_addr).expect("connect to test server");
+    for packet in [
+        DBPacket::new_set_key(SUPER_ADMIN_KEY.to_string()),
+        DBPacket::new_claim_super_admin(BOOTSTRAP_TOKEN),
+    ] {
+        let ser = packet.serialize_packet().expect("serialize packet");
+        stream
+            .write_all(ser.as_bytes())
+            .expect("send packet to test server");
+
+        let mut response = vec![];
+        let mut buf = [0; 1024];
+        while response.is_empty() || is_incomplete(&response).expect("valid response") {
+            let read_len = stream.read(&mut buf).expect("read test server response");
+            assert_ne!(read_len, 0, "test server disconnected");
+            response.extend_from_slice(&buf[..read_len]);
+        }
+        assert_eq!(
+            deserialize_response(&response).expect("deserialize response"),
+            Ok(SuccessNoData),
+            "claim super admin of test server"
+        );
+    }
+}
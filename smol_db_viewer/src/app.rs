@@ -1,57 +1,93 @@
+use crate::confirmation::Confirmation;
+use crate::content_table::ContentTable;
+use crate::copy_dialog::{CopyAction, CopyDialog};
+use crate::list_browser::{ListAction, ListBrowser};
+use crate::members_panel::{MemberAction, MembersPanel};
+use crate::profiles::{save_profile, ConnectionProfile};
+use crate::statistics_plots::{statistics_plots, TimeWindow};
+use crate::transfer_dialog::{TransferAction, TransferDialog};
+use crate::value_editor::{EditorAction, ValueEditor};
+use crate::worker::{ClientWorker, LoadedDB, Request, Response};
 use crate::{
-    app::ContentCacheState::{Cached, NotCached},
+    app::ContentCacheState::{Cached, Loading, NotCached},
     app::ProgramState::ChangeDBSettings,
     app::ProgramState::ClientConnectionError,
     app::ProgramState::CreateDB,
     app::ProgramState::DBResponseError,
     app::ProgramState::DisplayClient,
+    app::ProgramState::ManageMembers,
     app::ProgramState::NoClient,
     app::ProgramState::PromptForClientDetails,
     app::ProgramState::PromptForKey,
 };
 use chrono::{DateTime, Datelike, Local, Timelike};
 use egui::ViewportCommand;
-use smol_db_client::prelude::SmolDbClient;
+use smol_db_client::prelude::{Permission, Permissions};
 use smol_db_client::{
     client_error::ClientError, client_error::ClientError::BadPacket, db_settings::DBSettings,
     prelude::DBStatistics, DBPacketResponseError, DBSuccessResponse, Role,
 };
-use std::collections::HashMap;
-use std::ops::{Deref, DerefMut};
-use std::sync::{Arc, Mutex};
-use std::thread;
-use std::thread::JoinHandle;
-use std::time::Duration;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
 #[derive(serde::Deserialize, serde::Serialize)]
 #[serde(default)]
 pub struct ApplicationState {
+    /// Makes the requests of the client on a background thread, none while there is no client
     #[serde(skip)]
-    client: Arc<Mutex<Option<SmolDbClient>>>,
+    worker: Option<ClientWorker>,
     #[serde(skip)]
-    program_state: Arc<Mutex<ProgramState>>,
+    program_state: ProgramState,
 
-    ip_address: String,
+    /// The profile being connected with
+    profile: ConnectionProfile,
+
+    /// Profiles saved by name, which can be switched between
+    saved_profiles: Vec<ConnectionProfile>,
 
     #[serde(skip)]
     database_list: Option<Vec<DBCached>>,
 
-    client_key: String,
+    /// True while the db list has been requested from the worker, but has not been received yet
+    #[serde(skip)]
+    awaiting_db_list: bool,
+
+    /// True once end to end encryption has been requested, until it fails or the client disconnects
+    #[serde(skip)]
+    encryption_enabled: bool,
+
+    /// The fingerprint of the key the server presented, once the client is encrypted
+    #[serde(skip)]
+    server_fingerprint: Option<String>,
 
     #[serde(skip)]
     selected_database: Option<usize>,
 
     #[serde(skip)]
-    connection_thread: Option<JoinHandle<()>>,
+    content_table: ContentTable,
 
+    /// The entry of the selected db being edited, if any
     #[serde(skip)]
-    key_input: String,
+    value_editor: Option<ValueEditor>,
 
+    /// Where the checked entries of the selected db are copied to, if the copy dialog is open
     #[serde(skip)]
-    value_input: String,
+    copy_dialog: Option<CopyDialog>,
 
+    /// The db and file being exported or imported, if the transfer dialog is open
     #[serde(skip)]
-    desired_action: DesiredAction,
+    transfer_dialog: Option<TransferDialog>,
+
+    /// Whether the key value pairs or the keyed lists of the selected db are shown
+    #[serde(skip)]
+    content_view: ContentView,
+
+    /// The keyed list of the selected db being browsed, if any
+    #[serde(skip)]
+    list_browser: Option<ListBrowser>,
+
+    #[serde(skip)]
+    members_panel: MembersPanel,
 
     #[serde(skip)]
     submit_db_settings: DBSettings,
@@ -68,40 +104,109 @@ pub struct ApplicationState {
     #[serde(skip)]
     db_name_create: String,
 
-    auto_connect: bool,
+    /// The creation or deletion of a db waiting on the user to confirm it, if any
+    #[serde(skip)]
+    confirmation: Option<Confirmation>,
+
+    /// The db that was last deleted and when, so the deletion can be undone for a while after
+    #[serde(skip)]
+    undo_delete: Option<(String, Instant)>,
+
+    /// The span of time the request rate of the selected db is plotted over
+    stats_window: TimeWindow,
+
+    /// Read the content of the selected db again every `auto_refresh_seconds`
+    auto_refresh: bool,
 
-    auto_set_key: bool,
+    auto_refresh_seconds: u64,
+
+    /// When the content of the selected db was last read, none before it has been read
+    #[serde(skip)]
+    last_refresh: Option<Instant>,
 }
 
+/// How long a deleted db can be restored from the undo toast, the db stays in the trash of the server after that
+const UNDO_DELETE_TIME: Duration = Duration::from_secs(10);
+
 #[derive(Debug)]
 enum ContentCacheState<T> {
     NotCached,
+    /// Requested from the worker, but not received yet
+    Loading,
     Cached(T),
     Error(ClientError),
 }
 
-#[derive(Debug)]
-enum DesiredAction {
-    Write,
-    Delete,
-}
-
-impl DesiredAction {
-    const fn as_text(&self) -> &str {
-        match self {
-            Self::Write => "Write",
-            Self::Delete => "Delete",
+impl<T> From<Result<T, ClientError>> for ContentCacheState<T> {
+    fn from(result: Result<T, ClientError>) -> Self {
+        match result {
+            Ok(data) => Cached(data),
+            Err(err) => Self::Error(err),
         }
     }
 }
 
+/// The content of the selected db that is shown in the center panel
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum ContentView {
+    #[default]
+    Values,
+    Lists,
+}
+
 #[derive(Debug)]
 struct DBCached {
     name: String,
     content: ContentCacheState<HashMap<String, String>>,
+    /// The names of the keyed lists of the db
+    lists: ContentCacheState<Vec<String>>,
     role: ContentCacheState<Role>,
     db_settings: ContentCacheState<DBSettings>,
     statistics: ContentCacheState<DBStatistics>,
+    /// Keys that were added or whose value changed when the content was last refreshed
+    changed_keys: HashSet<String>,
+}
+
+impl DBCached {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            content: NotCached,
+            lists: NotCached,
+            role: NotCached,
+            db_settings: NotCached,
+            statistics: NotCached,
+            changed_keys: HashSet::new(),
+        }
+    }
+
+    /// Returns true if any of the information of the db has not been requested yet
+    const fn needs_loading(&self) -> bool {
+        matches!(self.content, NotCached)
+            || matches!(self.lists, NotCached)
+            || matches!(self.role, NotCached)
+            || matches!(self.db_settings, NotCached)
+            || matches!(self.statistics, NotCached)
+    }
+
+    /// Marks the information that has not been requested yet as loading
+    fn mark_loading(&mut self) {
+        if matches!(self.content, NotCached) {
+            self.content = Loading;
+        }
+        if matches!(self.lists, NotCached) {
+            self.lists = Loading;
+        }
+        if matches!(self.role, NotCached) {
+            self.role = Loading;
+        }
+        if matches!(self.db_settings, NotCached) {
+            self.db_settings = Loading;
+        }
+        if matches!(self.statistics, NotCached) {
+            self.statistics = Loading;
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -113,6 +218,7 @@ enum ProgramState {
     DBResponseError(DBPacketResponseError),
     PromptForKey,
     ChangeDBSettings,
+    ManageMembers,
     CreateDB,
     DisplayClient,
 }
@@ -120,23 +226,33 @@ enum ProgramState {
 impl Default for ApplicationState {
     fn default() -> Self {
         Self {
-            client: Arc::new(Mutex::new(None)),
-            program_state: Arc::new(Mutex::new(NoClient)),
-            ip_address: "".to_string(),
+            worker: None,
+            program_state: NoClient,
+            profile: ConnectionProfile::default(),
+            saved_profiles: vec![],
             database_list: None,
-            client_key: "".to_string(),
+            awaiting_db_list: false,
+            encryption_enabled: false,
+            server_fingerprint: None,
             selected_database: None,
-            connection_thread: None,
-            key_input: "".to_string(),
-            value_input: "".to_string(),
-            desired_action: DesiredAction::Write,
+            content_table: ContentTable::default(),
+            value_editor: None,
+            copy_dialog: None,
+            transfer_dialog: None,
+            content_view: ContentView::default(),
+            list_browser: None,
+            members_panel: MembersPanel::default(),
             submit_db_settings: DBSettings::default(),
             duration_seconds: 30,
             users_list: "".to_string(),
             admins_list: "".to_string(),
             db_name_create: "".to_string(),
-            auto_connect: false,
-            auto_set_key: false,
+            confirmation: None,
+            undo_delete: None,
+            stats_window: TimeWindow::default(),
+            auto_refresh: false,
+            auto_refresh_seconds: 5,
+            last_refresh: None,
         }
     }
 }
@@ -148,62 +264,10 @@ impl ApplicationState {
             let mut loaded_state: Self =
                 eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
 
-            if loaded_state.auto_connect && !loaded_state.ip_address.is_empty() {
-                let client_clone = Arc::clone(&loaded_state.client);
-                let program_state_clone = Arc::clone(&loaded_state.program_state);
-                let ip_clone = loaded_state.ip_address.clone();
-                let set_key_clone = loaded_state.auto_set_key;
-                let key_set_clone = loaded_state.client_key.clone();
-                loaded_state.connection_thread = Some(thread::spawn(move || {
-                    // instantly move all the variables into the thread
-                    let set_key = set_key_clone;
-                    let ps = program_state_clone;
-                    let client_mutex = client_clone;
-                    let ip = ip_clone;
-                    let key = key_set_clone;
-
-                    match SmolDbClient::new(&ip) {
-                        // connect the client to the server.
-                        Ok(mut client_connection) => {
-                            if set_key && !key.is_empty() {
-                                // if the auto set key flag is true, and the users key is not empty
-                                // attempt to set the clients key
-                                match client_connection.set_access_key(key) {
-                                    Ok(set_key_resp) => {
-                                        match set_key_resp {
-                                            DBSuccessResponse::SuccessNoData => {
-                                                // if the client set key was successful, then display the client to the user and pass the client connection to the program
-                                                *client_mutex.lock().unwrap() =
-                                                    Some(client_connection);
-                                                *ps.lock().unwrap() = DisplayClient;
-                                            }
-                                            DBSuccessResponse::SuccessReply(_) => {
-                                                // the set access key function for the client should never reply with data, if it did, then the packet sent was bad in some way.
-                                                *ps.lock().unwrap() =
-                                                    ClientConnectionError(BadPacket);
-                                            }
-                                        }
-                                    }
-                                    Err(err) => {
-                                        // if we are unable to set the access key due to a client error,
-                                        // we do not pass the client to the program, and we display the connection error.
-                                        *ps.lock().unwrap() = ClientConnectionError(err);
-                                    }
-                                }
-                            } else {
-                                // this else case is for when the user does not have auto connect on.
-                                // if client connection successful, move the client to the programs state.
-                                *client_mutex.lock().unwrap() = Some(client_connection);
-                                // change the program state into a DisplayClient state.
-                                *ps.lock().unwrap() = DisplayClient;
-                            }
-                        }
-                        Err(err) => {
-                            // if the client connection fails, change the program state accordingly.
-                            *ps.lock().unwrap() = ClientConnectionError(err);
-                        }
-                    }
-                }));
+            if loaded_state.profile.auto_connect && !loaded_state.profile.ip_address.is_empty() {
+                // only set the key on connect if the auto set key flag is true, and the users key is not empty
+                let key = loaded_state.profile.startup_key();
+                loaded_state.connect(&cc.egui_ctx, key);
             }
 
             return loaded_state;
@@ -211,18 +275,563 @@ impl ApplicationState {
 
         Self::default()
     }
+
+    /// Starts connecting to the ip address of the profile on a new worker, dropping the client that was connected before
+    fn connect(&mut self, ctx: &egui::Context, key: Option<String>) {
+        self.disconnect();
+        // the worker sets up encryption before anything else, the switch is shown again if that fails
+        self.encryption_enabled = self.profile.use_encryption;
+        self.worker = Some(ClientWorker::connect(
+            self.profile.ip_address.clone(),
+            key,
+            self.profile.use_encryption,
+            ctx.clone(),
+        ));
+    }
+
+    /// Drops the worker, which disconnects its client, and everything that was cached from it
+    fn disconnect(&mut self) {
+        self.worker = None;
+        self.database_list = None;
+        self.awaiting_db_list = false;
+        self.encryption_enabled = false;
+        self.server_fingerprint = None;
+        self.selected_database = None;
+        self.value_editor = None;
+        self.copy_dialog = None;
+        self.transfer_dialog = None;
+        self.list_browser = None;
+        self.members_panel.reset();
+        self.confirmation = None;
+        self.undo_delete = None;
+    }
+
+    /// Queues a request on the worker, if there is one
+    fn send(&mut self, request: Request) {
+        if let Some(worker) = &mut self.worker {
+            worker.send(request);
+        }
+    }
+
+    /// Returns true while the worker has requests that have not been answered yet
+    fn is_busy(&self) -> bool {
+        self.worker.as_ref().is_some_and(ClientWorker::is_busy)
+    }
+
+    fn cached_db_mut(&mut self, name: &str) -> Option<&mut DBCached> {
+        self.database_list
+            .as_mut()?
+            .iter_mut()
+            .find(|db| db.name == name)
+    }
+
+    fn is_selected(&self, name: &str) -> bool {
+        self.selected_database
+            .and_then(|index| self.database_list.as_ref()?.get(index))
+            .is_some_and(|db| db.name == name)
+    }
+
+    /// Selects the db at the index, requesting whatever of it has not been requested yet
+    fn select_db(&mut self, index: usize) {
+        let Some(db) = self
+            .database_list
+            .as_mut()
+            .and_then(|list| list.get_mut(index))
+        else {
+            return;
+        };
+
+        if db.needs_loading() {
+            db.mark_loading();
+            let name = db.name.clone();
+            self.send(Request::LoadDB(name));
+        } else if let Cached(settings) = &db.db_settings {
+            let settings = settings.clone();
+            self.set_settings_inputs(&settings);
+        }
+
+        // set the selected database number in the program state.
+        self.selected_database = Some(index);
+        self.last_refresh = Some(Instant::now());
+        self.content_table.reset();
+        self.value_editor = None;
+        self.copy_dialog = None;
+        self.list_browser = None;
+        self.members_panel.reset();
+    }
+
+    /// Fills the settings inputs with the given settings, so they can be edited and submitted
+    fn set_settings_inputs(&mut self, settings: &DBSettings) {
+        self.users_list = settings.users.join(",");
+        self.admins_list = settings.admins.join(",");
+        self.duration_seconds = settings.invalidation_time.as_secs();
+        self.submit_db_settings = settings.clone();
+    }
+
+    fn selected_db_name(&self) -> Option<String> {
+        self.selected_database
+            .and_then(|index| self.database_list.as_ref()?.get(index))
+            .map(|db| db.name.clone())
+    }
+
+    /// Sends the write or delete chosen in the value editor to the worker, and closes the editor
+    fn handle_editor_action(&mut self, action: EditorAction) {
+        let Some(db_name) = self.selected_db_name() else {
+            return;
+        };
+
+        match action {
+            EditorAction::Save { key, value } => {
+                self.send(Request::WriteData {
+                    db_name,
+                    key,
+                    value,
+                });
+            }
+            EditorAction::Delete(key) => {
+                self.send(Request::DeleteData { db_name, key });
+            }
+            EditorAction::Close => {}
+        }
+        self.value_editor = None;
+    }
+
+    /// Opens the list of the selected db in the list browser, requesting its items
+    fn open_list(&mut self, list_name: &str) {
+        let Some(db_name) = self.selected_db_name() else {
+            return;
+        };
+        self.list_browser = Some(ListBrowser::open(list_name));
+        self.send(Request::LoadList {
+            db_name,
+            list_name: list_name.to_string(),
+        });
+    }
+
+    /// Sends the change chosen in the list browser to the worker, the browser stays open to show the list after the change
+    fn handle_list_action(&mut self, action: ListAction) {
+        let Some(db_name) = self.selected_db_name() else {
+            return;
+        };
+
+        match action {
+            ListAction::Add {
+                list_name,
+                value,
+                index,
+            } => {
+                self.send(Request::AddToList {
+                    db_name,
+                    list_name,
+                    value,
+                    index,
+                });
+            }
+            ListAction::Remove { list_name, index } => {
+                self.send(Request::RemoveFromList {
+                    db_name,
+                    list_name,
+                    index,
+                });
+            }
+            ListAction::Refresh(list_name) => {
+                self.send(Request::LoadList { db_name, list_name });
+            }
+            ListAction::Close => {
+                self.list_browser = None;
+            }
+        }
+    }
+
+    /// Sends the copy of the checked entries chosen in the copy dialog to the worker, the dialog stays open to show the result
+    fn handle_copy_action(&mut self, action: CopyAction) {
+        let Some(db_name) = self.selected_db_name() else {
+            return;
+        };
+
+        match action {
+            CopyAction::Copy {
+                destination,
+                db_name: destination_db,
+            } => {
+                let keys = self.content_table.checked_keys().iter().cloned().collect();
+                self.send(Request::CopyEntries {
+                    db_name,
+                    keys,
+                    destination,
+                    destination_db,
+                });
+            }
+            CopyAction::Close => {
+                self.copy_dialog = None;
+            }
+        }
+    }
+
+    /// Sends the export or import chosen in the transfer dialog to the worker, the dialog stays open to show the result
+    fn handle_transfer_action(&mut self, action: TransferAction) {
+        match action {
+            TransferAction::Export {
+                db_name,
+                format,
+                path,
+            } => {
+                self.send(Request::ExportDB {
+                    db_name,
+                    format,
+                    path,
+                });
+            }
+            TransferAction::Import {
+                db_name,
+                format,
+                path,
+            } => {
+                self.send(Request::ImportDB {
+                    db_name,
+                    format,
+                    path,
+                });
+            }
+            TransferAction::Close => {
+                self.transfer_dialog = None;
+            }
+        }
+    }
+
+    /// Sends the member change chosen in the members panel to the worker
+    fn handle_member_action(&mut self, action: MemberAction) {
+        let Some(db_name) = self.selected_db_name() else {
+            return;
+        };
+
+        match action {
+            MemberAction::Add(role, key) => {
+                self.send(Request::AddMember { db_name, role, key });
+            }
+            MemberAction::Remove(role, key) => {
+                self.send(Request::RemoveMember { db_name, role, key });
+            }
+        }
+    }
+
+    /// Shows the items of the list if it is the list being browsed in the selected db
+    fn set_browsed_list_items(
+        &mut self,
+        db_name: &str,
+        list_name: &str,
+        items: Result<Vec<String>, ClientError>,
+    ) {
+        if !self.is_selected(db_name) {
+            return;
+        }
+        if let Some(browser) = &mut self.list_browser {
+            if browser.list_name() == list_name {
+                browser.set_items(items);
+            }
+        }
+    }
+
+    /// Requests the content of the selected db again once the refresh interval has passed since it was last read,
+    /// and schedules a repaint for when the next refresh is due, so it happens without any input
+    fn auto_refresh(&mut self, ctx: &egui::Context) {
+        if !self.auto_refresh || !matches!(self.program_state, DisplayClient) {
+            return;
+        }
+        let Some(db) = self
+            .selected_database
+            .and_then(|index| self.database_list.as_ref()?.get(index))
+        else {
+            return;
+        };
+        // a db that is still loading is refreshed once it has been read
+        if !matches!(db.content, Cached(_)) {
+            return;
+        }
+
+        let interval = Duration::from_secs(self.auto_refresh_seconds);
+        let elapsed = self.last_refresh.map_or(interval, |last| last.elapsed());
+        // requests are not queued behind ones the worker has not answered yet, so a slow server is not flooded
+        if elapsed >= interval && !self.is_busy() {
+            let name = db.name.clone();
+            self.send(Request::RefreshDB(name));
+            self.last_refresh = Some(Instant::now());
+            ctx.request_repaint_after(interval);
+        } else {
+            ctx.request_repaint_after(interval.saturating_sub(elapsed));
+        }
+    }
+
+    /// Applies every response the worker has ready
+    fn handle_responses(&mut self) {
+        while let Some(response) = self.worker.as_mut().and_then(ClientWorker::try_recv) {
+            self.handle_response(response);
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn handle_response(&mut self, response: Response) {
+        match response {
+            Response::Connected(result) => match result {
+                Ok(()) => {
+                    self.program_state = DisplayClient;
+                }
+                Err(err) => {
+                    // if the client connection fails, the worker has stopped, change the program state accordingly.
+                    self.worker = None;
+                    self.program_state = ClientConnectionError(err);
+                }
+            },
+            Response::KeySet(result) => match result {
+                Ok(DBSuccessResponse::SuccessNoData) => {
+                    self.program_state = DisplayClient;
+                }
+                Ok(DBSuccessResponse::SuccessReply(_) | DBSuccessResponse::SuccessData(_)) => {
+                    // the set access key function for the client should never reply with data, if it did, then the packet sent was bad in some way.
+                    self.program_state = ClientConnectionError(BadPacket);
+                }
+                Err(err) => {
+                    self.program_state = ClientConnectionError(err);
+                }
+            },
+            Response::DBList(result) => {
+                self.awaiting_db_list = false;
+                match result {
+                    Ok(list) => {
+                        self.database_list = Some(
+                            list.iter()
+                                .map(|db_packet| DBCached::new(db_packet.get_db_name().to_string()))
+                                .collect(),
+                        );
+                    }
+                    Err(err) => {
+                        self.program_state = ClientConnectionError(err);
+                    }
+                }
+            }
+            Response::DBLoaded(loaded) => {
+                let LoadedDB {
+                    name,
+                    content,
+                    lists,
+                    role,
+                    db_settings,
+                    statistics,
+                } = *loaded;
+
+                if let Ok(settings) = &db_settings {
+                    if self.is_selected(&name) {
+                        self.set_settings_inputs(settings);
+                    }
+                }
+
+                if let Some(db) = self.cached_db_mut(&name) {
+                    db.content = content.into();
+                    db.lists = lists.into();
+                    db.role = role.into();
+                    db.db_settings = db_settings.into();
+                    db.statistics = statistics.into();
+                }
+            }
+            Response::DBRefreshed {
+                db_name,
+                content,
+                statistics,
+            } => {
+                if let Some(db) = self.cached_db_mut(&db_name) {
+                    if let (Cached(previous), Ok(content)) = (&db.content, &content) {
+                        db.changed_keys = content
+                            .iter()
+                            .filter(|(key, value)| previous.get(*key) != Some(*value))
+                            .map(|(key, _)| key.clone())
+                            .collect();
+                    }
+                    db.content = content.into();
+                    db.statistics = statistics.into();
+                }
+            }
+            Response::ContentChanged {
+                db_name,
+                change,
+                content,
+            } => {
+                if let Err(err) = change {
+                    self.program_state = ClientConnectionError(err);
+                }
+                match content {
+                    Ok(data) => {
+                        if let Some(db) = self.cached_db_mut(&db_name) {
+                            db.content = Cached(data);
+                        }
+                    }
+                    Err(err) => {
+                        self.program_state = ClientConnectionError(err);
+                    }
+                }
+            }
+            Response::ListLoaded {
+                db_name,
+                list_name,
+                items,
+            } => {
+                self.set_browsed_list_items(&db_name, &list_name, items);
+            }
+            Response::ListChanged {
+                db_name,
+                list_name,
+                change,
+                lists,
+                items,
+            } => {
+                if let Err(err) = change {
+                    self.program_state = ClientConnectionError(err);
+                }
+                if let Some(db) = self.cached_db_mut(&db_name) {
+                    db.lists = lists.into();
+                }
+                self.set_browsed_list_items(&db_name, &list_name, items);
+            }
+            Response::DBSettingsSet {
+                db_name,
+                settings,
+                result,
+            } => {
+                if let Some(db) = self.cached_db_mut(&db_name) {
+                    db.db_settings = result.map(|_| settings).into();
+                }
+            }
+            Response::MembersChanged {
+                db_name,
+                change,
+                db_settings,
+            } => {
+                if self.is_selected(&db_name) {
+                    self.members_panel.set_result(change);
+                    if let Ok(settings) = &db_settings {
+                        self.set_settings_inputs(settings);
+                    }
+                }
+                if let Some(db) = self.cached_db_mut(&db_name) {
+                    db.db_settings = db_settings.into();
+                }
+            }
+            Response::EntriesCopied {
+                destination_db,
+                same_server,
+                result,
+            } => {
+                if same_server && result.is_ok() {
+                    // the copied entries are read again the next time the db is shown
+                    let selected = self.is_selected(&destination_db);
+                    if let Some(db) = self.cached_db_mut(&destination_db) {
+                        db.content = NotCached;
+                        if selected {
+                            db.mark_loading();
+                        }
+                    }
+                    if selected {
+                        self.send(Request::LoadDB(destination_db.clone()));
+                    }
+                }
+                if let Some(dialog) = &mut self.copy_dialog {
+                    dialog.set_result(result);
+                }
+            }
+            Response::DBExported { format, result } => {
+                if let Some(dialog) = &mut self.transfer_dialog {
+                    dialog.set_export_result(format, result);
+                }
+            }
+            Response::DBImported(result) => {
+                if let Ok(db_name) = &result {
+                    // the imported entries are read again the next time the db is shown, a new db is added to the list
+                    let selected = self.is_selected(db_name);
+                    match self.cached_db_mut(db_name) {
+                        Some(db) => {
+                            db.content = NotCached;
+                            if selected {
+                                db.mark_loading();
+                            }
+                        }
+                        None => {
+                            if let Some(list) = &mut self.database_list {
+                                list.push(DBCached::new(db_name.clone()));
+                            }
+                        }
+                    }
+                    if selected {
+                        self.send(Request::LoadDB(db_name.clone()));
+                    }
+                }
+                if let Some(dialog) = &mut self.transfer_dialog {
+                    dialog.set_import_result(result);
+                }
+            }
+            Response::DBCreated { db_name, content } => match content {
+                Ok(content) => {
+                    // after creating a db go back to displaying the client
+                    self.program_state = DisplayClient;
+                    if let Some(list) = &mut self.database_list {
+                        let mut db = DBCached::new(db_name);
+                        db.content = Cached(content);
+                        db.lists = Cached(vec![]);
+                        list.push(db);
+                    }
+                }
+                Err(err) => {
+                    self.program_state = ClientConnectionError(err);
+                }
+            },
+            Response::DBDeleted { db_name, result } => match result {
+                Ok(DBSuccessResponse::SuccessNoData) => {
+                    if let Some(list) = &mut self.database_list {
+                        list.retain(|db| db.name != db_name);
+                    }
+                    // the indices of the list have shifted
+                    self.selected_database = None;
+                    self.undo_delete = Some((db_name, Instant::now()));
+                }
+                Ok(DBSuccessResponse::SuccessReply(_) | DBSuccessResponse::SuccessData(_)) => {
+                    self.program_state = ClientConnectionError(BadPacket);
+                }
+                Err(err) => {
+                    self.program_state = ClientConnectionError(err);
+                }
+            },
+            Response::DBRestored { db_name, result } => match result {
+                Ok(_) => {
+                    if let Some(list) = &mut self.database_list {
+                        list.push(DBCached::new(db_name));
+                    }
+                }
+                Err(err) => {
+                    self.program_state = ClientConnectionError(err);
+                }
+            },
+            Response::EncryptionSetup(result) => match result {
+                Ok(fingerprint) => {
+                    self.server_fingerprint = Some(fingerprint);
+                }
+                Err(err) => {
+                    self.encryption_enabled = false;
+                    self.program_state = ClientConnectionError(err);
+                }
+            },
+        }
+    }
 }
 
 impl eframe::App for ApplicationState {
     #[tracing::instrument(skip_all)]
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        let Self { .. } = self;
+        self.handle_responses();
+        self.auto_refresh(ctx);
 
         // top panel block
         {
             egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
                 egui::menu::bar(ui, |ui| {
-                    let has_client = self.client.lock().unwrap().is_some();
+                    let has_client = self.worker.is_some();
                     ui.menu_button("File", |ui| {
                         if ui.button("Quit").clicked() {
                             ctx.send_viewport_cmd(ViewportCommand::Close);
@@ -231,75 +840,89 @@ impl eframe::App for ApplicationState {
                     ui.separator();
                     ui.menu_button("Client", |ui| {
                         if ui.button("Connect").clicked() {
-                            *self.program_state.lock().unwrap().deref_mut() =
-                                PromptForClientDetails;
+                            self.program_state = PromptForClientDetails;
+                        }
+                        if !self.saved_profiles.is_empty() {
+                            let mut switch_to = None;
+                            ui.menu_button("Switch server", |ui| {
+                                for profile in &self.saved_profiles {
+                                    if ui
+                                        .button(profile.name.as_str())
+                                        .on_hover_text(profile.ip_address.as_str())
+                                        .clicked()
+                                    {
+                                        switch_to = Some(profile.clone());
+                                        ui.close_menu();
+                                    }
+                                }
+                            });
+                            if let Some(profile) = switch_to {
+                                self.profile = profile;
+                                let key = self.profile.key();
+                                self.connect(ctx, key);
+                                self.program_state = NoClient;
+                            }
                         }
                         if has_client {
                             ui.separator();
                             if ui.button("Disconnect").clicked() {
-                                let mut lock = self.program_state.lock().unwrap();
-                                match self.client.lock().unwrap().as_ref() {
-                                    None => {}
-                                    Some(cl) => {
-                                        let _ = cl.disconnect();
-                                    }
-                                }
-                                *lock = NoClient;
-                                self.database_list = None;
-                                *self.client.lock().unwrap() = None;
+                                self.disconnect();
+                                self.program_state = NoClient;
                             }
                             ui.separator();
                             if ui.button("Set key").clicked() {
-                                let mut lock = self.program_state.lock().unwrap();
-                                match *lock {
+                                match self.program_state {
                                     NoClient => {}
                                     PromptForClientDetails => {}
                                     ClientConnectionError(_) => {}
                                     PromptForKey => {}
                                     DisplayClient => {
-                                        *lock = PromptForKey;
+                                        self.program_state = PromptForKey;
                                     }
                                     ChangeDBSettings => {
-                                        *lock = PromptForKey;
+                                        self.program_state = PromptForKey;
+                                    }
+                                    ManageMembers => {
+                                        self.program_state = PromptForKey;
                                     }
                                     CreateDB => {
-                                        *lock = PromptForKey;
+                                        self.program_state = PromptForKey;
                                     }
                                     DBResponseError(_) => {}
                                 }
                             }
                             ui.separator();
                             if ui.button("DB Settings").clicked() {
-                                *self.program_state.lock().unwrap() = ChangeDBSettings;
+                                self.program_state = ChangeDBSettings;
+                            }
+                            ui.separator();
+                            if ui.button("Members").clicked() {
+                                self.program_state = ManageMembers;
                             }
                             ui.separator();
                             if ui.button("Create DB").clicked() {
-                                *self.program_state.lock().unwrap() = CreateDB;
+                                self.program_state = CreateDB;
+                            }
+                            ui.separator();
+                            if ui.button("Export / Import").clicked() {
+                                self.transfer_dialog =
+                                    Some(TransferDialog::new(self.selected_db_name()));
+                                self.program_state = DisplayClient;
                             }
                         }
                         ui.separator();
                         if ui.button("Refresh stored data").clicked() {
-                            *self.client.lock().unwrap() = None;
-                            *self.program_state.lock().unwrap() = NoClient;
-                            self.database_list = None;
-                            self.selected_database = None;
-                            self.connection_thread = None;
+                            self.disconnect();
+                            self.program_state = NoClient;
                         }
 
-                        let mut client_lock = self.client.lock().unwrap();
-                        if let Some(client) = client_lock.as_mut() {
-                            // only display an encryption switch if the user does not have encryption enabled and the button is clicked
-                            if !client.is_encryption_enabled() {
-                                ui.separator();
-                                if ui.button("Switch to end to end encryption").clicked() {
-                                    match client.setup_encryption() {
-                                        Ok(_) => {}
-                                        Err(err) => {
-                                            *self.program_state.lock().unwrap() =
-                                                ClientConnectionError(err);
-                                        }
-                                    }
-                                }
+                        // only display an encryption switch if the user does not have encryption enabled and the button is clicked
+                        if has_client && !self.encryption_enabled {
+                            ui.separator();
+                            if ui.button("Switch to end to end encryption").clicked() {
+                                // hide the switch while the worker sets up encryption, it is shown again if that fails
+                                self.encryption_enabled = true;
+                                self.send(Request::SetupEncryption);
                             }
                         }
                     });
@@ -309,124 +932,202 @@ impl eframe::App for ApplicationState {
                             self.database_list = None;
                             self.selected_database = None;
                         }
+                        ui.checkbox(&mut self.auto_refresh, "Auto refresh every")
+                            .on_hover_text(
+                                "Reads the selected db again, coloring the keys that changed",
+                            );
+                        ui.add(
+                            egui::DragValue::new(&mut self.auto_refresh_seconds)
+                                .clamp_range(1..=3600)
+                                .suffix("s"),
+                        );
+                        ui.separator();
+                        match (&self.server_fingerprint, self.encryption_enabled) {
+                            (Some(fingerprint), _) => {
+                                // the start of the fingerprint is enough to tell servers apart at a glance
+                                ui.colored_label(egui::Color32::GREEN, "Encrypted")
+                                    .on_hover_text(format!(
+                                        "Server key fingerprint:\n{}",
+                                        fingerprint
+                                    ));
+                                ui.monospace(fingerprint.get(..16).unwrap_or(fingerprint));
+                            }
+                            (None, true) => {
+                                ui.label("Encrypting...");
+                            }
+                            (None, false) => {
+                                ui.colored_label(ui.visuals().warn_fg_color, "Not encrypted")
+                                    .on_hover_text(
+                                        "Requests, including the key, are sent as plain text",
+                                    );
+                            }
+                        }
                     }
                     ui.separator();
+                    if self.is_busy() {
+                        ui.spinner();
+                        ui.label("Working...");
+                    }
                 });
             });
         }
 
         // bottom panel block
         {
-            let mut lock = self.program_state.lock().unwrap();
-            match *lock {
+            match self.program_state {
                 NoClient => {}
                 PromptForClientDetails => {}
                 ClientConnectionError(_) => {}
                 PromptForKey => {}
                 DisplayClient => {
-                    if self.selected_database.is_some() && self.database_list.is_some() {
-                        egui::TopBottomPanel::bottom("side_panel2").show(ctx, |ui| {
+                    if self.selected_database.is_some()
+                        && self.database_list.is_some()
+                        && self.content_view == ContentView::Values
+                    {
+                        egui::TopBottomPanel::bottom("side_panel2").show(ctx, |ui| match &mut self
+                            .value_editor
+                        {
+                            None => {
                                 ui.horizontal(|ui| {
-                                    ui.label("Input:");
-
-                                    ui.add_sized([160.0, 20.0], egui::TextEdit::singleline(&mut self.key_input));
-                                    ui.add_sized([160.0, 20.0], egui::TextEdit::singleline(&mut self.value_input));
-                                    if ui.button("Submit").clicked() {
-                                        match self.selected_database {
-                                            None => {}
-                                            Some(index) => match &mut self.database_list {
-                                                None => {}
-                                                Some(list) => match list.get_mut(index) {
-                                                    None => {}
-                                                    Some(db) => {
-                                                        let mut client_lock = self.client.lock().unwrap();
-                                                        match *client_lock {
-                                                            None => {}
-                                                            Some(ref mut client) => {
-                                                                match self.desired_action {
-                                                                    DesiredAction::Write => {
-                                                                        match client.write_db(
-                                                                            db.name.as_str(),
-                                                                            self.key_input.as_str(),
-                                                                            self.value_input.as_str(),
-                                                                        ) {
-                                                                            Ok(response) => {
-                                                                                match response {
-                                                                                    DBSuccessResponse::SuccessNoData => {}
-                                                                                    DBSuccessResponse::SuccessReply(_) => {}
-                                                                                }
-                                                                            }
-                                                                            Err(err) => {
-                                                                                *lock = ClientConnectionError(err);
-                                                                            }
-                                                                        }
-                                                                    }
-                                                                    DesiredAction::Delete => {
-                                                                        match client.delete_data(
-                                                                            db.name.as_str(),
-                                                                            self.key_input.as_str(),
-                                                                        ) {
-                                                                            #[allow(unused_variables)]
-                                                                            Ok(resp) => {
-                                                                                #[cfg(debug_assertions)]
-                                                                                println!("{:?}", resp);
-                                                                            }
-                                                                            Err(err) => {
-                                                                                #[cfg(debug_assertions)]
-                                                                                println!("{:?}", err);
-                                                                                *lock = ClientConnectionError(err);
-                                                                            }
-                                                                        }
-                                                                    }
-                                                                }
-
-                                                                match client.list_db_contents(db.name.as_str()) {
-                                                                    Ok(data) => {
-                                                                        db.content =
-                                                                            Cached(data);
-                                                                    }
-                                                                    Err(err) => {
-                                                                        *lock = ClientConnectionError(err);
-                                                                    }
-                                                                }
-                                                            }
-                                                        }
-                                                    }
-                                                },
-                                            },
-                                        }
+                                    ui.label("Click an entry to edit it, or");
+                                    if ui.button("New entry").clicked() {
+                                        self.value_editor = Some(ValueEditor::new_entry());
                                     }
-
-                                    if ui.button(self.desired_action.as_text()).clicked() {
-                                        match self.desired_action {
-                                            DesiredAction::Write => {
-                                                self.desired_action = DesiredAction::Delete;
-                                            }
-                                            DesiredAction::Delete => {
-                                                self.desired_action = DesiredAction::Write;
-                                            }
-                                        }
+                                    let checked = self.content_table.checked_keys().len();
+                                    if checked > 0
+                                        && ui
+                                            .button(format!("Copy {} checked to...", checked))
+                                            .clicked()
+                                    {
+                                        self.copy_dialog = Some(CopyDialog::default());
                                     }
                                 });
-                            });
+                            }
+                            Some(editor) => {
+                                if let Some(action) = editor.show(ui) {
+                                    self.handle_editor_action(action);
+                                }
+                            }
+                        });
                     }
                 }
                 ChangeDBSettings => {}
+                ManageMembers => {}
                 CreateDB => {}
                 DBResponseError(_) => {}
             }
         }
 
+        // copy dialog block
+        {
+            if matches!(self.program_state, DisplayClient) {
+                let mut copy_action = None;
+                if let Some(dialog) = &mut self.copy_dialog {
+                    let db_names: Vec<&str> = self
+                        .database_list
+                        .iter()
+                        .flatten()
+                        .map(|db| db.name.as_str())
+                        .collect();
+                    let entry_count = self.content_table.checked_keys().len();
+                    egui::Window::new("Copy entries")
+                        .collapsible(false)
+                        .show(ctx, |ui| {
+                            copy_action =
+                                dialog.show(ui, entry_count, &db_names, &self.saved_profiles);
+                        });
+                }
+                if let Some(action) = copy_action {
+                    self.handle_copy_action(action);
+                }
+            }
+        }
+
+        // transfer dialog block
+        {
+            if matches!(self.program_state, DisplayClient) {
+                let mut transfer_action = None;
+                if let Some(dialog) = &mut self.transfer_dialog {
+                    let db_names: Vec<&str> = self
+                        .database_list
+                        .iter()
+                        .flatten()
+                        .map(|db| db.name.as_str())
+                        .collect();
+                    egui::Window::new("Export / Import")
+                        .collapsible(false)
+                        .show(ctx, |ui| {
+                            transfer_action = dialog.show(ui, &db_names);
+                        });
+                }
+                if let Some(action) = transfer_action {
+                    self.handle_transfer_action(action);
+                }
+            }
+        }
+
+        // confirmation block
+        {
+            let confirmed = self
+                .confirmation
+                .as_ref()
+                .and_then(|confirmation| confirmation.show(ctx));
+            if let Some(confirmed) = confirmed {
+                match self.confirmation.take() {
+                    Some(Confirmation::CreateDB(db_name, settings)) if confirmed => {
+                        self.send(Request::CreateDB(db_name, *settings));
+                    }
+                    Some(Confirmation::DeleteDB(db_name)) if confirmed => {
+                        self.send(Request::DeleteDB(db_name));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // undo delete block
+        {
+            if let Some((db_name, deleted_at)) = &self.undo_delete {
+                let remaining = UNDO_DELETE_TIME.saturating_sub(deleted_at.elapsed());
+                if remaining.is_zero() {
+                    self.undo_delete = None;
+                } else {
+                    let mut undo = false;
+                    let mut dismiss = false;
+                    egui::Window::new("Undo delete")
+                        .title_bar(false)
+                        .resizable(false)
+                        .anchor(egui::Align2::RIGHT_BOTTOM, [-10.0, -10.0])
+                        .show(ctx, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.label(format!("Deleted {:?}", db_name));
+                                undo = ui.button("Undo delete").clicked();
+                                dismiss = ui.button("Dismiss").clicked();
+                            });
+                        });
+                    // the toast is removed once its time runs out, even without input
+                    ctx.request_repaint_after(remaining);
+                    if undo {
+                        let db_name = db_name.clone();
+                        self.send(Request::RestoreDB(db_name));
+                    }
+                    if undo || dismiss {
+                        self.undo_delete = None;
+                    }
+                }
+            }
+        }
+
         // stats panel block
         {
-            let ps_lock = self.program_state.lock().unwrap();
-            match *ps_lock {
+            match self.program_state {
                 NoClient => {}
                 PromptForClientDetails => {}
                 ClientConnectionError(_) => {}
                 DBResponseError(_) => {}
                 PromptForKey => {}
                 ChangeDBSettings => {}
+                ManageMembers => {}
                 CreateDB => {}
                 DisplayClient => match &self.database_list {
                     None => {}
@@ -435,6 +1136,7 @@ impl eframe::App for ApplicationState {
                             if let Some(db) = list.get(index) {
                                 match &db.statistics {
                                     NotCached => {}
+                                    Loading => {}
                                     Cached(stats) => {
                                         egui::SidePanel::right("stats_panel").show(ctx, |ui| {
                                             ui.label(format!(
@@ -445,17 +1147,24 @@ impl eframe::App for ApplicationState {
                                                 "Average access time gap: {:.2}",
                                                 stats.get_avg_time()
                                             ));
-                                            let times_string = stats
-                                                .get_usage_time_list()
-                                                .iter()
-                                                .map(display_date)
-                                                .fold("".to_string(), |a, b| {
-                                                    format!("{}{}\n", a, b)
-                                                });
-                                            ui.label(format!(
-                                                "Previous access times:\n{}",
-                                                times_string
-                                            ));
+                                            if let Some(last) = stats.get_usage_time_list().last() {
+                                                ui.label(format!(
+                                                    "Last request: {}",
+                                                    display_date(last)
+                                                ));
+                                            }
+                                            ui.separator();
+                                            ui.horizontal(|ui| {
+                                                ui.label("Window:");
+                                                for window in TimeWindow::ALL {
+                                                    ui.selectable_value(
+                                                        &mut self.stats_window,
+                                                        window,
+                                                        window.as_text(),
+                                                    );
+                                                }
+                                            });
+                                            statistics_plots(ui, stats, self.stats_window);
                                         });
                                     }
                                     ContentCacheState::Error(_) => {}
@@ -470,13 +1179,12 @@ impl eframe::App for ApplicationState {
         // side panel block
         {
             egui::SidePanel::left("side_panel").show(ctx, |ui| {
-                let mut ps_lock = self.program_state.lock().unwrap();
-                match *ps_lock {
+                match self.program_state {
                     NoClient => {}
                     PromptForClientDetails => {}
                     ClientConnectionError(_) => {}
                     // side menu that is persistent when displaying the client data.
-                    DisplayClient | ChangeDBSettings => {
+                    DisplayClient | ChangeDBSettings | ManageMembers => {
                         if let Some(selected_db) = self.selected_database {
                             if let Some(db_list) = &self.database_list {
                                 if let Some(db) = db_list.get(selected_db) {
@@ -484,6 +1192,10 @@ impl eframe::App for ApplicationState {
                                     ui.separator();
                                     match &db.role {
                                         NotCached => {}
+                                        Loading => {
+                                            ui.spinner();
+                                            ui.separator();
+                                        }
                                         Cached(role) => {
                                             ui.label(format!("Role: {:?}", role));
                                             ui.separator();
@@ -493,212 +1205,31 @@ impl eframe::App for ApplicationState {
                                 }
                             }
                         }
-                        match &mut self.database_list {
-                            None => {}
-                            Some(list) => {
-                                for (index, item) in list.iter_mut().enumerate() {
-                                    if ui.button(format!("{}: {}", index + 1, item.name)).clicked()
-                                    {
-                                        let mut lock = self.client.lock().unwrap();
-                                        match *lock {
-                                            None => {}
-                                            Some(ref mut client) => {
-                                                // cache the content if it is not cached.
-                                                match &item.content {
-                                                    NotCached => {
-                                                        match client
-                                                            .list_db_contents(item.name.as_str())
-                                                        {
-                                                            Ok(data) => {
-                                                                item.content = Cached(data);
-                                                            }
-                                                            Err(err) => {
-                                                                item.content =
-                                                                    ContentCacheState::Error(err);
-                                                            }
-                                                        }
-                                                    }
-                                                    Cached(_) => {}
-                                                    ContentCacheState::Error(_) => {}
-                                                }
-
-                                                // cache the role if it is not cached.
-                                                match item.role {
-                                                    NotCached => {
-                                                        match client.get_role(item.name.as_str()) {
-                                                            Ok(role) => item.role = Cached(role),
-                                                            Err(err) => {
-                                                                item.role =
-                                                                    ContentCacheState::Error(err);
-                                                            }
-                                                        }
-                                                    }
-                                                    Cached(_) => {}
-                                                    ContentCacheState::Error(_) => {}
-                                                }
-
-                                                match &item.db_settings {
-                                                    NotCached => {
-                                                        match client
-                                                            .get_db_settings(item.name.as_str())
-                                                        {
-                                                            Ok(db_settings) => {
-                                                                item.db_settings =
-                                                                    Cached(db_settings.clone());
-
-                                                                let users_string = {
-                                                                    let mut s = String::new();
-                                                                    db_settings
-                                                                        .users
-                                                                        .iter()
-                                                                        .for_each(|user| {
-                                                                            s.push_str(
-                                                                                format!(
-                                                                                    "{},",
-                                                                                    user
-                                                                                )
-                                                                                .as_str(),
-                                                                            );
-                                                                        });
-                                                                    if s.ends_with(',') {
-                                                                        s.remove(s.len() - 1);
-                                                                    }
-                                                                    s
-                                                                };
-                                                                let admins_string = {
-                                                                    let mut s = String::new();
-                                                                    db_settings
-                                                                        .admins
-                                                                        .iter()
-                                                                        .for_each(|admin| {
-                                                                            s.push_str(
-                                                                                format!(
-                                                                                    "{},",
-                                                                                    admin
-                                                                                )
-                                                                                .as_str(),
-                                                                            );
-                                                                        });
-                                                                    if s.ends_with(',') {
-                                                                        s.remove(s.len() - 1);
-                                                                    }
-                                                                    s
-                                                                };
-
-                                                                self.users_list = users_string;
-                                                                self.admins_list = admins_string;
-                                                                self.duration_seconds = db_settings
-                                                                    .invalidation_time
-                                                                    .as_secs();
-                                                                self.submit_db_settings =
-                                                                    db_settings;
-                                                            }
-                                                            Err(err) => {
-                                                                item.db_settings =
-                                                                    ContentCacheState::Error(err);
-                                                            }
-                                                        }
-                                                    }
-                                                    Cached(settings) => {
-                                                        self.submit_db_settings = settings.clone();
-                                                        let users_string = {
-                                                            let mut s = String::new();
-                                                            settings.users.iter().for_each(
-                                                                |user| {
-                                                                    s.push_str(
-                                                                        format!("{},", user)
-                                                                            .as_str(),
-                                                                    );
-                                                                },
-                                                            );
-                                                            if s.ends_with(',') {
-                                                                s.remove(s.len() - 1);
-                                                            }
-                                                            s
-                                                        };
-                                                        let admins_string = {
-                                                            let mut s = String::new();
-                                                            settings.admins.iter().for_each(
-                                                                |admin| {
-                                                                    s.push_str(
-                                                                        format!("{},", admin)
-                                                                            .as_str(),
-                                                                    );
-                                                                },
-                                                            );
-                                                            if s.ends_with(',') {
-                                                                s.remove(s.len() - 1);
-                                                            }
-                                                            s
-                                                        };
-
-                                                        self.users_list = users_string;
-                                                        self.admins_list = admins_string;
-                                                        self.duration_seconds =
-                                                            settings.invalidation_time.as_secs();
-                                                    }
-                                                    ContentCacheState::Error(_) => {}
-                                                }
-
-                                                match &item.statistics {
-                                                    NotCached => {
-                                                        match client.get_stats(item.name.as_str()) {
-                                                            Ok(stats) => {
-                                                                item.statistics = Cached(stats);
-                                                            }
-                                                            Err(err) => {
-                                                                item.statistics =
-                                                                    ContentCacheState::Error(err);
-                                                            }
-                                                        }
-                                                    }
-                                                    Cached(_) => {}
-                                                    ContentCacheState::Error(_) => {}
-                                                }
-
-                                                // set the selected database number in the program state.
-                                                self.selected_database = Some(index);
-                                            }
-                                        }
-                                    }
+                        let mut clicked_db = None;
+                        let mut delete_db = None;
+                        if let Some(list) = &self.database_list {
+                            for (index, item) in list.iter().enumerate() {
+                                if ui.button(format!("{}: {}", index + 1, item.name)).clicked() {
+                                    clicked_db = Some(index);
                                 }
+                            }
 
-                                if let Some(index) = self.selected_database {
-                                    if let Some(db) = list.get(index) {
-                                        ui.separator();
-                                        if ui
-                                            .button("Delete DB")
-                                            .on_hover_text("Double click to delete DB")
-                                            .double_clicked()
-                                        {
-                                            let mut lock = self.client.lock().unwrap();
-                                            match *lock {
-                                                None => {}
-                                                Some(ref mut client) => {
-                                                    match client.delete_db(db.name.as_str()) {
-                                                        Ok(delete_response) => match delete_response
-                                                        {
-                                                            DBSuccessResponse::SuccessNoData => {
-                                                                list.remove(index);
-                                                            }
-                                                            DBSuccessResponse::SuccessReply(_) => {
-                                                                *ps_lock = ClientConnectionError(
-                                                                    BadPacket,
-                                                                );
-                                                            }
-                                                        },
-                                                        Err(err) => {
-                                                            *ps_lock = ClientConnectionError(err);
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        ui.separator();
+                            if let Some(index) = self.selected_database {
+                                if let Some(db) = list.get(index) {
+                                    ui.separator();
+                                    if ui.button("Delete DB").clicked() {
+                                        delete_db = Some(db.name.clone());
                                     }
+                                    ui.separator();
                                 }
                             }
                         }
+                        if let Some(index) = clicked_db {
+                            self.select_db(index);
+                        }
+                        if let Some(name) = delete_db {
+                            self.confirmation = Some(Confirmation::DeleteDB(name));
+                        }
                     }
                     PromptForKey => {}
                     CreateDB => {}
@@ -711,116 +1242,164 @@ impl eframe::App for ApplicationState {
         {
             egui::CentralPanel::default().show(ctx, |ui| {
                 #[cfg(debug_assertions)]
-                ui.label(format!(
-                    "DEBUG Program State: {:?}",
-                    self.program_state.lock().unwrap()
-                ));
-                let mut ps_lock = self.program_state.lock().unwrap();
-                match ps_lock.deref() {
+                ui.label(format!("DEBUG Program State: {:?}", self.program_state));
+                match &self.program_state {
                     NoClient => {
                         // Display nothing when there are is no client connection.
                         ui.label("Nothing to show here...");
-                        // display a spinner if there is an active client connection thread going.
-                        if let Some(thread) = &self.connection_thread {
-                            if !thread.is_finished() {
-                                ui.horizontal(|ui| {
-                                    ui.label("Connecting...");
-                                    ui.spinner();
-                                });
-                            }
+                        // display a spinner if the worker is still connecting.
+                        if self.is_busy() {
+                            ui.horizontal(|ui| {
+                                ui.label("Connecting...");
+                                ui.spinner();
+                            });
                         }
                     }
                     PromptForClientDetails => {
                         // When the user clicks connect, we prompt them for client connection details.
+                        ui.horizontal(|ui| {
+                            ui.label("Profile:");
+                            egui::ComboBox::from_id_source("connection_profile")
+                                .selected_text(self.profile.name.as_str())
+                                .show_ui(ui, |ui| {
+                                    for profile in &self.saved_profiles {
+                                        if ui.selectable_label(profile.name == self.profile.name, profile.name.as_str()).clicked() {
+                                            self.profile = profile.clone();
+                                        }
+                                    }
+                                });
+                        });
+
+                        ui.horizontal(|ui| {
+                            ui.label("Name:");
+                            ui.text_edit_singleline(&mut self.profile.name);
+                            if ui.add_enabled(!self.profile.name.is_empty(), egui::Button::new("Save profile")).clicked() {
+                                save_profile(&mut self.saved_profiles, &self.profile);
+                            }
+                            if ui.button("Delete profile").on_hover_text("Double click to delete the saved profile").double_clicked() {
+                                self.saved_profiles.retain(|profile| profile.name != self.profile.name);
+                            }
+                        });
+                        ui.separator();
+
                         ui.label("Enter Ip Address:");
-                        ui.text_edit_singleline(&mut self.ip_address);
+                        ui.text_edit_singleline(&mut self.profile.ip_address);
 
-                        if !self.ip_address.is_empty() {
-                            ui.checkbox(&mut self.auto_connect,"Auto connect to given ip address on startup");
-                            if self.auto_connect {
+                        ui.label("Enter Key:").on_hover_text("Set once connected if it is not empty");
+                        ui.add(egui::TextEdit::singleline(&mut self.profile.client_key).password(true));
+
+                        ui.checkbox(&mut self.profile.use_encryption, "Use end to end encryption")
+                            .on_hover_text("Encrypts the connection before the key is sent, the fingerprint of the server key is shown in the top bar once connected");
+
+                        if !self.profile.ip_address.is_empty() {
+                            ui.checkbox(&mut self.profile.auto_connect,"Auto connect to given ip address on startup");
+                            if self.profile.auto_connect {
                                 // if the users client key is not empty display the possibility to auto set their key.
-                                ui.checkbox(&mut self.auto_set_key, "Auto set key on connect on startup").on_hover_text("Key must not be empty to run at startup.");
+                                ui.checkbox(&mut self.profile.auto_set_key, "Auto set key on connect on startup").on_hover_text("Key must not be empty to run at startup.");
                             } else {
                                 // if auto connect is false, then auto set key should also be false.
-                                self.auto_set_key = false;
+                                self.profile.auto_set_key = false;
                             }
                         }
 
                         if ui.button("Connect to ip address").clicked() {
-                            // clone a bunch of things that need to be moved into the thread.
-                            let client_clone = Arc::clone(&self.client);
-                            let program_state_clone = Arc::clone(&self.program_state);
-                            let ip_clone = self.ip_address.clone();
-                            self.connection_thread = Some(thread::spawn(move || {
-                                // instantly move all the variables into the thread
-                                let ps = program_state_clone;
-                                let client_mutex = client_clone;
-                                let ip = ip_clone;
-
-                                match SmolDbClient::new(&ip) {
-                                    // connect the client to the server.
-                                    Ok(client_connection) => {
-                                        // if client connection successful, move the client to the programs state.
-                                        *client_mutex.lock().unwrap() = Some(client_connection);
-                                        // change the program state into a DisplayClient state.
-                                        *ps.lock().unwrap() = DisplayClient;
-                                    }
-                                    Err(err) => {
-                                        // if the client connection fails, change the program state accordingly.
-                                        *ps.lock().unwrap() = ClientConnectionError(err);
-                                    }
-                                }
-                            }));
+                            // the worker connects in the background, and the program state changes once it responds
+                            let key = self.profile.key();
+                            self.connect(ctx, key);
                         }
 
-                        if let Some(thread) = &self.connection_thread {
-                            if !thread.is_finished() {
-                                ui.spinner();
-                            }
+                        if self.is_busy() {
+                            ui.spinner();
                         }
                     }
                     DisplayClient => {
-                        match &mut self.database_list {
+                        match &self.database_list {
                             // get the database list if it is not known
                             None => {
-                                let mut lock = self.client.lock().unwrap();
-                                match *lock {
-                                    None => {}
-                                    Some(ref mut client) => match client.list_db() {
-                                        Ok(list) => {
-                                            self.database_list = Some(
-                                                list.iter()
-                                                    .map(|db_packet| DBCached {
-                                                        name: db_packet.get_db_name().to_string(),
-                                                        content: NotCached,
-                                                        role: NotCached,
-                                                        db_settings: NotCached,
-                                                        statistics: NotCached,
-                                                    })
-                                                    .collect(),
-                                            );
-                                        }
-                                        Err(err) => {
-                                            *self.program_state.lock().unwrap() =
-                                                ClientConnectionError(err);
-                                        }
-                                    },
+                                if !self.awaiting_db_list && self.worker.is_some() {
+                                    self.awaiting_db_list = true;
+                                    self.send(Request::ListDB);
                                 }
+                                ui.horizontal(|ui| {
+                                    ui.label("Loading databases...");
+                                    ui.spinner();
+                                });
                             }
                             // db list exists, populate its information on screen.
                             Some(list) => {
-                                if let Some(index_selected) = self.selected_database {
+                                if self.selected_database.is_some_and(|index| index < list.len()) {
+                                    ui.horizontal(|ui| {
+                                        ui.selectable_value(&mut self.content_view, ContentView::Values, "Values");
+                                        ui.selectable_value(&mut self.content_view, ContentView::Lists, "Lists")
+                                            .on_hover_text("Keyed lists and queues");
+                                    });
+                                    ui.separator();
+                                }
+                                if let Some(db_cached) = self.selected_database.filter(|_| self.content_view == ContentView::Lists).and_then(|index| list.get(index)) {
+                                    let mut clicked_list = None;
+                                    let mut list_action = None;
+                                    match &db_cached.lists {
+                                        NotCached => {}
+                                        Loading => {
+                                            ui.spinner();
+                                        }
+                                        Cached(list_names) => {
+                                            egui::SidePanel::left("list_names_panel").show_inside(ui, |ui| {
+                                                if ui.button("New list").clicked() {
+                                                    self.list_browser = Some(ListBrowser::new_list());
+                                                }
+                                                ui.separator();
+                                                if list_names.is_empty() {
+                                                    ui.label("No lists");
+                                                }
+                                                egui::ScrollArea::vertical().show(ui, |ui| {
+                                                    let browsed = self.list_browser.as_ref().map(ListBrowser::list_name);
+                                                    for list_name in list_names {
+                                                        if ui.selectable_label(browsed == Some(list_name.as_str()), list_name.as_str()).clicked() {
+                                                            clicked_list = Some(list_name.clone());
+                                                        }
+                                                    }
+                                                });
+                                            });
+                                            match &mut self.list_browser {
+                                                None => {
+                                                    ui.label("Click a list to browse it");
+                                                }
+                                                Some(browser) => {
+                                                    list_action = browser.show(ui);
+                                                }
+                                            }
+                                        }
+                                        ContentCacheState::Error(err) => {
+                                            ui.label(format!("{:?}", err));
+                                        }
+                                    }
+                                    if let Some(list_name) = clicked_list {
+                                        self.open_list(&list_name);
+                                    }
+                                    if let Some(action) = list_action {
+                                        self.handle_list_action(action);
+                                    }
+                                } else if let Some(index_selected) = self.selected_database {
                                     if let Some(db_cached) = list.get(index_selected) {
                                         match &db_cached.content {
                                             NotCached => {}
+                                            Loading => {
+                                                ui.spinner();
+                                            }
                                             Cached(data) => {
-                                                let mut list = data
-                                                    .iter()
-                                                    .map(|(s1, s2)| (s1.to_string(), s2.to_string()))
-                                                    .collect::<Vec<(String, String)>>();
-                                                list.sort();
-                                                for (key, value) in list {
-                                                    ui.label(format!("{} : {}", key, value));
+                                                let selected_key =
+                                                    self.value_editor.as_ref().map(ValueEditor::key);
+                                                if let Some(key) = self.content_table.show(
+                                                    ui,
+                                                    data,
+                                                    selected_key,
+                                                    &db_cached.changed_keys,
+                                                ) {
+                                                    if let Some(value) = data.get(&key) {
+                                                        self.value_editor =
+                                                            Some(ValueEditor::open(&key, value));
+                                                    }
                                                 }
                                             }
                                             ContentCacheState::Error(err) => {
@@ -838,53 +1417,40 @@ impl eframe::App for ApplicationState {
                     }
                     PromptForKey => {
                         ui.label("Enter Key:");
-                        ui.text_edit_singleline(&mut self.client_key);
+                        ui.text_edit_singleline(&mut self.profile.client_key);
                         if ui.button("Set Key").clicked() {
-                            let mut lock = self.client.lock().unwrap();
-                            match *lock {
-                                None => {}
-                                Some(ref mut client) => {
-                                    match client.set_access_key(self.client_key.clone()) {
-                                        Ok(_) => {
-                                            *ps_lock = DisplayClient;
-                                        }
-                                        Err(err) => {
-                                            *ps_lock = ClientConnectionError(err);
-                                        }
-                                    }
-                                }
-                            }
+                            let key = self.profile.client_key.clone();
+                            self.send(Request::SetKey(key));
+                        }
+                        if self.is_busy() {
+                            ui.spinner();
                         }
                     }
                     ChangeDBSettings => {
+                        let mut submit_db = None;
                         match self.selected_database {
                             None => {}
                             Some(index) => {
-                                match &mut self.database_list {
+                                match &self.database_list {
                                     None => {}
                                     Some(list) => {
-                                        match list.get_mut(index) {
+                                        match list.get(index) {
                                             None => {}
                                             Some(db) => {
-                                                match &mut db.db_settings {
+                                                match &db.db_settings {
                                                     NotCached => {}
+                                                    Loading => {
+                                                        ui.spinner();
+                                                    }
                                                     Cached(db_settings) => {
                                                         // invalidation time
                                                         ui.label(format!("Invalidation time: {}s", db_settings.get_invalidation_time().as_secs()));
 
                                                         // other permissions
-                                                        let others_perms = db_settings.get_other_rwx();
-                                                        ui.label(format!("Others permissions (rwx): {},{},{}", others_perms.0,others_perms.1,others_perms.2));
+                                                        ui.label(format!("Others permissions: {:?}", db_settings.get_other_permissions()));
 
                                                         // user permissions
-                                                        let users_perms = db_settings.get_user_rwx();
-                                                        ui.label(format!("Users permissions (rwx): {},{},{}", users_perms.0,users_perms.1,users_perms.2));
-
-                                                        // user list
-                                                        ui.label(format!("User list: {:?}", db_settings.get_user_list()));
-
-                                                        // admin list
-                                                        ui.label(format!("Admin list: {:?}", db_settings.get_admin_list()));
+                                                        ui.label(format!("Users permissions: {:?}", db_settings.get_user_permissions()));
 
                                                         ui.separator();
 
@@ -895,61 +1461,16 @@ impl eframe::App for ApplicationState {
 
                                                         self.submit_db_settings.invalidation_time = Duration::from_secs(self.duration_seconds);
 
-                                                        ui.horizontal(|ui| {
-                                                            ui.label("Others permissions: ").on_hover_text("Read, Write, List Contents");
-                                                            ui.checkbox(&mut self.submit_db_settings.can_others_rwx.0,"r");
-                                                            ui.checkbox(&mut self.submit_db_settings.can_others_rwx.1,"w");
-                                                            ui.checkbox(&mut self.submit_db_settings.can_others_rwx.2,"x");
-                                                        });
-                                                        ui.horizontal(|ui| {
-                                                            ui.label("Users permissions: ").on_hover_text("Read, Write, List Contents");
-                                                            ui.checkbox(&mut self.submit_db_settings.can_users_rwx.0,"r");
-                                                            ui.checkbox(&mut self.submit_db_settings.can_users_rwx.1,"w");
-                                                            ui.checkbox(&mut self.submit_db_settings.can_users_rwx.2,"x");
-                                                        });
-
-                                                        ui.horizontal(|ui| {
-                                                            ui.label("Users: ").on_hover_text("Comma separated :)");
-                                                            ui.text_edit_singleline(&mut self.users_list);
-                                                        });
-
-                                                        ui.horizontal(|ui| {
-                                                            ui.label("Admins: ").on_hover_text("Comma separated :)");
-                                                            ui.text_edit_singleline(&mut self.admins_list);
-                                                        });
-
-                                                        if !self.users_list.is_empty() {
-                                                            self.submit_db_settings.users = self.users_list.split(',').map(|string| string.to_string()).collect::<Vec<String>>();
-                                                        } else {
-                                                            self.submit_db_settings.users = vec![];
-                                                        }
-
-                                                        if !self.admins_list.is_empty() {
-                                                            self.submit_db_settings.admins = self.admins_list.split(',').map(|string| string.to_string()).collect::<Vec<String>>();
-                                                        } else {
-                                                            self.submit_db_settings.admins = vec![];
-                                                        }
+                                                        permission_checkboxes(ui, "Others permissions: ", &mut self.submit_db_settings.others_permissions);
+                                                        permission_checkboxes(ui, "Users permissions: ", &mut self.submit_db_settings.users_permissions);
 
-                                                        #[cfg(debug_assertions)]
-                                                        ui.label(format!("DEBUG users: {:?}", self.submit_db_settings.users));
-                                                        #[cfg(debug_assertions)]
-                                                        ui.label(format!("DEBUG admins: {:?}", self.submit_db_settings.admins));
+                                                        ui.label("Users and admins are managed in the Members panel.");
 
                                                         if ui.button("Submit").clicked() {
-                                                            let mut lock = self.client.lock().unwrap();
-                                                            match *lock {
-                                                                None => {}
-                                                                Some(ref mut client) => {
-                                                                    match client.set_db_settings(db.name.as_str(),self.submit_db_settings.clone()) {
-                                                                        Ok(_) => {
-                                                                            *db_settings = self.submit_db_settings.clone();
-                                                                        }
-                                                                        Err(err) => {
-                                                                            db.db_settings = ContentCacheState::Error(err);
-                                                                        }
-                                                                    }
-                                                                }
-                                                            }
+                                                            // the members panel changes the users and admins, so the lists the db has are kept
+                                                            self.submit_db_settings.users = db_settings.users.clone();
+                                                            self.submit_db_settings.admins = db_settings.admins.clone();
+                                                            submit_db = Some(db.name.clone());
                                                         }
                                                     }
                                                     ContentCacheState::Error(err) => {
@@ -963,13 +1484,49 @@ impl eframe::App for ApplicationState {
                             }
                         }
 
+                        if let Some(name) = submit_db {
+                            let settings = self.submit_db_settings.clone();
+                            self.send(Request::SetDBSettings(name, settings));
+                        }
+
                         if ui.button("Back").clicked() {
-                            *ps_lock = DisplayClient;
+                            self.program_state = DisplayClient;
                         }
 
                         ui.separator();
 
                     }
+                    ManageMembers => {
+                        let mut member_action = None;
+                        match self.selected_database.and_then(|index| self.database_list.as_ref()?.get(index)) {
+                            None => {
+                                ui.label("Select a DB to manage its members");
+                            }
+                            Some(db) => match &db.db_settings {
+                                NotCached => {}
+                                Loading => {
+                                    ui.spinner();
+                                }
+                                Cached(db_settings) => {
+                                    member_action = self.members_panel.show(ui, db_settings);
+                                }
+                                ContentCacheState::Error(err) => {
+                                    ui.label(format!("Error reading DBSettings: {:?}", err));
+                                }
+                            },
+                        }
+
+                        if let Some(action) = member_action {
+                            self.handle_member_action(action);
+                        }
+
+                        if self.is_busy() {
+                            ui.spinner();
+                        }
+                        if ui.button("Back").clicked() {
+                            self.program_state = DisplayClient;
+                        }
+                    }
                     CreateDB => {
 
                         ui.horizontal(|ui| {
@@ -984,18 +1541,8 @@ impl eframe::App for ApplicationState {
 
                         self.submit_db_settings.invalidation_time = Duration::from_secs(self.duration_seconds);
 
-                        ui.horizontal(|ui| {
-                            ui.label("Others permissions: ").on_hover_text("Read, Write, List Contents");
-                            ui.checkbox(&mut self.submit_db_settings.can_others_rwx.0,"r");
-                            ui.checkbox(&mut self.submit_db_settings.can_others_rwx.1,"w");
-                            ui.checkbox(&mut self.submit_db_settings.can_others_rwx.2,"x");
-                        });
-                        ui.horizontal(|ui| {
-                            ui.label("Users permissions: ").on_hover_text("Read, Write, List Contents");
-                            ui.checkbox(&mut self.submit_db_settings.can_users_rwx.0,"r");
-                            ui.checkbox(&mut self.submit_db_settings.can_users_rwx.1,"w");
-                            ui.checkbox(&mut self.submit_db_settings.can_users_rwx.2,"x");
-                        });
+                        permission_checkboxes(ui, "Others permissions: ", &mut self.submit_db_settings.others_permissions);
+                        permission_checkboxes(ui, "Users permissions: ", &mut self.submit_db_settings.users_permissions);
 
                         ui.horizontal(|ui| {
                             ui.label("Users: ").on_hover_text("Comma separated :)");
@@ -1025,55 +1572,14 @@ impl eframe::App for ApplicationState {
                         ui.label(format!("DEBUG admins: {:?}", self.submit_db_settings.admins));
 
                         if ui.button("Submit").clicked() && !self.db_name_create.is_empty() {
-                            let mut lock = self.client.lock().unwrap();
-                            match *lock {
-                                None => {}
-                                Some(ref mut client) => {
-                                    match client.create_db(self.db_name_create.as_str(),self.submit_db_settings.clone()) {
-                                        Ok(resp) => {
-                                            match resp {
-                                                DBSuccessResponse::SuccessNoData => {
-                                                    // after creating a db go back to displaying the client
-                                                    *ps_lock = DisplayClient;
-
-                                                    match &mut self.database_list {
-                                                        None => {}
-                                                        Some(list) => {
-                                                            match client.list_db_contents(self.db_name_create.as_str()) {
-                                                                Ok(response) => {
-                                                                    list.push(DBCached{
-                                                                        name: self.db_name_create.to_string(),
-                                                                        content: Cached(response),
-                                                                        role: NotCached,
-                                                                        db_settings: NotCached,
-                                                                        statistics: NotCached,
-                                                                    });
-                                                                }
-                                                                Err(err) => {
-                                                                    *ps_lock = ClientConnectionError(err);
-                                                                }
-                                                            }
-                                                        }
-                                                    }
-
-
-
-                                                }
-                                                DBSuccessResponse::SuccessReply(_) => {
-                                                    // this should not happen, creating a db does not respond with data.
-                                                    *ps_lock = ClientConnectionError(BadPacket);
-                                                }
-                                            }
-                                        }
-                                        Err(err) => {
-                                            *ps_lock = ClientConnectionError(err);
-                                        }
-                                    }
-                                }
-                            }
+                            // the program state goes back to displaying the client once the worker has created the db
+                            self.confirmation = Some(Confirmation::CreateDB(self.db_name_create.clone(), Box::new(self.submit_db_settings.clone())));
+                        }
+                        if self.is_busy() {
+                            ui.spinner();
                         }
                         if ui.button("Back").clicked() {
-                            *ps_lock = DisplayClient;
+                            self.program_state = DisplayClient;
                         }
                     }
                     DBResponseError(err) => {
@@ -1092,6 +1598,22 @@ impl eframe::App for ApplicationState {
     }
 }
 
+/// Shows a row of checkboxes, one for each permission, that add or remove it from the permissions
+fn permission_checkboxes(ui: &mut egui::Ui, label: &str, permissions: &mut Permissions) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        for permission in Permission::ALL {
+            let mut allowed = permissions.contains(permission);
+            if ui
+                .checkbox(&mut allowed, format!("{:?}", permission))
+                .changed()
+            {
+                permissions.set(permission, allowed);
+            }
+        }
+    });
+}
+
 fn display_date(time: &DateTime<Local>) -> String {
     format!(
         "{}/{}/{} {}:{} {}",
@@ -0,0 +1,61 @@
+//! Contains `Confirmation`, a change to the dbs of the server that is only made once the user confirms it
+use smol_db_client::db_settings::DBSettings;
+
+/// A change waiting on the user to confirm it
+#[derive(Debug)]
+pub enum Confirmation {
+    CreateDB(String, Box<DBSettings>),
+    DeleteDB(String),
+}
+
+impl Confirmation {
+    const fn title(&self) -> &str {
+        match self {
+            Self::CreateDB(..) => "Create DB",
+            Self::DeleteDB(_) => "Delete DB",
+        }
+    }
+
+    /// Shows the change in its own window, returning true if the user confirmed it, false if they cancelled it, or none if they have not chosen yet
+    pub fn show(&self, ctx: &egui::Context) -> Option<bool> {
+        let mut confirmed = None;
+
+        egui::Window::new(self.title())
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                match self {
+                    Self::CreateDB(db_name, settings) => {
+                        ui.label(format!("Create the db {:?}?", db_name));
+                        ui.label(format!(
+                            "Invalidation time: {}s",
+                            settings.invalidation_time.as_secs()
+                        ));
+                        ui.label(format!(
+                            "Users: {}, admins: {}",
+                            settings.users.len(),
+                            settings.admins.len()
+                        ));
+                    }
+                    Self::DeleteDB(db_name) => {
+                        ui.label(format!("Delete the db {:?}?", db_name));
+                        ui.label(
+                            "It is moved to the trash, and can be restored until the server purges it.",
+                        );
+                    }
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button(self.title()).clicked() {
+                        confirmed = Some(true);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        confirmed = Some(false);
+                    }
+                });
+            });
+
+        confirmed
+    }
+}
@@ -0,0 +1,222 @@
+//! Contains `ContentTable`, which shows the content of a db as a table that can be filtered, sorted and paged through
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Number of rows that can be shown on a page
+const PAGE_SIZES: [usize; 4] = [25, 50, 100, 500];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortColumn {
+    Key,
+    Value,
+}
+
+impl SortColumn {
+    const fn as_text(&self) -> &str {
+        match self {
+            Self::Key => "Key",
+            Self::Value => "Value",
+        }
+    }
+}
+
+/// The filter, sort order and page the content of the selected db is shown with
+#[derive(Debug)]
+pub struct ContentTable {
+    /// Only rows whose key or value contain the filter are shown
+    filter: String,
+    sort_column: SortColumn,
+    descending: bool,
+    page: usize,
+    page_size: usize,
+    /// Keys of the rows that are checked, so they can be copied to another db
+    checked: BTreeSet<String>,
+}
+
+impl Default for ContentTable {
+    fn default() -> Self {
+        Self {
+            filter: "".to_string(),
+            sort_column: SortColumn::Key,
+            descending: false,
+            page: 0,
+            page_size: 100,
+            checked: BTreeSet::new(),
+        }
+    }
+}
+
+impl ContentTable {
+    /// Goes back to the first page and unchecks every row, used when a different db is shown
+    pub fn reset(&mut self) {
+        self.page = 0;
+        self.checked.clear();
+    }
+
+    /// Returns the keys of the rows that are checked
+    pub const fn checked_keys(&self) -> &BTreeSet<String> {
+        &self.checked
+    }
+
+    /// Shows the filter and paging controls, followed by the rows of the current page with the row of the selected key highlighted,
+    /// and the keys that changed at the last refresh colored.
+    /// Returns the key of the row that was clicked, if any
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        content: &HashMap<String, String>,
+        selected_key: Option<&str>,
+        changed_keys: &HashSet<String>,
+    ) -> Option<String> {
+        let mut clicked_key = None;
+
+        ui.horizontal(|ui| {
+            ui.label("Filter:");
+            if ui.text_edit_singleline(&mut self.filter).changed() {
+                self.page = 0;
+            }
+            ui.label("Rows per page:");
+            egui::ComboBox::from_id_source("content_page_size")
+                .selected_text(self.page_size.to_string())
+                .show_ui(ui, |ui| {
+                    for page_size in PAGE_SIZES {
+                        if ui
+                            .selectable_value(&mut self.page_size, page_size, page_size.to_string())
+                            .changed()
+                        {
+                            self.page = 0;
+                        }
+                    }
+                });
+        });
+
+        let rows = self.rows(content);
+        let page_count = rows.len().div_ceil(self.page_size).max(1);
+        // the content may have shrunk since the page was chosen
+        self.page = self.page.min(page_count - 1);
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(self.page > 0, egui::Button::new("Previous"))
+                .clicked()
+            {
+                self.page -= 1;
+            }
+            ui.label(format!(
+                "Page {} of {} ({} of {} rows)",
+                self.page + 1,
+                page_count,
+                rows.len(),
+                content.len()
+            ));
+            if ui
+                .add_enabled(self.page + 1 < page_count, egui::Button::new("Next"))
+                .clicked()
+            {
+                self.page += 1;
+            }
+            ui.separator();
+            if ui
+                .button("Check all")
+                .on_hover_text("Checks every row that matches the filter")
+                .clicked()
+            {
+                self.checked
+                    .extend(rows.iter().map(|(key, _)| key.to_string()));
+            }
+            if ui
+                .add_enabled(!self.checked.is_empty(), egui::Button::new("Uncheck all"))
+                .clicked()
+            {
+                self.checked.clear();
+            }
+        });
+        ui.separator();
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("content_table")
+                .num_columns(3)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("");
+                    self.column_header(ui, SortColumn::Key);
+                    self.column_header(ui, SortColumn::Value);
+                    ui.end_row();
+
+                    for (key, value) in rows
+                        .iter()
+                        .skip(self.page * self.page_size)
+                        .take(self.page_size)
+                    {
+                        let mut checked = self.checked.contains(key.as_str());
+                        if ui.checkbox(&mut checked, "").changed() {
+                            if checked {
+                                self.checked.insert(key.to_string());
+                            } else {
+                                self.checked.remove(key.as_str());
+                            }
+                        }
+                        let selected = selected_key == Some(key.as_str());
+                        let mut key_text = egui::RichText::new(key.as_str());
+                        if changed_keys.contains(key.as_str()) {
+                            key_text = key_text.color(ui.visuals().warn_fg_color);
+                        }
+                        let key_clicked = ui.selectable_label(selected, key_text).clicked();
+                        let value_clicked = ui
+                            .add(
+                                egui::Label::new(value.as_str())
+                                    .truncate(true)
+                                    .sense(egui::Sense::click()),
+                            )
+                            .on_hover_text("Click to edit")
+                            .clicked();
+                        if key_clicked || value_clicked {
+                            clicked_key = Some(key.to_string());
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+
+        clicked_key
+    }
+
+    /// Shows the header of a column, clicking it sorts by the column, or reverses the order if the column is already sorted by
+    fn column_header(&mut self, ui: &mut egui::Ui, column: SortColumn) {
+        let arrow = match (self.sort_column == column, self.descending) {
+            (false, _) => "",
+            (true, false) => " ⬆",
+            (true, true) => " ⬇",
+        };
+        if ui
+            .button(format!("{}{}", column.as_text(), arrow))
+            .clicked()
+        {
+            if self.sort_column == column {
+                self.descending = !self.descending;
+            } else {
+                self.sort_column = column;
+                self.descending = false;
+            }
+            self.page = 0;
+        }
+    }
+
+    /// Returns the rows that match the filter, in the sort order
+    fn rows<'a>(&self, content: &'a HashMap<String, String>) -> Vec<(&'a String, &'a String)> {
+        let mut rows = content
+            .iter()
+            .filter(|(key, value)| key.contains(&self.filter) || value.contains(&self.filter))
+            .collect::<Vec<(&String, &String)>>();
+
+        match self.sort_column {
+            SortColumn::Key => rows.sort_by_key(|(key, _)| *key),
+            SortColumn::Value => rows.sort_by(|(key_a, value_a), (key_b, value_b)| {
+                value_a.cmp(value_b).then_with(|| key_a.cmp(key_b))
+            }),
+        }
+        if self.descending {
+            rows.reverse();
+        }
+        rows
+    }
+}
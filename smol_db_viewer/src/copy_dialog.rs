@@ -0,0 +1,120 @@
+//! Contains `CopyDialog`, which chooses the db, and optionally the server, the checked entries of the selected db are copied to
+use crate::profiles::ConnectionProfile;
+use smol_db_client::client_error::ClientError;
+
+/// What the user chose to do in the copy dialog
+#[derive(Debug)]
+pub enum CopyAction {
+    /// Copy the checked entries to the db, on the server of the profile, or the connected server if there is none
+    Copy {
+        destination: Option<ConnectionProfile>,
+        db_name: String,
+    },
+    Close,
+}
+
+/// Where the checked entries of the selected db are copied to
+#[derive(Debug, Default)]
+pub struct CopyDialog {
+    /// Name of the saved profile of the server to copy to, none for the connected server
+    profile_name: Option<String>,
+    db_name: String,
+    /// Number of entries copied by the last copy, or the reason it failed, none while there is no result yet
+    result: Option<Result<usize, ClientError>>,
+}
+
+impl CopyDialog {
+    /// Shows the result of the copy
+    pub fn set_result(&mut self, result: Result<usize, ClientError>) {
+        self.result = Some(result);
+    }
+
+    /// Shows the dialog for copying `entry_count` entries, returning the action the user chose, if any.
+    /// The names of the dbs of the connected server are offered as destinations, a db on another server is typed in.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        entry_count: usize,
+        db_names: &[&str],
+        profiles: &[ConnectionProfile],
+    ) -> Option<CopyAction> {
+        let mut action = None;
+
+        ui.label(format!("Copy {} checked entries to:", entry_count));
+
+        ui.horizontal(|ui| {
+            ui.label("Server:");
+            egui::ComboBox::from_id_source("copy_destination_server")
+                .selected_text(self.profile_name.as_deref().unwrap_or("Connected server"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.profile_name, None, "Connected server");
+                    for profile in profiles {
+                        ui.selectable_value(
+                            &mut self.profile_name,
+                            Some(profile.name.clone()),
+                            profile.name.as_str(),
+                        );
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("DB name:");
+            ui.add_sized([160.0, 20.0], egui::TextEdit::singleline(&mut self.db_name));
+            if self.profile_name.is_none() {
+                egui::ComboBox::from_id_source("copy_destination_db")
+                    .selected_text("Pick")
+                    .show_ui(ui, |ui| {
+                        for db_name in db_names {
+                            if ui
+                                .selectable_label(self.db_name == *db_name, *db_name)
+                                .clicked()
+                            {
+                                self.db_name = db_name.to_string();
+                            }
+                        }
+                    });
+            }
+        });
+
+        let destination = self
+            .profile_name
+            .as_ref()
+            .map(|name| profiles.iter().find(|profile| &profile.name == name));
+
+        ui.horizontal(|ui| {
+            // the profile may have been deleted since it was picked
+            let can_copy =
+                entry_count > 0 && !self.db_name.is_empty() && !matches!(destination, Some(None));
+            if ui
+                .add_enabled(can_copy, egui::Button::new("Copy"))
+                .on_hover_text("Existing values at the copied keys are overwritten")
+                .clicked()
+            {
+                self.result = None;
+                action = Some(CopyAction::Copy {
+                    destination: destination.flatten().cloned(),
+                    db_name: self.db_name.clone(),
+                });
+            }
+            if ui.button("Close").clicked() {
+                action = Some(CopyAction::Close);
+            }
+        });
+
+        match &self.result {
+            None => {}
+            Some(Ok(count)) => {
+                ui.label(format!("Copied {} entries", count));
+            }
+            Some(Err(err)) => {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    format!("Copy failed: {:?}", err),
+                );
+            }
+        }
+
+        action
+    }
+}
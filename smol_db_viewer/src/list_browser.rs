@@ -0,0 +1,159 @@
+//! Contains `ListBrowser`, which shows the items of a keyed list of the selected db so they can be appended, inserted and removed.
+//! Queues are keyed lists, so they are browsed the same way.
+use smol_db_client::client_error::ClientError;
+
+/// What the user chose to do with the list being browsed
+#[derive(Debug)]
+pub enum ListAction {
+    /// Add the value to the list, at the end of it if there is no index
+    Add {
+        list_name: String,
+        value: String,
+        index: Option<usize>,
+    },
+    /// Remove the item at the index of the list
+    Remove {
+        list_name: String,
+        index: usize,
+    },
+    /// Read the items of the list again
+    Refresh(String),
+    Close,
+}
+
+/// A keyed list of the selected db being browsed
+#[derive(Debug)]
+pub struct ListBrowser {
+    list_name: String,
+    /// True if the list is not in the db yet, so its name can still be chosen
+    new_list: bool,
+    /// The items of the list, none while they are being read
+    items: Option<Result<Vec<String>, ClientError>>,
+    /// The value that is added to the list
+    value: String,
+    /// True if the value is inserted at `insert_index` instead of appended
+    insert: bool,
+    insert_index: usize,
+}
+
+impl ListBrowser {
+    /// Opens a list of the db, its items are shown once they are set
+    pub fn open(list_name: &str) -> Self {
+        Self {
+            list_name: list_name.to_string(),
+            new_list: false,
+            items: None,
+            value: "".to_string(),
+            insert: false,
+            insert_index: 0,
+        }
+    }
+
+    /// Opens a list that is not in the db yet, it is created when its first item is added
+    pub fn new_list() -> Self {
+        Self {
+            new_list: true,
+            items: Some(Ok(vec![])),
+            ..Self::open("")
+        }
+    }
+
+    pub fn list_name(&self) -> &str {
+        &self.list_name
+    }
+
+    /// Replaces the items shown with the items read from the db
+    pub fn set_items(&mut self, items: Result<Vec<String>, ClientError>) {
+        self.items = Some(items);
+    }
+
+    /// Shows the items of the list followed by the inputs to add an item, returning the action the user chose, if any
+    pub fn show(&mut self, ui: &mut egui::Ui) -> Option<ListAction> {
+        let mut action = None;
+
+        ui.horizontal(|ui| {
+            ui.label("List:");
+            if self.new_list {
+                ui.add_sized(
+                    [160.0, 20.0],
+                    egui::TextEdit::singleline(&mut self.list_name),
+                );
+            } else {
+                ui.label(self.list_name.as_str());
+                if ui.button("Refresh").clicked() {
+                    self.items = None;
+                    action = Some(ListAction::Refresh(self.list_name.clone()));
+                }
+            }
+            if ui.button("Close").clicked() {
+                action = Some(ListAction::Close);
+            }
+        });
+        ui.separator();
+
+        let len = match &self.items {
+            None => {
+                ui.spinner();
+                0
+            }
+            Some(Err(err)) => {
+                ui.label(format!("Error reading list: {:?}", err));
+                0
+            }
+            Some(Ok(items)) => {
+                egui::ScrollArea::vertical()
+                    .max_height(ui.available_height() - 80.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("list_items_grid")
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("Index");
+                                ui.strong("Value");
+                                ui.end_row();
+                                for (index, item) in items.iter().enumerate() {
+                                    ui.label(index.to_string());
+                                    ui.label(item.as_str());
+                                    if ui
+                                        .button("Remove")
+                                        .on_hover_text("Double click to remove the item")
+                                        .double_clicked()
+                                    {
+                                        action = Some(ListAction::Remove {
+                                            list_name: self.list_name.clone(),
+                                            index,
+                                        });
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                items.len()
+            }
+        };
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Value:");
+            ui.text_edit_singleline(&mut self.value);
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.insert, "Insert at index");
+            if self.insert {
+                ui.add(egui::DragValue::new(&mut self.insert_index).clamp_range(0..=len));
+            }
+            let can_add = !self.list_name.is_empty() && self.items.is_some();
+            if ui.add_enabled(can_add, egui::Button::new("Add")).clicked() {
+                action = Some(ListAction::Add {
+                    list_name: self.list_name.clone(),
+                    value: self.value.clone(),
+                    index: self.insert.then_some(self.insert_index.min(len)),
+                });
+                // the list exists once the item is added, so its name can no longer change
+                self.new_list = false;
+                self.value.clear();
+            }
+        });
+
+        action
+    }
+}
@@ -4,6 +4,16 @@ use crate::app::ApplicationState;
 use tracing_subscriber::prelude::__tracing_subscriber_SubscriberExt;
 
 mod app;
+mod confirmation;
+mod content_table;
+mod copy_dialog;
+mod list_browser;
+mod members_panel;
+mod profiles;
+mod statistics_plots;
+mod transfer_dialog;
+mod value_editor;
+mod worker;
 
 fn main() -> eframe::Result<()> {
     env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
@@ -0,0 +1,103 @@
+//! Contains `MembersPanel`, which lists the users and admins of the selected db so they can be added and removed one at a time
+use smol_db_client::client_error::ClientError;
+use smol_db_client::db_settings::DBSettings;
+
+/// The role a member of a db has
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemberRole {
+    User,
+    Admin,
+}
+
+impl MemberRole {
+    const fn as_text(&self) -> &str {
+        match self {
+            Self::User => "Users",
+            Self::Admin => "Admins",
+        }
+    }
+}
+
+/// What the user chose to do with the members of the db
+#[derive(Debug)]
+pub enum MemberAction {
+    /// Give the key the role
+    Add(MemberRole, String),
+    /// Remove the role from the key, or from the hash of it listed in the db settings
+    Remove(MemberRole, String),
+}
+
+/// The keys being added as members, and the result of the last change
+#[derive(Debug, Default)]
+pub struct MembersPanel {
+    new_user_key: String,
+    new_admin_key: String,
+    /// The error of the last change, if it failed
+    error: Option<ClientError>,
+}
+
+impl MembersPanel {
+    /// Shows the result of a change sent to the server
+    pub fn set_result<T>(&mut self, result: Result<T, ClientError>) {
+        self.error = result.err();
+    }
+
+    /// Clears the inputs and the result of the last change, used when a different db is shown
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Shows the users and admins of the db, returning the change the user chose, if any.
+    /// Keys are listed as the hashes the server stores them as.
+    pub fn show(&mut self, ui: &mut egui::Ui, settings: &DBSettings) -> Option<MemberAction> {
+        let mut action = None;
+
+        if let Some(err) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, format!("{:?}", err));
+            ui.separator();
+        }
+
+        for (role, members, new_key) in [
+            (
+                MemberRole::User,
+                settings.get_user_list(),
+                &mut self.new_user_key,
+            ),
+            (
+                MemberRole::Admin,
+                settings.get_admin_list(),
+                &mut self.new_admin_key,
+            ),
+        ] {
+            ui.heading(role.as_text());
+            if members.is_empty() {
+                ui.label("None");
+            }
+            for member in members {
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Remove")
+                        .on_hover_text("Double click to remove")
+                        .double_clicked()
+                    {
+                        action = Some(MemberAction::Remove(role, member.clone()));
+                    }
+                    ui.monospace(member.as_str());
+                });
+            }
+            ui.horizontal(|ui| {
+                ui.label("Key:");
+                ui.add_sized([160.0, 20.0], egui::TextEdit::singleline(new_key));
+                if ui
+                    .add_enabled(!new_key.is_empty(), egui::Button::new("Add"))
+                    .clicked()
+                {
+                    action = Some(MemberAction::Add(role, std::mem::take(new_key)));
+                }
+            });
+            ui.separator();
+        }
+
+        action
+    }
+}
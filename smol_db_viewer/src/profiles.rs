@@ -0,0 +1,38 @@
+//! Contains `ConnectionProfile`, the details the viewer connects to a server with, which can be saved by name to switch between servers
+use serde::{Deserialize, Serialize};
+
+/// The address, key and connection preferences of a server
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConnectionProfile {
+    /// The name the profile is saved as, empty if it has not been saved
+    pub name: String,
+    pub ip_address: String,
+    pub client_key: String,
+    /// Connect to the server on startup
+    pub auto_connect: bool,
+    /// Set the key when connecting on startup
+    pub auto_set_key: bool,
+    /// Switch to end to end encryption as soon as the client connects, before the key is sent
+    pub use_encryption: bool,
+}
+
+impl ConnectionProfile {
+    /// Returns the key to set once connected, if there is one
+    pub fn key(&self) -> Option<String> {
+        (!self.client_key.is_empty()).then(|| self.client_key.clone())
+    }
+
+    /// Returns the key to set when connecting on startup, if it is set on startup
+    pub fn startup_key(&self) -> Option<String> {
+        self.key().filter(|_| self.auto_set_key)
+    }
+}
+
+/// Saves the profile under its name, replacing the saved profile with the same name
+pub fn save_profile(profiles: &mut Vec<ConnectionProfile>, profile: &ConnectionProfile) {
+    match profiles.iter_mut().find(|saved| saved.name == profile.name) {
+        Some(saved) => *saved = profile.clone(),
+        None => profiles.push(profile.clone()),
+    }
+}
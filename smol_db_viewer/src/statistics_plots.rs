@@ -0,0 +1,127 @@
+//! Contains the plots of the statistics panel, drawn from the `DBStatistics` of the selected db
+use chrono::{DateTime, Local};
+use egui_plot::{Line, Plot, PlotPoints};
+use smol_db_client::prelude::DBStatistics;
+use std::time::Duration;
+
+/// Number of buckets the requests in a time window are counted in
+const BUCKET_COUNT: i64 = 30;
+
+/// Number of access time gaps each point of the rolling average is taken over
+const ROLLING_AVERAGE_LENGTH: usize = 10;
+
+/// The span of time the request rate plot shows, ending now
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize, serde::Serialize)]
+pub enum TimeWindow {
+    FiveMinutes,
+    #[default]
+    Hour,
+    Day,
+    Week,
+    /// Every request that is still recorded
+    All,
+}
+
+impl TimeWindow {
+    pub const ALL: [Self; 5] = [
+        Self::FiveMinutes,
+        Self::Hour,
+        Self::Day,
+        Self::Week,
+        Self::All,
+    ];
+
+    pub const fn as_text(&self) -> &str {
+        match self {
+            Self::FiveMinutes => "5 minutes",
+            Self::Hour => "Hour",
+            Self::Day => "Day",
+            Self::Week => "Week",
+            Self::All => "All",
+        }
+    }
+
+    /// Returns the length of the window in seconds, or None if the window has no length
+    const fn seconds(self) -> Option<i64> {
+        match self {
+            Self::FiveMinutes => Some(5 * 60),
+            Self::Hour => Some(60 * 60),
+            Self::Day => Some(24 * 60 * 60),
+            Self::Week => Some(7 * 24 * 60 * 60),
+            Self::All => None,
+        }
+    }
+}
+
+/// Shows the request rate of the db within the window, and the rolling average of the time between its requests
+pub fn statistics_plots(ui: &mut egui::Ui, stats: &DBStatistics, window: TimeWindow) {
+    ui.label("Requests per minute:");
+    let rate = request_rate(stats.get_usage_time_list(), window, Local::now());
+    Plot::new("request_rate_plot")
+        .height(160.0)
+        .include_y(0.0)
+        .allow_scroll(false)
+        .x_axis_label("Minutes ago")
+        .show(ui, |plot_ui| {
+            plot_ui.line(Line::new(PlotPoints::from(rate)).name("Requests per minute"));
+        });
+
+    ui.label("Average access time gap (s):");
+    let average = rolling_average(stats.get_access_time_gaps(), ROLLING_AVERAGE_LENGTH);
+    Plot::new("rolling_average_plot")
+        .height(160.0)
+        .include_y(0.0)
+        .allow_scroll(false)
+        .x_axis_label("Request")
+        .show(ui, |plot_ui| {
+            plot_ui.line(Line::new(PlotPoints::from(average)).name("Average access time gap"));
+        });
+}
+
+/// Counts the requests made within the window in evenly sized buckets,
+/// returning the minutes before now each bucket starts at, and the requests per minute made in it, oldest bucket first
+fn request_rate(
+    times: &[DateTime<Local>],
+    window: TimeWindow,
+    now: DateTime<Local>,
+) -> Vec<[f64; 2]> {
+    let span = window
+        .seconds()
+        .unwrap_or_else(|| {
+            times
+                .first()
+                .map_or(0, |first| (now - *first).num_seconds() + 1)
+        })
+        .max(60);
+    let bucket_seconds = (span / BUCKET_COUNT).max(1);
+
+    let mut buckets = vec![0_u32; (span / bucket_seconds + 1) as usize];
+    for time in times {
+        let age = (now - *time).num_seconds();
+        if (0..span).contains(&age) {
+            buckets[(age / bucket_seconds) as usize] += 1;
+        }
+    }
+
+    buckets
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(index, count)| {
+            let minutes_ago = -(index as i64 * bucket_seconds) as f64 / 60.0;
+            let per_minute = f64::from(*count) * 60.0 / bucket_seconds as f64;
+            [minutes_ago, per_minute]
+        })
+        .collect()
+}
+
+/// Returns the average of each gap and the gaps before it, up to the given length, in seconds
+fn rolling_average(gaps: &[Duration], length: usize) -> Vec<[f64; 2]> {
+    (0..gaps.len())
+        .map(|index| {
+            let averaged = &gaps[(index + 1).saturating_sub(length)..=index];
+            let sum: f64 = averaged.iter().map(Duration::as_secs_f64).sum();
+            [(index + 1) as f64, sum / averaged.len() as f64]
+        })
+        .collect()
+}
@@ -0,0 +1,211 @@
+//! Contains `TransferDialog`, which exports a db to a file on disk, or imports a file into a db
+use smol_db_client::client_error::ClientError;
+use std::path::PathBuf;
+
+/// The format of the file a db is exported to or imported from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TransferFormat {
+    /// A single json object, with each key mapped to its value
+    #[default]
+    Json,
+    /// A `key,value` header row followed by a row for each key
+    Csv,
+    /// A dump of the content, settings and statistics of the db, which requires super admin privileges
+    Dump,
+}
+
+impl TransferFormat {
+    const fn as_text(&self) -> &str {
+        match self {
+            Self::Json => "JSON",
+            Self::Csv => "CSV",
+            Self::Dump => "Dump",
+        }
+    }
+
+    const fn hover_text(&self) -> &str {
+        match self {
+            Self::Json => "A json object of every key and value",
+            Self::Csv => "A key,value header row followed by a row for each key",
+            Self::Dump => {
+                "The content, settings and statistics of the db, requires super admin privileges"
+            }
+        }
+    }
+}
+
+/// Whether the dialog exports or imports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum TransferDirection {
+    #[default]
+    Export,
+    Import,
+}
+
+/// What the user chose to do in the transfer dialog
+#[derive(Debug)]
+pub enum TransferAction {
+    /// Write the db to the file at the path
+    Export {
+        db_name: String,
+        format: TransferFormat,
+        path: PathBuf,
+    },
+    /// Write the file at the path to the db, creating the db if it does not exist.
+    /// A dump holds the name of its db, so there is no db name for it
+    Import {
+        db_name: Option<String>,
+        format: TransferFormat,
+        path: PathBuf,
+    },
+    Close,
+}
+
+/// The db and file being exported or imported, and the result of the last transfer
+#[derive(Debug, Default)]
+pub struct TransferDialog {
+    direction: TransferDirection,
+    format: TransferFormat,
+    db_name: String,
+    path: String,
+    /// A description of the last transfer once it finished, or the reason it failed, none while there is no result yet
+    result: Option<Result<String, ClientError>>,
+}
+
+impl TransferDialog {
+    /// Opens the dialog with the given db picked, usually the selected db
+    pub fn new(db_name: Option<String>) -> Self {
+        Self {
+            db_name: db_name.unwrap_or_default(),
+            ..Self::default()
+        }
+    }
+
+    /// Shows the number of entries, or bytes for a dump, written to the file
+    pub fn set_export_result(
+        &mut self,
+        format: TransferFormat,
+        result: Result<usize, ClientError>,
+    ) {
+        let unit = match format {
+            TransferFormat::Dump => "bytes",
+            TransferFormat::Json | TransferFormat::Csv => "entries",
+        };
+        self.result = Some(result.map(|count| format!("Exported {} {}", count, unit)));
+    }
+
+    /// Shows the db the file was imported into
+    pub fn set_import_result(&mut self, result: Result<String, ClientError>) {
+        self.result = Some(result.map(|db_name| format!("Imported into {}", db_name)));
+    }
+
+    /// Shows the dialog, returning the action the user chose, if any.
+    /// The names of the dbs of the connected server can be picked instead of typed in.
+    pub fn show(&mut self, ui: &mut egui::Ui, db_names: &[&str]) -> Option<TransferAction> {
+        let mut action = None;
+
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.direction, TransferDirection::Export, "Export");
+            ui.selectable_value(&mut self.direction, TransferDirection::Import, "Import");
+        });
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Format:");
+            for format in [
+                TransferFormat::Json,
+                TransferFormat::Csv,
+                TransferFormat::Dump,
+            ] {
+                ui.selectable_value(&mut self.format, format, format.as_text())
+                    .on_hover_text(format.hover_text());
+            }
+        });
+
+        // a dump is always imported into the db it was exported from
+        let needs_db_name =
+            self.direction == TransferDirection::Export || self.format != TransferFormat::Dump;
+        if needs_db_name {
+            ui.horizontal(|ui| {
+                ui.label("DB name:");
+                ui.add_sized([160.0, 20.0], egui::TextEdit::singleline(&mut self.db_name));
+                egui::ComboBox::from_id_source("transfer_db")
+                    .selected_text("Pick")
+                    .show_ui(ui, |ui| {
+                        for db_name in db_names {
+                            if ui
+                                .selectable_label(self.db_name == *db_name, *db_name)
+                                .clicked()
+                            {
+                                self.db_name = db_name.to_string();
+                            }
+                        }
+                    });
+            });
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("File:");
+            ui.add_sized([240.0, 20.0], egui::TextEdit::singleline(&mut self.path));
+        });
+
+        ui.horizontal(|ui| {
+            let can_transfer = !self.path.is_empty() && (!needs_db_name || !self.db_name.is_empty());
+            let path = PathBuf::from(&self.path);
+            match self.direction {
+                TransferDirection::Export => {
+                    if ui
+                        .add_enabled(can_transfer, egui::Button::new("Export"))
+                        .on_hover_text("An existing file at the path is overwritten")
+                        .clicked()
+                    {
+                        self.result = None;
+                        action = Some(TransferAction::Export {
+                            db_name: self.db_name.clone(),
+                            format: self.format,
+                            path,
+                        });
+                    }
+                }
+                TransferDirection::Import => {
+                    let hover_text = match self.format {
+                        TransferFormat::Dump => "The db of the dump must not exist yet",
+                        TransferFormat::Json | TransferFormat::Csv => {
+                            "The db is created if it does not exist, existing values at the imported keys are overwritten"
+                        }
+                    };
+                    if ui
+                        .add_enabled(can_transfer, egui::Button::new("Import"))
+                        .on_hover_text(hover_text)
+                        .clicked()
+                    {
+                        self.result = None;
+                        action = Some(TransferAction::Import {
+                            db_name: needs_db_name.then(|| self.db_name.clone()),
+                            format: self.format,
+                            path,
+                        });
+                    }
+                }
+            }
+            if ui.button("Close").clicked() {
+                action = Some(TransferAction::Close);
+            }
+        });
+
+        match &self.result {
+            None => {}
+            Some(Ok(description)) => {
+                ui.label(description.as_str());
+            }
+            Some(Err(err)) => {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    format!("Transfer failed: {:?}", err),
+                );
+            }
+        }
+
+        action
+    }
+}
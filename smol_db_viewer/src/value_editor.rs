@@ -0,0 +1,137 @@
+//! Contains `ValueEditor`, which edits a single entry of the selected db before it is written back
+use serde_json::Value;
+
+/// What the user chose to do with the entry being edited
+#[derive(Debug)]
+pub enum EditorAction {
+    /// Write the value to the key
+    Save {
+        key: String,
+        value: String,
+    },
+    Delete(String),
+    Close,
+}
+
+/// An entry of the selected db being edited
+#[derive(Debug)]
+pub struct ValueEditor {
+    key: String,
+    /// True if the entry is not in the db yet, so its key can still be chosen
+    new_entry: bool,
+    text: String,
+    /// True if the value is edited as JSON, it must then be valid JSON to be saved
+    json: bool,
+}
+
+impl ValueEditor {
+    /// Opens an entry of the db, pretty printing the value if it is a JSON object or array
+    pub fn open(key: &str, value: &str) -> Self {
+        let pretty = serde_json::from_str::<Value>(value)
+            .ok()
+            .filter(|json| json.is_object() || json.is_array())
+            .and_then(|json| serde_json::to_string_pretty(&json).ok());
+
+        Self {
+            key: key.to_string(),
+            new_entry: false,
+            json: pretty.is_some(),
+            text: pretty.unwrap_or_else(|| value.to_string()),
+        }
+    }
+
+    /// Opens an entry that is not in the db yet
+    pub fn new_entry() -> Self {
+        Self {
+            key: "".to_string(),
+            new_entry: true,
+            text: "".to_string(),
+            json: false,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns the value that would be written, JSON is written compactly, or the reason it is not valid JSON
+    fn validate(&self) -> Result<String, String> {
+        if !self.json {
+            return Ok(self.text.clone());
+        }
+        serde_json::from_str::<Value>(&self.text)
+            .map(|json| json.to_string())
+            .map_err(|err| err.to_string())
+    }
+
+    /// Shows the editor, returning the action the user chose, if any
+    pub fn show(&mut self, ui: &mut egui::Ui) -> Option<EditorAction> {
+        let mut action = None;
+
+        ui.horizontal(|ui| {
+            ui.label("Key:");
+            if self.new_entry {
+                ui.add_sized([160.0, 20.0], egui::TextEdit::singleline(&mut self.key));
+            } else {
+                ui.label(self.key.as_str());
+            }
+            ui.checkbox(&mut self.json, "JSON")
+                .on_hover_text("JSON values are checked before they are saved");
+        });
+
+        ui.add(
+            egui::TextEdit::multiline(&mut self.text)
+                .code_editor()
+                .desired_rows(8)
+                .desired_width(f32::INFINITY),
+        );
+
+        let value = self.validate();
+        if let Err(err) = &value {
+            ui.colored_label(
+                ui.visuals().error_fg_color,
+                format!("Invalid JSON: {}", err),
+            );
+        }
+
+        ui.horizontal(|ui| {
+            let can_save = value.is_ok() && !self.key.is_empty();
+            if ui
+                .add_enabled(can_save, egui::Button::new("Save"))
+                .clicked()
+            {
+                if let Ok(value) = &value {
+                    action = Some(EditorAction::Save {
+                        key: self.key.clone(),
+                        value: value.clone(),
+                    });
+                }
+            }
+            if self.json
+                && ui
+                    .add_enabled(value.is_ok(), egui::Button::new("Format"))
+                    .clicked()
+            {
+                if let Some(pretty) = serde_json::from_str::<Value>(&self.text)
+                    .ok()
+                    .and_then(|json| serde_json::to_string_pretty(&json).ok())
+                {
+                    self.text = pretty;
+                }
+            }
+            if !self.new_entry
+                && ui
+                    .button("Delete")
+                    .on_hover_text("Double click to delete the entry")
+                    .double_clicked()
+            {
+                action = Some(EditorAction::Delete(self.key.clone()));
+            }
+            if ui.button("Close").clicked() {
+                action = Some(EditorAction::Close);
+            }
+        });
+
+        action
+    }
+}
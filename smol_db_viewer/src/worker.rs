@@ -0,0 +1,531 @@
+//! Contains `ClientWorker`, which owns the client and makes the requests of the viewer on a background thread,
+//! so the ui keeps drawing while a request waits on the network.
+use crate::members_panel::MemberRole;
+use crate::profiles::ConnectionProfile;
+use crate::transfer_dialog::TransferFormat;
+use smol_db_client::client_error::ClientError;
+use smol_db_client::client_error::ClientError::{BadPacket, ValueStreamError};
+use smol_db_client::prelude::{DBPacketInfo, DBStatistics, SmolDbClient};
+use smol_db_client::{db_settings::DBSettings, DBSuccessResponse, Role};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+
+/// Most entries written by a single request when copying entries, so each request fits in a packet
+const COPY_BATCH_SIZE: usize = 8;
+
+/// Most bytes of keys and values written by a single request when copying entries, a larger entry is written on its own
+const COPY_BATCH_BYTES: usize = 256;
+
+/// A request the ui sends to the worker, each is answered with exactly one `Response`
+#[derive(Debug)]
+pub enum Request {
+    /// Sets the access key of the client
+    SetKey(String),
+    /// Lists every db on the server
+    ListDB,
+    /// Reads the content, role, settings and statistics of a db.
+    /// The content is streamed in chunks, so the server does not serialize a large db into a single response
+    LoadDB(String),
+    /// Reads the content and statistics of a db again, without its role, settings or lists
+    RefreshDB(String),
+    /// Writes a value to a db, then reads the content of the db again
+    WriteData {
+        db_name: String,
+        key: String,
+        value: String,
+    },
+    /// Deletes a value from a db, then reads the content of the db again
+    DeleteData { db_name: String, key: String },
+    /// Reads every item of a keyed list
+    LoadList { db_name: String, list_name: String },
+    /// Adds a value to a keyed list, appending it if there is no index, then reads the list names and the list again
+    AddToList {
+        db_name: String,
+        list_name: String,
+        value: String,
+        index: Option<usize>,
+    },
+    /// Removes the item at the index of a keyed list, then reads the list names and the list again
+    RemoveFromList {
+        db_name: String,
+        list_name: String,
+        index: usize,
+    },
+    /// Replaces the settings of a db
+    SetDBSettings(String, DBSettings),
+    /// Gives a key a role in a db, then reads the settings of the db again
+    AddMember {
+        db_name: String,
+        role: MemberRole,
+        key: String,
+    },
+    /// Removes a role from a key or the hash of it in a db, then reads the settings of the db again
+    RemoveMember {
+        db_name: String,
+        role: MemberRole,
+        key: String,
+    },
+    /// Reads the entries at the keys of a db, and writes them to a db on the server of the profile,
+    /// or the connected server if there is no profile
+    CopyEntries {
+        db_name: String,
+        keys: Vec<String>,
+        destination: Option<ConnectionProfile>,
+        destination_db: String,
+    },
+    /// Writes a db to the file at the path
+    ExportDB {
+        db_name: String,
+        format: TransferFormat,
+        path: PathBuf,
+    },
+    /// Writes the file at the path to a db, creating the db with the default settings if it does not exist.
+    /// A dump creates the db it holds, so it has no db name
+    ImportDB {
+        db_name: Option<String>,
+        format: TransferFormat,
+        path: PathBuf,
+    },
+    /// Creates a db, then reads its content
+    CreateDB(String, DBSettings),
+    /// Deletes a db, moving it to the trash
+    DeleteDB(String),
+    /// Restores the most recently deleted db with the name from the trash
+    RestoreDB(String),
+    /// Switches the client to end to end encryption
+    SetupEncryption,
+}
+
+/// The result of a request, sent back to the ui
+#[derive(Debug)]
+pub enum Response {
+    /// The worker connected to the server, or failed to and stopped
+    Connected(Result<(), ClientError>),
+    KeySet(Result<DBSuccessResponse<String>, ClientError>),
+    DBList(Result<Vec<DBPacketInfo>, ClientError>),
+    DBLoaded(Box<LoadedDB>),
+    /// The content and statistics of the db were read again
+    DBRefreshed {
+        db_name: String,
+        content: Result<HashMap<String, String>, ClientError>,
+        statistics: Result<DBStatistics, ClientError>,
+    },
+    /// A value was written to or deleted from the db, followed by the content of the db after the change
+    ContentChanged {
+        db_name: String,
+        change: Result<DBSuccessResponse<String>, ClientError>,
+        content: Result<HashMap<String, String>, ClientError>,
+    },
+    ListLoaded {
+        db_name: String,
+        list_name: String,
+        items: Result<Vec<String>, ClientError>,
+    },
+    /// An item was added to or removed from a list, followed by the list names of the db and the items of the list after the change
+    ListChanged {
+        db_name: String,
+        list_name: String,
+        change: Result<(), ClientError>,
+        lists: Result<Vec<String>, ClientError>,
+        items: Result<Vec<String>, ClientError>,
+    },
+    DBSettingsSet {
+        db_name: String,
+        settings: DBSettings,
+        result: Result<DBSuccessResponse<String>, ClientError>,
+    },
+    /// A member was added to or removed from the db, followed by the settings of the db after the change
+    MembersChanged {
+        db_name: String,
+        change: Result<DBSuccessResponse<String>, ClientError>,
+        db_settings: Result<DBSettings, ClientError>,
+    },
+    /// Entries were copied to the db, the number copied is the result.
+    /// `same_server` is true if the db is on the connected server
+    EntriesCopied {
+        destination_db: String,
+        same_server: bool,
+        result: Result<usize, ClientError>,
+    },
+    /// A db was written to a file, the number of entries, or bytes for a dump, written is the result
+    DBExported {
+        format: TransferFormat,
+        result: Result<usize, ClientError>,
+    },
+    /// A file was written to a db, the name of the db is the result
+    DBImported(Result<String, ClientError>),
+    /// The db was created, holding the given content
+    DBCreated {
+        db_name: String,
+        content: Result<HashMap<String, String>, ClientError>,
+    },
+    DBDeleted {
+        db_name: String,
+        result: Result<DBSuccessResponse<String>, ClientError>,
+    },
+    DBRestored {
+        db_name: String,
+        result: Result<DBSuccessResponse<String>, ClientError>,
+    },
+    /// The client switched to end to end encryption, the fingerprint of the server key is the result
+    EncryptionSetup(Result<String, ClientError>),
+}
+
+/// Everything the viewer shows of a db, read when the db is first selected
+#[derive(Debug)]
+pub struct LoadedDB {
+    pub name: String,
+    pub content: Result<HashMap<String, String>, ClientError>,
+    /// The names of the keyed lists of the db
+    pub lists: Result<Vec<String>, ClientError>,
+    pub role: Result<Role, ClientError>,
+    pub db_settings: Result<DBSettings, ClientError>,
+    pub statistics: Result<DBStatistics, ClientError>,
+}
+
+/// Handle to the background thread that owns the client.
+/// Dropping the handle stops the thread once it finishes its current request, disconnecting the client.
+pub struct ClientWorker {
+    requests: Sender<Request>,
+    responses: Receiver<Response>,
+    /// Requests that have not been answered yet, including connecting and setting the key on connect
+    pending: usize,
+}
+
+impl ClientWorker {
+    /// Starts a worker that connects to the server at the given address, switches to end to end encryption once connected if `encrypt` is true,
+    /// and then sets the key of the client if one is given, so the key is encrypted too.
+    /// The context is repainted whenever a response is ready, so the ui shows it without waiting for input.
+    pub fn connect(
+        address: String,
+        key: Option<String>,
+        encrypt: bool,
+        ctx: egui::Context,
+    ) -> Self {
+        let (request_sender, request_receiver) = channel();
+        let (response_sender, response_receiver) = channel();
+        let pending = 1 + usize::from(encrypt) + usize::from(key.is_some());
+
+        thread::spawn(move || {
+            let respond = |response: Response| {
+                let sent = response_sender.send(response).is_ok();
+                ctx.request_repaint();
+                sent
+            };
+
+            let mut client = match SmolDbClient::new(&address) {
+                Ok(client) => client,
+                Err(err) => {
+                    respond(Response::Connected(Err(err)));
+                    return;
+                }
+            };
+            respond(Response::Connected(Ok(())));
+
+            if encrypt {
+                respond(Response::EncryptionSetup(setup_encryption(&mut client)));
+            }
+            if let Some(key) = key {
+                respond(Response::KeySet(client.set_access_key(key)));
+            }
+
+            // the receiver disconnects once the ui drops the worker
+            for request in request_receiver {
+                if !respond(handle_request(&mut client, request)) {
+                    break;
+                }
+            }
+            let _ = client.disconnect();
+        });
+
+        Self {
+            requests: request_sender,
+            responses: response_receiver,
+            pending,
+        }
+    }
+
+    /// Queues a request, which the worker makes after every request queued before it
+    pub fn send(&mut self, request: Request) {
+        if self.requests.send(request).is_ok() {
+            self.pending += 1;
+        }
+    }
+
+    /// Returns the next response that is ready, without waiting for one
+    pub fn try_recv(&mut self) -> Option<Response> {
+        let response = self.responses.try_recv().ok()?;
+        self.pending = self.pending.saturating_sub(1);
+        Some(response)
+    }
+
+    /// Returns true while a request has not been answered yet
+    pub const fn is_busy(&self) -> bool {
+        self.pending > 0
+    }
+}
+
+/// Makes the request with the client, returning the response the ui is sent
+fn handle_request(client: &mut SmolDbClient, request: Request) -> Response {
+    match request {
+        Request::SetKey(key) => Response::KeySet(client.set_access_key(key)),
+        Request::ListDB => Response::DBList(client.list_db()),
+        Request::LoadDB(name) => Response::DBLoaded(Box::new(LoadedDB {
+            content: client.stream_db_contents(&name),
+            lists: client.list_db_lists(&name),
+            role: client.get_role(&name),
+            db_settings: client.get_db_settings(&name),
+            statistics: client.get_stats(&name),
+            name,
+        })),
+        Request::RefreshDB(db_name) => Response::DBRefreshed {
+            content: client.stream_db_contents(&db_name),
+            statistics: client.get_stats(&db_name),
+            db_name,
+        },
+        Request::WriteData {
+            db_name,
+            key,
+            value,
+        } => Response::ContentChanged {
+            change: client.write_db(&db_name, &key, &value),
+            content: client.stream_db_contents(&db_name),
+            db_name,
+        },
+        Request::DeleteData { db_name, key } => Response::ContentChanged {
+            change: client.delete_data(&db_name, &key),
+            content: client.stream_db_contents(&db_name),
+            db_name,
+        },
+        Request::LoadList { db_name, list_name } => Response::ListLoaded {
+            items: read_list(client, &db_name, &list_name),
+            db_name,
+            list_name,
+        },
+        Request::AddToList {
+            db_name,
+            list_name,
+            value,
+            index,
+        } => {
+            let change = match index {
+                None => client.list_push(&db_name, &list_name, &value),
+                Some(index) => client.list_insert(&db_name, &list_name, index, &value),
+            };
+            Response::ListChanged {
+                change: change.map(|_| ()),
+                lists: client.list_db_lists(&db_name),
+                items: read_list(client, &db_name, &list_name),
+                db_name,
+                list_name,
+            }
+        }
+        Request::RemoveFromList {
+            db_name,
+            list_name,
+            index,
+        } => Response::ListChanged {
+            change: client.list_remove(&db_name, &list_name, index).map(|_| ()),
+            lists: client.list_db_lists(&db_name),
+            items: read_list(client, &db_name, &list_name),
+            db_name,
+            list_name,
+        },
+        Request::SetDBSettings(db_name, settings) => Response::DBSettingsSet {
+            result: client.set_db_settings(&db_name, settings.clone()),
+            db_name,
+            settings,
+        },
+        Request::AddMember { db_name, role, key } => Response::MembersChanged {
+            change: match role {
+                MemberRole::User => client.add_user(&db_name, &key),
+                MemberRole::Admin => client.add_admin(&db_name, &key),
+            },
+            db_settings: client.get_db_settings(&db_name),
+            db_name,
+        },
+        Request::RemoveMember { db_name, role, key } => Response::MembersChanged {
+            change: match role {
+                MemberRole::User => client.remove_user(&db_name, &key),
+                MemberRole::Admin => client.remove_admin(&db_name, &key),
+            },
+            db_settings: client.get_db_settings(&db_name),
+            db_name,
+        },
+        Request::CopyEntries {
+            db_name,
+            keys,
+            destination,
+            destination_db,
+        } => Response::EntriesCopied {
+            same_server: destination.is_none(),
+            result: copy_entries(client, &db_name, &keys, destination, &destination_db),
+            destination_db,
+        },
+        Request::ExportDB {
+            db_name,
+            format,
+            path,
+        } => Response::DBExported {
+            format,
+            result: export_db(client, &db_name, format, &path),
+        },
+        Request::ImportDB {
+            db_name,
+            format,
+            path,
+        } => Response::DBImported(import_db(client, db_name, format, &path)),
+        Request::CreateDB(db_name, settings) => {
+            let content = match client.create_db(&db_name, settings) {
+                Ok(DBSuccessResponse::SuccessNoData) => client.stream_db_contents(&db_name),
+                // creating a db does not respond with data
+                Ok(DBSuccessResponse::SuccessReply(_) | DBSuccessResponse::SuccessData(_)) => {
+                    Err(BadPacket)
+                }
+                Err(err) => Err(err),
+            };
+            Response::DBCreated { db_name, content }
+        }
+        Request::DeleteDB(db_name) => Response::DBDeleted {
+            result: client.delete_db(&db_name),
+            db_name,
+        },
+        Request::RestoreDB(db_name) => Response::DBRestored {
+            result: client.restore_db(&db_name),
+            db_name,
+        },
+        Request::SetupEncryption => Response::EncryptionSetup(setup_encryption(client)),
+    }
+}
+
+/// Switches the client to end to end encryption, returning the fingerprint of the key the server presented
+fn setup_encryption(client: &mut SmolDbClient) -> Result<String, ClientError> {
+    let _ = client.setup_encryption()?;
+    client
+        .server_fingerprint()
+        .map(str::to_string)
+        .ok_or(BadPacket)
+}
+
+/// Streams every item of the list, a list that is not in the db has no items
+fn read_list(
+    client: &mut SmolDbClient,
+    db_name: &str,
+    list_name: &str,
+) -> Result<Vec<String>, ClientError> {
+    client
+        .stream_list(db_name, list_name)
+        .map(Iterator::collect)
+}
+
+/// Reads the entries at the keys of the db and writes them to the destination db, connecting to the server of the profile if there is one.
+/// Each value is read on its own, as the size of a value is not known until it is read.
+/// Keys without a value are skipped, returns the number of entries written
+fn copy_entries(
+    client: &mut SmolDbClient,
+    db_name: &str,
+    keys: &[String],
+    destination: Option<ConnectionProfile>,
+    destination_db: &str,
+) -> Result<usize, ClientError> {
+    let mut entries = vec![];
+    for key in keys {
+        if let Some(Some(value)) = client.read_many(db_name, &[key])?.pop() {
+            entries.push((key.as_str(), value));
+        }
+    }
+
+    let Some(profile) = destination else {
+        return write_entries(client, destination_db, &entries);
+    };
+
+    let mut destination_client = SmolDbClient::new(&profile.ip_address)?;
+    if profile.use_encryption {
+        let _ = destination_client.setup_encryption()?;
+    }
+    if let Some(key) = profile.key() {
+        let _ = destination_client.set_access_key(key)?;
+    }
+    let written = write_entries(&mut destination_client, destination_db, &entries);
+    let _ = destination_client.disconnect();
+    written
+}
+
+/// Writes the entries to the db in batches of at most `COPY_BATCH_SIZE` entries and `COPY_BATCH_BYTES` bytes, returning the number of entries written
+fn write_entries(
+    client: &mut SmolDbClient,
+    db_name: &str,
+    entries: &[(&str, String)],
+) -> Result<usize, ClientError> {
+    let mut batch: Vec<(&str, &str)> = vec![];
+    let mut batch_bytes = 0;
+
+    for (key, value) in entries {
+        let entry_bytes = key.len() + value.len();
+        if !batch.is_empty()
+            && (batch.len() == COPY_BATCH_SIZE || batch_bytes + entry_bytes > COPY_BATCH_BYTES)
+        {
+            let _ = client.write_many(db_name, &batch)?;
+            batch.clear();
+            batch_bytes = 0;
+        }
+        batch.push((key, value));
+        batch_bytes += entry_bytes;
+    }
+    if !batch.is_empty() {
+        let _ = client.write_many(db_name, &batch)?;
+    }
+
+    Ok(entries.len())
+}
+
+/// Writes the db to the file at the path in the format, returning the number of entries, or bytes for a dump, written
+fn export_db(
+    client: &mut SmolDbClient,
+    db_name: &str,
+    format: TransferFormat,
+    path: &Path,
+) -> Result<usize, ClientError> {
+    if format == TransferFormat::Dump {
+        return client.export_db(db_name, path);
+    }
+
+    let mut writer = BufWriter::new(File::create(path).map_err(ValueStreamError)?);
+    let written = match format {
+        TransferFormat::Csv => client.export_table_csv(db_name, &mut writer)?,
+        TransferFormat::Json | TransferFormat::Dump => {
+            client.export_table_json(db_name, &mut writer)?
+        }
+    };
+    writer.flush().map_err(ValueStreamError)?;
+    Ok(written)
+}
+
+/// Writes the file at the path to the db, creating the db if it does not exist, and returns the name of the db.
+/// A dump is imported into the db it holds, so it has no db name
+fn import_db(
+    client: &mut SmolDbClient,
+    db_name: Option<String>,
+    format: TransferFormat,
+    path: &Path,
+) -> Result<String, ClientError> {
+    let Some(db_name) = db_name.filter(|_| format != TransferFormat::Dump) else {
+        return client.import_db(path);
+    };
+
+    let mut reader = BufReader::new(File::open(path).map_err(ValueStreamError)?);
+    if !client.db_exists(&db_name)? {
+        let _ = client.create_db(&db_name, DBSettings::default())?;
+    }
+    let _ = match format {
+        TransferFormat::Csv => client.import_table_csv(&db_name, &mut reader)?,
+        TransferFormat::Json | TransferFormat::Dump => {
+            client.import_table_json(&db_name, &mut reader)?
+        }
+    };
+    Ok(db_name)
+}